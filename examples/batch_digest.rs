@@ -0,0 +1,145 @@
+//! Fetches several locations at once, ranks them like the CLI's morning
+//! digest and adds a one-line outlook for the coming week
+//!
+//! cargo run --example batch_digest -- "Oslo" "Madrid" "Reykjavik"
+
+use std::thread;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use weather_app::prelude::*;
+use weather_app::utils::advisories::AdvisoryStandard;
+use weather_app::utils::digest::{self, DigestEntry};
+use weather_app::views::cl_view::ClView;
+
+/// Days with at least this much precipitation count as wet, in mm
+const WET_DAY_MM: f64 = 1.0;
+
+/// "week: 3–11°C, 18.4 mm on 4 wet days" over the seven days from the
+/// location's today; `None` without daily data for that week
+fn weekly_summary(info: &WeatherInfo, now: DateTime<Utc>) -> Option<String> {
+    let start = info.today(now)?;
+    let week: Vec<&DailyForecast> = info
+        .weather_data
+        .daily_between(start, start + TimeDelta::days(7))
+        .collect();
+    if week.is_empty() {
+        return None;
+    }
+    let low = week
+        .iter()
+        .filter_map(|d| d.temperature_min)
+        .reduce(f64::min)?;
+    let high = week
+        .iter()
+        .filter_map(|d| d.temperature_max)
+        .reduce(f64::max)?;
+    let total: f64 = week.iter().filter_map(|d| d.precipitation_sum).sum();
+    let wet_days = week
+        .iter()
+        .filter(|d| d.precipitation_sum.is_some_and(|mm| mm >= WET_DAY_MM))
+        .count();
+    Some(format!(
+        "week: {low:.0}–{high:.0}°C, {total:.1} mm on {wet_days} wet day{}",
+        if wet_days == 1 { "" } else { "s" }
+    ))
+}
+
+/// Digest lines, most urgent location first, each followed by its week;
+/// locations that failed are listed at the end
+fn digest(
+    repository: &(impl WeatherRepository + Sync),
+    locations: &[String],
+    now: DateTime<Utc>,
+) -> Vec<String> {
+    let results: Vec<Result<WeatherInfo, WeatherError>> = thread::scope(|scope| {
+        let handles: Vec<_> = locations
+            .iter()
+            .map(|location| scope.spawn(|| repository.fetch_weather(location)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("fetch thread panicked"))
+            .collect()
+    });
+
+    let mut entries = Vec::new();
+    let mut weeks = Vec::new();
+    let mut failures = Vec::new();
+    for (location, result) in locations.iter().zip(results) {
+        match result {
+            Ok(info) => {
+                entries.push(DigestEntry::assess(
+                    location.as_str(),
+                    &info,
+                    now,
+                    AdvisoryStandard::default(),
+                ));
+                weeks.push((location.clone(), weekly_summary(&info, now)));
+            }
+            Err(e) => failures.push((location.clone(), e)),
+        }
+    }
+
+    let mut lines = Vec::new();
+    for entry in digest::rank(entries) {
+        lines.extend(ClView::digest_lines(std::slice::from_ref(&entry), &[]));
+        if let Some((_, Some(week))) = weeks.iter().find(|(name, _)| *name == entry.name) {
+            lines.push(format!("  {week}"));
+        }
+    }
+    lines.extend(ClView::digest_lines(&[], &failures));
+    lines
+}
+
+fn main() {
+    let mut locations: Vec<String> = std::env::args().skip(1).collect();
+    if locations.is_empty() {
+        locations = vec!["Oslo".into(), "Madrid".into(), "Reykjavik".into()];
+    }
+
+    for line in digest(&ApiWeatherRepository::new(), &locations, Utc::now()) {
+        println!("{line}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answers from the bundled fixtures, which have no "Atlantis"
+    struct Fixtures;
+
+    impl WeatherRepository for Fixtures {
+        fn fetch_weather(&self, location: &str) -> Result<WeatherInfo, WeatherError> {
+            let (body, latitude, longitude) = match location {
+                "London" => (
+                    include_str!("../tests/fixtures/london/forecast.json"),
+                    51.5074,
+                    -0.1278,
+                ),
+                "Tromsø" => (
+                    include_str!("../tests/fixtures/tromso/forecast.json"),
+                    69.6516,
+                    18.9559,
+                ),
+                _ => return Err(WeatherError::location_not_found(location)),
+            };
+            ApiWeatherRepository::parse_weather_info(location, latitude, longitude, body)
+        }
+    }
+
+    #[test]
+    fn test_digest_with_weekly_summaries() {
+        let locations = ["London", "Tromsø", "Atlantis"].map(String::from);
+        let now = DateTime::parse_from_rfc3339("2025-12-10T10:00:00Z")
+            .unwrap()
+            .to_utc();
+        let lines = digest(&Fixtures, &locations, now);
+
+        let weeks = lines.iter().filter(|l| l.starts_with("  week: ")).count();
+        assert_eq!(weeks, 2, "{lines:#?}");
+        assert!(lines.iter().any(|l| l.starts_with("London")));
+        assert!(lines.iter().any(|l| l.starts_with("Tromsø")));
+        assert!(lines.last().unwrap().starts_with("Atlantis: "));
+    }
+}
@@ -0,0 +1,43 @@
+//! Prints current conditions for a location using only the prelude
+//!
+//! cargo run --example current_conditions -- "Lisbon"
+
+use weather_app::prelude::*;
+
+/// Works with any data source, e.g. a stub repository in tests
+fn describe(repository: &impl WeatherRepository, location: &str) -> Result<String, WeatherError> {
+    let info = repository.fetch_weather(location)?;
+    let current = &info.weather_data.current;
+
+    let temperature = current.temperature.map_or_else(
+        || "unknown".to_string(),
+        |t| format!("{t:.1}°C / {:.1}°F", Temperature::celsius_to_fahrenheit(t)),
+    );
+    let wind = current.wind_speed.map_or_else(
+        || "unknown".to_string(),
+        |w| format!("{w:.0} km/h / {:.0} mph", Speed::kmh_to_mph(w)),
+    );
+
+    Ok(format!(
+        "{}\nTemperature: {temperature}\nWind: {wind}\n{}",
+        info.location,
+        info.summary()
+    ))
+}
+
+fn main() {
+    let location = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let location = if location.is_empty() {
+        "Lisbon".to_string()
+    } else {
+        location
+    };
+
+    match describe(&ApiWeatherRepository::new(), &location) {
+        Ok(report) => println!("{report}"),
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,52 @@
+//! The library's `FileWeatherRepository`: a saved Open-Meteo response
+//! instead of the network, behind the same `WeatherRepository` trait as the
+//! API client
+//!
+//! cargo run --example custom_repository -- tests/fixtures/london/forecast.json
+
+use weather_app::prelude::*;
+
+fn main() {
+    let Some(path) = std::env::args().nth(1) else {
+        eprintln!("Usage: custom_repository <forecast.json> [location name]");
+        std::process::exit(2);
+    };
+    let location = std::env::args()
+        .nth(2)
+        .unwrap_or_else(|| "Saved forecast".to_string());
+
+    // Saved e.g. with `curl "https://api.open-meteo.com/v1/forecast?..." > forecast.json`
+    match FileWeatherRepository::new(path).fetch_weather(&location) {
+        Ok(info) => println!("{}", info.summary()),
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+    #[test]
+    fn test_reads_saved_response() {
+        let repository = FileWeatherRepository::new(format!("{FIXTURES}/london/forecast.json"));
+        let info = repository.fetch_weather("London").unwrap();
+        assert_eq!(info.location, "London");
+        assert!((info.latitude - 51.5074).abs() < 1e-9);
+        assert!(!info.weather_data.hourly.is_empty());
+        assert!(info.summary().to_string().starts_with("London"));
+    }
+
+    #[test]
+    fn test_missing_file_is_a_storage_error() {
+        let repository = FileWeatherRepository::new(format!("{FIXTURES}/nowhere/forecast.json"));
+        assert!(matches!(
+            repository.fetch_weather("Nowhere"),
+            Err(WeatherError::StorageError(_))
+        ));
+    }
+}
@@ -0,0 +1,66 @@
+//! Embeds the weather panel in another egui application.
+//!
+//! The host owns fetching: a `GuiController` runs requests in the background
+//! and each result is handed to the panel, which only renders it.
+//!
+//! Run with `cargo run --example embed_panel`.
+
+use eframe::egui;
+use weather_app::controllers::gui_controller::GuiController;
+use weather_app::repositories::weather_repository::ApiWeatherRepository;
+use weather_app::views::{DisplayOptions, WeatherPanel};
+
+struct HostApp {
+    location: String,
+    controller: GuiController<ApiWeatherRepository>,
+    panel: WeatherPanel,
+    error: Option<String>,
+}
+
+impl eframe::App for HostApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        if let Some(result) = self.controller.poll() {
+            match result {
+                Ok(weather) => {
+                    self.error = None;
+                    self.panel.set_weather(Some(weather));
+                }
+                Err(e) => self.error = Some(e.to_string()),
+            }
+        }
+        if self.controller.is_loading() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+
+        egui::TopBottomPanel::top("host_toolbar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("My dashboard");
+                ui.text_edit_singleline(&mut self.location);
+                if ui.button("Load").clicked() {
+                    if let Some(cached) = self.controller.request_weather(&self.location) {
+                        self.panel.set_weather(Some(cached));
+                    }
+                }
+                if let Some(error) = &self.error {
+                    ui.colored_label(egui::Color32::RED, error);
+                }
+            });
+        });
+
+        egui::CentralPanel::default().show(ctx, |ui| self.panel.ui(ui));
+    }
+}
+
+fn main() -> Result<(), eframe::Error> {
+    let app = HostApp {
+        location: "Seattle".to_string(),
+        controller: GuiController::new(ApiWeatherRepository::new()),
+        panel: WeatherPanel::new(DisplayOptions::default()),
+        error: None,
+    };
+    eframe::run_native(
+        "Embedded weather panel",
+        eframe::NativeOptions::default(),
+        Box::new(|_cc| Ok(Box::new(app))),
+    )
+}
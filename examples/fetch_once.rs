@@ -0,0 +1,55 @@
+//! Fetches one location and prints it as JSON using only the prelude
+//!
+//! cargo run --example fetch_once -- "Lisbon"
+
+use weather_app::prelude::*;
+
+/// The export the CLI prints for `--json`, in metric units
+fn fetch_json(
+    repository: &impl WeatherRepository,
+    location: &str,
+) -> Result<serde_json::Value, WeatherError> {
+    let info = repository.fetch_weather(location)?;
+    let options = DisplayOptions::builder().units(UnitSystem::Metric).build();
+    Ok(export::to_json(&info, &options))
+}
+
+fn main() {
+    let location = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let location = if location.is_empty() {
+        "Lisbon".to_string()
+    } else {
+        location
+    };
+
+    match fetch_json(&ApiWeatherRepository::new(), &location) {
+        Ok(json) => println!("{json:#}"),
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answers every location with the London fixture
+    struct Fixture;
+
+    impl WeatherRepository for Fixture {
+        fn fetch_weather(&self, location: &str) -> Result<WeatherInfo, WeatherError> {
+            let body = include_str!("../tests/fixtures/london/forecast.json");
+            ApiWeatherRepository::parse_weather_info(location, 51.5074, -0.1278, body)
+        }
+    }
+
+    #[test]
+    fn test_fetch_json() {
+        let json = fetch_json(&Fixture, "London").unwrap();
+        assert_eq!(json["location"], "London");
+        assert!(json["current"]["temperature"].is_number());
+        assert!(!json["daily"].as_array().unwrap().is_empty());
+    }
+}
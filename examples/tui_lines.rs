@@ -0,0 +1,80 @@
+//! One summary line per city, cut to fit a terminal row, as a status bar or
+//! TUI list would show them
+//!
+//! cargo run --example tui_lines -- "Tokyo" "Nairobi" "Lima"
+
+use weather_app::prelude::*;
+
+/// Columns a line may take when the terminal width is unknown
+const DEFAULT_WIDTH: usize = 80;
+
+/// `line` shortened to `width` characters, ending in "…" when cut
+fn fit(line: &str, width: usize) -> String {
+    if line.chars().count() <= width {
+        return line.to_string();
+    }
+    let mut fitted: String = line.chars().take(width.saturating_sub(1)).collect();
+    fitted.push('…');
+    fitted
+}
+
+/// A line per city in the given order; a city that fails shows its error
+/// in place of the summary so the list keeps its shape
+fn summary_lines(
+    repository: &impl WeatherRepository,
+    cities: &[String],
+    width: usize,
+) -> Vec<String> {
+    cities
+        .iter()
+        .map(|city| {
+            let line = match repository.fetch_weather(city) {
+                Ok(info) => info.summary().to_string(),
+                Err(e) => format!("{city}: {e}"),
+            };
+            fit(&line, width)
+        })
+        .collect()
+}
+
+fn main() {
+    let mut cities: Vec<String> = std::env::args().skip(1).collect();
+    if cities.is_empty() {
+        cities = vec!["Tokyo".into(), "Nairobi".into(), "Lima".into()];
+    }
+    let width = terminal_size::terminal_size().map_or(DEFAULT_WIDTH, |(w, _)| w.0 as usize);
+
+    for line in summary_lines(&ApiWeatherRepository::new(), &cities, width) {
+        println!("{line}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answers London from the fixture and nothing else
+    struct Fixture;
+
+    impl WeatherRepository for Fixture {
+        fn fetch_weather(&self, location: &str) -> Result<WeatherInfo, WeatherError> {
+            if location != "London" {
+                return Err(WeatherError::location_not_found(location));
+            }
+            let body = include_str!("../tests/fixtures/london/forecast.json");
+            ApiWeatherRepository::parse_weather_info(location, 51.5074, -0.1278, body)
+        }
+    }
+
+    #[test]
+    fn test_summary_lines_fit_the_width() {
+        let cities = ["London", "Atlantis"].map(String::from);
+        let lines = summary_lines(&Fixture, &cities, 40);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("London: "));
+        assert!(lines[1].starts_with("Atlantis: "));
+        assert!(lines.iter().all(|line| line.chars().count() <= 40));
+        assert_eq!(fit("short", 40), "short");
+        assert_eq!(fit("abcdef", 4), "abc…");
+    }
+}
@@ -1,25 +1,69 @@
+use std::path::PathBuf;
+
 use eframe::egui;
-use weather_app::views::gui_view::WeatherApp;
+use weather_app::repositories::favorites_store::FavoritesStore;
+use weather_app::repositories::persistent_weather_repository::OfflineStore;
+use weather_app::repositories::settings_store::SettingsStore;
+use weather_app::repositories::snapshot_store::SnapshotStore;
+use weather_app::views::gui_view::{WeatherApp, WINDOW_TITLE};
 
+/// Usage: gui_main [--from-file <forecast.json>]
 fn main() -> Result<(), eframe::Error> {
+    // Like the console's --from-file: every search reads this file
+    let saved_forecast = saved_forecast_arg(std::env::args().skip(1));
+
+    // Built with eframe's `accesskit` feature: the widget tree is exposed to
+    // screen readers as soon as one connects, with no setting to turn on
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([800.0, 600.0])
-            .with_title("Weather App"),
+            .with_title(WINDOW_TITLE),
         ..Default::default()
     };
 
     eframe::run_native(
-        "Weather App",
+        WINDOW_TITLE,
         options,
         Box::new(|cc| {
             // Setup fonts with better Unicode/emoji support
             setup_custom_fonts(&cc.egui_ctx);
-            Ok(Box::new(WeatherApp::default()))
+            let mut app = WeatherApp::default();
+            if let Some(store) = SettingsStore::default_location() {
+                app = app.with_settings_store(store);
+            }
+            if let Some(store) = FavoritesStore::default_location() {
+                app = app.with_favorites_store(store);
+            }
+            // After the settings, whose contact details go with the lookups
+            app = app.with_name_refresh(&cc.egui_ctx);
+            if let Some(store) = OfflineStore::default_location() {
+                app = app.with_offline_store(store);
+            }
+            // After the settings, which name the location to warm-start with
+            if let Some(store) = SnapshotStore::default_location() {
+                app = app.with_snapshot_store(store);
+            }
+            if let Some(path) = saved_forecast {
+                app = app.with_saved_forecast(path);
+            }
+            Ok(Box::new(app))
         }),
     )
 }
 
+/// Path given with `--from-file <path>` or `--from-file=<path>`
+fn saved_forecast_arg(mut args: impl Iterator<Item = String>) -> Option<PathBuf> {
+    while let Some(arg) = args.next() {
+        if arg == "--from-file" {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(path) = arg.strip_prefix("--from-file=") {
+            return Some(PathBuf::from(path));
+        }
+    }
+    None
+}
+
 fn setup_custom_fonts(ctx: &egui::Context) {
     let mut fonts = egui::FontDefinitions::default();
 
@@ -1,16 +1,204 @@
 //! Application-wide constants for API URLs and configuration
 
+use std::time::Duration;
+
+use chrono::TimeDelta;
+
 /// OpenStreetMap Nominatim API for geocoding location names to coordinates
 pub const NOMINATIM_API_URL: &str = "https://nominatim.openstreetmap.org/search";
 
+/// Open-Meteo geocoding API, an alternative to Nominatim that also returns time zones
+pub const OPEN_METEO_GEOCODING_API_URL: &str = "https://geocoding-api.open-meteo.com/v1/search";
+
 /// Open-Meteo API for fetching weather data
 pub const OPEN_METEO_API_URL: &str = "https://api.open-meteo.com/v1/forecast";
 
+/// Open-Meteo marine API for wave forecasts at coastal locations
+pub const OPEN_METEO_MARINE_API_URL: &str = "https://marine-api.open-meteo.com/v1/marine";
+
+/// Days of marine data requested, enough to cover the hourly forecast
+pub const MARINE_FORECAST_DAYS: usize = 3;
+
+/// Open-Meteo air quality API for pollutants and air quality indices
+pub const OPEN_METEO_AIR_QUALITY_API_URL: &str =
+    "https://air-quality-api.open-meteo.com/v1/air-quality";
+
+/// Days of air quality requested, enough to cover the hourly forecast
+pub const AIR_QUALITY_FORECAST_DAYS: usize = 2;
+
+/// Open-Meteo ensemble API, whose member spread gives forecast confidence
+pub const OPEN_METEO_ENSEMBLE_API_URL: &str = "https://ensemble-api.open-meteo.com/v1/ensemble";
+
+/// US National Weather Service API, for official forecasts in the United States
+pub const NWS_API_URL: &str = "https://api.weather.gov";
+
+/// OpenWeatherMap One Call API 3.0, for users with their own API key
+pub const OPENWEATHERMAP_API_URL: &str = "https://api.openweathermap.org/data/3.0/onecall";
+
+/// Met.no Locationforecast, the Norwegian Meteorological Institute's forecast behind Yr
+pub const METNO_API_URL: &str = "https://api.met.no/weatherapi/locationforecast/2.0/compact";
+
+/// Ensemble model asked for member temperatures; the Canadian global
+/// ensemble has 21 members, enough for a spread at a small payload
+pub const ENSEMBLE_MODEL: &str = "gem_global";
+
+/// How long to wait for a connection to an API server
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long one request may take in all, from connecting to the end of the
+/// response body; the largest forecasts take a few seconds on slow links
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Requests in all, first one included, before a transient failure is reported
+pub const RETRY_ATTEMPTS: u32 = 3;
+
+/// Wait before the first retry of a transient failure, doubled for each further one
+pub const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Age from which a geocoding result on disk is looked up again
+pub const GEOCODE_CACHE_TTL: TimeDelta = TimeDelta::days(30);
+
+/// Most locations kept in the geocoding cache; the oldest go beyond that
+pub const GEOCODE_CACHE_CAPACITY: usize = 500;
+
+/// Candidates offered when a location name is ambiguous
+pub const GEOCODE_CANDIDATES: usize = 5;
+
+/// Age below which cached weather is served without a new request
+pub const WEATHER_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// How long past the TTL cached weather is still shown while a fresh copy loads
+pub const WEATHER_CACHE_STALE_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+/// Locations kept in the in-memory weather cache; the least recently used goes first
+pub const WEATHER_CACHE_CAPACITY: usize = 64;
+
+/// How long a response body is reused for the same URL within a session
+pub const RESPONSE_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Response bodies kept for reuse; the oldest goes first
+pub const RESPONSE_CACHE_CAPACITY: usize = 16;
+
+/// Days between geocoding refreshes of a favorite's display name
+pub const FAVORITE_REFRESH_INTERVAL: TimeDelta = TimeDelta::days(30);
+
+/// Pause between the GUI's background name refreshes, Nominatim's usage
+/// policy allowing one request per second
+pub const FAVORITE_REFRESH_PAUSE: Duration = Duration::from_secs(1);
+
+/// Distance in km beyond which a refreshed favorite is not moved without the
+/// user's confirmation
+pub const FAVORITE_MAX_MOVE_KM: f64 = 10.0;
+
+/// Largest difference between the system clock and a server's `Date` header
+/// that is put down to latency; beyond it "now" is taken from the server
+pub const CLOCK_SKEW_TOLERANCE: TimeDelta = TimeDelta::minutes(5);
+
+/// Largest warm-start snapshot read at GUI startup; a bigger file is ignored
+/// so a damaged cache cannot stall the first frame
+pub const SNAPSHOT_MAX_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Locations whose last weather is kept for offline use; the copy fetched
+/// longest ago is dropped beyond that
+pub const OFFLINE_CAPACITY: usize = 16;
+
+/// Largest offline store read; a bigger file is ignored and started afresh
+pub const OFFLINE_MAX_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Size at which usage.jsonl is pruned to its newest half
+pub const USAGE_LOG_MAX_BYTES: u64 = 1024 * 1024;
+
+/// How long closing the GUI waits for a refresh in flight before abandoning it
+pub const GUI_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Least time between Nominatim requests, which its usage policy sets at one second
+pub const NOMINATIM_MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Cooldown after a 429 or 503 response without a usable `Retry-After` header
+pub const RATE_LIMIT_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// Longest cooldown honored from a `Retry-After` header
+pub const RATE_LIMIT_MAX_COOLDOWN: Duration = Duration::from_secs(3600);
+
+/// How long the GUI shows what changed after a location is fetched again
+pub const CHANGE_ALERT_DURATION: Duration = Duration::from_secs(10);
+
+/// How long the GUI status bar keeps a message such as "Chart saved"
+pub const STATUS_MESSAGE_DURATION: Duration = Duration::from_secs(8);
+
 /// User agent for API requests (required by Nominatim)
 pub const USER_AGENT: &str = "RustWeatherApp/1.0";
 
+/// Batch size from which the CLI asks for a contact address when geocoding with Nominatim
+pub const CONTACT_RECOMMENDED_LOCATIONS: usize = 10;
+
+/// Number of hourly forecast data points kept from the API response
+pub const HOURLY_FORECAST_LIMIT: usize = 48;
+
 /// Number of hourly forecast data points to display
-pub const HOURLY_FORECAST_LIMIT: usize = 24;
+pub const HOURLY_DISPLAY_LIMIT: usize = 24;
+
+/// 15-minute steps requested with the minutely forecast, three hours
+pub const MINUTELY_FORECAST_STEPS: usize = 12;
+
+/// 15-minute steps the console shows, the next two hours
+pub const MINUTELY_DISPLAY_STEPS: usize = 8;
+
+/// Fewest hours reporting a quantity before hourly statistics include it
+pub const HOURLY_STATS_MIN_POINTS: usize = 3;
 
 /// Number of days for daily forecast
 pub const DAILY_FORECAST_DAYS: usize = 7;
+
+/// Longest daily forecast Open-Meteo offers
+pub const MAX_FORECAST_DAYS: usize = 16;
+
+/// Most days of recent weather a fetch may ask for ahead of the forecast
+pub const MAX_PAST_DAYS: usize = 7;
+
+/// Hours of hourly data requested when a long forecast is split into an
+/// hourly and a daily request: the hourly entries kept anyway
+pub const SPLIT_FETCH_HOURLY_HOURS: usize = HOURLY_FORECAST_LIMIT;
+
+/// Grid elevation (meters) above which mountain-specific data is shown automatically
+pub const MOUNTAIN_ELEVATION_THRESHOLD_M: f64 = 500.0;
+
+/// Days of the garden watering table and its total, from today
+pub const GARDEN_WATERING_DAYS: usize = 7;
+
+/// Number of hours scanned for the storm-energy (CAPE) outlook
+pub const STORM_OUTLOOK_HOURS: usize = 48;
+
+/// Number of hours summed for the precipitation outlook in the current conditions
+pub const PRECIPITATION_OUTLOOK_HOURS: i64 = 24;
+
+/// Number of hours summed for the expected snowfall accumulation
+pub const SNOW_ACCUMULATION_HOURS: i64 = 48;
+
+/// Hours past the last hourly forecast point before data is flagged as very stale
+pub const FORECAST_EXPIRY_GRACE_HOURS: i64 = 1;
+
+/// Visibility (meters) below which an hour counts towards a fog / low-visibility window
+pub const FOG_VISIBILITY_THRESHOLD_M: f64 = 1000.0;
+
+/// How far (km/h) gusts must exceed the sustained wind before the views mention them
+pub const GUST_MARGIN_KMH: f64 = 10.0;
+
+/// Number of hours shown in the winds-aloft table
+pub const WINDS_ALOFT_HOURS: usize = 12;
+
+/// Spokes of the console wind rose (one per eight-point compass direction)
+pub const WIND_ROSE_SPOKES: usize = 8;
+
+/// Direction sectors of the GUI wind rose
+pub const WIND_ROSE_SECTORS: usize = 16;
+
+/// Precipitation probability (%) from which the digest flags a wet day
+pub const DIGEST_PRECIPITATION_PROBABILITY: f64 = 50.0;
+
+/// Precipitation probability (%) above which an hour counts as wet in the
+/// daily "wet hours" count
+pub const WET_HOUR_PROBABILITY: f64 = 50.0;
+
+/// Daily low (°C) at or below which the digest warns of frost
+pub const FROST_THRESHOLD_C: f64 = 0.0;
@@ -1,24 +1,525 @@
 //! Command-line controller for coordinating weather data flow
 
+use std::cell::Cell;
+
+use chrono::NaiveDate;
+
 use crate::errors::WeatherError;
+use crate::models::favorites::Favorites;
+use crate::models::usage::UsageRecord;
+use crate::models::weather_info::WeatherInfo;
+use crate::models::weather_log::WeatherLogRecord;
+use crate::repositories::usage_log::UsageLog;
+use crate::repositories::weather_log::WeatherLog;
 use crate::repositories::weather_repository::WeatherRepository;
+use crate::utils::clock;
+use crate::utils::digest::{self, DigestEntry};
+use crate::utils::shutdown::Shutdown;
 use crate::views::cl_view::ClView;
+use crate::views::DisplayOptions;
 
 /// Controller that coordinates between repository (data) and view (display)
 /// Generic over WeatherRepository to allow different data sources
 pub struct ClController<WeatherRepo: WeatherRepository> {
     repository: WeatherRepo,
+    options: DisplayOptions,
+    shutdown: Shutdown,
+    usage_log: Option<UsageLog>,
+    weather_log: Option<WeatherLog>,
+    /// Whether the system clock warning was printed already
+    clock_warned: Cell<bool>,
 }
 
 impl<WeatherRepo: WeatherRepository> ClController<WeatherRepo> {
     pub fn new(repository: WeatherRepo) -> Self {
-        ClController { repository }
+        ClController {
+            repository,
+            options: DisplayOptions::default(),
+            shutdown: Shutdown::default(),
+            usage_log: None,
+            weather_log: None,
+            clock_warned: Cell::new(false),
+        }
+    }
+
+    /// Sets the display options used when rendering
+    pub fn with_options(mut self, options: DisplayOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Stops batch runs early once shutdown is requested
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Records every lookup in `usage_log`; `None` records nothing
+    pub fn with_usage_log(mut self, usage_log: Option<UsageLog>) -> Self {
+        self.usage_log = usage_log;
+        self
+    }
+
+    /// Archives the current conditions of every report shown with `show_weather`
+    pub fn with_weather_log(mut self, weather_log: Option<WeatherLog>) -> Self {
+        self.weather_log = weather_log;
+        self
+    }
+
+    /// Fetches from the repository, recording the lookup if usage statistics are on
+    fn fetch(&self, location: &str) -> Result<WeatherInfo, WeatherError> {
+        let started = std::time::Instant::now();
+        let result = self.repository.fetch_weather(location);
+        if let Some(log) = &self.usage_log {
+            let record = UsageRecord::now(location, false, started.elapsed(), result.is_ok());
+            // Statistics never get in the way of the forecast
+            let _ = log.record(&record);
+        }
+        if let Ok(info) = &result {
+            self.warn_clock_skew(info);
+            if info.offline {
+                let fetched_at = info
+                    .fetched_at
+                    .map(|t| t.with_timezone(&chrono::Local).naive_local());
+                eprintln!("Note: {}", ClView::offline_line(fetched_at));
+            }
+        }
+        result
+    }
+
+    /// Prints the system clock warning for the first result that has one
+    fn warn_clock_skew(&self, info: &WeatherInfo) {
+        if let Some(skew) = info.clock_skew.filter(|_| !self.clock_warned.get()) {
+            eprintln!("Warning: {}", clock::skew_warning(skew));
+            self.clock_warned.set(true);
+        }
     }
 
-    /// Fetches weather data for location and displays it
+    /// Fetches weather data for location and displays it, then appends the
+    /// current conditions to the weather log if one is set
     pub fn show_weather(&self, location: &str) -> Result<(), WeatherError> {
-        let weather_info = self.repository.fetch_weather(location)?;
-        ClView::display(&weather_info);
+        let weather_info = self.fetch(location)?;
+        ClView::display(&weather_info, &self.options);
+        if let Some(log) = &self.weather_log {
+            let record = WeatherLogRecord::from_weather(&weather_info, chrono::Utc::now());
+            let path = log.append(location, &record)?;
+            if self.options.verbose {
+                eprintln!("Logged current conditions to {}", path.display());
+            }
+        }
         Ok(())
     }
+
+    /// Fetches each location in turn and prints one summary line per location,
+    /// stopping early on shutdown or when the server rate-limits us (naming
+    /// the locations skipped). Returns whether every location succeeded.
+    pub fn show_batch(&self, locations: &[String]) -> bool {
+        let mut all_ok = true;
+        for (index, location) in locations.iter().enumerate() {
+            if self.shutdown.is_requested() {
+                return false;
+            }
+            let result = self.fetch(location.trim());
+            println!("{}", ClView::batch_line(location, &result));
+            all_ok &= result.is_ok();
+            let rest = locations[index + 1..].iter().map(|l| l.trim());
+            if let Some(line) = ClView::rate_limit_stop(&result, rest) {
+                println!("{line}");
+                return false;
+            }
+        }
+        all_ok
+    }
+
+    /// Prints the current temperature for every favorite, stopping early on
+    /// shutdown or a rate limit. Returns whether every favorite succeeded.
+    pub fn show_favorites(&self, favorites: &Favorites) -> bool {
+        let mut all_ok = true;
+        let entries = favorites.entries();
+        for (index, favorite) in entries.iter().enumerate() {
+            if self.shutdown.is_requested() {
+                return false;
+            }
+            let result = self.fetch(&favorite.query());
+            println!("{}", ClView::favorite_line(favorite, &result));
+            all_ok &= result.is_ok();
+            let rest = entries[index + 1..].iter().map(|f| f.name());
+            if let Some(line) = ClView::rate_limit_stop(&result, rest) {
+                println!("{line}");
+                return false;
+            }
+        }
+        all_ok
+    }
+
+    /// Geocodes every favorite again to update the name it is shown by (see
+    /// `Favorite::apply_refresh`) and prints what changed, stopping early on
+    /// shutdown or a rate limit. Returns whether every lookup succeeded.
+    pub fn refresh_names(&self, favorites: &mut Favorites, today: NaiveDate) -> bool {
+        let locations: Vec<String> = favorites
+            .entries()
+            .iter()
+            .map(|f| f.location.clone())
+            .collect();
+        let mut all_ok = true;
+        for (index, location) in locations.iter().enumerate() {
+            if self.shutdown.is_requested() {
+                return false;
+            }
+            let recorded = favorites.find(location).and_then(|f| f.resolved.clone());
+            let result = self.repository.resolve_place(location, recorded.as_ref());
+            let rest = locations[index + 1..].iter().map(String::as_str);
+            let stopped = ClView::rate_limit_stop(&result, rest);
+            let outcome = match result {
+                Ok(place) => favorites.apply_refresh(location, place, today),
+                Err(e) => {
+                    println!("{location}: {e}");
+                    all_ok = false;
+                    None
+                }
+            };
+            let favorite = favorites.find(location);
+            if let Some(line) = favorite
+                .zip(outcome)
+                .and_then(|(favorite, outcome)| ClView::refresh_line(favorite, &outcome))
+            {
+                println!("{line}");
+            }
+            if let Some(line) = stopped {
+                println!("{line}");
+                return false;
+            }
+        }
+        all_ok
+    }
+
+    /// Fetches every favorite and prints them most urgent first, each as a
+    /// summary line with at most one callout. Nothing is printed if shutdown
+    /// interrupts the fetching. Returns whether every favorite succeeded.
+    pub fn show_digest(&self, favorites: &Favorites) -> bool {
+        let now = chrono::Utc::now();
+        let mut entries = Vec::new();
+        let mut failures = Vec::new();
+        let mut stopped = None;
+        let favorites = favorites.entries();
+        for (index, favorite) in favorites.iter().enumerate() {
+            if self.shutdown.is_requested() {
+                return false;
+            }
+            let result = self.fetch(&favorite.query());
+            let rest = favorites[index + 1..].iter().map(|f| f.name());
+            stopped = ClView::rate_limit_stop(&result, rest);
+            match result {
+                Ok(info) => entries.push(DigestEntry::assess(
+                    favorite.title(),
+                    &info,
+                    now,
+                    self.options.advisory_standard,
+                )),
+                Err(e) => failures.push((favorite.title(), e)),
+            }
+            if stopped.is_some() {
+                break;
+            }
+        }
+
+        for line in ClView::digest_lines(&digest::rank(entries), &failures) {
+            println!("{line}");
+        }
+        if let Some(line) = stopped {
+            println!("{line}");
+        }
+        failures.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::sample_weather_info;
+    use crate::models::favorites::ResolvedPlace;
+    use crate::models::settings::Settings;
+    use crate::repositories::file_weather_repository::FileWeatherRepository;
+    use crate::repositories::persistent_weather_repository::PersistentWeatherRepository;
+
+    /// Knows only Seattle
+    struct StubRepository;
+
+    impl WeatherRepository for StubRepository {
+        fn fetch_weather(&self, location: &str) -> Result<WeatherInfo, WeatherError> {
+            match location {
+                "Seattle" => Ok(sample_weather_info()),
+                _ => Err(WeatherError::location_not_found(location)),
+            }
+        }
+    }
+
+    /// Geocodes "seatle" and moves "Bend" 150 km; everything else fails
+    struct PlaceRepository;
+
+    impl WeatherRepository for PlaceRepository {
+        fn fetch_weather(&self, location: &str) -> Result<WeatherInfo, WeatherError> {
+            Err(WeatherError::location_not_found(location))
+        }
+
+        fn resolve_place(
+            &self,
+            location: &str,
+            _recorded: Option<&ResolvedPlace>,
+        ) -> Result<Option<ResolvedPlace>, WeatherError> {
+            match location {
+                "seatle" => Ok(Some(ResolvedPlace::new(
+                    "Seattle, United States",
+                    47.6,
+                    -122.3,
+                ))),
+                "Bend" => Ok(Some(ResolvedPlace::new(
+                    "Bend, United States",
+                    44.06,
+                    -119.9,
+                ))),
+                _ => Err(WeatherError::NetworkError("offline".to_string())),
+            }
+        }
+    }
+
+    #[test]
+    fn test_refresh_names() {
+        let today = NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+        let mut favorites = Favorites::default();
+        favorites.save("seatle", None, None);
+        favorites.save("Bend", Some("Cabin".to_string()), None);
+        favorites.apply_refresh(
+            "Bend",
+            Some(ResolvedPlace::new("Bend, United States", 44.06, -121.31)),
+            today,
+        );
+
+        let controller = ClController::new(PlaceRepository);
+        assert!(controller.refresh_names(&mut favorites, today));
+        let entries = favorites.entries();
+        assert_eq!(entries[0].name(), "Seattle, United States");
+        assert_eq!(entries[1].resolved.as_ref().unwrap().longitude, -121.31);
+        assert!(entries[1].moved.is_some());
+        assert!(entries.iter().all(|f| f.refreshed == Some(today)));
+
+        // A failed lookup leaves the entry as it was
+        favorites.save("Atlantis", None, None);
+        assert!(!controller.refresh_names(&mut favorites, today));
+        assert_eq!(favorites.entries()[2].refreshed, None);
+    }
+
+    #[test]
+    fn test_show_weather() {
+        let controller = ClController::new(StubRepository);
+        assert!(controller.show_weather("Seattle").is_ok());
+        assert!(matches!(
+            controller.show_weather("Atlantis"),
+            Err(WeatherError::LocationNotFound { .. })
+        ));
+    }
+
+    /// Controller reading a saved forecast the way `--from-file` does
+    fn from_file(
+        path: impl Into<std::path::PathBuf>,
+        options: DisplayOptions,
+    ) -> ClController<PersistentWeatherRepository<FileWeatherRepository>> {
+        ClController::new(PersistentWeatherRepository::new(
+            FileWeatherRepository::new(path),
+            None,
+        ))
+        .with_options(options)
+    }
+
+    #[test]
+    fn test_show_weather_renders_saved_forecasts() {
+        let options = DisplayOptions {
+            mountain: true,
+            storm: true,
+            garden: true,
+            ..Default::default()
+        };
+        for (name, temperature, condition) in [
+            ("sunny", "Temperature: 29.4°C", "Clear sky"),
+            ("rainy", "Temperature: 10.2°C", "Rain"),
+            ("snowy", "Temperature: -5.2°C", "Snow"),
+        ] {
+            let path = format!(
+                "{}/tests/fixtures/{name}/forecast.json",
+                env!("CARGO_MANIFEST_DIR")
+            );
+            let controller = from_file(path, options.clone());
+            assert!(controller.show_weather(name).is_ok(), "{name}");
+
+            let info = controller.fetch(name).unwrap();
+            let report = ClView::render(&info, &controller.options);
+            assert!(report.contains(&format!("Location: {name}")), "{report}");
+            assert!(report.contains(temperature), "{report}");
+            assert!(
+                report.contains(&format!(
+                    "Weather Code: {} ({condition})",
+                    info.weather_data.current.weather_code.unwrap()
+                )),
+                "{report}"
+            );
+
+            // Every location gets the saved forecast, named as asked
+            let locations = [name.to_string(), "Elsewhere".to_string()];
+            let results: Vec<_> = locations.iter().map(|l| controller.fetch(l)).collect();
+            let lines = ClView::batch_lines(&locations, &results);
+            assert_eq!(lines.len(), 2, "{lines:?}");
+            assert!(lines[0].starts_with(&format!("{name}: ")), "{lines:?}");
+            assert!(lines[1].starts_with("Elsewhere: "), "{lines:?}");
+            assert!(
+                lines.iter().all(|line| line.contains(condition)),
+                "{lines:?}"
+            );
+            assert!(controller.show_batch(&locations));
+        }
+    }
+
+    #[test]
+    fn test_unreadable_saved_forecast_is_reported() {
+        let dir = std::env::temp_dir().join(format!("weather-app-cl-file-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let missing = dir.join("missing.json");
+        let malformed = dir.join("malformed.json");
+        std::fs::write(&malformed, "{\"latitude\": 52.5,").unwrap();
+
+        let controller = from_file(&missing, DisplayOptions::default());
+        let error = controller.show_weather("Berlin").unwrap_err();
+        assert!(matches!(error, WeatherError::StorageError(_)), "{error:?}");
+        let results = [controller.fetch("Berlin")];
+        assert_eq!(
+            ClView::batch_lines(&["Berlin".to_string()], &results),
+            [format!(
+                "Berlin: Storage error: cannot read saved forecast {}: {}",
+                missing.display(),
+                std::fs::read_to_string(&missing).unwrap_err()
+            )]
+        );
+        assert!(!controller.show_batch(&["Berlin".to_string()]));
+
+        let controller = from_file(&malformed, DisplayOptions::default());
+        let error = controller.show_weather("Berlin").unwrap_err();
+        assert!(matches!(error, WeatherError::ParseError(_)), "{error:?}");
+        let results = [controller.fetch("Berlin")];
+        let lines = ClView::batch_lines(&["Berlin".to_string()], &results);
+        assert!(
+            lines[0].starts_with(&format!(
+                "Berlin: Parse error: Invalid file {}: ",
+                malformed.display()
+            )),
+            "{lines:?}"
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_show_weather_appends_to_weather_log() {
+        let dir = std::env::temp_dir().join(format!("weather-app-cl-log-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let controller =
+            ClController::new(StubRepository).with_weather_log(Some(WeatherLog::new(&dir)));
+        assert!(controller.show_weather("Seattle").is_ok());
+        assert!(controller.show_weather("Seattle").is_ok());
+        assert!(controller.show_weather("Atlantis").is_err());
+
+        let files: Vec<_> = std::fs::read_dir(&dir).unwrap().flatten().collect();
+        assert_eq!(files.len(), 1);
+        let (records, skipped) = crate::repositories::weather_log::read(&files[0].path()).unwrap();
+        assert_eq!((records.len(), skipped), (2, 0));
+        assert_eq!(
+            records[0].temperature,
+            sample_weather_info().weather_data.current.temperature
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_show_batch_stops_on_shutdown() {
+        let shutdown = Shutdown::default();
+        let controller = ClController::new(StubRepository).with_shutdown(shutdown.clone());
+        assert!(controller.show_batch(&["Seattle".to_string()]));
+
+        shutdown.request();
+        assert!(!controller.show_batch(&["Seattle".to_string()]));
+    }
+
+    #[test]
+    fn test_show_favorites() {
+        let controller = ClController::new(StubRepository);
+        let mut favorites = Favorites::default();
+        favorites.save("Seattle", Some("Home".to_string()), None);
+        assert!(controller.show_favorites(&favorites));
+
+        favorites.save("Atlantis", None, None);
+        assert!(!controller.show_favorites(&favorites));
+    }
+
+    #[test]
+    fn test_show_digest() {
+        let controller = ClController::new(StubRepository);
+        let mut favorites = Favorites::default();
+        favorites.save("Seattle", None, None);
+        assert!(controller.show_digest(&favorites));
+
+        favorites.save("Atlantis", None, None);
+        assert!(!controller.show_digest(&favorites));
+    }
+
+    #[test]
+    fn test_usage_is_recorded_only_when_enabled() {
+        let dir = std::env::temp_dir().join(format!("weather-app-cl-usage-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let log = UsageLog::new(dir.join("usage.jsonl"));
+
+        let controller = ClController::new(StubRepository)
+            .with_usage_log(log.clone().enabled_by(&Settings::default()));
+        assert!(controller.show_weather("Seattle").is_ok());
+        assert!(!log.path().exists());
+
+        let settings = Settings {
+            usage_stats: true,
+            ..Settings::default()
+        };
+        let controller =
+            ClController::new(StubRepository).with_usage_log(log.clone().enabled_by(&settings));
+        assert!(controller.show_weather("Seattle").is_ok());
+        assert!(controller.show_weather("Atlantis").is_err());
+        let records = log.load().unwrap();
+        assert_eq!(records.len(), 2);
+        assert!(records[0].ok && !records[1].ok);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_show_batch_reports_failures() {
+        let controller = ClController::new(StubRepository);
+        assert!(controller.show_batch(&[" Seattle ".to_string()]));
+        assert!(!controller.show_batch(&["Seattle".to_string(), "Atlantis".to_string()]));
+    }
+
+    #[test]
+    fn test_batch_stops_at_rate_limit_and_names_skipped() {
+        use crate::repositories::weather_repository::ApiWeatherRepository;
+        use crate::test_support::{MockRoute, MockServer};
+
+        let server = MockServer::start(vec![MockRoute::new("/v1/forecast")
+            .status(429)
+            .header("Retry-After", "30")]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"));
+        let controller = ClController::new(repository);
+
+        let mut favorites = Favorites::default();
+        favorites.save("46.02, 7.75", Some("Zermatt".to_string()), None);
+        favorites.save("59.91, 10.75", Some("Oslo".to_string()), None);
+        favorites.save("60.39, 5.32", None, None);
+        assert!(!controller.show_favorites(&favorites));
+        assert!(!controller.show_digest(&favorites));
+        // Only the first favorite reached the server; the rest were skipped
+        assert_eq!(server.requests_to("/v1/forecast").len(), 1);
+    }
 }
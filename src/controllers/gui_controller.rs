@@ -0,0 +1,636 @@
+//! GUI controller: non-blocking weather requests for the egui view
+
+use crate::errors::WeatherError;
+use crate::models::usage::UsageRecord;
+use crate::models::weather_info::WeatherInfo;
+use crate::repositories::cached_weather_repository::CachedWeatherRepository;
+use crate::repositories::snapshot_store::SnapshotStore;
+use crate::repositories::usage_log::UsageLog;
+use crate::repositories::weather_repository::WeatherRepository;
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant};
+
+/// Request counts for one GUI session, logged when the window closes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SessionMetrics {
+    pub requests: usize,
+    /// Requests answered at once from the cache (fresh or stale)
+    pub cache_hits: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// Requests still in flight when the session ended
+    pub abandoned: usize,
+    /// Locations in the weather cache when the metrics were taken
+    pub cache_entries: usize,
+    /// Network requests answered by the repository's URL response cache,
+    /// counted apart from `cache_hits`
+    pub response_cache_hits: usize,
+    /// Longest network time of a single fetch (geocoding through the last response)
+    pub slowest_fetch_ms: u128,
+}
+
+impl fmt::Display for SessionMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "requests={} cache_hits={} succeeded={} failed={} abandoned={} cache_entries={} response_cache_hits={} slowest_fetch_ms={}",
+            self.requests,
+            self.cache_hits,
+            self.succeeded,
+            self.failed,
+            self.abandoned,
+            self.cache_entries,
+            self.response_cache_hits,
+            self.slowest_fetch_ms
+        )
+    }
+}
+
+/// Outcome of `GuiController::shutdown`
+#[derive(Debug)]
+pub struct ShutdownReport {
+    /// Result of the refresh that finished during the grace period, if any
+    pub completed: Option<Result<WeatherInfo, WeatherError>>,
+    pub metrics: SessionMetrics,
+}
+
+/// A result and the generation of the request it answers
+type Tagged = (u64, Result<WeatherInfo, WeatherError>);
+
+/// The latest request, whose result `poll` is waiting for
+struct InFlight {
+    generation: u64,
+    location: String,
+    started: Instant,
+    cache_hit: bool,
+}
+
+/// Fetches in the background so the UI thread never blocks on the network.
+/// Cached data is returned at once and replaced when the fresh result arrives.
+/// A failed refresh leaves the cached data in place (see `showing_cached`).
+///
+/// Every request starts a new generation. Results of older generations are
+/// dropped on receipt, so a slow answer to an earlier search never replaces
+/// the answer to the latest one. A blocking request cannot be interrupted:
+/// a superseded fetch runs to the end and only fills the cache.
+pub struct GuiController<WeatherRepo: WeatherRepository> {
+    repository: CachedWeatherRepository<WeatherRepo>,
+    generation: u64,
+    sender: Sender<Tagged>,
+    results: Receiver<Tagged>,
+    in_flight: Option<InFlight>,
+    /// Whether the data handed out last came from a cache or snapshot and
+    /// has not been replaced by a fresh result yet
+    showing_cached: bool,
+    snapshots: Option<SnapshotStore>,
+    metrics: SessionMetrics,
+    usage_log: Option<UsageLog>,
+}
+
+impl<WeatherRepo: WeatherRepository + Send + Sync + 'static> GuiController<WeatherRepo> {
+    pub fn new(repository: WeatherRepo) -> Self {
+        Self::with_cache(CachedWeatherRepository::new(repository))
+    }
+
+    pub fn with_cache(repository: CachedWeatherRepository<WeatherRepo>) -> Self {
+        let (sender, results) = mpsc::channel();
+        GuiController {
+            repository,
+            generation: 0,
+            sender,
+            results,
+            in_flight: None,
+            showing_cached: false,
+            snapshots: None,
+            metrics: SessionMetrics::default(),
+            usage_log: None,
+        }
+    }
+
+    /// Saves every fresh result to `snapshots` for `warm_start`; `None` saves nothing
+    pub fn set_snapshot_store(&mut self, snapshots: Option<SnapshotStore>) {
+        self.snapshots = snapshots;
+    }
+
+    /// Records every completed request in `usage_log`; `None` records nothing
+    pub fn set_usage_log(&mut self, usage_log: Option<UsageLog>) {
+        self.usage_log = usage_log;
+    }
+
+    pub fn repository(&self) -> &CachedWeatherRepository<WeatherRepo> {
+        &self.repository
+    }
+
+    /// Sends later requests to `repository`, e.g. after a setting that
+    /// changes what is fetched. The session's metrics, the weather cache and
+    /// the request in flight are kept: `poll` still delivers its result.
+    pub fn set_repository(&mut self, repository: WeatherRepo) {
+        self.repository.set_inner(repository);
+    }
+
+    /// Starts fetching `location`, superseding any pending request, and
+    /// returns cached data to show until `poll` delivers the fresh result
+    pub fn request_weather(&mut self, location: &str) -> Option<WeatherInfo> {
+        self.start_request(location, false)
+    }
+
+    /// Fetches `location` again from the servers even when its cached data
+    /// or an identical response is fresh, for the refresh button; the result
+    /// arrives through `poll` as usual
+    pub fn refresh_weather(&mut self, location: &str) {
+        self.start_request(location, true);
+    }
+
+    /// Starts a new generation for `location`, bypassing the cache if `force`
+    fn start_request(&mut self, location: &str, force: bool) -> Option<WeatherInfo> {
+        let location = location.trim();
+        self.generation += 1;
+        let generation = self.generation;
+        if self.in_flight.is_some() {
+            self.metrics.abandoned += 1;
+        }
+        self.in_flight = Some(InFlight {
+            generation,
+            location: location.to_string(),
+            started: Instant::now(),
+            cache_hit: false,
+        });
+
+        let sender = self.sender.clone();
+        // The controller may be gone when a superseded fetch ends
+        let deliver = move |result| {
+            let _ = sender.send((generation, result));
+        };
+        let cached = if force {
+            self.repository.refresh_with(location, deliver);
+            None
+        } else {
+            self.repository.fetch_weather_swr_with(location, deliver)
+        };
+        self.metrics.requests += 1;
+        if cached.is_some() {
+            self.metrics.cache_hits += 1;
+            if let Some(request) = &mut self.in_flight {
+                request.cache_hit = true;
+            }
+        }
+        self.showing_cached = cached.is_some();
+        cached
+    }
+
+    /// `request_weather` for the first search of a session: without data in
+    /// memory, the snapshot saved for `location` by an earlier session is
+    /// returned instead, so the dashboard fills before the network answers
+    pub fn warm_start(&mut self, location: &str) -> Option<WeatherInfo> {
+        let cached = self.request_weather(location);
+        if cached.is_some() {
+            return cached;
+        }
+        // A local read of bounded size; an unreadable snapshot is just skipped
+        let snapshot = self
+            .snapshots
+            .as_ref()
+            .and_then(|store| store.load(location.trim()).ok().flatten());
+        self.showing_cached = snapshot.is_some();
+        snapshot
+    }
+
+    /// Whether the shown data is cached rather than the result of the latest
+    /// request: true from a cache hit or warm start until a fresh result
+    /// arrives, and still true after the refresh failed
+    pub fn showing_cached(&self) -> bool {
+        self.showing_cached
+    }
+
+    /// Result of the latest request, once it has arrived; results of
+    /// superseded requests are dropped
+    pub fn poll(&mut self) -> Option<Result<WeatherInfo, WeatherError>> {
+        let generation = self.in_flight.as_ref()?.generation;
+        while let Ok((tag, result)) = self.results.try_recv() {
+            if tag == generation {
+                self.finish(&result);
+                return Some(result);
+            }
+        }
+        None
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.in_flight.is_some()
+    }
+
+    /// Location of the request `poll` is waiting for, if any
+    pub fn pending_location(&self) -> Option<&str> {
+        self.in_flight
+            .as_ref()
+            .map(|request| request.location.as_str())
+    }
+
+    pub fn metrics(&self) -> SessionMetrics {
+        SessionMetrics {
+            cache_entries: self.repository.len(),
+            response_cache_hits: self.repository.response_cache_hits(),
+            ..self.metrics
+        }
+    }
+
+    /// Ends the session: gives a refresh in flight up to `timeout` to finish
+    /// (its result is cached by the worker either way), then abandons it so
+    /// closing never blocks on the network
+    pub fn shutdown(&mut self, timeout: Duration) -> ShutdownReport {
+        let deadline = Instant::now() + timeout;
+        let mut completed = None;
+        if let Some(generation) = self.in_flight.as_ref().map(|request| request.generation) {
+            // We hold a sender, so this ends with the result or the timeout
+            while let Ok((tag, result)) = self
+                .results
+                .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            {
+                if tag == generation {
+                    self.finish(&result);
+                    completed = Some(result);
+                    break;
+                }
+            }
+            if completed.is_none() {
+                self.in_flight = None;
+                self.metrics.abandoned += 1;
+            }
+        }
+
+        ShutdownReport {
+            completed,
+            metrics: self.metrics(),
+        }
+    }
+
+    /// Ends the latest request with `result`: counts it and logs its usage
+    fn finish(&mut self, result: &Result<WeatherInfo, WeatherError>) {
+        let request = self.in_flight.take();
+        match result {
+            Ok(info) => {
+                // An offline copy is cached data too, however it arrived
+                self.showing_cached = info.offline;
+                self.metrics.succeeded += 1;
+                if let Some(timings) = info.timings {
+                    self.metrics.slowest_fetch_ms =
+                        self.metrics.slowest_fetch_ms.max(timings.total.as_millis());
+                }
+            }
+            Err(_) => self.metrics.failed += 1,
+        }
+        if let (Some(store), Some(request), Ok(info)) = (&self.snapshots, &request, result) {
+            // Only costs the next warm start if it fails
+            let _ = store.save(&request.location, info);
+        }
+        if let (Some(log), Some(request)) = (&self.usage_log, request) {
+            let record = UsageRecord::now(
+                &request.location,
+                request.cache_hit,
+                request.started.elapsed(),
+                result.is_ok(),
+            );
+            // Statistics never get in the way of the forecast
+            let _ = log.record(&record);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::sample_weather_info;
+    use std::thread;
+
+    struct StubRepository;
+
+    impl WeatherRepository for StubRepository {
+        fn fetch_weather(&self, location: &str) -> Result<WeatherInfo, WeatherError> {
+            thread::sleep(Duration::from_millis(20));
+            match location {
+                "Seattle" => Ok(sample_weather_info()),
+                _ => Err(WeatherError::location_not_found(location)),
+            }
+        }
+    }
+
+    /// Takes far longer than any shutdown grace period
+    struct SlowRepository;
+
+    impl WeatherRepository for SlowRepository {
+        fn fetch_weather(&self, _location: &str) -> Result<WeatherInfo, WeatherError> {
+            thread::sleep(Duration::from_secs(5));
+            Ok(sample_weather_info())
+        }
+    }
+
+    /// Answers for any location, after a delay that depends on the location
+    struct DelayedRepository;
+
+    impl WeatherRepository for DelayedRepository {
+        fn fetch_weather(&self, location: &str) -> Result<WeatherInfo, WeatherError> {
+            let delay = if location.starts_with("Slow") {
+                200
+            } else {
+                20
+            };
+            thread::sleep(Duration::from_millis(delay));
+            let mut info = sample_weather_info();
+            info.location = location.to_string();
+            // Paris and Oslo are cached as the two places they are
+            (info.latitude, info.longitude) = if location.ends_with("Paris") {
+                (48.86, 2.35)
+            } else {
+                (59.91, 10.75)
+            };
+            Ok(info)
+        }
+    }
+
+    /// Answers with a copy from disk, as `PersistentWeatherRepository` does offline
+    struct OfflineRepository;
+
+    impl WeatherRepository for OfflineRepository {
+        fn fetch_weather(&self, _location: &str) -> Result<WeatherInfo, WeatherError> {
+            let mut info = sample_weather_info();
+            info.offline = true;
+            Ok(info)
+        }
+    }
+
+    fn wait_for<R: WeatherRepository + Send + Sync + 'static>(
+        controller: &mut GuiController<R>,
+    ) -> Result<WeatherInfo, WeatherError> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            if let Some(result) = controller.poll() {
+                return result;
+            }
+            assert!(Instant::now() < deadline, "request did not finish");
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn test_second_request_is_served_from_cache() {
+        let mut controller = GuiController::new(StubRepository);
+        assert!(controller.request_weather("Seattle").is_none());
+        assert!(controller.is_loading());
+        assert!(wait_for(&mut controller).is_ok());
+        assert!(!controller.is_loading());
+
+        assert_eq!(
+            controller.request_weather(" Seattle "),
+            Some(sample_weather_info())
+        );
+        assert!(wait_for(&mut controller).is_ok());
+    }
+
+    #[test]
+    fn test_refresh_skips_the_cache() {
+        let mut controller = GuiController::new(DelayedRepository);
+        controller.request_weather("Seattle");
+        assert!(wait_for(&mut controller).is_ok());
+
+        controller.refresh_weather("Seattle");
+        assert!(controller.is_loading());
+        assert!(!controller.showing_cached());
+        assert!(wait_for(&mut controller).is_ok());
+        let metrics = controller.metrics();
+        assert_eq!((metrics.requests, metrics.cache_hits), (2, 0));
+    }
+
+    #[test]
+    fn test_refresh_button_reaches_the_server_each_time() {
+        use crate::repositories::weather_repository::ApiWeatherRepository;
+        use crate::test_support::{MockRoute, MockServer};
+
+        let server = MockServer::start(vec![MockRoute::new("/v1/forecast")
+            .json(serde_json::json!({"current": {"temperature_2m": 12.0, "weather_code": 3}}))]);
+        let mut controller = GuiController::new(
+            ApiWeatherRepository::new()
+                .with_endpoints(server.url("/search"), server.url("/v1/forecast")),
+        );
+
+        controller.request_weather("47.61, -122.33");
+        assert!(wait_for(&mut controller).is_ok());
+        // Two presses inside the response cache's minute
+        for _ in 0..2 {
+            controller.refresh_weather("47.61, -122.33");
+            assert!(wait_for(&mut controller).is_ok());
+        }
+        assert_eq!(server.requests_to("/v1/forecast").len(), 3);
+    }
+
+    #[test]
+    fn test_usage_log_records_cache_hits() {
+        let dir =
+            std::env::temp_dir().join(format!("weather-app-gui-usage-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let log = UsageLog::new(dir.join("usage.jsonl"));
+        let mut controller = GuiController::new(StubRepository);
+        controller.set_usage_log(Some(log.clone()));
+
+        controller.request_weather("Seattle");
+        assert!(wait_for(&mut controller).is_ok());
+        controller.request_weather("Seattle");
+        assert!(wait_for(&mut controller).is_ok());
+
+        let records = log.load().unwrap();
+        assert_eq!(records.len(), 2);
+        assert!(!records[0].cache_hit && records[0].latency_ms >= 20);
+        assert!(records[1].cache_hit);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_shutdown_abandons_slow_refresh() {
+        let mut controller = GuiController::new(SlowRepository);
+        controller.request_weather("Seattle");
+
+        let started = Instant::now();
+        let report = controller.shutdown(Duration::from_millis(100));
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(report.completed.is_none());
+        assert_eq!(report.metrics.requests, 1);
+        assert_eq!(report.metrics.abandoned, 1);
+        assert!(!controller.is_loading());
+    }
+
+    #[test]
+    fn test_shutdown_waits_for_refresh_within_grace_period() {
+        let mut controller = GuiController::new(StubRepository);
+        controller.request_weather("Seattle");
+        let report = controller.shutdown(Duration::from_secs(1));
+        assert!(matches!(report.completed, Some(Ok(_))));
+
+        controller.request_weather("Seattle");
+        assert!(wait_for(&mut controller).is_ok());
+        controller.request_weather("Atlantis");
+        let report = controller.shutdown(Duration::from_secs(1));
+        assert!(matches!(report.completed, Some(Err(_))));
+        assert_eq!(
+            report.metrics,
+            SessionMetrics {
+                requests: 3,
+                cache_hits: 1,
+                succeeded: 2,
+                failed: 1,
+                abandoned: 0,
+                cache_entries: 1,
+                response_cache_hits: 0,
+                slowest_fetch_ms: 0,
+            }
+        );
+        assert_eq!(
+            report.metrics.to_string(),
+            "requests=3 cache_hits=1 succeeded=2 failed=1 abandoned=0 cache_entries=1 response_cache_hits=0 slowest_fetch_ms=0"
+        );
+    }
+
+    #[test]
+    fn test_latest_request_wins() {
+        // The earlier request finishes last, or first: either way only the
+        // later one is delivered
+        for (first, second) in [("Slow Paris", "Oslo"), ("Paris", "Slow Oslo")] {
+            let mut controller = GuiController::new(DelayedRepository);
+            controller.request_weather(first);
+            controller.request_weather(second);
+            assert_eq!(controller.pending_location(), Some(second));
+
+            assert_eq!(wait_for(&mut controller).unwrap().location, second);
+            assert!(!controller.is_loading());
+            // The superseded fetch still fills the cache, and is not delivered
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while controller.repository().len() < 2 {
+                assert!(Instant::now() < deadline, "superseded fetch did not finish");
+                thread::sleep(Duration::from_millis(5));
+            }
+            assert!(controller.poll().is_none());
+
+            let metrics = controller.metrics();
+            assert_eq!((metrics.requests, metrics.abandoned), (2, 1));
+            assert_eq!((metrics.succeeded, metrics.failed), (1, 0));
+            assert_eq!(metrics.cache_entries, 2);
+        }
+    }
+
+    #[test]
+    fn test_set_repository_keeps_the_session() {
+        let mut controller: GuiController<Box<dyn WeatherRepository + Send + Sync>> =
+            GuiController::new(Box::new(DelayedRepository));
+        controller.request_weather("Oslo");
+        wait_for(&mut controller).unwrap();
+        controller.request_weather("Slow Paris");
+
+        // Swapped mid-request: the pending search still arrives, from the
+        // repository it started on
+        controller.set_repository(Box::new(StubRepository));
+        assert_eq!(controller.pending_location(), Some("Slow Paris"));
+        assert_eq!(wait_for(&mut controller).unwrap().location, "Slow Paris");
+        let metrics = controller.metrics();
+        assert_eq!((metrics.requests, metrics.succeeded), (2, 2));
+        assert_eq!((metrics.abandoned, metrics.cache_entries), (0, 2));
+
+        // Later requests go to the new repository, which knows only Seattle
+        controller.request_weather("Bergen");
+        assert!(wait_for(&mut controller).is_err());
+        assert_eq!(controller.metrics().failed, 1);
+    }
+
+    /// Snapshot store in a fresh temporary directory, seeded with yesterday's
+    /// weather for `location`
+    fn seeded_snapshots(name: &str, location: &str) -> SnapshotStore {
+        let dir = std::env::temp_dir().join(format!(
+            "weather-app-warm-start-{name}-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        let store = SnapshotStore::new(dir.join("last_weather.json"));
+        let mut yesterday = sample_weather_info();
+        yesterday.location = format!("{location} (yesterday)");
+        yesterday.fetched_at = Some(chrono::Utc::now() - chrono::TimeDelta::days(1));
+        store.save(location, &yesterday).unwrap();
+        store
+    }
+
+    #[test]
+    fn test_warm_start_shows_snapshot_until_fresh_data() {
+        let snapshots = seeded_snapshots("fresh", "Slow Oslo");
+        let mut controller = GuiController::new(DelayedRepository);
+        controller.set_snapshot_store(Some(snapshots.clone()));
+
+        let started = Instant::now();
+        let cached = controller.warm_start("Slow Oslo").unwrap();
+        assert!(started.elapsed() < Duration::from_millis(150));
+        assert_eq!(cached.location, "Slow Oslo (yesterday)");
+        assert!(controller.showing_cached());
+        assert!(controller.is_loading());
+
+        let fresh = wait_for(&mut controller).unwrap();
+        assert_eq!(fresh.location, "Slow Oslo");
+        assert!(!controller.showing_cached());
+        // The fresh result is the next session's snapshot
+        assert_eq!(snapshots.load("slow oslo").unwrap(), Some(fresh));
+
+        // Other locations start cold
+        let mut controller = GuiController::new(DelayedRepository);
+        controller.set_snapshot_store(Some(snapshots));
+        assert_eq!(controller.warm_start("Paris"), None);
+        assert!(!controller.showing_cached());
+    }
+
+    #[test]
+    fn test_failed_refresh_keeps_cached_data() {
+        let snapshots = seeded_snapshots("failed", "Atlantis");
+        let mut controller = GuiController::new(StubRepository);
+        controller.set_snapshot_store(Some(snapshots.clone()));
+
+        assert!(controller.warm_start("Atlantis").is_some());
+        assert!(wait_for(&mut controller).is_err());
+        assert!(controller.showing_cached());
+        // A failure does not overwrite the snapshot
+        assert!(snapshots.load("Atlantis").unwrap().is_some());
+
+        // Without a snapshot store the start is cold
+        let mut controller = GuiController::new(StubRepository);
+        assert_eq!(controller.warm_start("Atlantis"), None);
+        assert!(wait_for(&mut controller).is_err());
+        assert!(!controller.showing_cached());
+    }
+
+    #[test]
+    fn test_offline_copy_counts_as_cached() {
+        let mut controller = GuiController::new(OfflineRepository);
+        controller.request_weather("Seattle");
+        assert!(wait_for(&mut controller).unwrap().offline);
+        assert!(controller.showing_cached());
+    }
+
+    #[test]
+    fn test_saved_forecast_is_delivered_and_cached() {
+        use crate::repositories::file_weather_repository::FileWeatherRepository;
+
+        let path = format!(
+            "{}/tests/fixtures/snowy/forecast.json",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let mut controller = GuiController::new(FileWeatherRepository::new(path));
+        assert!(controller.request_weather("Innsbruck").is_none());
+        let info = wait_for(&mut controller).unwrap();
+        assert_eq!(info.location, "Innsbruck");
+        assert_eq!(info.timezone.as_deref(), Some("Europe/Vienna"));
+        assert_eq!(controller.request_weather("Innsbruck"), Some(info));
+    }
+
+    #[test]
+    fn test_errors_are_delivered() {
+        let mut controller = GuiController::new(StubRepository);
+        controller.request_weather("Atlantis");
+        assert!(matches!(
+            wait_for(&mut controller),
+            Err(WeatherError::LocationNotFound { .. })
+        ));
+    }
+}
@@ -1,2 +1,3 @@
 // Controller layer - coordinates between models and views
 pub mod cl_controller;
+pub mod gui_controller;
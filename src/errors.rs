@@ -1,18 +1,41 @@
 //! Custom error types for the weather application
 
 use std::fmt;
+use std::time::Duration;
 
 /// Application-specific errors with descriptive context
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum WeatherError {
     /// Network connectivity or request failures
     NetworkError(String),
     /// JSON parsing or data format issues
     ParseError(String),
-    /// Location not found in geocoding service
-    LocationNotFound(String),
+    /// Location not found in geocoding service; `searched` names the
+    /// geocoders asked when there was more than one
+    LocationNotFound {
+        location: String,
+        searched: Vec<&'static str>,
+    },
     /// API returned error status or invalid response
     ApiError(String),
+    /// The server failed to answer (a 5xx status) rather than refusing the
+    /// request, so asking again later may succeed
+    ServerError(String),
+    /// Reading or writing local files (favorites, settings)
+    StorageError(String),
+    /// The server asked us to slow down; no request is sent until
+    /// `retry_after` has passed
+    RateLimited { retry_after: Duration },
+    /// Not sent, since it would come too soon after the previous request to
+    /// a server with a usage policy; its turn is `retry_after` away
+    Throttled { retry_after: Duration },
+    /// No connection or no complete answer within the repository's timeouts
+    Timeout(String),
+    /// A request option outside the range the API accepts
+    InvalidOption(String),
+    /// A provider that needs an API key got none, or rejected the one given
+    ApiKey(String),
 }
 
 impl fmt::Display for WeatherError {
@@ -20,10 +43,68 @@ impl fmt::Display for WeatherError {
         match self {
             WeatherError::NetworkError(msg) => write!(f, "Network error: {msg}"),
             WeatherError::ParseError(msg) => write!(f, "Parse error: {msg}"),
-            WeatherError::LocationNotFound(loc) => write!(f, "Location '{loc}' not found"),
+            WeatherError::LocationNotFound { location, searched } if searched.is_empty() => {
+                write!(f, "Location '{location}' not found")
+            }
+            WeatherError::LocationNotFound { location, searched } => write!(
+                f,
+                "Location '{location}' not found by {}",
+                searched.join(" or ")
+            ),
             WeatherError::ApiError(msg) => write!(f, "API error: {msg}"),
+            WeatherError::ServerError(msg) => write!(f, "Server error: {msg}"),
+            WeatherError::StorageError(msg) => write!(f, "Storage error: {msg}"),
+            WeatherError::RateLimited { retry_after } => write!(
+                f,
+                "Rate limited by the server; retry in {} s",
+                retry_after.as_secs().max(1)
+            ),
+            WeatherError::Throttled { retry_after } => write!(
+                f,
+                "Request not sent, to keep to the server's usage policy; next turn in {:.1} s",
+                retry_after.as_secs_f64()
+            ),
+            WeatherError::Timeout(msg) => write!(f, "Timed out: {msg}"),
+            WeatherError::InvalidOption(msg) => write!(f, "Invalid option: {msg}"),
+            WeatherError::ApiKey(msg) => write!(f, "API key error: {msg}"),
         }
     }
 }
 
 impl std::error::Error for WeatherError {}
+
+impl WeatherError {
+    /// `LocationNotFound` from a single source
+    pub fn location_not_found(location: impl Into<String>) -> Self {
+        WeatherError::LocationNotFound {
+            location: location.into(),
+            searched: Vec::new(),
+        }
+    }
+
+    /// Failed request. The URL is left out: Nominatim queries carry the contact address.
+    pub(crate) fn network(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            return WeatherError::Timeout(e.without_url().to_string());
+        }
+        WeatherError::NetworkError(e.without_url().to_string())
+    }
+
+    /// Error status: `ServerError` when the server failed, `ApiError` when
+    /// it refused the request
+    pub(crate) fn status(status: reqwest::StatusCode, msg: String) -> Self {
+        if status.is_server_error() {
+            WeatherError::ServerError(msg)
+        } else {
+            WeatherError::ApiError(msg)
+        }
+    }
+
+    /// Unreadable response body, again without the URL
+    pub(crate) fn body(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            return WeatherError::Timeout(e.without_url().to_string());
+        }
+        WeatherError::ParseError(e.without_url().to_string())
+    }
+}
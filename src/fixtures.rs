@@ -0,0 +1,124 @@
+//! Realistic sample data for tests (enable the `test-utils` feature to use downstream)
+
+use crate::models::weather_info::{CurrentWeather, DailyForecast, HourlyForecast, WeatherInfo};
+use crate::utils::conversions::Temperature;
+
+/// Seattle on an early-summer afternoon: 48 hourly entries from midnight and 7 days
+pub fn sample_weather_info() -> WeatherInfo {
+    let mut info = WeatherInfo::builder("Seattle", 47.6062, -122.3321)
+        .elevation(56.0)
+        .current(sample_current())
+        .hourly(sample_hourly())
+        .daily(sample_daily())
+        .build();
+    info.weather_data.fill_precipitation_chances();
+    info
+}
+
+fn sample_current() -> CurrentWeather {
+    CurrentWeather::builder()
+        .time("2024-06-01T14:15")
+        .temperature(19.4)
+        .apparent_temperature(18.7)
+        .humidity(58.0)
+        .dew_point(10.9)
+        .precipitation(0.0)
+        .snowfall(0.0)
+        .weather_code(2)
+        .wind_speed(11.2)
+        .wind_direction(225.0)
+        .wind_gusts(24.1)
+        .cloud_cover(40.0)
+        .pressure(1016.3)
+        .pressure_msl(1023.1)
+        .visibility(24_000.0)
+        .snow_depth(0.0)
+        .is_day(true)
+        .build()
+}
+
+fn sample_hourly() -> Vec<HourlyForecast> {
+    (0..48)
+        .map(|i| {
+            let day = 1 + i / 24;
+            let hour = i % 24;
+            // Coolest around 05:00, warmest around 16:00
+            let diurnal = (((hour as f64) - 10.5) / 24.0 * std::f64::consts::TAU).sin();
+            let temperature = 14.5 + 5.5 * diurnal;
+            // Showers move in on the second afternoon, after early-morning fog
+            let showers = day == 2 && (13..=18).contains(&hour);
+            let fog = day == 2 && (5..=8).contains(&hour);
+            let humidity = if showers { 85.0 } else { 60.0 };
+
+            HourlyForecast::builder(format!("2024-06-{day:02}T{hour:02}:00"))
+                .temperature(temperature)
+                .apparent_temperature(temperature - 0.8)
+                .precipitation_probability(if showers { 70.0 } else { 10.0 })
+                .precipitation(if showers { 1.2 } else { 0.0 })
+                .weather_code(match (showers, fog) {
+                    (true, _) => 80,
+                    (_, true) => 45,
+                    _ => 2,
+                })
+                .wind_speed(if showers { 18.0 } else { 9.0 })
+                .wind_direction(if showers { 200.0 } else { 315.0 })
+                .wind_gusts(if showers { 42.0 } else { 16.0 })
+                .humidity(humidity)
+                .dew_point(Temperature::dew_point(temperature, humidity))
+                .freezing_level_height(3200.0)
+                .cape(if showers { 450.0 } else { 80.0 })
+                .snowfall(0.0)
+                .snow_depth(0.0)
+                .cloud_cover(match (showers, fog) {
+                    (true, _) => 90.0,
+                    (_, true) => 100.0,
+                    _ => 40.0,
+                })
+                .visibility(if fog { 400.0 } else { 20_000.0 })
+                // Peaks at 7 around 13:00, none at night
+                .uv_index((7.0 - 0.9 * (hour as f64 - 13.0).abs()).max(0.0))
+                .build()
+        })
+        .collect()
+}
+
+fn sample_daily() -> Vec<DailyForecast> {
+    let days = [
+        (20.0, 9.5, 2, 0.0, 10.0, 14.0, 4.1, 58.0, 7.0),
+        (17.5, 10.0, 80, 6.8, 70.0, 22.0, 2.9, 81.0, 3.5),
+        (18.0, 9.0, 3, 0.4, 25.0, 15.0, 3.3, 70.0, 5.0),
+        (21.5, 10.5, 1, 0.0, 5.0, 12.0, 4.6, 55.0, 7.5),
+        (23.0, 11.5, 0, 0.0, 0.0, 10.0, 5.2, 50.0, 8.0),
+        (22.0, 12.0, 2, 0.0, 10.0, 13.0, 4.8, 56.0, 7.0),
+        (19.0, 11.0, 61, 3.5, 60.0, 20.0, 3.0, 77.0, 4.0),
+    ];
+
+    days.iter()
+        .enumerate()
+        .map(
+            |(i, &(max, min, code, precipitation, probability, wind, et0, humidity, uv))| {
+                let date = format!("2024-06-{:02}", i + 1);
+                let daylight = ((946 + i.min(5) + i) * 60) as f64;
+                DailyForecast::builder(date.clone())
+                    .temperature_max(max)
+                    .temperature_min(min)
+                    .weather_code(code)
+                    .precipitation_sum(precipitation)
+                    .snowfall_sum(0.0)
+                    .precipitation_probability(probability)
+                    .wind_speed_max(wind)
+                    // Squally on the showery days
+                    .wind_gusts_max(wind * if precipitation > 0.0 { 2.0 } else { 1.4 })
+                    .sunrise(format!("{date}T05:1{}", 5 - i.min(5)))
+                    .sunset(format!("{date}T21:0{}", i + 1))
+                    .daylight_duration(daylight)
+                    // Sunny for most of the daylight, less on wet days
+                    .sunshine_duration(daylight * (100.0 - probability) / 125.0)
+                    .et0_evapotranspiration(et0)
+                    .humidity_mean(humidity)
+                    .uv_index_max(uv)
+                    .build()
+            },
+        )
+        .collect()
+}
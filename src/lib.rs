@@ -1,11 +1,18 @@
 //! Weather application library
 //!
-//! Exposes modules for use in both CLI and GUI binaries
+//! Exposes modules for use in both CLI and GUI binaries.
+//! Downstream crates can `use weather_app::prelude::*;` for the common types.
 
 pub mod constants;
 pub mod controllers;
 pub mod errors;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod fixtures;
 pub mod models;
+pub mod prelude;
 pub mod repositories;
 pub mod utils;
 pub mod views;
+
+#[cfg(test)]
+pub(crate) mod test_support;
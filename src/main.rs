@@ -1,5 +1,3 @@
-use std::io::{self, Write};
-use std::process;
 /// Weather application entry point (CLI version)
 ///
 /// Uses MVC architecture with a repository pattern:
@@ -7,8 +5,371 @@ use std::process;
 /// - Views: Console display logic
 /// - Controllers: Coordinate between repository and view
 /// - Repositories: Handle API data fetching
+use clap::{Parser, Subcommand};
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::process;
+use std::time::Duration;
+use weather_app::constants::{
+    CONTACT_RECOMMENDED_LOCATIONS, DAILY_FORECAST_DAYS, METNO_API_URL, NOMINATIM_API_URL,
+    NWS_API_URL, OPENWEATHERMAP_API_URL, OPEN_METEO_AIR_QUALITY_API_URL, OPEN_METEO_API_URL,
+    OPEN_METEO_ENSEMBLE_API_URL, OPEN_METEO_GEOCODING_API_URL, OPEN_METEO_MARINE_API_URL,
+};
 use weather_app::controllers::cl_controller::ClController;
-use weather_app::repositories::weather_repository::ApiWeatherRepository;
+use weather_app::models::forecast_model::ForecastModel;
+use weather_app::models::settings::Settings;
+use weather_app::models::usage::UsageSummary;
+use weather_app::models::weather_log::WeatherLogSummary;
+use weather_app::repositories::app_state::{AppDirs, StateItem};
+use weather_app::repositories::data_selection::DataSelection;
+use weather_app::repositories::fallback_repository::FallbackRepository;
+use weather_app::repositories::favorites_store::FavoritesStore;
+use weather_app::repositories::file_weather_repository::FileWeatherRepository;
+use weather_app::repositories::geocode_cache::GeocodeCache;
+use weather_app::repositories::geocoding::GeocodingProvider;
+use weather_app::repositories::health;
+use weather_app::repositories::metno_repository::MetNoRepository;
+use weather_app::repositories::nws_weather_repository::NwsWeatherRepository;
+use weather_app::repositories::openweathermap_repository::OpenWeatherMapRepository;
+use weather_app::repositories::persistent_weather_repository::{
+    OfflineStore, PersistentWeatherRepository,
+};
+use weather_app::repositories::provider::WeatherProvider;
+use weather_app::repositories::request_spacing::SpacingMode;
+use weather_app::repositories::settings_store::SettingsStore;
+use weather_app::repositories::usage_log::UsageLog;
+use weather_app::repositories::user_agent::UserAgent;
+use weather_app::repositories::weather_log::{self, WeatherLog};
+use weather_app::repositories::weather_repository::{ApiWeatherRepository, WeatherRepository};
+use weather_app::utils::advice::FeelsLikeFormula;
+use weather_app::utils::advisories::AdvisoryStandard;
+use weather_app::utils::conversions::{PressureReference, WindUnit};
+use weather_app::utils::format::{NumberLocale, Precision};
+use weather_app::utils::shutdown::{self, Shutdown};
+use weather_app::utils::terminal;
+use weather_app::utils::uv::SkinType;
+use weather_app::views::cl_view::ClView;
+use weather_app::views::export::{self, CsvDialect, OutputFormat, UnitSystem};
+use weather_app::views::DisplayOptions;
+
+/// Command-line arguments
+#[derive(Parser, Debug)]
+#[command(
+    version,
+    about = "Weather forecasts powered by Open-Meteo",
+    args_conflicts_with_subcommands = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Location to look up (prompts interactively when omitted)
+    location: Vec<String>,
+
+    /// Nominatim search endpoint (for self-hosted instances)
+    #[arg(long, env = "WEATHER_NOMINATIM_URL", default_value = NOMINATIM_API_URL, global = true)]
+    nominatim_url: String,
+
+    /// What a Nominatim request too soon after the last one does: wait for
+    /// its turn, or fail without being sent
+    #[arg(long, env = "WEATHER_NOMINATIM_SPACING", default_value_t = SpacingMode::Wait, global = true)]
+    nominatim_spacing: SpacingMode,
+
+    /// Least milliseconds between Nominatim requests [default: 1000 for the
+    /// public server, none for a self-hosted one]
+    #[arg(
+        long,
+        env = "WEATHER_NOMINATIM_INTERVAL_MS",
+        value_name = "MS",
+        global = true
+    )]
+    nominatim_interval_ms: Option<u64>,
+
+    /// Geocoding service: nominatim or open-meteo (which also supplies the time zone)
+    #[arg(long, env = "WEATHER_GEOCODER", default_value_t = GeocodingProvider::Nominatim, global = true)]
+    geocoder: GeocodingProvider,
+
+    /// Open-Meteo geocoding endpoint used with --geocoder open-meteo
+    #[arg(long, env = "WEATHER_OPEN_METEO_GEOCODING_URL", default_value = OPEN_METEO_GEOCODING_API_URL, global = true)]
+    open_meteo_geocoding_url: String,
+
+    /// Report a failed geocoding search rather than retrying it with the other geocoder
+    #[arg(long, global = true)]
+    no_geocoder_fallback: bool,
+
+    /// Open-Meteo forecast endpoint (for self-hosted instances)
+    #[arg(long, env = "WEATHER_OPEN_METEO_URL", default_value = OPEN_METEO_API_URL, global = true)]
+    open_meteo_url: String,
+
+    /// Open-Meteo marine endpoint used with --marine
+    #[arg(long, env = "WEATHER_OPEN_METEO_MARINE_URL", default_value = OPEN_METEO_MARINE_API_URL, global = true)]
+    open_meteo_marine_url: String,
+
+    /// Open-Meteo ensemble endpoint used with --confidence
+    #[arg(long, env = "WEATHER_OPEN_METEO_ENSEMBLE_URL", default_value = OPEN_METEO_ENSEMBLE_API_URL, global = true)]
+    open_meteo_ensemble_url: String,
+
+    /// Open-Meteo air quality endpoint used with --air-quality
+    #[arg(long, env = "WEATHER_OPEN_METEO_AIR_QUALITY_URL", default_value = OPEN_METEO_AIR_QUALITY_API_URL, global = true)]
+    open_meteo_air_quality_url: String,
+
+    /// Forecast source for a single location: open-meteo (worldwide), nws
+    /// (US National Weather Service, United States only), openweathermap
+    /// (needs --openweathermap-api-key) or met-no (Norwegian Meteorological
+    /// Institute, worldwide). A list, e.g. open-meteo,met-no, asks each in
+    /// turn until one answers.
+    #[arg(
+        long,
+        env = "WEATHER_PROVIDER",
+        value_delimiter = ',',
+        default_value = "open-meteo"
+    )]
+    provider: Vec<WeatherProvider>,
+
+    /// National Weather Service API root used with --provider nws
+    #[arg(long, env = "WEATHER_NWS_URL", default_value = NWS_API_URL, global = true)]
+    nws_url: String,
+
+    /// OpenWeatherMap API key used with --provider openweathermap
+    #[arg(long, env = "OPENWEATHERMAP_API_KEY", hide_env_values = true)]
+    openweathermap_api_key: Option<String>,
+
+    /// OpenWeatherMap One Call endpoint used with --provider openweathermap
+    #[arg(long, env = "WEATHER_OPENWEATHERMAP_URL", default_value = OPENWEATHERMAP_API_URL, global = true)]
+    openweathermap_url: String,
+
+    /// Met.no Locationforecast endpoint used with --provider met-no
+    #[arg(long, env = "WEATHER_METNO_URL", default_value = METNO_API_URL, global = true)]
+    metno_url: String,
+
+    /// Always show mountain data (freezing level and snow line)
+    #[arg(long)]
+    mountain: bool,
+
+    /// Show the peak storm-energy (CAPE) window for the next 48 hours
+    #[arg(long)]
+    storm: bool,
+
+    /// Show the daily irrigation need (evapotranspiration minus rain)
+    #[arg(long)]
+    garden: bool,
+
+    /// Print a single summary line instead of the full report
+    #[arg(long)]
+    oneline: bool,
+
+    /// Output format: text report, json, csv (hourly forecast) or daily-csv
+    #[arg(long, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Units for json/csv output: metric (°C, km/h, hPa, mm) or si (K, m/s, Pa, kg/m²).
+    /// The text report always shows metric and imperial; see --feels-like-formula for
+    /// heat index vs humidex
+    #[arg(long, default_value_t = UnitSystem::Metric, global = true)]
+    units: UnitSystem,
+
+    /// Also include imperial and mmHg conversions in json output and the schema
+    /// (`pressure_msl_inhg`, `pressure_msl_mmhg`)
+    #[arg(long, global = true)]
+    all_units: bool,
+
+    /// Days of daily forecast, 1 to 16. Beyond 7 the hourly data (next 48 hours)
+    /// and the daily series are requested separately to keep the download small
+    #[arg(long, default_value_t = DAILY_FORECAST_DAYS as u64, value_parser = clap::value_parser!(u64).range(1..=16))]
+    days: u64,
+
+    /// Output width in columns (defaults to the terminal width, or 100 when piped)
+    #[arg(long)]
+    width: Option<usize>,
+
+    /// Show wind speed and direction at 10 m, 80 m and 120 m for the next 12 hours
+    #[arg(long)]
+    winds_aloft: bool,
+
+    /// Show the next two hours in 15-minute steps: temperature, precipitation
+    /// and conditions
+    #[arg(long)]
+    minutely: bool,
+
+    /// Show wave height, period and direction for the next 24 hours (coastal locations)
+    #[arg(long)]
+    marine: bool,
+
+    /// Mark days whose forecast is uncertain, from the spread of an ensemble
+    /// (one more request per location; skipped if it fails)
+    #[arg(long)]
+    confidence: bool,
+
+    /// Show PM2.5, PM10, ozone, NO2 and the US and European AQI with the
+    /// current conditions (one more request per location; skipped if it fails)
+    #[arg(long)]
+    air_quality: bool,
+
+    /// Append the current conditions to a monthly JSONL file per location in this directory
+    #[arg(long, value_name = "DIR")]
+    log_to: Option<PathBuf>,
+
+    /// Show a wind rose: how often and how strongly the wind blows from each direction
+    #[arg(long)]
+    wind_rose: bool,
+
+    /// Wind speed unit for --winds-aloft: kmh, mph, knots or ms
+    #[arg(long, env = "WEATHER_WIND_UNIT", default_value_t = WindUnit::Kmh)]
+    wind_unit: WindUnit,
+
+    /// Pressure shown with the current conditions: msl (reduced to sea level)
+    /// or surface; defaults to the one chosen in the GUI settings
+    #[arg(long, env = "WEATHER_PRESSURE")]
+    pressure: Option<PressureReference>,
+
+    /// Weather model for the forecast: best-match (Open-Meteo's choice), ecmwf,
+    /// gfs, icon, gem, meteofrance or ukmo; regional models only cover their region
+    #[arg(long, env = "WEATHER_MODEL", default_value_t = ForecastModel::BestMatch)]
+    model: ForecastModel,
+
+    /// Temperature precision: whole degrees or tenths (default tenths)
+    #[arg(long)]
+    precision: Option<Precision>,
+
+    /// Decimal separator and date order of the text report: en (14.2°C, 2024-06-12)
+    /// or de (14,2 °C, 12.06.); defaults to "number_locale" in settings.json
+    #[arg(long, env = "WEATHER_NUMBER_LOCALE")]
+    number_locale: Option<NumberLocale>,
+
+    /// CSV flavor: standard (comma-separated, decimal point) or excel-de
+    /// (semicolon-separated, decimal comma)
+    #[arg(long, default_value_t = CsvDialect::Standard)]
+    csv_dialect: CsvDialect,
+
+    /// Fitzpatrick skin type (I to VI) for the time to sunburn next to UV values;
+    /// defaults to the one chosen in the GUI settings
+    #[arg(long, env = "WEATHER_SKIN_TYPE")]
+    skin_type: Option<SkinType>,
+
+    /// Heat-stress index for feels-like values and heat advisories: heat-index (US) or humidex (Canada)
+    #[arg(long, env = "WEATHER_FEELS_LIKE_FORMULA", default_value_t = FeelsLikeFormula::HeatIndex)]
+    feels_like_formula: FeelsLikeFormula,
+
+    /// Weather service whose cold and heat advisory tiers to use: us, canada or eu;
+    /// defaults to the one chosen in the GUI settings
+    #[arg(long, env = "WEATHER_ADVISORY_STANDARD")]
+    advisory_standard: Option<AdvisoryStandard>,
+
+    /// Read locations from stdin, one per line, and print a summary line for each
+    #[arg(long, conflicts_with = "location")]
+    stdin: bool,
+
+    /// Read the forecast from a saved file instead of the network, for demos
+    /// and offline work: an Open-Meteo forecast response or a forecast saved
+    /// with FileWeatherRepository::record. The location only names it.
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["stdin", "favorites", "digest"])]
+    from_file: Option<PathBuf>,
+
+    /// Print the current temperature for every saved favorite
+    #[arg(long, conflicts_with_all = ["location", "stdin"])]
+    favorites: bool,
+
+    /// With --favorites: geocode every favorite again and show it by the
+    /// name found; one that moved over 10 km is held back for --accept-moves
+    #[arg(long, requires = "favorites")]
+    refresh_names: bool,
+
+    /// With --favorites: accept the new positions a name refresh held back
+    #[arg(long, requires = "favorites")]
+    accept_moves: bool,
+
+    /// Morning digest: every favorite, severe weather first, then precipitation today
+    #[arg(long, conflicts_with_all = ["location", "stdin", "favorites"])]
+    digest: bool,
+
+    /// Save a location to favorites (updates the label/note if already saved)
+    #[arg(long, value_name = "LOCATION", conflicts_with_all = ["location", "stdin", "favorites", "digest"])]
+    save: Option<String>,
+
+    /// Label for --save, e.g. "Home"
+    #[arg(long, requires = "save")]
+    label: Option<String>,
+
+    /// Free-text note for --save
+    #[arg(long, requires = "save")]
+    note: Option<String>,
+
+    /// Favorites file (defaults to favorites.json in the platform config directory)
+    #[arg(long, env = "WEATHER_FAVORITES_FILE", global = true)]
+    favorites_file: Option<PathBuf>,
+
+    /// Maximum number of locations fetched at once with --stdin
+    #[cfg(feature = "async")]
+    #[arg(long, default_value_t = 4)]
+    concurrency: usize,
+
+    /// Always ask the servers, never reusing a response from the last minute
+    #[arg(long, global = true)]
+    no_cache: bool,
+
+    /// Report skipped sections and other diagnostics on stderr
+    #[arg(short, long)]
+    verbose: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Check connectivity to the configured endpoints and print the effective configuration
+    Doctor,
+    /// Print the field names and units of the json/csv output for --units
+    Schema,
+    /// Summarize the local usage statistics (recorded only with "usage_stats": true in settings.json)
+    Stats,
+    /// Work with the weather logs written by --log-to
+    #[command(subcommand)]
+    Log(LogCommand),
+    /// Remove data that can be fetched again
+    #[command(subcommand)]
+    Cache(CacheCommand),
+    /// Remove the lookup history behind `stats`
+    #[command(subcommand)]
+    History(HistoryCommand),
+    /// Show what the app stores on disk
+    #[command(subcommand)]
+    State(StateCommand),
+}
+
+#[derive(Subcommand, Debug)]
+enum CacheCommand {
+    /// Delete cached data; without a flag, every cache
+    Clear {
+        /// Coordinates of places looked up before
+        #[arg(long)]
+        geocode: bool,
+        /// Saved weather: the snapshot the GUI starts with and the offline copies
+        #[arg(long)]
+        weather: bool,
+        /// Every cache
+        #[arg(long, conflicts_with_all = ["geocode", "weather"])]
+        all: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum HistoryCommand {
+    /// Delete usage.jsonl
+    Clear,
+}
+
+#[derive(Subcommand, Debug)]
+enum StateCommand {
+    /// List every file the app may have created, with its size
+    Paths,
+}
+
+#[derive(Subcommand, Debug)]
+enum LogCommand {
+    /// Temperature range and mean and total precipitation of one log file
+    Summarize {
+        /// Log file, e.g. ~/weather-log/seattle-2024-06.jsonl
+        file: PathBuf,
+    },
+}
 
 fn main() {
     if let Err(e) = run() {
@@ -19,16 +380,384 @@ fn main() {
 
 /// Main application logic with proper error handling
 fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+    let settings = saved_settings();
+    let repository = api_repository(&cli, &settings);
+
+    let options = display_options(&cli, &settings);
+
+    if let Some(Command::Schema) = cli.command {
+        println!("{:#}", export::schema(&options));
+        return Ok(());
+    }
+
+    if let Some(Command::Stats) = cli.command {
+        let log = UsageLog::default_location().ok_or("No config directory found")?;
+        let summary = UsageSummary::from_records(&log.load()?);
+        ClView::display_usage_stats(&summary, usage_log(&settings).is_some(), log.path());
+        return Ok(());
+    }
+
+    if let Some(Command::Log(LogCommand::Summarize { file })) = &cli.command {
+        let (records, skipped) = weather_log::read(file)?;
+        ClView::display_log_summary(
+            &WeatherLogSummary::from_records(&records, skipped),
+            file,
+            &options,
+        );
+        return Ok(());
+    }
+
+    if let Some(Command::Cache(_) | Command::History(_) | Command::State(_)) = &cli.command {
+        let dirs = AppDirs::default_location()
+            .ok_or("No config or cache directory found")?
+            .with_favorites_file(cli.favorites_file.clone());
+        let items: Vec<StateItem> = match &cli.command {
+            Some(Command::Cache(CacheCommand::Clear {
+                geocode,
+                weather,
+                all,
+            })) => {
+                // Neither flag, like both, means every cache
+                let every = *all || geocode == weather;
+                StateItem::CACHES
+                    .into_iter()
+                    .filter(|item| every || (*item == StateItem::GeocodeCache) == *geocode)
+                    .collect()
+            }
+            Some(Command::History(HistoryCommand::Clear)) => vec![StateItem::UsageHistory],
+            _ => {
+                ClView::display_state_files(&dirs.files());
+                return Ok(());
+            }
+        };
+        ClView::display_removed_state(&dirs.clear(&items)?);
+        return Ok(());
+    }
+
+    if let Some(Command::Doctor) = cli.command {
+        let files = AppDirs::default_location()
+            .map(|dirs| dirs.with_favorites_file(cli.favorites_file.clone()).files())
+            .unwrap_or_default();
+        let checks = health::run_checks(&repository);
+        ClView::display_doctor_report(&repository, &options, &files, &checks);
+        if !checks.iter().all(health::HealthCheck::is_ok) {
+            process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if let Some(location) = &cli.save {
+        let store = favorites_store(&cli)?;
+        let mut favorites = store.load()?;
+        favorites.save(location, cli.label.clone(), cli.note.clone());
+        store.save(&favorites)?;
+        println!("Saved {location} to {}", store.path().display());
+        return Ok(());
+    }
+
+    if cli.favorites || cli.digest {
+        let store = favorites_store(&cli)?;
+        let mut favorites = store.load()?;
+        if favorites.is_empty() {
+            println!("No favorites yet; add one with --save <LOCATION> [--label NAME]");
+            return Ok(());
+        }
+        if cli.accept_moves {
+            let accepted = (0..favorites.entries().len())
+                .filter(|&index| favorites.accept_move(index))
+                .count();
+            store.save(&favorites)?;
+            println!("Accepted {accepted} moved favorite(s)");
+        }
+        warn_without_contact(&repository, favorites.entries().len());
+        let shutdown = Shutdown::install()?;
+        let all_ok = if cli.digest {
+            ClController::new(repository.with_data_selection(DataSelection::digest()))
+                .with_options(options)
+                .with_usage_log(usage_log(&settings))
+                .with_shutdown(shutdown.clone())
+                .show_digest(&favorites)
+        } else {
+            let controller =
+                ClController::new(repository.with_data_selection(DataSelection::current_only()))
+                    .with_usage_log(usage_log(&settings))
+                    .with_shutdown(shutdown.clone());
+            let mut all_ok = true;
+            if cli.refresh_names {
+                all_ok =
+                    controller.refresh_names(&mut favorites, chrono::Local::now().date_naive());
+                store.save(&favorites)?;
+            }
+            controller.show_favorites(&favorites) && all_ok
+        };
+        if shutdown.is_requested() {
+            shutdown::cleanup(&mut io::stdout(), &mut io::stderr())?;
+            process::exit(shutdown::INTERRUPTED_EXIT_CODE);
+        }
+        if !all_ok {
+            process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if cli.stdin {
+        let locations = read_stdin_locations()?;
+        warn_without_contact(&repository, locations.len());
+        let shutdown = Shutdown::install()?;
+        let repository = repository.with_data_selection(DataSelection::compact());
+        let all_ok = run_batch(repository, &locations, &cli, &shutdown)?;
+        if shutdown.is_requested() {
+            shutdown::cleanup(&mut io::stdout(), &mut io::stderr())?;
+            process::exit(shutdown::INTERRUPTED_EXIT_CODE);
+        }
+        if !all_ok {
+            process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let location = if cli.location.is_empty() {
+        prompt_location()?
+    } else {
+        cli.location.join(" ")
+    };
+
+    let weather_log = cli.log_to.clone().map(WeatherLog::new);
+    if let Some(path) = &cli.from_file {
+        // A saved forecast is no fetch, so it stays out of the offline store
+        let repository = PersistentWeatherRepository::new(FileWeatherRepository::new(path), None);
+        return show_report(repository, location.trim(), options, &settings, weather_log);
+    }
+
+    // Each provider has its own client, caches and rate limit backoff
+    let mut repository = Some(repository);
+    let repositories: Vec<Box<dyn WeatherRepository>> = cli
+        .provider
+        .iter()
+        .map(|&provider| {
+            let api = repository
+                .take()
+                .unwrap_or_else(|| api_repository(&cli, &settings))
+                .with_data_selection(options.data_selection());
+            provider_repository(provider, api, &cli)
+        })
+        .collect();
+    let repository: Box<dyn WeatherRepository> = match <[_; 1]>::try_from(repositories) {
+        Ok([repository]) => repository,
+        Err(repositories) => Box::new(FallbackRepository::new(repositories)),
+    };
+    show_report(
+        PersistentWeatherRepository::new(repository, OfflineStore::default_location()),
+        location.trim(),
+        options,
+        &settings,
+        weather_log,
+    )
+}
+
+/// Repository for Open-Meteo with the endpoints, caches and user agent of the
+/// command line and settings
+fn api_repository(cli: &Cli, settings: &Settings) -> ApiWeatherRepository {
+    let (geocoding_url, fallback_geocoder, fallback_url) = match cli.geocoder {
+        GeocodingProvider::Nominatim => (
+            &cli.nominatim_url,
+            GeocodingProvider::OpenMeteo,
+            &cli.open_meteo_geocoding_url,
+        ),
+        GeocodingProvider::OpenMeteo => (
+            &cli.open_meteo_geocoding_url,
+            GeocodingProvider::Nominatim,
+            &cli.nominatim_url,
+        ),
+    };
+    let repository = ApiWeatherRepository::new()
+        .with_geocoder(cli.geocoder)
+        .with_endpoints(geocoding_url, &cli.open_meteo_url)
+        .with_fallback_geocoder((!cli.no_geocoder_fallback).then_some(fallback_geocoder))
+        .with_fallback_geocoding_url(fallback_url)
+        .with_nominatim_spacing(cli.nominatim_spacing)
+        .with_marine_url(&cli.open_meteo_marine_url)
+        .with_ensemble_url(&cli.open_meteo_ensemble_url)
+        .with_air_quality_url(&cli.open_meteo_air_quality_url)
+        .with_forecast_days(cli.days as usize)
+        .with_model(cli.model)
+        .with_user_agent(UserAgent::from_settings(settings))
+        .with_response_cache(!cli.no_cache)
+        .with_geocode_cache(
+            (!cli.no_cache)
+                .then(GeocodeCache::default_location)
+                .flatten(),
+        )
+        .with_verbose(cli.verbose);
+    match cli.nominatim_interval_ms {
+        Some(ms) => repository.with_nominatim_interval(Duration::from_millis(ms)),
+        None => repository,
+    }
+}
+
+/// Repository for one forecast provider, sending its requests through `api`
+fn provider_repository(
+    provider: WeatherProvider,
+    api: ApiWeatherRepository,
+    cli: &Cli,
+) -> Box<dyn WeatherRepository> {
+    match provider {
+        WeatherProvider::OpenMeteo => Box::new(api),
+        WeatherProvider::Nws => Box::new(NwsWeatherRepository::new(api).with_nws_url(&cli.nws_url)),
+        WeatherProvider::OpenWeatherMap => Box::new(
+            OpenWeatherMapRepository::new(
+                api,
+                cli.openweathermap_api_key.clone().unwrap_or_default(),
+            )
+            .with_onecall_url(&cli.openweathermap_url),
+        ),
+        WeatherProvider::MetNo => {
+            Box::new(MetNoRepository::new(api).with_metno_url(&cli.metno_url))
+        }
+    }
+}
+
+/// Prints the report for one location from `repository`, which falls back
+/// to the offline copy when the fetch fails if it has an offline store
+fn show_report<R: WeatherRepository>(
+    repository: PersistentWeatherRepository<R>,
+    location: &str,
+    options: DisplayOptions,
+    settings: &Settings,
+    weather_log: Option<WeatherLog>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let controller = ClController::new(repository)
+        .with_options(options)
+        .with_usage_log(usage_log(settings))
+        .with_weather_log(weather_log);
+
+    controller.show_weather(location)?;
+    Ok(())
+}
+
+/// Presentation options from the command line, with the saved number locale
+/// as the fallback
+fn display_options(cli: &Cli, settings: &Settings) -> DisplayOptions {
+    DisplayOptions::builder()
+        .mountain(cli.mountain)
+        .storm(cli.storm)
+        .garden(cli.garden)
+        .verbose(cli.verbose)
+        .oneline(cli.oneline)
+        .feels_like_formula(cli.feels_like_formula)
+        .advisory_standard(
+            cli.advisory_standard
+                .unwrap_or(settings.display.advisory_standard),
+        )
+        .format(cli.format)
+        .units(cli.units)
+        .all_units(cli.all_units)
+        .width(terminal::output_width(cli.width))
+        .winds_aloft(cli.winds_aloft)
+        .minutely(cli.minutely)
+        .marine(cli.marine)
+        .confidence(cli.confidence)
+        .air_quality(cli.air_quality)
+        .wind_rose(cli.wind_rose)
+        .wind_unit(cli.wind_unit)
+        .pressure(cli.pressure.unwrap_or(settings.display.pressure))
+        .precision(cli.precision)
+        .number_locale(cli.number_locale.unwrap_or(settings.number_locale))
+        .csv_dialect(cli.csv_dialect)
+        .skin_type(cli.skin_type.or(settings.display.skin_type))
+        .build()
+}
+
+/// Preferences saved by the GUI, or the defaults if there are none
+fn saved_settings() -> Settings {
+    SettingsStore::default_location()
+        .and_then(|store| store.load().ok())
+        .unwrap_or_default()
+}
+
+/// Usage log, if the user opted in through `usage_stats` in settings.json
+fn usage_log(settings: &Settings) -> Option<UsageLog> {
+    UsageLog::default_location()?.enabled_by(settings)
+}
+
+/// Favorites file from --favorites-file, or the platform default
+fn favorites_store(cli: &Cli) -> Result<FavoritesStore, Box<dyn std::error::Error>> {
+    match &cli.favorites_file {
+        Some(path) => Ok(FavoritesStore::new(path)),
+        None => FavoritesStore::default_location()
+            .ok_or_else(|| "No config directory found; pass --favorites-file".into()),
+    }
+}
+
+/// Non-empty lines from stdin
+fn read_stdin_locations() -> io::Result<Vec<String>> {
+    let mut locations = Vec::new();
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            locations.push(line);
+        }
+    }
+    Ok(locations)
+}
+
+/// Points out Nominatim's contact policy before geocoding many locations anonymously
+fn warn_without_contact(repository: &ApiWeatherRepository, locations: usize) {
+    if locations >= CONTACT_RECOMMENDED_LOCATIONS
+        && repository.geocoder() == GeocodingProvider::Nominatim
+        && !repository.user_agent().has_contact()
+    {
+        eprintln!(
+            "Note: looking up {locations} locations with Nominatim; please set \"contact_email\" \
+             in settings.json so its operators can reach you (see its usage policy)"
+        );
+    }
+}
+
+/// Fetches and prints every location, returning whether all succeeded
+#[cfg(not(feature = "async"))]
+fn run_batch(
+    repository: ApiWeatherRepository,
+    locations: &[String],
+    _cli: &Cli,
+    shutdown: &Shutdown,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    Ok(ClController::new(repository)
+        .with_shutdown(shutdown.clone())
+        .show_batch(locations))
+}
+
+/// Fetches and prints every location concurrently, returning whether all succeeded
+#[cfg(feature = "async")]
+fn run_batch(
+    repository: ApiWeatherRepository,
+    locations: &[String],
+    cli: &Cli,
+    shutdown: &Shutdown,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    use weather_app::repositories::async_weather_repository::AsyncApiWeatherRepository;
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?;
+    let results = runtime.block_on(async {
+        AsyncApiWeatherRepository::from_config(&repository)
+            .fetch_batch(locations, cli.concurrency, shutdown)
+            .await
+    });
+
+    ClView::display_batch(locations, &results);
+    Ok(results.len() == locations.len() && results.iter().all(Result::is_ok))
+}
+
+/// Asks the user for a location on stdin
+fn prompt_location() -> io::Result<String> {
     println!("Welcome to the Rust Weather App!");
     print!("Where are you? ");
     io::stdout().flush()?;
 
     let mut location = String::new();
     io::stdin().read_line(&mut location)?;
-
-    let repository = ApiWeatherRepository::new();
-    let controller = ClController::new(repository);
-
-    controller.show_weather(location.trim())?;
-    Ok(())
+    Ok(location)
 }
@@ -0,0 +1,142 @@
+//! Pollutants and air quality indices (Open-Meteo air quality API)
+
+use serde::{Deserialize, Serialize};
+
+/// Air quality at one time, from the CAMS models behind the API
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct AirQualityReading {
+    /// Local time, e.g. "2024-06-01T14:00"
+    pub time: String,
+    /// Fine particulate matter (PM2.5) in µg/m³
+    pub pm2_5: Option<f64>,
+    /// Coarse particulate matter (PM10) in µg/m³
+    pub pm10: Option<f64>,
+    /// Ozone in µg/m³
+    pub ozone: Option<f64>,
+    /// Nitrogen dioxide in µg/m³
+    pub nitrogen_dioxide: Option<f64>,
+    /// European Air Quality Index, 0 (good) to 100 and above (extremely poor)
+    pub european_aqi: Option<f64>,
+    /// US Air Quality Index, 0 (good) to 500 (hazardous)
+    pub us_aqi: Option<f64>,
+}
+
+impl AirQualityReading {
+    pub fn new(time: impl Into<String>) -> Self {
+        AirQualityReading {
+            time: time.into(),
+            ..Self::default()
+        }
+    }
+
+    fn has_data(&self) -> bool {
+        [
+            self.pm2_5,
+            self.pm10,
+            self.ozone,
+            self.nitrogen_dioxide,
+            self.european_aqi,
+            self.us_aqi,
+        ]
+        .iter()
+        .any(Option::is_some)
+    }
+
+    /// EPA category of the US AQI, e.g. "Moderate"
+    pub fn us_aqi_category(&self) -> Option<&'static str> {
+        self.us_aqi.map(us_aqi_category)
+    }
+}
+
+/// EPA category for a US AQI value
+pub fn us_aqi_category(aqi: f64) -> &'static str {
+    match aqi {
+        aqi if aqi <= 50.0 => "Good",
+        aqi if aqi <= 100.0 => "Moderate",
+        aqi if aqi <= 150.0 => "Unhealthy for sensitive groups",
+        aqi if aqi <= 200.0 => "Unhealthy",
+        aqi if aqi <= 300.0 => "Very unhealthy",
+        _ => "Hazardous",
+    }
+}
+
+/// Current and hourly air quality; only present when the API had values
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AirQuality {
+    pub current: Option<AirQualityReading>,
+    pub hourly: Vec<AirQualityReading>,
+}
+
+impl AirQuality {
+    /// `None` when no reading has any value
+    pub fn from_readings(
+        current: Option<AirQualityReading>,
+        hourly: Vec<AirQualityReading>,
+    ) -> Option<Self> {
+        let current = current.filter(AirQualityReading::has_data);
+        (current.is_some() || hourly.iter().any(AirQualityReading::has_data))
+            .then_some(AirQuality { current, hourly })
+    }
+
+    /// The current reading, or else the hourly one for the hour containing
+    /// `time` ("YYYY-MM-DDTHH:MM", local)
+    pub fn at(&self, time: Option<&str>) -> Option<&AirQualityReading> {
+        let hour_prefix = time.and_then(|t| t.get(..13)).unwrap_or_default();
+        self.current.as_ref().or_else(|| {
+            self.hourly
+                .iter()
+                .find(|h| !hour_prefix.is_empty() && h.time.starts_with(hour_prefix))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(time: &str, us_aqi: Option<f64>) -> AirQualityReading {
+        AirQualityReading {
+            us_aqi,
+            ..AirQualityReading::new(time)
+        }
+    }
+
+    #[test]
+    fn test_empty_readings_are_no_air_quality() {
+        assert_eq!(AirQuality::from_readings(None, Vec::new()), None);
+        assert_eq!(
+            AirQuality::from_readings(
+                Some(reading("2024-06-01T14:00", None)),
+                vec![reading("2024-06-01T14:00", None)]
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_current_reading_falls_back_to_the_hour() {
+        let hourly = vec![
+            reading("2024-06-01T13:00", Some(40.0)),
+            reading("2024-06-01T14:00", Some(55.0)),
+        ];
+        let air = AirQuality::from_readings(None, hourly).unwrap();
+        assert_eq!(air.at(Some("2024-06-01T14:15")).unwrap().us_aqi, Some(55.0));
+        assert_eq!(air.at(None), None);
+
+        let current = reading("2024-06-01T14:15", Some(61.0));
+        let air = AirQuality::from_readings(Some(current), air.hourly).unwrap();
+        assert_eq!(air.at(Some("2024-06-01T14:15")).unwrap().us_aqi, Some(61.0));
+    }
+
+    #[test]
+    fn test_us_aqi_categories() {
+        assert_eq!(us_aqi_category(0.0), "Good");
+        assert_eq!(us_aqi_category(50.0), "Good");
+        assert_eq!(us_aqi_category(51.0), "Moderate");
+        assert_eq!(us_aqi_category(150.0), "Unhealthy for sensitive groups");
+        assert_eq!(us_aqi_category(180.0), "Unhealthy");
+        assert_eq!(us_aqi_category(300.0), "Very unhealthy");
+        assert_eq!(us_aqi_category(420.0), "Hazardous");
+    }
+}
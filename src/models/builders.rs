@@ -0,0 +1,233 @@
+//! Builders for constructing models by hand (tests, fixtures, custom repositories)
+//!
+//! The models are `#[non_exhaustive]`, so code outside this crate cannot use
+//! struct literals; these builders set one field at a time instead.
+
+use crate::models::weather_info::{
+    CurrentWeather, DailyForecast, HourlyForecast, WeatherData, WeatherInfo,
+};
+use crate::utils::confidence::Confidence;
+
+/// Generates consuming setters that wrap the value in `Some`
+macro_rules! optional_setters {
+    ($($field:ident: $ty:ty),* $(,)?) => {
+        $(
+            pub fn $field(mut self, value: impl Into<$ty>) -> Self {
+                self.0.$field = Some(value.into());
+                self
+            }
+        )*
+    };
+}
+
+/// Builder returned by `CurrentWeather::builder()`
+#[derive(Clone, Debug, Default)]
+pub struct CurrentWeatherBuilder(CurrentWeather);
+
+impl CurrentWeather {
+    pub fn builder() -> CurrentWeatherBuilder {
+        CurrentWeatherBuilder::default()
+    }
+}
+
+impl CurrentWeatherBuilder {
+    optional_setters! {
+        time: String,
+        temperature: f64,
+        apparent_temperature: f64,
+        humidity: f64,
+        dew_point: f64,
+        precipitation: f64,
+        snowfall: f64,
+        weather_code: i32,
+        wind_speed: f64,
+        wind_direction: f64,
+        wind_gusts: f64,
+        cloud_cover: f64,
+        pressure: f64,
+        pressure_msl: f64,
+        visibility: f64,
+        snow_depth: f64,
+        is_day: bool,
+    }
+
+    pub fn build(self) -> CurrentWeather {
+        self.0
+    }
+}
+
+/// Builder returned by `HourlyForecast::builder()`
+#[derive(Clone, Debug)]
+pub struct HourlyForecastBuilder(HourlyForecast);
+
+impl HourlyForecast {
+    /// Starts a forecast hour at `time` (ISO 8601, e.g. "2024-06-01T14:00")
+    pub fn builder(time: impl Into<String>) -> HourlyForecastBuilder {
+        HourlyForecastBuilder(HourlyForecast {
+            time: time.into(),
+            ..Default::default()
+        })
+    }
+}
+
+impl HourlyForecastBuilder {
+    optional_setters! {
+        temperature: f64,
+        apparent_temperature: f64,
+        precipitation_probability: f64,
+        precipitation: f64,
+        weather_code: i32,
+        wind_speed: f64,
+        wind_direction: f64,
+        wind_gusts: f64,
+        wind_speed_80m: f64,
+        wind_direction_80m: f64,
+        wind_speed_120m: f64,
+        wind_direction_120m: f64,
+        humidity: f64,
+        dew_point: f64,
+        freezing_level_height: f64,
+        cape: f64,
+        snowfall: f64,
+        snow_depth: f64,
+        cloud_cover: f64,
+        visibility: f64,
+        uv_index: f64,
+    }
+
+    pub fn build(self) -> HourlyForecast {
+        self.0
+    }
+}
+
+/// Builder returned by `DailyForecast::builder()`
+#[derive(Clone, Debug)]
+pub struct DailyForecastBuilder(DailyForecast);
+
+impl DailyForecast {
+    /// Starts a forecast day at `date` (ISO 8601, e.g. "2024-06-01")
+    pub fn builder(date: impl Into<String>) -> DailyForecastBuilder {
+        DailyForecastBuilder(DailyForecast {
+            date: date.into(),
+            ..Default::default()
+        })
+    }
+}
+
+impl DailyForecastBuilder {
+    optional_setters! {
+        temperature_max: f64,
+        temperature_min: f64,
+        weather_code: i32,
+        precipitation_sum: f64,
+        snowfall_sum: f64,
+        precipitation_probability: f64,
+        precipitation_probability_mean: f64,
+        wet_hours: u32,
+        wind_speed_max: f64,
+        wind_gusts_max: f64,
+        sunrise: String,
+        sunset: String,
+        daylight_duration: f64,
+        sunshine_duration: f64,
+        et0_evapotranspiration: f64,
+        humidity_mean: f64,
+        uv_index_max: f64,
+        confidence: Confidence,
+    }
+
+    pub fn build(self) -> DailyForecast {
+        self.0
+    }
+}
+
+/// Builder returned by `WeatherInfo::builder()`
+#[derive(Clone, Debug)]
+pub struct WeatherInfoBuilder(WeatherInfo);
+
+impl WeatherInfo {
+    pub fn builder(
+        location: impl Into<String>,
+        latitude: f64,
+        longitude: f64,
+    ) -> WeatherInfoBuilder {
+        WeatherInfoBuilder(WeatherInfo::new(
+            location.into(),
+            latitude,
+            longitude,
+            WeatherData::default(),
+        ))
+    }
+}
+
+impl WeatherInfoBuilder {
+    optional_setters! {
+        query: String,
+        elevation: f64,
+        fetched_at: chrono::DateTime<chrono::Utc>,
+        generation_time_ms: f64,
+        utc_offset_seconds: i32,
+        timezone: String,
+        timezone_abbreviation: String,
+        marine: crate::models::marine::MarineForecast,
+        air_quality: crate::models::air_quality::AirQuality,
+    }
+
+    pub fn current(mut self, current: CurrentWeather) -> Self {
+        self.0.weather_data.current = current;
+        self
+    }
+
+    pub fn hourly(mut self, hourly: Vec<HourlyForecast>) -> Self {
+        self.0.weather_data.hourly = hourly;
+        self
+    }
+
+    pub fn daily(mut self, daily: Vec<DailyForecast>) -> Self {
+        self.0.weather_data.daily = daily;
+        self
+    }
+
+    pub fn reduced_data(mut self, reduced_data: bool) -> Self {
+        self.0.reduced_data = reduced_data;
+        self
+    }
+
+    pub fn build(self) -> WeatherInfo {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builders_set_fields() {
+        let info = WeatherInfo::builder("Oslo", 59.9, 10.7)
+            .elevation(23.0)
+            .current(
+                CurrentWeather::builder()
+                    .temperature(12.5)
+                    .is_day(true)
+                    .build(),
+            )
+            .hourly(vec![HourlyForecast::builder("2024-06-01T14:00")
+                .cape(300.0)
+                .build()])
+            .daily(vec![DailyForecast::builder("2024-06-01")
+                .sunrise("2024-06-01T03:54")
+                .build()])
+            .build();
+
+        assert_eq!(info.elevation, Some(23.0));
+        assert_eq!(info.weather_data.current.temperature, Some(12.5));
+        assert_eq!(info.weather_data.current.is_day, Some(true));
+        assert_eq!(info.weather_data.current.humidity, None);
+        assert_eq!(info.weather_data.hourly[0].cape, Some(300.0));
+        assert_eq!(
+            info.weather_data.daily[0].sunrise.as_deref(),
+            Some("2024-06-01T03:54")
+        );
+    }
+}
@@ -0,0 +1,506 @@
+//! Saved locations with optional user labels and notes
+//!
+//! A favorite keeps the query it was saved with, which may be a typo the
+//! geocoder happened to forgive or a pair of coordinates. Every
+//! `FAVORITE_REFRESH_INTERVAL` the query is geocoded again (reverse geocoded
+//! for coordinates) and the result's name shown in its place. A result that
+//! lands more than `FAVORITE_MAX_MOVE_KM` from the recorded position is held
+//! back until the user accepts it, so a favorite never silently relocates.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{FAVORITE_MAX_MOVE_KM, FAVORITE_REFRESH_INTERVAL};
+use crate::utils::coords::Coordinates;
+use crate::utils::location::normalize_location_key;
+
+/// A saved location, optionally labelled ("Home", "Cabin") and annotated
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "StoredFavorite")]
+#[non_exhaustive]
+pub struct Favorite {
+    /// Location query passed to the geocoder
+    pub location: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Name and position from the last name refresh
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved: Option<ResolvedPlace>,
+    /// Date of the last name refresh
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refreshed: Option<NaiveDate>,
+    /// Result of a refresh too far from `resolved`, awaiting confirmation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub moved: Option<ResolvedPlace>,
+}
+
+/// Name and position the geocoder gives a favorite's location
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ResolvedPlace {
+    /// "Seattle, United States"
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl ResolvedPlace {
+    pub fn new(name: impl Into<String>, latitude: f64, longitude: f64) -> Self {
+        ResolvedPlace {
+            name: name.into(),
+            latitude,
+            longitude,
+        }
+    }
+
+    pub fn coordinates(&self) -> Coordinates {
+        Coordinates {
+            latitude: self.latitude,
+            longitude: self.longitude,
+        }
+    }
+}
+
+/// What a name refresh changed
+#[derive(Clone, Debug, PartialEq)]
+pub enum RefreshOutcome {
+    Unchanged,
+    /// The shown name changed
+    Renamed {
+        from: String,
+        to: String,
+    },
+    /// The geocoder now places the location `distance_km` away; the result
+    /// waits in `Favorite::moved`
+    NeedsConfirmation {
+        distance_km: f64,
+    },
+}
+
+/// Whether a favorite last refreshed on `last` is due for another refresh
+pub fn refresh_due(last: Option<NaiveDate>, today: NaiveDate) -> bool {
+    last.is_none_or(|last| today - last >= FAVORITE_REFRESH_INTERVAL)
+}
+
+/// Distance from `recorded` to `refreshed` in km, when it is too far to move
+/// a favorite without asking
+pub fn moved_too_far(recorded: &ResolvedPlace, refreshed: &ResolvedPlace) -> Option<f64> {
+    let distance = recorded.coordinates().distance_km(&refreshed.coordinates());
+    (distance > FAVORITE_MAX_MOVE_KM).then_some(distance)
+}
+
+/// On-disk entry: a bare location string (files written before labels existed) or a full record
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredFavorite {
+    Plain(String),
+    Record {
+        location: String,
+        #[serde(default)]
+        label: Option<String>,
+        #[serde(default)]
+        note: Option<String>,
+        #[serde(default)]
+        resolved: Option<ResolvedPlace>,
+        #[serde(default)]
+        refreshed: Option<NaiveDate>,
+        #[serde(default)]
+        moved: Option<ResolvedPlace>,
+    },
+}
+
+impl From<StoredFavorite> for Favorite {
+    fn from(stored: StoredFavorite) -> Self {
+        match stored {
+            StoredFavorite::Plain(location) => Favorite::new(location),
+            StoredFavorite::Record {
+                location,
+                label,
+                note,
+                resolved,
+                refreshed,
+                moved,
+            } => Favorite {
+                location,
+                label,
+                note,
+                resolved,
+                refreshed,
+                moved,
+            },
+        }
+    }
+}
+
+impl Favorite {
+    pub fn new(location: impl Into<String>) -> Self {
+        Favorite {
+            location: location.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Geocoded name from the last refresh, otherwise the saved query
+    pub fn place_name(&self) -> &str {
+        self.resolved
+            .as_ref()
+            .map_or(&self.location, |place| &place.name)
+    }
+
+    /// What to fetch the weather for: the recorded position once a refresh
+    /// has resolved one, so a geocoder that now places the query elsewhere
+    /// never changes the forecast shown under this name; the saved query
+    /// until then
+    pub fn query(&self) -> String {
+        self.resolved.as_ref().map_or_else(
+            || self.location.clone(),
+            |place| place.coordinates().to_string(),
+        )
+    }
+
+    /// Label if set, otherwise the place name
+    pub fn name(&self) -> &str {
+        self.label.as_deref().unwrap_or(self.place_name())
+    }
+
+    /// "Home (Seattle)" for labelled entries, "Seattle" otherwise
+    pub fn title(&self) -> String {
+        match &self.label {
+            Some(label) => format!("{label} ({})", self.place_name()),
+            None => self.place_name().to_string(),
+        }
+    }
+
+    /// Records the result of a name refresh on `today`: `None` when the
+    /// geocoder found nothing, which only counts as a refresh. A place too
+    /// far from the recorded one is kept in `moved` instead.
+    pub fn apply_refresh(
+        &mut self,
+        place: Option<ResolvedPlace>,
+        today: NaiveDate,
+    ) -> RefreshOutcome {
+        self.refreshed = Some(today);
+        let Some(place) = place else {
+            return RefreshOutcome::Unchanged;
+        };
+        if let Some(distance_km) = self
+            .resolved
+            .as_ref()
+            .and_then(|recorded| moved_too_far(recorded, &place))
+        {
+            self.moved = Some(place);
+            return RefreshOutcome::NeedsConfirmation { distance_km };
+        }
+
+        let from = self.place_name().to_string();
+        self.moved = None;
+        self.resolved = Some(place);
+        let to = self.place_name();
+        if from == to {
+            RefreshOutcome::Unchanged
+        } else {
+            RefreshOutcome::Renamed {
+                from,
+                to: to.to_string(),
+            }
+        }
+    }
+
+    /// Takes the place held back by a refresh as the recorded one; `false`
+    /// when none was waiting
+    pub fn accept_move(&mut self) -> bool {
+        match self.moved.take() {
+            Some(place) => {
+                self.resolved = Some(place);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Ordered list of saved locations
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Favorites {
+    entries: Vec<Favorite>,
+}
+
+impl Favorites {
+    pub fn entries(&self) -> &[Favorite] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Adds a location, or updates the label and note of an existing entry
+    /// (matched by `normalize_location_key`, so "München" is "MÜNCHEN" and
+    /// "Seattle," is "Seattle"). `None` keeps the current value.
+    pub fn save(&mut self, location: &str, label: Option<String>, note: Option<String>) {
+        let location = location.trim();
+        let key = normalize_location_key(location);
+        let index = match self
+            .entries
+            .iter()
+            .position(|f| normalize_location_key(&f.location) == key)
+        {
+            Some(index) => index,
+            None => {
+                self.entries.push(Favorite::new(location));
+                self.entries.len() - 1
+            }
+        };
+
+        let entry = &mut self.entries[index];
+        if label.is_some() {
+            entry.label = label;
+        }
+        if note.is_some() {
+            entry.note = note;
+        }
+    }
+
+    /// Sets or clears (empty string) the label of the entry at `index`
+    pub fn rename(&mut self, index: usize, label: &str) {
+        if let Some(entry) = self.entries.get_mut(index) {
+            let label = label.trim();
+            entry.label = (!label.is_empty()).then(|| label.to_string());
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.entries.len() {
+            self.entries.remove(index);
+        }
+    }
+
+    /// Locations whose names are due for a refresh on `today`
+    pub fn due_for_refresh(&self, today: NaiveDate) -> Vec<String> {
+        self.entries
+            .iter()
+            .filter(|f| refresh_due(f.refreshed, today))
+            .map(|f| f.location.clone())
+            .collect()
+    }
+
+    /// Entry saved as `location`, matched as `save` matches
+    pub fn find(&self, location: &str) -> Option<&Favorite> {
+        let key = normalize_location_key(location);
+        self.entries
+            .iter()
+            .find(|f| normalize_location_key(&f.location) == key)
+    }
+
+    /// Applies a refresh result to the entry saved as `location` (matched as
+    /// `save` matches); `None` when it was removed in the meantime
+    pub fn apply_refresh(
+        &mut self,
+        location: &str,
+        place: Option<ResolvedPlace>,
+        today: NaiveDate,
+    ) -> Option<RefreshOutcome> {
+        let key = normalize_location_key(location);
+        let entry = self
+            .entries
+            .iter_mut()
+            .find(|f| normalize_location_key(&f.location) == key)?;
+        Some(entry.apply_refresh(place, today))
+    }
+
+    /// Accepts the moved place of the entry at `index`
+    pub fn accept_move(&mut self, index: usize) -> bool {
+        self.entries
+            .get_mut(index)
+            .is_some_and(Favorite::accept_move)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_label_less_format() {
+        let favorites: Favorites = serde_json::from_str(r#"["Seattle", "Boston"]"#).unwrap();
+        assert_eq!(favorites.entries()[0], Favorite::new("Seattle"));
+        assert_eq!(favorites.entries()[1].title(), "Boston");
+    }
+
+    #[test]
+    fn test_round_trips_mixed_entries() {
+        let json =
+            r#"["Seattle", {"location": "Bend", "label": "Cabin", "note": "Key under mat"}]"#;
+        let favorites: Favorites = serde_json::from_str(json).unwrap();
+        assert_eq!(favorites.entries()[1].title(), "Cabin (Bend)");
+
+        let written = serde_json::to_string(&favorites).unwrap();
+        assert_eq!(
+            written,
+            r#"[{"location":"Seattle"},{"location":"Bend","label":"Cabin","note":"Key under mat"}]"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Favorites>(&written).unwrap(),
+            favorites
+        );
+    }
+
+    #[test]
+    fn test_save_updates_existing_entry() {
+        let mut favorites = Favorites::default();
+        favorites.save("Seattle", None, None);
+        favorites.save(" seattle ", Some("Home".to_string()), None);
+        favorites.save("Seattle", None, Some("Upstairs sensor".to_string()));
+
+        assert_eq!(favorites.entries().len(), 1);
+        assert_eq!(favorites.entries()[0].name(), "Home");
+        assert_eq!(
+            favorites.entries()[0].note.as_deref(),
+            Some("Upstairs sensor")
+        );
+
+        favorites.rename(0, "  ");
+        assert_eq!(favorites.entries()[0].label, None);
+    }
+
+    #[test]
+    fn test_save_matches_unicode_case_and_trailing_commas() {
+        let mut favorites = Favorites::default();
+        favorites.save("München", None, None);
+        favorites.save("MÜNCHEN", Some("Oktoberfest".to_string()), None);
+        favorites.save("Seattle", None, None);
+        favorites.save("Seattle,", Some("Home".to_string()), None);
+        favorites.save("New  York", None, None);
+        favorites.save("new york", Some("Work".to_string()), None);
+
+        let entries = favorites.entries();
+        assert_eq!(entries.len(), 3);
+        // The spelling first saved is kept
+        assert_eq!(entries[0].location, "München");
+        assert_eq!(entries[0].name(), "Oktoberfest");
+        assert_eq!(entries[1].location, "Seattle");
+        assert_eq!(entries[1].name(), "Home");
+        assert_eq!(entries[2].name(), "Work");
+        // Refreshes find their entry the same way
+        assert_eq!(favorites.find("seattle, ").unwrap().name(), "Home");
+        let place = ResolvedPlace::new("München, Deutschland", 48.14, 11.58);
+        let today = date("2024-06-30");
+        assert!(favorites
+            .apply_refresh("MÜNCHEN", Some(place), today)
+            .is_some());
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_refresh_schedule() {
+        let today = date("2024-06-30");
+        assert!(refresh_due(None, today));
+        assert!(!refresh_due(Some(today), today));
+        assert!(!refresh_due(Some(date("2024-06-01")), today));
+        assert!(refresh_due(Some(date("2024-05-31")), today));
+        assert!(refresh_due(Some(date("2023-01-01")), today));
+
+        let mut favorites = Favorites::default();
+        favorites.save("Seattle", None, None);
+        favorites.save("Bend", None, None);
+        favorites.apply_refresh("Bend", None, date("2024-06-20"));
+        assert_eq!(favorites.due_for_refresh(today), ["Seattle"]);
+    }
+
+    #[test]
+    fn test_moved_too_far() {
+        let seattle = ResolvedPlace::new("Seattle", 47.6062, -122.3321);
+        let nearby = ResolvedPlace::new("Seattle", 47.65, -122.35);
+        let tacoma = ResolvedPlace::new("Tacoma", 47.2529, -122.4443);
+        assert_eq!(moved_too_far(&seattle, &nearby), None);
+        let distance = moved_too_far(&seattle, &tacoma).unwrap();
+        assert!((distance - 40.2).abs() < 1.0, "{distance}");
+        // Just either side of the limit: 0.09° of latitude is 10.0 km
+        let edge = |latitude| ResolvedPlace::new("", latitude, 0.0);
+        assert_eq!(moved_too_far(&edge(0.0), &edge(0.089)), None);
+        assert!(moved_too_far(&edge(0.0), &edge(0.091)).is_some());
+    }
+
+    #[test]
+    fn test_refresh_renames_and_holds_back_moves() {
+        let today = date("2024-06-30");
+        let mut favorite = Favorite::new("seatle");
+        assert_eq!(favorite.query(), "seatle");
+        let seattle = ResolvedPlace::new("Seattle, United States", 47.6062, -122.3321);
+        assert_eq!(
+            favorite.apply_refresh(Some(seattle.clone()), today),
+            RefreshOutcome::Renamed {
+                from: "seatle".to_string(),
+                to: "Seattle, United States".to_string()
+            }
+        );
+        assert_eq!(favorite.name(), "Seattle, United States");
+        assert_eq!(favorite.location, "seatle");
+        assert_eq!(favorite.refreshed, Some(today));
+        // Fetched where it was resolved, whatever the geocoder says later
+        let query = crate::utils::coords::parse(&favorite.query()).unwrap();
+        assert_eq!(query, seattle.coordinates());
+
+        // A renamed boundary nearby is taken as it is
+        let renamed = ResolvedPlace::new("Seattle, Washington", 47.61, -122.33);
+        assert!(matches!(
+            favorite.apply_refresh(Some(renamed.clone()), today),
+            RefreshOutcome::Renamed { .. }
+        ));
+        assert_eq!(
+            favorite.apply_refresh(Some(renamed.clone()), today),
+            RefreshOutcome::Unchanged
+        );
+
+        // A place 40 km off waits for confirmation
+        let tacoma = ResolvedPlace::new("Tacoma, United States", 47.2529, -122.4443);
+        let outcome = favorite.apply_refresh(Some(tacoma.clone()), today);
+        assert!(matches!(outcome, RefreshOutcome::NeedsConfirmation { .. }));
+        assert_eq!(favorite.resolved, Some(renamed));
+        assert_eq!(favorite.moved, Some(tacoma.clone()));
+        assert!(favorite.accept_move());
+        assert_eq!(favorite.resolved, Some(tacoma.clone()));
+        assert!(!favorite.accept_move());
+
+        // Accepted moves are fetched at their new position
+        assert_eq!(favorite.query(), tacoma.coordinates().to_string());
+
+        // Nothing found only counts as a refresh
+        favorite.refreshed = None;
+        assert_eq!(
+            favorite.apply_refresh(None, today),
+            RefreshOutcome::Unchanged
+        );
+        assert_eq!(favorite.refreshed, Some(today));
+        assert_eq!(favorite.name(), "Tacoma, United States");
+    }
+
+    #[test]
+    fn test_round_trips_refresh_state() {
+        let mut favorites = Favorites::default();
+        favorites.save("seatle", Some("Home".to_string()), None);
+        favorites.apply_refresh(
+            "seatle",
+            Some(ResolvedPlace::new(
+                "Seattle, United States",
+                47.6062,
+                -122.3321,
+            )),
+            date("2024-06-30"),
+        );
+        assert_eq!(
+            favorites.entries()[0].title(),
+            "Home (Seattle, United States)"
+        );
+        let written = serde_json::to_string(&favorites).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Favorites>(&written).unwrap(),
+            favorites
+        );
+    }
+}
@@ -0,0 +1,131 @@
+//! Weather model behind a forecast
+//!
+//! Open-Meteo blends the best models for each location unless `models=` pins
+//! one. Pinning lets a forecast be compared across models, e.g. ECMWF
+//! against GFS; regional models only cover their own region.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Model the forecast is requested from
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ForecastModel {
+    /// Open-Meteo's choice of models for the location
+    #[default]
+    BestMatch,
+    /// ECMWF IFS at 0.25°
+    Ecmwf,
+    /// NOAA GFS, blended with HRRR over the US
+    Gfs,
+    /// DWD ICON, global with the European and German nests
+    IconSeamless,
+    /// Environment Canada GEM
+    Gem,
+    /// Météo-France ARPEGE and AROME
+    MeteoFrance,
+    /// UK Met Office global and UK models
+    Ukmo,
+}
+
+impl ForecastModel {
+    pub const ALL: [ForecastModel; 7] = [
+        ForecastModel::BestMatch,
+        ForecastModel::Ecmwf,
+        ForecastModel::Gfs,
+        ForecastModel::IconSeamless,
+        ForecastModel::Gem,
+        ForecastModel::MeteoFrance,
+        ForecastModel::Ukmo,
+    ];
+
+    /// Name shown next to a forecast, e.g. "ECMWF"
+    pub fn label(&self) -> &'static str {
+        match self {
+            ForecastModel::BestMatch => "Best match",
+            ForecastModel::Ecmwf => "ECMWF",
+            ForecastModel::Gfs => "GFS",
+            ForecastModel::IconSeamless => "ICON",
+            ForecastModel::Gem => "GEM",
+            ForecastModel::MeteoFrance => "Météo-France",
+            ForecastModel::Ukmo => "UK Met Office",
+        }
+    }
+
+    /// Value of Open-Meteo's `models` parameter
+    pub fn api_name(&self) -> &'static str {
+        match self {
+            ForecastModel::BestMatch => "best_match",
+            ForecastModel::Ecmwf => "ecmwf_ifs025",
+            ForecastModel::Gfs => "gfs_seamless",
+            ForecastModel::IconSeamless => "icon_seamless",
+            ForecastModel::Gem => "gem_seamless",
+            ForecastModel::MeteoFrance => "meteofrance_seamless",
+            ForecastModel::Ukmo => "ukmo_seamless",
+        }
+    }
+
+    /// Whether a model is pinned rather than left to Open-Meteo
+    pub fn is_pinned(&self) -> bool {
+        *self != ForecastModel::BestMatch
+    }
+}
+
+impl fmt::Display for ForecastModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ForecastModel::BestMatch => "best-match",
+            ForecastModel::Ecmwf => "ecmwf",
+            ForecastModel::Gfs => "gfs",
+            ForecastModel::IconSeamless => "icon",
+            ForecastModel::Gem => "gem",
+            ForecastModel::MeteoFrance => "meteofrance",
+            ForecastModel::Ukmo => "ukmo",
+        })
+    }
+}
+
+impl FromStr for ForecastModel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().replace(['_', ' '], "-").as_str() {
+            "best-match" | "best" | "auto" => Ok(ForecastModel::BestMatch),
+            "ecmwf" | "ecmwf-ifs025" | "ifs" => Ok(ForecastModel::Ecmwf),
+            "gfs" | "gfs-seamless" => Ok(ForecastModel::Gfs),
+            "icon" | "icon-seamless" => Ok(ForecastModel::IconSeamless),
+            "gem" | "gem-seamless" => Ok(ForecastModel::Gem),
+            "meteofrance" | "meteo-france" | "meteofrance-seamless" => {
+                Ok(ForecastModel::MeteoFrance)
+            }
+            "ukmo" | "ukmo-seamless" | "met-office" => Ok(ForecastModel::Ukmo),
+            other => Err(format!(
+                "unknown forecast model '{other}' (expected best-match, ecmwf, gfs, icon, gem, meteofrance or ukmo)"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_round_trip() {
+        for model in ForecastModel::ALL {
+            assert_eq!(model.to_string().parse::<ForecastModel>(), Ok(model));
+            assert_eq!(model.api_name().parse::<ForecastModel>(), Ok(model));
+        }
+        assert_eq!("ECMWF".parse(), Ok(ForecastModel::Ecmwf));
+        assert_eq!("Icon".parse(), Ok(ForecastModel::IconSeamless));
+        assert!(!ForecastModel::default().is_pinned());
+        assert!(ForecastModel::Gfs.is_pinned());
+        let error = "harmonie".parse::<ForecastModel>().unwrap_err();
+        assert!(
+            error.starts_with("unknown forecast model 'harmonie'"),
+            "{error}"
+        );
+    }
+}
@@ -0,0 +1,203 @@
+//! Minimum, maximum and totals over a run of hourly forecasts
+
+use crate::constants::HOURLY_STATS_MIN_POINTS;
+use crate::models::weather_info::HourlyForecast;
+use crate::utils::conversions::WindUnit;
+use crate::utils::format::{NumberLocale, Precision};
+
+/// Extreme value of an hourly series and the hour it occurs in
+#[derive(Clone, Debug, PartialEq)]
+pub struct Extremum {
+    pub value: f64,
+    /// Start of the hour (ISO 8601, local time)
+    pub time: String,
+}
+
+impl Extremum {
+    /// "15:00", or the whole time if it has no clock part
+    pub fn clock(&self) -> &str {
+        self.time
+            .split_once('T')
+            .map_or(self.time.as_str(), |(_, clock)| {
+                clock.get(..5).unwrap_or(clock)
+            })
+    }
+}
+
+/// Statistics of an hourly range; each is `None` when fewer than
+/// `HOURLY_STATS_MIN_POINTS` hours report the quantity. Ties go to the
+/// earliest hour. Temperatures in °C, precipitation in mm, wind in km/h.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct HourlyStats {
+    pub temperature_min: Option<Extremum>,
+    pub temperature_max: Option<Extremum>,
+    pub temperature_mean: Option<f64>,
+    pub precipitation_total: Option<f64>,
+    pub wind_peak: Option<Extremum>,
+}
+
+impl HourlyStats {
+    pub fn from_hours(hours: &[HourlyForecast]) -> Self {
+        let series = |value: fn(&HourlyForecast) -> Option<f64>| -> Vec<(f64, &str)> {
+            let points: Vec<(f64, &str)> = hours
+                .iter()
+                .filter_map(|h| value(h).map(|v| (v, h.time.as_str())))
+                .collect();
+            if points.len() >= HOURLY_STATS_MIN_POINTS {
+                points
+            } else {
+                Vec::new()
+            }
+        };
+        // Strict comparisons keep the first of equal values
+        let extremum = |points: &[(f64, &str)], better: fn(f64, f64) -> bool| {
+            points
+                .iter()
+                .copied()
+                .reduce(|best, point| if better(point.0, best.0) { point } else { best })
+                .map(|(value, time)| Extremum {
+                    value,
+                    time: time.to_string(),
+                })
+        };
+
+        let temperatures = series(|h| h.temperature);
+        let precipitation = series(|h| h.precipitation);
+        let wind = series(|h| h.wind_speed);
+        HourlyStats {
+            temperature_min: extremum(&temperatures, |a, b| a < b),
+            temperature_max: extremum(&temperatures, |a, b| a > b),
+            temperature_mean: (!temperatures.is_empty()).then(|| {
+                temperatures.iter().map(|(t, _)| t).sum::<f64>() / temperatures.len() as f64
+            }),
+            precipitation_total: (!precipitation.is_empty())
+                .then(|| precipitation.iter().map(|(p, _)| p).sum()),
+            wind_peak: extremum(&wind, |a, b| a > b),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == HourlyStats::default()
+    }
+
+    /// "min 8.2°C (05:00) · max 17.6°C (15:00) · avg 12.4°C · total precip
+    /// 3.2 mm · peak wind 32 km/h (13:00)", leaving out missing figures
+    pub fn footer(
+        &self,
+        precision: Precision,
+        locale: NumberLocale,
+        wind_unit: WindUnit,
+    ) -> Option<String> {
+        let parts: Vec<String> = [
+            self.temperature_min.as_ref().map(|min| {
+                format!(
+                    "min {} ({})",
+                    locale.celsius(min.value, precision),
+                    min.clock()
+                )
+            }),
+            self.temperature_max.as_ref().map(|max| {
+                format!(
+                    "max {} ({})",
+                    locale.celsius(max.value, precision),
+                    max.clock()
+                )
+            }),
+            self.temperature_mean
+                .map(|mean| format!("avg {}", locale.celsius(mean, precision))),
+            self.precipitation_total
+                .map(|total| format!("total precip {} mm", locale.decimal(total, 1))),
+            self.wind_peak.as_ref().map(|peak| {
+                format!(
+                    "peak wind {} {} ({})",
+                    locale.decimal(wind_unit.from_kmh(peak.value), 0),
+                    wind_unit.label(),
+                    peak.clock()
+                )
+            }),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        (!parts.is_empty()).then(|| parts.join(" · "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hour(clock: &str, temperature: Option<f64>, wind: Option<f64>) -> HourlyForecast {
+        let mut hour = HourlyForecast::builder(format!("2024-06-01T{clock}"));
+        if let Some(t) = temperature {
+            hour = hour.temperature(t);
+        }
+        if let Some(w) = wind {
+            hour = hour.wind_speed(w).precipitation(0.5);
+        }
+        hour.build()
+    }
+
+    #[test]
+    fn test_stats_with_ties_keep_the_first_hour() {
+        let hours = [
+            hour("05:00", Some(8.2), Some(10.0)),
+            hour("06:00", Some(12.0), Some(32.0)),
+            hour("13:00", None, Some(32.0)),
+            hour("15:00", Some(17.6), None),
+            hour("16:00", Some(17.6), Some(20.0)),
+            hour("17:00", Some(8.2), None),
+        ];
+        let stats = HourlyStats::from_hours(&hours);
+        assert_eq!(stats.temperature_min.as_ref().unwrap().clock(), "05:00");
+        assert_eq!(stats.temperature_max.as_ref().unwrap().clock(), "15:00");
+        assert_eq!(stats.wind_peak.as_ref().unwrap().clock(), "06:00");
+        assert!((stats.temperature_mean.unwrap() - 12.72).abs() < 1e-9);
+        assert_eq!(stats.precipitation_total, Some(2.0));
+
+        assert_eq!(
+            stats
+                .footer(Precision::Tenths, NumberLocale::En, WindUnit::Kmh)
+                .unwrap(),
+            "min 8.2°C (05:00) · max 17.6°C (15:00) · avg 12.7°C · \
+             total precip 2.0 mm · peak wind 32 km/h (06:00)"
+        );
+    }
+
+    #[test]
+    fn test_sparse_data_reports_nothing() {
+        let hours = [
+            hour("00:00", Some(3.0), Some(12.0)),
+            hour("01:00", None, None),
+            hour("02:00", Some(4.0), None),
+            hour("03:00", None, Some(14.0)),
+            hour("04:00", None, Some(15.0)),
+        ];
+        let stats = HourlyStats::from_hours(&hours);
+        // Two temperatures are too few; three wind speeds are enough
+        assert_eq!(stats.temperature_min, None);
+        assert_eq!(stats.temperature_mean, None);
+        assert_eq!(stats.precipitation_total, Some(1.5));
+        assert_eq!(stats.wind_peak.as_ref().unwrap().value, 15.0);
+        assert_eq!(
+            stats
+                .footer(Precision::Whole, NumberLocale::En, WindUnit::Knots)
+                .unwrap(),
+            "total precip 1.5 mm · peak wind 8 kn (04:00)"
+        );
+        assert_eq!(
+            stats
+                .footer(Precision::Tenths, NumberLocale::De, WindUnit::Kmh)
+                .unwrap(),
+            "total precip 1,5 mm · peak wind 15 km/h (04:00)"
+        );
+
+        let empty = HourlyStats::from_hours(&[]);
+        assert!(empty.is_empty());
+        assert_eq!(
+            empty.footer(Precision::Whole, NumberLocale::En, WindUnit::Kmh),
+            None
+        );
+    }
+}
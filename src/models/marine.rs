@@ -0,0 +1,105 @@
+//! Wave forecast for coastal locations (Open-Meteo marine API)
+
+use serde::{Deserialize, Serialize};
+
+/// Sea state for one hour at the nearest ocean grid point
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct MarineHour {
+    /// Local time, e.g. "2024-06-01T14:00"
+    pub time: String,
+    /// Significant wave height in meters
+    pub wave_height: Option<f64>,
+    /// Mean wave period in seconds
+    pub wave_period: Option<f64>,
+    /// Direction the waves come from, in degrees
+    pub wave_direction: Option<f64>,
+}
+
+impl MarineHour {
+    pub fn new(time: impl Into<String>) -> Self {
+        MarineHour {
+            time: time.into(),
+            ..Self::default()
+        }
+    }
+
+    fn has_data(&self) -> bool {
+        self.wave_height.is_some() || self.wave_period.is_some() || self.wave_direction.is_some()
+    }
+}
+
+/// Hourly waves; only present when the API has data for the coordinates
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MarineForecast {
+    pub hourly: Vec<MarineHour>,
+}
+
+impl MarineForecast {
+    /// `None` when no hour has any value, which is how inland points come back
+    pub fn from_hours(hourly: Vec<MarineHour>) -> Option<Self> {
+        hourly
+            .iter()
+            .any(MarineHour::has_data)
+            .then_some(MarineForecast { hourly })
+    }
+
+    /// Up to `hours` entries starting at the hour containing `time`
+    /// ("YYYY-MM-DDTHH:MM", local); from the first entry when it is not covered
+    pub fn upcoming(&self, time: Option<&str>, hours: usize) -> &[MarineHour] {
+        let hour_prefix = time.and_then(|t| t.get(..13)).unwrap_or_default();
+        let start = self
+            .hourly
+            .iter()
+            .position(|h| !hour_prefix.is_empty() && h.time.starts_with(hour_prefix))
+            .unwrap_or(0);
+        let end = start.saturating_add(hours).min(self.hourly.len());
+        &self.hourly[start..end]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hour(time: &str, wave_height: Option<f64>) -> MarineHour {
+        MarineHour {
+            wave_height,
+            ..MarineHour::new(time)
+        }
+    }
+
+    #[test]
+    fn test_all_null_hours_are_no_forecast() {
+        let inland = vec![
+            hour("2024-06-01T00:00", None),
+            hour("2024-06-01T01:00", None),
+        ];
+        assert_eq!(MarineForecast::from_hours(inland), None);
+        assert_eq!(MarineForecast::from_hours(Vec::new()), None);
+
+        let coast = vec![
+            hour("2024-06-01T00:00", None),
+            hour("2024-06-01T01:00", Some(1.2)),
+        ];
+        assert_eq!(MarineForecast::from_hours(coast).unwrap().hourly.len(), 2);
+    }
+
+    #[test]
+    fn test_upcoming_starts_at_the_current_hour() {
+        let hourly = (0..24)
+            .map(|h| hour(&format!("2024-06-01T{h:02}:00"), Some(h as f64)))
+            .collect();
+        let forecast = MarineForecast { hourly };
+
+        let next = forecast.upcoming(Some("2024-06-01T10:45"), 3);
+        assert_eq!(next.len(), 3);
+        assert_eq!(next[0].time, "2024-06-01T10:00");
+        assert_eq!(forecast.upcoming(Some("2024-06-01T22:00"), 5).len(), 2);
+        assert_eq!(forecast.upcoming(None, 2)[0].time, "2024-06-01T00:00");
+        assert_eq!(
+            forecast.upcoming(Some("2024-07-01T10:00"), 1)[0].time,
+            "2024-06-01T00:00"
+        );
+    }
+}
@@ -1,2 +1,14 @@
 // Model layer - defines data structures
+pub mod air_quality;
+pub mod builders;
+pub mod favorites;
+pub mod forecast_model;
+pub mod hourly_stats;
+pub mod marine;
+pub mod settings;
+pub mod summary;
+pub mod timings;
+pub mod usage;
 pub mod weather_info;
+pub mod weather_log;
+pub mod wind_rose;
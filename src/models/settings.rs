@@ -0,0 +1,31 @@
+//! User preferences remembered between sessions
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::format::NumberLocale;
+use crate::utils::hourly_range::HourlyRange;
+use crate::utils::i18n::Locale;
+use crate::views::DisplayOptions;
+
+/// Preferences saved by the GUI settings window
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct Settings {
+    /// Interface language
+    pub locale: Locale,
+    /// Hours shown in the hourly tab
+    pub hourly_range: HourlyRange,
+    /// Keep local usage statistics (see `UsageLog`); off unless the user opts in
+    pub usage_stats: bool,
+    /// Decimal separator and date order of the text views, separate from `locale`
+    pub number_locale: NumberLocale,
+    /// Replaces "RustWeatherApp/1.0" in the `User-Agent` header
+    pub user_agent: Option<String>,
+    /// Contact sent to Nominatim, as its usage policy asks (see `UserAgent`)
+    pub contact_email: Option<String>,
+    /// Sections and units chosen in the settings window
+    pub display: DisplayOptions,
+    /// Last location fetched successfully, searched again at startup
+    pub last_location: Option<String>,
+}
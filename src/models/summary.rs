@@ -0,0 +1,219 @@
+//! Compact weather summary for embedding and one-line output
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::models::weather_info::WeatherInfo;
+use crate::utils::daylight::Daylight;
+use crate::utils::format::{self, Precision};
+use crate::utils::weather_codes;
+
+/// Placeholder rendered for values the forecast did not include
+const MISSING: &str = "—";
+
+/// Headline numbers for a location; temperatures in Celsius
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct WeatherSummary {
+    pub location: String,
+    pub temperature: Option<f64>,
+    pub feels_like: Option<f64>,
+    pub weather_code: Option<i32>,
+    pub description: Option<String>,
+    /// Today's high (the location's today, falling back to the first forecast day)
+    pub high: Option<f64>,
+    /// Today's low
+    pub low: Option<f64>,
+    /// Precipitation probability (0-100) for the hour after the current one
+    pub next_hour_precipitation_probability: Option<f64>,
+    /// Sunrise time (ISO 8601 format)
+    pub sunrise: Option<String>,
+    /// Sunset time (ISO 8601 format)
+    pub sunset: Option<String>,
+    /// Polar day or night when today has neither
+    pub daylight: Daylight,
+}
+
+impl WeatherSummary {
+    pub(crate) fn from_info(info: &WeatherInfo, now: DateTime<Utc>) -> Self {
+        let data = &info.weather_data;
+        let current = &data.current;
+        let today = info
+            .today(now)
+            .and_then(|date| data.day(date))
+            .or_else(|| data.daily.first());
+        let next_hour = data.next_hour();
+
+        WeatherSummary {
+            location: info.location.clone(),
+            temperature: current.temperature,
+            feels_like: current.apparent_temperature,
+            weather_code: current.weather_code,
+            description: current
+                .weather_code
+                .map(|code| weather_codes::description(code).to_string()),
+            high: today.and_then(|d| d.temperature_max),
+            low: today.and_then(|d| d.temperature_min),
+            next_hour_precipitation_probability: next_hour
+                .and_then(|h| h.precipitation_probability),
+            sunrise: today.and_then(|d| d.sunrise.clone()),
+            sunset: today.and_then(|d| d.sunset.clone()),
+            daylight: today.map_or(Daylight::RisesAndSets, |d| d.daylight(info.latitude)),
+        }
+    }
+}
+
+fn temperature(value: Option<f64>) -> String {
+    value.map_or_else(
+        || MISSING.to_string(),
+        |t| format::celsius(t, Precision::Whole),
+    )
+}
+
+/// "HH:MM" from an ISO 8601 timestamp
+fn clock_time(value: Option<&str>) -> String {
+    value
+        .and_then(|t| t.split('T').nth(1))
+        .and_then(|t| t.get(..5))
+        .unwrap_or(MISSING)
+        .to_string()
+}
+
+impl fmt::Display for WeatherSummary {
+    /// e.g. "Seattle: 14°C (feels 12°C), Partly cloudy | H 18°C L 9°C | rain 30% | sun 05:12–21:02"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sun = match self.daylight.label() {
+            Some(polar) => polar.to_string(),
+            None => format!(
+                "{}–{}",
+                clock_time(self.sunrise.as_deref()),
+                clock_time(self.sunset.as_deref())
+            ),
+        };
+        write!(
+            f,
+            "{}: {} (feels {}), {} | H {} L {} | rain {} | sun {sun}",
+            self.location,
+            temperature(self.temperature),
+            temperature(self.feels_like),
+            self.description.as_deref().unwrap_or(MISSING),
+            temperature(self.high),
+            temperature(self.low),
+            self.next_hour_precipitation_probability
+                .map_or_else(|| MISSING.to_string(), |p| format!("{p:.0}%")),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::sample_weather_info;
+    use crate::models::weather_info::{CurrentWeather, DailyForecast, HourlyForecast, WeatherInfo};
+
+    #[test]
+    fn test_summary_line() {
+        let hour = |time: &str, probability: f64| {
+            HourlyForecast::builder(time)
+                .precipitation_probability(probability)
+                .build()
+        };
+        let info = WeatherInfo::builder("Seattle", 47.6, -122.3)
+            .current(
+                CurrentWeather::builder()
+                    .time("2024-06-01T14:15")
+                    .temperature(14.2)
+                    .apparent_temperature(11.6)
+                    .weather_code(2)
+                    .build(),
+            )
+            .hourly(vec![
+                hour("2024-06-01T13:00", 10.0),
+                hour("2024-06-01T14:00", 20.0),
+                hour("2024-06-01T15:00", 30.0),
+            ])
+            .daily(vec![DailyForecast::builder("2024-06-01")
+                .temperature_max(18.0)
+                .temperature_min(9.0)
+                .sunrise("2024-06-01T05:12")
+                .sunset("2024-06-01T21:02")
+                .build()])
+            .build();
+
+        let summary = info.summary();
+        assert_eq!(summary.next_hour_precipitation_probability, Some(30.0));
+        assert_eq!(
+            summary.to_string(),
+            "Seattle: 14°C (feels 12°C), Partly cloudy | H 18°C L 9°C | rain 30% | sun 05:12–21:02"
+        );
+    }
+
+    #[test]
+    fn test_summary_uses_location_today() {
+        use chrono::{TimeZone, Utc};
+
+        let day = |date: &str, high: f64| {
+            DailyForecast::builder(date)
+                .temperature_max(high)
+                .temperature_min(high - 8.0)
+                .build()
+        };
+        let info = WeatherInfo::builder("Sydney", -33.9, 151.2)
+            .utc_offset_seconds(11 * 3600)
+            .daily(vec![day("2024-06-07", 18.0), day("2024-06-08", 21.0)])
+            .build();
+
+        // Friday 14:30 UTC is Saturday at the location, whatever the host's zone
+        let now = Utc.with_ymd_and_hms(2024, 6, 7, 14, 30, 0).unwrap();
+        assert_eq!(info.summary_at(now).high, Some(21.0));
+
+        let earlier = Utc.with_ymd_and_hms(2024, 6, 7, 12, 30, 0).unwrap();
+        assert_eq!(info.summary_at(earlier).high, Some(18.0));
+    }
+
+    #[test]
+    fn test_summary_of_sample_data() {
+        let summary = sample_weather_info().summary();
+        assert_eq!(summary.temperature, Some(19.4));
+        assert_eq!(summary.high, Some(20.0));
+        assert_eq!(summary.sunrise.as_deref(), Some("2024-06-01T05:15"));
+    }
+
+    #[test]
+    fn test_summary_missing_fields_render_dashes() {
+        let summary = WeatherInfo::builder("Seattle", 47.6, -122.3)
+            .build()
+            .summary();
+        assert_eq!(summary.description, None);
+        assert_eq!(
+            summary.to_string(),
+            "Seattle: — (feels —), — | H — L — | rain — | sun —–—"
+        );
+    }
+
+    #[test]
+    fn test_summary_in_polar_night() {
+        let info = WeatherInfo::builder("Longyearbyen", 78.22, 15.65)
+            .daily(vec![DailyForecast::builder("2024-12-21")
+                .temperature_max(-8.0)
+                .temperature_min(-13.0)
+                .build()])
+            .build();
+        let summary = info.summary();
+        assert!(
+            summary.to_string().ends_with("| sun polar night"),
+            "{summary}"
+        );
+        let json = serde_json::to_value(summary).unwrap();
+        assert_eq!(json["daylight"], "polar-night");
+    }
+
+    #[test]
+    fn test_summary_serializes() {
+        let info = WeatherInfo::builder("Seattle", 47.6, -122.3).build();
+        let json = serde_json::to_value(info.summary()).unwrap();
+        assert_eq!(json["location"], "Seattle");
+        assert!(json["temperature"].is_null());
+    }
+}
@@ -0,0 +1,147 @@
+//! Where the time of a fetch went
+
+use std::fmt;
+use std::time::Duration;
+
+/// How the forecast was requested
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FetchStrategy {
+    /// Every section in one request
+    #[default]
+    Single,
+    /// Current and hourly data for the first days, and the daily series for
+    /// the whole horizon, as two concurrent requests
+    Split,
+}
+
+impl fmt::Display for FetchStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FetchStrategy::Single => "single request",
+            FetchStrategy::Split => "split request",
+        })
+    }
+}
+
+/// Wall-clock time of each request in one fetch. The forecast and the
+/// secondary requests run side by side, so `total` is close to geocoding
+/// plus the slowest of them rather than the sum of all.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FetchTimings {
+    /// `None` when the location was given as coordinates
+    pub geocoding: Option<Duration>,
+    /// Both requests of a split forecast, which run side by side
+    pub forecast: Duration,
+    /// `None` unless waves were requested
+    pub marine: Option<Duration>,
+    /// `None` unless forecast confidence was requested
+    pub confidence: Option<Duration>,
+    /// `None` unless air quality was requested
+    pub air_quality: Option<Duration>,
+    pub total: Duration,
+    pub strategy: FetchStrategy,
+    /// Size of the forecast response bodies; 0 when not measured
+    pub forecast_bytes: usize,
+}
+
+impl FetchTimings {
+    pub fn new(
+        geocoding: Option<Duration>,
+        forecast: Duration,
+        marine: Option<Duration>,
+        total: Duration,
+    ) -> Self {
+        FetchTimings {
+            geocoding,
+            forecast,
+            marine,
+            confidence: None,
+            air_quality: None,
+            total,
+            strategy: FetchStrategy::Single,
+            forecast_bytes: 0,
+        }
+    }
+
+    /// Records the time of the ensemble request, made alongside the forecast
+    pub fn with_confidence(mut self, confidence: Option<Duration>) -> Self {
+        self.confidence = confidence;
+        self
+    }
+
+    /// Records the time of the air quality request, made alongside the forecast
+    pub fn with_air_quality(mut self, air_quality: Option<Duration>) -> Self {
+        self.air_quality = air_quality;
+        self
+    }
+
+    /// Records how the forecast was requested and how large its response was
+    pub fn with_forecast_payload(mut self, strategy: FetchStrategy, bytes: usize) -> Self {
+        self.strategy = strategy;
+        self.forecast_bytes = bytes;
+        self
+    }
+}
+
+/// "geocoding 312 ms, forecast 298 ms, marine 305 ms, total 611 ms", then
+/// the forecast payload when measured: "; forecast 41.2 KB, split request"
+impl fmt::Display for FetchTimings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts = [
+            ("geocoding", self.geocoding),
+            ("forecast", Some(self.forecast)),
+            ("marine", self.marine),
+            ("confidence", self.confidence),
+            ("air quality", self.air_quality),
+            ("total", Some(self.total)),
+        ];
+        let text: Vec<String> = parts
+            .iter()
+            .filter_map(|(label, time)| time.map(|t| format!("{label} {} ms", t.as_millis())))
+            .collect();
+        f.write_str(&text.join(", "))?;
+        if self.forecast_bytes > 0 {
+            write!(
+                f,
+                "; forecast {:.1} KB, {}",
+                self.forecast_bytes as f64 / 1024.0,
+                self.strategy
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_skips_requests_not_made() {
+        let ms = Duration::from_millis;
+        let timings = FetchTimings::new(Some(ms(312)), ms(298), Some(ms(305)), ms(611));
+        assert_eq!(
+            timings.to_string(),
+            "geocoding 312 ms, forecast 298 ms, marine 305 ms, total 611 ms"
+        );
+        assert_eq!(
+            timings.with_confidence(Some(ms(280))).to_string(),
+            "geocoding 312 ms, forecast 298 ms, marine 305 ms, confidence 280 ms, total 611 ms"
+        );
+        assert_eq!(
+            FetchTimings::new(None, ms(298), None, ms(301))
+                .with_air_quality(Some(ms(150)))
+                .to_string(),
+            "forecast 298 ms, air quality 150 ms, total 301 ms"
+        );
+        let coordinates = FetchTimings::new(None, ms(298), None, ms(299));
+        assert_eq!(coordinates.to_string(), "forecast 298 ms, total 299 ms");
+        assert_eq!(
+            coordinates
+                .with_forecast_payload(FetchStrategy::Split, 42_188)
+                .to_string(),
+            "forecast 298 ms, total 299 ms; forecast 41.2 KB, split request"
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! Local usage statistics: one record per weather lookup, aggregated when read
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, FixedOffset, Local, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::location::normalize_location_key;
+
+/// One weather lookup
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct UsageRecord {
+    /// RFC 3339 in the user's local offset at the time, e.g. "2024-06-01T08:15:00+02:00"
+    pub time: String,
+    /// Location as typed
+    pub location: String,
+    /// Answered at once from the in-memory cache
+    pub cache_hit: bool,
+    /// Time until the result arrived
+    pub latency_ms: u64,
+    pub ok: bool,
+}
+
+impl UsageRecord {
+    /// Record of a lookup that finished now
+    pub fn now(location: &str, cache_hit: bool, latency: Duration, ok: bool) -> Self {
+        UsageRecord {
+            time: Local::now().fixed_offset().to_rfc3339(),
+            location: location.trim().to_string(),
+            cache_hit,
+            latency_ms: latency.as_millis().try_into().unwrap_or(u64::MAX),
+            ok,
+        }
+    }
+
+    fn parsed_time(&self) -> Option<DateTime<FixedOffset>> {
+        DateTime::parse_from_rfc3339(&self.time).ok()
+    }
+}
+
+/// How often a location was looked up
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LocationCount {
+    /// Most recent spelling of the location
+    pub location: String,
+    pub fetches: usize,
+}
+
+/// Totals over every record
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct UsageSummary {
+    pub fetches: usize,
+    pub failures: usize,
+    pub cache_hits: usize,
+    /// Lookups by local day of week, Monday first
+    pub per_weekday: [usize; 7],
+    /// Most-checked first; ties in order of first use
+    pub per_location: Vec<LocationCount>,
+    /// Mean time of lookups that went to the network
+    pub average_latency: Option<Duration>,
+}
+
+impl UsageSummary {
+    pub fn from_records(records: &[UsageRecord]) -> Self {
+        let mut summary = UsageSummary::default();
+        let mut index: HashMap<String, usize> = HashMap::new();
+        let mut network_ms = Vec::new();
+
+        for record in records {
+            summary.fetches += 1;
+            summary.failures += usize::from(!record.ok);
+            if record.cache_hit {
+                summary.cache_hits += 1;
+            } else {
+                network_ms.push(record.latency_ms);
+            }
+            if let Some(time) = record.parsed_time() {
+                summary.per_weekday[time.weekday().num_days_from_monday() as usize] += 1;
+            }
+
+            let key = normalize_location_key(&record.location);
+            let slot = *index.entry(key).or_insert_with(|| {
+                summary.per_location.push(LocationCount {
+                    location: String::new(),
+                    fetches: 0,
+                });
+                summary.per_location.len() - 1
+            });
+            let entry = &mut summary.per_location[slot];
+            entry.location.clone_from(&record.location);
+            entry.fetches += 1;
+        }
+
+        // Stable, so equal counts keep their first-use order
+        summary
+            .per_location
+            .sort_by_key(|entry| std::cmp::Reverse(entry.fetches));
+        summary.average_latency = (!network_ms.is_empty()).then(|| {
+            Duration::from_millis(network_ms.iter().sum::<u64>() / network_ms.len() as u64)
+        });
+        summary
+    }
+
+    /// Share of lookups answered from the cache (0–1), `None` without lookups
+    pub fn cache_hit_rate(&self) -> Option<f64> {
+        (self.fetches > 0).then(|| self.cache_hits as f64 / self.fetches as f64)
+    }
+
+    /// Lookups on `weekday`
+    pub fn on(&self, weekday: Weekday) -> usize {
+        self.per_weekday[weekday.num_days_from_monday() as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(time: &str, location: &str, cache_hit: bool, latency_ms: u64) -> UsageRecord {
+        UsageRecord {
+            time: time.to_string(),
+            location: location.to_string(),
+            cache_hit,
+            latency_ms,
+            ok: latency_ms > 0,
+        }
+    }
+
+    #[test]
+    fn test_summary_of_synthetic_records() {
+        let records = [
+            // 2024-06-03 is a Monday
+            record("2024-06-03T08:00:00+02:00", "seattle", false, 800),
+            record("2024-06-03T08:01:00+02:00", "Zermatt", false, 400),
+            record("2024-06-03T08:02:00+02:00", " Seattle ", true, 1),
+            record("2024-06-04T23:30:00-07:00", "Seattle", false, 0),
+            record("not a time", "Zermatt", true, 3),
+            record("2024-06-09T09:00:00+00:00", "Oslo", false, 600),
+        ];
+        let summary = UsageSummary::from_records(&records);
+
+        assert_eq!(summary.fetches, 6);
+        assert_eq!(summary.failures, 1);
+        assert_eq!(summary.cache_hit_rate(), Some(2.0 / 6.0));
+        // (800 + 400 + 0 + 600) / 4 network lookups
+        assert_eq!(summary.average_latency, Some(Duration::from_millis(450)));
+        assert_eq!(summary.on(Weekday::Mon), 3);
+        // The record's own offset decides the day
+        assert_eq!(summary.on(Weekday::Tue), 1);
+        assert_eq!(summary.on(Weekday::Sun), 1);
+        assert_eq!(summary.per_weekday.iter().sum::<usize>(), 5);
+
+        assert_eq!(
+            summary.per_location,
+            vec![
+                LocationCount {
+                    location: "Seattle".to_string(),
+                    fetches: 3
+                },
+                LocationCount {
+                    location: "Zermatt".to_string(),
+                    fetches: 2
+                },
+                LocationCount {
+                    location: "Oslo".to_string(),
+                    fetches: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_empty_summary() {
+        let summary = UsageSummary::from_records(&[]);
+        assert_eq!(summary.cache_hit_rate(), None);
+        assert_eq!(summary.average_latency, None);
+        assert!(summary.per_location.is_empty());
+    }
+}
@@ -2,41 +2,78 @@
 //!
 //! All measurements stored in metric units (scientific standard):
 //! - Temperature: Celsius
-//! - Distance: millimeters/meters
+//! - Distance: millimeters/meters (snowfall in centimeters, as reported by the API)
 //! - Speed: km/h
 //! - Pressure: hPa
 
-use serde::Deserialize;
+use std::ops::Range;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{FORECAST_EXPIRY_GRACE_HOURS, GUST_MARGIN_KMH, WET_HOUR_PROBABILITY};
+use crate::models::air_quality::{AirQuality, AirQualityReading};
+use crate::models::forecast_model::ForecastModel;
+use crate::models::hourly_stats::HourlyStats;
+use crate::models::marine::{MarineForecast, MarineHour};
+use crate::models::summary::WeatherSummary;
+use crate::models::timings::FetchTimings;
+use crate::models::wind_rose::WindRose;
+use crate::utils::clock::LocationClock;
+use crate::utils::confidence::Confidence;
+use crate::utils::conversions::PressureReference;
+use crate::utils::coords::{self, Coordinates};
+use crate::utils::daylight::{self, Daylight, HourLight};
+use crate::utils::fog::{self, LowVisibilityWindow};
+use crate::utils::freshness::{self, Freshness};
+use crate::utils::location::CoordinateKey;
+use crate::utils::time::{self, ClockChange};
 
 /// Current weather conditions
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct CurrentWeather {
+    // Observation time (ISO 8601 format)
+    pub time: Option<String>,
     // Temperature in Celsius
     pub temperature: Option<f64>,
     // Feels-like temperature in Celsius
     pub apparent_temperature: Option<f64>,
     // Relative humidity percentage (0-100)
     pub humidity: Option<f64>,
+    // Dew point in Celsius
+    pub dew_point: Option<f64>,
     // Precipitation amount in mm
     pub precipitation: Option<f64>,
+    // Snowfall amount in cm
+    pub snowfall: Option<f64>,
     // Weather condition code (WMO code)
     pub weather_code: Option<i32>,
     // Wind speed in km/h
     pub wind_speed: Option<f64>,
     // Wind direction in degrees (0-360)
     pub wind_direction: Option<f64>,
+    // Wind gusts in km/h
+    pub wind_gusts: Option<f64>,
     // Cloud cover percentage (0-100)
     pub cloud_cover: Option<f64>,
-    // Atmospheric pressure in hPa
+    // Surface (station) pressure in hPa
     pub pressure: Option<f64>,
+    // Pressure reduced to mean sea level in hPa
+    pub pressure_msl: Option<f64>,
     // Visibility in meters
     pub visibility: Option<f64>,
+    // Snow depth on the ground in meters
+    pub snow_depth: Option<f64>,
+    // Whether it is currently daytime at the location
+    pub is_day: Option<bool>,
 }
 
 /// Hourly forecast data point
 /// Note: Some fields fetched from API but not yet displayed in view
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 #[allow(dead_code)]
+#[non_exhaustive]
 pub struct HourlyForecast {
     // Time of forecast (ISO 8601 format)
     pub time: String,
@@ -52,12 +89,111 @@ pub struct HourlyForecast {
     pub weather_code: Option<i32>,
     // Wind speed in km/h
     pub wind_speed: Option<f64>,
+    // Wind direction in degrees
+    pub wind_direction: Option<f64>,
+    // Wind gusts in km/h
+    pub wind_gusts: Option<f64>,
+    // Wind speed 80 m above ground in km/h
+    pub wind_speed_80m: Option<f64>,
+    // Wind direction 80 m above ground in degrees
+    pub wind_direction_80m: Option<f64>,
+    // Wind speed 120 m above ground in km/h
+    pub wind_speed_120m: Option<f64>,
+    // Wind direction 120 m above ground in degrees
+    pub wind_direction_120m: Option<f64>,
     // Humidity percentage
     pub humidity: Option<f64>,
+    // Dew point in Celsius
+    pub dew_point: Option<f64>,
+    // Height of the 0°C isotherm above sea level in meters
+    pub freezing_level_height: Option<f64>,
+    // Convective available potential energy in J/kg
+    pub cape: Option<f64>,
+    // Snowfall amount in cm
+    pub snowfall: Option<f64>,
+    // Snow depth on the ground in meters
+    pub snow_depth: Option<f64>,
+    // Cloud cover percentage (0-100)
+    pub cloud_cover: Option<f64>,
+    // Visibility in meters
+    pub visibility: Option<f64>,
+    // UV index, 0 at night; 11 and above is extreme
+    pub uv_index: Option<f64>,
+    // Jump of the local clock since the previous entry (daylight-saving changes)
+    #[serde(skip)]
+    pub clock_change: Option<ClockChange>,
+    // Whether the hour ended before the current observation, e.g. one of
+    // the `past_days` asked for ahead of the forecast
+    #[serde(default)]
+    pub is_past: bool,
+}
+
+impl CurrentWeather {
+    /// Pressure in hPa as `preferred`, or the other one when the server only
+    /// sent that, labelled with what it is
+    pub fn pressure_reading(
+        &self,
+        preferred: PressureReference,
+    ) -> Option<(PressureReference, f64)> {
+        let reading = |reference| {
+            match reference {
+                PressureReference::SeaLevel => self.pressure_msl,
+                PressureReference::Surface => self.pressure,
+            }
+            .map(|hpa| (reference, hpa))
+        };
+        let other = match preferred {
+            PressureReference::SeaLevel => PressureReference::Surface,
+            PressureReference::Surface => PressureReference::SeaLevel,
+        };
+        reading(preferred).or_else(|| reading(other))
+    }
+
+    /// Gusts worth mentioning next to the sustained wind
+    pub fn notable_gusts(&self) -> Option<f64> {
+        notable_gusts(self.wind_speed, self.wind_gusts)
+    }
+}
+
+impl HourlyForecast {
+    /// Gusts worth mentioning next to the sustained wind
+    pub fn notable_gusts(&self) -> Option<f64> {
+        notable_gusts(self.wind_speed, self.wind_gusts)
+    }
+}
+
+/// Gusts at least `GUST_MARGIN_KMH` above the sustained `speed` (km/h);
+/// closer to it they say nothing the speed doesn't
+fn notable_gusts(speed: Option<f64>, gusts: Option<f64>) -> Option<f64> {
+    gusts.filter(|gusts| *gusts >= speed.unwrap_or(0.0) + GUST_MARGIN_KMH)
+}
+
+/// 15-minute forecast data point, requested for the next few hours only
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct MinutelyForecast {
+    // Time of forecast (ISO 8601 format)
+    pub time: String,
+    // Temperature in Celsius
+    pub temperature: Option<f64>,
+    // Precipitation over the 15 minutes up to `time` in mm
+    pub precipitation: Option<f64>,
+    // Weather condition code
+    pub weather_code: Option<i32>,
+}
+
+impl MinutelyForecast {
+    pub fn new(time: impl Into<String>) -> Self {
+        MinutelyForecast {
+            time: time.into(),
+            ..Self::default()
+        }
+    }
 }
 
 /// Daily forecast data point
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct DailyForecast {
     // Date of forecast (ISO 8601 format)
     pub date: String,
@@ -69,37 +205,506 @@ pub struct DailyForecast {
     pub weather_code: Option<i32>,
     // Total precipitation in mm
     pub precipitation_sum: Option<f64>,
-    // Precipitation probability (0-100)
+    // Total snowfall in cm
+    pub snowfall_sum: Option<f64>,
+    // Highest hourly precipitation probability of the day (0-100), not the
+    // chance of rain at all during the day
     pub precipitation_probability: Option<f64>,
+    // Mean hourly precipitation probability (0-100), derived from hourly data
+    pub precipitation_probability_mean: Option<f64>,
+    // Hours with a precipitation probability above WET_HOUR_PROBABILITY,
+    // derived from hourly data
+    pub wet_hours: Option<u32>,
     // Maximum wind speed in km/h
     pub wind_speed_max: Option<f64>,
+    // Maximum wind gusts in km/h
+    pub wind_gusts_max: Option<f64>,
     // Sunrise time (ISO 8601 format)
     pub sunrise: Option<String>,
     // Sunset time (ISO 8601 format)
     pub sunset: Option<String>,
+    // Time between sunrise and sunset in seconds
+    pub daylight_duration: Option<f64>,
+    // Time with direct sunshine (above 120 W/m²) in seconds
+    pub sunshine_duration: Option<f64>,
+    // Reference evapotranspiration (FAO-56 ET₀) in mm
+    pub et0_evapotranspiration: Option<f64>,
+    // Mean relative humidity percentage
+    pub humidity_mean: Option<f64>,
+    // Maximum UV index
+    pub uv_index_max: Option<f64>,
+    // Agreement of the ensemble on the day's temperatures, when requested
+    pub confidence: Option<Confidence>,
+    // Whether the date is before that of the current observation
+    #[serde(default)]
+    pub is_past: bool,
+}
+
+impl DailyForecast {
+    /// Peak gusts worth mentioning next to the day's maximum wind
+    pub fn notable_gusts(&self) -> Option<f64> {
+        notable_gusts(self.wind_speed_max, self.wind_gusts_max)
+    }
+
+    /// "80% peak · wet hours ~3", or just "80%" without hourly data
+    pub fn precipitation_chance(&self) -> Option<String> {
+        let peak = self.precipitation_probability?;
+        Some(match self.wet_hours {
+            Some(wet_hours) => format!("{peak:.0}% peak · wet hours ~{wet_hours}"),
+            None => format!("{peak:.0}%"),
+        })
+    }
+
+    /// Polar day or night at `latitude` when the forecast has no sunrise or
+    /// sunset for this date
+    pub fn daylight(&self, latitude: f64) -> Daylight {
+        match time::parse_date(&self.date) {
+            Some(date) => daylight::daylight(
+                self.sunrise.as_deref(),
+                self.sunset.as_deref(),
+                date,
+                latitude,
+            ),
+            None => Daylight::RisesAndSets,
+        }
+    }
+}
+
+/// Sum of an hourly quantity over a time window, with how much of the window had data
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct AccumulationTotal {
+    /// Sum over the hours that reported a value
+    pub total: f64,
+    /// Hours in the window that reported a value
+    pub hours_with_data: usize,
+    /// Hours the window spans
+    pub window_hours: usize,
+}
+
+impl AccumulationTotal {
+    /// Whether every hour of the window reported a value
+    pub fn is_complete(&self) -> bool {
+        self.hours_with_data >= self.window_hours
+    }
 }
 
 /// Complete weather data for a location
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct WeatherData {
     // Current weather conditions
     pub current: CurrentWeather,
     // Hourly forecasts (typically 24-48 hours)
     pub hourly: Vec<HourlyForecast>,
-    // Daily forecasts (typically 7-14 days)
+    // Daily forecasts (typically 7-14 days), recent days first when asked for
     pub daily: Vec<DailyForecast>,
+    // 15-minute forecasts for the next hours, when asked for
+    #[serde(default)]
+    pub minutely: Vec<MinutelyForecast>,
+}
+
+impl WeatherData {
+    pub fn new(
+        current: CurrentWeather,
+        hourly: Vec<HourlyForecast>,
+        daily: Vec<DailyForecast>,
+    ) -> Self {
+        let mut data = WeatherData {
+            current,
+            hourly,
+            daily,
+            minutely: Vec::new(),
+        };
+        data.mark_clock_changes();
+        data.mark_past();
+        data
+    }
+
+    /// Flags the hours that ended and the dates that are over at the current
+    /// observation time; nothing is past without one
+    pub(crate) fn mark_past(&mut self) {
+        let observed = self.current.time.as_deref().and_then(time::parse_hour);
+        for hour in &mut self.hourly {
+            hour.is_past = observed
+                .zip(time::parse_hour(&hour.time))
+                .is_some_and(|(observed, start)| start + TimeDelta::hours(1) <= observed);
+        }
+        for day in &mut self.daily {
+            day.is_past = observed
+                .zip(time::parse_date(&day.date))
+                .is_some_and(|(observed, date)| date < observed.date());
+        }
+    }
+
+    /// Hourly entries from midnight of the observation date on, leaving out
+    /// those of earlier days fetched with `past_days`
+    pub fn hourly_from_today(&self) -> &[HourlyForecast] {
+        let Some(today) = self.current.time.as_deref().and_then(time::parse_hour) else {
+            return &self.hourly;
+        };
+        let start = self.hourly.partition_point(|h| {
+            h.is_past && time::parse_hour(&h.time).is_some_and(|t| t.date() < today.date())
+        });
+        &self.hourly[start..]
+    }
+
+    /// Daily entries from the observation date on
+    pub fn daily_from_today(&self) -> &[DailyForecast] {
+        &self.daily[self.daily.partition_point(|d| d.is_past)..]
+    }
+
+    /// Annotates each hourly entry that follows a skipped or repeated local hour
+    pub(crate) fn mark_clock_changes(&mut self) {
+        let mut previous = None;
+        for hour in &mut self.hourly {
+            let start = time::parse_hour(&hour.time);
+            hour.clock_change = previous
+                .zip(start)
+                .and_then(|(previous, start)| ClockChange::between(previous, start));
+            previous = start;
+        }
+    }
+
+    /// Hourly entries that follow a clock change
+    pub fn clock_changes(&self) -> impl Iterator<Item = &HourlyForecast> {
+        self.hourly.iter().filter(|h| h.clock_change.is_some())
+    }
+
+    /// Index of the hourly entry covering the current observation time (0 if unknown)
+    pub fn current_hour_index(&self) -> usize {
+        let hour_prefix = self
+            .current
+            .time
+            .as_deref()
+            .and_then(|t| t.get(..13))
+            .unwrap_or_default();
+
+        if hour_prefix.is_empty() {
+            return 0;
+        }
+
+        self.hourly
+            .iter()
+            .position(|h| h.time.starts_with(hour_prefix))
+            .unwrap_or(0)
+    }
+
+    /// Up to `steps` 15-minute entries after the current observation; from
+    /// the first entry when the observation has no time. A step's values
+    /// cover the quarter hour up to its time, so the step at or before the
+    /// observation is already past.
+    pub fn upcoming_minutely(&self, steps: usize) -> &[MinutelyForecast] {
+        // ISO timestamps order like the times they name
+        let start = self.current.time.as_deref().map_or(0, |observed| {
+            self.minutely
+                .iter()
+                .position(|step| step.time.as_str() > observed)
+                .unwrap_or(self.minutely.len())
+        });
+        let end = start.saturating_add(steps).min(self.minutely.len());
+        &self.minutely[start..end]
+    }
+
+    /// Hourly entry covering the current observation time, falling back to the first hour
+    pub fn current_hour(&self) -> Option<&HourlyForecast> {
+        self.hourly.get(self.current_hour_index())
+    }
+
+    /// Hourly entry after the current one, or the current one at the end of the series
+    pub fn next_hour(&self) -> Option<&HourlyForecast> {
+        self.hourly
+            .get(self.current_hour_index() + 1)
+            .or_else(|| self.current_hour())
+    }
+
+    /// Daily entry for the date of the current observation, falling back to
+    /// the first day. Prefer `WeatherInfo::today` and `day` when the present
+    /// moment matters rather than the observation.
+    pub fn today(&self) -> Option<&DailyForecast> {
+        self.current
+            .time
+            .as_deref()
+            .and_then(time::parse_hour)
+            .and_then(|observed| self.day(observed.date()))
+            .or_else(|| self.daily.first())
+    }
+
+    /// Hourly entries starting in `start..end` (local time at the location).
+    /// The range is half-open, so adjacent windows share no hour and miss
+    /// none; entries with an unreadable time belong to no window.
+    pub fn hourly_between(
+        &self,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> impl Iterator<Item = &HourlyForecast> {
+        self.hourly
+            .iter()
+            .filter(move |h| time::parse_hour(&h.time).is_some_and(|t| start <= t && t < end))
+    }
+
+    /// Hourly entries of a local date (23 or 25 of them when the clocks change)
+    pub fn hourly_on(&self, date: NaiveDate) -> impl Iterator<Item = &HourlyForecast> {
+        let start = date.and_time(NaiveTime::MIN);
+        self.hourly_between(start, start + TimeDelta::days(1))
+    }
+
+    /// Hourly entries from the one containing `now` (local time at the
+    /// location) to the end of the series; hours that have ended are skipped
+    pub fn hourly_from(&self, now: NaiveDateTime) -> impl Iterator<Item = &HourlyForecast> {
+        self.hourly[first_upcoming_hour(&self.hourly, now)..].iter()
+    }
+
+    /// `hourly_from` the present moment at the location
+    pub fn hourly_from_now(&self, clock: &LocationClock) -> impl Iterator<Item = &HourlyForecast> {
+        self.hourly_from(clock.now())
+    }
+
+    /// Daily entries for dates in `start..end`, half-open like `hourly_between`
+    pub fn daily_between(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> impl Iterator<Item = &DailyForecast> {
+        self.daily.iter().filter(move |d| {
+            time::parse_date(&d.date).is_some_and(|date| start <= date && date < end)
+        })
+    }
+
+    /// Upcoming windows where visibility drops below `threshold_m` or fog is forecast
+    /// (see `constants::FOG_VISIBILITY_THRESHOLD_M` for the usual threshold)
+    pub fn low_visibility_windows(&self, threshold_m: f64) -> Vec<LowVisibilityWindow> {
+        fog::low_visibility_windows(self.upcoming_hours(self.hourly.len()), threshold_m)
+    }
+
+    /// Start of the hourly entry covering the current observation time
+    pub fn current_hour_start(&self) -> Option<NaiveDateTime> {
+        self.current_hour().and_then(|h| time::parse_hour(&h.time))
+    }
+
+    /// Precipitation (mm) in the hours starting within `window` from `from`.
+    /// `None` when no hour in the window reports a value.
+    pub fn precipitation_total(
+        &self,
+        window: TimeDelta,
+        from: NaiveDateTime,
+    ) -> Option<AccumulationTotal> {
+        self.accumulate(window, from, |h| h.precipitation)
+    }
+
+    /// Snowfall (cm) in the hours starting within `window` from `from`.
+    /// `None` when no hour in the window reports a value.
+    pub fn snow_total(&self, window: TimeDelta, from: NaiveDateTime) -> Option<AccumulationTotal> {
+        self.accumulate(window, from, |h| h.snowfall)
+    }
+
+    fn accumulate(
+        &self,
+        window: TimeDelta,
+        from: NaiveDateTime,
+        value: impl Fn(&HourlyForecast) -> Option<f64>,
+    ) -> Option<AccumulationTotal> {
+        let values: Vec<f64> = self
+            .hours_within(window, from)
+            .into_iter()
+            .filter_map(value)
+            .collect();
+        if values.is_empty() {
+            return None;
+        }
+        let total: f64 = values.iter().sum();
+        debug_assert!(
+            total.is_finite(),
+            "non-finite hourly values reached accumulate"
+        );
+
+        Some(AccumulationTotal {
+            total,
+            hours_with_data: values.len(),
+            window_hours: window.num_hours().max(0) as usize,
+        })
+    }
+
+    /// Hours starting within `window` of real time from `from`, which counts
+    /// a skipped or repeated local hour as the single hour it lasted
+    pub(crate) fn hours_within(
+        &self,
+        window: TimeDelta,
+        from: NaiveDateTime,
+    ) -> Vec<&HourlyForecast> {
+        let Some(start) = self
+            .hourly
+            .iter()
+            .position(|h| time::parse_hour(&h.time).is_some_and(|t| t >= from))
+        else {
+            return Vec::new();
+        };
+        let hours = &self.hourly[start..];
+        let lead = time::parse_hour(&hours[0].time).map_or(TimeDelta::zero(), |t| t - from);
+        time::elapsed(hours.iter().map(|h| h.time.as_str()))
+            .into_iter()
+            .zip(hours)
+            .take_while(|(offset, _)| lead + *offset < window)
+            .map(|(_, hour)| hour)
+            .collect()
+    }
+
+    /// Values of an hourly quantity over a local date (23 or 25 of them when
+    /// the clocks change); `None` unless the series covers the whole day and
+    /// every hour reports the quantity
+    fn whole_day_values(
+        &self,
+        date: NaiveDate,
+        value: fn(&HourlyForecast) -> Option<f64>,
+    ) -> Option<Vec<f64>> {
+        let hours: Vec<&HourlyForecast> = self.hourly_on(date).collect();
+        let (first, last) = (hours.first()?, hours.last()?);
+        let whole_day = first.time.ends_with("T00:00")
+            && last.time.ends_with("T23:00")
+            && time::elapsed(hours.iter().map(|h| h.time.as_str()))
+                .windows(2)
+                .all(|pair| pair[1] - pair[0] == TimeDelta::hours(1));
+        let values: Vec<f64> = hours.iter().filter_map(|h| value(h)).collect();
+        (whole_day && values.len() == hours.len()).then_some(values)
+    }
+
+    /// Mean relative humidity (%) over the hourly values of a local date;
+    /// `None` unless every hour of the day reports humidity
+    pub fn humidity_mean(&self, date: NaiveDate) -> Option<f64> {
+        let values = self.whole_day_values(date, |h| h.humidity)?;
+        Some(values.iter().sum::<f64>() / values.len() as f64)
+    }
+
+    /// Mean hourly precipitation probability (%) of a local date and the
+    /// number of hours above `WET_HOUR_PROBABILITY`; `None` unless every hour
+    /// of the day reports a probability
+    pub fn precipitation_chances(&self, date: NaiveDate) -> Option<(f64, u32)> {
+        let values = self.whole_day_values(date, |h| h.precipitation_probability)?;
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let wet_hours = values.iter().filter(|&&p| p > WET_HOUR_PROBABILITY).count() as u32;
+        Some((mean, wet_hours))
+    }
+
+    /// Fills missing daily humidity means from the hourly series, for servers
+    /// that do not provide `relative_humidity_2m_mean`
+    pub(crate) fn fill_humidity_means(&mut self) {
+        let means: Vec<Option<f64>> = self
+            .daily
+            .iter()
+            .map(|day| {
+                day.humidity_mean.or_else(|| {
+                    time::parse_date(&day.date).and_then(|date| self.humidity_mean(date))
+                })
+            })
+            .collect();
+        for (day, mean) in self.daily.iter_mut().zip(means) {
+            day.humidity_mean = mean;
+        }
+    }
+
+    /// Derives each day's mean precipitation probability and wet hours from
+    /// the hourly series, where it covers the whole day
+    pub(crate) fn fill_precipitation_chances(&mut self) {
+        let chances: Vec<Option<(f64, u32)>> = self
+            .daily
+            .iter()
+            .map(|day| {
+                time::parse_date(&day.date).and_then(|date| self.precipitation_chances(date))
+            })
+            .collect();
+        for (day, chance) in self.daily.iter_mut().zip(chances) {
+            day.precipitation_probability_mean = chance.map(|(mean, _)| mean);
+            day.wet_hours = chance.map(|(_, wet_hours)| wet_hours);
+        }
+    }
+
+    /// Daily entry for a local date
+    pub fn day(&self, date: NaiveDate) -> Option<&DailyForecast> {
+        self.daily
+            .iter()
+            .find(|d| time::parse_date(&d.date) == Some(date))
+    }
+
+    /// Minimum, maximum, mean and totals over the hourly entries in `range`
+    /// (indices into `hourly`, clamped to its length)
+    pub fn hourly_stats(&self, range: Range<usize>) -> HourlyStats {
+        let end = range.end.min(self.hourly.len());
+        HourlyStats::from_hours(&self.hourly[range.start.min(end)..end])
+    }
+
+    /// Up to `hours` hourly entries starting at the current hour
+    pub fn upcoming_hours(&self, hours: usize) -> &[HourlyForecast] {
+        let start = self.current_hour_index().min(self.hourly.len());
+        let end = start.saturating_add(hours).min(self.hourly.len());
+        &self.hourly[start..end]
+    }
+
+    /// Wind distribution in `bins` direction sectors over the remaining
+    /// forecast, from the current hour on
+    pub fn wind_rose(&self, bins: usize) -> WindRose {
+        WindRose::from_hours(self.upcoming_hours(usize::MAX), bins)
+    }
 }
 
-/// Complete weather information including location and data
-#[derive(Clone, Debug)]
+/// Index of the first entry of `hourly` that has not ended by `now`, or the
+/// length of the series when every hour has
+pub(crate) fn first_upcoming_hour(hourly: &[HourlyForecast], now: NaiveDateTime) -> usize {
+    hourly
+        .iter()
+        .position(|h| time::parse_hour(&h.time).is_some_and(|t| t + TimeDelta::hours(1) > now))
+        .unwrap_or(hourly.len())
+}
+
+/// Complete weather information including location and data. Serializes
+/// for the warm-start snapshot (see `SnapshotStore`); fetch timings are
+/// left out since they describe a past session.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct WeatherInfo {
-    // Location name
+    // Location name; for a fetch, the place the geocoder resolved the query to
     pub location: String,
+    // What was asked for, e.g. "nyc" for "New York, United States" (None for hand-built data)
+    pub query: Option<String>,
     // Coordinates
     pub latitude: f64,
     pub longitude: f64,
+    // Elevation of the forecast grid point in meters
+    pub elevation: Option<f64>,
     // Weather data for this location
     pub weather_data: WeatherData,
+    // Whether the server only provided reduced (legacy) current conditions
+    pub reduced_data: bool,
+    // When the response was received (None for hand-built data)
+    pub fetched_at: Option<DateTime<Utc>>,
+    // Time the API spent generating the response, in milliseconds
+    pub generation_time_ms: Option<f64>,
+    // Offset of the location's time zone from UTC; forecast times are local to it
+    pub utc_offset_seconds: Option<i32>,
+    // IANA name of the location's time zone, e.g. "Asia/Tokyo"
+    pub timezone: Option<String>,
+    // Its abbreviation, e.g. "JST", or "GMT+9" where there is no common one
+    pub timezone_abbreviation: Option<String>,
+    // Waves, when requested and the location has marine data
+    pub marine: Option<MarineForecast>,
+    // Pollutants and AQI, when requested and the air quality API answered
+    pub air_quality: Option<AirQuality>,
+    // Weather model the forecast was requested from
+    #[serde(default)]
+    pub model: ForecastModel,
+    // Service that supplied the forecast when several were tried in turn
+    // (see `FallbackRepository`), e.g. "Met.no"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    // How long each request of the fetch took (None for hand-built data)
+    #[serde(skip)]
+    pub timings: Option<FetchTimings>,
+    // Server time minus system time when the system clock is off (see `ServerClock`)
+    #[serde(skip)]
+    pub clock_skew: Option<TimeDelta>,
+    // Served from disk after the network request failed (see `PersistentWeatherRepository`);
+    // `fetched_at` then tells how old it is
+    #[serde(skip)]
+    pub offline: bool,
 }
 
 impl WeatherInfo {
@@ -107,9 +712,571 @@ impl WeatherInfo {
     pub fn new(location: String, latitude: f64, longitude: f64, weather_data: WeatherData) -> Self {
         WeatherInfo {
             location,
+            query: None,
             latitude,
             longitude,
+            elevation: None,
             weather_data,
+            reduced_data: false,
+            fetched_at: None,
+            generation_time_ms: None,
+            utc_offset_seconds: None,
+            timezone: None,
+            timezone_abbreviation: None,
+            marine: None,
+            air_quality: None,
+            model: ForecastModel::BestMatch,
+            source: None,
+            timings: None,
+            clock_skew: None,
+            offline: false,
+        }
+    }
+
+    /// "(ECMWF)" for a forecast pinned to one model, `None` for Open-Meteo's
+    /// own choice
+    pub fn model_tag(&self) -> Option<String> {
+        self.model
+            .is_pinned()
+            .then(|| format!("({})", self.model.label()))
+    }
+
+    /// "Forecast by Met.no" when the service that supplied it was recorded
+    pub fn attribution(&self) -> Option<String> {
+        self.source
+            .as_ref()
+            .map(|source| format!("Forecast by {source}"))
+    }
+
+    /// Air quality at the current observation time, when fetched
+    pub fn current_air_quality(&self) -> Option<&AirQualityReading> {
+        self.air_quality
+            .as_ref()?
+            .at(self.weather_data.current.time.as_deref())
+    }
+
+    /// Up to `hours` marine entries from the current hour; empty without marine data
+    pub fn upcoming_marine(&self, hours: usize) -> &[MarineHour] {
+        self.marine.as_ref().map_or(&[], |marine| {
+            marine.upcoming(self.weather_data.current.time.as_deref(), hours)
+        })
+    }
+
+    /// Approximate model run the forecast belongs to: the earliest hourly timestamp
+    /// of today (location time), past days aside. Open-Meteo does not report the run itself.
+    pub fn reference_time(&self) -> Option<&str> {
+        self.weather_data
+            .hourly_from_today()
+            .iter()
+            .map(|h| h.time.as_str())
+            .min()
+    }
+
+    /// Location with its time zone abbreviation, "Tokyo, Japan (JST)", so
+    /// the times below it read as local
+    pub fn location_label(&self) -> String {
+        match &self.timezone_abbreviation {
+            Some(abbreviation) if !abbreviation.trim().is_empty() => {
+                format!("{} ({})", self.location, abbreviation.trim())
+            }
+            _ => self.location.clone(),
+        }
+    }
+
+    /// Wall clock at the location as of `now` (`None` when the response had
+    /// no UTC offset), corrected by the server time when the system clock is off
+    pub fn clock(&self, now: DateTime<Utc>) -> Option<LocationClock> {
+        self.utc_offset_seconds.map(|offset| {
+            let clock = LocationClock::new(offset, now);
+            self.clock_skew.map_or(clock, |skew| clock.corrected(skew))
+        })
+    }
+
+    /// Day, night or a sunrise or sunset hour for an hourly entry, from its
+    /// day's sunrise and sunset or the latitude when the forecast lacks them
+    pub fn hour_light(&self, hour: &HourlyForecast) -> Option<HourLight> {
+        let start = time::parse_hour(&hour.time)?;
+        let day = self.weather_data.day(start.date());
+        let event = |time: Option<&String>| time.and_then(|time| time::parse_hour(time));
+        Some(daylight::hour_light(
+            start,
+            event(day.and_then(|day| day.sunrise.as_ref())),
+            event(day.and_then(|day| day.sunset.as_ref())),
+            self.latitude,
+        ))
+    }
+
+    /// Current local time at the location; without a UTC offset, the observation
+    /// time advanced by the time elapsed since the data was fetched
+    pub fn location_now(&self, now: DateTime<Utc>) -> Option<NaiveDateTime> {
+        if let Some(clock) = self.clock(now) {
+            return Some(clock.now());
+        }
+        let observed = time::parse_hour(self.weather_data.current.time.as_deref()?)?;
+        let elapsed = self
+            .fetched_at
+            .map_or(TimeDelta::zero(), |fetched| now - fetched);
+        Some(observed + elapsed.max(TimeDelta::zero()))
+    }
+
+    /// Current date at the location
+    pub fn today(&self, now: DateTime<Utc>) -> Option<NaiveDate> {
+        self.location_now(now).map(|t| t.date())
+    }
+
+    /// Whether the hourly series still covers the present at `now`
+    /// (`None` without hourly data or an observation time)
+    pub fn freshness(&self, now: DateTime<Utc>) -> Option<Freshness> {
+        let last_hour = self
+            .weather_data
+            .hourly
+            .iter()
+            .filter_map(|h| time::parse_hour(&h.time))
+            .max()?;
+        Some(freshness::classify(
+            last_hour,
+            self.location_now(now)?,
+            FORECAST_EXPIRY_GRACE_HOURS,
+        ))
+    }
+
+    /// Canonical identity of this location for sharing cached weather data
+    pub fn coordinate_key(&self) -> CoordinateKey {
+        CoordinateKey::new(self.latitude, self.longitude)
+    }
+
+    /// Position of the location, displayed with hemisphere letters
+    pub fn coordinates(&self) -> Coordinates {
+        Coordinates {
+            latitude: self.latitude,
+            longitude: coords::normalize_longitude(self.longitude),
+        }
+    }
+
+    /// Compact snapshot of the headline numbers for status bars and one-line output
+    pub fn summary(&self) -> WeatherSummary {
+        self.summary_at(Utc::now())
+    }
+
+    /// Summary with "today" taken at the location as of `now`
+    pub fn summary_at(&self, now: DateTime<Utc>) -> WeatherSummary {
+        WeatherSummary::from_info(self, now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{advice, agriculture, snow, storm};
+
+    #[test]
+    fn test_aggregations_over_empty_data() {
+        let data = WeatherData::default();
+
+        assert!(data.current_hour().is_none());
+        assert_eq!(data.current_hour_index(), 0);
+        assert!(data.upcoming_hours(48).is_empty());
+        assert!(data.upcoming_minutely(8).is_empty());
+        assert!(storm::thunderstorm_periods(&data.hourly).is_empty());
+        assert!(storm::peak_cape_window(&data.hourly).is_none());
+        assert!(snow::snowfall_accumulation(&data, 48).is_none());
+        assert!(agriculture::irrigation_deficit(&data.daily).is_empty());
+        assert!(advice::feels_like_callout(&data.current).is_none());
+    }
+
+    #[test]
+    fn test_upcoming_minutely_starts_after_the_observation() {
+        let mut data = WeatherData {
+            minutely: ["10:00", "10:15", "10:30", "10:45", "11:00"]
+                .iter()
+                .map(|time| MinutelyForecast::new(format!("2024-06-01T{time}")))
+                .collect(),
+            ..Default::default()
+        };
+        let times = |data: &WeatherData, steps| -> Vec<String> {
+            data.upcoming_minutely(steps)
+                .iter()
+                .map(|step| step.time[11..].to_string())
+                .collect()
+        };
+        assert_eq!(times(&data, 2), ["10:00", "10:15"]);
+
+        // The quarter hour ending at or before the observation is past
+        data.current.time = Some("2024-06-01T10:30".to_string());
+        assert_eq!(times(&data, 8), ["10:45", "11:00"]);
+        data.current.time = Some("2024-06-01T10:40".to_string());
+        assert_eq!(times(&data, 1), ["10:45"]);
+        data.current.time = Some("2024-06-01T09:00".to_string());
+        assert_eq!(times(&data, 1), ["10:00"]);
+        // A series that ended before the observation shows nothing
+        data.current.time = Some("2024-06-01T12:00".to_string());
+        assert!(data.upcoming_minutely(8).is_empty());
+    }
+
+    #[test]
+    fn test_aggregations_over_sample_data() {
+        let info = crate::fixtures::sample_weather_info();
+        let data = &info.weather_data;
+
+        assert_eq!(data.current_hour_index(), 14);
+        assert_eq!(data.upcoming_hours(48).len(), 34);
+        assert!(storm::thunderstorm_periods(&data.hourly).is_empty());
+        assert!(snow::snowfall_accumulation(data, 48).is_some());
+        assert_eq!(agriculture::irrigation_deficit(&data.daily).len(), 7);
+
+        let fog = data.low_visibility_windows(crate::constants::FOG_VISIBILITY_THRESHOLD_M);
+        assert_eq!(fog.len(), 1);
+        assert_eq!(fog[0].start, "2024-06-02T05:00");
+        assert_eq!(fog[0].end, "2024-06-02T09:00");
+    }
+
+    #[test]
+    fn test_totals_report_coverage_beyond_available_data() {
+        let info = crate::fixtures::sample_weather_info();
+        let data = &info.weather_data;
+        let now = data.current_hour_start().unwrap();
+
+        let day = data.precipitation_total(TimeDelta::hours(24), now).unwrap();
+        assert!((day.total - 1.2).abs() < 1e-9);
+        assert!(day.is_complete());
+
+        // Only 34 of the 48 hours are in the sample
+        let two_days = data.precipitation_total(TimeDelta::hours(48), now).unwrap();
+        assert!((two_days.total - 7.2).abs() < 1e-9);
+        assert_eq!((two_days.hours_with_data, two_days.window_hours), (34, 48));
+        assert_eq!(
+            data.snow_total(TimeDelta::hours(48), now).unwrap().total,
+            0.0
+        );
+    }
+
+    /// Hourly series from 2024-06-01T00:00 with random gaps of one to three
+    /// hours and the occasional unreadable time, from a fixed seed
+    fn random_series(seed: u64) -> WeatherData {
+        let mut state = seed;
+        let mut next = move |bound: u64| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) % bound
+        };
+        let mut t = time::parse_hour("2024-06-01T00:00").unwrap();
+        let hourly = (0..60)
+            .map(|_| {
+                t += TimeDelta::hours(1 + next(3) as i64);
+                let time = if next(10) == 0 {
+                    "not a time".to_string()
+                } else {
+                    t.format("%Y-%m-%dT%H:%M").to_string()
+                };
+                HourlyForecast::builder(time).build()
+            })
+            .collect();
+        let daily = (1..=9)
+            .map(|day| DailyForecast {
+                date: format!("2024-06-{day:02}"),
+                ..Default::default()
+            })
+            .collect();
+        WeatherData::new(CurrentWeather::default(), hourly, daily)
+    }
+
+    #[test]
+    fn test_adjacent_windows_cover_the_series_once() {
+        let start = time::parse_hour("2024-06-01T00:00").unwrap();
+        for seed in 0..20 {
+            let data = random_series(seed);
+            let mut cuts = vec![start];
+            for step in [5, 1, 17, 24, 2, 40, 9, 30, 60, 3] {
+                cuts.push(*cuts.last().unwrap() + TimeDelta::hours(step + seed as i64 % 4));
+            }
+            let end = *cuts.last().unwrap();
+
+            let pieces: Vec<*const HourlyForecast> = cuts
+                .windows(2)
+                .flat_map(|pair| data.hourly_between(pair[0], pair[1]))
+                .map(|h| h as *const _)
+                .collect();
+            let whole: Vec<*const HourlyForecast> = data
+                .hourly
+                .iter()
+                .filter(|h| time::parse_hour(&h.time).is_some_and(|t| start <= t && t < end))
+                .map(|h| h as *const _)
+                .collect();
+            assert_eq!(pieces, whole, "seed {seed}");
+            assert_eq!(
+                data.hourly_between(start, end).count(),
+                whole.len(),
+                "seed {seed}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_days_and_upcoming_hours() {
+        let data = random_series(7);
+        let june = |day| NaiveDate::from_ymd_opt(2024, 6, day).unwrap();
+        let dates: Vec<&str> = data
+            .daily_between(june(2), june(5))
+            .map(|d| d.date.as_str())
+            .collect();
+        assert_eq!(dates, ["2024-06-02", "2024-06-03", "2024-06-04"]);
+        assert_eq!(data.daily_between(june(5), june(5)).count(), 0);
+        assert!(data
+            .hourly_on(june(3))
+            .all(|h| h.time.starts_with("2024-06-03")));
+
+        // The hour containing `now` is included, hours that have ended are not
+        let now = time::parse_hour("2024-06-03T10:30").unwrap();
+        let first = data.hourly_from(now).next().unwrap();
+        assert!(time::parse_hour(&first.time).unwrap() + TimeDelta::hours(1) > now);
+        let clock = LocationClock::new(0, "2024-06-03T10:30:00Z".parse().unwrap());
+        assert_eq!(data.hourly_from_now(&clock).next(), Some(first));
+        assert_eq!(
+            data.hourly_from(time::parse_hour("2024-07-01T00:00").unwrap())
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_next_hour_and_today_follow_the_observation() {
+        let info = crate::fixtures::sample_weather_info();
+        let data = &info.weather_data;
+        assert_eq!(
+            data.next_hour(),
+            data.hourly.get(data.current_hour_index() + 1)
+        );
+        let observed = data.current.time.as_deref().unwrap();
+        assert!(observed.starts_with(&data.today().unwrap().date));
+
+        let data = WeatherData::default();
+        assert_eq!(data.next_hour(), None);
+        assert_eq!(data.today(), None);
+    }
+
+    #[test]
+    fn test_totals_without_values_are_none() {
+        let hourly = vec![
+            HourlyForecast::builder("2024-06-01T00:00").build(),
+            HourlyForecast::builder("2024-06-01T01:00").build(),
+        ];
+        let data = WeatherData::new(CurrentWeather::default(), hourly, Vec::new());
+        let from = time::parse_hour("2024-06-01T00:00").unwrap();
+
+        assert_eq!(data.precipitation_total(TimeDelta::hours(24), from), None);
+        assert_eq!(data.snow_total(TimeDelta::hours(24), from), None);
+        assert_eq!(WeatherData::default().current_hour_start(), None);
+    }
+
+    #[test]
+    fn test_humidity_mean_falls_back_to_hourly_values() {
+        let hourly = (0..48)
+            .map(|h| {
+                let hour =
+                    HourlyForecast::builder(format!("2024-06-{:02}T{:02}:00", 1 + h / 24, h % 24));
+                // Day two loses an hour, so its mean stays unknown
+                if h == 30 {
+                    hour.build()
+                } else {
+                    hour.humidity(if h % 2 == 0 { 50.0 } else { 70.0 }).build()
+                }
+            })
+            .collect();
+        let daily = vec![
+            DailyForecast::builder("2024-06-01").build(),
+            DailyForecast::builder("2024-06-02").build(),
+            DailyForecast::builder("2024-06-03")
+                .humidity_mean(81.0)
+                .build(),
+        ];
+        let mut data = WeatherData::new(CurrentWeather::default(), hourly, daily);
+        data.fill_humidity_means();
+
+        assert_eq!(data.daily[0].humidity_mean, Some(60.0));
+        assert_eq!(data.daily[1].humidity_mean, None);
+        // Values from the server are kept
+        assert_eq!(data.daily[2].humidity_mean, Some(81.0));
+    }
+
+    #[test]
+    fn test_precipitation_chances_from_hourly_values() {
+        // A brief likely shower on day one, a long soaker on day two, and a
+        // day three the hourly series only half covers
+        let hourly = (0..60)
+            .map(|h| {
+                let hour = h % 24;
+                let probability = match 1 + h / 24 {
+                    1 if (15..18).contains(&hour) => 80.0,
+                    1 => 0.0,
+                    2 if hour >= 4 => 80.0,
+                    2 => 50.0,
+                    _ => 90.0,
+                };
+                HourlyForecast::builder(format!("2024-06-{:02}T{hour:02}:00", 1 + h / 24))
+                    .precipitation_probability(probability)
+                    .build()
+            })
+            .collect();
+        let daily = ["2024-06-01", "2024-06-02", "2024-06-03"]
+            .iter()
+            .map(|date| {
+                DailyForecast::builder(*date)
+                    .precipitation_probability(80.0)
+                    .build()
+            })
+            .collect();
+        let mut data = WeatherData::new(CurrentWeather::default(), hourly, daily);
+        data.fill_precipitation_chances();
+
+        let [brief, soaker, partial] = &data.daily[..] else {
+            panic!("three days expected");
+        };
+        assert_eq!(brief.precipitation_probability_mean, Some(10.0));
+        assert_eq!(brief.wet_hours, Some(3));
+        // 50% is not above the threshold
+        assert_eq!(soaker.precipitation_probability_mean, Some(75.0));
+        assert_eq!(soaker.wet_hours, Some(20));
+        assert_eq!(partial.precipitation_probability_mean, None);
+        assert_eq!(partial.wet_hours, None);
+
+        assert_eq!(
+            brief.precipitation_chance().as_deref(),
+            Some("80% peak · wet hours ~3")
+        );
+        assert_eq!(partial.precipitation_chance().as_deref(), Some("80%"));
+        assert_eq!(DailyForecast::default().precipitation_chance(), None);
+    }
+
+    /// Hourly series for a local day on which the clocks change, `skip`ping
+    /// or `repeat`ing the local hour 02:00
+    fn clock_change_day(date: &str, skip: bool, repeat: bool) -> Vec<HourlyForecast> {
+        let mut hours = Vec::new();
+        for h in 0..24 {
+            let repeats = if h == 2 && repeat { 2 } else { 1 };
+            for _ in 0..repeats {
+                if !(h == 2 && skip) {
+                    let hour = HourlyForecast::builder(format!("{date}T{h:02}:00"));
+                    hours.push(hour.precipitation(1.0).humidity(60.0).build());
+                }
+            }
         }
+        hours
+    }
+
+    #[test]
+    fn test_spring_forward_window_spans_real_hours() {
+        let mut hourly = clock_change_day("2024-03-31", true, false);
+        hourly.extend(clock_change_day("2024-04-01", false, false));
+        let daily = vec![DailyForecast::builder("2024-03-31").build()];
+        let mut data = WeatherData::new(CurrentWeather::default(), hourly, daily);
+
+        let changes: Vec<&HourlyForecast> = data.clock_changes().collect();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].time, "2024-03-31T03:00");
+        assert_eq!(
+            changes[0].clock_change,
+            Some(ClockChange::Forward(TimeDelta::hours(1)))
+        );
+
+        // 24 real hours from midnight run to 00:00 the next day
+        let from = time::parse_hour("2024-03-31T00:00").unwrap();
+        let day = data
+            .precipitation_total(TimeDelta::hours(24), from)
+            .unwrap();
+        assert_eq!(day.hours_with_data, 24);
+        assert!(day.is_complete());
+
+        // The 23-hour local day still has a whole day of humidity
+        data.fill_humidity_means();
+        assert_eq!(data.daily[0].humidity_mean, Some(60.0));
+    }
+
+    #[test]
+    fn test_fall_back_window_spans_real_hours() {
+        let hourly = clock_change_day("2024-10-27", false, true);
+        assert_eq!(hourly.len(), 25);
+        let data = WeatherData::new(CurrentWeather::default(), hourly, Vec::new());
+
+        let changes: Vec<usize> = data
+            .hourly
+            .iter()
+            .enumerate()
+            .filter(|(_, h)| h.clock_change.is_some())
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(changes, [3]);
+        assert_eq!(
+            data.hourly[3].clock_change,
+            Some(ClockChange::Back(TimeDelta::hours(1)))
+        );
+
+        // Midnight to midnight is 25 real hours; a day's window holds 24 of them
+        let from = time::parse_hour("2024-10-27T00:00").unwrap();
+        let day = data
+            .precipitation_total(TimeDelta::hours(24), from)
+            .unwrap();
+        assert_eq!(day.hours_with_data, 24);
+        assert_eq!(data.humidity_mean(from.date()), Some(60.0));
+    }
+
+    #[test]
+    fn test_hour_light_uses_the_hours_own_day() {
+        let hourly: Vec<HourlyForecast> =
+            ["2024-06-01T05:00", "2024-06-02T05:00", "2024-06-03T05:00"]
+                .into_iter()
+                .map(|time| HourlyForecast::builder(time).build())
+                .collect();
+        let daily = vec![
+            DailyForecast::builder("2024-06-01")
+                .sunrise("2024-06-01T05:50")
+                .sunset("2024-06-01T21:10")
+                .build(),
+            DailyForecast::builder("2024-06-02")
+                .sunrise("2024-06-02T04:58")
+                .sunset("2024-06-02T21:11")
+                .build(),
+        ];
+        let data = WeatherData::new(CurrentWeather::default(), hourly, daily);
+        let info = WeatherInfo::new("Berlin".to_string(), 52.52, 13.4, data);
+        let lights: Vec<Option<HourLight>> = info
+            .weather_data
+            .hourly
+            .iter()
+            .map(|hour| info.hour_light(hour))
+            .collect();
+        // The third day has no sunrise, so the latitude stands in for it
+        assert_eq!(
+            lights,
+            [
+                Some(HourLight::Sunrise),
+                Some(HourLight::Day),
+                Some(HourLight::Day)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reference_time_and_freshness() {
+        let mut info = crate::fixtures::sample_weather_info();
+        assert_eq!(info.reference_time(), Some("2024-06-01T00:00"));
+
+        let fetched = DateTime::parse_from_rfc3339("2024-06-01T21:15:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        info.fetched_at = Some(fetched);
+        assert_eq!(
+            info.location_now(fetched + TimeDelta::hours(2)),
+            time::parse_hour("2024-06-01T16:15")
+        );
+        assert_eq!(info.freshness(fetched), Some(Freshness::Current));
+        assert_eq!(
+            info.freshness(fetched + TimeDelta::hours(63)),
+            Some(Freshness::Expired { hours_past: 30 })
+        );
+
+        let empty = WeatherInfo::new("Nowhere".to_string(), 0.0, 0.0, WeatherData::default());
+        assert_eq!(empty.reference_time(), None);
+        assert_eq!(empty.freshness(fetched), None);
     }
 }
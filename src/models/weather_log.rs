@@ -0,0 +1,148 @@
+//! Local climate record: one snapshot of the current conditions per lookup,
+//! summarized per monthly file
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::weather_info::WeatherInfo;
+
+/// Current conditions at one lookup
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WeatherLogRecord {
+    /// When the lookup ran, RFC 3339 in UTC
+    pub time: String,
+    /// Observation time at the location, e.g. "2024-06-01T14:00"
+    pub observed: Option<String>,
+    /// °C
+    pub temperature: Option<f64>,
+    /// Relative humidity in percent
+    pub humidity: Option<f64>,
+    /// Sea-level pressure in hPa
+    pub pressure: Option<f64>,
+    /// km/h
+    pub wind_speed: Option<f64>,
+    /// Degrees the wind comes from
+    pub wind_direction: Option<f64>,
+    /// WMO weather code
+    pub weather_code: Option<i32>,
+    /// Precipitation of the preceding hour in mm
+    pub precipitation: Option<f64>,
+}
+
+impl WeatherLogRecord {
+    pub fn from_weather(weather: &WeatherInfo, now: DateTime<Utc>) -> Self {
+        let current = &weather.weather_data.current;
+        WeatherLogRecord {
+            time: now.to_rfc3339(),
+            observed: current.time.clone(),
+            temperature: current.temperature,
+            humidity: current.humidity,
+            pressure: current.pressure_msl,
+            wind_speed: current.wind_speed,
+            wind_direction: current.wind_direction,
+            weather_code: current.weather_code,
+            precipitation: current.precipitation,
+        }
+    }
+
+    /// Month the record belongs to, "YYYY-MM": the local month at the
+    /// location, or the UTC month of the lookup without an observation time
+    pub fn month(&self) -> Option<&str> {
+        fn month(time: &str) -> Option<&str> {
+            time.get(..7).filter(|m| m.as_bytes()[4] == b'-')
+        }
+        self.observed
+            .as_deref()
+            .and_then(month)
+            .or_else(|| month(&self.time))
+    }
+}
+
+/// Totals over the records of a log file
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct WeatherLogSummary {
+    pub records: usize,
+    /// Lines that could not be read (cut short, edited by hand)
+    pub skipped: usize,
+    pub temperature_min: Option<f64>,
+    pub temperature_max: Option<f64>,
+    pub temperature_mean: Option<f64>,
+    /// Sum of the logged precipitation; the month's true total only when the
+    /// log was written once an hour
+    pub precipitation_total: Option<f64>,
+}
+
+impl WeatherLogSummary {
+    pub fn from_records(records: &[WeatherLogRecord], skipped: usize) -> Self {
+        let temperatures: Vec<f64> = records.iter().filter_map(|r| r.temperature).collect();
+        let precipitation: Vec<f64> = records.iter().filter_map(|r| r.precipitation).collect();
+        WeatherLogSummary {
+            records: records.len(),
+            skipped,
+            temperature_min: temperatures.iter().copied().reduce(f64::min),
+            temperature_max: temperatures.iter().copied().reduce(f64::max),
+            temperature_mean: (!temperatures.is_empty())
+                .then(|| temperatures.iter().sum::<f64>() / temperatures.len() as f64),
+            precipitation_total: (!precipitation.is_empty()).then(|| precipitation.iter().sum()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(
+        observed: Option<&str>,
+        temperature: Option<f64>,
+        rain: Option<f64>,
+    ) -> WeatherLogRecord {
+        WeatherLogRecord {
+            time: "2024-07-01T06:30:00+00:00".to_string(),
+            observed: observed.map(str::to_string),
+            temperature,
+            humidity: None,
+            pressure: None,
+            wind_speed: None,
+            wind_direction: None,
+            weather_code: None,
+            precipitation: rain,
+        }
+    }
+
+    #[test]
+    fn test_summary() {
+        let records = [
+            record(Some("2024-06-01T08:00"), Some(11.0), Some(0.0)),
+            record(Some("2024-06-14T15:00"), Some(24.0), Some(1.2)),
+            record(Some("2024-06-20T03:00"), None, Some(0.4)),
+            record(Some("2024-06-30T23:00"), Some(16.0), None),
+        ];
+        let summary = WeatherLogSummary::from_records(&records, 2);
+        assert_eq!(summary.records, 4);
+        assert_eq!(summary.skipped, 2);
+        assert_eq!(summary.temperature_min, Some(11.0));
+        assert_eq!(summary.temperature_max, Some(24.0));
+        assert_eq!(summary.temperature_mean, Some(17.0));
+        assert!((summary.precipitation_total.unwrap() - 1.6).abs() < 1e-9);
+
+        let empty = WeatherLogSummary::from_records(&[record(None, None, None)], 0);
+        assert_eq!(empty.temperature_mean, None);
+        assert_eq!(empty.precipitation_total, None);
+    }
+
+    #[test]
+    fn test_month_prefers_the_local_observation() {
+        // Late on June 30th locally is already July in UTC
+        assert_eq!(
+            record(Some("2024-06-30T23:00"), None, None).month(),
+            Some("2024-06")
+        );
+        assert_eq!(record(None, None, None).month(), Some("2024-07"));
+        assert_eq!(
+            record(Some("30.06.2024"), None, None).month(),
+            Some("2024-07")
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Wind direction and speed distribution over a run of hourly forecasts
+
+use crate::models::weather_info::HourlyForecast;
+
+/// Hours whose wind came from one direction sector
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindBin {
+    /// Sector center in degrees (0 = north, clockwise)
+    pub center: f64,
+    pub hours: usize,
+    /// Share of the hours with a direction, 0–1
+    pub frequency: f64,
+    /// Share of the total wind run (speed × hours), 0–1; strong winds weigh more
+    pub weighted_frequency: f64,
+    /// Mean speed in km/h of the hours reporting one
+    pub mean_speed: Option<f64>,
+}
+
+/// Direction sectors of equal width, the first centered on north. Hours
+/// without a direction are left out and only lower the coverage.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct WindRose {
+    pub bins: Vec<WindBin>,
+    /// Hours with a direction
+    pub counted: usize,
+    /// Hours considered
+    pub total: usize,
+}
+
+impl WindRose {
+    /// Distribution over `hours` in `bins` sectors (at least one)
+    pub fn from_hours(hours: &[HourlyForecast], bins: usize) -> Self {
+        let bins = bins.max(1);
+        let width = 360.0 / bins as f64;
+        let mut counts = vec![0usize; bins];
+        let mut speeds = vec![(0.0, 0usize); bins];
+
+        for hour in hours {
+            let Some(direction) = hour.wind_direction else {
+                continue;
+            };
+            // Shift by half a sector so the first one straddles north (wrap-around)
+            let index = ((direction + width / 2.0).rem_euclid(360.0) / width) as usize % bins;
+            counts[index] += 1;
+            if let Some(speed) = hour.wind_speed {
+                speeds[index].0 += speed;
+                speeds[index].1 += 1;
+            }
+        }
+
+        let counted: usize = counts.iter().sum();
+        let wind_run: f64 = speeds.iter().map(|(sum, _)| sum).sum();
+        let bins = counts
+            .iter()
+            .zip(&speeds)
+            .enumerate()
+            .map(|(i, (&count, &(sum, with_speed)))| WindBin {
+                center: i as f64 * width,
+                hours: count,
+                frequency: share(count as f64, counted as f64),
+                weighted_frequency: share(sum, wind_run),
+                mean_speed: (with_speed > 0).then(|| sum / with_speed as f64),
+            })
+            .collect();
+
+        WindRose {
+            bins,
+            counted,
+            total: hours.len(),
+        }
+    }
+
+    /// Percentage of hours with a direction (0 without hours)
+    pub fn coverage(&self) -> f64 {
+        share(self.counted as f64, self.total as f64) * 100.0
+    }
+
+    /// Most frequent sector; ties go to the first clockwise from north
+    pub fn prevailing(&self) -> Option<&WindBin> {
+        self.bins
+            .iter()
+            .filter(|bin| bin.hours > 0)
+            .reduce(|best, bin| if bin.hours > best.hours { bin } else { best })
+    }
+
+    /// Largest frequency of any sector, for scaling spokes
+    pub fn max_frequency(&self) -> f64 {
+        self.bins
+            .iter()
+            .map(|bin| bin.frequency)
+            .fold(0.0, f64::max)
+    }
+}
+
+fn share(part: f64, whole: f64) -> f64 {
+    if whole > 0.0 {
+        part / whole
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hours(winds: &[(Option<f64>, Option<f64>)]) -> Vec<HourlyForecast> {
+        winds
+            .iter()
+            .enumerate()
+            .map(|(i, &(direction, speed))| {
+                let mut hour = HourlyForecast::builder(format!("2024-06-01T{i:02}:00"));
+                if let Some(d) = direction {
+                    hour = hour.wind_direction(d);
+                }
+                if let Some(s) = speed {
+                    hour = hour.wind_speed(s);
+                }
+                hour.build()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sectors_wrap_around_north() {
+        let rose = WindRose::from_hours(
+            &hours(&[
+                (Some(350.0), Some(10.0)),
+                (Some(10.0), Some(20.0)),
+                (Some(22.4), None),
+                (Some(22.5), Some(5.0)),
+                (Some(337.5), Some(30.0)),
+                (Some(-10.0), Some(10.0)),
+                (Some(720.0), Some(10.0)),
+            ]),
+            8,
+        );
+        let north = &rose.bins[0];
+        assert_eq!(north.center, 0.0);
+        // 350, 10, 22.4, 337.5, -10 and 720 all fall in the sector straddling north
+        assert_eq!(north.hours, 6);
+        assert_eq!(north.mean_speed, Some(16.0));
+        assert_eq!(rose.bins[1].center, 45.0);
+        assert_eq!(rose.bins[1].hours, 1);
+        assert_eq!(rose.prevailing().unwrap().center, 0.0);
+    }
+
+    #[test]
+    fn test_frequency_and_speed_weighting() {
+        // Three light easterlies, one strong westerly
+        let rose = WindRose::from_hours(
+            &hours(&[
+                (Some(90.0), Some(10.0)),
+                (Some(95.0), Some(10.0)),
+                (Some(85.0), Some(10.0)),
+                (Some(270.0), Some(90.0)),
+            ]),
+            4,
+        );
+        let east = &rose.bins[1];
+        let west = &rose.bins[3];
+        assert_eq!((east.center, west.center), (90.0, 270.0));
+        assert_eq!(east.frequency, 0.75);
+        assert_eq!(west.frequency, 0.25);
+        assert_eq!(east.weighted_frequency, 0.25);
+        assert_eq!(west.weighted_frequency, 0.75);
+        assert_eq!(rose.max_frequency(), 0.75);
+        let total: f64 = rose.bins.iter().map(|b| b.frequency).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_missing_directions_lower_coverage() {
+        let rose = WindRose::from_hours(
+            &hours(&[
+                (Some(180.0), Some(12.0)),
+                (None, Some(40.0)),
+                (None, None),
+                (Some(200.0), None),
+            ]),
+            16,
+        );
+        assert_eq!((rose.counted, rose.total), (2, 4));
+        assert_eq!(rose.coverage(), 50.0);
+        assert_eq!(rose.bins.len(), 16);
+        let south = &rose.bins[8];
+        assert_eq!(south.center, 180.0);
+        assert_eq!(south.frequency, 0.5);
+        // The hour without a direction does not count towards the wind run
+        assert_eq!(south.weighted_frequency, 1.0);
+
+        let empty = WindRose::from_hours(&[], 8);
+        assert_eq!(empty.coverage(), 0.0);
+        assert!(empty.prevailing().is_none());
+        assert_eq!(WindRose::from_hours(&[], 0).bins.len(), 1);
+    }
+}
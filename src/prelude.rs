@@ -0,0 +1,17 @@
+//! Common types for using the library: `use weather_app::prelude::*;`
+
+pub use crate::errors::WeatherError;
+pub use crate::models::air_quality::{AirQuality, AirQualityReading};
+pub use crate::models::forecast_model::ForecastModel;
+pub use crate::models::summary::WeatherSummary;
+pub use crate::models::weather_info::{
+    CurrentWeather, DailyForecast, HourlyForecast, MinutelyForecast, WeatherData, WeatherInfo,
+};
+pub use crate::repositories::data_selection::DataSelection;
+pub use crate::repositories::fetch_options::FetchOptions;
+pub use crate::repositories::file_weather_repository::FileWeatherRepository;
+pub use crate::repositories::geocoding::GeocodeResult;
+pub use crate::repositories::weather_repository::{ApiWeatherRepository, WeatherRepository};
+pub use crate::utils::conversions::{Distance, Pressure, Speed, Temperature};
+pub use crate::views::export::{self, UnitSystem};
+pub use crate::views::DisplayOptions;
@@ -0,0 +1,195 @@
+//! Open-Meteo air quality API: pollutants and AQI, current and hourly
+//!
+//! The numbers come from atmospheric models rather than stations, so every
+//! point on land and sea has them. Air quality is extra to the forecast: a
+//! failed request leaves it out instead of failing the fetch.
+
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use crate::constants::{AIR_QUALITY_FORECAST_DAYS, HOURLY_FORECAST_LIMIT};
+use crate::errors::WeatherError;
+use crate::models::air_quality::{AirQuality, AirQualityReading};
+use crate::repositories::sanitize;
+use crate::repositories::weather_repository::ApiWeatherRepository;
+
+/// Variables requested from the air quality API, for current and hourly data
+pub(crate) const AIR_QUALITY_VARIABLES: &[&str] = &[
+    "pm2_5",
+    "pm10",
+    "ozone",
+    "nitrogen_dioxide",
+    "european_aqi",
+    "us_aqi",
+];
+
+#[derive(Debug, Deserialize)]
+struct OpenMeteoAirQuality {
+    current: Option<OpenMeteoAirQualityCurrent>,
+    hourly: Option<OpenMeteoAirQualityHourly>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenMeteoAirQualityCurrent {
+    time: String,
+    pm2_5: Option<f64>,
+    pm10: Option<f64>,
+    ozone: Option<f64>,
+    nitrogen_dioxide: Option<f64>,
+    european_aqi: Option<f64>,
+    us_aqi: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenMeteoAirQualityHourly {
+    time: Vec<String>,
+    #[serde(default)]
+    pm2_5: Vec<Option<f64>>,
+    #[serde(default)]
+    pm10: Vec<Option<f64>>,
+    #[serde(default)]
+    ozone: Vec<Option<f64>>,
+    #[serde(default)]
+    nitrogen_dioxide: Vec<Option<f64>>,
+    #[serde(default)]
+    european_aqi: Vec<Option<f64>>,
+    #[serde(default)]
+    us_aqi: Vec<Option<f64>>,
+}
+
+/// Air quality request for the same point and time zone as the forecast,
+/// so the hours line up with the hourly forecast
+pub(crate) fn air_quality_url(
+    base_url: &str,
+    lat: f64,
+    lon: f64,
+    timezone: Option<&str>,
+) -> String {
+    let variables = AIR_QUALITY_VARIABLES.join(",");
+    format!(
+        "{base_url}?latitude={lat}&longitude={lon}&current={variables}&hourly={variables}&forecast_days={AIR_QUALITY_FORECAST_DAYS}&timezone={}",
+        timezone.map_or("auto".into(), urlencoding::encode)
+    )
+}
+
+/// Concentrations and indices are never negative; anything else is missing
+fn amount(value: Option<f64>) -> Option<f64> {
+    value.filter(|v| *v >= 0.0)
+}
+
+/// Reads an air quality response: `Ok(None)` when it has no values, `Err`
+/// for failed requests and unreadable bodies
+pub(crate) fn parse_air_quality_response(
+    status: StatusCode,
+    body: &str,
+) -> Result<Option<AirQuality>, WeatherError> {
+    if !status.is_success() {
+        return Err(ApiWeatherRepository::api_status_error(
+            "Air quality",
+            status,
+            body,
+        ));
+    }
+
+    let (json, _) = sanitize::non_finite_to_null(body);
+    let air: OpenMeteoAirQuality =
+        serde_json::from_str(&json).map_err(|e| WeatherError::ParseError(e.to_string()))?;
+
+    let current = air.current.map(|current| AirQualityReading {
+        pm2_5: amount(current.pm2_5),
+        pm10: amount(current.pm10),
+        ozone: amount(current.ozone),
+        nitrogen_dioxide: amount(current.nitrogen_dioxide),
+        european_aqi: amount(current.european_aqi),
+        us_aqi: amount(current.us_aqi),
+        ..AirQualityReading::new(current.time)
+    });
+    let hourly = air.hourly.map_or_else(Vec::new, |hourly| {
+        let value = |values: &[Option<f64>], i: usize| amount(values.get(i).copied().flatten());
+        hourly
+            .time
+            .iter()
+            .take(HOURLY_FORECAST_LIMIT)
+            .enumerate()
+            .map(|(i, time)| AirQualityReading {
+                pm2_5: value(&hourly.pm2_5, i),
+                pm10: value(&hourly.pm10, i),
+                ozone: value(&hourly.ozone, i),
+                nitrogen_dioxide: value(&hourly.nitrogen_dioxide, i),
+                european_aqi: value(&hourly.european_aqi, i),
+                us_aqi: value(&hourly.us_aqi, i),
+                ..AirQualityReading::new(time.as_str())
+            })
+            .collect()
+    });
+    Ok(AirQuality::from_readings(current, hourly))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_current_and_hourly() {
+        let body = r#"{
+            "latitude": 51.5, "longitude": -0.12,
+            "current": {
+                "time": "2025-12-10T10:00", "interval": 3600,
+                "pm2_5": 9.4, "pm10": 14.1, "ozone": 38.0,
+                "nitrogen_dioxide": 27.5, "european_aqi": 21, "us_aqi": 39
+            },
+            "hourly": {
+                "time": ["2025-12-10T00:00", "2025-12-10T01:00", "2025-12-10T02:00"],
+                "pm2_5": [11.2, NaN, 10.1],
+                "pm10": [16.0, 15.2, -1.0],
+                "ozone": [30.0, 31.0, 33.0],
+                "nitrogen_dioxide": [35.1, 33.0, null],
+                "european_aqi": [24, 23, 22],
+                "us_aqi": [46, 44, 42]
+            }
+        }"#;
+        let air = parse_air_quality_response(StatusCode::OK, body)
+            .unwrap()
+            .unwrap();
+        let current = air.current.as_ref().unwrap();
+        assert_eq!(current.time, "2025-12-10T10:00");
+        assert_eq!(current.pm2_5, Some(9.4));
+        assert_eq!(current.us_aqi, Some(39.0));
+        assert_eq!(current.us_aqi_category(), Some("Good"));
+
+        assert_eq!(air.hourly.len(), 3);
+        assert_eq!(air.hourly[0].nitrogen_dioxide, Some(35.1));
+        assert_eq!(air.hourly[1].pm2_5, None);
+        assert_eq!(air.hourly[2].pm10, None);
+        assert_eq!(air.hourly[2].european_aqi, Some(22.0));
+    }
+
+    #[test]
+    fn test_failures_and_empty_responses() {
+        assert_eq!(
+            parse_air_quality_response(StatusCode::OK, "{}").unwrap(),
+            None
+        );
+        assert!(matches!(
+            parse_air_quality_response(StatusCode::BAD_GATEWAY, "upstream down"),
+            Err(WeatherError::ServerError(_))
+        ));
+        assert!(matches!(
+            parse_air_quality_response(StatusCode::OK, "<html>"),
+            Err(WeatherError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn test_url_asks_for_current_and_hourly() {
+        let url = air_quality_url(
+            "https://air-quality-api.open-meteo.com/v1/air-quality",
+            51.5,
+            -0.12,
+            Some("Europe/London"),
+        );
+        assert!(url.contains("&current=pm2_5,pm10,ozone,nitrogen_dioxide,european_aqi,us_aqi&"));
+        assert!(url.contains("&hourly=pm2_5,pm10,"));
+        assert!(url.ends_with("&forecast_days=2&timezone=Europe%2FLondon"));
+    }
+}
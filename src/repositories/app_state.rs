@@ -0,0 +1,322 @@
+//! Everything the app stores on disk, listed and cleared in one place
+//!
+//! Files live in two directories of the app's own: preferences and history
+//! under `<config dir>/weather-app`, and data that can be fetched again under
+//! `<cache dir>/weather-app`. Clearing removes known file names from those
+//! directories only, never a directory tree, and refuses directories that
+//! could hold more than the app's files (the home directory or one of its
+//! ancestors).
+
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::errors::WeatherError;
+
+/// Name of the app's subdirectory in the config and cache directories
+const APP_DIR_NAME: &str = "weather-app";
+
+/// One kind of stored state
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StateItem {
+    /// Preferences from the GUI settings window (`settings.json`)
+    Settings,
+    /// Saved locations (`favorites.json`)
+    Favorites,
+    /// Lookup history for usage statistics (`usage.jsonl`)
+    UsageHistory,
+    /// Weather of the last GUI fetch, for the warm start (`last_weather.json`)
+    WeatherSnapshot,
+    /// Last weather per location, shown when offline (`offline_weather.json`)
+    OfflineWeather,
+    /// Coordinates of places looked up before (`geocode_cache.json`)
+    GeocodeCache,
+}
+
+impl StateItem {
+    pub const ALL: [StateItem; 6] = [
+        StateItem::Settings,
+        StateItem::Favorites,
+        StateItem::UsageHistory,
+        StateItem::WeatherSnapshot,
+        StateItem::OfflineWeather,
+        StateItem::GeocodeCache,
+    ];
+
+    /// Items that can be fetched again, removed by `cache clear`
+    pub const CACHES: [StateItem; 3] = [
+        StateItem::WeatherSnapshot,
+        StateItem::OfflineWeather,
+        StateItem::GeocodeCache,
+    ];
+
+    pub fn file_name(self) -> &'static str {
+        match self {
+            StateItem::Settings => "settings.json",
+            StateItem::Favorites => "favorites.json",
+            StateItem::UsageHistory => "usage.jsonl",
+            StateItem::WeatherSnapshot => "last_weather.json",
+            StateItem::OfflineWeather => "offline_weather.json",
+            StateItem::GeocodeCache => "geocode_cache.json",
+        }
+    }
+
+    /// Whether the item lives in the cache directory rather than the config one
+    fn is_cache(self) -> bool {
+        StateItem::CACHES.contains(&self)
+    }
+}
+
+impl fmt::Display for StateItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            StateItem::Settings => "settings",
+            StateItem::Favorites => "favorites",
+            StateItem::UsageHistory => "usage history",
+            StateItem::WeatherSnapshot => "weather snapshot",
+            StateItem::OfflineWeather => "offline weather",
+            StateItem::GeocodeCache => "geocoding cache",
+        })
+    }
+}
+
+/// A stored file and its size, `None` when it does not exist
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StateFile {
+    pub item: StateItem,
+    pub path: PathBuf,
+    pub size: Option<u64>,
+}
+
+/// The app's config and cache directories
+#[derive(Clone, Debug)]
+pub struct AppDirs {
+    config: PathBuf,
+    cache: PathBuf,
+    favorites: Option<PathBuf>,
+}
+
+impl AppDirs {
+    /// `config` and `cache` as the app's directories; `StorageError` for the
+    /// home directory, one of its ancestors, or a relative path
+    pub fn new(
+        config: impl Into<PathBuf>,
+        cache: impl Into<PathBuf>,
+    ) -> Result<Self, WeatherError> {
+        let (config, cache) = (config.into(), cache.into());
+        check_app_dir(&config)?;
+        check_app_dir(&cache)?;
+        Ok(AppDirs {
+            config,
+            cache,
+            favorites: None,
+        })
+    }
+
+    /// `weather-app` in the platform config and cache directories
+    pub fn default_location() -> Option<Self> {
+        let config = dirs::config_dir()?.join(APP_DIR_NAME);
+        let cache = dirs::cache_dir()?.join(APP_DIR_NAME);
+        AppDirs::new(config, cache).ok()
+    }
+
+    /// Lists favorites at `path` (from `--favorites-file`) instead of the
+    /// config directory. Favorites are never cleared, so the path is only shown.
+    pub fn with_favorites_file(mut self, path: Option<PathBuf>) -> Self {
+        self.favorites = path;
+        self
+    }
+
+    pub fn config_dir(&self) -> &Path {
+        &self.config
+    }
+
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache
+    }
+
+    pub fn path(&self, item: StateItem) -> PathBuf {
+        match (item, &self.favorites) {
+            (StateItem::Favorites, Some(path)) => path.clone(),
+            _ if item.is_cache() => self.cache.join(item.file_name()),
+            _ => self.config.join(item.file_name()),
+        }
+    }
+
+    /// Every file the app may have created, with its size if present
+    pub fn files(&self) -> Vec<StateFile> {
+        StateItem::ALL
+            .into_iter()
+            .map(|item| {
+                let path = self.path(item);
+                let size = fs::metadata(&path).ok().map(|m| m.len());
+                StateFile { item, path, size }
+            })
+            .collect()
+    }
+
+    /// Removes the files of `items` from the app's directories and returns
+    /// the ones that existed, with their sizes. Favorites are refused: they
+    /// are the user's own list, not state to purge.
+    pub fn clear(&self, items: &[StateItem]) -> Result<Vec<StateFile>, WeatherError> {
+        if items.contains(&StateItem::Favorites) {
+            return Err(WeatherError::StorageError(
+                "Favorites are not cleared; remove them in the GUI sidebar".to_string(),
+            ));
+        }
+        let mut removed = Vec::new();
+        for &item in items {
+            let path = self.path(item);
+            let storage_error =
+                |e: std::io::Error| WeatherError::StorageError(format!("{}: {e}", path.display()));
+            // The link itself, never what it points to
+            let size = match fs::symlink_metadata(&path) {
+                Ok(metadata) if metadata.is_dir() => {
+                    return Err(WeatherError::StorageError(format!(
+                        "{} is a directory; not removed",
+                        path.display()
+                    )))
+                }
+                Ok(metadata) => metadata.len(),
+                Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                Err(e) => return Err(storage_error(e)),
+            };
+            fs::remove_file(&path).map_err(storage_error)?;
+            removed.push(StateFile {
+                item,
+                path,
+                size: Some(size),
+            });
+        }
+        Ok(removed)
+    }
+}
+
+/// Rejects directories clearing must never work in
+fn check_app_dir(dir: &Path) -> Result<(), WeatherError> {
+    let home = dirs::home_dir();
+    let suspicious = !dir.is_absolute()
+        || dir.parent().is_none()
+        || home.as_deref().is_some_and(|home| home.starts_with(dir));
+    if suspicious {
+        return Err(WeatherError::StorageError(format!(
+            "Refusing to use {} as an app directory",
+            dir.display()
+        )));
+    }
+    Ok(())
+}
+
+/// "1.2 MB", "340 B"
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// App directories under a fresh temporary directory, with every file present
+    fn populated(name: &str) -> (PathBuf, AppDirs) {
+        let root =
+            std::env::temp_dir().join(format!("weather-app-state-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let dirs = AppDirs::new(root.join("config"), root.join("cache")).unwrap();
+        for item in StateItem::ALL {
+            let path = dirs.path(item);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "{}").unwrap();
+        }
+        (root, dirs)
+    }
+
+    #[test]
+    fn test_selective_clear() {
+        let (root, dirs) = populated("selective");
+        let removed = dirs.clear(&StateItem::CACHES).unwrap();
+        assert_eq!(removed.len(), 3);
+        assert_eq!(removed[0].item, StateItem::WeatherSnapshot);
+        assert_eq!(removed[1].item, StateItem::OfflineWeather);
+        assert_eq!(removed[2].item, StateItem::GeocodeCache);
+        assert_eq!(removed[0].size, Some(2));
+
+        let present: Vec<StateItem> = dirs
+            .files()
+            .into_iter()
+            .filter(|file| file.size.is_some())
+            .map(|file| file.item)
+            .collect();
+        assert_eq!(
+            present,
+            [
+                StateItem::Settings,
+                StateItem::Favorites,
+                StateItem::UsageHistory
+            ]
+        );
+        // Clearing again finds nothing to remove
+        assert_eq!(dirs.clear(&StateItem::CACHES).unwrap(), vec![]);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_full_clear_keeps_favorites() {
+        let (root, dirs) = populated("full");
+        assert!(dirs.clear(&StateItem::ALL).is_err());
+
+        let all_but_favorites = [
+            StateItem::Settings,
+            StateItem::UsageHistory,
+            StateItem::WeatherSnapshot,
+            StateItem::OfflineWeather,
+            StateItem::GeocodeCache,
+        ];
+        assert_eq!(dirs.clear(&all_but_favorites).unwrap().len(), 5);
+        let files = dirs.files();
+        assert!(files
+            .iter()
+            .all(|file| file.size.is_some() == (file.item == StateItem::Favorites)));
+        // Only files go; the directories stay
+        assert!(dirs.config_dir().is_dir() && dirs.cache_dir().is_dir());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_favorites_file_override_is_listed() {
+        let (root, dirs) = populated("override");
+        let dirs = dirs.with_favorites_file(Some(root.join("elsewhere.json")));
+        assert_eq!(dirs.path(StateItem::Favorites), root.join("elsewhere.json"));
+        assert_eq!(dirs.files()[1].size, None);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_suspicious_directories_are_refused() {
+        assert!(AppDirs::new("/", "/tmp/weather-app").is_err());
+        assert!(AppDirs::new("relative/weather-app", "/tmp/weather-app").is_err());
+        if let Some(home) = dirs::home_dir() {
+            assert!(AppDirs::new(&home, "/tmp/weather-app").is_err());
+            assert!(AppDirs::new("/tmp/weather-app", home.parent().unwrap_or(&home)).is_err());
+            assert!(AppDirs::new(home.join(".config/weather-app"), "/tmp/weather-app").is_ok());
+        }
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(340), "340 B");
+        assert_eq!(format_size(2048), "2.0 KB");
+        assert_eq!(format_size(5 * 1024 * 1024 + 512 * 1024), "5.5 MB");
+    }
+}
@@ -0,0 +1,1008 @@
+//! Async (tokio) weather repository for fetching many locations concurrently
+//!
+//! Mirrors `ApiWeatherRepository` request for request; URL construction and
+//! response parsing are shared with the blocking implementation.
+//! `AsyncWeatherRepository` is the async counterpart of `WeatherRepository`,
+//! and `BlockingWeatherRepository` runs any implementation of it where a
+//! blocking repository is expected, such as `ClController`.
+
+use chrono::Utc;
+use futures_util::future::{self, Future};
+use futures_util::stream::{self, StreamExt};
+use reqwest::{Client, Response, StatusCode};
+use serde_json::Value;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::runtime::{Handle, Runtime};
+
+use crate::errors::WeatherError;
+use crate::models::air_quality::AirQuality;
+use crate::models::forecast_model::ForecastModel;
+use crate::models::marine::MarineForecast;
+use crate::models::timings::{FetchStrategy, FetchTimings};
+use crate::models::weather_info::WeatherInfo;
+use crate::repositories::air_quality;
+use crate::repositories::backoff::{Backoff, RequestRole};
+use crate::repositories::data_selection::DataSelection;
+use crate::repositories::ensemble;
+use crate::repositories::fetch_options::FetchOptions;
+use crate::repositories::geocode_cache::GeocodeCache;
+use crate::repositories::geocoding::{self, Geocoder, GeocodingProvider};
+use crate::repositories::marine;
+use crate::repositories::request_spacing::{self, Clock, SpacingMode};
+use crate::repositories::retry::{self, RetryPolicy};
+use crate::repositories::server_clock::ServerClock;
+use crate::repositories::user_agent::UserAgent;
+use crate::repositories::weather_repository::{
+    ApiWeatherRepository, ForecastResponse, GeocodeMatch, WeatherRepository,
+};
+use crate::utils::confidence::Confidence;
+use crate::utils::coords::{self, Coordinates};
+use crate::utils::shutdown::Shutdown;
+
+/// Async counterpart of `WeatherRepository`, for embedding in async applications
+pub trait AsyncWeatherRepository {
+    fn fetch_weather(
+        &self,
+        location: &str,
+    ) -> impl Future<Output = Result<WeatherInfo, WeatherError>> + Send;
+}
+
+/// Async counterpart of `ApiWeatherRepository`
+pub struct AsyncApiWeatherRepository {
+    client: Client,
+    retry_policy: RetryPolicy,
+    geocoder: GeocodingProvider,
+    geocoding_url: String,
+    /// Least time between requests to `geocoding_url`
+    geocoding_spacing: Duration,
+    /// Search geocoders in order, the first being `geocoder`, with the least
+    /// time between requests to each
+    geocoders: Vec<(GeocodingProvider, String, Duration)>,
+    nominatim_spacing: SpacingMode,
+    spacing_clock: Arc<dyn Clock>,
+    geocode_cache: Option<GeocodeCache>,
+    weather_url: String,
+    marine_url: String,
+    ensemble_url: String,
+    air_quality_url: String,
+    data_selection: DataSelection,
+    forecast_days: usize,
+    /// Hours of hourly data in the first half of a split forecast
+    hourly_hours: usize,
+    model: ForecastModel,
+    user_agent: UserAgent,
+    verbose: bool,
+    backoff: Backoff,
+    server_clock: ServerClock,
+}
+
+impl AsyncApiWeatherRepository {
+    /// Uses the same endpoints, variable selection, forecast days, split
+    /// hourly hours, model, timeouts, retry policy, Nominatim spacing and geocoding cache as a
+    /// configured blocking repository
+    pub fn from_config(repository: &ApiWeatherRepository) -> Self {
+        let (connect_timeout, request_timeout) = repository.timeouts();
+        AsyncApiWeatherRepository {
+            client: Client::builder()
+                .connect_timeout(connect_timeout)
+                .timeout(request_timeout)
+                .build()
+                .expect("HTTP client (TLS backend) failed to initialize"),
+            retry_policy: repository.retry_policy(),
+            geocoder: repository.geocoder(),
+            geocoding_url: repository.geocoding_url().to_string(),
+            geocoding_spacing: repository
+                .geocoding_spacing(repository.geocoder(), repository.geocoding_url()),
+            geocoders: repository
+                .geocoders()
+                .into_iter()
+                .map(|(geocoder, url)| {
+                    let spacing = repository.geocoding_spacing(geocoder, url);
+                    (geocoder, url.to_string(), spacing)
+                })
+                .collect(),
+            nominatim_spacing: repository.nominatim_spacing(),
+            spacing_clock: repository.spacing_clock(),
+            geocode_cache: repository.geocode_cache().cloned(),
+            weather_url: repository.weather_url().to_string(),
+            marine_url: repository.marine_url().to_string(),
+            ensemble_url: repository.ensemble_url().to_string(),
+            air_quality_url: repository.air_quality_url().to_string(),
+            data_selection: repository.data_selection().clone(),
+            forecast_days: repository.forecast_days(),
+            hourly_hours: repository.hourly_hours(),
+            model: repository.model(),
+            user_agent: repository.user_agent().clone(),
+            verbose: repository.verbose(),
+            backoff: Backoff::default(),
+            server_clock: ServerClock::default(),
+        }
+    }
+
+    async fn send(
+        &self,
+        url: &str,
+        service: &str,
+        spacing: Duration,
+        role: RequestRole,
+    ) -> Result<Response, WeatherError> {
+        let response = self.send_unchecked(url, spacing, role).await?;
+
+        if !response.status().is_success() {
+            return Err(Self::status_error(service, response).await);
+        }
+
+        Ok(response)
+    }
+
+    /// Each attempt waits for its turn at the host, `spacing` after the
+    /// previous request there from anywhere in the process; a retry refused
+    /// its turn gives up with the failure it was to repeat
+    async fn send_unchecked(
+        &self,
+        url: &str,
+        spacing: Duration,
+        role: RequestRole,
+    ) -> Result<Response, WeatherError> {
+        let mut attempt = 1;
+        let mut result = self.send_once(url, spacing, role).await;
+        loop {
+            let transient = match &result {
+                Ok(response) => retry::is_transient_status(response.status()),
+                Err(e) => retry::is_transient_error(e),
+            };
+            if !transient || !self.retry_policy.allows_retry(attempt) {
+                return result;
+            }
+            let delay = self.retry_policy.delay(attempt);
+            if self.verbose {
+                eprintln!("Note: request failed, retrying in {delay:.1?}");
+            }
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+            match self.send_once(url, spacing, role).await {
+                Err(WeatherError::Throttled { .. }) => return result,
+                retried => result = retried,
+            }
+        }
+    }
+
+    async fn send_once(
+        &self,
+        url: &str,
+        spacing: Duration,
+        role: RequestRole,
+    ) -> Result<Response, WeatherError> {
+        self.backoff.check()?;
+        let wait = request_spacing::reserve_turn(
+            url,
+            spacing,
+            self.nominatim_spacing,
+            self.spacing_clock.as_ref(),
+        )?;
+        tokio::time::sleep(wait).await;
+        let response = self
+            .client
+            .get(url)
+            .header("User-Agent", self.user_agent.header())
+            .send()
+            .await
+            .map_err(WeatherError::network)?;
+        self.server_clock
+            .on_response(response.headers(), Utc::now());
+        match self
+            .backoff
+            .on_response(response.status(), response.headers(), role)
+        {
+            Some(rate_limited) => Err(rate_limited),
+            None => Ok(response),
+        }
+    }
+
+    async fn status_error(service: &str, response: Response) -> WeatherError {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        ApiWeatherRepository::api_status_error(service, status, &body)
+    }
+
+    /// Coordinates of `location`, from the geocoding cache when it has them,
+    /// as in the blocking repository
+    async fn fetch_coordinates(&self, location: &str) -> Result<GeocodeMatch, WeatherError> {
+        if let Some(found) = self
+            .geocode_cache
+            .as_ref()
+            .and_then(|cache| cache.get(self.geocoder, location, Utc::now()))
+        {
+            return Ok(found);
+        }
+        let found = self.lookup(location).await?;
+        if let Some(cache) = &self.geocode_cache {
+            if let Err(e) = cache.insert(self.geocoder, location, &found, Utc::now()) {
+                if self.verbose {
+                    eprintln!("Note: geocoding result not cached: {e}");
+                }
+            }
+        }
+        Ok(found)
+    }
+
+    /// Asks each geocoder in turn, as the blocking repository does
+    async fn lookup(&self, location: &str) -> Result<GeocodeMatch, WeatherError> {
+        let mut failures = Vec::new();
+        for (geocoder, base_url, spacing) in &self.geocoders {
+            match self.search(*geocoder, base_url, *spacing, location).await {
+                Ok(found) => return Ok(found),
+                Err(e) => {
+                    if self.verbose {
+                        eprintln!("Note: {} geocoding failed: {e}", geocoder.label());
+                    }
+                    failures.push((*geocoder, e));
+                }
+            }
+        }
+        Err(geocoding::combine_failures(location, failures))
+    }
+
+    async fn search(
+        &self,
+        geocoder: GeocodingProvider,
+        base_url: &str,
+        spacing: Duration,
+        location: &str,
+    ) -> Result<GeocodeMatch, WeatherError> {
+        let url = geocoder.search_url(base_url, location, &self.user_agent);
+
+        let json: Value = self
+            .send(&url, "Geocoding", spacing, RequestRole::Primary)
+            .await?
+            .json()
+            .await
+            .map_err(WeatherError::body)?;
+
+        let found = geocoder.parse(json, location)?;
+        found.log_skipped(self.verbose);
+        Ok(found)
+    }
+
+    /// Reverse geocoded name for pasted coordinates, or the formatted
+    /// coordinates, as in the blocking repository
+    async fn coordinates_name(&self, coordinates: Coordinates) -> String {
+        let Some(url) =
+            self.geocoder
+                .reverse_url(&self.geocoding_url, coordinates, &self.user_agent)
+        else {
+            return coordinates.to_string();
+        };
+        let json: Result<Value, WeatherError> = match self
+            .send(
+                &url,
+                "Reverse geocoding",
+                self.geocoding_spacing,
+                RequestRole::Secondary,
+            )
+            .await
+        {
+            Ok(response) => response.json().await.map_err(WeatherError::body),
+            Err(e) => Err(e),
+        };
+        match json {
+            Ok(json) => geocoding::reverse_name(&json)
+                .map_or_else(|| coordinates.to_string(), geocoding::short_name),
+            Err(e) => {
+                if self.verbose {
+                    eprintln!("Note: no place name for {coordinates}: {e}");
+                }
+                coordinates.to_string()
+            }
+        }
+    }
+
+    /// Forecast days and model from the blocking repository's settings
+    fn options(&self) -> FetchOptions {
+        FetchOptions::new()
+            .with_forecast_days(self.forecast_days as u8)
+            .with_model(self.model)
+    }
+
+    /// Requests the forecast, split in two for long horizons, as the
+    /// blocking repository does
+    async fn fetch_forecast(
+        &self,
+        lat: f64,
+        lon: f64,
+        timezone: Option<&str>,
+    ) -> Result<ForecastResponse, WeatherError> {
+        let options = self.options();
+        if ApiWeatherRepository::splits_forecast(&self.data_selection, &options) {
+            if let Some(split) = self
+                .fetch_split_forecast(lat, lon, timezone, &options)
+                .await?
+            {
+                return Ok(split);
+            }
+        }
+        self.fetch_single_forecast(lat, lon, timezone, &options)
+            .await
+    }
+
+    /// Both halves of a split forecast at once, merged; `None` when the
+    /// server rejects the granular parameters
+    async fn fetch_split_forecast(
+        &self,
+        lat: f64,
+        lon: f64,
+        timezone: Option<&str>,
+        options: &FetchOptions,
+    ) -> Result<Option<ForecastResponse>, WeatherError> {
+        let (hourly_url, daily_url) = ApiWeatherRepository::build_split_weather_api_urls(
+            &self.weather_url,
+            lat,
+            lon,
+            &self.data_selection,
+            timezone,
+            options,
+            self.hourly_hours,
+        );
+        let (hourly, daily) = future::join(
+            self.fetch_forecast_part(&hourly_url),
+            self.fetch_forecast_part(&daily_url),
+        )
+        .await;
+        let (Some(hourly), Some(daily)) = (hourly?, daily?) else {
+            return Ok(None);
+        };
+
+        let weather = ApiWeatherRepository::merge_split_forecast(
+            ApiWeatherRepository::parse_forecast_body(&hourly, self.verbose)?,
+            ApiWeatherRepository::parse_forecast_body(&daily, self.verbose)?,
+        );
+        Ok(Some(ForecastResponse {
+            weather,
+            reduced_data: false,
+            strategy: FetchStrategy::Split,
+            bytes: hourly.len() + daily.len(),
+        }))
+    }
+
+    /// Body of one part of a split forecast, or `None` for a 400 response
+    async fn fetch_forecast_part(&self, url: &str) -> Result<Option<String>, WeatherError> {
+        let response = self
+            .send_unchecked(url, Duration::ZERO, RequestRole::Primary)
+            .await?;
+        if response.status() == StatusCode::BAD_REQUEST {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(Self::status_error("Weather", response).await);
+        }
+        let body = response.text().await.map_err(WeatherError::body)?;
+        Ok(Some(body))
+    }
+
+    /// Same legacy fallback as the blocking repository, and none for a
+    /// pinned model
+    async fn fetch_single_forecast(
+        &self,
+        lat: f64,
+        lon: f64,
+        timezone: Option<&str>,
+        options: &FetchOptions,
+    ) -> Result<ForecastResponse, WeatherError> {
+        let url = ApiWeatherRepository::build_weather_api_url(
+            &self.weather_url,
+            lat,
+            lon,
+            &self.data_selection,
+            timezone,
+            options,
+        );
+        let response = self
+            .send_unchecked(&url, Duration::ZERO, RequestRole::Primary)
+            .await?;
+
+        let (response, reduced_data) =
+            if response.status() == StatusCode::BAD_REQUEST && !options.model.is_pinned() {
+                let legacy_url = ApiWeatherRepository::build_legacy_weather_api_url(
+                    &self.weather_url,
+                    lat,
+                    lon,
+                    timezone,
+                    options,
+                );
+                (
+                    self.send(&legacy_url, "Weather", Duration::ZERO, RequestRole::Primary)
+                        .await?,
+                    true,
+                )
+            } else if !response.status().is_success() {
+                return Err(Self::status_error("Weather", response).await);
+            } else {
+                (response, false)
+            };
+
+        let body = response.text().await.map_err(WeatherError::body)?;
+        Ok(ForecastResponse {
+            weather: ApiWeatherRepository::parse_forecast_body(&body, self.verbose)?,
+            reduced_data,
+            strategy: FetchStrategy::Single,
+            bytes: body.len(),
+        })
+    }
+
+    /// Same inland handling as the blocking repository
+    async fn fetch_marine(
+        &self,
+        lat: f64,
+        lon: f64,
+        timezone: Option<&str>,
+    ) -> Option<MarineForecast> {
+        let url = marine::marine_url(&self.marine_url, lat, lon, timezone);
+        let result = match self
+            .send_unchecked(&url, Duration::ZERO, RequestRole::Secondary)
+            .await
+        {
+            Ok(response) => {
+                let status = response.status();
+                match response.text().await {
+                    Ok(body) => marine::parse_marine_response(status, &body),
+                    Err(e) => Err(WeatherError::body(e)),
+                }
+            }
+            Err(e) => Err(e),
+        };
+        ApiWeatherRepository::marine_or_note(result, self.verbose)
+    }
+
+    /// Same failure tolerance as the blocking repository
+    async fn fetch_air_quality(
+        &self,
+        lat: f64,
+        lon: f64,
+        timezone: Option<&str>,
+    ) -> Option<AirQuality> {
+        let url = air_quality::air_quality_url(&self.air_quality_url, lat, lon, timezone);
+        let result = match self
+            .send_unchecked(&url, Duration::ZERO, RequestRole::Secondary)
+            .await
+        {
+            Ok(response) => {
+                let status = response.status();
+                match response.text().await {
+                    Ok(body) => air_quality::parse_air_quality_response(status, &body),
+                    Err(e) => Err(WeatherError::body(e)),
+                }
+            }
+            Err(e) => Err(e),
+        };
+        ApiWeatherRepository::air_quality_or_note(result, self.verbose)
+    }
+
+    /// Same failure tolerance as the blocking repository
+    async fn fetch_confidence(
+        &self,
+        lat: f64,
+        lon: f64,
+        timezone: Option<&str>,
+    ) -> Option<Vec<(String, Confidence)>> {
+        let url =
+            ensemble::ensemble_url(&self.ensemble_url, lat, lon, timezone, self.forecast_days);
+        let result = match self
+            .send_unchecked(&url, Duration::ZERO, RequestRole::Secondary)
+            .await
+        {
+            Ok(response) => {
+                let status = response.status();
+                match response.text().await {
+                    Ok(body) => ensemble::parse_ensemble_response(status, &body),
+                    Err(e) => Err(WeatherError::body(e)),
+                }
+            }
+            Err(e) => Err(e),
+        };
+        ApiWeatherRepository::confidence_or_note(result, self.verbose)
+    }
+
+    pub async fn fetch_weather(&self, location: &str) -> Result<WeatherInfo, WeatherError> {
+        let location = location.trim();
+        let started = Instant::now();
+        let coordinates = coords::parse(location);
+        let (found, geocoding) = match coordinates {
+            Some(coordinates) => (GeocodeMatch::at(coordinates), None),
+            None => {
+                let (found, elapsed) = timed(self.fetch_coordinates(location)).await;
+                (found?, Some(elapsed))
+            }
+        };
+        let (lat, lon) = (found.latitude, found.longitude);
+        let timezone = found.timezone.as_deref();
+
+        // Pasted coordinates only need a name, looked up alongside the forecast
+        let name = async {
+            match coordinates {
+                Some(coordinates) => self.coordinates_name(coordinates).await,
+                None => found.resolved_name(location),
+            }
+        };
+
+        // As in the blocking repository, secondary requests run alongside the forecast
+        let marine = async {
+            if self.data_selection.marine {
+                Some(timed(self.fetch_marine(lat, lon, timezone)).await)
+            } else {
+                None
+            }
+        };
+        let confidence = async {
+            if self.data_selection.confidence {
+                Some(timed(self.fetch_confidence(lat, lon, timezone)).await)
+            } else {
+                None
+            }
+        };
+        let air = async {
+            if self.data_selection.air_quality {
+                Some(timed(self.fetch_air_quality(lat, lon, timezone)).await)
+            } else {
+                None
+            }
+        };
+        let (name, ((forecast, forecast_time), marine, confidence, air)) = future::join(
+            name,
+            future::join4(
+                timed(self.fetch_forecast(lat, lon, timezone)),
+                marine,
+                confidence,
+                air,
+            ),
+        )
+        .await;
+        let forecast = forecast?;
+        let (marine, marine_time) =
+            marine.map_or((None, None), |(marine, elapsed)| (marine, Some(elapsed)));
+        let (confidence, confidence_time) = confidence
+            .map_or((None, None), |(confidence, elapsed)| {
+                (confidence, Some(elapsed))
+            });
+        let (air, air_time) = air.map_or((None, None), |(air, elapsed)| (air, Some(elapsed)));
+        let mut info = ApiWeatherRepository::build_weather_info(
+            &name,
+            lat,
+            lon,
+            forecast.weather,
+            forecast.reduced_data,
+            self.verbose,
+        )?;
+        info.query = Some(location.to_string());
+        info.marine = marine;
+        info.air_quality = air;
+        info.model = self.model;
+        if let Some(confidence) = confidence {
+            ensemble::apply_confidence(&mut info.weather_data.daily, &confidence);
+        }
+        info.clock_skew = self.server_clock.skew();
+        info.timings = Some(
+            FetchTimings::new(geocoding, forecast_time, marine_time, started.elapsed())
+                .with_confidence(confidence_time)
+                .with_air_quality(air_time)
+                .with_forecast_payload(forecast.strategy, forecast.bytes),
+        );
+        Ok(info)
+    }
+
+    /// Fetches every location with at most `concurrency` requests in flight.
+    /// Results are returned in input order regardless of completion order. Once
+    /// shutdown is requested, or the server has rate-limited a location, no
+    /// new requests start, and only the leading run of completed locations is
+    /// returned.
+    pub async fn fetch_batch(
+        &self,
+        locations: &[String],
+        concurrency: usize,
+        shutdown: &Shutdown,
+    ) -> Vec<Result<WeatherInfo, WeatherError>> {
+        let rate_limited = AtomicBool::new(false);
+        let rate_limited = &rate_limited;
+        let mut results: Vec<_> = stream::iter(locations.iter().enumerate())
+            .take_while(|_| {
+                std::future::ready(!shutdown.is_requested() && !rate_limited.load(Ordering::SeqCst))
+            })
+            .map(|(index, location)| async move {
+                let result = self.fetch_weather(location).await;
+                if matches!(result, Err(WeatherError::RateLimited { .. })) {
+                    rate_limited.store(true, Ordering::SeqCst);
+                }
+                (index, result)
+            })
+            .buffer_unordered(concurrency.max(1))
+            .collect()
+            .await;
+
+        results.sort_by_key(|(index, _)| *index);
+        results
+            .into_iter()
+            .enumerate()
+            .take_while(|(position, (index, _))| position == index)
+            .map(|(_, (_, result))| result)
+            .collect()
+    }
+}
+
+impl AsyncWeatherRepository for AsyncApiWeatherRepository {
+    fn fetch_weather(
+        &self,
+        location: &str,
+    ) -> impl Future<Output = Result<WeatherInfo, WeatherError>> + Send {
+        AsyncApiWeatherRepository::fetch_weather(self, location)
+    }
+}
+
+/// A blocking `WeatherRepository` that runs an async one to completion on a
+/// tokio runtime. Must not be called from inside an async task, where
+/// blocking on the runtime panics.
+pub struct BlockingWeatherRepository<R> {
+    inner: R,
+    handle: Handle,
+    /// Runtime started by `new`, kept alive for `handle`
+    _runtime: Option<Runtime>,
+}
+
+impl<R: AsyncWeatherRepository> BlockingWeatherRepository<R> {
+    /// Runs `inner` on a runtime of its own with one worker thread
+    pub fn new(inner: R) -> io::Result<Self> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()?;
+        Ok(BlockingWeatherRepository {
+            inner,
+            handle: runtime.handle().clone(),
+            _runtime: Some(runtime),
+        })
+    }
+
+    /// Runs `inner` on an existing multi-threaded runtime, whose workers
+    /// drive the requests while the caller blocks
+    pub fn with_handle(inner: R, handle: Handle) -> Self {
+        BlockingWeatherRepository {
+            inner,
+            handle,
+            _runtime: None,
+        }
+    }
+
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+}
+
+impl<R: AsyncWeatherRepository> WeatherRepository for BlockingWeatherRepository<R> {
+    fn fetch_weather(&self, location: &str) -> Result<WeatherInfo, WeatherError> {
+        self.handle.block_on(self.inner.fetch_weather(location))
+    }
+}
+
+/// Awaits `future`, returning its output and how long it took
+async fn timed<T>(future: impl Future<Output = T>) -> (T, Duration) {
+    let started = Instant::now();
+    let output = future.await;
+    (output, started.elapsed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::cl_controller::ClController;
+    use crate::repositories::weather_repository::WeatherRepository;
+    use crate::test_support::{MockRoute, MockServer, SimulatedClock};
+    use crate::views::cl_view::ClView;
+
+    fn forecast(temperature: f64) -> serde_json::Value {
+        serde_json::json!({
+            "current": {"temperature_2m": temperature, "weather_code": 3},
+            "daily": {"time": ["2024-06-01"], "temperature_2m_max": [temperature + 4.0]}
+        })
+    }
+
+    #[test]
+    fn test_marine_runs_alongside_the_forecast() {
+        let delay = Duration::from_millis(300);
+        let server = MockServer::start(vec![
+            MockRoute::new("/v1/forecast")
+                .json(forecast(24.0))
+                .delay(delay),
+            MockRoute::new("/v1/marine")
+                .json(serde_json::json!({"hourly": {
+                    "time": ["2024-06-01T00:00"], "wave_height": [1.4]
+                }}))
+                .delay(delay),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+            .with_marine_url(server.url("/v1/marine"))
+            .with_data_selection(DataSelection::full().with_marine());
+        let async_repository = AsyncApiWeatherRepository::from_config(&repository);
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let info = runtime
+            .block_on(async_repository.fetch_weather("-33.87, 151.21"))
+            .unwrap();
+        let timings = info.timings.unwrap();
+        assert!(info.marine.is_some());
+        assert!(timings.marine.unwrap() >= delay);
+        assert!(timings.total < delay * 2 - delay / 3, "{timings}");
+    }
+
+    #[test]
+    fn test_retry_policy_carries_over_from_blocking_config() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/v1/forecast").status(502).times(1),
+            MockRoute::new("/v1/forecast").json(forecast(8.0)),
+        ]);
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let retrying = AsyncApiWeatherRepository::from_config(
+            &ApiWeatherRepository::new()
+                .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+                .with_retry_policy(RetryPolicy::new(2, Duration::from_millis(10))),
+        );
+        assert!(runtime
+            .block_on(retrying.fetch_weather("46.02, 7.75"))
+            .is_ok());
+        assert_eq!(server.requests_to("/v1/forecast").len(), 2);
+
+        let server_errors = MockServer::start(vec![MockRoute::new("/v1/forecast").status(502)]);
+        let single = AsyncApiWeatherRepository::from_config(
+            &ApiWeatherRepository::new()
+                .with_endpoints(
+                    server_errors.url("/search"),
+                    server_errors.url("/v1/forecast"),
+                )
+                .with_retry_policy(RetryPolicy::none()),
+        );
+        assert!(runtime
+            .block_on(single.fetch_weather("46.02, 7.75"))
+            .is_err());
+        assert_eq!(server_errors.requests_to("/v1/forecast").len(), 1);
+    }
+
+    #[test]
+    fn test_long_forecast_is_split_on_both_backends() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/v1/forecast?latitude=51.5&longitude=-0.12&current=")
+                .json(forecast(8.0)),
+            MockRoute::new("/v1/forecast?latitude=51.5&longitude=-0.12&daily=").json(forecast(8.0)),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+            .with_forecast_days(10);
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let blocking = repository.fetch_weather("51.5, -0.12").unwrap();
+        assert_eq!(server.requests_to("/v1/forecast").len(), 2);
+        let async_repository = AsyncApiWeatherRepository::from_config(&repository);
+        let concurrent = runtime
+            .block_on(async_repository.fetch_weather("51.5, -0.12"))
+            .unwrap();
+        let requests = server.requests_to("/v1/forecast");
+        assert_eq!(requests.len(), 4);
+        assert_eq!(
+            requests[2..]
+                .iter()
+                .filter(|r| r.contains("&daily="))
+                .count(),
+            1
+        );
+
+        assert_eq!(concurrent.weather_data, blocking.weather_data);
+        let (blocking, concurrent) = (blocking.timings.unwrap(), concurrent.timings.unwrap());
+        assert_eq!(concurrent.strategy, FetchStrategy::Split);
+        assert_eq!(concurrent.forecast_bytes, blocking.forecast_bytes);
+    }
+
+    #[test]
+    fn test_timeouts_carry_over_from_blocking_config() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(format!("{url}/search"), format!("{url}/v1/forecast"))
+            .with_timeouts(Duration::from_secs(1), Duration::from_millis(200))
+            .with_retry_policy(RetryPolicy::none());
+        let async_repository = AsyncApiWeatherRepository::from_config(&repository);
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let error = runtime
+            .block_on(async_repository.fetch_weather("Zermatt"))
+            .unwrap_err();
+        assert!(matches!(error, WeatherError::Timeout(_)), "{error:?}");
+        drop(listener);
+    }
+
+    #[test]
+    fn test_batch_matches_blocking_path() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/search?q=Oslo")
+                .json(serde_json::json!([{"lat": "59.9", "lon": "10.7"}])),
+            MockRoute::new("/search?q=Bergen")
+                .json(serde_json::json!([{"lat": "60.4", "lon": "5.3"}])),
+            MockRoute::new("/search?q=Tromso")
+                .json(serde_json::json!([{"lat": "69.6", "lon": "18.9"}])),
+            MockRoute::new("/search").json(serde_json::json!([])),
+            MockRoute::new("/v1/forecast?latitude=59.9").json(forecast(18.0)),
+            MockRoute::new("/v1/forecast?latitude=60.4").json(forecast(14.0)),
+            MockRoute::new("/v1/forecast?latitude=69.6").json(forecast(9.0)),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+            .with_data_selection(DataSelection::compact());
+        let locations: Vec<String> = ["Oslo", "Nowhere", "Bergen", " Tromso "]
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+
+        let blocking: Vec<_> = locations
+            .iter()
+            .map(|l| repository.fetch_weather(l.trim()))
+            .collect();
+        let async_repository = AsyncApiWeatherRepository::from_config(&repository);
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let concurrent =
+            runtime.block_on(async_repository.fetch_batch(&locations, 3, &Shutdown::default()));
+
+        let expected = ClView::batch_lines(&locations, &blocking);
+        assert_eq!(ClView::batch_lines(&locations, &concurrent), expected);
+        assert_eq!(expected[1], "Nowhere: Location 'Nowhere' not found");
+        assert!(expected[3].starts_with("Tromso: 9°C"));
+        assert!(!ClController::new(repository).show_batch(&locations));
+    }
+
+    #[test]
+    fn test_batch_stops_at_rate_limit_like_blocking_path() {
+        let rate_limiting = || {
+            MockServer::start(vec![MockRoute::new("/v1/forecast")
+                .status(429)
+                .header("Retry-After", "120")])
+        };
+        let repository = |server: &MockServer| {
+            ApiWeatherRepository::new()
+                .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+                .with_retry_policy(RetryPolicy::none())
+        };
+        let locations: Vec<String> = ["46.02, 7.75", "59.91, 10.75", "60.39, 5.32"]
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+
+        let server = rate_limiting();
+        assert!(!ClController::new(repository(&server)).show_batch(&locations));
+        assert_eq!(server.requests_to("/v1/forecast").len(), 1);
+        let server = rate_limiting();
+        let blocking_repository = repository(&server);
+        let blocking: Vec<_> = locations
+            .iter()
+            .map(|l| blocking_repository.fetch_weather(l))
+            .collect();
+
+        let server = rate_limiting();
+        let async_repository = AsyncApiWeatherRepository::from_config(&repository(&server));
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let concurrent =
+            runtime.block_on(async_repository.fetch_batch(&locations, 1, &Shutdown::default()));
+        // Nothing after the refused location is scheduled
+        assert_eq!(concurrent.len(), 1);
+        assert_eq!(server.requests_to("/v1/forecast").len(), 1);
+
+        let expected = ClView::batch_lines(&locations, &blocking);
+        assert_eq!(ClView::batch_lines(&locations, &concurrent), expected);
+        assert_eq!(expected.len(), 2);
+        assert!(
+            expected[1].ends_with("; skipped 59.91, 10.75, 60.39, 5.32"),
+            "{expected:?}"
+        );
+    }
+
+    #[test]
+    fn test_concurrent_nominatim_lookups_are_spaced() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/search").json(serde_json::json!([{"lat": "59.9", "lon": "10.7"}]))
+        ]);
+        let clock = Arc::new(SimulatedClock::default());
+        let interval = Duration::from_secs(1);
+        let async_repository = AsyncApiWeatherRepository::from_config(
+            &ApiWeatherRepository::new()
+                .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+                .with_nominatim_interval(interval)
+                .with_spacing_clock(clock.clone()),
+        );
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let (oslo, bergen) = runtime.block_on(future::join(
+            async_repository.fetch_coordinates("Oslo"),
+            async_repository.fetch_coordinates("Bergen"),
+        ));
+        assert!(oslo.is_ok() && bergen.is_ok());
+        assert_eq!(server.requests_to("/search").len(), 2);
+        // One goes at once, the other a slot later
+        assert_eq!(clock.waits(), [Duration::ZERO, interval]);
+
+        // A refusing repository fails instead of sleeping
+        let server = MockServer::start(vec![
+            MockRoute::new("/search").json(serde_json::json!([{"lat": "69.6", "lon": "18.9"}]))
+        ]);
+        let refusing = AsyncApiWeatherRepository::from_config(
+            &ApiWeatherRepository::new()
+                .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+                .with_spacing_clock(Arc::new(SimulatedClock::default()))
+                .with_nominatim_interval(Duration::from_secs(30))
+                .with_nominatim_spacing(SpacingMode::Fail),
+        );
+        runtime
+            .block_on(refusing.fetch_coordinates("Tromso"))
+            .unwrap();
+        assert!(runtime
+            .block_on(refusing.fetch_coordinates("Narvik"))
+            .is_err());
+        assert_eq!(server.requests_to("/search").len(), 1);
+    }
+
+    #[test]
+    fn test_geocode_cache_carries_over_from_blocking_config() {
+        let dir =
+            std::env::temp_dir().join(format!("weather-app-async-geocode-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let server = MockServer::start(vec![
+            MockRoute::new("/search?q=Oslo")
+                .json(serde_json::json!([{"lat": "59.9", "lon": "10.7"}])),
+            MockRoute::new("/search?q=Bergen")
+                .json(serde_json::json!([{"lat": "60.4", "lon": "5.3"}])),
+            MockRoute::new("/v1/forecast").json(forecast(12.0)),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+            .with_response_cache(false)
+            .with_geocode_cache(Some(GeocodeCache::new(dir.join("geocode_cache.json"))));
+        let locations = vec!["Oslo".to_string(), "Bergen".to_string()];
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let run = || {
+            let async_repository = AsyncApiWeatherRepository::from_config(&repository);
+            runtime.block_on(async_repository.fetch_batch(&locations, 2, &Shutdown::default()))
+        };
+
+        assert!(run().iter().all(Result::is_ok));
+        assert_eq!(server.requests_to("/search").len(), 2);
+        // A later batch finds both places in the cache file
+        let results = run();
+        assert_eq!(results[1].as_ref().unwrap().latitude, 60.4);
+        assert_eq!(server.requests_to("/search").len(), 2);
+        // The blocking repository reads what the async one stored
+        repository.fetch_weather("oslo").unwrap();
+        assert_eq!(server.requests_to("/search").len(), 2);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,208 @@
+//! Cooldown after a server asks clients to slow down
+//!
+//! A 429 or 503 response starts a cooldown, taken from its `Retry-After`
+//! header (seconds or an HTTP date) or `RATE_LIMIT_COOLDOWN` without one.
+//! Until it ends every request through the same repository fails at once
+//! with `RateLimited`, so a batch does not keep hitting a server that
+//! already refused it. Only the requests a fetch depends on start one:
+//! supplementary ones are refused by other services often enough that
+//! their 429s and 503s are reported without holding back the rest.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+
+use crate::constants::{RATE_LIMIT_COOLDOWN, RATE_LIMIT_MAX_COOLDOWN};
+use crate::errors::WeatherError;
+
+/// Whether a request's 429 or 503 starts the cooldown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RequestRole {
+    /// The forecast and geocoding searches it needs
+    Primary,
+    /// Data a fetch can do without, such as marine, air quality or place
+    /// names, and other providers' services
+    Secondary,
+}
+
+/// Shared by every request of one repository
+#[derive(Debug, Default)]
+pub(crate) struct Backoff {
+    until: Mutex<Option<Instant>>,
+}
+
+impl Backoff {
+    /// Time left in the current cooldown, if any
+    pub(crate) fn remaining(&self) -> Option<Duration> {
+        let mut until = self.until.lock().unwrap();
+        let remaining = until.map(|t| t.saturating_duration_since(Instant::now()));
+        match remaining {
+            Some(remaining) if !remaining.is_zero() => Some(remaining),
+            _ => {
+                *until = None;
+                None
+            }
+        }
+    }
+
+    /// `RateLimited` while a cooldown is running; call before each request
+    pub(crate) fn check(&self) -> Result<(), WeatherError> {
+        match self.remaining() {
+            Some(retry_after) => Err(WeatherError::RateLimited { retry_after }),
+            None => Ok(()),
+        }
+    }
+
+    /// Starts (or extends) a cooldown for 429 and 503 responses to primary
+    /// requests and returns the error to report for them; other statuses
+    /// pass through as `None`
+    pub(crate) fn on_response(
+        &self,
+        status: StatusCode,
+        headers: &HeaderMap,
+        role: RequestRole,
+    ) -> Option<WeatherError> {
+        if status != StatusCode::TOO_MANY_REQUESTS && status != StatusCode::SERVICE_UNAVAILABLE {
+            return None;
+        }
+        let retry_after = headers
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| parse_retry_after(value, Utc::now()))
+            .unwrap_or(RATE_LIMIT_COOLDOWN)
+            .min(RATE_LIMIT_MAX_COOLDOWN);
+        if role == RequestRole::Secondary {
+            return Some(WeatherError::RateLimited { retry_after });
+        }
+
+        let mut until = self.until.lock().unwrap();
+        let end = Instant::now() + retry_after;
+        if until.is_none_or(|current| current < end) {
+            *until = Some(end);
+        }
+        Some(WeatherError::RateLimited { retry_after })
+    }
+}
+
+/// `Retry-After` as delay-seconds or an HTTP date relative to `now`; dates
+/// in the past mean no wait
+pub(crate) fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (date.with_timezone(&Utc) - now)
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = DateTime::parse_from_rfc3339("2024-06-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            parse_retry_after("120", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Sat, 01 Jun 2024 12:01:30 GMT", now),
+            Some(Duration::from_secs(90))
+        );
+        assert_eq!(
+            parse_retry_after("Sat, 01 Jun 2024 11:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+        assert_eq!(parse_retry_after("-5", now), None);
+    }
+
+    #[test]
+    fn test_cooldown_from_status_and_header() {
+        let backoff = Backoff::default();
+        assert!(backoff.check().is_ok());
+        assert!(backoff
+            .on_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                &HeaderMap::new(),
+                RequestRole::Primary
+            )
+            .is_none());
+        assert!(backoff.check().is_ok());
+
+        // Without a header the default cooldown applies
+        let error = backoff.on_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            &HeaderMap::new(),
+            RequestRole::Primary,
+        );
+        assert!(matches!(
+            error,
+            Some(WeatherError::RateLimited { retry_after }) if retry_after == RATE_LIMIT_COOLDOWN
+        ));
+        assert!(backoff.remaining().unwrap() > RATE_LIMIT_COOLDOWN - Duration::from_secs(5));
+
+        // A shorter Retry-After does not cut a running cooldown short
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("1"));
+        backoff.on_response(
+            StatusCode::TOO_MANY_REQUESTS,
+            &headers,
+            RequestRole::Primary,
+        );
+        assert!(backoff.remaining().unwrap() > Duration::from_secs(5));
+        assert!(matches!(
+            backoff.check(),
+            Err(WeatherError::RateLimited { .. })
+        ));
+
+        // Absurd values are capped
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("999999"));
+        let error = backoff.on_response(
+            StatusCode::TOO_MANY_REQUESTS,
+            &headers,
+            RequestRole::Primary,
+        );
+        assert!(matches!(
+            error,
+            Some(WeatherError::RateLimited { retry_after }) if retry_after == RATE_LIMIT_MAX_COOLDOWN
+        ));
+    }
+
+    #[test]
+    fn test_secondary_requests_do_not_start_a_cooldown() {
+        let backoff = Backoff::default();
+        let error = backoff.on_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            &HeaderMap::new(),
+            RequestRole::Secondary,
+        );
+        assert!(matches!(error, Some(WeatherError::RateLimited { .. })));
+        assert!(backoff.check().is_ok());
+    }
+
+    #[test]
+    fn test_cooldown_expires() {
+        let backoff = Backoff::default();
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("0"));
+        backoff.on_response(
+            StatusCode::TOO_MANY_REQUESTS,
+            &headers,
+            RequestRole::Primary,
+        );
+        assert!(backoff.check().is_ok());
+        assert_eq!(backoff.remaining(), None);
+    }
+}
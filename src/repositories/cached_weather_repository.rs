@@ -0,0 +1,519 @@
+//! In-memory weather cache with stale-while-revalidate lookups
+
+use crate::constants::{WEATHER_CACHE_CAPACITY, WEATHER_CACHE_STALE_WINDOW, WEATHER_CACHE_TTL};
+use crate::errors::WeatherError;
+use crate::models::favorites::ResolvedPlace;
+use crate::models::weather_info::WeatherInfo;
+use crate::repositories::provider::WeatherProvider;
+use crate::repositories::weather_repository::WeatherRepository;
+use crate::utils::location::{normalize_location_key, CoordinateKey};
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug)]
+struct CacheEntry {
+    info: WeatherInfo,
+    fetched_at: Instant,
+    /// Value of `Entries::clock` at the last lookup or store
+    last_used: u64,
+}
+
+/// Cached places, at most `capacity` of them. Weather is kept per place, so
+/// queries that resolve to the same coordinates ("NYC" and "New York")
+/// share one entry.
+struct Entries {
+    map: HashMap<CoordinateKey, CacheEntry>,
+    /// Place each query (by `normalize_location_key`) resolved to
+    places: HashMap<String, CoordinateKey>,
+    capacity: usize,
+    clock: u64,
+}
+
+impl Entries {
+    fn new(capacity: usize) -> Self {
+        Entries {
+            map: HashMap::new(),
+            places: HashMap::new(),
+            capacity,
+            clock: 0,
+        }
+    }
+
+    /// Cached data for a query key and its age, marking the entry as
+    /// recently used
+    fn get(&mut self, key: &str) -> Option<(WeatherInfo, Duration)> {
+        self.clock += 1;
+        let clock = self.clock;
+        let place = self.places.get(key)?;
+        self.map.get_mut(place).map(|entry| {
+            entry.last_used = clock;
+            (entry.info.clone(), entry.fetched_at.elapsed())
+        })
+    }
+
+    /// Stores `info` for its place and the query key that found it,
+    /// dropping entries older than `max_age` and then the least recently
+    /// used ones until the cache is within its capacity
+    fn insert(&mut self, key: String, info: &WeatherInfo, max_age: Duration) {
+        self.clock += 1;
+        self.map
+            .retain(|_, entry| entry.fetched_at.elapsed() < max_age);
+        let place = info.coordinate_key();
+        self.places.insert(key, place);
+        self.map.insert(
+            place,
+            CacheEntry {
+                info: info.clone(),
+                fetched_at: Instant::now(),
+                last_used: self.clock,
+            },
+        );
+        while self.map.len() > self.capacity {
+            let Some(oldest) = self
+                .map
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(place, _)| *place)
+            else {
+                break;
+            };
+            self.map.remove(&oldest);
+        }
+        let map = &self.map;
+        self.places.retain(|_, place| map.contains_key(place));
+    }
+}
+
+type SharedEntries = Arc<Mutex<Entries>>;
+
+/// Wraps a repository and remembers results per place, found by the
+/// normalized query and shared by every query that resolved to it.
+///
+/// Entries younger than the TTL are served without a request. Older entries
+/// are still returned by `fetch_weather_swr` during the serve-stale window
+/// while a background request revalidates them. At most
+/// `WEATHER_CACHE_CAPACITY` locations are kept (see `with_capacity`), and
+/// entries past the serve-stale window are dropped whenever one is stored.
+/// `refresh` and `refresh_with` skip the lookup for an explicit refresh, and
+/// the inner repository's copies of recent responses with it.
+pub struct CachedWeatherRepository<R> {
+    inner: Arc<R>,
+    entries: SharedEntries,
+    ttl: Duration,
+    stale_window: Duration,
+}
+
+impl<R: WeatherRepository> CachedWeatherRepository<R> {
+    pub fn new(inner: R) -> Self {
+        CachedWeatherRepository {
+            inner: Arc::new(inner),
+            entries: Arc::new(Mutex::new(Entries::new(WEATHER_CACHE_CAPACITY))),
+            ttl: WEATHER_CACHE_TTL,
+            stale_window: WEATHER_CACHE_STALE_WINDOW,
+        }
+    }
+
+    /// Age below which cached data is served without revalidating
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// How long past the TTL stale data may still be shown while revalidating
+    pub fn with_stale_window(mut self, stale_window: Duration) -> Self {
+        self.stale_window = stale_window;
+        self
+    }
+
+    /// Most locations kept; the least recently used is evicted beyond that
+    pub fn with_capacity(self, capacity: usize) -> Self {
+        self.entries.lock().unwrap().capacity = capacity;
+        self
+    }
+
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    /// Sends later fetches to `inner`, keeping the cached places; fetches
+    /// already running finish with the repository they started on
+    pub fn set_inner(&mut self, inner: R) {
+        self.inner = Arc::new(inner);
+    }
+
+    /// Number of cached places
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Forgets every cached location, and the inner repository's copies of
+    /// recent responses
+    pub fn clear(&self) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.map.clear();
+        entries.places.clear();
+        self.inner.clear_response_cache();
+    }
+
+    /// Cached data and its age
+    fn cached(&self, key: &str) -> Option<(WeatherInfo, Duration)> {
+        self.entries.lock().unwrap().get(key)
+    }
+
+    /// Entries older than this are never served again
+    fn max_age(&self) -> Duration {
+        self.ttl + self.stale_window
+    }
+
+    /// Fetches `location` whatever is cached for it, here or as a recent
+    /// response of the inner repository, and caches the result, for an
+    /// explicit refresh
+    pub fn refresh(&self, location: &str) -> Result<WeatherInfo, WeatherError> {
+        self.inner.clear_response_cache();
+        let info = self.inner.fetch_weather(location)?;
+        self.entries.lock().unwrap().insert(
+            normalize_location_key(location),
+            &info,
+            self.max_age(),
+        );
+        Ok(info)
+    }
+}
+
+impl<R: WeatherRepository + Send + Sync + 'static> CachedWeatherRepository<R> {
+    /// Returns cached data immediately (fresh, or stale within the serve-stale
+    /// window) and delivers the up-to-date result on the receiver. Fresh hits
+    /// are delivered at once; otherwise a background thread fetches and caches.
+    pub fn fetch_weather_swr(
+        &self,
+        location: &str,
+    ) -> (
+        Option<WeatherInfo>,
+        Receiver<Result<WeatherInfo, WeatherError>>,
+    ) {
+        let (sender, receiver) = mpsc::channel();
+        // The requester may have moved on; nothing to do then
+        let cached = self.fetch_weather_swr_with(location, move |result| {
+            let _ = sender.send(result);
+        });
+        (cached, receiver)
+    }
+
+    /// `fetch_weather_swr` handing the up-to-date result to `deliver`
+    /// instead of a channel; `deliver` runs on the background thread, or
+    /// before this returns for a fresh hit
+    pub fn fetch_weather_swr_with(
+        &self,
+        location: &str,
+        deliver: impl FnOnce(Result<WeatherInfo, WeatherError>) + Send + 'static,
+    ) -> Option<WeatherInfo> {
+        let key = normalize_location_key(location);
+        let stale = match self.cached(&key) {
+            Some((info, age)) if age < self.ttl => {
+                deliver(Ok(info.clone()));
+                return Some(info);
+            }
+            Some((info, age)) if age < self.ttl + self.stale_window => Some(info),
+            _ => None,
+        };
+        self.revalidate(key, location, deliver);
+        stale
+    }
+
+    /// `refresh` in the background: fetches `location` even when its cached
+    /// data is fresh and hands the result to `deliver`
+    pub fn refresh_with(
+        &self,
+        location: &str,
+        deliver: impl FnOnce(Result<WeatherInfo, WeatherError>) + Send + 'static,
+    ) {
+        self.inner.clear_response_cache();
+        self.revalidate(normalize_location_key(location), location, deliver);
+    }
+
+    /// Fetches and caches on a background thread
+    fn revalidate(
+        &self,
+        key: String,
+        location: &str,
+        deliver: impl FnOnce(Result<WeatherInfo, WeatherError>) + Send + 'static,
+    ) {
+        let inner = Arc::clone(&self.inner);
+        let entries = Arc::clone(&self.entries);
+        let max_age = self.max_age();
+        let location = location.to_string();
+        thread::spawn(move || {
+            let result = inner.fetch_weather(&location);
+            if let Ok(info) = &result {
+                entries.lock().unwrap().insert(key, info, max_age);
+            }
+            deliver(result);
+        });
+    }
+}
+
+impl<R: WeatherRepository> WeatherRepository for CachedWeatherRepository<R> {
+    fn fetch_weather(&self, location: &str) -> Result<WeatherInfo, WeatherError> {
+        let key = normalize_location_key(location);
+        if let Some((info, age)) = self.cached(&key) {
+            if age < self.ttl {
+                return Ok(info);
+            }
+        }
+        self.refresh(location)
+    }
+
+    fn response_cache_hits(&self) -> usize {
+        self.inner.response_cache_hits()
+    }
+
+    fn clear_response_cache(&self) {
+        self.inner.clear_response_cache()
+    }
+
+    fn resolve_place(
+        &self,
+        location: &str,
+        recorded: Option<&ResolvedPlace>,
+    ) -> Result<Option<ResolvedPlace>, WeatherError> {
+        self.inner.resolve_place(location, recorded)
+    }
+
+    fn provider(&self) -> WeatherProvider {
+        self.inner.provider()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::sample_weather_info;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Answers after `delay`; the nth call reports a temperature of n °C
+    struct SlowRepository {
+        delay: Duration,
+        calls: AtomicUsize,
+    }
+
+    impl SlowRepository {
+        fn new(delay: Duration) -> Self {
+            SlowRepository {
+                delay,
+                calls: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    /// Somewhere different for every "Town n", New York for "NYC" and
+    /// "New York", and the sample's place for anything else
+    fn place_of(location: &str) -> Option<(f64, f64)> {
+        if let Some(n) = location.trim().strip_prefix("Town ") {
+            let n: u32 = n.parse().ok()?;
+            return Some((f64::from(n / 100), f64::from(n % 100)));
+        }
+        match location.trim() {
+            "NYC" => Some((40.7128, -74.0060)),
+            "New York" => Some((40.7143, -74.0058)),
+            _ => None,
+        }
+    }
+
+    impl WeatherRepository for SlowRepository {
+        fn fetch_weather(&self, location: &str) -> Result<WeatherInfo, WeatherError> {
+            thread::sleep(self.delay);
+            let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            let mut info = sample_weather_info();
+            if let Some((latitude, longitude)) = place_of(location) {
+                (info.latitude, info.longitude) = (latitude, longitude);
+            }
+            info.weather_data.current.temperature = Some(call as f64);
+            Ok(info)
+        }
+    }
+
+    fn temperature(info: &WeatherInfo) -> Option<f64> {
+        info.weather_data.current.temperature
+    }
+
+    /// Heap and inline size of the forecast vectors, ignoring strings
+    fn approximate_bytes(info: &WeatherInfo) -> usize {
+        use crate::models::weather_info::{DailyForecast, HourlyForecast};
+        std::mem::size_of::<CacheEntry>()
+            + info.weather_data.hourly.capacity() * std::mem::size_of::<HourlyForecast>()
+            + info.weather_data.daily.capacity() * std::mem::size_of::<DailyForecast>()
+    }
+
+    #[test]
+    fn test_fresh_entries_skip_the_network() {
+        let repository = CachedWeatherRepository::new(SlowRepository::new(Duration::ZERO));
+        repository.fetch_weather("Seattle").unwrap();
+        repository.fetch_weather(" seattle").unwrap();
+        repository.fetch_weather("Seattle,").unwrap();
+        assert_eq!(repository.inner().calls.load(Ordering::SeqCst), 1);
+
+        let (cached, receiver) = repository.fetch_weather_swr("SEATTLE");
+        assert_eq!(cached.as_ref().and_then(temperature), Some(1.0));
+        assert_eq!(temperature(&receiver.recv().unwrap().unwrap()), Some(1.0));
+        assert_eq!(repository.inner().calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_names_for_one_place_share_an_entry() {
+        let repository = CachedWeatherRepository::new(SlowRepository::new(Duration::ZERO));
+        repository.fetch_weather("NYC").unwrap();
+        // A new name has to be geocoded once to be recognized
+        let new_york = repository.fetch_weather("New York").unwrap();
+        assert_eq!(repository.len(), 1);
+        assert_eq!(temperature(&new_york), Some(2.0));
+
+        // Either name now finds the latest data without a fetch
+        assert_eq!(
+            temperature(&repository.fetch_weather("nyc").unwrap()),
+            Some(2.0)
+        );
+        let (cached, _) = repository.fetch_weather_swr("new york,");
+        assert_eq!(cached.as_ref().and_then(temperature), Some(2.0));
+        assert_eq!(repository.inner().calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_stale_value_arrives_first_then_fresh() {
+        let repository =
+            CachedWeatherRepository::new(SlowRepository::new(Duration::from_millis(100)))
+                .with_ttl(Duration::ZERO);
+
+        let (cached, receiver) = repository.fetch_weather_swr("Seattle");
+        assert!(cached.is_none());
+        assert_eq!(temperature(&receiver.recv().unwrap().unwrap()), Some(1.0));
+
+        let started = Instant::now();
+        let (stale, receiver) = repository.fetch_weather_swr("Seattle");
+        assert!(started.elapsed() < Duration::from_millis(100));
+        assert_eq!(stale.as_ref().and_then(temperature), Some(1.0));
+
+        let fresh = receiver.recv().unwrap().unwrap();
+        assert_eq!(temperature(&fresh), Some(2.0));
+        let (stale, _) = repository.fetch_weather_swr("Seattle");
+        assert_eq!(stale.as_ref().and_then(temperature), Some(2.0));
+    }
+
+    #[test]
+    fn test_refresh_bypasses_fresh_entries() {
+        let repository = CachedWeatherRepository::new(SlowRepository::new(Duration::ZERO));
+        repository.fetch_weather("Seattle").unwrap();
+        assert_eq!(
+            temperature(&repository.refresh("seattle").unwrap()),
+            Some(2.0)
+        );
+        // The refreshed data replaces the cached entry
+        assert_eq!(
+            temperature(&repository.fetch_weather("Seattle").unwrap()),
+            Some(2.0)
+        );
+
+        let (sender, receiver) = mpsc::channel();
+        repository.refresh_with("Seattle", move |result| sender.send(result).unwrap());
+        assert_eq!(temperature(&receiver.recv().unwrap().unwrap()), Some(3.0));
+        assert_eq!(
+            temperature(&repository.fetch_weather("Seattle").unwrap()),
+            Some(3.0)
+        );
+        assert_eq!(repository.inner().calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_refresh_skips_recent_responses() {
+        use crate::repositories::weather_repository::ApiWeatherRepository;
+        use crate::test_support::{MockRoute, MockServer};
+
+        let server = MockServer::start(vec![
+            MockRoute::new("/search").json(serde_json::json!([
+                {"lat": "47.61", "lon": "-122.33", "display_name": "Seattle, USA"}
+            ])),
+            MockRoute::new("/v1/forecast").json(serde_json::json!({
+                "current": {"temperature_2m": 12.0, "weather_code": 3}
+            })),
+        ]);
+        let repository = CachedWeatherRepository::new(
+            ApiWeatherRepository::new()
+                .with_endpoints(server.url("/search"), server.url("/v1/forecast")),
+        );
+        let forecasts = || server.requests_to("/v1/forecast").len();
+
+        repository.fetch_weather("Seattle").unwrap();
+        // Two refreshes well within the response cache's minute
+        repository.refresh("Seattle").unwrap();
+        let (sender, receiver) = mpsc::channel();
+        repository.refresh_with("Seattle", move |result| sender.send(result).unwrap());
+        receiver.recv().unwrap().unwrap();
+        assert_eq!(forecasts(), 3);
+        assert_eq!(repository.response_cache_hits(), 0);
+
+        // Clearing forgets the responses as well as the locations
+        repository.clear();
+        repository.fetch_weather("Seattle").unwrap();
+        assert_eq!(forecasts(), 4);
+    }
+
+    #[test]
+    fn test_cache_stays_within_capacity() {
+        let repository =
+            CachedWeatherRepository::new(SlowRepository::new(Duration::ZERO)).with_capacity(50);
+        let one_entry = approximate_bytes(&sample_weather_info());
+
+        for n in 0..5000 {
+            repository.fetch_weather(&format!("Town {n}")).unwrap();
+            // Seattle is looked up every time and never becomes least recently used
+            repository.fetch_weather("Seattle").unwrap();
+        }
+        assert_eq!(repository.len(), 50);
+        assert_eq!(repository.inner().calls.load(Ordering::SeqCst), 5001);
+
+        let entries = repository.entries.lock().unwrap();
+        assert!(entries.places.contains_key("seattle"));
+        assert!(entries.places.contains_key("town 4999"));
+        assert!(!entries.places.contains_key("town 0"));
+        assert_eq!(entries.places.len(), 50);
+        let bytes: usize = entries
+            .map
+            .values()
+            .map(|entry| approximate_bytes(&entry.info))
+            .sum();
+        assert!(bytes <= 50 * one_entry);
+    }
+
+    #[test]
+    fn test_expired_entries_are_dropped_on_store() {
+        let repository = CachedWeatherRepository::new(SlowRepository::new(Duration::ZERO))
+            .with_ttl(Duration::ZERO)
+            .with_stale_window(Duration::ZERO);
+        for n in 0..100 {
+            repository.fetch_weather(&format!("Town {n}")).unwrap();
+        }
+        assert_eq!(repository.len(), 1);
+
+        let (_, receiver) = repository.fetch_weather_swr("Seattle");
+        receiver.recv().unwrap().unwrap();
+        assert_eq!(repository.len(), 1);
+    }
+
+    #[test]
+    fn test_entries_past_the_stale_window_are_not_served() {
+        let repository = CachedWeatherRepository::new(SlowRepository::new(Duration::ZERO))
+            .with_ttl(Duration::ZERO)
+            .with_stale_window(Duration::ZERO);
+        repository.fetch_weather("Seattle").unwrap();
+
+        let (stale, receiver) = repository.fetch_weather_swr("Seattle");
+        assert!(stale.is_none());
+        assert_eq!(temperature(&receiver.recv().unwrap().unwrap()), Some(2.0));
+    }
+}
@@ -0,0 +1,287 @@
+//! Selection of Open-Meteo variables to request
+//!
+//! Smaller selections keep payloads down for callers that only render a subset
+//! of the data (compact summaries, current conditions only).
+
+use crate::constants::MINUTELY_FORECAST_STEPS;
+
+/// All current-condition variables the models understand
+pub(crate) const CURRENT_VARIABLES: &[&str] = &[
+    "temperature_2m",
+    "apparent_temperature",
+    "relative_humidity_2m",
+    "dew_point_2m",
+    "precipitation",
+    "snowfall",
+    "weather_code",
+    "wind_speed_10m",
+    "wind_direction_10m",
+    "wind_gusts_10m",
+    "cloud_cover",
+    "surface_pressure",
+    "pressure_msl",
+    "visibility",
+    "snow_depth",
+    "is_day",
+];
+
+/// All hourly variables the models understand
+pub(crate) const HOURLY_VARIABLES: &[&str] = &[
+    "temperature_2m",
+    "apparent_temperature",
+    "precipitation_probability",
+    "precipitation",
+    "weather_code",
+    "wind_speed_10m",
+    "wind_direction_10m",
+    "wind_gusts_10m",
+    "relative_humidity_2m",
+    "dew_point_2m",
+    "freezing_level_height",
+    "cape",
+    "snowfall",
+    "snow_depth",
+    "cloud_cover",
+    "visibility",
+    "uv_index",
+];
+
+/// Hourly winds aloft (80 m and 120 m), requested for aviation
+pub(crate) const AVIATION_HOURLY_VARIABLES: &[&str] = &[
+    "wind_speed_80m",
+    "wind_direction_80m",
+    "wind_speed_120m",
+    "wind_direction_120m",
+];
+
+/// 15-minute variables, requested only for the minutely forecast
+pub(crate) const MINUTELY_VARIABLES: &[&str] = &["temperature_2m", "precipitation", "weather_code"];
+
+/// All daily variables the models understand
+pub(crate) const DAILY_VARIABLES: &[&str] = &[
+    "temperature_2m_max",
+    "temperature_2m_min",
+    "weather_code",
+    "precipitation_sum",
+    "snowfall_sum",
+    "precipitation_probability_max",
+    "wind_speed_10m_max",
+    "wind_gusts_10m_max",
+    "sunrise",
+    "sunset",
+    "daylight_duration",
+    "sunshine_duration",
+    "et0_fao_evapotranspiration",
+    "relative_humidity_2m_mean",
+    "uv_index_max",
+];
+
+/// Variables requested for each section of the forecast; empty sections are omitted
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DataSelection {
+    pub current: Vec<&'static str>,
+    pub hourly: Vec<&'static str>,
+    pub daily: Vec<&'static str>,
+    /// 15-minute variables for the next `MINUTELY_FORECAST_STEPS` steps;
+    /// none unless asked for, as they add a series per quarter hour
+    pub minutely: Vec<&'static str>,
+    /// Also request waves from the marine API (a second request per location)
+    pub marine: bool,
+    /// Also request member temperatures from the ensemble API for the
+    /// confidence of each day (another request per location)
+    pub confidence: bool,
+    /// Also request pollutants and AQI from the air quality API (another
+    /// request per location)
+    pub air_quality: bool,
+}
+
+impl Default for DataSelection {
+    fn default() -> Self {
+        Self::full()
+    }
+}
+
+impl DataSelection {
+    /// Everything the views can display
+    pub fn full() -> Self {
+        DataSelection {
+            current: CURRENT_VARIABLES.to_vec(),
+            hourly: HOURLY_VARIABLES.to_vec(),
+            daily: DAILY_VARIABLES.to_vec(),
+            minutely: Vec::new(),
+            marine: false,
+            confidence: false,
+            air_quality: false,
+        }
+    }
+
+    /// Current conditions only, no hourly or daily series
+    pub fn current_only() -> Self {
+        DataSelection {
+            current: CURRENT_VARIABLES.to_vec(),
+            hourly: Vec::new(),
+            daily: Vec::new(),
+            minutely: Vec::new(),
+            marine: false,
+            confidence: false,
+            air_quality: false,
+        }
+    }
+
+    /// Just enough for a one-line summary: current temperature and conditions,
+    /// next-hour precipitation chance, and today's high/low and sun times
+    pub fn compact() -> Self {
+        DataSelection {
+            current: vec![
+                "temperature_2m",
+                "apparent_temperature",
+                "weather_code",
+                "is_day",
+            ],
+            hourly: vec!["precipitation_probability"],
+            daily: vec![
+                "temperature_2m_max",
+                "temperature_2m_min",
+                "sunrise",
+                "sunset",
+            ],
+            minutely: Vec::new(),
+            marine: false,
+            confidence: false,
+            air_quality: false,
+        }
+    }
+
+    /// The compact summary plus what the digest ranks by: humidity for heat
+    /// stress, hourly thunderstorm codes and CAPE, and today's precipitation
+    pub fn digest() -> Self {
+        let mut selection = Self::compact();
+        selection.current.push("relative_humidity_2m");
+        selection.hourly.extend(["weather_code", "cape"]);
+        selection
+            .daily
+            .extend(["precipitation_sum", "precipitation_probability_max"]);
+        selection
+    }
+
+    /// Adds the hourly winds-aloft variables for drone and aviation use
+    pub fn with_aviation(mut self) -> Self {
+        for variable in AVIATION_HOURLY_VARIABLES {
+            if !self.hourly.contains(variable) {
+                self.hourly.push(variable);
+            }
+        }
+        self
+    }
+
+    /// Adds the 15-minute forecast for the next hours
+    pub fn with_minutely(mut self) -> Self {
+        self.minutely = MINUTELY_VARIABLES.to_vec();
+        self
+    }
+
+    /// Adds the wave forecast for coastal locations
+    pub fn with_marine(mut self) -> Self {
+        self.marine = true;
+        self
+    }
+
+    /// Adds the ensemble request that rates each day's confidence
+    pub fn with_confidence(mut self) -> Self {
+        self.confidence = true;
+        self
+    }
+
+    /// Adds the air quality request
+    pub fn with_air_quality(mut self) -> Self {
+        self.air_quality = true;
+        self
+    }
+
+    /// Query string fragment (leading `&`) listing the selected variables
+    pub fn query_params(&self) -> String {
+        let mut params: String = [
+            ("current", &self.current),
+            ("hourly", &self.hourly),
+            ("daily", &self.daily),
+            ("minutely_15", &self.minutely),
+        ]
+        .iter()
+        .filter(|(_, variables)| !variables.is_empty())
+        .map(|(section, variables)| format!("&{section}={}", variables.join(",")))
+        .collect();
+        if !self.minutely.is_empty() {
+            params.push_str(&format!("&forecast_minutely_15={MINUTELY_FORECAST_STEPS}"));
+        }
+        params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_query_params() {
+        let params = DataSelection::full().query_params();
+        assert_eq!(
+            params,
+            format!(
+                "&current={}&hourly={}&daily={}",
+                CURRENT_VARIABLES.join(","),
+                HOURLY_VARIABLES.join(","),
+                DAILY_VARIABLES.join(",")
+            )
+        );
+    }
+
+    #[test]
+    fn test_current_only_query_params() {
+        let params = DataSelection::current_only().query_params();
+        assert_eq!(params, format!("&current={}", CURRENT_VARIABLES.join(",")));
+        assert!(!params.contains("hourly="));
+        assert!(!params.contains("daily="));
+    }
+
+    #[test]
+    fn test_aviation_query_params() {
+        let params = DataSelection::full().with_aviation().query_params();
+        assert!(params.contains(&format!(
+            "{},{}&daily=",
+            HOURLY_VARIABLES.join(","),
+            AVIATION_HOURLY_VARIABLES.join(",")
+        )));
+        assert_eq!(
+            DataSelection::full().with_aviation().with_aviation(),
+            DataSelection::full().with_aviation()
+        );
+    }
+
+    #[test]
+    fn test_minutely_query_params() {
+        assert!(!DataSelection::full().query_params().contains("minutely"));
+        let params = DataSelection::full().with_minutely().query_params();
+        assert!(params.ends_with(
+            "&minutely_15=temperature_2m,precipitation,weather_code&forecast_minutely_15=12"
+        ));
+    }
+
+    #[test]
+    fn test_compact_query_params() {
+        assert_eq!(
+            DataSelection::compact().query_params(),
+            "&current=temperature_2m,apparent_temperature,weather_code,is_day\
+             &hourly=precipitation_probability\
+             &daily=temperature_2m_max,temperature_2m_min,sunrise,sunset"
+        );
+    }
+
+    #[test]
+    fn test_digest_extends_compact() {
+        let compact = DataSelection::compact();
+        let digest = DataSelection::digest();
+        assert!(compact.hourly.iter().all(|v| digest.hourly.contains(v)));
+        assert!(digest.hourly.contains(&"cape"));
+        assert!(digest.daily.contains(&"precipitation_probability_max"));
+    }
+}
@@ -0,0 +1,195 @@
+//! Open-Meteo ensemble API: forecast confidence from the members' spread
+//!
+//! The response has one daily series per member, named like
+//! `temperature_2m_max_member07` next to the control run's
+//! `temperature_2m_max`. The days are matched to the forecast by date, so
+//! an ensemble starting on another day or covering fewer days still lines
+//! up; forecast days it does not cover keep no confidence.
+
+use std::collections::HashMap;
+
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use crate::constants::ENSEMBLE_MODEL;
+use crate::errors::WeatherError;
+use crate::models::weather_info::DailyForecast;
+use crate::repositories::sanitize;
+use crate::repositories::weather_repository::ApiWeatherRepository;
+use crate::utils::confidence::{self, Confidence};
+
+/// Daily variables requested from the ensemble API
+pub(crate) const ENSEMBLE_DAILY_VARIABLES: &[&str] = &["temperature_2m_max", "temperature_2m_min"];
+
+#[derive(Debug, Deserialize)]
+struct OpenMeteoEnsemble {
+    daily: Option<OpenMeteoEnsembleDaily>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenMeteoEnsembleDaily {
+    time: Vec<String>,
+    /// Every member's series, keyed by variable and member
+    #[serde(flatten)]
+    series: HashMap<String, Vec<Option<f64>>>,
+}
+
+impl OpenMeteoEnsembleDaily {
+    /// Values of every member of `variable` on day `day`
+    fn members(&self, variable: &str, day: usize) -> Vec<f64> {
+        let member_prefix = format!("{variable}_member");
+        self.series
+            .iter()
+            .filter(|(name, _)| *name == variable || name.starts_with(&member_prefix))
+            .filter_map(|(_, values)| values.get(day).copied().flatten())
+            .collect()
+    }
+}
+
+/// Ensemble request for the same point, time zone and days as the forecast
+pub(crate) fn ensemble_url(
+    base_url: &str,
+    lat: f64,
+    lon: f64,
+    timezone: Option<&str>,
+    days: usize,
+) -> String {
+    format!(
+        "{base_url}?latitude={lat}&longitude={lon}&models={ENSEMBLE_MODEL}&daily={}&forecast_days={days}&timezone={}",
+        ENSEMBLE_DAILY_VARIABLES.join(","),
+        timezone.map_or("auto".into(), urlencoding::encode)
+    )
+}
+
+/// Confidence per date from an ensemble response; dates with too few
+/// members are left out. `Err` for server failures and unreadable bodies.
+pub(crate) fn parse_ensemble_response(
+    status: StatusCode,
+    body: &str,
+) -> Result<Vec<(String, Confidence)>, WeatherError> {
+    if !status.is_success() {
+        return Err(ApiWeatherRepository::api_status_error(
+            "Ensemble", status, body,
+        ));
+    }
+
+    let (json, _) = sanitize::non_finite_to_null(body);
+    let ensemble: OpenMeteoEnsemble =
+        serde_json::from_str(&json).map_err(|e| WeatherError::ParseError(e.to_string()))?;
+    let Some(daily) = ensemble.daily else {
+        return Ok(Vec::new());
+    };
+
+    let [max, min] = [ENSEMBLE_DAILY_VARIABLES[0], ENSEMBLE_DAILY_VARIABLES[1]];
+    Ok(daily
+        .time
+        .iter()
+        .enumerate()
+        .filter_map(|(day, date)| {
+            let confidence =
+                confidence::day_confidence(&daily.members(max, day), &daily.members(min, day))?;
+            Some((date.clone(), confidence))
+        })
+        .collect())
+}
+
+/// Sets each forecast day's confidence from the ensemble day of the same date
+pub(crate) fn apply_confidence(daily: &mut [DailyForecast], confidence: &[(String, Confidence)]) {
+    for day in daily {
+        day.confidence = confidence
+            .iter()
+            .find(|(date, _)| *date == day.date)
+            .map(|(_, confidence)| *confidence);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An ensemble response with `members` members per variable, member `m`
+    /// reading `base + m * step` on every day
+    fn synthetic(dates: &[&str], members: usize, steps: &[f64]) -> String {
+        let mut daily = serde_json::Map::new();
+        daily.insert("time".into(), serde_json::json!(dates));
+        for (variable, base) in [("temperature_2m_max", 20.0), ("temperature_2m_min", 10.0)] {
+            for m in 0..members {
+                let name = if m == 0 {
+                    variable.to_string()
+                } else {
+                    format!("{variable}_member{m:02}")
+                };
+                let values: Vec<f64> = steps.iter().map(|step| base + m as f64 * step).collect();
+                daily.insert(name, serde_json::json!(values));
+            }
+        }
+        serde_json::json!({ "daily": daily }).to_string()
+    }
+
+    #[test]
+    fn test_confidence_per_day_from_member_spread() {
+        // 11 members: the interquartile range is 5 steps
+        let body = synthetic(
+            &["2025-12-10", "2025-12-11", "2025-12-12"],
+            11,
+            &[0.1, 0.5, 1.0],
+        );
+        let confidence = parse_ensemble_response(StatusCode::OK, &body).unwrap();
+        assert_eq!(
+            confidence,
+            [
+                ("2025-12-10".to_string(), Confidence::High),
+                ("2025-12-11".to_string(), Confidence::Medium),
+                ("2025-12-12".to_string(), Confidence::Low),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_member_values_and_short_ensembles() {
+        let body = r#"{"daily": {
+            "time": ["2025-12-10", "2025-12-11"],
+            "temperature_2m_max": [20.0, NaN],
+            "temperature_2m_max_member01": [20.5, null],
+            "temperature_2m_max_member02": [21.0],
+            "temperature_2m_max_member03": [20.2, 25.0],
+            "temperature_2m_max_member04": [20.8, 18.0],
+            "temperature_2m_min": [10.0, 10.0]
+        }}"#;
+        // Day one has five highs; day two only two readable ones
+        let confidence = parse_ensemble_response(StatusCode::OK, body).unwrap();
+        assert_eq!(confidence, [("2025-12-10".to_string(), Confidence::High)]);
+
+        assert_eq!(parse_ensemble_response(StatusCode::OK, "{}").unwrap(), []);
+        assert!(parse_ensemble_response(StatusCode::BAD_GATEWAY, "").is_err());
+        assert!(parse_ensemble_response(StatusCode::OK, "not json").is_err());
+    }
+
+    #[test]
+    fn test_alignment_by_date() {
+        let mut daily: Vec<DailyForecast> = ["2025-12-09", "2025-12-10", "2025-12-11"]
+            .iter()
+            .map(|date| DailyForecast::builder(*date).build())
+            .collect();
+        // The ensemble starts a day later and ends a day sooner than the forecast
+        let confidence = [
+            ("2025-12-10".to_string(), Confidence::Medium),
+            ("2025-12-11".to_string(), Confidence::Low),
+            ("2025-12-12".to_string(), Confidence::High),
+        ];
+        apply_confidence(&mut daily, &confidence);
+        let applied: Vec<_> = daily.iter().map(|day| day.confidence).collect();
+        assert_eq!(
+            applied,
+            [None, Some(Confidence::Medium), Some(Confidence::Low)]
+        );
+    }
+
+    #[test]
+    fn test_ensemble_url() {
+        assert_eq!(
+            ensemble_url("http://ensemble.local/v1/ensemble", 51.5, -0.13, None, 7),
+            "http://ensemble.local/v1/ensemble?latitude=51.5&longitude=-0.13&models=gem_global&daily=temperature_2m_max,temperature_2m_min&forecast_days=7&timezone=auto"
+        );
+    }
+}
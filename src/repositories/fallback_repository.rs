@@ -0,0 +1,243 @@
+//! Several forecast providers tried in turn
+//!
+//! `FallbackRepository` asks its providers in order and returns the first
+//! forecast it gets, so an outage at Open-Meteo can be covered by Met.no.
+//! A location no provider can find is the user's mistake, not an outage,
+//! and is reported at once. The forecast records which provider supplied
+//! it (`WeatherInfo::source`) for the views to attribute.
+
+use crate::errors::WeatherError;
+use crate::models::favorites::ResolvedPlace;
+use crate::models::weather_info::WeatherInfo;
+use crate::repositories::provider::WeatherProvider;
+use crate::repositories::weather_repository::WeatherRepository;
+
+/// Whether the next provider may succeed where one failed: not for unknown
+/// locations or options, which are the same everywhere
+fn tries_next(error: &WeatherError) -> bool {
+    !matches!(
+        error,
+        WeatherError::LocationNotFound { .. } | WeatherError::InvalidOption(_)
+    )
+}
+
+/// Error for a location every provider failed on, naming each one. The
+/// first failure decides the kind, so a network error still counts as an
+/// outage for `PersistentWeatherRepository`.
+fn combine_failures(
+    location: &str,
+    mut failures: Vec<(WeatherProvider, WeatherError)>,
+) -> WeatherError {
+    if failures.len() == 1 {
+        return failures.remove(0).1;
+    }
+    let tried = failures
+        .iter()
+        .map(|(provider, e)| format!("{} ({e})", provider.label()))
+        .collect::<Vec<_>>()
+        .join(" and ");
+    let message = format!("forecast for '{location}' failed with {tried}");
+    match failures.remove(0).1 {
+        WeatherError::NetworkError(_) => WeatherError::NetworkError(message),
+        WeatherError::ParseError(_) => WeatherError::ParseError(message),
+        WeatherError::Timeout(_) => WeatherError::Timeout(message),
+        WeatherError::StorageError(_) => WeatherError::StorageError(message),
+        WeatherError::ApiKey(_) => WeatherError::ApiKey(message),
+        WeatherError::ServerError(_) => WeatherError::ServerError(message),
+        _ => WeatherError::ApiError(message),
+    }
+}
+
+/// Providers in order of preference, each asked only when those before it
+/// failed
+pub struct FallbackRepository {
+    repositories: Vec<Box<dyn WeatherRepository>>,
+}
+
+impl FallbackRepository {
+    pub fn new(repositories: Vec<Box<dyn WeatherRepository>>) -> Self {
+        FallbackRepository { repositories }
+    }
+
+    /// Providers in the order they are asked
+    pub fn providers(&self) -> Vec<WeatherProvider> {
+        self.repositories.iter().map(|r| r.provider()).collect()
+    }
+}
+
+impl WeatherRepository for FallbackRepository {
+    fn fetch_weather(&self, location: &str) -> Result<WeatherInfo, WeatherError> {
+        let mut failures = Vec::new();
+        for repository in &self.repositories {
+            match repository.fetch_weather(location) {
+                Ok(mut info) => {
+                    info.source = Some(repository.provider().label().to_string());
+                    return Ok(info);
+                }
+                Err(e) if !tries_next(&e) => return Err(e),
+                Err(e) => failures.push((repository.provider(), e)),
+            }
+        }
+        if failures.is_empty() {
+            return Err(WeatherError::InvalidOption(
+                "no weather provider to ask".to_string(),
+            ));
+        }
+        Err(combine_failures(location, failures))
+    }
+
+    fn response_cache_hits(&self) -> usize {
+        self.repositories
+            .iter()
+            .map(|r| r.response_cache_hits())
+            .sum()
+    }
+
+    fn clear_response_cache(&self) {
+        for repository in &self.repositories {
+            repository.clear_response_cache();
+        }
+    }
+
+    /// From the first provider that can resolve places
+    fn resolve_place(
+        &self,
+        location: &str,
+        recorded: Option<&ResolvedPlace>,
+    ) -> Result<Option<ResolvedPlace>, WeatherError> {
+        let mut first_error = None;
+        for repository in &self.repositories {
+            match repository.resolve_place(location, recorded) {
+                Ok(Some(place)) => return Ok(Some(place)),
+                Ok(None) => {}
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        first_error.map_or(Ok(None), Err)
+    }
+
+    fn provider(&self) -> WeatherProvider {
+        self.repositories
+            .first()
+            .map_or(WeatherProvider::default(), |r| r.provider())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::sample_weather_info;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Answers every fetch with `result`, counting the calls
+    struct FakeRepository {
+        provider: WeatherProvider,
+        result: fn(&str) -> Result<WeatherInfo, WeatherError>,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl FakeRepository {
+        fn boxed(
+            provider: WeatherProvider,
+            result: fn(&str) -> Result<WeatherInfo, WeatherError>,
+        ) -> (Box<dyn WeatherRepository>, Arc<AtomicUsize>) {
+            let calls = Arc::new(AtomicUsize::new(0));
+            let repository = FakeRepository {
+                provider,
+                result,
+                calls: Arc::clone(&calls),
+            };
+            (Box::new(repository), calls)
+        }
+    }
+
+    impl WeatherRepository for FakeRepository {
+        fn fetch_weather(&self, location: &str) -> Result<WeatherInfo, WeatherError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            (self.result)(location)
+        }
+
+        fn provider(&self) -> WeatherProvider {
+            self.provider
+        }
+    }
+
+    fn found(location: &str) -> Result<WeatherInfo, WeatherError> {
+        let mut info = sample_weather_info();
+        info.location = location.to_string();
+        Ok(info)
+    }
+
+    fn unreachable(_: &str) -> Result<WeatherInfo, WeatherError> {
+        Err(WeatherError::NetworkError("connection refused".into()))
+    }
+
+    fn server_error(_: &str) -> Result<WeatherInfo, WeatherError> {
+        Err(WeatherError::ApiError(
+            "Met.no API returned status: 500".into(),
+        ))
+    }
+
+    fn not_found(location: &str) -> Result<WeatherInfo, WeatherError> {
+        Err(WeatherError::location_not_found(location))
+    }
+
+    #[test]
+    fn test_second_provider_covers_the_first() {
+        let (open_meteo, _) = FakeRepository::boxed(WeatherProvider::OpenMeteo, unreachable);
+        let (metno, metno_calls) = FakeRepository::boxed(WeatherProvider::MetNo, found);
+        let repository = FallbackRepository::new(vec![open_meteo, metno]);
+
+        let info = repository.fetch_weather("Oslo").unwrap();
+        assert_eq!(info.location, "Oslo");
+        assert_eq!(info.source.as_deref(), Some("Met.no"));
+        assert_eq!(info.attribution().as_deref(), Some("Forecast by Met.no"));
+        assert_eq!(metno_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_first_success_ends_the_search() {
+        let (open_meteo, _) = FakeRepository::boxed(WeatherProvider::OpenMeteo, found);
+        let (metno, metno_calls) = FakeRepository::boxed(WeatherProvider::MetNo, found);
+        let repository = FallbackRepository::new(vec![open_meteo, metno]);
+
+        let info = repository.fetch_weather("Oslo").unwrap();
+        assert_eq!(info.source.as_deref(), Some("Open-Meteo"));
+        assert_eq!(metno_calls.load(Ordering::SeqCst), 0);
+        assert_eq!(
+            repository.providers(),
+            [WeatherProvider::OpenMeteo, WeatherProvider::MetNo]
+        );
+    }
+
+    #[test]
+    fn test_every_failure_is_listed() {
+        let (open_meteo, _) = FakeRepository::boxed(WeatherProvider::OpenMeteo, unreachable);
+        let (metno, _) = FakeRepository::boxed(WeatherProvider::MetNo, server_error);
+        let repository = FallbackRepository::new(vec![open_meteo, metno]);
+
+        let error = repository.fetch_weather("Oslo").unwrap_err();
+        // The first failure's kind, so the offline copy can still stand in
+        assert!(matches!(error, WeatherError::NetworkError(_)));
+        assert_eq!(
+            error.to_string(),
+            "Network error: forecast for 'Oslo' failed with \
+             Open-Meteo (Network error: connection refused) and \
+             Met.no (API error: Met.no API returned status: 500)"
+        );
+    }
+
+    #[test]
+    fn test_unknown_location_is_not_retried_elsewhere() {
+        let (open_meteo, _) = FakeRepository::boxed(WeatherProvider::OpenMeteo, not_found);
+        let (metno, metno_calls) = FakeRepository::boxed(WeatherProvider::MetNo, found);
+        let repository = FallbackRepository::new(vec![open_meteo, metno]);
+
+        let error = repository.fetch_weather("Atlantis").unwrap_err();
+        assert!(matches!(error, WeatherError::LocationNotFound { .. }));
+        assert_eq!(metno_calls.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,73 @@
+//! JSON file persistence for favorites
+
+use crate::errors::WeatherError;
+use crate::models::favorites::Favorites;
+use crate::repositories::json_file::JsonFile;
+use std::path::{Path, PathBuf};
+
+/// Favorites file, e.g. `~/.config/weather-app/favorites.json`
+#[derive(Clone, Debug)]
+pub struct FavoritesStore {
+    file: JsonFile,
+}
+
+impl FavoritesStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FavoritesStore {
+            file: JsonFile::new(path),
+        }
+    }
+
+    /// Store in the platform config directory, if one can be determined
+    pub fn default_location() -> Option<Self> {
+        JsonFile::in_config_dir("favorites.json").map(|file| FavoritesStore { file })
+    }
+
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+
+    /// Reads the favorites; a missing file is an empty list
+    pub fn load(&self) -> Result<Favorites, WeatherError> {
+        self.file.load()
+    }
+
+    /// Writes the favorites, creating the parent directory when needed
+    pub fn save(&self, favorites: &Favorites) -> Result<(), WeatherError> {
+        self.file.save(favorites)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn temp_store(name: &str) -> FavoritesStore {
+        let dir = std::env::temp_dir().join(format!("weather-app-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        FavoritesStore::new(dir.join("favorites.json"))
+    }
+
+    #[test]
+    fn test_migrates_label_less_file() {
+        let store = temp_store("favorites-migrate");
+        fs::create_dir_all(store.path().parent().unwrap()).unwrap();
+        fs::write(store.path(), r#"["Seattle", "Bend"]"#).unwrap();
+
+        let mut favorites = store.load().unwrap();
+        favorites.save("Seattle", Some("Home".to_string()), None);
+        store.save(&favorites).unwrap();
+
+        let reloaded = store.load().unwrap();
+        assert_eq!(reloaded, favorites);
+        assert_eq!(reloaded.entries()[0].title(), "Home (Seattle)");
+        assert_eq!(reloaded.entries()[1].title(), "Bend");
+    }
+
+    #[test]
+    fn test_missing_file_is_empty() {
+        let store = temp_store("favorites-missing");
+        assert!(store.load().unwrap().is_empty());
+    }
+}
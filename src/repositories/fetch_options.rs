@@ -0,0 +1,137 @@
+//! Per-request forecast options
+//!
+//! `ApiWeatherRepository::with_forecast_days` sets the horizon for every
+//! fetch and quietly clamps it. A caller asking for one particular fetch,
+//! say a two-week trip, passes `FetchOptions` instead and learns about an
+//! out-of-range value from the error rather than from a shorter forecast.
+//! The same fetch can reach back with `past_days`, e.g. to compare today
+//! with yesterday, or pin the forecast to one weather model.
+
+use crate::constants::{DAILY_FORECAST_DAYS, MAX_FORECAST_DAYS, MAX_PAST_DAYS};
+use crate::errors::WeatherError;
+use crate::models::forecast_model::ForecastModel;
+
+/// What one fetch asks the forecast API for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FetchOptions {
+    /// Days of daily forecast, 1 to `MAX_FORECAST_DAYS`
+    pub forecast_days: u8,
+    /// Days of recent weather ahead of the forecast, 0 to `MAX_PAST_DAYS`.
+    /// Their entries come first and are marked `is_past`.
+    pub past_days: u8,
+    /// Also fetch pollutants and AQI (see `ApiWeatherRepository::fetch_weather_full`)
+    pub air_quality: bool,
+    /// Weather model to ask for; `BestMatch` leaves the choice to Open-Meteo
+    pub model: ForecastModel,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        FetchOptions {
+            forecast_days: DAILY_FORECAST_DAYS as u8,
+            past_days: 0,
+            air_quality: false,
+            model: ForecastModel::BestMatch,
+        }
+    }
+}
+
+impl FetchOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_forecast_days(mut self, days: u8) -> Self {
+        self.forecast_days = days;
+        self
+    }
+
+    pub fn with_past_days(mut self, days: u8) -> Self {
+        self.past_days = days;
+        self
+    }
+
+    pub fn with_air_quality(mut self, air_quality: bool) -> Self {
+        self.air_quality = air_quality;
+        self
+    }
+
+    pub fn with_model(mut self, model: ForecastModel) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// `InvalidOption` for values the API would reject
+    pub fn validate(&self) -> Result<(), WeatherError> {
+        if !(1..=MAX_FORECAST_DAYS).contains(&(self.forecast_days as usize)) {
+            return Err(WeatherError::InvalidOption(format!(
+                "forecast days must be between 1 and {MAX_FORECAST_DAYS}, not {}",
+                self.forecast_days
+            )));
+        }
+        if self.past_days as usize > MAX_PAST_DAYS {
+            return Err(WeatherError::InvalidOption(format!(
+                "past days must be between 0 and {MAX_PAST_DAYS}, not {}",
+                self.past_days
+            )));
+        }
+        Ok(())
+    }
+
+    /// `&past_days=N` for the query string, empty for none
+    pub(crate) fn past_days_param(&self) -> String {
+        match self.past_days {
+            0 => String::new(),
+            days => format!("&past_days={days}"),
+        }
+    }
+
+    /// `&models=...` for the query string, empty for Open-Meteo's own choice
+    pub(crate) fn models_param(&self) -> String {
+        match self.model {
+            ForecastModel::BestMatch => String::new(),
+            model => format!("&models={}", model.api_name()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forecast_days_range() {
+        assert_eq!(FetchOptions::new().forecast_days, 7);
+        assert!(FetchOptions::new().validate().is_ok());
+        assert!(FetchOptions::new().with_forecast_days(1).validate().is_ok());
+        assert!(FetchOptions::new()
+            .with_forecast_days(16)
+            .validate()
+            .is_ok());
+        for days in [0, 17, 255] {
+            let error = FetchOptions::new()
+                .with_forecast_days(days)
+                .validate()
+                .unwrap_err();
+            assert!(matches!(error, WeatherError::InvalidOption(_)));
+            assert!(error.to_string().contains("between 1 and 16"), "{error}");
+        }
+    }
+
+    #[test]
+    fn test_past_days_range() {
+        assert_eq!(FetchOptions::new().past_days, 0);
+        assert_eq!(FetchOptions::new().past_days_param(), "");
+        let week = FetchOptions::new().with_past_days(7);
+        assert!(week.validate().is_ok());
+        assert_eq!(week.past_days_param(), "&past_days=7");
+        let error = FetchOptions::new()
+            .with_past_days(8)
+            .validate()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid option: past days must be between 0 and 7, not 8"
+        );
+    }
+}
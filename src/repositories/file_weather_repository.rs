@@ -0,0 +1,167 @@
+//! Weather read from a file instead of the network
+//!
+//! `FileWeatherRepository` answers every location with the forecast saved at
+//! its path, for demos, offline work and tests that render real data without
+//! a server. The file is either an Open-Meteo forecast response, e.g. one of
+//! the `tests/fixtures/*/forecast.json`, or a `WeatherInfo` as serialized by
+//! `FileWeatherRepository::record`. The file is read on each fetch, so it can
+//! be replaced while the app runs.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::errors::WeatherError;
+use crate::models::weather_info::WeatherInfo;
+use crate::repositories::json_file::JsonFile;
+use crate::repositories::weather_repository::{ApiWeatherRepository, WeatherRepository};
+
+/// The forecast saved at one path, whatever location is asked for
+#[derive(Clone, Debug)]
+pub struct FileWeatherRepository {
+    path: PathBuf,
+}
+
+impl FileWeatherRepository {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileWeatherRepository { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Fetches `location` from `repository` and saves the result to `path`
+    /// for a `FileWeatherRepository` to return later. Works with any
+    /// provider, since the forecast is saved as parsed rather than as the
+    /// provider's response.
+    pub fn record<R: WeatherRepository + ?Sized>(
+        repository: &R,
+        location: &str,
+        path: impl Into<PathBuf>,
+    ) -> Result<WeatherInfo, WeatherError> {
+        let info = repository.fetch_weather(location)?;
+        JsonFile::new(path).save(&info)?;
+        Ok(info)
+    }
+
+    fn parse_error(&self, message: impl std::fmt::Display) -> WeatherError {
+        WeatherError::ParseError(format!("Invalid file {}: {message}", self.path.display()))
+    }
+}
+
+impl WeatherRepository for FileWeatherRepository {
+    /// An Open-Meteo response is named after `location`; a recorded
+    /// `WeatherInfo` keeps the name it was saved with
+    fn fetch_weather(&self, location: &str) -> Result<WeatherInfo, WeatherError> {
+        let body = fs::read_to_string(&self.path).map_err(|e| {
+            WeatherError::StorageError(format!(
+                "cannot read saved forecast {}: {e}",
+                self.path.display()
+            ))
+        })?;
+        let document: Value = serde_json::from_str(&body).map_err(|e| self.parse_error(e))?;
+
+        let mut info = if document.get("weather_data").is_some() {
+            serde_json::from_value::<WeatherInfo>(document).map_err(|e| self.parse_error(e))?
+        } else {
+            // Open-Meteo echoes the coordinates it answered for
+            let (Some(latitude), Some(longitude)) = (
+                document["latitude"].as_f64(),
+                document["longitude"].as_f64(),
+            ) else {
+                return Err(self.parse_error("no coordinates in the forecast response"));
+            };
+            ApiWeatherRepository::parse_weather_info(location.trim(), latitude, longitude, &body)?
+        };
+        info.query = Some(location.trim().to_string());
+        Ok(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::sample_weather_info;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+    fn temp_path(file_name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("weather-app-{}-{file_name}", std::process::id()))
+    }
+
+    fn fixture(name: &str) -> FileWeatherRepository {
+        FileWeatherRepository::new(format!("{FIXTURES}/{name}/forecast.json"))
+    }
+
+    #[test]
+    fn test_reads_bundled_forecasts() {
+        let info = fixture("sunny").fetch_weather("Seville").unwrap();
+        assert_eq!(info.location, "Seville");
+        assert!((info.latitude - 37.3886).abs() < 1e-9);
+        assert_eq!(info.timezone.as_deref(), Some("Europe/Madrid"));
+        assert_eq!(info.weather_data.current.weather_code, Some(0));
+        assert_eq!(info.weather_data.daily.len(), 7);
+
+        let info = fixture("rainy").fetch_weather("Bergen").unwrap();
+        assert!(info
+            .weather_data
+            .daily
+            .iter()
+            .all(|day| day.weather_code == Some(65)));
+
+        // Any location gets the same forecast
+        let info = fixture("snowy").fetch_weather(" Atlantis ").unwrap();
+        assert_eq!(info.location, "Atlantis");
+        assert_eq!(info.query.as_deref(), Some("Atlantis"));
+        assert_eq!(info.weather_data.current.weather_code, Some(75));
+        assert!(info.weather_data.current.temperature.unwrap() < 0.0);
+    }
+
+    #[test]
+    fn test_recorded_forecast_reads_back() {
+        let path = temp_path("recorded-forecast.json");
+        let recorded = FileWeatherRepository::record(&fixture("rainy"), "Bergen", &path).unwrap();
+
+        let info = FileWeatherRepository::new(&path)
+            .fetch_weather("Somewhere else")
+            .unwrap();
+        assert_eq!(info.location, "Bergen");
+        assert_eq!(info.query.as_deref(), Some("Somewhere else"));
+        assert_eq!(info.weather_data, recorded.weather_data);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_saved_weather_info_is_read_as_is() {
+        let path = temp_path("saved-weather-info.json");
+        JsonFile::new(&path).save(&sample_weather_info()).unwrap();
+
+        let info = FileWeatherRepository::new(&path)
+            .fetch_weather("Seattle")
+            .unwrap();
+        assert_eq!(info.location, sample_weather_info().location);
+        assert_eq!(info.weather_data, sample_weather_info().weather_data);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_unreadable_files_are_errors() {
+        assert!(matches!(
+            fixture("nowhere").fetch_weather("Nowhere"),
+            Err(WeatherError::StorageError(_))
+        ));
+
+        let path = temp_path("not-a-forecast.json");
+        fs::write(&path, r#"{"reason": "no coordinates"}"#).unwrap();
+        let error = FileWeatherRepository::new(&path)
+            .fetch_weather("Nowhere")
+            .unwrap_err();
+        assert!(matches!(error, WeatherError::ParseError(_)));
+        assert!(error
+            .to_string()
+            .contains("no coordinates in the forecast response"));
+        let _ = fs::remove_file(&path);
+    }
+}
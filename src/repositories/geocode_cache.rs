@@ -0,0 +1,228 @@
+//! Geocoding results on disk, so a place is looked up once a month
+//!
+//! Nominatim's usage policy asks clients to cache results, and a city does
+//! not move between runs. Each match is kept for `GEOCODE_CACHE_TTL` under
+//! the geocoder and the normalized location, and consulted before any
+//! request. The file lives in the cache directory; a damaged one is ignored
+//! and replaced by the next lookup.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{GEOCODE_CACHE_CAPACITY, GEOCODE_CACHE_TTL};
+use crate::errors::WeatherError;
+use crate::repositories::geocoding::GeocodingProvider;
+use crate::repositories::json_file::JsonFile;
+use crate::repositories::weather_repository::GeocodeMatch;
+use crate::utils::location::normalize_location_key;
+
+/// What a lookup found, and when
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct CachedPlace {
+    latitude: f64,
+    longitude: f64,
+    name: Option<String>,
+    timezone: Option<String>,
+    stored: DateTime<Utc>,
+}
+
+/// Places by geocoder and normalized location, e.g. "nominatim:new york"
+type Places = HashMap<String, CachedPlace>;
+
+/// Geocoding cache file, e.g. `~/.cache/weather-app/geocode_cache.json`.
+/// Clones share one file and its write lock.
+#[derive(Clone, Debug)]
+pub struct GeocodeCache {
+    file: JsonFile,
+    ttl: TimeDelta,
+    /// Serializes read-modify-write between concurrent lookups
+    writing: Arc<Mutex<()>>,
+}
+
+impl GeocodeCache {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        GeocodeCache {
+            file: JsonFile::new(path),
+            ttl: GEOCODE_CACHE_TTL,
+            writing: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Cache in the platform cache directory, if one can be determined
+    pub fn default_location() -> Option<Self> {
+        JsonFile::in_cache_dir("geocode_cache.json").map(|file| GeocodeCache::new(file.path()))
+    }
+
+    /// Age from which a stored match is looked up again
+    pub fn with_ttl(mut self, ttl: TimeDelta) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+
+    /// Forgets every stored match
+    pub fn clear(&self) -> Result<(), WeatherError> {
+        let _writing = self.writing.lock().unwrap();
+        match fs::remove_file(self.path()) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(WeatherError::StorageError(format!(
+                "{}: {e}",
+                self.path().display()
+            ))),
+        }
+    }
+
+    /// Stored places; a missing or damaged file reads as empty
+    fn places(&self) -> Places {
+        self.file.load().unwrap_or_default()
+    }
+
+    /// Match stored for `location` by `geocoder` less than the TTL before `now`
+    pub(crate) fn get(
+        &self,
+        geocoder: GeocodingProvider,
+        location: &str,
+        now: DateTime<Utc>,
+    ) -> Option<GeocodeMatch> {
+        let place = self.places().remove(&key(geocoder, location))?;
+        (now - place.stored < self.ttl).then(|| GeocodeMatch {
+            latitude: place.latitude,
+            longitude: place.longitude,
+            timezone: place.timezone,
+            name: place.name,
+            skipped: Vec::new(),
+        })
+    }
+
+    /// Stores `found` for `location`, dropping expired entries and then the
+    /// oldest ones beyond `GEOCODE_CACHE_CAPACITY`
+    pub(crate) fn insert(
+        &self,
+        geocoder: GeocodingProvider,
+        location: &str,
+        found: &GeocodeMatch,
+        now: DateTime<Utc>,
+    ) -> Result<(), WeatherError> {
+        let _writing = self.writing.lock().unwrap();
+        let mut places = self.places();
+        places.retain(|_, place| now - place.stored < self.ttl);
+        places.insert(
+            key(geocoder, location),
+            CachedPlace {
+                latitude: found.latitude,
+                longitude: found.longitude,
+                name: found.name.clone(),
+                timezone: found.timezone.clone(),
+                stored: now,
+            },
+        );
+        while places.len() > GEOCODE_CACHE_CAPACITY {
+            let Some(oldest) = places
+                .iter()
+                .min_by_key(|(_, place)| place.stored)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            places.remove(&oldest);
+        }
+        self.file.save(&places)
+    }
+}
+
+fn key(geocoder: GeocodingProvider, location: &str) -> String {
+    format!("{geocoder}:{}", normalize_location_key(location))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(name: &str) -> GeocodeCache {
+        let dir =
+            std::env::temp_dir().join(format!("weather-app-geocode-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        GeocodeCache::new(dir.join("geocode_cache.json"))
+    }
+
+    fn berlin() -> GeocodeMatch {
+        GeocodeMatch {
+            latitude: 52.52,
+            longitude: 13.405,
+            timezone: Some("Europe/Berlin".to_string()),
+            name: Some("Berlin, Deutschland".to_string()),
+            skipped: vec!["ignored".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_hit_and_miss() {
+        let cache = cache("hit");
+        let now = Utc::now();
+        let nominatim = GeocodingProvider::Nominatim;
+        assert_eq!(cache.get(nominatim, "Berlin", now), None);
+
+        cache.insert(nominatim, "Berlin", &berlin(), now).unwrap();
+        let found = cache.get(nominatim, "  BERLIN ", now).unwrap();
+        assert_eq!((found.latitude, found.longitude), (52.52, 13.405));
+        assert_eq!(found.name.as_deref(), Some("Berlin, Deutschland"));
+        assert_eq!(found.timezone.as_deref(), Some("Europe/Berlin"));
+        assert!(found.skipped.is_empty());
+        assert!(cache.get(nominatim, "Berlin,", now).is_some());
+
+        // Another geocoder may place it elsewhere
+        assert_eq!(cache.get(GeocodingProvider::OpenMeteo, "Berlin", now), None);
+        assert_eq!(cache.get(nominatim, "Bern", now), None);
+
+        cache.clear().unwrap();
+        assert_eq!(cache.get(nominatim, "Berlin", now), None);
+        cache.clear().unwrap();
+    }
+
+    #[test]
+    fn test_expiry() {
+        let cache = cache("expiry").with_ttl(TimeDelta::days(30));
+        let stored = Utc::now();
+        let nominatim = GeocodingProvider::Nominatim;
+        cache
+            .insert(nominatim, "Berlin", &berlin(), stored)
+            .unwrap();
+        assert!(cache
+            .get(nominatim, "Berlin", stored + TimeDelta::days(29))
+            .is_some());
+        assert_eq!(
+            cache.get(nominatim, "Berlin", stored + TimeDelta::days(30)),
+            None
+        );
+
+        // Expired entries are dropped when another is stored
+        cache
+            .insert(nominatim, "Bern", &berlin(), stored + TimeDelta::days(31))
+            .unwrap();
+        assert_eq!(cache.places().len(), 1);
+        let _ = fs::remove_dir_all(cache.path().parent().unwrap());
+    }
+
+    #[test]
+    fn test_damaged_file_is_ignored_and_rebuilt() {
+        let cache = cache("damaged");
+        let now = Utc::now();
+        let nominatim = GeocodingProvider::Nominatim;
+        fs::create_dir_all(cache.path().parent().unwrap()).unwrap();
+        fs::write(cache.path(), "{\"nominatim:berlin\": {\"lat").unwrap();
+        assert_eq!(cache.get(nominatim, "Berlin", now), None);
+
+        cache.insert(nominatim, "Berlin", &berlin(), now).unwrap();
+        assert!(cache.get(nominatim, "Berlin", now).is_some());
+        let _ = fs::remove_dir_all(cache.path().parent().unwrap());
+    }
+}
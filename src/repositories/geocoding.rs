@@ -0,0 +1,697 @@
+//! Geocoding providers: Nominatim (default) and Open-Meteo's geocoding API
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::constants::{NOMINATIM_API_URL, OPEN_METEO_GEOCODING_API_URL};
+use crate::errors::WeatherError;
+use crate::repositories::user_agent::UserAgent;
+use crate::repositories::weather_repository::{ApiWeatherRepository, GeocodeMatch};
+use crate::utils::coords::Coordinates;
+
+/// Number of Open-Meteo candidates compared by population
+const OPEN_METEO_CANDIDATES: usize = 10;
+
+/// One place a search matched, for the user to choose from
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeocodeResult {
+    /// Provider's full name, e.g. "Springfield, Sangamon County, Illinois, United States"
+    pub display_name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Kind of place, e.g. "city" from Nominatim or "PPLA" from Open-Meteo
+    pub place_type: Option<String>,
+    /// First-level region, e.g. "Illinois", when the provider returns one
+    pub region: Option<String>,
+    pub country: Option<String>,
+    /// IANA time zone, when the provider returns one
+    pub timezone: Option<String>,
+}
+
+impl GeocodeResult {
+    /// Short name that still tells same-named places apart: the place, its
+    /// region and country, "Springfield, Illinois, United States"
+    pub fn label(&self) -> String {
+        let place = self
+            .display_name
+            .split(',')
+            .next()
+            .map(str::trim)
+            .unwrap_or_default();
+        let region = self.region.as_deref().filter(|region| *region != place);
+        match (region, self.country.as_deref()) {
+            (None, None) => short_name(&self.display_name),
+            (region, country) => std::iter::once(place)
+                .chain(region)
+                .chain(country)
+                .collect::<Vec<_>>()
+                .join(", "),
+        }
+    }
+}
+
+/// Nominatim reverse geocoding detail: 10 names the city, not the street
+const REVERSE_ZOOM: u8 = 10;
+
+/// Service that turns a location name into coordinates
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GeocodingProvider {
+    /// OpenStreetMap Nominatim
+    #[default]
+    Nominatim,
+    /// Open-Meteo geocoding, which also returns the location's time zone
+    OpenMeteo,
+}
+
+impl GeocodingProvider {
+    pub fn label(&self) -> &'static str {
+        match self {
+            GeocodingProvider::Nominatim => "Nominatim",
+            GeocodingProvider::OpenMeteo => "Open-Meteo",
+        }
+    }
+
+    /// The other provider, asked by default when this one fails
+    pub fn fallback(&self) -> GeocodingProvider {
+        match self {
+            GeocodingProvider::Nominatim => GeocodingProvider::OpenMeteo,
+            GeocodingProvider::OpenMeteo => GeocodingProvider::Nominatim,
+        }
+    }
+
+    /// Public endpoint of the provider
+    pub fn default_url(&self) -> &'static str {
+        match self {
+            GeocodingProvider::Nominatim => NOMINATIM_API_URL,
+            GeocodingProvider::OpenMeteo => OPEN_METEO_GEOCODING_API_URL,
+        }
+    }
+
+    /// Search URL for up to `limit` candidates; Nominatim is asked for the
+    /// address details that name the country
+    pub(crate) fn candidates_url(
+        &self,
+        base_url: &str,
+        location: &str,
+        user_agent: &UserAgent,
+        limit: usize,
+    ) -> String {
+        let query = urlencoding::encode(location);
+        match self {
+            GeocodingProvider::Nominatim => {
+                let url =
+                    format!("{base_url}?q={query}&format=json&limit={limit}&addressdetails=1");
+                match user_agent.email_param() {
+                    Some(email) => format!("{url}&{email}"),
+                    None => url,
+                }
+            }
+            GeocodingProvider::OpenMeteo => {
+                format!("{base_url}?name={query}&count={limit}&language=en&format=json")
+            }
+        }
+    }
+
+    /// Nominatim reverse geocoding URL for `coordinates` next to its search
+    /// endpoint `search_url`, at city level. `None` for Open-Meteo, which has
+    /// no reverse geocoding, and for search endpoints not named `search`.
+    pub(crate) fn reverse_url(
+        &self,
+        search_url: &str,
+        coordinates: Coordinates,
+        user_agent: &UserAgent,
+    ) -> Option<String> {
+        match self {
+            GeocodingProvider::Nominatim => {
+                let base = search_url.strip_suffix("search")?;
+                let url = format!(
+                    "{base}reverse?lat={}&lon={}&format=json&zoom={REVERSE_ZOOM}",
+                    coordinates.latitude, coordinates.longitude
+                );
+                Some(match user_agent.email_param() {
+                    Some(email) => format!("{url}&{email}"),
+                    None => url,
+                })
+            }
+            GeocodingProvider::OpenMeteo => None,
+        }
+    }
+
+    /// Every candidate with valid coordinates in a search response of this
+    /// provider, in the provider's order. No results at all is
+    /// `LocationNotFound`; results that are all malformed are a `ParseError`.
+    pub(crate) fn parse_candidates(
+        &self,
+        json: serde_json::Value,
+        location: &str,
+    ) -> Result<Vec<GeocodeResult>, WeatherError> {
+        let (total, candidates) = match self {
+            GeocodingProvider::Nominatim => {
+                let array = json.as_array().ok_or_else(|| {
+                    WeatherError::ParseError(
+                        "Invalid response format from geocoding API".to_string(),
+                    )
+                })?;
+                (
+                    array.len(),
+                    array
+                        .iter()
+                        .filter_map(nominatim_candidate)
+                        .collect::<Vec<_>>(),
+                )
+            }
+            GeocodingProvider::OpenMeteo => {
+                let response: OpenMeteoGeocoding = serde_json::from_value(json)
+                    .map_err(|e| WeatherError::ParseError(e.to_string()))?;
+                (
+                    response.results.len(),
+                    response
+                        .results
+                        .iter()
+                        .filter(|place| place.has_valid_coordinates())
+                        .map(OpenMeteoPlace::to_result)
+                        .collect(),
+                )
+            }
+        };
+        if total == 0 {
+            return Err(WeatherError::location_not_found(location));
+        }
+        if candidates.is_empty() {
+            return Err(WeatherError::ParseError(format!(
+                "None of the {total} geocoding results for '{location}' had valid coordinates"
+            )));
+        }
+        Ok(candidates)
+    }
+}
+
+/// A geocoding service the repositories search in turn: how to ask it for a
+/// place and how to read its answer
+pub(crate) trait Geocoder {
+    /// Name in messages, e.g. "Nominatim"
+    fn name(&self) -> &'static str;
+
+    /// Search URL for `location` on the service's endpoint `base_url`
+    fn search_url(&self, base_url: &str, location: &str, user_agent: &UserAgent) -> String;
+
+    /// Best match in a search response
+    fn parse(&self, json: serde_json::Value, location: &str) -> Result<GeocodeMatch, WeatherError>;
+}
+
+impl Geocoder for GeocodingProvider {
+    fn name(&self) -> &'static str {
+        self.label()
+    }
+
+    /// Search URL for a location on `base_url`; Nominatim also gets the contact
+    fn search_url(&self, base_url: &str, location: &str, user_agent: &UserAgent) -> String {
+        let query = urlencoding::encode(location);
+        match self {
+            GeocodingProvider::Nominatim => {
+                let url = format!("{base_url}?q={query}&format=json&limit=1");
+                match user_agent.email_param() {
+                    Some(email) => format!("{url}&{email}"),
+                    None => url,
+                }
+            }
+            GeocodingProvider::OpenMeteo => format!(
+                "{base_url}?name={query}&count={OPEN_METEO_CANDIDATES}&language=en&format=json"
+            ),
+        }
+    }
+
+    /// Best match in a search response of this provider
+    fn parse(&self, json: serde_json::Value, location: &str) -> Result<GeocodeMatch, WeatherError> {
+        match self {
+            GeocodingProvider::Nominatim => {
+                ApiWeatherRepository::parse_coordinates(&json, location)
+            }
+            GeocodingProvider::OpenMeteo => {
+                let response: OpenMeteoGeocoding = serde_json::from_value(json)
+                    .map_err(|e| WeatherError::ParseError(e.to_string()))?;
+                parse_open_meteo(response, location)
+            }
+        }
+    }
+}
+
+/// Candidate from one entry of a Nominatim search response, `None` when its
+/// coordinates are missing or out of range or it has no name at all
+fn nominatim_candidate(entry: &serde_json::Value) -> Option<GeocodeResult> {
+    let latitude = ApiWeatherRepository::parse_candidate_coordinate(entry, "lat", 90.0).ok()?;
+    let longitude = ApiWeatherRepository::parse_candidate_coordinate(entry, "lon", 180.0).ok()?;
+    let text = |value: &serde_json::Value| value.as_str().map(str::to_string);
+    let address_country = text(&entry["address"]["country"]);
+    // Entries without a full name are named by their own name and country
+    let display_name = match text(&entry["display_name"]) {
+        Some(display_name) => display_name,
+        None => std::iter::once(text(&entry["name"])?)
+            .chain(address_country.clone())
+            .collect::<Vec<_>>()
+            .join(", "),
+    };
+    Some(GeocodeResult {
+        latitude,
+        longitude,
+        place_type: text(&entry["addresstype"]).or_else(|| text(&entry["type"])),
+        region: text(&entry["address"]["state"]),
+        // Without address details the country is the name's last part
+        country: address_country.or_else(|| {
+            display_name
+                .rsplit(',')
+                .next()
+                .map(|part| part.trim().to_string())
+        }),
+        timezone: None,
+        display_name,
+    })
+}
+
+impl fmt::Display for GeocodingProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            GeocodingProvider::Nominatim => "nominatim",
+            GeocodingProvider::OpenMeteo => "open-meteo",
+        })
+    }
+}
+
+impl FromStr for GeocodingProvider {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+            "nominatim" => Ok(GeocodingProvider::Nominatim),
+            "open-meteo" | "openmeteo" => Ok(GeocodingProvider::OpenMeteo),
+            other => Err(format!(
+                "unknown geocoder '{other}' (expected nominatim or open-meteo)"
+            )),
+        }
+    }
+}
+
+/// Error for a location every geocoder tried failed on, naming each one.
+/// `LocationNotFound`, with the geocoders searched, only when none of them
+/// knew the place, since a provider that failed might have; a rate limit or
+/// a request held back for one is passed on unchanged.
+pub(crate) fn combine_failures(
+    location: &str,
+    mut failures: Vec<(impl Geocoder, WeatherError)>,
+) -> WeatherError {
+    if failures.len() == 1 {
+        return failures.remove(0).1;
+    }
+    if let Some(index) = failures.iter().position(|(_, e)| {
+        matches!(
+            e,
+            WeatherError::RateLimited { .. } | WeatherError::Throttled { .. }
+        )
+    }) {
+        return failures.remove(index).1;
+    }
+    if failures
+        .iter()
+        .all(|(_, e)| matches!(e, WeatherError::LocationNotFound { .. }))
+    {
+        return WeatherError::LocationNotFound {
+            location: location.to_string(),
+            searched: failures
+                .iter()
+                .map(|(geocoder, _)| geocoder.name())
+                .collect(),
+        };
+    }
+    let tried = failures
+        .iter()
+        .map(|(geocoder, e)| format!("{} ({e})", geocoder.name()))
+        .collect::<Vec<_>>()
+        .join(" and ");
+    let message = format!("geocoding '{location}' failed with {tried}");
+    // The first real failure decides the kind, e.g. a timeout
+    let (_, first) = failures
+        .into_iter()
+        .find(|(_, e)| !matches!(e, WeatherError::LocationNotFound { .. }))
+        .expect("a failure other than not found");
+    match first {
+        WeatherError::NetworkError(_) => WeatherError::NetworkError(message),
+        WeatherError::ParseError(_) => WeatherError::ParseError(message),
+        WeatherError::Timeout(_) => WeatherError::Timeout(message),
+        WeatherError::StorageError(_) => WeatherError::StorageError(message),
+        WeatherError::ServerError(_) => WeatherError::ServerError(message),
+        _ => WeatherError::ApiError(message),
+    }
+}
+
+/// Name in a Nominatim reverse geocoding response, `None` when nothing is
+/// at the coordinates (open sea), which Nominatim reports as an `error`
+pub(crate) fn reverse_name(json: &serde_json::Value) -> Option<&str> {
+    json["display_name"].as_str()
+}
+
+/// First and last parts of a provider's full name, "Bern, Switzerland" for
+/// "Bern, Verwaltungskreis Bern-Mittelland, Bern, Switzerland"
+pub(crate) fn short_name(name: &str) -> String {
+    let parts: Vec<&str> = name
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect();
+    match parts.as_slice() {
+        [] => name.trim().to_string(),
+        [only] => only.to_string(),
+        [first, .., last] => format!("{first}, {last}"),
+    }
+}
+
+/// Open-Meteo geocoding response; `results` is absent when nothing matched
+#[derive(Debug, Deserialize)]
+pub(crate) struct OpenMeteoGeocoding {
+    #[serde(default)]
+    results: Vec<OpenMeteoPlace>,
+}
+
+/// One Open-Meteo geocoding result
+#[derive(Debug, Deserialize)]
+struct OpenMeteoPlace {
+    name: String,
+    latitude: f64,
+    longitude: f64,
+    /// First-level administrative region, e.g. "England"
+    admin1: Option<String>,
+    country: Option<String>,
+    population: Option<u64>,
+    /// GeoNames feature code, e.g. "PPLC" for a capital
+    feature_code: Option<String>,
+    /// IANA time zone, e.g. "Europe/London"
+    timezone: Option<String>,
+}
+
+impl OpenMeteoPlace {
+    fn has_valid_coordinates(&self) -> bool {
+        self.latitude.is_finite()
+            && self.latitude.abs() <= 90.0
+            && self.longitude.is_finite()
+            && self.longitude.abs() <= 180.0
+    }
+
+    fn to_result(&self) -> GeocodeResult {
+        GeocodeResult {
+            display_name: self.display_name(),
+            latitude: self.latitude,
+            longitude: self.longitude,
+            place_type: self.feature_code.clone(),
+            region: self.admin1.clone(),
+            country: self.country.clone(),
+            timezone: self.timezone.clone(),
+        }
+    }
+
+    /// "London, England, United Kingdom"
+    fn display_name(&self) -> String {
+        [
+            Some(&self.name),
+            self.admin1.as_ref(),
+            self.country.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+}
+
+/// The most populous result with valid coordinates; on equal population the
+/// API's own ranking wins
+fn parse_open_meteo(
+    response: OpenMeteoGeocoding,
+    location: &str,
+) -> Result<GeocodeMatch, WeatherError> {
+    if response.results.is_empty() {
+        return Err(WeatherError::location_not_found(location));
+    }
+
+    let mut skipped = Vec::new();
+    let mut best: Option<&OpenMeteoPlace> = None;
+    for (index, place) in response.results.iter().enumerate() {
+        if !place.has_valid_coordinates() {
+            skipped.push(format!(
+                "result {}: invalid coordinates for {}",
+                index + 1,
+                place.display_name()
+            ));
+            continue;
+        }
+        if best.is_none_or(|b| place.population > b.population) {
+            best = Some(place);
+        }
+    }
+
+    let place = best.ok_or_else(|| {
+        WeatherError::ParseError(format!(
+            "None of the {} geocoding results for '{location}' had valid coordinates (first: {})",
+            skipped.len(),
+            skipped[0]
+        ))
+    })?;
+    Ok(GeocodeMatch {
+        latitude: place.latitude,
+        longitude: place.longitude,
+        timezone: place.timezone.clone(),
+        name: Some(place.display_name()),
+        skipped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fixture(name: &str) -> serde_json::Value {
+        let path = format!(
+            "{}/tests/fixtures/{name}/geocoding.json",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_open_meteo_fixtures() {
+        let london = GeocodingProvider::OpenMeteo
+            .parse(fixture("london"), "London")
+            .unwrap();
+        assert_eq!((london.latitude, london.longitude), (51.50853, -0.12574));
+        assert_eq!(london.timezone.as_deref(), Some("Europe/London"));
+        assert_eq!(
+            london.name.as_deref().map(short_name).as_deref(),
+            Some("London, United Kingdom")
+        );
+
+        let sydney = GeocodingProvider::OpenMeteo
+            .parse(fixture("sydney"), "Sydney")
+            .unwrap();
+        assert!(sydney.latitude < 0.0);
+        assert_eq!(sydney.timezone.as_deref(), Some("Australia/Sydney"));
+    }
+
+    #[test]
+    fn test_prefers_higher_population() {
+        let json = json!({"results": [
+            {"name": "Paris", "latitude": 33.66, "longitude": -95.56, "country": "United States",
+             "population": 24782, "timezone": "America/Chicago"},
+            {"name": "Paris", "latitude": 91.0, "longitude": 2.35, "population": 9_000_000},
+            {"name": "Paris", "latitude": 48.85, "longitude": 2.35, "admin1": "Île-de-France",
+             "country": "France", "population": 2138551, "timezone": "Europe/Paris"},
+            {"name": "Paris", "latitude": 36.30, "longitude": -88.33}
+        ]});
+        let found = GeocodingProvider::OpenMeteo.parse(json, "Paris").unwrap();
+        assert_eq!(found.latitude, 48.85);
+        assert_eq!(found.timezone.as_deref(), Some("Europe/Paris"));
+        assert_eq!(
+            found.skipped,
+            vec!["result 2: invalid coordinates for Paris"]
+        );
+    }
+
+    #[test]
+    fn test_no_results_is_location_not_found() {
+        let result = GeocodingProvider::OpenMeteo.parse(json!({"generationtime_ms": 0.5}), "Xyzzy");
+        assert!(matches!(result, Err(WeatherError::LocationNotFound { .. })));
+    }
+
+    #[test]
+    fn test_parse_candidates() {
+        let springfields = json!([
+            {"lat": "39.7990", "lon": "-89.6440", "addresstype": "city", "type": "administrative",
+             "display_name": "Springfield, Sangamon County, Illinois, United States",
+             "address": {"city": "Springfield", "country": "United States"}},
+            {"lat": "north", "lon": "-72.59", "display_name": "Springfield, Massachusetts"},
+            {"lat": "37.2090", "lon": "-93.2923", "type": "city",
+             "display_name": "Springfield, Greene County, Missouri, United States"},
+            {"lat": "44.05", "lon": "-123.02", "name": "Springfield",
+             "address": {"country": "United States"}},
+            {"lat": "0.0", "lon": "0.0"}
+        ]);
+        let found = GeocodingProvider::Nominatim
+            .parse_candidates(springfields, "Springfield")
+            .unwrap();
+        assert_eq!(found.len(), 3);
+        assert_eq!((found[0].latitude, found[0].longitude), (39.799, -89.644));
+        assert_eq!(found[0].place_type.as_deref(), Some("city"));
+        assert_eq!(found[0].country.as_deref(), Some("United States"));
+        assert_eq!(
+            found[1].display_name,
+            "Springfield, Greene County, Missouri, United States"
+        );
+        assert_eq!(found[1].country.as_deref(), Some("United States"));
+        // Without a full name the entry keeps its own name and country
+        assert_eq!(found[2].display_name, "Springfield, United States");
+        assert_eq!(found[2].label(), "Springfield, United States");
+
+        let london = GeocodingProvider::OpenMeteo
+            .parse_candidates(fixture("london"), "London")
+            .unwrap();
+        assert!(london.len() > 1);
+        assert_eq!(london[0].display_name, "London, England, United Kingdom");
+        assert_eq!(london[0].label(), "London, England, United Kingdom");
+        assert_eq!(london[0].place_type.as_deref(), Some("PPLC"));
+        assert_eq!(london[1].country.as_deref(), Some("Canada"));
+        assert_eq!(london[1].timezone.as_deref(), Some("America/Toronto"));
+    }
+
+    #[test]
+    fn test_no_candidates() {
+        for (provider, empty) in [
+            (GeocodingProvider::Nominatim, json!([])),
+            (
+                GeocodingProvider::OpenMeteo,
+                json!({"generationtime_ms": 0.5}),
+            ),
+        ] {
+            assert!(matches!(
+                provider.parse_candidates(empty, "Xyzzy"),
+                Err(WeatherError::LocationNotFound { .. })
+            ));
+        }
+        let malformed = json!([{"lat": "", "lon": "1.0", "display_name": "Nowhere"}]);
+        assert!(matches!(
+            GeocodingProvider::Nominatim.parse_candidates(malformed, "Nowhere"),
+            Err(WeatherError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn test_combined_failures_name_every_geocoder() {
+        let nominatim = GeocodingProvider::Nominatim;
+        let open_meteo = GeocodingProvider::OpenMeteo;
+        let not_found = || WeatherError::location_not_found("Xyzzy");
+
+        let error = combine_failures(
+            "Xyzzy",
+            vec![
+                (
+                    nominatim,
+                    WeatherError::ApiError("Geocoding API returned 403".into()),
+                ),
+                (open_meteo, not_found()),
+            ],
+        );
+        assert!(matches!(error, WeatherError::ApiError(_)));
+        assert_eq!(
+            error.to_string(),
+            "API error: geocoding 'Xyzzy' failed with Nominatim (API error: Geocoding API \
+             returned 403) and Open-Meteo (Location 'Xyzzy' not found)"
+        );
+
+        let error = combine_failures(
+            "Xyzzy",
+            vec![
+                (nominatim, not_found()),
+                (open_meteo, WeatherError::Timeout("slow".into())),
+            ],
+        );
+        assert!(matches!(error, WeatherError::Timeout(_)));
+        let error = combine_failures(
+            "Xyzzy",
+            vec![(nominatim, not_found()), (open_meteo, not_found())],
+        );
+        assert!(matches!(error, WeatherError::LocationNotFound { .. }));
+        assert_eq!(
+            error.to_string(),
+            "Location 'Xyzzy' not found by Nominatim or Open-Meteo"
+        );
+        // A single geocoder's failure is passed on as it is
+        assert_eq!(
+            combine_failures(
+                "Xyzzy",
+                vec![(nominatim, WeatherError::ParseError("bad".into()))]
+            )
+            .to_string(),
+            "Parse error: bad"
+        );
+    }
+
+    #[test]
+    fn test_short_and_reverse_names() {
+        assert_eq!(
+            short_name("Bern, Verwaltungskreis Bern-Mittelland, Bern, Switzerland"),
+            "Bern, Switzerland"
+        );
+        assert_eq!(short_name("Monaco"), "Monaco");
+        assert_eq!(short_name(" Oslo , Norway"), "Oslo, Norway");
+        assert_eq!(
+            reverse_name(&json!({"display_name": "Zermatt, Visp, Valais, Switzerland"})),
+            Some("Zermatt, Visp, Valais, Switzerland")
+        );
+        assert_eq!(reverse_name(&json!({"error": "Unable to geocode"})), None);
+    }
+
+    #[test]
+    fn test_search_urls() {
+        let anonymous = UserAgent::default();
+        let contact = UserAgent::new(None, Some("me@example.com"));
+        assert_eq!(
+            GeocodingProvider::OpenMeteo.search_url("http://geo/v1/search", "São Paulo", &contact),
+            "http://geo/v1/search?name=S%C3%A3o%20Paulo&count=10&language=en&format=json"
+        );
+        assert_eq!(
+            GeocodingProvider::Nominatim.search_url("http://osm/search", "Bern", &anonymous),
+            "http://osm/search?q=Bern&format=json&limit=1"
+        );
+        assert_eq!(
+            GeocodingProvider::Nominatim.search_url("http://osm/search", "Bern", &contact),
+            "http://osm/search?q=Bern&format=json&limit=1&email=me%40example.com"
+        );
+        assert_eq!("open-meteo".parse(), Ok(GeocodingProvider::OpenMeteo));
+        let bern = Coordinates::new(46.95, 7.45).unwrap();
+        assert_eq!(
+            GeocodingProvider::Nominatim.reverse_url("http://osm/search", bern, &contact),
+            Some(
+                "http://osm/reverse?lat=46.95&lon=7.45&format=json&zoom=10&email=me%40example.com"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            GeocodingProvider::OpenMeteo.reverse_url("http://geo/v1/search", bern, &contact),
+            None
+        );
+        assert_eq!(
+            GeocodingProvider::Nominatim.candidates_url("http://osm/search", "Bern", &anonymous, 5),
+            "http://osm/search?q=Bern&format=json&limit=5&addressdetails=1"
+        );
+        assert_eq!(
+            GeocodingProvider::OpenMeteo.candidates_url(
+                "http://geo/v1/search",
+                "Bern",
+                &contact,
+                5
+            ),
+            "http://geo/v1/search?name=Bern&count=5&language=en&format=json"
+        );
+        assert!("google".parse::<GeocodingProvider>().is_err());
+    }
+}
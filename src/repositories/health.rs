@@ -0,0 +1,225 @@
+//! Connectivity checks for the configured API endpoints (`weather doctor`)
+
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use crate::errors::WeatherError;
+use crate::repositories::geocoding::{Geocoder, GeocodingProvider};
+use crate::repositories::weather_repository::ApiWeatherRepository;
+
+/// Outcome of probing a single endpoint
+#[derive(Debug)]
+pub struct HealthCheck {
+    /// Service name, e.g. "Geocoding (Nominatim)"
+    pub service: &'static str,
+    /// Endpoint that was probed
+    pub url: String,
+    /// Round-trip time of the request
+    pub latency: Duration,
+    /// HTTP status, or the classified failure
+    pub result: Result<u16, WeatherError>,
+}
+
+impl HealthCheck {
+    pub fn is_ok(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+/// Probes the geocoding and weather endpoints with one lightweight request
+/// each, never retried
+pub fn run_checks(repository: &ApiWeatherRepository) -> Vec<HealthCheck> {
+    let geocoding_url = repository.geocoder().search_url(
+        repository.geocoding_url(),
+        "London",
+        repository.user_agent(),
+    );
+    let (geocoding_service, is_geocoding_shape): (&'static str, fn(&Value) -> bool) =
+        match repository.geocoder() {
+            GeocodingProvider::Nominatim => ("Geocoding (Nominatim)", |json| json.is_array()),
+            GeocodingProvider::OpenMeteo => ("Geocoding (Open-Meteo)", |json| {
+                json.get("results").is_some_and(Value::is_array)
+            }),
+        };
+    let weather_url = format!(
+        "{}?latitude=51.5&longitude=-0.12&current=temperature_2m",
+        repository.weather_url()
+    );
+
+    // The probe is a search like any other and takes its turn at Nominatim
+    let geocoding_spacing =
+        repository.geocoding_spacing(repository.geocoder(), repository.geocoding_url());
+
+    vec![
+        check(
+            repository,
+            geocoding_service,
+            "Geocoding",
+            geocoding_url,
+            geocoding_spacing,
+            is_geocoding_shape,
+        ),
+        check(
+            repository,
+            "Weather (Open-Meteo)",
+            "Weather",
+            weather_url,
+            Duration::ZERO,
+            |json| json.get("current").is_some(),
+        ),
+    ]
+}
+
+fn check(
+    repository: &ApiWeatherRepository,
+    service: &'static str,
+    api_name: &str,
+    url: String,
+    spacing: Duration,
+    is_expected_shape: fn(&Value) -> bool,
+) -> HealthCheck {
+    let started = Instant::now();
+    let result = repository
+        .send_single(&url, api_name, spacing)
+        .and_then(|response| {
+            let status = response.status().as_u16();
+            let json: Value = response.json().map_err(WeatherError::body)?;
+            if is_expected_shape(&json) {
+                Ok(status)
+            } else {
+                Err(WeatherError::ParseError(format!(
+                    "Unexpected response format from {api_name} API"
+                )))
+            }
+        });
+
+    HealthCheck {
+        service,
+        url,
+        latency: started.elapsed(),
+        result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockRoute, MockServer, SimulatedClock};
+    use std::sync::Arc;
+
+    fn repository_for(server: &MockServer) -> ApiWeatherRepository {
+        ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+    }
+
+    #[test]
+    fn test_healthy_endpoints() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/search").json(serde_json::json!([{"lat": "51.5", "lon": "-0.12"}])),
+            MockRoute::new("/v1/forecast")
+                .json(serde_json::json!({"current": {"temperature_2m": 12.0}})),
+        ]);
+
+        let checks = run_checks(&repository_for(&server));
+        assert_eq!(checks.len(), 2);
+        assert!(checks.iter().all(HealthCheck::is_ok));
+        assert!(matches!(checks[0].result, Ok(200)));
+        assert_eq!(server.hits(), 2);
+    }
+
+    #[test]
+    fn test_open_meteo_geocoder_is_checked() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/v1/search?name=London").json(serde_json::json!({"results": []})),
+            MockRoute::new("/v1/forecast")
+                .json(serde_json::json!({"current": {"temperature_2m": 12.0}})),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_geocoder(GeocodingProvider::OpenMeteo)
+            .with_endpoints(server.url("/v1/search"), server.url("/v1/forecast"));
+
+        let checks = run_checks(&repository);
+        assert_eq!(checks[0].service, "Geocoding (Open-Meteo)");
+        assert!(checks.iter().all(HealthCheck::is_ok));
+    }
+
+    #[test]
+    fn test_geocoding_probe_takes_its_turn() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/search").json(
+                serde_json::json!([{"lat": "51.5", "lon": "-0.12", "display_name": "London"}]),
+            ),
+            MockRoute::new("/v1/forecast")
+                .json(serde_json::json!({"current": {"temperature_2m": 12.0}})),
+        ]);
+        let clock = Arc::new(SimulatedClock::default());
+        let interval = Duration::from_secs(1);
+        let repository = repository_for(&server)
+            .with_nominatim_interval(interval)
+            .with_spacing_clock(clock.clone());
+
+        repository.geocode("London").unwrap();
+        let checks = run_checks(&repository);
+        assert!(checks.iter().all(HealthCheck::is_ok));
+        // The forecast probe on the same server is no Nominatim request
+        assert_eq!(clock.waits(), [Duration::ZERO, interval]);
+    }
+
+    #[test]
+    fn test_error_status_is_reported() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/search").status(500),
+            MockRoute::new("/v1/forecast")
+                .json(serde_json::json!({"current": {"temperature_2m": 12.0}})),
+        ]);
+
+        let checks = run_checks(&repository_for(&server));
+        assert!(matches!(
+            checks[0].result,
+            Err(WeatherError::ServerError(_))
+        ));
+        assert!(checks[1].is_ok());
+    }
+
+    #[test]
+    fn test_unavailable_service_reports_cooldown() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/search").status(503),
+            MockRoute::new("/v1/forecast")
+                .json(serde_json::json!({"current": {"temperature_2m": 12.0}})),
+        ]);
+
+        // The cooldown also holds back the second check instead of probing again
+        let checks = run_checks(&repository_for(&server));
+        assert!(checks
+            .iter()
+            .all(|check| matches!(check.result, Err(WeatherError::RateLimited { .. }))));
+        assert_eq!(server.hits(), 1);
+    }
+
+    #[test]
+    fn test_unparseable_response_is_reported() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/search").body("<html>maintenance</html>"),
+            MockRoute::new("/v1/forecast").json(serde_json::json!({"unexpected": true})),
+        ]);
+
+        let checks = run_checks(&repository_for(&server));
+        assert!(matches!(checks[0].result, Err(WeatherError::ParseError(_))));
+        assert!(matches!(checks[1].result, Err(WeatherError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_unreachable_endpoint_is_network_error() {
+        let repository = ApiWeatherRepository::new().with_endpoints(
+            "http://127.0.0.1:1/search",
+            "http://127.0.0.1:1/v1/forecast",
+        );
+
+        let checks = run_checks(&repository);
+        assert!(checks
+            .iter()
+            .all(|c| matches!(c.result, Err(WeatherError::NetworkError(_)))));
+    }
+}
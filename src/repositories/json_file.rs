@@ -0,0 +1,60 @@
+//! Small JSON documents in the user's config directory
+
+use crate::errors::WeatherError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// A JSON file holding one document
+#[derive(Clone, Debug)]
+pub(crate) struct JsonFile {
+    path: PathBuf,
+}
+
+impl JsonFile {
+    pub(crate) fn new(path: impl Into<PathBuf>) -> Self {
+        JsonFile { path: path.into() }
+    }
+
+    /// `<config dir>/weather-app/<file_name>`, if the platform has a config directory
+    pub(crate) fn in_config_dir(file_name: &str) -> Option<Self> {
+        dirs::config_dir().map(|dir| Self::new(dir.join("weather-app").join(file_name)))
+    }
+
+    /// `<cache dir>/weather-app/<file_name>`, for data that may be lost
+    pub(crate) fn in_cache_dir(file_name: &str) -> Option<Self> {
+        dirs::cache_dir().map(|dir| Self::new(dir.join("weather-app").join(file_name)))
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads the document; a missing file yields the default value
+    pub(crate) fn load<T: DeserializeOwned + Default>(&self) -> Result<T, WeatherError> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(T::default()),
+            Err(e) => return Err(self.storage_error(e)),
+        };
+        serde_json::from_str(&contents).map_err(|e| {
+            WeatherError::ParseError(format!("Invalid file {}: {e}", self.path.display()))
+        })
+    }
+
+    /// Writes the document, creating the parent directory when needed
+    pub(crate) fn save<T: Serialize>(&self, value: &T) -> Result<(), WeatherError> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|e| self.storage_error(e))?;
+        }
+        let json = serde_json::to_string_pretty(value)
+            .map_err(|e| WeatherError::ParseError(e.to_string()))?;
+        fs::write(&self.path, json).map_err(|e| self.storage_error(e))
+    }
+
+    fn storage_error(&self, e: std::io::Error) -> WeatherError {
+        WeatherError::StorageError(format!("{}: {e}", self.path.display()))
+    }
+}
@@ -0,0 +1,146 @@
+//! Open-Meteo marine API: hourly waves for coastal locations
+//!
+//! Inland coordinates have no ocean grid point. The API then either rejects
+//! the request with a reason or answers with every value null; both read as
+//! "no marine data" and leave the rest of the forecast untouched.
+
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use crate::constants::{HOURLY_FORECAST_LIMIT, MARINE_FORECAST_DAYS};
+use crate::errors::WeatherError;
+use crate::models::marine::{MarineForecast, MarineHour};
+use crate::repositories::sanitize;
+use crate::repositories::weather_repository::ApiWeatherRepository;
+
+/// Hourly variables requested from the marine API
+pub(crate) const MARINE_HOURLY_VARIABLES: &[&str] =
+    &["wave_height", "wave_period", "wave_direction"];
+
+#[derive(Debug, Deserialize)]
+struct OpenMeteoMarine {
+    hourly: Option<OpenMeteoMarineHourly>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenMeteoMarineHourly {
+    time: Vec<String>,
+    #[serde(default)]
+    wave_height: Vec<Option<f64>>,
+    #[serde(default)]
+    wave_period: Vec<Option<f64>>,
+    #[serde(default)]
+    wave_direction: Vec<Option<f64>>,
+}
+
+/// Marine request for the same point and time zone as the forecast, so the
+/// hours line up with the hourly forecast
+pub(crate) fn marine_url(base_url: &str, lat: f64, lon: f64, timezone: Option<&str>) -> String {
+    format!(
+        "{base_url}?latitude={lat}&longitude={lon}&hourly={}&forecast_days={MARINE_FORECAST_DAYS}&timezone={}",
+        MARINE_HOURLY_VARIABLES.join(","),
+        timezone.map_or("auto".into(), urlencoding::encode)
+    )
+}
+
+/// Reads a marine response: `Ok(None)` for points without marine data,
+/// `Err` for server failures and unreadable bodies
+pub(crate) fn parse_marine_response(
+    status: StatusCode,
+    body: &str,
+) -> Result<Option<MarineForecast>, WeatherError> {
+    if status == StatusCode::BAD_REQUEST {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        return Err(ApiWeatherRepository::api_status_error(
+            "Marine", status, body,
+        ));
+    }
+
+    let (json, _) = sanitize::non_finite_to_null(body);
+    let marine: OpenMeteoMarine =
+        serde_json::from_str(&json).map_err(|e| WeatherError::ParseError(e.to_string()))?;
+    let Some(hourly) = marine.hourly else {
+        return Ok(None);
+    };
+
+    let value = |values: &[Option<f64>], i: usize| values.get(i).copied().flatten();
+    let hours = hourly
+        .time
+        .iter()
+        .take(HOURLY_FORECAST_LIMIT)
+        .enumerate()
+        .map(|(i, time)| MarineHour {
+            wave_height: value(&hourly.wave_height, i).filter(|h| *h >= 0.0),
+            wave_period: value(&hourly.wave_period, i).filter(|p| *p >= 0.0),
+            wave_direction: value(&hourly.wave_direction, i),
+            ..MarineHour::new(time.as_str())
+        })
+        .collect();
+    Ok(MarineForecast::from_hours(hours))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_coastal_response() {
+        let body = r#"{
+            "latitude": -33.875, "longitude": 151.25,
+            "hourly_units": {"wave_height": "m", "wave_period": "s", "wave_direction": "°"},
+            "hourly": {
+                "time": ["2025-12-10T00:00", "2025-12-10T01:00", "2025-12-10T02:00"],
+                "wave_height": [1.42, NaN, 1.38],
+                "wave_period": [8.9, 9.1, -1.0],
+                "wave_direction": [135, 140, null]
+            }
+        }"#;
+        let forecast = parse_marine_response(StatusCode::OK, body)
+            .unwrap()
+            .unwrap();
+        assert_eq!(forecast.hourly.len(), 3);
+        assert_eq!(forecast.hourly[0].wave_height, Some(1.42));
+        assert_eq!(forecast.hourly[0].wave_direction, Some(135.0));
+        assert_eq!(forecast.hourly[1].wave_height, None);
+        assert_eq!(forecast.hourly[2].wave_period, None);
+    }
+
+    #[test]
+    fn test_inland_responses_have_no_marine_data() {
+        let all_null = r#"{"hourly": {
+            "time": ["2025-12-10T00:00", "2025-12-10T01:00"],
+            "wave_height": [null, null], "wave_period": [null, null], "wave_direction": [null, null]
+        }}"#;
+        assert_eq!(
+            parse_marine_response(StatusCode::OK, all_null).unwrap(),
+            None
+        );
+        assert_eq!(parse_marine_response(StatusCode::OK, "{}").unwrap(), None);
+
+        let rejected = r#"{"error": true, "reason": "No data is available for this location"}"#;
+        assert_eq!(
+            parse_marine_response(StatusCode::BAD_REQUEST, rejected).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_server_errors_are_reported() {
+        let err = parse_marine_response(StatusCode::BAD_GATEWAY, "").unwrap_err();
+        assert!(
+            err.to_string().contains("Marine API returned status: 502"),
+            "{err}"
+        );
+        assert!(parse_marine_response(StatusCode::OK, "not json").is_err());
+    }
+
+    #[test]
+    fn test_marine_url() {
+        assert_eq!(
+            marine_url("http://marine.local/v1/marine", -33.87, 151.21, Some("Australia/Sydney")),
+            "http://marine.local/v1/marine?latitude=-33.87&longitude=151.21&hourly=wave_height,wave_period,wave_direction&forecast_days=3&timezone=Australia%2FSydney"
+        );
+    }
+}
@@ -0,0 +1,666 @@
+//! Norwegian Meteorological Institute (Met.no, the forecast behind Yr) as
+//! the forecast source
+//!
+//! Locationforecast is free and needs no key, but like Nominatim it refuses
+//! requests without a User-Agent that identifies the app. The compact
+//! format is one timeseries: hourly steps for the next two and a half days,
+//! then six-hourly ones, each with the instant's values and a summary of
+//! the next 1 (or 6) hours' weather and precipitation. Daily values are
+//! aggregated from those steps.
+//!
+//! Its times are UTC. The location's offset comes from Open-Meteo, asked
+//! alongside the forecast for the zone the geocoder named (or the point's
+//! own), and applies to the whole forecast, so a clock change within it
+//! shifts the later hours by one. Should that request fail, the forecast is
+//! shown in mean solar time to the hour, one hour per 15° of longitude.
+//! Locations are geocoded, and requests sent, by an `ApiWeatherRepository`.
+
+use std::collections::BTreeMap;
+use std::thread;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeDelta};
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use crate::constants::{HOURLY_FORECAST_LIMIT, METNO_API_URL};
+use crate::errors::WeatherError;
+use crate::models::favorites::ResolvedPlace;
+use crate::models::weather_info::{
+    CurrentWeather, DailyForecast, HourlyForecast, WeatherData, WeatherInfo,
+};
+use crate::repositories::provider::WeatherProvider;
+use crate::repositories::weather_repository::{ApiWeatherRepository, WeatherRepository};
+use crate::utils::conversions::Speed;
+
+#[derive(Debug, Deserialize)]
+struct MetNoForecast {
+    geometry: Option<MetNoGeometry>,
+    properties: MetNoProperties,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetNoGeometry {
+    /// Longitude, latitude and elevation in meters
+    coordinates: Vec<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetNoProperties {
+    #[serde(default)]
+    timeseries: Vec<MetNoStep>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetNoStep {
+    /// UTC, e.g. "2025-12-10T10:00:00Z"
+    time: String,
+    data: MetNoStepData,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetNoStepData {
+    instant: MetNoInstant,
+    next_1_hours: Option<MetNoPeriod>,
+    next_6_hours: Option<MetNoPeriod>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetNoInstant {
+    details: MetNoInstantDetails,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MetNoInstantDetails {
+    /// Sea-level pressure in hPa
+    air_pressure_at_sea_level: Option<f64>,
+    /// °C
+    air_temperature: Option<f64>,
+    /// Percent
+    cloud_area_fraction: Option<f64>,
+    relative_humidity: Option<f64>,
+    wind_from_direction: Option<f64>,
+    /// m/s
+    wind_speed: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetNoPeriod {
+    summary: Option<MetNoSummary>,
+    details: Option<MetNoPeriodDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetNoSummary {
+    /// e.g. "lightrainshowers_day"
+    symbol_code: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetNoPeriodDetails {
+    /// mm over the period
+    precipitation_amount: Option<f64>,
+}
+
+impl MetNoPeriod {
+    fn symbol(&self) -> Option<&str> {
+        self.summary.as_ref().map(|s| s.symbol_code.as_str())
+    }
+
+    fn precipitation(&self) -> Option<f64> {
+        self.details.as_ref().and_then(|d| d.precipitation_amount)
+    }
+}
+
+/// Forecast from Met.no's Locationforecast
+pub struct MetNoRepository {
+    /// Geocodes locations and sends the requests
+    api: ApiWeatherRepository,
+    metno_url: String,
+}
+
+impl MetNoRepository {
+    pub fn new(api: ApiWeatherRepository) -> Self {
+        MetNoRepository {
+            api,
+            metno_url: METNO_API_URL.to_string(),
+        }
+    }
+
+    /// Locationforecast compact endpoint (default `METNO_API_URL`)
+    pub fn with_metno_url(mut self, url: impl Into<String>) -> Self {
+        self.metno_url = url.into();
+        self
+    }
+
+    pub fn metno_url(&self) -> &str {
+        &self.metno_url
+    }
+
+    /// Offset and zone name at the point, or mean solar time when Open-Meteo
+    /// cannot say
+    fn local_zone(&self, lat: f64, lon: f64, timezone: Option<&str>) -> LocalZone {
+        match self.api.utc_offset_at(lat, lon, timezone) {
+            Ok((offset, name)) => LocalZone {
+                offset,
+                name: name.or(timezone.map(str::to_string)),
+            },
+            Err(e) => {
+                if self.api.verbose() {
+                    eprintln!("Note: no time zone for {lat:.4},{lon:.4}, using solar time: {e}");
+                }
+                LocalZone::solar(lon)
+            }
+        }
+    }
+}
+
+/// Where the forecast's UTC times are shown
+#[derive(Debug, Clone, PartialEq)]
+struct LocalZone {
+    /// Seconds east of UTC
+    offset: i32,
+    /// IANA name, e.g. "Europe/Oslo"; none for solar time
+    name: Option<String>,
+}
+
+impl LocalZone {
+    /// Mean solar time to the hour
+    fn solar(longitude: f64) -> Self {
+        LocalZone {
+            offset: solar_offset_seconds(longitude),
+            name: None,
+        }
+    }
+}
+
+impl WeatherRepository for MetNoRepository {
+    fn fetch_weather(&self, location: &str) -> Result<WeatherInfo, WeatherError> {
+        self.api.fetch_located(location, |found, name| {
+            let (lat, lon) = (found.latitude, found.longitude);
+            // Met.no's terms allow at most four decimals, so caches can share answers
+            let url = format!("{}?lat={lat:.4}&lon={lon:.4}", self.metno_url);
+            let (forecast, zone) = thread::scope(|scope| {
+                let zone = scope.spawn(|| self.local_zone(lat, lon, found.timezone.as_deref()));
+                let forecast = self.api.get_text(&url);
+                (
+                    forecast,
+                    zone.join().unwrap_or_else(|p| std::panic::resume_unwind(p)),
+                )
+            });
+            let (status, body) = forecast?;
+            let forecast = parse_forecast_response(status, &body)?;
+
+            build_weather_info(
+                name,
+                lat,
+                lon,
+                forecast,
+                zone,
+                self.api.forecast_days(),
+                self.api.verbose(),
+            )
+        })
+    }
+
+    fn response_cache_hits(&self) -> usize {
+        self.api.response_cache_hits()
+    }
+
+    fn clear_response_cache(&self) {
+        self.api.clear_response_cache()
+    }
+
+    fn resolve_place(
+        &self,
+        location: &str,
+        recorded: Option<&ResolvedPlace>,
+    ) -> Result<Option<ResolvedPlace>, WeatherError> {
+        self.api.resolve_place(location, recorded)
+    }
+
+    fn provider(&self) -> WeatherProvider {
+        WeatherProvider::MetNo
+    }
+}
+
+/// Reads a forecast response. Met.no answers 403 to requests it cannot
+/// tell apart, so that error says how to identify the app.
+fn parse_forecast_response(status: StatusCode, body: &str) -> Result<MetNoForecast, WeatherError> {
+    if status == StatusCode::FORBIDDEN {
+        return Err(WeatherError::ApiError(format!(
+            "Met.no API returned status: {status}; it requires a User-Agent that identifies the app, so set \"contact_email\" in the settings"
+        )));
+    }
+    if !status.is_success() {
+        return Err(WeatherError::status(
+            status,
+            format!("Met.no API returned status: {status}"),
+        ));
+    }
+    serde_json::from_str(body).map_err(|e| WeatherError::ParseError(e.to_string()))
+}
+
+/// A step of the timeseries in local time, with the period it summarizes
+struct Step<'a> {
+    time: NaiveDateTime,
+    details: &'a MetNoInstantDetails,
+    /// The next hour's summary, or the next six hours' where the steps are
+    /// six hours apart
+    period: Option<&'a MetNoPeriod>,
+    /// Whether `period` is the next hour's
+    hourly: bool,
+}
+
+fn build_weather_info(
+    location: &str,
+    lat: f64,
+    lon: f64,
+    forecast: MetNoForecast,
+    zone: LocalZone,
+    forecast_days: usize,
+    verbose: bool,
+) -> Result<WeatherInfo, WeatherError> {
+    let offset = zone.offset;
+    let steps = local_steps(&forecast.properties.timeseries, offset);
+    let first = steps
+        .first()
+        .ok_or_else(|| WeatherError::ParseError("Met.no forecast has no timeseries".to_string()))?;
+
+    let mut weather_data = WeatherData {
+        current: current_weather(first),
+        hourly: steps
+            .iter()
+            .filter(|step| step.hourly)
+            .take(HOURLY_FORECAST_LIMIT)
+            .map(hourly_forecast)
+            .collect(),
+        daily: daily_forecasts(&steps)
+            .into_iter()
+            .take(forecast_days)
+            .collect(),
+        minutely: Vec::new(),
+    };
+    ApiWeatherRepository::prepare_weather_data(&mut weather_data, verbose);
+
+    let mut info = WeatherInfo::new(location.to_string(), lat, lon, weather_data);
+    info.elevation = forecast
+        .geometry
+        .and_then(|geometry| geometry.coordinates.get(2).copied());
+    info.utc_offset_seconds = Some(offset);
+    info.timezone = zone.name;
+    info.fetched_at = Some(chrono::Utc::now());
+    Ok(info)
+}
+
+/// Steps with parseable times, each with the period reaching to the next step
+fn local_steps(timeseries: &[MetNoStep], offset: i32) -> Vec<Step<'_>> {
+    let times: Vec<Option<NaiveDateTime>> = timeseries
+        .iter()
+        .map(|step| {
+            DateTime::parse_from_rfc3339(&step.time)
+                .ok()
+                .map(|time| time.naive_utc() + TimeDelta::seconds(offset.into()))
+        })
+        .collect();
+    timeseries
+        .iter()
+        .zip(&times)
+        .enumerate()
+        .filter_map(|(i, (step, time))| {
+            let time = (*time)?;
+            let gap = times.get(i + 1).copied().flatten().map(|next| next - time);
+            // The six-hour summary where the next step is six hours away, as
+            // at the end of the hourly steps, so the periods cover the whole
+            // forecast without overlapping
+            let hourly =
+                step.data.next_1_hours.is_some() && gap.is_none_or(|gap| gap < TimeDelta::hours(6));
+            let period = if hourly {
+                step.data.next_1_hours.as_ref()
+            } else {
+                step.data.next_6_hours.as_ref()
+            };
+            Some(Step {
+                time,
+                details: &step.data.instant.details,
+                period,
+                hourly,
+            })
+        })
+        .collect()
+}
+
+fn current_weather(step: &Step) -> CurrentWeather {
+    let symbol = step.period.and_then(MetNoPeriod::symbol);
+    CurrentWeather {
+        time: Some(format_time(&step.time)),
+        temperature: step.details.air_temperature,
+        humidity: step.details.relative_humidity,
+        precipitation: step.period.and_then(MetNoPeriod::precipitation),
+        weather_code: symbol.and_then(weather_code),
+        wind_speed: step.details.wind_speed.map(Speed::ms_to_kmh),
+        wind_direction: step.details.wind_from_direction,
+        cloud_cover: step.details.cloud_area_fraction,
+        pressure_msl: step.details.air_pressure_at_sea_level,
+        is_day: symbol.and_then(is_day),
+        ..CurrentWeather::default()
+    }
+}
+
+fn hourly_forecast(step: &Step) -> HourlyForecast {
+    HourlyForecast {
+        time: format_time(&step.time),
+        temperature: step.details.air_temperature,
+        precipitation: step.period.and_then(MetNoPeriod::precipitation),
+        weather_code: step
+            .period
+            .and_then(MetNoPeriod::symbol)
+            .and_then(weather_code),
+        wind_speed: step.details.wind_speed.map(Speed::ms_to_kmh),
+        wind_direction: step.details.wind_from_direction,
+        humidity: step.details.relative_humidity,
+        cloud_cover: step.details.cloud_area_fraction,
+        ..HourlyForecast::default()
+    }
+}
+
+/// One day per local date the steps touch: lowest and highest instant
+/// temperature, strongest wind, precipitation summed over the periods that
+/// start that day, and the most severe weather, as Open-Meteo's daily code is
+fn daily_forecasts(steps: &[Step]) -> Vec<DailyForecast> {
+    let mut days: BTreeMap<NaiveDate, DailyForecast> = BTreeMap::new();
+    for step in steps {
+        let date = step.time.date();
+        let day = days.entry(date).or_insert_with(|| DailyForecast {
+            date: date.format("%Y-%m-%d").to_string(),
+            ..DailyForecast::default()
+        });
+        let temperature = step.details.air_temperature;
+        day.temperature_min = min(day.temperature_min, temperature);
+        day.temperature_max = max(day.temperature_max, temperature);
+        day.wind_speed_max = max(
+            day.wind_speed_max,
+            step.details.wind_speed.map(Speed::ms_to_kmh),
+        );
+        if let Some(amount) = step.period.and_then(MetNoPeriod::precipitation) {
+            day.precipitation_sum = Some(day.precipitation_sum.unwrap_or(0.0) + amount);
+        }
+        let code = step
+            .period
+            .and_then(MetNoPeriod::symbol)
+            .and_then(weather_code);
+        day.weather_code = max(day.weather_code, code);
+    }
+    days.into_values().collect()
+}
+
+fn min<T: PartialOrd>(a: Option<T>, b: Option<T>) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(if b < a { b } else { a }),
+        (a, b) => a.or(b),
+    }
+}
+
+fn max<T: PartialOrd>(a: Option<T>, b: Option<T>) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(if b > a { b } else { a }),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Mean solar time's offset from UTC, rounded to the hour
+fn solar_offset_seconds(longitude: f64) -> i32 {
+    (longitude / 15.0).round() as i32 * 3600
+}
+
+fn format_time(time: &NaiveDateTime) -> String {
+    time.format("%Y-%m-%dT%H:%M").to_string()
+}
+
+/// Whether a symbol is for daylight: "_day" by day, "_night" and
+/// "_polartwilight" otherwise, and no suffix where it looks the same
+fn is_day(symbol: &str) -> Option<bool> {
+    let (_, variant) = symbol.split_once('_')?;
+    Some(variant == "day")
+}
+
+/// WMO code for a Met.no symbol, e.g. "lightrainshowers_day" → 80. Sleet,
+/// mixed rain and snow, counts as rain: WMO's codes for it are not among
+/// those Open-Meteo uses.
+fn weather_code(symbol: &str) -> Option<i32> {
+    let name = symbol.split('_').next()?;
+    let fixed = match name {
+        "clearsky" => Some(0),
+        "fair" => Some(1),
+        "partlycloudy" => Some(2),
+        "cloudy" => Some(3),
+        "fog" => Some(45),
+        _ => None,
+    };
+    if fixed.is_some() {
+        return fixed;
+    }
+    if name.contains("thunder") {
+        return Some(95);
+    }
+    // "lightssleet..." and "lightssnow..." are Met.no's own spellings
+    let (intensity, kind) = if let Some(kind) = name
+        .strip_prefix("lights")
+        .filter(|kind| kind.starts_with("sleet") || kind.starts_with("snow"))
+    {
+        (0, kind)
+    } else if let Some(kind) = name.strip_prefix("light") {
+        (0, kind)
+    } else if let Some(kind) = name.strip_prefix("heavy") {
+        (2, kind)
+    } else {
+        (1, name)
+    };
+    let showers = kind.ends_with("showers");
+    Some(match (kind.trim_end_matches("showers"), showers) {
+        ("rain" | "sleet", false) => [61, 63, 65][intensity],
+        ("rain" | "sleet", true) => [80, 81, 82][intensity],
+        ("snow", false) => [71, 73, 75][intensity],
+        ("snow", true) => [85, 85, 86][intensity],
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::geocoding::GeocodingProvider;
+    use crate::repositories::retry::RetryPolicy;
+    use crate::test_support::{MockRoute, MockServer};
+
+    /// Oslo (10.75° E, so UTC+1 solar time), trimmed from a recorded
+    /// compact response: hourly steps up to 18:00 UTC, then six-hourly
+    const OSLO: &str = r#"{
+        "type": "Feature",
+        "geometry": {"type": "Point", "coordinates": [10.7522, 59.9139, 23]},
+        "properties": {
+            "meta": {"updated_at": "2025-12-10T09:41:17Z", "units": {"air_temperature": "celsius"}},
+            "timeseries": [
+                {"time": "2025-12-10T16:00:00Z", "data": {"instant": {"details": {"air_pressure_at_sea_level": 1008.4, "air_temperature": -1.5, "cloud_area_fraction": 90.0, "relative_humidity": 90.0, "wind_from_direction": 200.0, "wind_speed": 2.5}}, "next_1_hours": {"summary": {"symbol_code": "lightsnow"}, "details": {"precipitation_amount": 0.3}}, "next_6_hours": {"summary": {"symbol_code": "snow"}, "details": {"precipitation_amount": 2.1}}}},
+                {"time": "2025-12-10T17:00:00Z", "data": {"instant": {"details": {"air_pressure_at_sea_level": 1008.0, "air_temperature": -2.0, "cloud_area_fraction": 90.0, "relative_humidity": 90.0, "wind_from_direction": 200.0, "wind_speed": 3.0}}, "next_1_hours": {"summary": {"symbol_code": "heavysnowshowers_night"}, "details": {"precipitation_amount": 1.2}}, "next_6_hours": {"summary": {"symbol_code": "snow"}, "details": {"precipitation_amount": 2.4}}}},
+                {"time": "2025-12-10T18:00:00Z", "data": {"instant": {"details": {"air_pressure_at_sea_level": 1008.0, "air_temperature": -2.6, "cloud_area_fraction": 90.0, "relative_humidity": 90.0, "wind_from_direction": 200.0, "wind_speed": 4.0}}, "next_1_hours": {"summary": {"symbol_code": "cloudy"}, "details": {"precipitation_amount": 0.0}}, "next_6_hours": {"summary": {"symbol_code": "partlycloudy_night"}, "details": {"precipitation_amount": 0.4}}}},
+                {"time": "2025-12-11T00:00:00Z", "data": {"instant": {"details": {"air_pressure_at_sea_level": 1008.0, "air_temperature": -4.1, "cloud_area_fraction": 90.0, "relative_humidity": 90.0, "wind_from_direction": 200.0, "wind_speed": 1.5}}, "next_6_hours": {"summary": {"symbol_code": "partlycloudy_night"}, "details": {"precipitation_amount": 0.0}}}},
+                {"time": "2025-12-11T06:00:00Z", "data": {"instant": {"details": {"air_pressure_at_sea_level": 1008.0, "air_temperature": -6.3, "cloud_area_fraction": 90.0, "relative_humidity": 90.0, "wind_from_direction": 200.0, "wind_speed": 1.0}}, "next_6_hours": {"summary": {"symbol_code": "clearsky_day"}, "details": {"precipitation_amount": 0.0}}}},
+                {"time": "2025-12-11T12:00:00Z", "data": {"instant": {"details": {"air_pressure_at_sea_level": 1008.0, "air_temperature": -0.8, "cloud_area_fraction": 90.0, "relative_humidity": 90.0, "wind_from_direction": 200.0, "wind_speed": 5.5}}, "next_6_hours": {"summary": {"symbol_code": "lightrainshowers_day"}, "details": {"precipitation_amount": 0.7}}}},
+                {"time": "2025-12-11T18:00:00Z", "data": {"instant": {"details": {"air_pressure_at_sea_level": 1008.0, "air_temperature": -3.0, "cloud_area_fraction": 90.0, "relative_humidity": 90.0, "wind_from_direction": 200.0, "wind_speed": 2.0}}, "next_6_hours": {"summary": {"symbol_code": "cloudy"}, "details": {"precipitation_amount": 0.1}}}},
+                {"time": "2025-12-12T00:00:00Z", "data": {"instant": {"details": {"air_pressure_at_sea_level": 1008.0, "air_temperature": -3.4, "cloud_area_fraction": 90.0, "relative_humidity": 90.0, "wind_from_direction": 200.0, "wind_speed": 2.2}}}}
+            ]
+        }
+    }"#;
+
+    fn oslo() -> WeatherInfo {
+        let forecast = parse_forecast_response(StatusCode::OK, OSLO).unwrap();
+        let zone = LocalZone::solar(10.7522);
+        build_weather_info("Oslo", 59.9139, 10.7522, forecast, zone, 7, false).unwrap()
+    }
+
+    #[test]
+    fn test_steps_in_solar_time() {
+        let info = oslo();
+        assert_eq!(info.utc_offset_seconds, Some(3600));
+        assert_eq!(info.elevation, Some(23.0));
+        assert_eq!(solar_offset_seconds(-74.0), -5 * 3600);
+        assert_eq!(solar_offset_seconds(151.2), 10 * 3600);
+
+        let current = &info.weather_data.current;
+        assert_eq!(current.time.as_deref(), Some("2025-12-10T17:00"));
+        assert_eq!(current.temperature, Some(-1.5));
+        assert_eq!(current.wind_speed, Some(9.0));
+        assert_eq!(current.pressure_msl, Some(1008.4));
+        assert_eq!(current.precipitation, Some(0.3));
+        assert_eq!(current.weather_code, Some(71));
+    }
+
+    #[test]
+    fn test_only_hourly_steps_are_hours() {
+        // 19:00 is summarized for the six hours up to the next step
+        let hours = oslo().weather_data.hourly;
+        let times: Vec<&str> = hours.iter().map(|h| h.time.as_str()).collect();
+        assert_eq!(times, ["2025-12-10T17:00", "2025-12-10T18:00"]);
+        assert_eq!(hours[1].weather_code, Some(86));
+        assert_eq!(hours[1].precipitation, Some(1.2));
+    }
+
+    #[test]
+    fn test_days_aggregate_hourly_and_six_hourly_steps() {
+        let days = oslo().weather_data.daily;
+        assert_eq!(days.len(), 3);
+
+        // Two hours from 17:00 and six from 19:00, snow showers the worst
+        let wednesday = &days[0];
+        assert_eq!(wednesday.date, "2025-12-10");
+        assert_eq!(wednesday.temperature_min, Some(-2.6));
+        assert_eq!(wednesday.temperature_max, Some(-1.5));
+        assert!((wednesday.precipitation_sum.unwrap() - 1.9).abs() < 1e-9);
+        assert_eq!(wednesday.weather_code, Some(86));
+        assert_eq!(wednesday.wind_speed_max, Some(14.4));
+
+        // Six-hourly steps from 01:00
+        let thursday = &days[1];
+        assert_eq!(thursday.date, "2025-12-11");
+        assert_eq!(thursday.temperature_min, Some(-6.3));
+        assert_eq!(thursday.temperature_max, Some(-0.8));
+        assert!((thursday.precipitation_sum.unwrap() - 0.8).abs() < 1e-9);
+        assert_eq!(thursday.weather_code, Some(80));
+        assert_eq!(thursday.wind_speed_max, Some(19.8));
+
+        // A lone final step without a summary still counts its temperature
+        let friday = &days[2];
+        assert_eq!(friday.temperature_min, Some(-3.4));
+        assert_eq!(friday.precipitation_sum, None);
+        assert_eq!(friday.weather_code, None);
+    }
+
+    #[test]
+    fn test_forecast_days_limit_the_days() {
+        let forecast = parse_forecast_response(StatusCode::OK, OSLO).unwrap();
+        let zone = LocalZone::solar(10.7522);
+        let info = build_weather_info("Oslo", 59.9139, 10.7522, forecast, zone, 2, false).unwrap();
+        assert_eq!(info.weather_data.daily.len(), 2);
+    }
+
+    #[test]
+    fn test_symbol_codes() {
+        assert_eq!(weather_code("clearsky_day"), Some(0));
+        assert_eq!(weather_code("fair_polartwilight"), Some(1));
+        assert_eq!(weather_code("fog"), Some(45));
+        assert_eq!(weather_code("lightrain"), Some(61));
+        assert_eq!(weather_code("heavyrain"), Some(65));
+        assert_eq!(weather_code("rainshowers_night"), Some(81));
+        assert_eq!(weather_code("sleet"), Some(63));
+        assert_eq!(weather_code("lightssleetshowersandthunder_day"), Some(95));
+        assert_eq!(weather_code("lightsnowshowers_day"), Some(85));
+        assert_eq!(weather_code("heavysnow"), Some(75));
+        assert_eq!(weather_code("sandstorm"), None);
+
+        assert_eq!(is_day("fair_day"), Some(true));
+        assert_eq!(is_day("fair_polartwilight"), Some(false));
+        assert_eq!(is_day("cloudy"), None);
+    }
+
+    #[test]
+    fn test_times_follow_the_zone_open_meteo_resolves() {
+        // Lisbon keeps UTC in winter although it is 9° west: solar time would
+        // put every step an hour early, midnight's on the day before
+        let server = MockServer::start(vec![
+            MockRoute::new("/v1/search").json(serde_json::json!({"results": [{
+                "name": "Lisbon", "country": "Portugal", "latitude": 38.72,
+                "longitude": -9.14, "timezone": "Europe/Lisbon"
+            }]})),
+            MockRoute::new("/v1/forecast").json(serde_json::json!({
+                "utc_offset_seconds": 0, "timezone": "Europe/Lisbon"
+            })),
+            MockRoute::new("/compact").json(serde_json::json!({
+                "properties": {"timeseries": [
+                    {"time": "2025-12-10T23:00:00Z", "data": {"instant": {"details": {"air_temperature": 11.0}}}},
+                    {"time": "2025-12-11T00:00:00Z", "data": {"instant": {"details": {"air_temperature": 10.0}}}}
+                ]}
+            })),
+        ]);
+        let repository = MetNoRepository::new(
+            ApiWeatherRepository::new()
+                .with_geocoder(GeocodingProvider::OpenMeteo)
+                .with_endpoints(server.url("/v1/search"), server.url("/v1/forecast")),
+        )
+        .with_metno_url(server.url("/compact"));
+
+        let info = repository.fetch_weather("Lisbon").unwrap();
+        assert_eq!(info.utc_offset_seconds, Some(0));
+        assert_eq!(info.timezone.as_deref(), Some("Europe/Lisbon"));
+        let data = &info.weather_data;
+        assert_eq!(data.current.time.as_deref(), Some("2025-12-10T23:00"));
+        let dates: Vec<&str> = data.daily.iter().map(|d| d.date.as_str()).collect();
+        assert_eq!(dates, ["2025-12-10", "2025-12-11"]);
+        let zone_requests = server.requests_to("/v1/forecast");
+        assert!(
+            zone_requests[0].contains("&timezone=Europe%2FLisbon"),
+            "{zone_requests:?}"
+        );
+
+        // Without an answer the forecast is still shown, in solar time
+        let server = MockServer::start(vec![
+            MockRoute::new("/search").json(serde_json::json!([{"lat": "38.72", "lon": "-9.14"}])),
+            MockRoute::new("/v1/forecast").status(500),
+            MockRoute::new("/compact").json(serde_json::json!({
+                "properties": {"timeseries": [
+                    {"time": "2025-12-10T23:00:00Z", "data": {"instant": {"details": {"air_temperature": 11.0}}}}
+                ]}
+            })),
+        ]);
+        let repository = MetNoRepository::new(
+            ApiWeatherRepository::new()
+                .with_retry_policy(RetryPolicy::none())
+                .with_endpoints(server.url("/search"), server.url("/v1/forecast")),
+        )
+        .with_metno_url(server.url("/compact"));
+        let info = repository.fetch_weather("Lisbon").unwrap();
+        assert_eq!(info.utc_offset_seconds, Some(-3600));
+        assert_eq!(
+            info.weather_data.current.time.as_deref(),
+            Some("2025-12-10T22:00")
+        );
+    }
+
+    #[test]
+    fn test_forbidden_asks_for_a_contact() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/search")
+                .json(serde_json::json!([{"lat": "59.9139", "lon": "10.7522"}])),
+            MockRoute::new("/compact").status(403),
+        ]);
+        let repository = MetNoRepository::new(
+            ApiWeatherRepository::new()
+                .with_endpoints(server.url("/search"), server.url("/v1/forecast")),
+        )
+        .with_metno_url(server.url("/compact"));
+
+        let error = repository.fetch_weather("Oslo").unwrap_err();
+        assert!(matches!(error, WeatherError::ApiError(_)));
+        assert!(error.to_string().contains("contact_email"), "{error}");
+        assert!(server.requests_to("/compact")[0].contains("?lat=59.9139&lon=10.7522"));
+    }
+}
@@ -1,2 +1,34 @@
 // Repository layer - handles data fetching and persistence
+pub(crate) mod air_quality;
+pub mod app_state;
+#[cfg(feature = "async")]
+pub mod async_weather_repository;
+pub(crate) mod backoff;
+pub mod cached_weather_repository;
+pub mod data_selection;
+pub(crate) mod ensemble;
+pub mod fallback_repository;
+pub mod favorites_store;
+pub mod fetch_options;
+pub mod file_weather_repository;
+pub mod geocode_cache;
+pub mod geocoding;
+pub mod health;
+pub(crate) mod json_file;
+pub(crate) mod marine;
+pub mod metno_repository;
+pub mod nws_weather_repository;
+pub mod openweathermap_repository;
+pub mod persistent_weather_repository;
+pub mod provider;
+pub mod request_spacing;
+pub(crate) mod response_cache;
+pub mod retry;
+pub(crate) mod sanitize;
+pub(crate) mod server_clock;
+pub mod settings_store;
+pub mod snapshot_store;
+pub mod usage_log;
+pub mod user_agent;
+pub mod weather_log;
 pub mod weather_repository;
@@ -0,0 +1,494 @@
+//! US National Weather Service (api.weather.gov) as the forecast source
+//!
+//! The NWS publishes official forecasts for the United States for free and
+//! without a key. A forecast takes two steps: `/points/{lat},{lon}` names
+//! the forecast office and grid square for the coordinates, and the
+//! gridpoint's `forecast` (day and night periods) and `forecast/hourly`
+//! hold the forecast itself. Locations are geocoded, and requests sent, by
+//! an `ApiWeatherRepository`, so the User-Agent, retries and caches are the
+//! same as for Open-Meteo.
+//!
+//! NWS periods carry less than Open-Meteo's variables: there is no
+//! pressure, precipitation amount, sunrise or sunset, so those stay `None`.
+
+use chrono::DateTime;
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::constants::{HOURLY_FORECAST_LIMIT, NWS_API_URL};
+use crate::errors::WeatherError;
+use crate::models::favorites::ResolvedPlace;
+use crate::models::weather_info::{
+    CurrentWeather, DailyForecast, HourlyForecast, WeatherData, WeatherInfo,
+};
+use crate::repositories::provider::WeatherProvider;
+use crate::repositories::weather_repository::{ApiWeatherRepository, WeatherRepository};
+use crate::utils::conversions::{Direction, Speed, Temperature};
+
+#[derive(Debug, Deserialize)]
+struct NwsPoint {
+    properties: NwsPointProperties,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NwsPointProperties {
+    grid_id: String,
+    grid_x: u32,
+    grid_y: u32,
+    time_zone: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NwsForecast {
+    properties: NwsForecastProperties,
+}
+
+#[derive(Debug, Deserialize)]
+struct NwsForecastProperties {
+    elevation: Option<NwsValue>,
+    #[serde(default)]
+    periods: Vec<NwsPeriod>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NwsPeriod {
+    start_time: String,
+    is_daytime: bool,
+    temperature: Option<f64>,
+    temperature_unit: Option<String>,
+    probability_of_precipitation: Option<NwsValue>,
+    dewpoint: Option<NwsValue>,
+    relative_humidity: Option<NwsValue>,
+    /// e.g. "10 mph" or "10 to 15 mph"
+    wind_speed: Option<String>,
+    /// 16-point compass name, e.g. "NW"
+    wind_direction: Option<String>,
+    /// e.g. "https://api.weather.gov/icons/land/day/tsra_sct,40/sct?size=medium"
+    icon: Option<String>,
+}
+
+/// Quantity with a WMO unit code, e.g. `{"unitCode": "wmoUnit:degC", "value": 4.4}`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NwsValue {
+    unit_code: Option<String>,
+    value: Option<f64>,
+}
+
+impl NwsValue {
+    /// Temperature in °C, whichever unit it came in
+    fn celsius(&self) -> Option<f64> {
+        match self.unit_code.as_deref() {
+            Some("wmoUnit:degF") => self.value.map(Temperature::fahrenheit_to_celsius),
+            _ => self.value,
+        }
+    }
+}
+
+/// Error body (RFC 7807 problem details)
+#[derive(Debug, Deserialize)]
+struct NwsProblem {
+    title: Option<String>,
+    detail: Option<String>,
+}
+
+/// Forecast from the US National Weather Service for locations in the
+/// United States; anywhere else fails with an `ApiError` suggesting the
+/// default provider
+pub struct NwsWeatherRepository {
+    /// Geocodes locations and sends the requests
+    api: ApiWeatherRepository,
+    nws_url: String,
+}
+
+impl NwsWeatherRepository {
+    pub fn new(api: ApiWeatherRepository) -> Self {
+        NwsWeatherRepository {
+            api,
+            nws_url: NWS_API_URL.to_string(),
+        }
+    }
+
+    /// Root of the NWS API (default `NWS_API_URL`)
+    pub fn with_nws_url(mut self, url: impl Into<String>) -> Self {
+        self.nws_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn nws_url(&self) -> &str {
+        &self.nws_url
+    }
+
+    /// Forecast office and grid square for the coordinates
+    fn point(&self, lat: f64, lon: f64) -> Result<NwsPointProperties, WeatherError> {
+        // The API redirects anything more precise than four decimals
+        let url = format!("{}/points/{lat:.4},{lon:.4}", self.nws_url);
+        let (status, body) = self.api.get_text(&url)?;
+        if status == StatusCode::NOT_FOUND {
+            return Err(WeatherError::ApiError(format!(
+                "the National Weather Service only covers the United States, not {lat:.4},{lon:.4}; use the default provider (--provider open-meteo) elsewhere"
+            )));
+        }
+        parse::<NwsPoint>(status, &body).map(|point| point.properties)
+    }
+
+    /// Periods of the gridpoint's `forecast` or `forecast/hourly`
+    fn forecast(
+        &self,
+        point: &NwsPointProperties,
+        kind: &str,
+    ) -> Result<NwsForecastProperties, WeatherError> {
+        let url = format!(
+            "{}/gridpoints/{}/{},{}/{kind}",
+            self.nws_url, point.grid_id, point.grid_x, point.grid_y
+        );
+        let (status, body) = self.api.get_text(&url)?;
+        parse::<NwsForecast>(status, &body).map(|forecast| forecast.properties)
+    }
+}
+
+impl WeatherRepository for NwsWeatherRepository {
+    fn fetch_weather(&self, location: &str) -> Result<WeatherInfo, WeatherError> {
+        self.api.fetch_located(location, |found, name| {
+            let (lat, lon) = (found.latitude, found.longitude);
+            let point = self.point(lat, lon)?;
+            let periods = self.forecast(&point, "forecast")?;
+            let hourly = self.forecast(&point, "forecast/hourly")?;
+
+            let mut info = build_weather_info(name, lat, lon, periods, hourly, self.api.verbose())?;
+            info.timezone = point.time_zone.or(found.timezone);
+            Ok(info)
+        })
+    }
+
+    fn response_cache_hits(&self) -> usize {
+        self.api.response_cache_hits()
+    }
+
+    fn clear_response_cache(&self) {
+        self.api.clear_response_cache()
+    }
+
+    fn resolve_place(
+        &self,
+        location: &str,
+        recorded: Option<&ResolvedPlace>,
+    ) -> Result<Option<ResolvedPlace>, WeatherError> {
+        self.api.resolve_place(location, recorded)
+    }
+
+    fn provider(&self) -> WeatherProvider {
+        WeatherProvider::Nws
+    }
+}
+
+/// Reads a successful response, or turns an error one into an `ApiError`
+/// (`ServerError` for a 5xx) with the problem's detail
+fn parse<T: DeserializeOwned>(status: StatusCode, body: &str) -> Result<T, WeatherError> {
+    if !status.is_success() {
+        let reason = serde_json::from_str::<NwsProblem>(body)
+            .ok()
+            .and_then(|problem| problem.detail.or(problem.title));
+        let message = match reason {
+            Some(reason) => format!("NWS API returned status: {status} ({reason})"),
+            None => format!("NWS API returned status: {status}"),
+        };
+        return Err(WeatherError::status(status, message));
+    }
+    serde_json::from_str(body).map_err(|e| WeatherError::ParseError(e.to_string()))
+}
+
+/// The domain model from the day-and-night and the hourly forecast: current
+/// conditions are those of the hour under way
+fn build_weather_info(
+    location: &str,
+    lat: f64,
+    lon: f64,
+    periods: NwsForecastProperties,
+    hourly: NwsForecastProperties,
+    verbose: bool,
+) -> Result<WeatherInfo, WeatherError> {
+    let first_hour = hourly.periods.first().ok_or_else(|| {
+        WeatherError::ParseError("NWS hourly forecast has no periods".to_string())
+    })?;
+    let utc_offset_seconds = DateTime::parse_from_rfc3339(&first_hour.start_time)
+        .ok()
+        .map(|time| time.offset().local_minus_utc());
+
+    let hourly_forecasts: Vec<HourlyForecast> = hourly
+        .periods
+        .iter()
+        .take(HOURLY_FORECAST_LIMIT)
+        .filter_map(hourly_forecast)
+        .collect();
+    let current = current_weather(first_hour);
+    let mut weather_data = WeatherData {
+        current,
+        hourly: hourly_forecasts,
+        daily: daily_forecasts(&periods.periods),
+        minutely: Vec::new(),
+    };
+    ApiWeatherRepository::prepare_weather_data(&mut weather_data, verbose);
+
+    let mut info = WeatherInfo::new(location.to_string(), lat, lon, weather_data);
+    info.elevation = periods
+        .elevation
+        .or(hourly.elevation)
+        .and_then(|elevation| elevation.value);
+    info.utc_offset_seconds = utc_offset_seconds;
+    info.fetched_at = Some(chrono::Utc::now());
+    Ok(info)
+}
+
+fn current_weather(hour: &NwsPeriod) -> CurrentWeather {
+    CurrentWeather {
+        time: local_time(&hour.start_time),
+        temperature: temperature(hour),
+        humidity: hour.relative_humidity.as_ref().and_then(|h| h.value),
+        dew_point: hour.dewpoint.as_ref().and_then(NwsValue::celsius),
+        weather_code: hour.icon.as_deref().and_then(weather_code),
+        wind_speed: hour.wind_speed.as_deref().and_then(wind_speed),
+        wind_direction: hour.wind_direction.as_deref().and_then(Direction::bearing),
+        is_day: Some(hour.is_daytime),
+        ..CurrentWeather::default()
+    }
+}
+
+fn hourly_forecast(hour: &NwsPeriod) -> Option<HourlyForecast> {
+    Some(HourlyForecast {
+        time: local_time(&hour.start_time)?,
+        temperature: temperature(hour),
+        precipitation_probability: precipitation_probability(hour),
+        weather_code: hour.icon.as_deref().and_then(weather_code),
+        wind_speed: hour.wind_speed.as_deref().and_then(wind_speed),
+        wind_direction: hour.wind_direction.as_deref().and_then(Direction::bearing),
+        humidity: hour.relative_humidity.as_ref().and_then(|h| h.value),
+        dew_point: hour.dewpoint.as_ref().and_then(NwsValue::celsius),
+        ..HourlyForecast::default()
+    })
+}
+
+/// One day per date from day and night periods, paired as the NWS does:
+/// "Monday" gives the high and "Monday Night" the low that follows it.
+/// The day's weather is the daytime one when there is a daytime period.
+fn daily_forecasts(periods: &[NwsPeriod]) -> Vec<DailyForecast> {
+    let mut days: Vec<DailyForecast> = Vec::new();
+    for period in periods {
+        let Some(date) = local_time(&period.start_time).and_then(|t| t.get(..10).map(String::from))
+        else {
+            continue;
+        };
+        if days.last().is_none_or(|day| day.date != date) {
+            days.push(DailyForecast {
+                date,
+                ..DailyForecast::default()
+            });
+        }
+        let day = days.last_mut().expect("pushed above");
+        let code = period.icon.as_deref().and_then(weather_code);
+        if period.is_daytime {
+            day.temperature_max = temperature(period);
+            day.weather_code = code.or(day.weather_code);
+        } else {
+            day.temperature_min = temperature(period);
+            day.weather_code = day.weather_code.or(code);
+        }
+        day.precipitation_probability = max(
+            day.precipitation_probability,
+            precipitation_probability(period),
+        );
+        day.wind_speed_max = max(
+            day.wind_speed_max,
+            period.wind_speed.as_deref().and_then(wind_speed),
+        );
+    }
+    days
+}
+
+fn max(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    a.into_iter().chain(b).reduce(f64::max)
+}
+
+/// "2025-01-15T18:00:00-05:00" → "2025-01-15T18:00", local like Open-Meteo's times
+fn local_time(timestamp: &str) -> Option<String> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|time| time.naive_local().format("%Y-%m-%dT%H:%M").to_string())
+}
+
+/// Period temperature in °C
+fn temperature(period: &NwsPeriod) -> Option<f64> {
+    let value = period.temperature?;
+    Some(match period.temperature_unit.as_deref() {
+        Some("F") => Temperature::fahrenheit_to_celsius(value),
+        _ => value,
+    })
+}
+
+fn precipitation_probability(period: &NwsPeriod) -> Option<f64> {
+    // A null chance means none is forecast
+    period
+        .probability_of_precipitation
+        .as_ref()
+        .map(|p| p.value.unwrap_or(0.0))
+}
+
+/// Wind speed in km/h from "10 mph", or the upper end of "10 to 15 mph"
+fn wind_speed(text: &str) -> Option<f64> {
+    let speed = text
+        .split_whitespace()
+        .filter_map(|word| word.parse::<f64>().ok())
+        .reduce(f64::max)?;
+    Some(if text.ends_with("mph") {
+        Speed::mph_to_kmh(speed)
+    } else {
+        speed
+    })
+}
+
+/// WMO code for the first condition in an NWS icon URL, e.g. `tsra_sct`
+/// in ".../icons/land/day/tsra_sct,40/sct?size=medium"; `None` for
+/// conditions without one, such as smoke or haze
+fn weather_code(icon: &str) -> Option<i32> {
+    let path = icon.split('?').next()?;
+    let mut segments = path.split('/');
+    segments.find(|segment| matches!(*segment, "day" | "night"))?;
+    let condition = segments.next()?.split(',').next()?;
+    Some(match condition.strip_prefix("wind_").unwrap_or(condition) {
+        "skc" | "hot" | "cold" => 0,
+        "few" => 1,
+        "sct" | "bkn" => 2,
+        "ovc" => 3,
+        "fog" => 45,
+        "rain" => 63,
+        "rain_showers" | "rain_showers_hi" => 80,
+        "fzra" | "rain_fzra" | "snow_fzra" => 66,
+        "snow" | "rain_snow" => 73,
+        "sleet" | "rain_sleet" | "snow_sleet" => 77,
+        "blizzard" => 75,
+        "tsra" | "tsra_sct" | "tsra_hi" | "tornado" | "hurricane" | "tropical_storm" => 95,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::retry::RetryPolicy;
+    use crate::test_support::{MockRoute, MockServer};
+
+    /// Geocoder on the mock server, which names no place for coordinates
+    fn api(server: &MockServer) -> ApiWeatherRepository {
+        ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+    }
+
+    #[test]
+    fn test_weather_codes_from_icons() {
+        let icon =
+            |condition: &str| format!("https://api.weather.gov/icons/land/{condition}?size=medium");
+        assert_eq!(weather_code(&icon("day/skc")), Some(0));
+        assert_eq!(weather_code(&icon("night/wind_few")), Some(1));
+        assert_eq!(weather_code(&icon("day/tsra_sct,40/sct")), Some(95));
+        assert_eq!(weather_code(&icon("day/rain_showers,60")), Some(80));
+        assert_eq!(weather_code(&icon("night/snow,90")), Some(73));
+        assert_eq!(weather_code(&icon("day/smoke")), None);
+        assert_eq!(weather_code("not an icon"), None);
+    }
+
+    #[test]
+    fn test_wind_speed_in_kmh() {
+        assert_eq!(wind_speed("0 mph"), Some(0.0));
+        assert!((wind_speed("10 mph").unwrap() - 16.09344).abs() < 1e-9);
+        assert!((wind_speed("10 to 15 mph").unwrap() - 24.14016).abs() < 1e-9);
+        assert_eq!(wind_speed("20 km/h"), Some(20.0));
+        assert_eq!(wind_speed(""), None);
+    }
+
+    #[test]
+    fn test_day_and_night_periods_pair_up() {
+        let period = |start: &str, is_daytime, temperature| NwsPeriod {
+            start_time: start.to_string(),
+            is_daytime,
+            temperature: Some(temperature),
+            temperature_unit: Some("F".to_string()),
+            probability_of_precipitation: Some(NwsValue {
+                unit_code: None,
+                value: is_daytime.then_some(40.0),
+            }),
+            dewpoint: None,
+            relative_humidity: None,
+            wind_speed: Some("5 to 10 mph".to_string()),
+            wind_direction: Some("NW".to_string()),
+            icon: Some(format!(
+                "https://api.weather.gov/icons/land/{}/ovc",
+                if is_daytime { "day" } else { "night" }
+            )),
+        };
+        let days = daily_forecasts(&[
+            period("2025-01-15T18:00:00-05:00", false, 23.0),
+            period("2025-01-16T06:00:00-05:00", true, 41.0),
+            period("2025-01-16T18:00:00-05:00", false, 32.0),
+        ]);
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].date, "2025-01-15");
+        assert_eq!(days[0].temperature_max, None);
+        assert!((days[0].temperature_min.unwrap() + 5.0).abs() < 1e-9);
+        assert_eq!(days[0].precipitation_probability, Some(0.0));
+        assert_eq!(days[1].date, "2025-01-16");
+        assert_eq!(days[1].temperature_max, Some(5.0));
+        assert_eq!(days[1].temperature_min, Some(0.0));
+        assert_eq!(days[1].precipitation_probability, Some(40.0));
+        assert!((days[1].wind_speed_max.unwrap() - 16.09344).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_points_outside_the_us_suggest_the_default_provider() {
+        let server = MockServer::start(vec![MockRoute::new("/points/").status(404).json(
+            serde_json::json!({
+                "title": "Data Unavailable For Requested Point",
+                "type": "https://api.weather.gov/problems/InvalidPoint",
+                "status": 404,
+                "detail": "Unable to provide data for requested point 51.5074,-0.1278"
+            }),
+        )]);
+        let repository = NwsWeatherRepository::new(api(&server)).with_nws_url(server.url(""));
+
+        let error = repository.fetch_weather("51.5074, -0.1278").unwrap_err();
+        assert!(matches!(error, WeatherError::ApiError(_)));
+        let message = error.to_string();
+        assert!(
+            message.contains("only covers the United States"),
+            "{message}"
+        );
+        assert!(message.contains("--provider open-meteo"), "{message}");
+        assert_eq!(server.requests_to("/points/"), ["/points/51.5074,-0.1278"]);
+    }
+
+    #[test]
+    fn test_gridpoint_errors_carry_the_detail() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/points/").json(serde_json::json!({
+                "properties": {"gridId": "OKX", "gridX": 33, "gridY": 35}
+            })),
+            MockRoute::new("/gridpoints/").status(500).json(serde_json::json!({
+                "title": "Forecast Grid Expired",
+                "detail": "The requested forecast grid was issued 2025-01-14T03:12:08+00:00 and has expired."
+            })),
+        ]);
+        let repository =
+            NwsWeatherRepository::new(api(&server).with_retry_policy(RetryPolicy::none()))
+                .with_nws_url(server.url(""));
+
+        let error = repository.fetch_weather("40.7128,-74.006").unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("(The requested forecast grid was issued"),
+            "{error}"
+        );
+    }
+}
@@ -0,0 +1,574 @@
+//! OpenWeatherMap One Call API 3.0 as the forecast source
+//!
+//! For users who already have an OpenWeatherMap key and prefer its data.
+//! One request returns current conditions, 48 hours and 8 days, of which
+//! the repository's forecast days are kept. Values
+//! come in the API's standard units, Kelvin and m/s, and are converted to
+//! the crate's °C and km/h; snowfall arrives as water equivalent in mm.
+//! Locations are geocoded, and requests sent, by an `ApiWeatherRepository`.
+//!
+//! The key is part of the request URL, which errors never include (see
+//! `WeatherError::network`).
+
+use chrono::{DateTime, NaiveDateTime, TimeDelta};
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use crate::constants::{HOURLY_FORECAST_LIMIT, OPENWEATHERMAP_API_URL};
+use crate::errors::WeatherError;
+use crate::models::favorites::ResolvedPlace;
+use crate::models::weather_info::{
+    CurrentWeather, DailyForecast, HourlyForecast, WeatherData, WeatherInfo,
+};
+use crate::repositories::provider::WeatherProvider;
+use crate::repositories::weather_repository::{ApiWeatherRepository, WeatherRepository};
+use crate::utils::conversions::{Speed, Temperature};
+
+/// Centimeters of fresh snow per millimeter of water, the ratio Open-Meteo
+/// uses for its snowfall
+const SNOW_CM_PER_MM_WATER: f64 = 0.7;
+
+#[derive(Debug, Deserialize)]
+struct OwmOneCall {
+    timezone: Option<String>,
+    /// Seconds from UTC
+    timezone_offset: i32,
+    current: Option<OwmCurrent>,
+    #[serde(default)]
+    hourly: Vec<OwmHour>,
+    #[serde(default)]
+    daily: Vec<OwmDay>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OwmCurrent {
+    dt: i64,
+    temp: Option<f64>,
+    feels_like: Option<f64>,
+    /// Sea-level pressure in hPa
+    pressure: Option<f64>,
+    humidity: Option<f64>,
+    dew_point: Option<f64>,
+    clouds: Option<f64>,
+    visibility: Option<f64>,
+    wind_speed: Option<f64>,
+    wind_deg: Option<f64>,
+    wind_gust: Option<f64>,
+    #[serde(default)]
+    weather: Vec<OwmCondition>,
+    rain: Option<OwmLastHour>,
+    snow: Option<OwmLastHour>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OwmHour {
+    dt: i64,
+    temp: Option<f64>,
+    feels_like: Option<f64>,
+    humidity: Option<f64>,
+    dew_point: Option<f64>,
+    uvi: Option<f64>,
+    clouds: Option<f64>,
+    visibility: Option<f64>,
+    wind_speed: Option<f64>,
+    wind_deg: Option<f64>,
+    wind_gust: Option<f64>,
+    /// Probability of precipitation, 0 to 1
+    pop: Option<f64>,
+    #[serde(default)]
+    weather: Vec<OwmCondition>,
+    rain: Option<OwmLastHour>,
+    snow: Option<OwmLastHour>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OwmDay {
+    dt: i64,
+    sunrise: Option<i64>,
+    sunset: Option<i64>,
+    temp: OwmDayTemperatures,
+    humidity: Option<f64>,
+    wind_speed: Option<f64>,
+    wind_gust: Option<f64>,
+    pop: Option<f64>,
+    /// Day's rain in mm
+    rain: Option<f64>,
+    /// Day's snow as water in mm
+    snow: Option<f64>,
+    uvi: Option<f64>,
+    #[serde(default)]
+    weather: Vec<OwmCondition>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OwmDayTemperatures {
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OwmCondition {
+    /// Condition code, e.g. 500 for light rain
+    id: i32,
+    /// Icon name ending in "d" by day and "n" by night, e.g. "10d"
+    icon: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OwmLastHour {
+    #[serde(rename = "1h")]
+    one_hour: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OwmErrorBody {
+    message: String,
+}
+
+/// Forecast from OpenWeatherMap's One Call API with the user's own key
+pub struct OpenWeatherMapRepository {
+    /// Geocodes locations and sends the requests
+    api: ApiWeatherRepository,
+    api_key: String,
+    onecall_url: String,
+}
+
+impl OpenWeatherMapRepository {
+    /// Repository asking with `api_key`; an empty key fails every fetch
+    /// with `WeatherError::ApiKey` before a request is sent
+    pub fn new(api: ApiWeatherRepository, api_key: impl Into<String>) -> Self {
+        OpenWeatherMapRepository {
+            api,
+            api_key: api_key.into().trim().to_string(),
+            onecall_url: OPENWEATHERMAP_API_URL.to_string(),
+        }
+    }
+
+    /// One Call endpoint (default `OPENWEATHERMAP_API_URL`)
+    pub fn with_onecall_url(mut self, url: impl Into<String>) -> Self {
+        self.onecall_url = url.into();
+        self
+    }
+
+    pub fn onecall_url(&self) -> &str {
+        &self.onecall_url
+    }
+
+    fn fetch_onecall(&self, lat: f64, lon: f64) -> Result<OwmOneCall, WeatherError> {
+        let url = format!(
+            "{}?lat={lat}&lon={lon}&exclude=minutely,alerts&appid={}",
+            self.onecall_url,
+            urlencoding::encode(&self.api_key)
+        );
+        let (status, body) = self.api.get_text(&url)?;
+        parse_onecall_response(status, &body)
+    }
+}
+
+impl WeatherRepository for OpenWeatherMapRepository {
+    fn fetch_weather(&self, location: &str) -> Result<WeatherInfo, WeatherError> {
+        if self.api_key.is_empty() {
+            return Err(WeatherError::ApiKey(
+                "OpenWeatherMap needs an API key; pass --openweathermap-api-key or set OPENWEATHERMAP_API_KEY".to_string(),
+            ));
+        }
+        self.api.fetch_located(location, |found, name| {
+            let onecall = self.fetch_onecall(found.latitude, found.longitude)?;
+            build_weather_info(
+                name,
+                found.latitude,
+                found.longitude,
+                onecall,
+                self.api.forecast_days(),
+                self.api.verbose(),
+            )
+        })
+    }
+
+    fn response_cache_hits(&self) -> usize {
+        self.api.response_cache_hits()
+    }
+
+    fn clear_response_cache(&self) {
+        self.api.clear_response_cache()
+    }
+
+    fn resolve_place(
+        &self,
+        location: &str,
+        recorded: Option<&ResolvedPlace>,
+    ) -> Result<Option<ResolvedPlace>, WeatherError> {
+        self.api.resolve_place(location, recorded)
+    }
+
+    fn provider(&self) -> WeatherProvider {
+        WeatherProvider::OpenWeatherMap
+    }
+}
+
+/// Reads a One Call response. A 401 is about the key, so it becomes
+/// `ApiKey` with OpenWeatherMap's explanation.
+fn parse_onecall_response(status: StatusCode, body: &str) -> Result<OwmOneCall, WeatherError> {
+    let message = || {
+        serde_json::from_str::<OwmErrorBody>(body)
+            .map(|error| error.message)
+            .ok()
+    };
+    if status == StatusCode::UNAUTHORIZED {
+        let reason = message().map_or(String::new(), |message| format!(" ({message})"));
+        return Err(WeatherError::ApiKey(format!(
+            "OpenWeatherMap rejected the API key{reason}; check it at https://home.openweathermap.org/api_keys (new keys take a few hours to work)"
+        )));
+    }
+    if !status.is_success() {
+        let message = match message() {
+            Some(message) => format!("OpenWeatherMap API returned status: {status} ({message})"),
+            None => format!("OpenWeatherMap API returned status: {status}"),
+        };
+        return Err(WeatherError::status(status, message));
+    }
+    serde_json::from_str(body).map_err(|e| WeatherError::ParseError(e.to_string()))
+}
+
+fn build_weather_info(
+    location: &str,
+    lat: f64,
+    lon: f64,
+    onecall: OwmOneCall,
+    forecast_days: usize,
+    verbose: bool,
+) -> Result<WeatherInfo, WeatherError> {
+    let offset = onecall.timezone_offset;
+    let current = onecall.current.ok_or_else(|| {
+        WeatherError::ParseError("OpenWeatherMap response is missing current conditions".into())
+    })?;
+    let mut weather_data = WeatherData {
+        current: current_weather(&current, offset),
+        hourly: onecall
+            .hourly
+            .iter()
+            .take(HOURLY_FORECAST_LIMIT)
+            .filter_map(|hour| hourly_forecast(hour, offset))
+            .collect(),
+        daily: onecall
+            .daily
+            .iter()
+            .take(forecast_days)
+            .filter_map(|day| daily_forecast(day, offset))
+            .collect(),
+        minutely: Vec::new(),
+    };
+    ApiWeatherRepository::prepare_weather_data(&mut weather_data, verbose);
+
+    let mut info = WeatherInfo::new(location.to_string(), lat, lon, weather_data);
+    info.utc_offset_seconds = Some(offset);
+    info.timezone = onecall.timezone;
+    info.fetched_at = Some(chrono::Utc::now());
+    Ok(info)
+}
+
+fn current_weather(current: &OwmCurrent, offset: i32) -> CurrentWeather {
+    let condition = current.weather.first();
+    CurrentWeather {
+        time: local_time(current.dt, offset).map(|time| format_time(&time)),
+        temperature: current.temp.map(Temperature::kelvin_to_celsius),
+        apparent_temperature: current.feels_like.map(Temperature::kelvin_to_celsius),
+        humidity: current.humidity,
+        dew_point: current.dew_point.map(Temperature::kelvin_to_celsius),
+        precipitation: precipitation(&current.rain, &current.snow),
+        snowfall: snowfall(&current.snow),
+        weather_code: condition.and_then(|c| weather_code(c.id)),
+        wind_speed: current.wind_speed.map(Speed::ms_to_kmh),
+        wind_direction: current.wind_deg,
+        wind_gusts: current.wind_gust.map(Speed::ms_to_kmh),
+        cloud_cover: current.clouds,
+        pressure_msl: current.pressure,
+        visibility: current.visibility,
+        is_day: condition
+            .and_then(|c| c.icon.as_deref())
+            .map(|icon| icon.ends_with('d')),
+        ..CurrentWeather::default()
+    }
+}
+
+fn hourly_forecast(hour: &OwmHour, offset: i32) -> Option<HourlyForecast> {
+    Some(HourlyForecast {
+        time: format_time(&local_time(hour.dt, offset)?),
+        temperature: hour.temp.map(Temperature::kelvin_to_celsius),
+        apparent_temperature: hour.feels_like.map(Temperature::kelvin_to_celsius),
+        precipitation_probability: hour.pop.map(|pop| pop * 100.0),
+        precipitation: precipitation(&hour.rain, &hour.snow),
+        snowfall: snowfall(&hour.snow),
+        weather_code: hour.weather.first().and_then(|c| weather_code(c.id)),
+        wind_speed: hour.wind_speed.map(Speed::ms_to_kmh),
+        wind_direction: hour.wind_deg,
+        wind_gusts: hour.wind_gust.map(Speed::ms_to_kmh),
+        humidity: hour.humidity,
+        dew_point: hour.dew_point.map(Temperature::kelvin_to_celsius),
+        cloud_cover: hour.clouds,
+        visibility: hour.visibility,
+        uv_index: hour.uvi,
+        ..HourlyForecast::default()
+    })
+}
+
+fn daily_forecast(day: &OwmDay, offset: i32) -> Option<DailyForecast> {
+    let sun = |time: Option<i64>| {
+        time.and_then(|t| local_time(t, offset))
+            .map(|t| format_time(&t))
+    };
+    Some(DailyForecast {
+        date: local_time(day.dt, offset)?.format("%Y-%m-%d").to_string(),
+        temperature_max: day.temp.max.map(Temperature::kelvin_to_celsius),
+        temperature_min: day.temp.min.map(Temperature::kelvin_to_celsius),
+        weather_code: day.weather.first().and_then(|c| weather_code(c.id)),
+        precipitation_sum: sum(day.rain, day.snow),
+        snowfall_sum: day.snow.map(|mm| mm * SNOW_CM_PER_MM_WATER),
+        precipitation_probability: day.pop.map(|pop| pop * 100.0),
+        wind_speed_max: day.wind_speed.map(Speed::ms_to_kmh),
+        wind_gusts_max: day.wind_gust.map(Speed::ms_to_kmh),
+        sunrise: sun(day.sunrise),
+        sunset: sun(day.sunset),
+        humidity_mean: day.humidity,
+        uv_index_max: day.uvi,
+        ..DailyForecast::default()
+    })
+}
+
+/// Rain plus snow water in mm; `None` when neither was reported, which
+/// One Call means as none falling
+fn precipitation(rain: &Option<OwmLastHour>, snow: &Option<OwmLastHour>) -> Option<f64> {
+    let amount = |block: &Option<OwmLastHour>| block.as_ref().and_then(|b| b.one_hour);
+    sum(amount(rain), amount(snow)).or(Some(0.0))
+}
+
+fn snowfall(snow: &Option<OwmLastHour>) -> Option<f64> {
+    let water = snow.as_ref().and_then(|s| s.one_hour).unwrap_or(0.0);
+    Some(water * SNOW_CM_PER_MM_WATER)
+}
+
+fn sum(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    a.into_iter().chain(b).reduce(|a, b| a + b)
+}
+
+/// Local time of a Unix timestamp at `offset` seconds from UTC
+fn local_time(timestamp: i64, offset: i32) -> Option<NaiveDateTime> {
+    let utc = DateTime::from_timestamp(timestamp, 0)?.naive_utc();
+    Some(utc + TimeDelta::seconds(offset.into()))
+}
+
+fn format_time(time: &NaiveDateTime) -> String {
+    time.format("%Y-%m-%dT%H:%M").to_string()
+}
+
+/// WMO code for an OpenWeatherMap condition id; `None` for conditions
+/// without one, such as smoke, haze or dust
+fn weather_code(id: i32) -> Option<i32> {
+    Some(match id {
+        200..=299 => 95,
+        300 | 310 => 51,
+        301 | 311 | 313 | 321 => 53,
+        302 | 312 | 314 => 55,
+        500 => 61,
+        501 => 63,
+        502..=504 => 65,
+        511 => 66,
+        520 => 80,
+        521 => 81,
+        522 | 531 => 82,
+        600 | 615 | 616 => 71,
+        601 => 73,
+        602 => 75,
+        611..=613 => 77,
+        620 => 85,
+        621 | 622 => 86,
+        701 | 741 => 45,
+        781 => 95,
+        800 => 0,
+        801 => 1,
+        802 | 803 => 2,
+        804 => 3,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockRoute, MockServer};
+
+    /// London, trimmed from a recorded One Call response (standard units)
+    const LONDON: &str = r#"{
+        "lat": 51.5074, "lon": -0.1278,
+        "timezone": "Europe/London", "timezone_offset": 0,
+        "current": {
+            "dt": 1765360800, "sunrise": 1765353517, "sunset": 1765381863,
+            "temp": 281.95, "feels_like": 279.7, "pressure": 1023, "humidity": 87,
+            "dew_point": 279.87, "uvi": 0.21, "clouds": 75, "visibility": 10000,
+            "wind_speed": 4.12, "wind_deg": 240, "wind_gust": 8.23,
+            "weather": [{"id": 500, "main": "Rain", "description": "light rain", "icon": "10d"}],
+            "rain": {"1h": 0.25}
+        },
+        "hourly": [
+            {
+                "dt": 1765360800, "temp": 281.95, "feels_like": 279.7, "pressure": 1023,
+                "humidity": 87, "dew_point": 279.87, "uvi": 0.21, "clouds": 75,
+                "visibility": 10000, "wind_speed": 4.12, "wind_deg": 240, "wind_gust": 8.23,
+                "weather": [{"id": 500, "main": "Rain", "description": "light rain", "icon": "10d"}],
+                "pop": 0.56, "rain": {"1h": 0.25}
+            },
+            {
+                "dt": 1765364400, "temp": 272.15, "feels_like": 268.4, "pressure": 1022,
+                "humidity": 93, "dew_point": 271.2, "uvi": 0.3, "clouds": 100,
+                "visibility": 4200, "wind_speed": 2.5, "wind_deg": 10,
+                "weather": [{"id": 601, "main": "Snow", "description": "snow", "icon": "13d"}],
+                "pop": 0.8, "snow": {"1h": 1.5}
+            }
+        ],
+        "daily": [
+            {
+                "dt": 1765368000, "sunrise": 1765353517, "sunset": 1765381863,
+                "summary": "Expect a day of partly cloudy with rain",
+                "temp": {"day": 282.1, "min": 278.56, "max": 283.15, "night": 279.0, "eve": 280.2, "morn": 278.9},
+                "feels_like": {"day": 280.0, "night": 276.4, "eve": 277.9, "morn": 276.1},
+                "pressure": 1022, "humidity": 81, "dew_point": 278.9,
+                "wind_speed": 6.2, "wind_deg": 233, "wind_gust": 12.5,
+                "weather": [{"id": 803, "main": "Clouds", "description": "broken clouds", "icon": "04d"}],
+                "clouds": 80, "pop": 0.62, "rain": 1.2, "snow": 0.5, "uvi": 0.4
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_current_conditions_in_crate_units() {
+        let onecall = parse_onecall_response(StatusCode::OK, LONDON).unwrap();
+        let info = build_weather_info("London", 51.5074, -0.1278, onecall, 8, false).unwrap();
+        assert_eq!(info.timezone.as_deref(), Some("Europe/London"));
+        assert_eq!(info.utc_offset_seconds, Some(0));
+
+        let current = &info.weather_data.current;
+        assert_eq!(current.time.as_deref(), Some("2025-12-10T10:00"));
+        // Kelvin to °C
+        assert!((current.temperature.unwrap() - 8.8).abs() < 1e-9);
+        assert!((current.dew_point.unwrap() - 6.72).abs() < 1e-9);
+        // m/s to km/h
+        assert!((current.wind_speed.unwrap() - 14.832).abs() < 1e-9);
+        assert!((current.wind_gusts.unwrap() - 29.628).abs() < 1e-9);
+        assert_eq!(current.wind_direction, Some(240.0));
+        assert_eq!(current.pressure_msl, Some(1023.0));
+        assert_eq!(current.pressure, None);
+        assert_eq!(current.precipitation, Some(0.25));
+        assert_eq!(current.weather_code, Some(61));
+        assert_eq!(current.is_day, Some(true));
+    }
+
+    #[test]
+    fn test_hourly_and_daily_in_crate_units() {
+        let onecall = parse_onecall_response(StatusCode::OK, LONDON).unwrap();
+        let info = build_weather_info("London", 51.5074, -0.1278, onecall, 8, false).unwrap();
+        let data = &info.weather_data;
+
+        let snowy = &data.hourly[1];
+        assert_eq!(snowy.time, "2025-12-10T11:00");
+        assert!((snowy.temperature.unwrap() + 1.0).abs() < 1e-9);
+        assert_eq!(snowy.precipitation_probability, Some(80.0));
+        assert_eq!(snowy.precipitation, Some(1.5));
+        assert!((snowy.snowfall.unwrap() - 1.05).abs() < 1e-9);
+        assert_eq!(snowy.wind_speed, Some(9.0));
+        assert_eq!(snowy.weather_code, Some(73));
+        assert_eq!(snowy.visibility, Some(4200.0));
+
+        let today = &data.daily[0];
+        assert_eq!(today.date, "2025-12-10");
+        assert!((today.temperature_min.unwrap() - 5.41).abs() < 1e-9);
+        assert!((today.temperature_max.unwrap() - 10.0).abs() < 1e-9);
+        assert_eq!(today.precipitation_sum, Some(1.7));
+        assert_eq!(today.precipitation_probability, Some(62.0));
+        assert!((today.wind_speed_max.unwrap() - 22.32).abs() < 1e-9);
+        assert_eq!(today.sunrise.as_deref(), Some("2025-12-10T07:58"));
+        assert_eq!(today.sunset.as_deref(), Some("2025-12-10T15:51"));
+        assert_eq!(today.weather_code, Some(2));
+    }
+
+    #[test]
+    fn test_local_times_follow_the_offset() {
+        assert_eq!(
+            local_time(1765360800, 9 * 3600).map(|t| format_time(&t)),
+            Some("2025-12-10T19:00".to_string())
+        );
+        assert_eq!(
+            local_time(1765360800, -5 * 3600).map(|t| format_time(&t)),
+            Some("2025-12-10T05:00".to_string())
+        );
+    }
+
+    #[test]
+    fn test_condition_codes() {
+        assert_eq!(weather_code(211), Some(95));
+        assert_eq!(weather_code(502), Some(65));
+        assert_eq!(weather_code(511), Some(66));
+        assert_eq!(weather_code(622), Some(86));
+        assert_eq!(weather_code(741), Some(45));
+        assert_eq!(weather_code(804), Some(3));
+        assert_eq!(weather_code(711), None);
+    }
+
+    #[test]
+    fn test_missing_key_fails_before_any_request() {
+        let server = MockServer::start(Vec::new());
+        let repository = OpenWeatherMapRepository::new(
+            ApiWeatherRepository::new()
+                .with_endpoints(server.url("/search"), server.url("/v1/forecast")),
+            "  ",
+        )
+        .with_onecall_url(server.url("/data/3.0/onecall"));
+
+        let error = repository.fetch_weather("London").unwrap_err();
+        assert!(matches!(error, WeatherError::ApiKey(_)));
+        assert!(
+            error.to_string().contains("OPENWEATHERMAP_API_KEY"),
+            "{error}"
+        );
+        assert_eq!(server.hits(), 0);
+    }
+
+    #[test]
+    fn test_rejected_key_is_an_api_key_error() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/search").json(serde_json::json!([{"lat": "51.5", "lon": "-0.12"}])),
+            MockRoute::new("/data/3.0/onecall").status(401).json(serde_json::json!({
+                "cod": 401,
+                "message": "Invalid API key. Please see https://openweathermap.org/faq#error401 for more info."
+            })),
+        ]);
+        let repository = OpenWeatherMapRepository::new(
+            ApiWeatherRepository::new()
+                .with_endpoints(server.url("/search"), server.url("/v1/forecast")),
+            "0123456789abcdef",
+        )
+        .with_onecall_url(server.url("/data/3.0/onecall"));
+
+        let error = repository.fetch_weather("London").unwrap_err();
+        assert!(matches!(error, WeatherError::ApiKey(_)));
+        let message = error.to_string();
+        assert!(
+            message.contains("rejected the API key (Invalid API key."),
+            "{message}"
+        );
+        assert!(!message.contains("0123456789abcdef"), "{message}");
+        assert!(server.requests()[1].contains("&appid=0123456789abcdef"));
+    }
+
+    #[test]
+    fn test_other_failures_are_api_errors() {
+        let error = parse_onecall_response(
+            StatusCode::BAD_REQUEST,
+            r#"{"cod": "400", "message": "wrong latitude"}"#,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "API error: OpenWeatherMap API returned status: 400 Bad Request (wrong latitude)"
+        );
+    }
+}
@@ -0,0 +1,300 @@
+//! Last weather per location on disk, for when the network is gone
+//!
+//! `PersistentWeatherRepository` keeps the latest successful result of every
+//! location it fetches in `OfflineStore` and answers from it when a request
+//! fails for lack of a connection or a working server. The stored copy is
+//! marked `offline` and keeps its original `fetched_at`, so the views can say
+//! how old it is. Unknown locations and unreadable answers are reported as
+//! they are, never papered over with old data.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::constants::{OFFLINE_CAPACITY, OFFLINE_MAX_BYTES};
+use crate::errors::WeatherError;
+use crate::models::favorites::ResolvedPlace;
+use crate::models::weather_info::WeatherInfo;
+use crate::repositories::json_file::JsonFile;
+use crate::repositories::provider::WeatherProvider;
+use crate::repositories::weather_repository::WeatherRepository;
+use crate::utils::location::normalize_location_key;
+
+/// Weather by normalized location, e.g. "new york"
+type Copies = HashMap<String, WeatherInfo>;
+
+/// Offline copies file, e.g. `~/.cache/weather-app/offline_weather.json`
+#[derive(Clone, Debug)]
+pub struct OfflineStore {
+    file: JsonFile,
+}
+
+impl OfflineStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        OfflineStore {
+            file: JsonFile::new(path),
+        }
+    }
+
+    /// Store in the platform cache directory, if one can be determined
+    pub fn default_location() -> Option<Self> {
+        JsonFile::in_cache_dir("offline_weather.json").map(|file| OfflineStore { file })
+    }
+
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+
+    fn copies(&self) -> Result<Copies, WeatherError> {
+        match fs::metadata(self.path()) {
+            Ok(metadata) if metadata.len() > OFFLINE_MAX_BYTES => Ok(Copies::new()),
+            _ => self.file.load(),
+        }
+    }
+
+    /// Weather last saved for `location` (compared by `normalize_location_key`)
+    pub fn load(&self, location: &str) -> Result<Option<WeatherInfo>, WeatherError> {
+        Ok(self.copies()?.remove(&normalize_location_key(location)))
+    }
+
+    /// Keeps `weather` as the copy for `location`, dropping the copies
+    /// fetched longest ago beyond `OFFLINE_CAPACITY`. An unreadable file is
+    /// started afresh.
+    pub fn save(&self, location: &str, weather: &WeatherInfo) -> Result<(), WeatherError> {
+        let mut copies = self.copies().unwrap_or_default();
+        copies.insert(normalize_location_key(location), weather.clone());
+        while copies.len() > OFFLINE_CAPACITY {
+            let Some(oldest) = copies
+                .iter()
+                .min_by_key(|(_, weather)| weather.fetched_at)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            copies.remove(&oldest);
+        }
+        self.file.save(&copies)
+    }
+}
+
+/// Whether a failed request may be answered with an offline copy: the
+/// network or the server let us down, or the request could not be sent
+/// yet. A 4xx answer is the server refusing the request itself, which an
+/// old copy would only hide.
+fn is_outage(error: &WeatherError) -> bool {
+    matches!(
+        error,
+        WeatherError::NetworkError(_)
+            | WeatherError::Timeout(_)
+            | WeatherError::RateLimited { .. }
+            | WeatherError::Throttled { .. }
+            | WeatherError::ServerError(_)
+    )
+}
+
+/// Wraps a repository, saving every result to an `OfflineStore` and falling
+/// back to the saved copy when a fetch fails with an outage
+pub struct PersistentWeatherRepository<R> {
+    inner: R,
+    /// `None` stores nothing and never falls back
+    store: Option<OfflineStore>,
+    /// Serializes the store's read-modify-write between concurrent fetches
+    saving: Mutex<()>,
+}
+
+impl<R: WeatherRepository> PersistentWeatherRepository<R> {
+    pub fn new(inner: R, store: Option<OfflineStore>) -> Self {
+        PersistentWeatherRepository {
+            inner,
+            store,
+            saving: Mutex::new(()),
+        }
+    }
+
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn store(&self) -> Option<&OfflineStore> {
+        self.store.as_ref()
+    }
+}
+
+impl<R: WeatherRepository> WeatherRepository for PersistentWeatherRepository<R> {
+    fn fetch_weather(&self, location: &str) -> Result<WeatherInfo, WeatherError> {
+        let Some(store) = &self.store else {
+            return self.inner.fetch_weather(location);
+        };
+        match self.inner.fetch_weather(location) {
+            Ok(info) => {
+                let _saving = self.saving.lock().unwrap();
+                // A full disk costs the next offline start, not this result
+                let _ = store.save(location, &info);
+                Ok(info)
+            }
+            Err(e) if is_outage(&e) => match store.load(location) {
+                Ok(Some(mut info)) => {
+                    info.offline = true;
+                    Ok(info)
+                }
+                _ => Err(e),
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    fn response_cache_hits(&self) -> usize {
+        self.inner.response_cache_hits()
+    }
+
+    fn clear_response_cache(&self) {
+        self.inner.clear_response_cache()
+    }
+
+    fn resolve_place(
+        &self,
+        location: &str,
+        recorded: Option<&ResolvedPlace>,
+    ) -> Result<Option<ResolvedPlace>, WeatherError> {
+        self.inner.resolve_place(location, recorded)
+    }
+
+    fn provider(&self) -> WeatherProvider {
+        self.inner.provider()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::sample_weather_info;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// Fails with `error` while offline; knows every location but Atlantis
+    struct FlakyRepository {
+        offline: AtomicBool,
+        error: fn() -> WeatherError,
+    }
+
+    impl FlakyRepository {
+        fn new(error: fn() -> WeatherError) -> Self {
+            FlakyRepository {
+                offline: AtomicBool::new(false),
+                error,
+            }
+        }
+    }
+
+    impl WeatherRepository for FlakyRepository {
+        fn fetch_weather(&self, location: &str) -> Result<WeatherInfo, WeatherError> {
+            if self.offline.load(Ordering::SeqCst) {
+                return Err((self.error)());
+            }
+            if location == "Atlantis" {
+                return Err(WeatherError::location_not_found(location));
+            }
+            let mut info = sample_weather_info();
+            info.location = location.to_string();
+            info.fetched_at = Some(chrono::Utc::now());
+            Ok(info)
+        }
+    }
+
+    fn store(name: &str) -> OfflineStore {
+        let dir =
+            std::env::temp_dir().join(format!("weather-app-offline-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        OfflineStore::new(dir.join("offline_weather.json"))
+    }
+
+    #[test]
+    fn test_network_failure_falls_back_to_saved_copy() {
+        let store = store("fallback");
+        let repository = PersistentWeatherRepository::new(
+            FlakyRepository::new(|| WeatherError::NetworkError("unreachable".into())),
+            Some(store.clone()),
+        );
+        let online = repository.fetch_weather("Seattle").unwrap();
+        assert!(!online.offline);
+
+        repository.inner().offline.store(true, Ordering::SeqCst);
+        let offline = repository.fetch_weather(" seattle ").unwrap();
+        assert!(offline.offline);
+        assert_eq!(offline.fetched_at, online.fetched_at);
+        assert_eq!(offline.weather_data, online.weather_data);
+
+        // Nothing saved for Portland: the failure is reported
+        assert!(matches!(
+            repository.fetch_weather("Portland"),
+            Err(WeatherError::NetworkError(_))
+        ));
+        let _ = fs::remove_dir_all(store.path().parent().unwrap());
+    }
+
+    #[test]
+    fn test_server_answers_are_not_masked() {
+        let store = store("answers");
+        store.save("Atlantis", &sample_weather_info()).unwrap();
+        let repository = PersistentWeatherRepository::new(
+            FlakyRepository::new(|| WeatherError::ParseError("bad JSON".into())),
+            Some(store.clone()),
+        );
+        assert!(matches!(
+            repository.fetch_weather("Atlantis"),
+            Err(WeatherError::LocationNotFound { .. })
+        ));
+        repository.inner().offline.store(true, Ordering::SeqCst);
+        assert!(matches!(
+            repository.fetch_weather("Atlantis"),
+            Err(WeatherError::ParseError(_))
+        ));
+
+        // A rejected request (4xx) is reported; a failing server (5xx) is an outage
+        let rejected = PersistentWeatherRepository::new(
+            FlakyRepository::new(|| WeatherError::ApiError("400 Bad Request".into())),
+            Some(store.clone()),
+        );
+        rejected.inner().offline.store(true, Ordering::SeqCst);
+        assert!(matches!(
+            rejected.fetch_weather("Atlantis"),
+            Err(WeatherError::ApiError(_))
+        ));
+        let failing = PersistentWeatherRepository::new(
+            FlakyRepository::new(|| WeatherError::ServerError("502 Bad Gateway".into())),
+            Some(store.clone()),
+        );
+        failing.inner().offline.store(true, Ordering::SeqCst);
+        assert!(failing.fetch_weather("Atlantis").unwrap().offline);
+
+        // Without a store nothing is kept
+        let repository = PersistentWeatherRepository::new(
+            FlakyRepository::new(|| WeatherError::Timeout("slow".into())),
+            None,
+        );
+        repository.fetch_weather("Seattle").unwrap();
+        repository.inner().offline.store(true, Ordering::SeqCst);
+        assert!(repository.fetch_weather("Seattle").is_err());
+        let _ = fs::remove_dir_all(store.path().parent().unwrap());
+    }
+
+    #[test]
+    fn test_store_capacity_and_damaged_file() {
+        let store = store("capacity");
+        let mut weather = sample_weather_info();
+        for n in 0..=OFFLINE_CAPACITY {
+            weather.fetched_at = Some(chrono::Utc::now() + chrono::TimeDelta::minutes(n as i64));
+            store.save(&format!("Town {n}"), &weather).unwrap();
+        }
+        assert_eq!(store.load("Town 0").unwrap(), None);
+        assert!(store.load("town 1").unwrap().is_some());
+
+        // A damaged file fails the lookup and is replaced on the next save
+        fs::write(store.path(), "{\"seattle\": ").unwrap();
+        assert!(store.load("Seattle").is_err());
+        store.save("Seattle", &weather).unwrap();
+        assert_eq!(store.load("Seattle").unwrap(), Some(weather));
+        assert_eq!(store.load("Town 1").unwrap(), None);
+        let _ = fs::remove_dir_all(store.path().parent().unwrap());
+    }
+}
@@ -0,0 +1,79 @@
+//! Forecast services the app can take its weather from
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Service that supplies the forecast for a location
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WeatherProvider {
+    /// Open-Meteo, worldwide
+    #[default]
+    OpenMeteo,
+    /// US National Weather Service, for the United States only
+    Nws,
+    /// OpenWeatherMap One Call, with the user's own API key
+    OpenWeatherMap,
+    /// Norwegian Meteorological Institute (Yr), worldwide
+    MetNo,
+}
+
+impl WeatherProvider {
+    pub const ALL: [WeatherProvider; 4] = [
+        WeatherProvider::OpenMeteo,
+        WeatherProvider::Nws,
+        WeatherProvider::OpenWeatherMap,
+        WeatherProvider::MetNo,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            WeatherProvider::OpenMeteo => "Open-Meteo",
+            WeatherProvider::Nws => "National Weather Service",
+            WeatherProvider::OpenWeatherMap => "OpenWeatherMap",
+            WeatherProvider::MetNo => "Met.no",
+        }
+    }
+}
+
+impl fmt::Display for WeatherProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            WeatherProvider::OpenMeteo => "open-meteo",
+            WeatherProvider::Nws => "nws",
+            WeatherProvider::OpenWeatherMap => "openweathermap",
+            WeatherProvider::MetNo => "met-no",
+        })
+    }
+}
+
+impl FromStr for WeatherProvider {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+            "open-meteo" | "openmeteo" => Ok(WeatherProvider::OpenMeteo),
+            "nws" | "weather.gov" => Ok(WeatherProvider::Nws),
+            "openweathermap" | "owm" => Ok(WeatherProvider::OpenWeatherMap),
+            "met-no" | "metno" | "met.no" | "yr" => Ok(WeatherProvider::MetNo),
+            other => Err(format!(
+                "unknown weather provider '{other}' (expected open-meteo, nws, openweathermap or met-no)"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_round_trip() {
+        for provider in WeatherProvider::ALL {
+            assert_eq!(provider.to_string().parse(), Ok(provider));
+        }
+        assert_eq!("Open_Meteo".parse(), Ok(WeatherProvider::OpenMeteo));
+        assert_eq!("OWM".parse(), Ok(WeatherProvider::OpenWeatherMap));
+        assert_eq!("Yr".parse(), Ok(WeatherProvider::MetNo));
+        assert!("accuweather".parse::<WeatherProvider>().is_err());
+    }
+}
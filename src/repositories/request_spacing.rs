@@ -0,0 +1,209 @@
+//! Minimum time between requests to one server, across the whole process
+//!
+//! Nominatim's usage policy allows at most one request per second, and a
+//! batch or a user typing quickly in the GUI would send more. Every request
+//! to a server first takes the next free slot for its host, so repositories
+//! on several threads (the GUI's workers, the providers of a fallback) share
+//! the spacing; async requests reserve their slot the same way and sleep
+//! on the runtime. A slot is either waited for or refused with `Throttled`,
+//! which, unlike a server's `RateLimited`, does not stop a batch.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use reqwest::Url;
+
+use crate::errors::WeatherError;
+
+/// Earliest time of the next request to each host, e.g. "nominatim.openstreetmap.org:443"
+static NEXT_SLOT: Mutex<BTreeMap<String, Instant>> = Mutex::new(BTreeMap::new());
+
+/// Time as the spacing sees it. Tests pass a simulated clock that jumps to
+/// each slot instead of sleeping.
+pub(crate) trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+
+    /// Time the caller still has to sleep from `now` until `slot`
+    fn until(&self, now: Instant, slot: Instant) -> Duration;
+}
+
+/// The real clock, waited on by sleeping
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn until(&self, now: Instant, slot: Instant) -> Duration {
+        slot - now
+    }
+}
+
+/// What a request sent too soon after the last one to its server does
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SpacingMode {
+    /// Sleeps until its turn
+    #[default]
+    Wait,
+    /// Fails without sending, with `WeatherError::Throttled` saying how long
+    /// is left
+    Fail,
+}
+
+impl fmt::Display for SpacingMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SpacingMode::Wait => "wait",
+            SpacingMode::Fail => "fail",
+        })
+    }
+}
+
+impl FromStr for SpacingMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "wait" | "sleep" => Ok(SpacingMode::Wait),
+            "fail" | "error" => Ok(SpacingMode::Fail),
+            other => Err(format!(
+                "unknown rate limit mode '{other}' (expected wait or fail)"
+            )),
+        }
+    }
+}
+
+/// Host and port of `url`, the unit the spacing applies to
+fn host_key(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|url| {
+            let host = url.host_str()?.to_string();
+            Some(match url.port_or_known_default() {
+                Some(port) => format!("{host}:{port}"),
+                None => host,
+            })
+        })
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// Whether `a` and `b` are requests to the same server
+pub(crate) fn same_host(a: &str, b: &str) -> bool {
+    host_key(a) == host_key(b)
+}
+
+/// Takes the next slot for a request to `url`'s host, at least `interval`
+/// after the previous one: sleeping until it comes in `Wait` mode, failing
+/// unless it is now in `Fail` mode. A zero interval never waits.
+pub(crate) fn take_turn(
+    url: &str,
+    interval: Duration,
+    mode: SpacingMode,
+    clock: &dyn Clock,
+) -> Result<(), WeatherError> {
+    thread::sleep(reserve_turn(url, interval, mode, clock)?);
+    Ok(())
+}
+
+/// Reserves the slot `take_turn` would wait for and returns the time left
+/// until it, for async callers to sleep without blocking their thread
+pub(crate) fn reserve_turn(
+    url: &str,
+    interval: Duration,
+    mode: SpacingMode,
+    clock: &dyn Clock,
+) -> Result<Duration, WeatherError> {
+    if interval.is_zero() {
+        return Ok(Duration::ZERO);
+    }
+    let mut next_slot = NEXT_SLOT.lock().unwrap_or_else(|e| e.into_inner());
+    let now = clock.now();
+    let next = next_slot.entry(host_key(url)).or_insert(now);
+    let slot = (*next).max(now);
+    if slot > now && mode == SpacingMode::Fail {
+        return Err(WeatherError::Throttled {
+            retry_after: slot - now,
+        });
+    }
+    // Reserved before sleeping, so requests waiting together go one by one
+    *next = slot + interval;
+    Ok(clock.until(now, slot))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::SimulatedClock;
+
+    #[test]
+    fn test_host_key() {
+        assert_eq!(
+            host_key("https://nominatim.openstreetmap.org/search?q=Oslo"),
+            "nominatim.openstreetmap.org:443"
+        );
+        assert_eq!(host_key("http://127.0.0.1:8080/reverse"), "127.0.0.1:8080");
+        assert_eq!(host_key("not a url"), "not a url");
+        assert!(same_host(
+            "https://nominatim.openstreetmap.org/reverse?lat=1&lon=2",
+            "https://nominatim.openstreetmap.org/search"
+        ));
+        assert!(!same_host(
+            "http://127.0.0.1:8080/search",
+            "http://127.0.0.1:8081/search"
+        ));
+    }
+
+    #[test]
+    fn test_waiting_threads_are_spaced() {
+        let url = "http://spacing.test:1/search";
+        let clock = SimulatedClock::default();
+        let interval = Duration::from_secs(1);
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                let clock = &clock;
+                scope.spawn(move || take_turn(url, interval, SpacingMode::Wait, clock).unwrap());
+            }
+        });
+        // Whoever comes first goes at once, the others a slot apart
+        assert_eq!(
+            clock.waits(),
+            [Duration::ZERO, interval, interval, interval]
+        );
+        assert_eq!(clock.elapsed(), interval * 3);
+    }
+
+    #[test]
+    fn test_fail_mode_refuses_early_requests() {
+        let url = "http://spacing.test:2/search";
+        let clock = &SimulatedClock::default();
+        let interval = Duration::from_secs(30);
+        take_turn(url, interval, SpacingMode::Fail, clock).unwrap();
+        let error = take_turn(url, interval, SpacingMode::Fail, clock).unwrap_err();
+        assert!(
+            matches!(error, WeatherError::Throttled { retry_after } if retry_after == interval),
+            "{error:?}"
+        );
+        // Other hosts have slots of their own
+        take_turn(
+            "http://spacing.test:3/search",
+            interval,
+            SpacingMode::Fail,
+            clock,
+        )
+        .unwrap();
+        take_turn(url, Duration::ZERO, SpacingMode::Fail, clock).unwrap();
+    }
+
+    #[test]
+    fn test_mode_from_str() {
+        assert_eq!("wait".parse::<SpacingMode>(), Ok(SpacingMode::Wait));
+        assert_eq!(" Fail ".parse::<SpacingMode>(), Ok(SpacingMode::Fail));
+        assert!("later".parse::<SpacingMode>().is_err());
+    }
+}
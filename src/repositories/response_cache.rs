@@ -0,0 +1,124 @@
+//! Short-lived response bodies keyed by URL
+//!
+//! Features that run back to back often ask for the same URL: the favorites
+//! digest and then opening one of its locations, or a batch naming a city
+//! twice. Successful bodies are kept for `RESPONSE_CACHE_TTL` so a repeated
+//! URL is answered from memory. The URL carries every parameter of the
+//! request, so a cached body is exactly what the server would have sent.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::constants::{RESPONSE_CACHE_CAPACITY, RESPONSE_CACHE_TTL};
+
+/// Shared by every request of one repository
+#[derive(Debug)]
+pub(crate) struct ResponseCache {
+    bodies: Mutex<HashMap<String, (Instant, String)>>,
+    ttl: Duration,
+    capacity: usize,
+    hits: AtomicUsize,
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        ResponseCache::new(RESPONSE_CACHE_TTL, RESPONSE_CACHE_CAPACITY)
+    }
+}
+
+impl ResponseCache {
+    pub(crate) fn new(ttl: Duration, capacity: usize) -> Self {
+        ResponseCache {
+            bodies: Mutex::new(HashMap::new()),
+            ttl,
+            capacity,
+            hits: AtomicUsize::new(0),
+        }
+    }
+
+    /// Body stored for `url` within the TTL, counting the hit
+    pub(crate) fn get(&self, url: &str) -> Option<String> {
+        self.get_at(url, Instant::now())
+    }
+
+    /// `get` as of `now`
+    fn get_at(&self, url: &str, now: Instant) -> Option<String> {
+        let bodies = self.bodies.lock().unwrap();
+        let (stored, body) = bodies.get(url)?;
+        if now.saturating_duration_since(*stored) >= self.ttl {
+            return None;
+        }
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        Some(body.clone())
+    }
+
+    /// Stores the body of a successful response, dropping expired entries
+    /// and then the oldest ones beyond the capacity
+    pub(crate) fn insert(&self, url: &str, body: &str) {
+        self.insert_at(url, body, Instant::now())
+    }
+
+    /// `insert` as of `now`
+    fn insert_at(&self, url: &str, body: &str, now: Instant) {
+        let mut bodies = self.bodies.lock().unwrap();
+        bodies.retain(|_, (stored, _)| now.saturating_duration_since(*stored) < self.ttl);
+        bodies.insert(url.to_string(), (now, body.to_string()));
+        while bodies.len() > self.capacity {
+            let Some(oldest) = bodies
+                .iter()
+                .min_by_key(|(_, (stored, _))| *stored)
+                .map(|(url, _)| url.clone())
+            else {
+                break;
+            };
+            bodies.remove(&oldest);
+        }
+    }
+
+    /// Forgets every body, so the next request for any URL is sent
+    pub(crate) fn clear(&self) {
+        self.bodies.lock().unwrap().clear();
+    }
+
+    /// Requests answered from memory so far
+    pub(crate) fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entries_expire() {
+        let cache = ResponseCache::new(Duration::from_secs(30), 4);
+        let start = Instant::now();
+        cache.insert_at("https://a", "{}", start);
+        let later = |secs| start + Duration::from_secs(secs);
+        assert_eq!(cache.get_at("https://a", later(29)).as_deref(), Some("{}"));
+        assert_eq!(cache.get_at("https://b", later(29)), None);
+        assert_eq!(cache.get_at("https://a", later(30)), None);
+        assert_eq!(cache.hits(), 1);
+
+        // Inserting drops what has expired
+        cache.insert_at("https://b", "{}", later(31));
+        assert_eq!(cache.bodies.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_capacity_drops_oldest() {
+        let cache = ResponseCache::new(Duration::from_secs(60), 2);
+        let start = Instant::now();
+        for (secs, url) in [(0, "https://a"), (1, "https://b"), (2, "https://c")] {
+            cache.insert_at(url, url, start + Duration::from_secs(secs));
+        }
+        let now = start + Duration::from_secs(3);
+        assert_eq!(cache.get_at("https://a", now), None);
+        assert_eq!(cache.get_at("https://b", now).as_deref(), Some("https://b"));
+        assert_eq!(cache.get_at("https://c", now).as_deref(), Some("https://c"));
+        assert_eq!(cache.bodies.lock().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,121 @@
+//! Retries for transient failures
+//!
+//! Open-Meteo now and then answers with a 502 and Nominatim drops
+//! connections; the next request usually goes through. Connection errors,
+//! timeouts and 5xx statuses are retried a few times with exponential
+//! backoff and jitter. Client errors and unknown locations are answers, not
+//! accidents, and come back at once. A 503 is left to `Backoff`, which
+//! already holds every request back for the cooldown the server asks for.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use reqwest::StatusCode;
+
+use crate::constants::{RETRY_ATTEMPTS, RETRY_BASE_DELAY};
+use crate::errors::WeatherError;
+
+/// How often and how patiently a request is repeated
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Requests in all, including the first; 1 disables retries
+    pub attempts: u32,
+    /// Wait before the first retry, doubled for each further one
+    pub base_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            attempts: RETRY_ATTEMPTS,
+            base_delay: RETRY_BASE_DELAY,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new(attempts: u32, base_delay: Duration) -> Self {
+        RetryPolicy {
+            attempts: attempts.max(1),
+            base_delay,
+        }
+    }
+
+    /// A single attempt per request
+    pub fn none() -> Self {
+        RetryPolicy::new(1, Duration::ZERO)
+    }
+
+    /// Whether another attempt may follow attempt number `attempt` (from 1)
+    pub(crate) fn allows_retry(&self, attempt: u32) -> bool {
+        attempt < self.attempts
+    }
+
+    /// Wait after failed attempt number `attempt` (from 1): the base delay
+    /// doubled per earlier retry, plus up to half of that again as jitter so
+    /// clients that failed together do not retry together
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .base_delay
+            .saturating_mul(1 << attempt.saturating_sub(1).min(16));
+        backoff + backoff.mul_f64(jitter() / 2.0)
+    }
+}
+
+/// Uniform-ish value in [0, 1) from the std hasher's random keys
+fn jitter() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(0);
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Whether a failed request is worth repeating
+pub(crate) fn is_transient_error(error: &WeatherError) -> bool {
+    matches!(
+        error,
+        WeatherError::NetworkError(_) | WeatherError::Timeout(_)
+    )
+}
+
+/// Whether a response status is worth repeating the request for; 503 is
+/// the rate limiter's
+pub(crate) fn is_transient_status(status: StatusCode) -> bool {
+    status.is_server_error() && status != StatusCode::SERVICE_UNAVAILABLE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay_doubles_with_jitter() {
+        let policy = RetryPolicy::new(4, Duration::from_millis(100));
+        for (attempt, base) in [(1, 100), (2, 200), (3, 400)] {
+            let delay = policy.delay(attempt);
+            let base = Duration::from_millis(base);
+            assert!(delay >= base && delay <= base + base / 2, "{delay:?}");
+        }
+        assert!(policy.allows_retry(3));
+        assert!(!policy.allows_retry(4));
+        assert!(!RetryPolicy::none().allows_retry(1));
+        assert_eq!(RetryPolicy::new(0, Duration::ZERO), RetryPolicy::none());
+    }
+
+    #[test]
+    fn test_transient_conditions() {
+        assert!(is_transient_error(&WeatherError::NetworkError(
+            "reset".into()
+        )));
+        assert!(is_transient_error(&WeatherError::Timeout("slow".into())));
+        assert!(!is_transient_error(&WeatherError::location_not_found(
+            "Atlantis"
+        )));
+        assert!(!is_transient_error(&WeatherError::ApiError("400".into())));
+        assert!(is_transient_status(StatusCode::BAD_GATEWAY));
+        assert!(is_transient_status(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(!is_transient_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(!is_transient_status(StatusCode::NOT_FOUND));
+        assert!(!is_transient_status(StatusCode::TOO_MANY_REQUESTS));
+    }
+}
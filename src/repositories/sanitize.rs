@@ -0,0 +1,351 @@
+//! Sanitation of forecast responses: non-finite and physically impossible
+//! values become `None` before they reach the views or any aggregation
+
+use std::borrow::Cow;
+
+use crate::models::weather_info::{
+    CurrentWeather, DailyForecast, HourlyForecast, MinutelyForecast, WeatherData,
+};
+
+/// Percentages this far outside 0–100 are rounding noise and get clamped;
+/// anything further out is dropped
+const PERCENT_CLAMP_TOLERANCE: f64 = 5.0;
+
+/// Plausible range of a quantity
+#[derive(Clone, Copy, Debug)]
+enum Range {
+    /// Air temperature in °C; excludes sentinels such as −999
+    Temperature,
+    /// Humidity, cloud cover and probabilities
+    Percent,
+    /// Amounts, depths, distances and energies
+    NonNegative,
+    /// Wind speed in km/h
+    Speed,
+    Degrees,
+    /// Surface pressure in hPa
+    Pressure,
+    /// Height above sea level in meters
+    Height,
+    UvIndex,
+    /// Seconds within a day
+    DayLength,
+}
+
+impl Range {
+    fn bounds(self) -> (f64, f64) {
+        match self {
+            Range::Temperature => (-95.0, 65.0),
+            Range::Percent => (0.0, 100.0),
+            Range::NonNegative => (0.0, f64::MAX),
+            Range::Speed => (0.0, 500.0),
+            Range::Degrees => (0.0, 360.0),
+            Range::Pressure => (300.0, 1100.0),
+            Range::Height => (-500.0, 20_000.0),
+            Range::UvIndex => (0.0, 25.0),
+            Range::DayLength => (0.0, 86_400.0),
+        }
+    }
+
+    fn clamp_tolerance(self) -> f64 {
+        match self {
+            Range::Percent => PERCENT_CLAMP_TOLERANCE,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Collects a note for every value it drops or clamps
+#[derive(Default)]
+struct Sanitizer {
+    notes: Vec<String>,
+}
+
+impl Sanitizer {
+    fn check(&mut self, field: &str, value: &mut Option<f64>, range: Range) {
+        let Some(v) = *value else {
+            return;
+        };
+        let (min, max) = range.bounds();
+        let tolerance = range.clamp_tolerance();
+
+        if !v.is_finite() {
+            self.notes
+                .push(format!("{field}: non-finite value dropped"));
+            *value = None;
+        } else if v >= min && v <= max {
+            // In range
+        } else if v >= min - tolerance && v <= max + tolerance {
+            let clamped = v.clamp(min, max);
+            self.notes
+                .push(format!("{field}: {v} clamped to {clamped}"));
+            *value = Some(clamped);
+        } else {
+            self.notes
+                .push(format!("{field}: {v} out of range, dropped"));
+            *value = None;
+        }
+    }
+
+    /// WMO weather codes run from 0 to 99
+    fn check_code(&mut self, field: &str, value: &mut Option<i32>) {
+        if let Some(code) = *value {
+            if !(0..=99).contains(&code) {
+                self.notes
+                    .push(format!("{field}: unknown weather code {code} dropped"));
+                *value = None;
+            }
+        }
+    }
+
+    fn current(&mut self, c: &mut CurrentWeather) {
+        self.check(
+            "current.temperature",
+            &mut c.temperature,
+            Range::Temperature,
+        );
+        self.check(
+            "current.apparent_temperature",
+            &mut c.apparent_temperature,
+            Range::Temperature,
+        );
+        self.check("current.humidity", &mut c.humidity, Range::Percent);
+        self.check("current.dew_point", &mut c.dew_point, Range::Temperature);
+        self.check(
+            "current.precipitation",
+            &mut c.precipitation,
+            Range::NonNegative,
+        );
+        self.check("current.snowfall", &mut c.snowfall, Range::NonNegative);
+        self.check_code("current.weather_code", &mut c.weather_code);
+        self.check("current.wind_speed", &mut c.wind_speed, Range::Speed);
+        self.check(
+            "current.wind_direction",
+            &mut c.wind_direction,
+            Range::Degrees,
+        );
+        self.check("current.wind_gusts", &mut c.wind_gusts, Range::Speed);
+        self.check("current.cloud_cover", &mut c.cloud_cover, Range::Percent);
+        self.check("current.pressure", &mut c.pressure, Range::Pressure);
+        self.check("current.pressure_msl", &mut c.pressure_msl, Range::Pressure);
+        self.check("current.visibility", &mut c.visibility, Range::NonNegative);
+        self.check("current.snow_depth", &mut c.snow_depth, Range::NonNegative);
+    }
+
+    fn hour(&mut self, i: usize, h: &mut HourlyForecast) {
+        let mut check = |name: &str, value: &mut Option<f64>, range: Range| {
+            self.check(&format!("hourly[{i}].{name}"), value, range)
+        };
+        check("temperature", &mut h.temperature, Range::Temperature);
+        check(
+            "apparent_temperature",
+            &mut h.apparent_temperature,
+            Range::Temperature,
+        );
+        check(
+            "precipitation_probability",
+            &mut h.precipitation_probability,
+            Range::Percent,
+        );
+        check("precipitation", &mut h.precipitation, Range::NonNegative);
+        check("wind_speed", &mut h.wind_speed, Range::Speed);
+        check("wind_direction", &mut h.wind_direction, Range::Degrees);
+        check("wind_gusts", &mut h.wind_gusts, Range::Speed);
+        check("wind_speed_80m", &mut h.wind_speed_80m, Range::Speed);
+        check(
+            "wind_direction_80m",
+            &mut h.wind_direction_80m,
+            Range::Degrees,
+        );
+        check("wind_speed_120m", &mut h.wind_speed_120m, Range::Speed);
+        check(
+            "wind_direction_120m",
+            &mut h.wind_direction_120m,
+            Range::Degrees,
+        );
+        check("humidity", &mut h.humidity, Range::Percent);
+        check("dew_point", &mut h.dew_point, Range::Temperature);
+        check(
+            "freezing_level_height",
+            &mut h.freezing_level_height,
+            Range::Height,
+        );
+        check("cape", &mut h.cape, Range::NonNegative);
+        check("snowfall", &mut h.snowfall, Range::NonNegative);
+        check("snow_depth", &mut h.snow_depth, Range::NonNegative);
+        check("cloud_cover", &mut h.cloud_cover, Range::Percent);
+        check("visibility", &mut h.visibility, Range::NonNegative);
+        check("uv_index", &mut h.uv_index, Range::UvIndex);
+        self.check_code(&format!("hourly[{i}].weather_code"), &mut h.weather_code);
+    }
+
+    fn minutely(&mut self, i: usize, m: &mut MinutelyForecast) {
+        let mut check = |name: &str, value: &mut Option<f64>, range: Range| {
+            self.check(&format!("minutely[{i}].{name}"), value, range)
+        };
+        check("temperature", &mut m.temperature, Range::Temperature);
+        check("precipitation", &mut m.precipitation, Range::NonNegative);
+        self.check_code(&format!("minutely[{i}].weather_code"), &mut m.weather_code);
+    }
+
+    fn day(&mut self, i: usize, d: &mut DailyForecast) {
+        let mut check = |name: &str, value: &mut Option<f64>, range: Range| {
+            self.check(&format!("daily[{i}].{name}"), value, range)
+        };
+        check(
+            "temperature_max",
+            &mut d.temperature_max,
+            Range::Temperature,
+        );
+        check(
+            "temperature_min",
+            &mut d.temperature_min,
+            Range::Temperature,
+        );
+        check(
+            "precipitation_sum",
+            &mut d.precipitation_sum,
+            Range::NonNegative,
+        );
+        check("snowfall_sum", &mut d.snowfall_sum, Range::NonNegative);
+        check(
+            "precipitation_probability",
+            &mut d.precipitation_probability,
+            Range::Percent,
+        );
+        check("wind_speed_max", &mut d.wind_speed_max, Range::Speed);
+        check("wind_gusts_max", &mut d.wind_gusts_max, Range::Speed);
+        check(
+            "daylight_duration",
+            &mut d.daylight_duration,
+            Range::DayLength,
+        );
+        check(
+            "sunshine_duration",
+            &mut d.sunshine_duration,
+            Range::DayLength,
+        );
+        check(
+            "et0_evapotranspiration",
+            &mut d.et0_evapotranspiration,
+            Range::NonNegative,
+        );
+        check("humidity_mean", &mut d.humidity_mean, Range::Percent);
+        check("uv_index_max", &mut d.uv_index_max, Range::UvIndex);
+        self.check_code(&format!("daily[{i}].weather_code"), &mut d.weather_code);
+    }
+}
+
+/// Drops non-finite and out-of-range values (clamping near-miss percentages)
+/// and returns a note for each change
+pub(crate) fn sanitize(data: &mut WeatherData) -> Vec<String> {
+    let mut sanitizer = Sanitizer::default();
+    sanitizer.current(&mut data.current);
+    for (i, hour) in data.hourly.iter_mut().enumerate() {
+        sanitizer.hour(i, hour);
+    }
+    for (i, day) in data.daily.iter_mut().enumerate() {
+        sanitizer.day(i, day);
+    }
+    for (i, step) in data.minutely.iter_mut().enumerate() {
+        sanitizer.minutely(i, step);
+    }
+    sanitizer.notes
+}
+
+/// Replaces bare `NaN`, `Infinity` and `-Infinity` tokens, which are not JSON
+/// and would fail the whole response, with `null`, and counts them. String
+/// contents are untouched.
+pub(crate) fn non_finite_to_null(body: &str) -> (Cow<'_, str>, usize) {
+    const TOKENS: [&str; 3] = ["-Infinity", "Infinity", "NaN"];
+    if !TOKENS.iter().any(|token| body.contains(token)) {
+        return (Cow::Borrowed(body), 0);
+    }
+
+    let mut out = String::with_capacity(body.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut rest = body;
+    let mut replaced = 0;
+    while let Some(c) = rest.chars().next() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if let Some(token) = TOKENS.iter().find(|token| rest.starts_with(**token)) {
+            out.push_str("null");
+            replaced += 1;
+            rest = &rest[token.len()..];
+            continue;
+        }
+        out.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    (Cow::Owned(out), replaced)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_non_finite_tokens_become_null() {
+        let body = r#"{"a": NaN, "b": [1.0, -Infinity, Infinity], "c": "NaN stays"}"#;
+        let (json, replaced) = non_finite_to_null(body);
+        assert_eq!(
+            json,
+            r#"{"a": null, "b": [1.0, null, null], "c": "NaN stays"}"#
+        );
+        assert_eq!(replaced, 3);
+        assert!(matches!(
+            non_finite_to_null(r#"{"a": 1}"#),
+            (Cow::Borrowed(_), 0)
+        ));
+    }
+
+    #[test]
+    fn test_sanitize_drops_and_clamps() {
+        let mut data = WeatherData::new(
+            CurrentWeather::builder()
+                .temperature(-999.0)
+                .humidity(250.0)
+                .cloud_cover(100.4)
+                .pressure(1013.0)
+                .build(),
+            vec![HourlyForecast::builder("2024-06-01T00:00")
+                .precipitation_probability(-3.0)
+                .precipitation(f64::NAN)
+                .wind_speed(f64::INFINITY)
+                .weather_code(250)
+                .build()],
+            vec![DailyForecast::builder("2024-06-01")
+                .temperature_max(21.0)
+                .uv_index_max(-1.0)
+                .build()],
+        );
+
+        let notes = sanitize(&mut data);
+        assert_eq!(data.current.temperature, None);
+        assert_eq!(data.current.humidity, None);
+        assert_eq!(data.current.cloud_cover, Some(100.0));
+        assert_eq!(data.current.pressure, Some(1013.0));
+
+        let hour = &data.hourly[0];
+        assert_eq!(hour.precipitation_probability, Some(0.0));
+        assert_eq!(hour.precipitation, None);
+        assert_eq!(hour.wind_speed, None);
+        assert_eq!(hour.weather_code, None);
+        assert_eq!(data.daily[0].temperature_max, Some(21.0));
+        assert_eq!(data.daily[0].uv_index_max, None);
+
+        assert_eq!(notes.len(), 8);
+        assert!(notes.contains(&"current.temperature: -999 out of range, dropped".to_string()));
+        assert!(notes.contains(&"current.cloud_cover: 100.4 clamped to 100".to_string()));
+        assert!(notes.contains(&"hourly[0].precipitation: non-finite value dropped".to_string()));
+    }
+}
@@ -0,0 +1,95 @@
+//! System clock checked against the servers' `Date` header
+//!
+//! Every "now"-relative feature (the hourly window, staleness warnings, sun
+//! countdowns) goes wrong on a machine whose clock is hours off. Both APIs
+//! send a `Date` header with each response; when it disagrees with the
+//! system clock by more than `CLOCK_SKEW_TOLERANCE`, the difference is
+//! attached to the fetched data and `WeatherInfo::clock` corrects for it.
+
+use std::sync::Mutex;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use reqwest::header::{HeaderMap, DATE};
+
+use crate::constants::CLOCK_SKEW_TOLERANCE;
+
+/// Shared by every request of one repository
+#[derive(Debug, Default)]
+pub(crate) struct ServerClock {
+    skew: Mutex<Option<TimeDelta>>,
+}
+
+impl ServerClock {
+    /// Records the skew shown by a response's `Date` header against the
+    /// system time `system_now`; responses without a readable header leave
+    /// the last measurement in place
+    pub(crate) fn on_response(&self, headers: &HeaderMap, system_now: DateTime<Utc>) {
+        let server_now = headers
+            .get(DATE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| DateTime::parse_from_rfc2822(value.trim()).ok());
+        if let Some(server_now) = server_now {
+            *self.skew.lock().unwrap() = significant_skew(server_now.to_utc() - system_now);
+        }
+    }
+
+    /// Server time minus system time, when beyond `CLOCK_SKEW_TOLERANCE`
+    pub(crate) fn skew(&self) -> Option<TimeDelta> {
+        *self.skew.lock().unwrap()
+    }
+}
+
+/// `skew` unless it is within the tolerance, which covers request latency
+/// and the header's one-second resolution
+fn significant_skew(skew: TimeDelta) -> Option<TimeDelta> {
+    (skew.abs() > CLOCK_SKEW_TOLERANCE).then_some(skew)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn date_header(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(DATE, HeaderValue::from_static(value));
+        headers
+    }
+
+    /// A fake system clock reading `time`
+    fn system(time: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(time).unwrap().to_utc()
+    }
+
+    #[test]
+    fn test_skew_from_date_header() {
+        let clock = ServerClock::default();
+        let server = date_header("Sat, 01 Jun 2024 12:00:00 GMT");
+
+        // A system clock running 2 h 14 m behind
+        clock.on_response(&server, system("2024-06-01T09:46:00Z"));
+        assert_eq!(clock.skew(), Some(TimeDelta::minutes(134)));
+
+        // Or ahead
+        clock.on_response(&server, system("2024-06-01T14:00:00Z"));
+        assert_eq!(clock.skew(), Some(TimeDelta::hours(-2)));
+
+        // Latency-sized differences are no skew
+        clock.on_response(&server, system("2024-06-01T12:00:03Z"));
+        assert_eq!(clock.skew(), None);
+    }
+
+    #[test]
+    fn test_missing_or_unreadable_header_keeps_measurement() {
+        let clock = ServerClock::default();
+        clock.on_response(&HeaderMap::new(), system("2024-06-01T12:00:00Z"));
+        assert_eq!(clock.skew(), None);
+
+        clock.on_response(
+            &date_header("Sat, 01 Jun 2024 12:00:00 GMT"),
+            system("2024-06-01T11:00:00Z"),
+        );
+        clock.on_response(&date_header("yesterday"), system("2024-06-01T11:00:00Z"));
+        assert_eq!(clock.skew(), Some(TimeDelta::hours(1)));
+    }
+}
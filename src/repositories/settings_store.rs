@@ -0,0 +1,72 @@
+//! JSON file persistence for user preferences
+
+use crate::errors::WeatherError;
+use crate::models::settings::Settings;
+use crate::repositories::json_file::JsonFile;
+use std::path::{Path, PathBuf};
+
+/// Settings file, e.g. `~/.config/weather-app/settings.json`
+#[derive(Clone, Debug)]
+pub struct SettingsStore {
+    file: JsonFile,
+}
+
+impl SettingsStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        SettingsStore {
+            file: JsonFile::new(path),
+        }
+    }
+
+    /// Store in the platform config directory, if one can be determined
+    pub fn default_location() -> Option<Self> {
+        JsonFile::in_config_dir("settings.json").map(|file| SettingsStore { file })
+    }
+
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+
+    /// Reads the settings; a missing file or missing fields use the defaults
+    pub fn load(&self) -> Result<Settings, WeatherError> {
+        self.file.load()
+    }
+
+    pub fn save(&self, settings: &Settings) -> Result<(), WeatherError> {
+        self.file.save(settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::conversions::WindUnit;
+    use crate::utils::format::NumberLocale;
+    use crate::utils::hourly_range::HourlyRange;
+    use crate::utils::i18n::Locale;
+    use crate::views::DisplayOptions;
+
+    #[test]
+    fn test_settings_round_trip() {
+        let dir = std::env::temp_dir().join(format!("weather-app-settings-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let store = SettingsStore::new(dir.join("settings.json"));
+        assert_eq!(store.load().unwrap(), Settings::default());
+
+        let settings = Settings {
+            locale: Locale::De,
+            hourly_range: HourlyRange::Hours48,
+            usage_stats: true,
+            number_locale: NumberLocale::De,
+            user_agent: Some("MyStation/2.1".to_string()),
+            contact_email: Some("me@example.com".to_string()),
+            display: DisplayOptions::builder()
+                .mountain(true)
+                .wind_unit(WindUnit::Knots)
+                .build(),
+            last_location: Some("Oslo".to_string()),
+        };
+        store.save(&settings).unwrap();
+        assert_eq!(store.load().unwrap(), settings);
+    }
+}
@@ -0,0 +1,97 @@
+//! Last weather shown by the GUI, for an instant start
+//!
+//! The GUI writes the weather of each successful fetch here and reads it back
+//! on the next launch, so the dashboard is filled before the network answers.
+//! The file lives in the cache directory: losing it only costs the warm start.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::constants::SNAPSHOT_MAX_BYTES;
+use crate::errors::WeatherError;
+use crate::models::weather_info::WeatherInfo;
+use crate::repositories::json_file::JsonFile;
+use crate::utils::location::normalize_location_key;
+
+/// The query that was searched and the weather it returned
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Snapshot {
+    location: String,
+    weather: WeatherInfo,
+}
+
+/// Snapshot file, e.g. `~/.cache/weather-app/last_weather.json`
+#[derive(Clone, Debug)]
+pub struct SnapshotStore {
+    file: JsonFile,
+}
+
+impl SnapshotStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        SnapshotStore {
+            file: JsonFile::new(path),
+        }
+    }
+
+    /// Store in the platform cache directory, if one can be determined
+    pub fn default_location() -> Option<Self> {
+        JsonFile::in_cache_dir("last_weather.json").map(|file| SnapshotStore { file })
+    }
+
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+
+    /// Weather saved for `location` (compared by `normalize_location_key`), or `None`
+    /// when there is no snapshot, it is for another location, or the file is
+    /// larger than `SNAPSHOT_MAX_BYTES`
+    pub fn load(&self, location: &str) -> Result<Option<WeatherInfo>, WeatherError> {
+        match fs::metadata(self.path()) {
+            Ok(metadata) if metadata.len() > SNAPSHOT_MAX_BYTES => return Ok(None),
+            _ => {}
+        }
+        let snapshot: Option<Snapshot> = self.file.load()?;
+        Ok(snapshot
+            .filter(|snapshot| {
+                normalize_location_key(&snapshot.location) == normalize_location_key(location)
+            })
+            .map(|snapshot| snapshot.weather))
+    }
+
+    /// Replaces the snapshot with `weather`, fetched for `location`
+    pub fn save(&self, location: &str, weather: &WeatherInfo) -> Result<(), WeatherError> {
+        self.file.save(&Snapshot {
+            location: location.to_string(),
+            weather: weather.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::sample_weather_info;
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let dir = std::env::temp_dir().join(format!("weather-app-snapshot-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let store = SnapshotStore::new(dir.join("last_weather.json"));
+        assert_eq!(store.load("Seattle").unwrap(), None);
+
+        let mut weather = sample_weather_info();
+        weather.fetched_at = Some(chrono::Utc::now());
+        store.save("Seattle", &weather).unwrap();
+        assert_eq!(store.load(" seattle ").unwrap(), Some(weather));
+        assert_eq!(store.load("Portland").unwrap(), None);
+
+        // An oversized file is skipped rather than parsed
+        fs::write(store.path(), vec![b' '; SNAPSHOT_MAX_BYTES as usize + 1]).unwrap();
+        assert_eq!(store.load("Seattle").unwrap(), None);
+        fs::write(store.path(), "{\"location\": ").unwrap();
+        assert!(store.load("Seattle").is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,176 @@
+//! Append-only usage statistics file, written only when the user opts in
+//!
+//! Each lookup appends one JSON line with a single `write` on a file opened
+//! in append mode, so the CLI and GUI can record at the same time without
+//! interleaving. Counting happens when the file is read.
+//!
+//! Once the file grows past `USAGE_LOG_MAX_BYTES` the write that crossed the
+//! limit rewrites it with its newest half, so statistics cover recent use and
+//! the file never grows without bound.
+
+use crate::constants::USAGE_LOG_MAX_BYTES;
+use crate::errors::WeatherError;
+use crate::models::settings::Settings;
+use crate::models::usage::UsageRecord;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+/// Usage file, e.g. `~/.config/weather-app/usage.jsonl`
+#[derive(Clone, Debug)]
+pub struct UsageLog {
+    path: PathBuf,
+    max_bytes: u64,
+}
+
+impl UsageLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        UsageLog {
+            path: path.into(),
+            max_bytes: USAGE_LOG_MAX_BYTES,
+        }
+    }
+
+    /// Size past which the file is pruned to its newest `max_bytes / 2`
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Log in the platform config directory, if one can be determined
+    pub fn default_location() -> Option<Self> {
+        dirs::config_dir().map(|dir| Self::new(dir.join("weather-app").join("usage.jsonl")))
+    }
+
+    /// This log if `settings` opt in to usage statistics, else `None`
+    pub fn enabled_by(self, settings: &Settings) -> Option<Self> {
+        settings.usage_stats.then_some(self)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends one record, creating the file and its directory when needed,
+    /// and prunes the file once it exceeds the size limit
+    pub fn record(&self, record: &UsageRecord) -> Result<(), WeatherError> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|e| self.storage_error(e))?;
+        }
+        let mut line =
+            serde_json::to_string(record).map_err(|e| WeatherError::ParseError(e.to_string()))?;
+        line.push('\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|e| self.storage_error(e))?;
+
+        match fs::metadata(&self.path) {
+            Ok(metadata) if metadata.len() > self.max_bytes => self.prune(),
+            _ => Ok(()),
+        }
+    }
+
+    /// Keeps the newest whole lines that fit in half the size limit. The file
+    /// is replaced by a rename, so readers see either version; a record
+    /// appended by another process during the rewrite may be lost.
+    fn prune(&self) -> Result<(), WeatherError> {
+        let contents = fs::read_to_string(&self.path).map_err(|e| self.storage_error(e))?;
+        let budget = (self.max_bytes / 2) as usize;
+        let mut size = 0;
+        let mut kept = Vec::new();
+        for line in contents.lines().rev() {
+            size += line.len() + 1;
+            if size > budget {
+                break;
+            }
+            kept.push(line);
+        }
+        let pruned: String = kept.iter().rev().map(|line| format!("{line}\n")).collect();
+
+        let temporary = self.path.with_extension("jsonl.tmp");
+        fs::write(&temporary, pruned)
+            .and_then(|()| fs::rename(&temporary, &self.path))
+            .map_err(|e| self.storage_error(e))
+    }
+
+    /// Every record; a missing file has none, and unreadable lines (such as
+    /// one cut short by a crash) are skipped
+    pub fn load(&self) -> Result<Vec<UsageRecord>, WeatherError> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(self.storage_error(e)),
+        };
+        Ok(contents
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    fn storage_error(&self, e: std::io::Error) -> WeatherError {
+        WeatherError::StorageError(format!("{}: {e}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_records_append_and_skip_torn_lines() {
+        let dir = std::env::temp_dir().join(format!("weather-app-usage-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let log = UsageLog::new(dir.join("usage.jsonl"));
+        assert!(log.load().unwrap().is_empty());
+
+        let first = UsageRecord::now("Seattle", false, Duration::from_millis(420), true);
+        let second = UsageRecord::now("Oslo", true, Duration::ZERO, true);
+        log.record(&first).unwrap();
+        fs::OpenOptions::new()
+            .append(true)
+            .open(log.path())
+            .unwrap()
+            .write_all(b"{\"time\": \"2024-06-0\n")
+            .unwrap();
+        log.record(&second).unwrap();
+
+        assert_eq!(log.load().unwrap(), vec![first, second]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_pruned_on_write() {
+        let dir = std::env::temp_dir().join(format!("weather-app-prune-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let log = UsageLog::new(dir.join("usage.jsonl")).with_max_bytes(4096);
+
+        for n in 0..2000 {
+            let record = UsageRecord::now(&format!("Town {n}"), false, Duration::ZERO, true);
+            log.record(&record).unwrap();
+            assert!(fs::metadata(log.path()).unwrap().len() <= 4096);
+        }
+
+        let records = log.load().unwrap();
+        assert!(records.len() > 10);
+        assert_eq!(records.last().unwrap().location, "Town 1999");
+        // Whole lines only: every remaining line parses
+        let lines = fs::read_to_string(log.path()).unwrap().lines().count();
+        assert_eq!(records.len(), lines);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_disabled_by_default() {
+        let log = UsageLog::new("usage.jsonl");
+        assert!(log.clone().enabled_by(&Settings::default()).is_none());
+        let settings = Settings {
+            usage_stats: true,
+            ..Settings::default()
+        };
+        assert!(log.enabled_by(&settings).is_some());
+    }
+}
@@ -0,0 +1,128 @@
+//! User agent and contact address sent to the APIs
+//!
+//! Nominatim's usage policy asks each application instance to identify
+//! itself with a way to reach its operator. With a contact configured every
+//! request carries `RustWeatherApp/1.0 (mailto:me@example.com)` and Nominatim
+//! searches add `email=`. The address is kept out of `Display` and `Debug`,
+//! which is what ends up in diagnostics.
+
+use std::fmt;
+
+use crate::constants::USER_AGENT;
+use crate::models::settings::Settings;
+
+/// Product token and optional contact of this installation
+#[derive(Clone, PartialEq, Eq)]
+pub struct UserAgent {
+    product: String,
+    contact_email: Option<String>,
+}
+
+impl Default for UserAgent {
+    fn default() -> Self {
+        UserAgent {
+            product: USER_AGENT.to_string(),
+            contact_email: None,
+        }
+    }
+}
+
+impl UserAgent {
+    /// `product` replaces "RustWeatherApp/1.0"; blank values are ignored
+    pub fn new(product: Option<&str>, contact_email: Option<&str>) -> Self {
+        let non_blank = |value: Option<&str>| {
+            value
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        UserAgent {
+            product: non_blank(product).unwrap_or_else(|| USER_AGENT.to_string()),
+            contact_email: non_blank(contact_email),
+        }
+    }
+
+    /// From `user_agent` and `contact_email` in settings.json
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self::new(
+            settings.user_agent.as_deref(),
+            settings.contact_email.as_deref(),
+        )
+    }
+
+    pub fn has_contact(&self) -> bool {
+        self.contact_email.is_some()
+    }
+
+    /// `User-Agent` header value
+    pub fn header(&self) -> String {
+        match &self.contact_email {
+            Some(email) => format!("{} (mailto:{email})", self.product),
+            None => self.product.clone(),
+        }
+    }
+
+    /// Nominatim's `email` query parameter, e.g. "email=me%40example.com"
+    pub(crate) fn email_param(&self) -> Option<String> {
+        self.contact_email
+            .as_ref()
+            .map(|email| format!("email={}", urlencoding::encode(email)))
+    }
+}
+
+/// "RustWeatherApp/1.0 (contact configured)", without the address
+impl fmt::Display for UserAgent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.product)?;
+        if self.has_contact() {
+            f.write_str(" (contact configured)")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for UserAgent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UserAgent")
+            .field("product", &self.product)
+            .field("has_contact", &self.has_contact())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_and_query_parameter() {
+        let anonymous = UserAgent::default();
+        assert_eq!(anonymous.header(), "RustWeatherApp/1.0");
+        assert_eq!(anonymous.email_param(), None);
+
+        let contact = UserAgent::new(None, Some(" me+weather@example.com "));
+        assert_eq!(
+            contact.header(),
+            "RustWeatherApp/1.0 (mailto:me+weather@example.com)"
+        );
+        assert_eq!(
+            contact.email_param().as_deref(),
+            Some("email=me%2Bweather%40example.com")
+        );
+
+        let custom = UserAgent::new(Some("MyStation/2.1"), Some(""));
+        assert_eq!(custom.header(), "MyStation/2.1");
+        assert!(!custom.has_contact());
+    }
+
+    #[test]
+    fn test_contact_is_redacted_from_diagnostics() {
+        let settings = Settings {
+            contact_email: Some("me@example.com".to_string()),
+            ..Settings::default()
+        };
+        let agent = UserAgent::from_settings(&settings);
+        assert_eq!(agent.to_string(), "RustWeatherApp/1.0 (contact configured)");
+        assert!(!format!("{agent:?}").contains("example.com"));
+    }
+}
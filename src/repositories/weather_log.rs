@@ -0,0 +1,161 @@
+//! Archive of current conditions in a user-chosen directory
+//!
+//! Each location gets one file per month, e.g. `seattle-2024-06.jsonl`, so a
+//! new month starts a new file and old ones are never touched again. Records
+//! are appended with a single `write` on a file opened in append mode, so
+//! several processes can log the same location at once without interleaving.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::errors::WeatherError;
+use crate::models::weather_log::WeatherLogRecord;
+use crate::utils::location::normalize_location_key;
+
+/// Directory of monthly log files
+#[derive(Clone, Debug)]
+pub struct WeatherLog {
+    dir: PathBuf,
+}
+
+impl WeatherLog {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        WeatherLog { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// File for `location` in `month` ("YYYY-MM")
+    pub fn path_for(&self, location: &str, month: &str) -> PathBuf {
+        self.dir
+            .join(format!("{}-{month}.jsonl", file_stem(location)))
+    }
+
+    /// Appends `record` to the file of its month, creating the directory and
+    /// file when needed. Returns the file written to.
+    pub fn append(
+        &self,
+        location: &str,
+        record: &WeatherLogRecord,
+    ) -> Result<PathBuf, WeatherError> {
+        let month = record.month().ok_or_else(|| {
+            WeatherError::ParseError(format!("Unreadable log record time: {}", record.time))
+        })?;
+        let path = self.path_for(location, month);
+        let storage_error =
+            |e: std::io::Error| WeatherError::StorageError(format!("{}: {e}", path.display()));
+
+        fs::create_dir_all(&self.dir).map_err(storage_error)?;
+        let mut line =
+            serde_json::to_string(record).map_err(|e| WeatherError::ParseError(e.to_string()))?;
+        line.push('\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(storage_error)?;
+        Ok(path)
+    }
+}
+
+/// Reads a log file: the records, and how many lines could not be read
+pub fn read(path: &Path) -> Result<(Vec<WeatherLogRecord>, usize), WeatherError> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| WeatherError::StorageError(format!("{}: {e}", path.display())))?;
+    let mut records = Vec::new();
+    let mut skipped = 0;
+    for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+        match serde_json::from_str(line) {
+            Ok(record) => records.push(record),
+            Err(_) => skipped += 1,
+        }
+    }
+    Ok((records, skipped))
+}
+
+/// Letters and digits of the normalized location key, other runs replaced
+/// by one dash: "Sankt Gallen, CH" → "sankt-gallen-ch". Spellings that share
+/// a cache key share a file.
+fn file_stem(location: &str) -> String {
+    let key = normalize_location_key(location);
+    let stem = key
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if stem.is_empty() {
+        "location".to_string()
+    } else {
+        stem
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(time: &str, observed: Option<&str>, temperature: f64) -> WeatherLogRecord {
+        WeatherLogRecord {
+            time: time.to_string(),
+            observed: observed.map(str::to_string),
+            temperature: Some(temperature),
+            humidity: Some(70.0),
+            pressure: Some(1016.2),
+            wind_speed: Some(9.0),
+            wind_direction: Some(200.0),
+            weather_code: Some(3),
+            precipitation: Some(0.0),
+        }
+    }
+
+    #[test]
+    fn test_file_names() {
+        let log = WeatherLog::new("/logs");
+        assert_eq!(
+            log.path_for("Seattle", "2024-06"),
+            PathBuf::from("/logs/seattle-2024-06.jsonl")
+        );
+        assert_eq!(file_stem(" Sankt Gallen, CH "), "sankt-gallen-ch");
+        assert_eq!(file_stem("Zürich"), "zürich");
+        assert_eq!(file_stem("MÜNCHEN,"), file_stem("münchen"));
+        assert_eq!(file_stem("../etc"), "etc");
+        assert_eq!(file_stem("47.6, -122.3"), "47-6-122-3");
+        assert_eq!(file_stem("  ,, "), "location");
+    }
+
+    #[test]
+    fn test_append_rotates_monthly_and_keeps_records() {
+        let dir = std::env::temp_dir().join(format!("weather-app-archive-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let log = WeatherLog::new(dir.join("nested"));
+
+        let june = record("2024-06-30T21:00:00+00:00", Some("2024-06-30T14:00"), 18.5);
+        let july = record("2024-07-01T07:00:00+00:00", Some("2024-07-01T00:00"), 12.0);
+        let june_path = log.append("Seattle", &june).unwrap();
+        assert_eq!(june_path, dir.join("nested/seattle-2024-06.jsonl"));
+        assert_eq!(june_path, log.append("seattle", &june).unwrap());
+        let july_path = log.append("Seattle", &july).unwrap();
+        assert_eq!(july_path, dir.join("nested/seattle-2024-07.jsonl"));
+
+        // A torn line from a crashed writer is skipped, later records still read
+        OpenOptions::new()
+            .append(true)
+            .open(&june_path)
+            .unwrap()
+            .write_all(b"{\"time\": \"2024-06-3\n\n")
+            .unwrap();
+        log.append("Seattle", &june).unwrap();
+
+        assert_eq!(
+            read(&june_path).unwrap(),
+            (vec![june.clone(), june.clone(), june], 1)
+        );
+        assert_eq!(read(&july_path).unwrap(), (vec![july], 0));
+        assert!(read(&dir.join("missing.jsonl")).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
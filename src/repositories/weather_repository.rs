@@ -1,53 +1,143 @@
 //! Weather data repository for fetching from external APIs
 
-use reqwest::blocking::Client;
+use chrono::Utc;
+use reqwest::blocking::{Client, Response};
+use reqwest::StatusCode;
 use serde::Deserialize;
 use serde_json::Value;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use crate::constants::{
-    DAILY_FORECAST_DAYS, HOURLY_FORECAST_LIMIT, NOMINATIM_API_URL, OPEN_METEO_API_URL, USER_AGENT,
+    CONNECT_TIMEOUT, DAILY_FORECAST_DAYS, GEOCODE_CANDIDATES, HOURLY_FORECAST_LIMIT,
+    MAX_FORECAST_DAYS, NOMINATIM_API_URL, NOMINATIM_MIN_INTERVAL, OPEN_METEO_AIR_QUALITY_API_URL,
+    OPEN_METEO_API_URL, OPEN_METEO_ENSEMBLE_API_URL, OPEN_METEO_MARINE_API_URL, REQUEST_TIMEOUT,
+    SPLIT_FETCH_HOURLY_HOURS,
 };
 use crate::errors::WeatherError;
+use crate::models::air_quality::AirQuality;
+use crate::models::favorites::{moved_too_far, ResolvedPlace};
+use crate::models::forecast_model::ForecastModel;
+use crate::models::marine::MarineForecast;
+use crate::models::timings::{FetchStrategy, FetchTimings};
 use crate::models::weather_info::{
-    CurrentWeather, DailyForecast, HourlyForecast, WeatherData, WeatherInfo,
+    CurrentWeather, DailyForecast, HourlyForecast, MinutelyForecast, WeatherData, WeatherInfo,
 };
+use crate::repositories::air_quality;
+use crate::repositories::backoff::{Backoff, RequestRole};
+use crate::repositories::data_selection::DataSelection;
+use crate::repositories::ensemble;
+use crate::repositories::fetch_options::FetchOptions;
+use crate::repositories::geocode_cache::GeocodeCache;
+use crate::repositories::geocoding::{self, GeocodeResult, Geocoder, GeocodingProvider};
+use crate::repositories::marine;
+use crate::repositories::provider::WeatherProvider;
+use crate::repositories::request_spacing::{self, Clock, SpacingMode, SystemClock};
+use crate::repositories::response_cache::ResponseCache;
+use crate::repositories::retry::{self, RetryPolicy};
+use crate::repositories::sanitize;
+use crate::repositories::server_clock::ServerClock;
+use crate::repositories::user_agent::UserAgent;
+use crate::utils::confidence::Confidence;
+use crate::utils::coords::{self, Coordinates};
 
 /// API response structure from Open-Meteo
 #[derive(Debug, Deserialize)]
-struct OpenMeteoWeather {
-    current: OpenMeteoCurrent,
+pub(crate) struct OpenMeteoWeather {
+    /// Elevation of the forecast grid point in meters
+    elevation: Option<f64>,
+    generationtime_ms: Option<f64>,
+    /// Offset of the location's time zone (`timezone=auto`); all times are local to it
+    utc_offset_seconds: Option<i32>,
+    /// IANA name of that time zone, e.g. "Asia/Tokyo"
+    timezone: Option<String>,
+    /// e.g. "JST", or "GMT+9" for zones without a common abbreviation
+    timezone_abbreviation: Option<String>,
+    current: Option<OpenMeteoCurrent>,
+    /// Legacy current conditions returned for `current_weather=true`
+    current_weather: Option<OpenMeteoLegacyCurrent>,
     hourly: Option<OpenMeteoHourly>,
     daily: Option<OpenMeteoDaily>,
+    minutely_15: Option<OpenMeteoMinutely>,
+}
+
+/// Parsed forecast and how it was obtained
+pub(crate) struct ForecastResponse {
+    pub(crate) weather: OpenMeteoWeather,
+    /// The legacy `current_weather=true` form was used
+    pub(crate) reduced_data: bool,
+    pub(crate) strategy: FetchStrategy,
+    /// Size of the response bodies
+    pub(crate) bytes: usize,
+}
+
+/// Error body returned by Open-Meteo, e.g. `{"error": true, "reason": "..."}`
+#[derive(Debug, Deserialize)]
+struct OpenMeteoErrorBody {
+    reason: String,
+}
+
+/// Legacy `current_weather` block supported by older and self-hosted deployments
+#[derive(Debug, Deserialize)]
+struct OpenMeteoLegacyCurrent {
+    time: Option<String>,
+    /// Temperature in Celsius
+    temperature: Option<f64>,
+    /// Wind speed in km/h
+    windspeed: Option<f64>,
+    /// Wind direction in degrees
+    winddirection: Option<f64>,
+    /// Weather condition code (WMO)
+    weathercode: Option<i32>,
+    /// 1 during daylight, 0 at night
+    is_day: Option<u8>,
 }
 
 /// Current weather from Open-Meteo API
 /// Field names match API response format (snake_case with units)
 #[derive(Debug, Deserialize)]
 struct OpenMeteoCurrent {
+    /// Observation time (ISO 8601)
+    time: Option<String>,
     /// Temperature at 2 meters above ground in Celsius
     temperature_2m: Option<f64>,
     /// Apparent temperature (feels like) in Celsius
     apparent_temperature: Option<f64>,
     /// Relative humidity percentage
     relative_humidity_2m: Option<f64>,
+    /// Dew point at 2 meters above ground in Celsius
+    dew_point_2m: Option<f64>,
     /// Precipitation amount in mm
     precipitation: Option<f64>,
+    /// Snowfall amount in cm
+    snowfall: Option<f64>,
     /// Weather condition code (WMO)
     weather_code: Option<i32>,
     /// Wind speed in km/h
     wind_speed_10m: Option<f64>,
     /// Wind direction in degrees
     wind_direction_10m: Option<f64>,
+    /// Wind gusts in km/h
+    wind_gusts_10m: Option<f64>,
     /// Cloud cover percentage
     cloud_cover: Option<f64>,
     /// Surface pressure in hPa
     surface_pressure: Option<f64>,
+    /// Pressure reduced to mean sea level in hPa
+    pressure_msl: Option<f64>,
     /// Visibility in meters
     visibility: Option<f64>,
+    /// Snow depth in meters
+    snow_depth: Option<f64>,
+    /// 1 during daylight, 0 at night
+    is_day: Option<u8>,
 }
 
 /// Hourly forecast arrays from Open-Meteo API
-#[derive(Debug, Deserialize)]
+/// Arrays missing from the response (variables not requested) default to empty
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
 struct OpenMeteoHourly {
     time: Vec<String>,
     temperature_2m: Vec<Option<f64>>,
@@ -56,31 +146,241 @@ struct OpenMeteoHourly {
     precipitation: Vec<Option<f64>>,
     weather_code: Vec<Option<i32>>,
     wind_speed_10m: Vec<Option<f64>>,
+    wind_direction_10m: Vec<Option<f64>>,
+    wind_gusts_10m: Vec<Option<f64>>,
+    wind_speed_80m: Vec<Option<f64>>,
+    wind_direction_80m: Vec<Option<f64>>,
+    wind_speed_120m: Vec<Option<f64>>,
+    wind_direction_120m: Vec<Option<f64>>,
     relative_humidity_2m: Vec<Option<f64>>,
+    dew_point_2m: Vec<Option<f64>>,
+    freezing_level_height: Vec<Option<f64>>,
+    cape: Vec<Option<f64>>,
+    snowfall: Vec<Option<f64>>,
+    snow_depth: Vec<Option<f64>>,
+    cloud_cover: Vec<Option<f64>>,
+    visibility: Vec<Option<f64>>,
+    uv_index: Vec<Option<f64>>,
+}
+
+impl OpenMeteoHourly {
+    /// Drops the hours before local midnight of `date` ("2024-06-01")
+    fn drop_before(&mut self, date: &str) {
+        fn drain<T>(values: &mut Vec<T>, count: usize) {
+            values.drain(..count.min(values.len()));
+        }
+        let count = self.time.iter().take_while(|t| t.as_str() < date).count();
+        drain(&mut self.time, count);
+        drain(&mut self.temperature_2m, count);
+        drain(&mut self.apparent_temperature, count);
+        drain(&mut self.precipitation_probability, count);
+        drain(&mut self.precipitation, count);
+        drain(&mut self.weather_code, count);
+        drain(&mut self.wind_speed_10m, count);
+        drain(&mut self.wind_direction_10m, count);
+        drain(&mut self.wind_gusts_10m, count);
+        drain(&mut self.wind_speed_80m, count);
+        drain(&mut self.wind_direction_80m, count);
+        drain(&mut self.wind_speed_120m, count);
+        drain(&mut self.wind_direction_120m, count);
+        drain(&mut self.relative_humidity_2m, count);
+        drain(&mut self.dew_point_2m, count);
+        drain(&mut self.freezing_level_height, count);
+        drain(&mut self.cape, count);
+        drain(&mut self.snowfall, count);
+        drain(&mut self.snow_depth, count);
+        drain(&mut self.cloud_cover, count);
+        drain(&mut self.visibility, count);
+        drain(&mut self.uv_index, count);
+    }
+}
+
+/// 15-minute forecast arrays from Open-Meteo API (`minutely_15`)
+/// Arrays missing from the response (variables not requested) default to empty
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct OpenMeteoMinutely {
+    time: Vec<String>,
+    temperature_2m: Vec<Option<f64>>,
+    precipitation: Vec<Option<f64>>,
+    weather_code: Vec<Option<i32>>,
 }
 
 /// Daily forecast arrays from Open-Meteo API
-#[derive(Debug, Deserialize)]
+/// Arrays missing from the response (variables not requested) default to empty
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
 struct OpenMeteoDaily {
     time: Vec<String>,
     temperature_2m_max: Vec<Option<f64>>,
     temperature_2m_min: Vec<Option<f64>>,
     weather_code: Vec<Option<i32>>,
     precipitation_sum: Vec<Option<f64>>,
+    snowfall_sum: Vec<Option<f64>>,
     precipitation_probability_max: Vec<Option<f64>>,
     wind_speed_10m_max: Vec<Option<f64>>,
+    wind_gusts_10m_max: Vec<Option<f64>>,
     sunrise: Vec<Option<String>>,
     sunset: Vec<Option<String>>,
+    daylight_duration: Vec<Option<f64>>,
+    sunshine_duration: Vec<Option<f64>>,
+    et0_fao_evapotranspiration: Vec<Option<f64>>,
+    relative_humidity_2m_mean: Vec<Option<f64>>,
+    uv_index_max: Vec<Option<f64>>,
+}
+
+/// First usable geocoding candidate, with why earlier candidates were skipped
+#[derive(Debug, PartialEq)]
+pub(crate) struct GeocodeMatch {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// IANA time zone, when the provider returns one
+    pub timezone: Option<String>,
+    /// Provider's full name for the match, e.g. "Bern, Verwaltungskreis
+    /// Bern-Mittelland, Switzerland"
+    pub name: Option<String>,
+    pub skipped: Vec<String>,
+}
+
+impl GeocodeMatch {
+    /// Match for coordinates given directly, with no geocoding
+    pub(crate) fn at(coordinates: Coordinates) -> Self {
+        GeocodeMatch {
+            latitude: coordinates.latitude,
+            longitude: coordinates.longitude,
+            timezone: None,
+            name: None,
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Short form of the provider's name for the match, e.g. "New York,
+    /// United States"; `query` itself when the provider named nothing
+    pub(crate) fn resolved_name(&self, query: &str) -> String {
+        self.name
+            .as_deref()
+            .map_or_else(|| query.trim().to_string(), geocoding::short_name)
+    }
+
+    /// Reports skipped candidates on stderr when `verbose` is set
+    pub(crate) fn log_skipped(&self, verbose: bool) {
+        if verbose {
+            for reason in &self.skipped {
+                eprintln!("Note: skipped geocoding result: {reason}");
+            }
+        }
+    }
+}
+
+/// Blocking client with the given timeouts
+fn http_client(connect: Duration, request: Duration) -> Client {
+    Client::builder()
+        .connect_timeout(connect)
+        .timeout(request)
+        .build()
+        .expect("HTTP client (TLS backend) failed to initialize")
 }
 
 /// Trait for weather data sources (enables dependency injection)
 pub trait WeatherRepository {
     fn fetch_weather(&self, location: &str) -> Result<WeatherInfo, WeatherError>;
+
+    /// Requests answered from an in-memory copy of an identical earlier
+    /// response instead of the network
+    fn response_cache_hits(&self) -> usize {
+        0
+    }
+
+    /// Drops the in-memory copies of earlier responses, so the next fetch
+    /// asks the servers again; for a manual refresh
+    fn clear_response_cache(&self) {}
+
+    /// Current name and position of a favorite's location, reverse geocoded
+    /// for coordinates; `None` when nothing matches or the repository has no
+    /// geocoder. `recorded` is where the favorite was last resolved to: a
+    /// result too far from it (see `moved_too_far`) is returned for the user
+    /// to confirm, but not stored for later lookups.
+    fn resolve_place(
+        &self,
+        _location: &str,
+        _recorded: Option<&ResolvedPlace>,
+    ) -> Result<Option<ResolvedPlace>, WeatherError> {
+        Ok(None)
+    }
+
+    /// Service the forecasts come from, to attribute them to
+    fn provider(&self) -> WeatherProvider {
+        WeatherProvider::OpenMeteo
+    }
+}
+
+impl<R: WeatherRepository + ?Sized> WeatherRepository for Box<R> {
+    fn fetch_weather(&self, location: &str) -> Result<WeatherInfo, WeatherError> {
+        (**self).fetch_weather(location)
+    }
+
+    fn response_cache_hits(&self) -> usize {
+        (**self).response_cache_hits()
+    }
+
+    fn clear_response_cache(&self) {
+        (**self).clear_response_cache()
+    }
+
+    fn resolve_place(
+        &self,
+        location: &str,
+        recorded: Option<&ResolvedPlace>,
+    ) -> Result<Option<ResolvedPlace>, WeatherError> {
+        (**self).resolve_place(location, recorded)
+    }
+
+    fn provider(&self) -> WeatherProvider {
+        (**self).provider()
+    }
+}
+
+/// Geocoder a repository asks when its own fails
+enum FallbackGeocoder {
+    /// The other provider, while the main geocoder is at its public endpoint
+    Default,
+    Off,
+    Set(GeocodingProvider, String),
 }
 
-/// Implementation using OpenStreetMap Nominatim (geocoding) and Open-Meteo (weather)
+/// Implementation using OpenStreetMap Nominatim or Open-Meteo (geocoding) and
+/// Open-Meteo (weather)
 pub struct ApiWeatherRepository {
     client: Client,
+    connect_timeout: Duration,
+    request_timeout: Duration,
+    retry_policy: RetryPolicy,
+    geocoder: GeocodingProvider,
+    geocoding_url: String,
+    /// Asked when `geocoder` fails
+    fallback_geocoder: FallbackGeocoder,
+    /// Least time between Nominatim requests, shared by the whole process;
+    /// `None` spaces only those to the public server
+    nominatim_interval: Option<Duration>,
+    nominatim_spacing: SpacingMode,
+    /// Times the Nominatim spacing; simulated in tests
+    spacing_clock: Arc<dyn Clock>,
+    weather_url: String,
+    marine_url: String,
+    ensemble_url: String,
+    air_quality_url: String,
+    data_selection: DataSelection,
+    forecast_days: usize,
+    hourly_hours: usize,
+    model: ForecastModel,
+    user_agent: UserAgent,
+    verbose: bool,
+    backoff: Backoff,
+    server_clock: ServerClock,
+    /// `None` when responses must always come from the network
+    response_cache: Option<ResponseCache>,
+    /// Geocoding results kept on disk across runs
+    geocode_cache: Option<GeocodeCache>,
 }
 
 impl Default for ApiWeatherRepository {
@@ -92,159 +392,3164 @@ impl Default for ApiWeatherRepository {
 impl ApiWeatherRepository {
     pub fn new() -> Self {
         ApiWeatherRepository {
-            client: Client::new(),
+            client: http_client(CONNECT_TIMEOUT, REQUEST_TIMEOUT),
+            connect_timeout: CONNECT_TIMEOUT,
+            request_timeout: REQUEST_TIMEOUT,
+            retry_policy: RetryPolicy::default(),
+            geocoder: GeocodingProvider::default(),
+            geocoding_url: NOMINATIM_API_URL.to_string(),
+            fallback_geocoder: FallbackGeocoder::Default,
+            nominatim_interval: None,
+            nominatim_spacing: SpacingMode::default(),
+            spacing_clock: Arc::new(SystemClock),
+            weather_url: OPEN_METEO_API_URL.to_string(),
+            marine_url: OPEN_METEO_MARINE_API_URL.to_string(),
+            ensemble_url: OPEN_METEO_ENSEMBLE_API_URL.to_string(),
+            air_quality_url: OPEN_METEO_AIR_QUALITY_API_URL.to_string(),
+            data_selection: DataSelection::full(),
+            forecast_days: DAILY_FORECAST_DAYS,
+            hourly_hours: SPLIT_FETCH_HOURLY_HOURS,
+            model: ForecastModel::BestMatch,
+            user_agent: UserAgent::default(),
+            verbose: false,
+            backoff: Backoff::default(),
+            server_clock: ServerClock::default(),
+            response_cache: Some(ResponseCache::default()),
+            geocode_cache: None,
         }
     }
 
-    /// Converts location name to coordinates using Nominatim geocoding API
-    fn fetch_coordinates(&self, location: &str) -> Result<(f64, f64), WeatherError> {
-        let location = location.trim();
-        let url = format!(
-            "{}?q={}&format=json&limit=1",
-            NOMINATIM_API_URL,
-            urlencoding::encode(location)
-        );
+    /// Gives up on a server that takes longer than `connect` to accept the
+    /// connection or `request` to answer in full, with `WeatherError::Timeout`
+    /// (defaults `CONNECT_TIMEOUT` and `REQUEST_TIMEOUT`)
+    pub fn with_timeouts(mut self, connect: Duration, request: Duration) -> Self {
+        self.client = http_client(connect, request);
+        self.connect_timeout = connect;
+        self.request_timeout = request;
+        self
+    }
 
-        let response = self
-            .client
-            .get(&url)
-            .header("User-Agent", USER_AGENT)
-            .send()
-            .map_err(|e| WeatherError::NetworkError(e.to_string()))?;
+    /// Connect and whole-request timeouts
+    pub fn timeouts(&self) -> (Duration, Duration) {
+        (self.connect_timeout, self.request_timeout)
+    }
 
-        if !response.status().is_success() {
-            return Err(WeatherError::ApiError(format!(
-                "Geocoding API returned status: {}",
-                response.status()
-            )));
-        }
+    /// Repeats requests that fail with a connection error, a timeout or a
+    /// 5xx status; `RetryPolicy::none()` reports the first failure
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
 
-        let json: Value = response
-            .json()
-            .map_err(|e| WeatherError::ParseError(e.to_string()))?;
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
 
-        let array = json.as_array().ok_or_else(|| {
-            WeatherError::ParseError("Invalid response format from geocoding API".to_string())
-        })?;
+    /// Reuses response bodies for identical URLs requested within
+    /// `RESPONSE_CACHE_TTL`; off for `--no-cache`
+    pub fn with_response_cache(mut self, enabled: bool) -> Self {
+        self.response_cache = enabled.then(ResponseCache::default);
+        self
+    }
 
-        if array.is_empty() {
-            return Err(WeatherError::LocationNotFound(location.to_string()));
+    /// Looks place names up in `cache` before asking the geocoder, and
+    /// stores what the geocoder finds; `None` (the default) always asks
+    pub fn with_geocode_cache(mut self, cache: Option<GeocodeCache>) -> Self {
+        self.geocode_cache = cache;
+        self
+    }
+
+    /// The geocoding cache in use, e.g. to clear it
+    pub fn geocode_cache(&self) -> Option<&GeocodeCache> {
+        self.geocode_cache.as_ref()
+    }
+
+    /// Reports recoverable problems (such as skipped geocoding results) on stderr
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    pub fn verbose(&self) -> bool {
+        self.verbose
+    }
+
+    /// Identifies this installation, ideally with a contact (see `UserAgent`)
+    pub fn with_user_agent(mut self, user_agent: UserAgent) -> Self {
+        self.user_agent = user_agent;
+        self
+    }
+
+    pub fn user_agent(&self) -> &UserAgent {
+        &self.user_agent
+    }
+
+    /// Limits which variables are requested (and therefore parsed)
+    pub fn with_data_selection(mut self, selection: DataSelection) -> Self {
+        self.data_selection = selection;
+        self
+    }
+
+    /// Days of daily forecast to request (1 to 16). Beyond the default 7 the
+    /// hourly data is fetched separately for the first `hourly_hours` only.
+    pub fn with_forecast_days(mut self, days: usize) -> Self {
+        self.forecast_days = days.clamp(1, MAX_FORECAST_DAYS);
+        self
+    }
+
+    pub fn forecast_days(&self) -> usize {
+        self.forecast_days
+    }
+
+    /// Hours of hourly data requested when a long forecast is split
+    pub fn with_hourly_hours(mut self, hours: usize) -> Self {
+        self.hourly_hours = hours.max(1);
+        self
+    }
+
+    pub fn hourly_hours(&self) -> usize {
+        self.hourly_hours
+    }
+
+    /// Weather model to request forecasts from (default `BestMatch`)
+    pub fn with_model(mut self, model: ForecastModel) -> Self {
+        self.model = model;
+        self
+    }
+
+    pub fn model(&self) -> ForecastModel {
+        self.model
+    }
+
+    /// Selects the geocoding service. A geocoding endpoint still at the previous
+    /// provider's default moves to the new provider's default; overrides are kept.
+    pub fn with_geocoder(mut self, geocoder: GeocodingProvider) -> Self {
+        if self.geocoding_url == self.geocoder.default_url() {
+            self.geocoding_url = geocoder.default_url().to_string();
         }
+        self.geocoder = geocoder;
+        self
+    }
 
-        let first_result = &array[0];
-        let lat = first_result["lat"]
-            .as_str()
-            .and_then(|s| s.parse::<f64>().ok())
-            .ok_or_else(|| WeatherError::ParseError("Failed to parse latitude".to_string()))?;
-        let lon = first_result["lon"]
-            .as_str()
-            .and_then(|s| s.parse::<f64>().ok())
-            .ok_or_else(|| WeatherError::ParseError("Failed to parse longitude".to_string()))?;
+    pub fn geocoder(&self) -> GeocodingProvider {
+        self.geocoder
+    }
 
-        Ok((lat, lon))
+    /// Keeps Nominatim requests, search and reverse, at least `interval`
+    /// apart across the process, wherever the endpoint is; zero turns the
+    /// spacing off. By default only the public server is spaced, by
+    /// `NOMINATIM_MIN_INTERVAL` as its usage policy asks, since a
+    /// self-hosted instance sets its own rules. Answers from the caches are
+    /// not requests and never wait.
+    pub fn with_nominatim_interval(mut self, interval: Duration) -> Self {
+        self.nominatim_interval = Some(interval);
+        self
     }
 
-    /// Maps API response to domain model
-    fn parse_current_weather(current: &OpenMeteoCurrent) -> CurrentWeather {
-        CurrentWeather {
-            temperature: current.temperature_2m,
-            apparent_temperature: current.apparent_temperature,
-            humidity: current.relative_humidity_2m,
-            precipitation: current.precipitation,
-            weather_code: current.weather_code,
-            wind_speed: current.wind_speed_10m,
-            wind_direction: current.wind_direction_10m,
-            cloud_cover: current.cloud_cover,
-            pressure: current.surface_pressure,
-            visibility: current.visibility,
+    /// Least time between requests to the Nominatim endpoint at `url`
+    pub fn nominatim_interval(&self, url: &str) -> Duration {
+        match self.nominatim_interval {
+            Some(interval) => interval,
+            None if request_spacing::same_host(url, NOMINATIM_API_URL) => NOMINATIM_MIN_INTERVAL,
+            None => Duration::ZERO,
         }
     }
 
-    fn parse_hourly_forecasts(hourly: Option<OpenMeteoHourly>) -> Vec<HourlyForecast> {
-        if let Some(hourly) = hourly {
-            let count = hourly.time.len().min(HOURLY_FORECAST_LIMIT);
-            (0..count)
-                .map(|i| HourlyForecast {
-                    time: hourly.time[i].clone(),
-                    temperature: hourly.temperature_2m.get(i).and_then(|v| *v),
-                    apparent_temperature: hourly.apparent_temperature.get(i).and_then(|v| *v),
-                    precipitation_probability: hourly
-                        .precipitation_probability
-                        .get(i)
-                        .and_then(|v| *v),
-                    precipitation: hourly.precipitation.get(i).and_then(|v| *v),
-                    weather_code: hourly.weather_code.get(i).and_then(|v| *v),
-                    wind_speed: hourly.wind_speed_10m.get(i).and_then(|v| *v),
-                    humidity: hourly.relative_humidity_2m.get(i).and_then(|v| *v),
-                })
-                .collect()
-        } else {
-            Vec::new()
+    /// Whether a Nominatim request that comes too early waits for its turn
+    /// (the default) or fails with `WeatherError::Throttled`, e.g. for a
+    /// GUI that would rather not block
+    pub fn with_nominatim_spacing(mut self, mode: SpacingMode) -> Self {
+        self.nominatim_spacing = mode;
+        self
+    }
+
+    pub fn nominatim_spacing(&self) -> SpacingMode {
+        self.nominatim_spacing
+    }
+
+    /// Times the Nominatim spacing on `clock` instead of the system's
+    #[cfg(test)]
+    pub(crate) fn with_spacing_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.spacing_clock = clock;
+        self
+    }
+
+    /// Clock the async repository shares
+    #[cfg(feature = "async")]
+    pub(crate) fn spacing_clock(&self) -> Arc<dyn Clock> {
+        Arc::clone(&self.spacing_clock)
+    }
+
+    /// Geocoder asked, at its public endpoint, when the main one fails or
+    /// finds nothing; `None` relies on the main one alone. By default the
+    /// other provider is asked, as long as the main geocoder is at its public
+    /// endpoint: a self-hosted or test server's lookups stay there.
+    pub fn with_fallback_geocoder(mut self, fallback: Option<GeocodingProvider>) -> Self {
+        self.fallback_geocoder = match fallback {
+            Some(geocoder) => FallbackGeocoder::Set(geocoder, geocoder.default_url().to_string()),
+            None => FallbackGeocoder::Off,
+        };
+        self
+    }
+
+    /// Overrides the fallback geocoder's search endpoint
+    pub fn with_fallback_geocoding_url(mut self, url: impl Into<String>) -> Self {
+        match &mut self.fallback_geocoder {
+            FallbackGeocoder::Default => {
+                self.fallback_geocoder = FallbackGeocoder::Set(self.geocoder.fallback(), url.into())
+            }
+            FallbackGeocoder::Set(_, fallback_url) => *fallback_url = url.into(),
+            FallbackGeocoder::Off => {}
         }
+        self
     }
 
-    /// Parses daily forecast data from API response
-    fn parse_daily_forecasts(daily: Option<OpenMeteoDaily>) -> Vec<DailyForecast> {
-        if let Some(daily) = daily {
-            (0..daily.time.len())
-                .map(|i| DailyForecast {
-                    date: daily.time[i].clone(),
-                    temperature_max: daily.temperature_2m_max.get(i).and_then(|v| *v),
-                    temperature_min: daily.temperature_2m_min.get(i).and_then(|v| *v),
-                    weather_code: daily.weather_code.get(i).and_then(|v| *v),
-                    precipitation_sum: daily.precipitation_sum.get(i).and_then(|v| *v),
-                    precipitation_probability: daily
-                        .precipitation_probability_max
-                        .get(i)
-                        .and_then(|v| *v),
-                    wind_speed_max: daily.wind_speed_10m_max.get(i).and_then(|v| *v),
-                    sunrise: daily.sunrise.get(i).and_then(|v| v.clone()),
-                    sunset: daily.sunset.get(i).and_then(|v| v.clone()),
-                })
-                .collect()
-        } else {
-            Vec::new()
+    /// Fallback geocoder and its search endpoint
+    pub fn fallback_geocoder(&self) -> Option<(GeocodingProvider, &str)> {
+        match &self.fallback_geocoder {
+            FallbackGeocoder::Default if self.geocoding_url == self.geocoder.default_url() => {
+                let fallback = self.geocoder.fallback();
+                Some((fallback, fallback.default_url()))
+            }
+            FallbackGeocoder::Default | FallbackGeocoder::Off => None,
+            FallbackGeocoder::Set(geocoder, url) => Some((*geocoder, url.as_str())),
         }
     }
 
-    // Constructs the Open-Meteo API URL with query parameters
-    fn build_weather_api_url(lat: f64, lon: f64) -> String {
-        format!(
-            "{OPEN_METEO_API_URL}?latitude={lat}&longitude={lon}&current=temperature_2m,apparent_temperature,relative_humidity_2m,precipitation,weather_code,wind_speed_10m,wind_direction_10m,cloud_cover,surface_pressure,visibility&hourly=temperature_2m,apparent_temperature,precipitation_probability,precipitation,weather_code,wind_speed_10m,relative_humidity_2m&daily=temperature_2m_max,temperature_2m_min,weather_code,precipitation_sum,precipitation_probability_max,wind_speed_10m_max,sunrise,sunset&forecast_days={DAILY_FORECAST_DAYS}"
-        )
+    /// Overrides the API endpoints, e.g. to use self-hosted instances
+    pub fn with_endpoints(
+        mut self,
+        geocoding_url: impl Into<String>,
+        weather_url: impl Into<String>,
+    ) -> Self {
+        self.geocoding_url = geocoding_url.into();
+        self.weather_url = weather_url.into();
+        self
     }
-}
 
-// Implement the WeatherRepository trait for ApiWeatherRepository
-impl WeatherRepository for ApiWeatherRepository {
-    // Fetches weather information for a given location
-    fn fetch_weather(&self, location: &str) -> Result<WeatherInfo, WeatherError> {
-        let (lat, lon) = self.fetch_coordinates(location)?;
+    /// Overrides the marine endpoint used when the selection includes waves
+    pub fn with_marine_url(mut self, marine_url: impl Into<String>) -> Self {
+        self.marine_url = marine_url.into();
+        self
+    }
+
+    /// Overrides the ensemble endpoint used when the selection includes confidence
+    pub fn with_ensemble_url(mut self, ensemble_url: impl Into<String>) -> Self {
+        self.ensemble_url = ensemble_url.into();
+        self
+    }
+
+    /// Overrides the air quality endpoint used when air quality is requested
+    pub fn with_air_quality_url(mut self, air_quality_url: impl Into<String>) -> Self {
+        self.air_quality_url = air_quality_url.into();
+        self
+    }
+
+    /// Geocoding search endpoint in use
+    pub fn geocoding_url(&self) -> &str {
+        &self.geocoding_url
+    }
+
+    /// Open-Meteo forecast endpoint in use
+    pub fn weather_url(&self) -> &str {
+        &self.weather_url
+    }
+
+    /// Open-Meteo marine endpoint in use
+    pub fn marine_url(&self) -> &str {
+        &self.marine_url
+    }
+
+    /// Open-Meteo ensemble endpoint in use
+    pub fn ensemble_url(&self) -> &str {
+        &self.ensemble_url
+    }
+
+    /// Open-Meteo air quality endpoint in use
+    pub fn air_quality_url(&self) -> &str {
+        &self.air_quality_url
+    }
+
+    /// Forecast variables requested
+    pub fn data_selection(&self) -> &DataSelection {
+        &self.data_selection
+    }
+
+    /// Time left before requests are sent again after a 429 or 503, if any
+    pub fn rate_limited_for(&self) -> Option<Duration> {
+        self.backoff.remaining()
+    }
+
+    /// Sends a GET request, mapping transport failures and error statuses to
+    /// `WeatherError`. A single attempt whatever the retry policy, for probes
+    /// that report every failure and its latency as they happen; it still
+    /// takes its turn at the host, `spacing` after the previous request.
+    pub(crate) fn send_single(
+        &self,
+        url: &str,
+        service: &str,
+        spacing: Duration,
+    ) -> Result<Response, WeatherError> {
+        let response = self.send_once(url, spacing, RequestRole::Primary)?;
+
+        if !response.status().is_success() {
+            return Err(Self::status_error(service, response));
+        }
+
+        Ok(response)
+    }
+
+    /// Sends a GET request, leaving the status for the caller to inspect.
+    /// Fails without sending during a rate-limit cooldown, and starts one on
+    /// 429 and 503 responses to a `Primary` request. Transient failures are
+    /// repeated as the retry policy allows, each attempt at least `spacing`
+    /// after the previous request to the host. A retry refused its turn
+    /// (`SpacingMode::Fail`) gives up with the failure it was to repeat.
+    fn send_unchecked(
+        &self,
+        url: &str,
+        spacing: Duration,
+        role: RequestRole,
+    ) -> Result<Response, WeatherError> {
+        let mut attempt = 1;
+        let mut result = self.send_once(url, spacing, role);
+        loop {
+            let transient = match &result {
+                Ok(response) => retry::is_transient_status(response.status()),
+                Err(e) => retry::is_transient_error(e),
+            };
+            if !transient || !self.retry_policy.allows_retry(attempt) {
+                return result;
+            }
+            let delay = self.retry_policy.delay(attempt);
+            if self.verbose {
+                eprintln!("Note: request failed, retrying in {delay:.1?}");
+            }
+            thread::sleep(delay);
+            attempt += 1;
+            match self.send_once(url, spacing, role) {
+                Err(WeatherError::Throttled { .. }) => return result,
+                retried => result = retried,
+            }
+        }
+    }
 
-        let url = Self::build_weather_api_url(lat, lon);
-        let weather: OpenMeteoWeather = self
+    /// One request, without retries, once its turn at the host has come
+    fn send_once(
+        &self,
+        url: &str,
+        spacing: Duration,
+        role: RequestRole,
+    ) -> Result<Response, WeatherError> {
+        self.backoff.check()?;
+        request_spacing::take_turn(
+            url,
+            spacing,
+            self.nominatim_spacing,
+            self.spacing_clock.as_ref(),
+        )?;
+        let response = self
             .client
-            .get(&url)
+            .get(url)
+            .header("User-Agent", self.user_agent.header())
             .send()
-            .map_err(|e| WeatherError::NetworkError(e.to_string()))?
-            .json()
-            .map_err(|e| WeatherError::ParseError(e.to_string()))?;
+            .map_err(WeatherError::network)?;
+        self.server_clock
+            .on_response(response.headers(), Utc::now());
+        match self
+            .backoff
+            .on_response(response.status(), response.headers(), role)
+        {
+            Some(rate_limited) => Err(rate_limited),
+            None => Ok(response),
+        }
+    }
 
-        let current_weather = Self::parse_current_weather(&weather.current);
-        let hourly_forecasts = Self::parse_hourly_forecasts(weather.hourly);
-        let daily_forecasts = Self::parse_daily_forecasts(weather.daily);
+    /// Status and body for `url`, from the response cache when an identical
+    /// request succeeded moments ago. Only successful bodies are kept, so
+    /// errors and rate limits are always seen as the server sends them.
+    pub(crate) fn get_text(&self, url: &str) -> Result<(StatusCode, String), WeatherError> {
+        self.get_spaced_text(url, Duration::ZERO, RequestRole::Primary)
+    }
 
-        let weather_data = WeatherData {
-            current: current_weather,
-            hourly: hourly_forecasts,
-            daily: daily_forecasts,
+    /// `get_text` for a request the fetch can do without, or one to another
+    /// provider's service: its 429 or 503 is reported but starts no cooldown
+    pub(crate) fn get_secondary_text(
+        &self,
+        url: &str,
+    ) -> Result<(StatusCode, String), WeatherError> {
+        self.get_spaced_text(url, Duration::ZERO, RequestRole::Secondary)
+    }
+
+    /// `get_text` whose requests, retries included, are each sent at least
+    /// `spacing` after the previous one to the host
+    fn get_spaced_text(
+        &self,
+        url: &str,
+        spacing: Duration,
+        role: RequestRole,
+    ) -> Result<(StatusCode, String), WeatherError> {
+        if let Some(body) = self.response_cache.as_ref().and_then(|c| c.get(url)) {
+            return Ok((StatusCode::OK, body));
+        }
+        let response = self.send_unchecked(url, spacing, role)?;
+        let status = response.status();
+        let body = response.text().map_err(WeatherError::body)?;
+        if let (Some(cache), true) = (&self.response_cache, status.is_success()) {
+            cache.insert(url, &body);
+        }
+        Ok((status, body))
+    }
+
+    /// `get_text` for a geocoding request, each attempt of which waits for
+    /// (or is refused) its turn at Nominatim
+    fn get_geocoding_text(
+        &self,
+        geocoder: GeocodingProvider,
+        url: &str,
+    ) -> Result<(StatusCode, String), WeatherError> {
+        let spacing = self.geocoding_spacing(geocoder, url);
+        self.get_spaced_text(url, spacing, RequestRole::Primary)
+    }
+
+    /// Least time between requests to `geocoder` at `url`; only Nominatim
+    /// asks for any
+    pub(crate) fn geocoding_spacing(&self, geocoder: GeocodingProvider, url: &str) -> Duration {
+        match geocoder {
+            GeocodingProvider::Nominatim => self.nominatim_interval(url),
+            GeocodingProvider::OpenMeteo => Duration::ZERO,
+        }
+    }
+
+    /// Builds an `ApiError` (or `ServerError`) from an error response, including Open-Meteo's reason if present
+    fn status_error(service: &str, response: Response) -> WeatherError {
+        let status = response.status();
+        let body = response.text().unwrap_or_default();
+        Self::api_status_error(service, status, &body)
+    }
+
+    /// Builds an `ApiError` (or `ServerError`) for an error status and its response body
+    pub(crate) fn api_status_error(service: &str, status: StatusCode, body: &str) -> WeatherError {
+        let message = match serde_json::from_str::<OpenMeteoErrorBody>(body) {
+            Ok(body) => format!("{service} API returned status: {status} ({})", body.reason),
+            Err(_) => format!("{service} API returned status: {status}"),
+        };
+        WeatherError::status(status, message)
+    }
+
+    /// Whether the forecast goes out as an hourly and a daily request: only
+    /// for horizons past the default, whose hourly detail no view uses
+    pub(crate) fn splits_forecast(selection: &DataSelection, options: &FetchOptions) -> bool {
+        options.forecast_days as usize > DAILY_FORECAST_DAYS
+            && !selection.hourly.is_empty()
+            && !selection.daily.is_empty()
+    }
+
+    /// Requests the forecast, split in two for long horizons
+    fn fetch_forecast(
+        &self,
+        lat: f64,
+        lon: f64,
+        timezone: Option<&str>,
+        options: &FetchOptions,
+    ) -> Result<ForecastResponse, WeatherError> {
+        if Self::splits_forecast(&self.data_selection, options) {
+            if let Some(split) = self.fetch_split_forecast(lat, lon, timezone, options)? {
+                return Ok(split);
+            }
+        }
+        self.fetch_single_forecast(lat, lon, timezone, options)
+    }
+
+    /// Requests current and hourly data for `hourly_hours` alongside the
+    /// daily series, and merges them. `None` when the server rejects the
+    /// granular parameters, leaving the legacy fallback to the single request.
+    fn fetch_split_forecast(
+        &self,
+        lat: f64,
+        lon: f64,
+        timezone: Option<&str>,
+        options: &FetchOptions,
+    ) -> Result<Option<ForecastResponse>, WeatherError> {
+        let (hourly_url, daily_url) = Self::build_split_weather_api_urls(
+            &self.weather_url,
+            lat,
+            lon,
+            &self.data_selection,
+            timezone,
+            options,
+            self.hourly_hours,
+        );
+        let (hourly, daily) = thread::scope(|scope| {
+            let daily = scope.spawn(|| self.fetch_forecast_part(&daily_url));
+            let hourly = self.fetch_forecast_part(&hourly_url);
+            (hourly, joined(daily))
+        });
+        let (Some(hourly), Some(daily)) = (hourly?, daily?) else {
+            return Ok(None);
         };
 
-        Ok(WeatherInfo::new(
-            location.to_string(),
+        let weather = Self::merge_split_forecast(
+            Self::parse_forecast_body(&hourly, self.verbose)?,
+            Self::parse_forecast_body(&daily, self.verbose)?,
+        );
+        Ok(Some(ForecastResponse {
+            weather,
+            reduced_data: false,
+            strategy: FetchStrategy::Split,
+            bytes: hourly.len() + daily.len(),
+        }))
+    }
+
+    /// Body of one part of a split forecast, or `None` for a 400 response
+    fn fetch_forecast_part(&self, url: &str) -> Result<Option<String>, WeatherError> {
+        let (status, body) = self.get_text(url)?;
+        if status == StatusCode::BAD_REQUEST {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            return Err(Self::api_status_error("Weather", status, &body));
+        }
+        Ok(Some(body))
+    }
+
+    /// One forecast from the two halves of a split request: everything but
+    /// the daily series comes from the hourly part, which starts at local
+    /// midnight of the first day so that day's means see all its hours
+    pub(crate) fn merge_split_forecast(
+        mut hourly: OpenMeteoWeather,
+        daily: OpenMeteoWeather,
+    ) -> OpenMeteoWeather {
+        let first_day = daily.daily.as_ref().and_then(|d| d.time.first());
+        if let (Some(series), Some(first_day)) = (hourly.hourly.as_mut(), first_day) {
+            series.drop_before(first_day);
+        }
+        OpenMeteoWeather {
+            elevation: hourly.elevation.or(daily.elevation),
+            utc_offset_seconds: hourly.utc_offset_seconds.or(daily.utc_offset_seconds),
+            timezone: hourly.timezone.or(daily.timezone),
+            timezone_abbreviation: hourly.timezone_abbreviation.or(daily.timezone_abbreviation),
+            daily: daily.daily,
+            ..hourly
+        }
+    }
+
+    /// Requests the forecast, retrying once with the legacy `current_weather=true`
+    /// form when the server rejects the granular parameters. A pinned model
+    /// has no legacy form, so its rejection is reported with the server's reason.
+    fn fetch_single_forecast(
+        &self,
+        lat: f64,
+        lon: f64,
+        timezone: Option<&str>,
+        options: &FetchOptions,
+    ) -> Result<ForecastResponse, WeatherError> {
+        let url = Self::build_weather_api_url(
+            &self.weather_url,
             lat,
             lon,
-            weather_data,
-        ))
+            &self.data_selection,
+            timezone,
+            options,
+        );
+        let (status, body) = self.get_text(&url)?;
+
+        let (body, reduced_data) = if status == StatusCode::BAD_REQUEST
+            && !options.model.is_pinned()
+        {
+            let legacy_url =
+                Self::build_legacy_weather_api_url(&self.weather_url, lat, lon, timezone, options);
+            let (status, body) = self.get_text(&legacy_url)?;
+            if !status.is_success() {
+                return Err(Self::api_status_error("Weather", status, &body));
+            }
+            (body, true)
+        } else if !status.is_success() {
+            return Err(Self::api_status_error("Weather", status, &body));
+        } else {
+            (body, false)
+        };
+
+        Ok(ForecastResponse {
+            weather: Self::parse_forecast_body(&body, self.verbose)?,
+            reduced_data,
+            strategy: FetchStrategy::Single,
+            bytes: body.len(),
+        })
+    }
+
+    /// Waves for the point, or `None` inland. Marine data is supplementary, so
+    /// other failures are only reported in verbose mode.
+    fn fetch_marine(&self, lat: f64, lon: f64, timezone: Option<&str>) -> Option<MarineForecast> {
+        let url = marine::marine_url(&self.marine_url, lat, lon, timezone);
+        let result = self
+            .get_secondary_text(&url)
+            .and_then(|(status, body)| marine::parse_marine_response(status, &body));
+        Self::marine_or_note(result, self.verbose)
+    }
+
+    /// UTC offset in seconds and time zone name at a point, as Open-Meteo
+    /// resolves them for `timezone` (the point's own zone without one); for
+    /// providers whose times are UTC. Asks for no variables, so the answer
+    /// is only the metadata.
+    pub(crate) fn utc_offset_at(
+        &self,
+        lat: f64,
+        lon: f64,
+        timezone: Option<&str>,
+    ) -> Result<(i32, Option<String>), WeatherError> {
+        let url = format!(
+            "{}?latitude={lat}&longitude={lon}&forecast_days=1&timezone={}",
+            self.weather_url,
+            timezone.map_or("auto".into(), urlencoding::encode)
+        );
+        let (status, body) = self.get_secondary_text(&url)?;
+        if !status.is_success() {
+            return Err(Self::api_status_error("Weather", status, &body));
+        }
+        let weather = Self::parse_forecast_body(&body, false)?;
+        let offset = weather.utc_offset_seconds.ok_or_else(|| {
+            WeatherError::ParseError("no utc_offset_seconds in the response".to_string())
+        })?;
+        Ok((offset, weather.timezone))
+    }
+
+    /// Drops a marine failure, noting it on stderr when `verbose`
+    pub(crate) fn marine_or_note(
+        result: Result<Option<MarineForecast>, WeatherError>,
+        verbose: bool,
+    ) -> Option<MarineForecast> {
+        result.unwrap_or_else(|e| {
+            if verbose {
+                eprintln!("Note: marine forecast unavailable: {e}");
+            }
+            None
+        })
+    }
+
+    /// Current and hourly air quality at a point, in the point's time zone.
+    /// `Ok(None)` when the API has no values there.
+    pub fn fetch_air_quality(
+        &self,
+        lat: f64,
+        lon: f64,
+    ) -> Result<Option<AirQuality>, WeatherError> {
+        self.air_quality_at(lat, lon, None)
+    }
+
+    fn air_quality_at(
+        &self,
+        lat: f64,
+        lon: f64,
+        timezone: Option<&str>,
+    ) -> Result<Option<AirQuality>, WeatherError> {
+        let url = air_quality::air_quality_url(&self.air_quality_url, lat, lon, timezone);
+        self.get_secondary_text(&url)
+            .and_then(|(status, body)| air_quality::parse_air_quality_response(status, &body))
+    }
+
+    /// Drops an air quality failure, noting it on stderr when `verbose`
+    pub(crate) fn air_quality_or_note(
+        result: Result<Option<AirQuality>, WeatherError>,
+        verbose: bool,
+    ) -> Option<AirQuality> {
+        result.unwrap_or_else(|e| {
+            if verbose {
+                eprintln!("Note: air quality unavailable: {e}");
+            }
+            None
+        })
+    }
+
+    /// Confidence per date from the ensemble, or `None` when it could not be
+    /// fetched. Like marine data it is supplementary.
+    fn fetch_confidence(
+        &self,
+        lat: f64,
+        lon: f64,
+        timezone: Option<&str>,
+        options: &FetchOptions,
+    ) -> Option<Vec<(String, Confidence)>> {
+        let url = ensemble::ensemble_url(
+            &self.ensemble_url,
+            lat,
+            lon,
+            timezone,
+            options.forecast_days as usize,
+        );
+        let result = self
+            .get_secondary_text(&url)
+            .and_then(|(status, body)| ensemble::parse_ensemble_response(status, &body));
+        Self::confidence_or_note(result, self.verbose)
+    }
+
+    /// Drops an ensemble failure, noting it on stderr when `verbose`
+    pub(crate) fn confidence_or_note(
+        result: Result<Vec<(String, Confidence)>, WeatherError>,
+        verbose: bool,
+    ) -> Option<Vec<(String, Confidence)>> {
+        result
+            .inspect_err(|e| {
+                if verbose {
+                    eprintln!("Note: forecast confidence unavailable: {e}");
+                }
+            })
+            .ok()
+    }
+
+    /// Weather for a location from an Open-Meteo forecast response body, e.g.
+    /// one saved to disk. Values are cleaned up as for a live fetch.
+    pub fn parse_weather_info(
+        location: &str,
+        latitude: f64,
+        longitude: f64,
+        body: &str,
+    ) -> Result<WeatherInfo, WeatherError> {
+        let weather = Self::parse_forecast_body(body, false)?;
+        Self::build_weather_info(location, latitude, longitude, weather, false, false)
+    }
+
+    /// Parses a forecast response body, reading bare `NaN` and `Infinity`
+    /// tokens as missing values; `verbose` reports how many there were
+    pub(crate) fn parse_forecast_body(
+        body: &str,
+        verbose: bool,
+    ) -> Result<OpenMeteoWeather, WeatherError> {
+        let (json, replaced) = sanitize::non_finite_to_null(body);
+        if verbose && replaced > 0 {
+            eprintln!("Note: {replaced} non-finite values in the forecast read as missing");
+        }
+        serde_json::from_str(&json).map_err(|e| WeatherError::ParseError(e.to_string()))
+    }
+
+    /// Coordinates of `location`, from the geocoding cache when it has them
+    fn fetch_coordinates(&self, location: &str) -> Result<GeocodeMatch, WeatherError> {
+        let Some(cache) = &self.geocode_cache else {
+            return self.lookup(location);
+        };
+        if let Some(found) = cache.get(self.geocoder, location, Utc::now()) {
+            return Ok(found);
+        }
+        let found = self.lookup(location)?;
+        self.remember(cache, location, &found);
+        Ok(found)
+    }
+
+    /// Stores a match in the geocoding cache; failing to write only costs
+    /// the next run a request
+    fn remember(&self, cache: &GeocodeCache, location: &str, found: &GeocodeMatch) {
+        if let Err(e) = cache.insert(self.geocoder, location, found, Utc::now()) {
+            if self.verbose {
+                eprintln!("Note: geocoding result not cached: {e}");
+            }
+        }
+    }
+
+    /// Places the geocoder finds for `query`, best first, so an ambiguous
+    /// name like "Springfield" can be settled by the user. Never cached.
+    pub fn geocode(&self, query: &str) -> Result<Vec<GeocodeResult>, WeatherError> {
+        let query = query.trim();
+        self.with_geocoders(query, |geocoder, base_url| {
+            let url =
+                geocoder.candidates_url(base_url, query, &self.user_agent, GEOCODE_CANDIDATES);
+            geocoder.parse_candidates(self.get_geocoding_json(geocoder, &url)?, query)
+        })
+    }
+
+    /// Weather at a place chosen from `geocode`, named by its label
+    pub fn fetch_weather_at(&self, place: &GeocodeResult) -> Result<WeatherInfo, WeatherError> {
+        let found = GeocodeMatch {
+            latitude: place.latitude,
+            longitude: place.longitude,
+            timezone: place.timezone.clone(),
+            name: Some(place.display_name.clone()),
+            skipped: Vec::new(),
+        };
+        self.fetch_weather_for(
+            &place.label(),
+            found,
+            None,
+            Instant::now(),
+            &self.default_options(),
+        )
+    }
+
+    /// Weather for `location` with `options` in place of the repository's
+    /// forecast settings; invalid options fail before any request is sent
+    pub fn fetch_weather_with_options(
+        &self,
+        location: &str,
+        options: &FetchOptions,
+    ) -> Result<WeatherInfo, WeatherError> {
+        options.validate()?;
+        let started = Instant::now();
+        self.fetch_located(location, |found, name| {
+            // Only a search holds up the forecast; a name for coordinates does not
+            let geocoding = (coords::parse(location).is_none()).then(|| started.elapsed());
+            self.fetch_weather_for(name, found, geocoding, started, options)
+        })
+    }
+
+    /// Weather for `location` with its air quality, whatever the data
+    /// selection says. A failed air quality request leaves `air_quality`
+    /// empty rather than failing the fetch.
+    pub fn fetch_weather_full(&self, location: &str) -> Result<WeatherInfo, WeatherError> {
+        self.fetch_weather_with_options(location, &self.default_options().with_air_quality(true))
+    }
+
+    /// Runs `fetch` at the point for `location` with the name to show for
+    /// it, and records the query on the result. Pasted coordinates need no
+    /// search, only a name for the place: that lookup runs alongside `fetch`,
+    /// which meanwhile names the place by its coordinates.
+    pub(crate) fn fetch_located(
+        &self,
+        location: &str,
+        fetch: impl FnOnce(GeocodeMatch, &str) -> Result<WeatherInfo, WeatherError>,
+    ) -> Result<WeatherInfo, WeatherError> {
+        let mut info = match coords::parse(location) {
+            Some(coordinates) => thread::scope(|scope| {
+                let name = scope.spawn(|| self.coordinates_name(coordinates));
+                let info = fetch(GeocodeMatch::at(coordinates), &coordinates.to_string());
+                let name = joined(name);
+                info.map(|info| WeatherInfo {
+                    location: name,
+                    ..info
+                })
+            })?,
+            None => {
+                let found = self.fetch_coordinates(location)?;
+                let name = found.resolved_name(location);
+                fetch(found, &name)?
+            }
+        };
+        info.query = Some(location.trim().to_string());
+        Ok(info)
+    }
+
+    /// Options `fetch_weather` uses: the configured forecast days and model,
+    /// and air quality when the data selection asks for it
+    fn default_options(&self) -> FetchOptions {
+        FetchOptions::new()
+            .with_forecast_days(self.forecast_days as u8)
+            .with_air_quality(self.data_selection.air_quality)
+            .with_model(self.model)
+    }
+
+    /// Asks the geocoder for the coordinates of `location`
+    fn lookup(&self, location: &str) -> Result<GeocodeMatch, WeatherError> {
+        let location = location.trim();
+        self.with_geocoders(location, |geocoder, base_url| {
+            let url = geocoder.search_url(base_url, location, &self.user_agent);
+            let found = geocoder.parse(self.get_geocoding_json(geocoder, &url)?, location)?;
+            found.log_skipped(self.verbose);
+            Ok(found)
+        })
+    }
+
+    /// Runs `search` with the geocoder and, when that fails or finds nothing,
+    /// with the fallback geocoder; the error names every geocoder tried
+    fn with_geocoders<T>(
+        &self,
+        location: &str,
+        search: impl Fn(GeocodingProvider, &str) -> Result<T, WeatherError>,
+    ) -> Result<T, WeatherError> {
+        let mut failures = Vec::new();
+        for (geocoder, base_url) in self.geocoders() {
+            match search(geocoder, base_url) {
+                Ok(found) => return Ok(found),
+                Err(e) => {
+                    if self.verbose {
+                        eprintln!("Note: {} geocoding failed: {e}", geocoder.label());
+                    }
+                    failures.push((geocoder, e));
+                }
+            }
+        }
+        Err(geocoding::combine_failures(location, failures))
+    }
+
+    /// Geocoders to try in order, with their search endpoints
+    pub(crate) fn geocoders(&self) -> Vec<(GeocodingProvider, &str)> {
+        let mut geocoders = vec![(self.geocoder, self.geocoding_url.as_str())];
+        geocoders.extend(self.fallback_geocoder());
+        geocoders
+    }
+
+    /// JSON body of a successful geocoding search
+    fn get_geocoding_json(
+        &self,
+        geocoder: GeocodingProvider,
+        url: &str,
+    ) -> Result<Value, WeatherError> {
+        let (status, body) = self.get_geocoding_text(geocoder, url)?;
+        if !status.is_success() {
+            return Err(Self::api_status_error("Geocoding", status, &body));
+        }
+        serde_json::from_str(&body).map_err(|e| WeatherError::ParseError(e.to_string()))
+    }
+
+    /// Short name of the place at `latitude`, `longitude` from Nominatim's
+    /// reverse geocoding, e.g. "Shinjuku, Japan". `None` when nothing is
+    /// there (open sea) or the geocoder cannot reverse geocode; coordinates
+    /// out of range are `LocationNotFound`.
+    pub fn reverse_geocode(
+        &self,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<Option<String>, WeatherError> {
+        let coordinates = Coordinates::new(latitude, longitude)
+            .ok_or_else(|| WeatherError::location_not_found(format!("{latitude}, {longitude}")))?;
+        self.reverse_geocode_at(coordinates)
+    }
+
+    /// Name for coordinates given instead of a place: the reverse geocoded
+    /// one, or the formatted coordinates when there is none or the lookup
+    /// failed, since the forecast does not depend on it
+    fn coordinates_name(&self, coordinates: Coordinates) -> String {
+        match self.reverse_geocode_at(coordinates) {
+            Ok(Some(name)) => name,
+            Ok(None) => coordinates.to_string(),
+            Err(e) => {
+                if self.verbose {
+                    eprintln!("Note: no place name for {coordinates}: {e}");
+                }
+                coordinates.to_string()
+            }
+        }
+    }
+
+    fn reverse_geocode_at(&self, coordinates: Coordinates) -> Result<Option<String>, WeatherError> {
+        let Some(url) =
+            self.geocoder
+                .reverse_url(&self.geocoding_url, coordinates, &self.user_agent)
+        else {
+            return Ok(None);
+        };
+        // Only a name: its refusal must not hold back the forecast
+        let spacing = self.geocoding_spacing(self.geocoder, &url);
+        let (status, body) = self.get_spaced_text(&url, spacing, RequestRole::Secondary)?;
+        if !status.is_success() {
+            return Err(Self::api_status_error("Reverse geocoding", status, &body));
+        }
+        let json: Value =
+            serde_json::from_str(&body).map_err(|e| WeatherError::ParseError(e.to_string()))?;
+        Ok(geocoding::reverse_name(&json).map(geocoding::short_name))
+    }
+
+    /// Coordinates of the first result in a Nominatim search response whose
+    /// `lat`/`lon` parse, skipping malformed entries
+    pub(crate) fn parse_coordinates(
+        json: &Value,
+        location: &str,
+    ) -> Result<GeocodeMatch, WeatherError> {
+        let array = json.as_array().ok_or_else(|| {
+            WeatherError::ParseError("Invalid response format from geocoding API".to_string())
+        })?;
+
+        if array.is_empty() {
+            return Err(WeatherError::location_not_found(location));
+        }
+
+        let mut skipped = Vec::new();
+        for (index, candidate) in array.iter().enumerate() {
+            let coordinates =
+                Self::parse_candidate_coordinate(candidate, "lat", 90.0).and_then(|lat| {
+                    Self::parse_candidate_coordinate(candidate, "lon", 180.0).map(|lon| (lat, lon))
+                });
+            match coordinates {
+                Ok((latitude, longitude)) => {
+                    return Ok(GeocodeMatch {
+                        latitude,
+                        longitude,
+                        timezone: None,
+                        name: candidate["display_name"].as_str().map(str::to_string),
+                        skipped,
+                    })
+                }
+                Err(reason) => skipped.push(format!("result {}: {reason}", index + 1)),
+            }
+        }
+
+        Err(WeatherError::ParseError(format!(
+            "None of the {} geocoding results for '{location}' had valid coordinates (first: {})",
+            skipped.len(),
+            skipped[0]
+        )))
+    }
+
+    /// Parses a string coordinate field, rejecting values outside ±`limit`
+    pub(crate) fn parse_candidate_coordinate(
+        candidate: &Value,
+        field: &str,
+        limit: f64,
+    ) -> Result<f64, String> {
+        let raw = candidate[field]
+            .as_str()
+            .ok_or_else(|| format!("missing {field}"))?;
+        match raw.trim().parse::<f64>() {
+            Ok(value) if value.is_finite() && value.abs() <= limit => Ok(value),
+            _ => Err(format!("invalid {field} \"{raw}\"")),
+        }
+    }
+
+    /// Assembles the domain model from a forecast response. Non-finite and
+    /// out-of-range values are dropped; `verbose` reports each one on stderr.
+    pub(crate) fn build_weather_info(
+        location: &str,
+        lat: f64,
+        lon: f64,
+        weather: OpenMeteoWeather,
+        reduced_data: bool,
+        verbose: bool,
+    ) -> Result<WeatherInfo, WeatherError> {
+        let current_weather = match (&weather.current, &weather.current_weather) {
+            (Some(current), _) => Self::parse_current_weather(current),
+            (None, Some(legacy)) => Self::parse_legacy_current_weather(legacy),
+            (None, None) => {
+                return Err(WeatherError::ParseError(
+                    "Weather response is missing current conditions".to_string(),
+                ))
+            }
+        };
+        let today = current_weather.time.as_deref().and_then(|t| t.get(..10));
+        let hourly_forecasts = Self::parse_hourly_forecasts(weather.hourly, today);
+        let daily_forecasts = Self::parse_daily_forecasts(weather.daily);
+        let minutely_forecasts = Self::parse_minutely_forecasts(weather.minutely_15);
+
+        let mut weather_data = WeatherData {
+            current: current_weather,
+            hourly: hourly_forecasts,
+            daily: daily_forecasts,
+            minutely: minutely_forecasts,
+        };
+        Self::prepare_weather_data(&mut weather_data, verbose);
+
+        let mut weather_info = WeatherInfo::new(location.to_string(), lat, lon, weather_data);
+        weather_info.elevation = weather.elevation;
+        weather_info.reduced_data = reduced_data;
+        weather_info.generation_time_ms = weather.generationtime_ms;
+        weather_info.utc_offset_seconds = weather.utc_offset_seconds;
+        weather_info.timezone = weather.timezone;
+        weather_info.timezone_abbreviation = weather.timezone_abbreviation;
+        weather_info.fetched_at = Some(Utc::now());
+
+        Ok(weather_info)
+    }
+
+    /// Drops non-finite and out-of-range values, then derives what the views
+    /// expect beyond the raw series: clock changes, daily means and chances,
+    /// and which entries are past. Shared by every provider.
+    pub(crate) fn prepare_weather_data(weather_data: &mut WeatherData, verbose: bool) {
+        let dropped = sanitize::sanitize(weather_data);
+        if verbose {
+            for note in &dropped {
+                eprintln!("Note: {note}");
+            }
+        }
+        weather_data.mark_clock_changes();
+        weather_data.fill_humidity_means();
+        weather_data.fill_precipitation_chances();
+        weather_data.mark_past();
+    }
+
+    /// Maps API response to domain model
+    fn parse_current_weather(current: &OpenMeteoCurrent) -> CurrentWeather {
+        CurrentWeather {
+            time: current.time.clone(),
+            temperature: current.temperature_2m,
+            apparent_temperature: current.apparent_temperature,
+            humidity: current.relative_humidity_2m,
+            dew_point: current.dew_point_2m,
+            precipitation: current.precipitation,
+            snowfall: current.snowfall,
+            weather_code: current.weather_code,
+            wind_speed: current.wind_speed_10m,
+            wind_direction: current.wind_direction_10m,
+            wind_gusts: current.wind_gusts_10m,
+            cloud_cover: current.cloud_cover,
+            pressure: current.surface_pressure,
+            pressure_msl: current.pressure_msl,
+            visibility: current.visibility,
+            snow_depth: current.snow_depth,
+            is_day: current.is_day.map(|d| d == 1),
+        }
+    }
+
+    /// Maps the legacy `current_weather` block; fields it lacks stay `None`
+    fn parse_legacy_current_weather(current: &OpenMeteoLegacyCurrent) -> CurrentWeather {
+        CurrentWeather {
+            time: current.time.clone(),
+            temperature: current.temperature,
+            weather_code: current.weathercode,
+            wind_speed: current.windspeed,
+            wind_direction: current.winddirection,
+            is_day: current.is_day.map(|d| d == 1),
+            ..Default::default()
+        }
+    }
+
+    /// Parses hourly forecast data, keeping `HOURLY_FORECAST_LIMIT` entries
+    /// from the start of `today` (the observation date) on, after every
+    /// earlier entry asked for with `past_days`
+    fn parse_hourly_forecasts(
+        hourly: Option<OpenMeteoHourly>,
+        today: Option<&str>,
+    ) -> Vec<HourlyForecast> {
+        if let Some(hourly) = hourly {
+            // ISO timestamps order like the times they name
+            let earlier = today.map_or(0, |today| {
+                hourly.time.partition_point(|t| t.as_str() < today)
+            });
+            let count = hourly.time.len().min(earlier + HOURLY_FORECAST_LIMIT);
+            (0..count)
+                .map(|i| HourlyForecast {
+                    time: hourly.time[i].clone(),
+                    temperature: hourly.temperature_2m.get(i).and_then(|v| *v),
+                    apparent_temperature: hourly.apparent_temperature.get(i).and_then(|v| *v),
+                    precipitation_probability: hourly
+                        .precipitation_probability
+                        .get(i)
+                        .and_then(|v| *v),
+                    precipitation: hourly.precipitation.get(i).and_then(|v| *v),
+                    weather_code: hourly.weather_code.get(i).and_then(|v| *v),
+                    wind_speed: hourly.wind_speed_10m.get(i).and_then(|v| *v),
+                    wind_direction: hourly.wind_direction_10m.get(i).and_then(|v| *v),
+                    wind_gusts: hourly.wind_gusts_10m.get(i).and_then(|v| *v),
+                    wind_speed_80m: hourly.wind_speed_80m.get(i).and_then(|v| *v),
+                    wind_direction_80m: hourly.wind_direction_80m.get(i).and_then(|v| *v),
+                    wind_speed_120m: hourly.wind_speed_120m.get(i).and_then(|v| *v),
+                    wind_direction_120m: hourly.wind_direction_120m.get(i).and_then(|v| *v),
+                    humidity: hourly.relative_humidity_2m.get(i).and_then(|v| *v),
+                    dew_point: hourly.dew_point_2m.get(i).and_then(|v| *v),
+                    freezing_level_height: hourly.freezing_level_height.get(i).and_then(|v| *v),
+                    cape: hourly.cape.get(i).and_then(|v| *v),
+                    snowfall: hourly.snowfall.get(i).and_then(|v| *v),
+                    snow_depth: hourly.snow_depth.get(i).and_then(|v| *v),
+                    cloud_cover: hourly.cloud_cover.get(i).and_then(|v| *v),
+                    visibility: hourly.visibility.get(i).and_then(|v| *v),
+                    uv_index: hourly.uv_index.get(i).and_then(|v| *v),
+                    clock_change: None,
+                    // Known once the current observation is
+                    is_past: false,
+                })
+                .collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Parses the 15-minute forecast, present only when it was requested
+    fn parse_minutely_forecasts(minutely: Option<OpenMeteoMinutely>) -> Vec<MinutelyForecast> {
+        let Some(minutely) = minutely else {
+            return Vec::new();
+        };
+        minutely
+            .time
+            .iter()
+            .enumerate()
+            .map(|(i, time)| MinutelyForecast {
+                temperature: minutely.temperature_2m.get(i).and_then(|v| *v),
+                precipitation: minutely.precipitation.get(i).and_then(|v| *v),
+                weather_code: minutely.weather_code.get(i).and_then(|v| *v),
+                ..MinutelyForecast::new(time.as_str())
+            })
+            .collect()
+    }
+
+    /// Parses daily forecast data from API response
+    fn parse_daily_forecasts(daily: Option<OpenMeteoDaily>) -> Vec<DailyForecast> {
+        if let Some(daily) = daily {
+            (0..daily.time.len())
+                .map(|i| DailyForecast {
+                    date: daily.time[i].clone(),
+                    temperature_max: daily.temperature_2m_max.get(i).and_then(|v| *v),
+                    temperature_min: daily.temperature_2m_min.get(i).and_then(|v| *v),
+                    weather_code: daily.weather_code.get(i).and_then(|v| *v),
+                    precipitation_sum: daily.precipitation_sum.get(i).and_then(|v| *v),
+                    snowfall_sum: daily.snowfall_sum.get(i).and_then(|v| *v),
+                    precipitation_probability: daily
+                        .precipitation_probability_max
+                        .get(i)
+                        .and_then(|v| *v),
+                    // Derived from the hourly series once it is parsed
+                    precipitation_probability_mean: None,
+                    wet_hours: None,
+                    wind_speed_max: daily.wind_speed_10m_max.get(i).and_then(|v| *v),
+                    wind_gusts_max: daily.wind_gusts_10m_max.get(i).and_then(|v| *v),
+                    sunrise: daily.sunrise.get(i).and_then(|v| v.clone()),
+                    sunset: daily.sunset.get(i).and_then(|v| v.clone()),
+                    daylight_duration: daily.daylight_duration.get(i).and_then(|v| *v),
+                    sunshine_duration: daily.sunshine_duration.get(i).and_then(|v| *v),
+                    et0_evapotranspiration: daily
+                        .et0_fao_evapotranspiration
+                        .get(i)
+                        .and_then(|v| *v),
+                    humidity_mean: daily.relative_humidity_2m_mean.get(i).and_then(|v| *v),
+                    uv_index_max: daily.uv_index_max.get(i).and_then(|v| *v),
+                    // From the ensemble, when requested
+                    confidence: None,
+                    is_past: false,
+                })
+                .collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    // Constructs the Open-Meteo API URL with query parameters; a time zone known
+    // from geocoding saves the server resolving `timezone=auto` from coordinates
+    pub(crate) fn build_weather_api_url(
+        base_url: &str,
+        lat: f64,
+        lon: f64,
+        selection: &DataSelection,
+        timezone: Option<&str>,
+        options: &FetchOptions,
+    ) -> String {
+        format!(
+            "{base_url}?latitude={lat}&longitude={lon}{}&forecast_days={}{}{}&timezone={}",
+            selection.query_params(),
+            options.forecast_days,
+            options.past_days_param(),
+            options.models_param(),
+            timezone.map_or("auto".into(), urlencoding::encode)
+        )
+    }
+
+    // Constructs the two URLs of a split forecast: current and hourly data
+    // for the next `hourly_hours`, and the daily series for `forecast_days`.
+    // The hourly part counts back in hours from now, as `forecast_hours`
+    // counts ahead: a day more than the past days reaches local midnight of
+    // the first day whatever the hour, and the merge drops the rest.
+    pub(crate) fn build_split_weather_api_urls(
+        base_url: &str,
+        lat: f64,
+        lon: f64,
+        selection: &DataSelection,
+        timezone: Option<&str>,
+        options: &FetchOptions,
+        hourly_hours: usize,
+    ) -> (String, String) {
+        let past_hours = (options.past_days as usize + 1) * 24;
+        let timezone = timezone.map_or("auto".into(), urlencoding::encode);
+        let models = options.models_param();
+        let hourly = DataSelection {
+            daily: Vec::new(),
+            ..selection.clone()
+        };
+        let daily = DataSelection {
+            current: Vec::new(),
+            hourly: Vec::new(),
+            minutely: Vec::new(),
+            ..selection.clone()
+        };
+        (
+            format!(
+                "{base_url}?latitude={lat}&longitude={lon}{}&forecast_hours={hourly_hours}&past_hours={past_hours}{models}&timezone={timezone}",
+                hourly.query_params()
+            ),
+            format!(
+                "{base_url}?latitude={lat}&longitude={lon}{}&forecast_days={}{}{models}&timezone={timezone}",
+                daily.query_params(),
+                options.forecast_days,
+                options.past_days_param()
+            ),
+        )
+    }
+
+    // Constructs the legacy Open-Meteo URL understood by older deployments,
+    // keeping the horizon, past days and time zone of the granular request
+    pub(crate) fn build_legacy_weather_api_url(
+        base_url: &str,
+        lat: f64,
+        lon: f64,
+        timezone: Option<&str>,
+        options: &FetchOptions,
+    ) -> String {
+        format!(
+            "{base_url}?latitude={lat}&longitude={lon}&current_weather=true&hourly=temperature_2m,apparent_temperature,precipitation_probability,precipitation,weather_code,wind_speed_10m,relative_humidity_2m&daily=temperature_2m_max,temperature_2m_min,weather_code,precipitation_sum,precipitation_probability_max,wind_speed_10m_max,sunrise,sunset&forecast_days={}{}&timezone={}",
+            options.forecast_days,
+            options.past_days_param(),
+            timezone.map_or("auto".into(), urlencoding::encode)
+        )
+    }
+
+    /// Forecast and supplementary data at a geocoded point, named `name`;
+    /// `geocoding` is how long finding the point took, `started` when the
+    /// fetch began
+    fn fetch_weather_for(
+        &self,
+        name: &str,
+        found: GeocodeMatch,
+        geocoding: Option<Duration>,
+        started: Instant,
+        options: &FetchOptions,
+    ) -> Result<WeatherInfo, WeatherError> {
+        let (lat, lon) = (found.latitude, found.longitude);
+        let timezone = found.timezone.as_deref();
+
+        // Secondary requests only need the coordinates, so they run alongside the forecast
+        let ((forecast, forecast_time), marine, confidence, air) = thread::scope(|scope| {
+            let marine = self
+                .data_selection
+                .marine
+                .then(|| scope.spawn(|| timed(|| self.fetch_marine(lat, lon, timezone))));
+            let confidence = self.data_selection.confidence.then(|| {
+                scope.spawn(|| timed(|| self.fetch_confidence(lat, lon, timezone, options)))
+            });
+            let air = options.air_quality.then(|| {
+                scope.spawn(|| {
+                    timed(|| {
+                        Self::air_quality_or_note(
+                            self.air_quality_at(lat, lon, timezone),
+                            self.verbose,
+                        )
+                    })
+                })
+            });
+            let forecast = timed(|| self.fetch_forecast(lat, lon, timezone, options));
+            (
+                forecast,
+                marine.map(joined),
+                confidence.map(joined),
+                air.map(joined),
+            )
+        });
+        let forecast = forecast?;
+        let (marine, marine_time) =
+            marine.map_or((None, None), |(marine, elapsed)| (marine, Some(elapsed)));
+        let (confidence, confidence_time) = confidence
+            .map_or((None, None), |(confidence, elapsed)| {
+                (confidence, Some(elapsed))
+            });
+        let (air, air_time) = air.map_or((None, None), |(air, elapsed)| (air, Some(elapsed)));
+        let mut info = Self::build_weather_info(
+            name,
+            lat,
+            lon,
+            forecast.weather,
+            forecast.reduced_data,
+            self.verbose,
+        )?;
+        info.marine = marine;
+        info.air_quality = air;
+        info.model = options.model;
+        if let Some(confidence) = confidence {
+            ensemble::apply_confidence(&mut info.weather_data.daily, &confidence);
+        }
+        info.clock_skew = self.server_clock.skew();
+        info.timings = Some(
+            FetchTimings::new(geocoding, forecast_time, marine_time, started.elapsed())
+                .with_confidence(confidence_time)
+                .with_air_quality(air_time)
+                .with_forecast_payload(forecast.strategy, forecast.bytes),
+        );
+        Ok(info)
+    }
+}
+
+// Implement the WeatherRepository trait for ApiWeatherRepository
+impl WeatherRepository for ApiWeatherRepository {
+    // Fetches weather information for a given location
+    fn fetch_weather(&self, location: &str) -> Result<WeatherInfo, WeatherError> {
+        self.fetch_weather_with_options(location, &self.default_options())
+    }
+
+    fn response_cache_hits(&self) -> usize {
+        self.response_cache.as_ref().map_or(0, ResponseCache::hits)
+    }
+
+    fn clear_response_cache(&self) {
+        if let Some(cache) = &self.response_cache {
+            cache.clear();
+        }
+    }
+
+    fn resolve_place(
+        &self,
+        location: &str,
+        recorded: Option<&ResolvedPlace>,
+    ) -> Result<Option<ResolvedPlace>, WeatherError> {
+        if let Some(coordinates) = coords::parse(location) {
+            let name = self.reverse_geocode_at(coordinates)?;
+            return Ok(name.map(|name| {
+                ResolvedPlace::new(name, coordinates.latitude, coordinates.longitude)
+            }));
+        }
+        // A refresh asks the geocoder, and keeps the cache up to date unless
+        // the answer moved too far to take without asking
+        match self.lookup(location) {
+            Ok(found) => {
+                let place = ResolvedPlace::new(
+                    found.resolved_name(location),
+                    found.latitude,
+                    found.longitude,
+                );
+                let moved =
+                    recorded.is_some_and(|recorded| moved_too_far(recorded, &place).is_some());
+                if let (Some(cache), false) = (&self.geocode_cache, moved) {
+                    self.remember(cache, location, &found);
+                }
+                Ok(Some(place))
+            }
+            Err(WeatherError::LocationNotFound { .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Runs `f`, returning its result and how long it took
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let started = Instant::now();
+    let result = f();
+    (result, started.elapsed())
+}
+
+/// What a scoped thread returned; a panic there is raised again here, as it
+/// would have been had the work run on this thread
+fn joined<T>(handle: thread::ScopedJoinHandle<'_, T>) -> T {
+    handle
+        .join()
+        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::OPEN_METEO_GEOCODING_API_URL;
+    use crate::test_support::{MockRoute, MockServer, SimulatedClock};
+
+    fn sample_response() -> OpenMeteoWeather {
+        serde_json::from_value(serde_json::json!({
+            "elevation": 1820.0,
+            "utc_offset_seconds": 3600,
+            "current": {
+                "time": "2024-01-15T10:15",
+                "temperature_2m": -2.5,
+                "weather_code": 71,
+                "snow_depth": 0.45
+            },
+            "hourly": {
+                "time": ["2024-01-15T09:00", "2024-01-15T10:00"],
+                "temperature_2m": [-3.0, -2.4],
+                "apparent_temperature": [-7.0, -6.1],
+                "precipitation_probability": [40.0, 55.0],
+                "precipitation": [0.2, 0.6],
+                "weather_code": [71, 73],
+                "wind_speed_10m": [12.0, 14.5],
+                "relative_humidity_2m": [88.0, 90.0],
+                "freezing_level_height": [1650.0, null],
+                "cape": [420.0, 2650.0],
+                "snowfall": [0.7, null],
+                "snow_depth": [0.44, 0.45]
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_freezing_level_height() {
+        let weather = sample_response();
+        let hourly = ApiWeatherRepository::parse_hourly_forecasts(weather.hourly, None);
+
+        assert_eq!(hourly[0].freezing_level_height, Some(1650.0));
+        assert_eq!(hourly[1].freezing_level_height, None);
+    }
+
+    #[test]
+    fn test_parse_hourly_without_freezing_level_array() {
+        let hourly: OpenMeteoHourly = serde_json::from_value(serde_json::json!({
+            "time": ["2024-01-15T09:00"],
+            "temperature_2m": [1.0],
+            "apparent_temperature": [0.0],
+            "precipitation_probability": [0.0],
+            "precipitation": [0.0],
+            "weather_code": [0],
+            "wind_speed_10m": [5.0],
+            "relative_humidity_2m": [70.0]
+        }))
+        .unwrap();
+
+        let parsed = ApiWeatherRepository::parse_hourly_forecasts(Some(hourly), None);
+        assert_eq!(parsed[0].freezing_level_height, None);
+        assert_eq!(parsed[0].cape, None);
+        assert_eq!(parsed[0].uv_index, None);
+    }
+
+    #[test]
+    fn test_parse_uv_index() {
+        assert!(DataSelection::full().hourly.contains(&"uv_index"));
+        assert!(DataSelection::full().daily.contains(&"uv_index_max"));
+        let weather: OpenMeteoWeather = serde_json::from_value(serde_json::json!({
+            "current": {"time": "2024-06-01T13:15", "temperature_2m": 24.0},
+            "hourly": {
+                "time": ["2024-06-01T12:00", "2024-06-01T13:00", "2024-06-01T14:00"],
+                "uv_index": [6.85, null, 31.0]
+            },
+            "daily": {"time": ["2024-06-01", "2024-06-02"], "uv_index_max": [7.1, null]}
+        }))
+        .unwrap();
+        let info =
+            ApiWeatherRepository::build_weather_info("Madrid", 40.4, -3.7, weather, false, false)
+                .unwrap();
+        let data = &info.weather_data;
+        assert_eq!(data.hourly[0].uv_index, Some(6.85));
+        assert_eq!(data.hourly[1].uv_index, None);
+        // No UV index reaches 31; dropped as a bad value
+        assert_eq!(data.hourly[2].uv_index, None);
+        assert_eq!(data.daily[0].uv_index_max, Some(7.1));
+        assert_eq!(data.daily[1].uv_index_max, None);
+    }
+
+    #[test]
+    fn test_parse_hourly_cloud_cover_and_visibility() {
+        assert!(DataSelection::full().hourly.contains(&"cloud_cover"));
+        assert!(DataSelection::full().hourly.contains(&"visibility"));
+        // Arrays shorter than `time` leave the later hours empty
+        let hourly: OpenMeteoHourly = serde_json::from_value(serde_json::json!({
+            "time": ["2024-08-12T22:00", "2024-08-12T23:00", "2024-08-13T00:00"],
+            "cloud_cover": [5, null],
+            "visibility": [24140.0]
+        }))
+        .unwrap();
+        let parsed = ApiWeatherRepository::parse_hourly_forecasts(Some(hourly), None);
+        assert_eq!(parsed.len(), 3);
+        assert_eq!(parsed[0].cloud_cover, Some(5.0));
+        assert_eq!(parsed[0].visibility, Some(24140.0));
+        assert_eq!(parsed[1].cloud_cover, None);
+        assert_eq!(parsed[1].visibility, None);
+        assert_eq!(parsed[2].cloud_cover, None);
+        assert_eq!(parsed[2].visibility, None);
+    }
+
+    #[test]
+    fn test_parse_minutely_forecast() {
+        let weather: OpenMeteoWeather = serde_json::from_value(serde_json::json!({
+            "current": {"time": "2024-06-01T14:00", "temperature_2m": 18.2},
+            "minutely_15": {
+                "time": ["2024-06-01T14:00", "2024-06-01T14:15", "2024-06-01T14:30"],
+                "temperature_2m": [18.2, null, 17.6],
+                "precipitation": [0.0, 0.4, -1.0],
+                "weather_code": [3, 80, 180]
+            }
+        }))
+        .unwrap();
+        let info =
+            ApiWeatherRepository::build_weather_info("Leeds", 53.8, -1.55, weather, false, false)
+                .unwrap();
+        let minutely = &info.weather_data.minutely;
+        assert_eq!(minutely.len(), 3);
+        assert_eq!(minutely[1].time, "2024-06-01T14:15");
+        assert_eq!(minutely[0].temperature, Some(18.2));
+        assert_eq!(minutely[1].temperature, None);
+        assert_eq!(minutely[1].precipitation, Some(0.4));
+        assert_eq!(minutely[1].weather_code, Some(80));
+        // Impossible values are dropped like hourly ones
+        assert_eq!(minutely[2].precipitation, None);
+        assert_eq!(minutely[2].weather_code, None);
+
+        // Not requested, not there
+        let weather: OpenMeteoWeather = serde_json::from_value(serde_json::json!({
+            "current": {"time": "2024-06-01T14:00"}
+        }))
+        .unwrap();
+        let info =
+            ApiWeatherRepository::build_weather_info("Leeds", 53.8, -1.55, weather, false, false)
+                .unwrap();
+        assert!(info.weather_data.minutely.is_empty());
+    }
+
+    #[test]
+    fn test_parse_surface_and_sea_level_pressure() {
+        let selection = DataSelection::full();
+        assert!(selection.current.contains(&"surface_pressure"));
+        assert!(selection.current.contains(&"pressure_msl"));
+        let weather: OpenMeteoWeather = serde_json::from_value(serde_json::json!({
+            "current": {
+                "time": "2024-01-10T09:00",
+                "surface_pressure": 845.2,
+                "pressure_msl": 1019.6
+            }
+        }))
+        .unwrap();
+        let info =
+            ApiWeatherRepository::build_weather_info("Davos", 46.8, 9.84, weather, false, false)
+                .unwrap();
+        let current = &info.weather_data.current;
+        assert_eq!(current.pressure, Some(845.2));
+        assert_eq!(current.pressure_msl, Some(1019.6));
+    }
+
+    #[test]
+    fn test_parse_wind_gusts() {
+        let selection = DataSelection::full();
+        assert!(selection.current.contains(&"wind_gusts_10m"));
+        assert!(selection.hourly.contains(&"wind_gusts_10m"));
+        assert!(selection.daily.contains(&"wind_gusts_10m_max"));
+        let weather: OpenMeteoWeather = serde_json::from_value(serde_json::json!({
+            "current": {"time": "2024-01-10T09:00", "wind_speed_10m": 21.0, "wind_gusts_10m": 58.3},
+            "hourly": {
+                "time": ["2024-01-10T09:00", "2024-01-10T10:00", "2024-01-10T11:00"],
+                "wind_speed_10m": [21.0, 24.0, 20.0],
+                "wind_gusts_10m": [58.3, null, -4.0]
+            },
+            "daily": {
+                "time": ["2024-01-10", "2024-01-11"],
+                "wind_speed_10m_max": [30.0, 18.0],
+                "wind_gusts_10m_max": [72.4, null]
+            }
+        }))
+        .unwrap();
+        let info =
+            ApiWeatherRepository::build_weather_info("Brest", 48.4, -4.5, weather, false, false)
+                .unwrap();
+        let data = &info.weather_data;
+        assert_eq!(data.current.wind_gusts, Some(58.3));
+        assert_eq!(data.hourly[0].wind_gusts, Some(58.3));
+        assert_eq!(data.hourly[1].wind_gusts, None);
+        assert_eq!(data.hourly[2].wind_gusts, None);
+        assert_eq!(data.daily[0].wind_gusts_max, Some(72.4));
+        assert_eq!(data.daily[1].wind_gusts_max, None);
+
+        // Servers without gusts leave them out
+        let weather: OpenMeteoWeather = serde_json::from_value(serde_json::json!({
+            "current": {"time": "2024-01-10T09:00", "wind_speed_10m": 21.0},
+            "hourly": {"time": ["2024-01-10T09:00"], "wind_speed_10m": [21.0]},
+            "daily": {"time": ["2024-01-10"], "wind_speed_10m_max": [30.0]}
+        }))
+        .unwrap();
+        let info =
+            ApiWeatherRepository::build_weather_info("Brest", 48.4, -4.5, weather, false, false)
+                .unwrap();
+        let data = &info.weather_data;
+        assert_eq!(data.current.wind_gusts, None);
+        assert_eq!(data.hourly[0].wind_gusts, None);
+        assert_eq!(data.daily[0].wind_gusts_max, None);
+    }
+
+    #[test]
+    fn test_parse_hourly_wind_direction_without_winds_aloft() {
+        assert!(DataSelection::full().hourly.contains(&"wind_direction_10m"));
+
+        let hourly: OpenMeteoHourly = serde_json::from_value(serde_json::json!({
+            "time": ["2024-01-15T09:00", "2024-01-15T10:00"],
+            "wind_speed_10m": [5.0, 12.0],
+            "wind_direction_10m": [315.0, null]
+        }))
+        .unwrap();
+
+        let parsed = ApiWeatherRepository::parse_hourly_forecasts(Some(hourly), None);
+        assert_eq!(parsed[0].wind_direction, Some(315.0));
+        assert_eq!(parsed[1].wind_direction, None);
+        assert_eq!(parsed[0].wind_direction_80m, None);
+    }
+
+    #[test]
+    fn test_parse_cape() {
+        let weather = sample_response();
+        let hourly = ApiWeatherRepository::parse_hourly_forecasts(weather.hourly, None);
+
+        assert_eq!(hourly[0].cape, Some(420.0));
+        assert_eq!(hourly[1].cape, Some(2650.0));
+    }
+
+    #[test]
+    fn test_build_weather_info_keeps_utc_offset() {
+        let info = ApiWeatherRepository::build_weather_info(
+            "Zermatt",
+            46.0,
+            7.7,
+            sample_response(),
+            false,
+            false,
+        )
+        .unwrap();
+        assert_eq!(info.utc_offset_seconds, Some(3600));
+        let clock = info.clock(Utc::now()).unwrap();
+        assert_eq!(clock.utc_offset_seconds(), 3600);
+    }
+
+    #[test]
+    fn test_parse_keeps_time_zone_and_local_times() {
+        let body = serde_json::json!({
+            "utc_offset_seconds": 32400,
+            "timezone": "Asia/Tokyo",
+            "timezone_abbreviation": "JST",
+            "current": {"time": "2025-01-15T21:00", "temperature_2m": 4.0},
+            "hourly": {"time": ["2025-01-15T21:00", "2025-01-15T22:00"],
+                       "temperature_2m": [4.0, 3.5]}
+        })
+        .to_string();
+        let info =
+            ApiWeatherRepository::parse_weather_info("Tokyo, Japan", 35.68, 139.69, &body).unwrap();
+        assert_eq!(info.timezone.as_deref(), Some("Asia/Tokyo"));
+        assert_eq!(info.location_label(), "Tokyo, Japan (JST)");
+        // Times are kept as the server gave them, local to Tokyo
+        assert_eq!(info.weather_data.hourly[1].time, "2025-01-15T22:00");
+        assert_eq!(
+            info.weather_data.current.time.as_deref(),
+            Some("2025-01-15T21:00")
+        );
+
+        let body = serde_json::json!({"current": {"temperature_2m": 4.0}}).to_string();
+        let info = ApiWeatherRepository::parse_weather_info("Tokyo", 35.68, 139.69, &body).unwrap();
+        assert_eq!(info.location_label(), "Tokyo");
+    }
+
+    #[test]
+    fn test_parse_winds_aloft() {
+        let hourly: OpenMeteoHourly = serde_json::from_value(serde_json::json!({
+            "time": ["2024-06-01T12:00", "2024-06-01T13:00"],
+            "wind_speed_10m": [12.0, 14.0],
+            "wind_direction_10m": [200.0, 210.0],
+            "wind_speed_80m": [24.5, null],
+            "wind_direction_80m": [215.0, 220.0],
+            "wind_speed_120m": [31.0, 33.0],
+            "wind_direction_120m": [220.0, 225.0]
+        }))
+        .unwrap();
+
+        let parsed = ApiWeatherRepository::parse_hourly_forecasts(Some(hourly), None);
+        assert_eq!(parsed[0].wind_direction, Some(200.0));
+        assert_eq!(parsed[0].wind_speed_80m, Some(24.5));
+        assert_eq!(parsed[0].wind_direction_120m, Some(220.0));
+        assert_eq!(parsed[1].wind_speed_80m, None);
+        assert_eq!(parsed[1].wind_speed_120m, Some(33.0));
+    }
+
+    #[test]
+    fn test_parse_daily_evapotranspiration() {
+        let daily: OpenMeteoDaily = serde_json::from_value(serde_json::json!({
+            "time": ["2024-06-01", "2024-06-02"],
+            "temperature_2m_max": [24.0, 26.0],
+            "temperature_2m_min": [12.0, 14.0],
+            "weather_code": [1, 61],
+            "precipitation_sum": [0.0, 4.2],
+            "precipitation_probability_max": [5.0, 80.0],
+            "wind_speed_10m_max": [15.0, 22.0],
+            "sunrise": ["2024-06-01T05:12", "2024-06-02T05:11"],
+            "sunset": ["2024-06-01T21:02", "2024-06-02T21:03"],
+            "et0_fao_evapotranspiration": [4.8, null]
+        }))
+        .unwrap();
+
+        let parsed = ApiWeatherRepository::parse_daily_forecasts(Some(daily));
+        assert_eq!(parsed[0].et0_evapotranspiration, Some(4.8));
+        assert_eq!(parsed[1].et0_evapotranspiration, None);
+    }
+
+    #[test]
+    fn test_parse_snow_depth_and_snowfall() {
+        let weather = sample_response();
+        let current =
+            ApiWeatherRepository::parse_current_weather(weather.current.as_ref().unwrap());
+        let hourly = ApiWeatherRepository::parse_hourly_forecasts(weather.hourly, None);
+
+        assert_eq!(current.snow_depth, Some(0.45));
+        assert_eq!(hourly[0].snowfall, Some(0.7));
+        assert_eq!(hourly[1].snowfall, None);
+        assert_eq!(hourly[1].snow_depth, Some(0.45));
+    }
+
+    #[test]
+    fn test_current_hour_matches_observation_time() {
+        let weather = sample_response();
+        let data = WeatherData {
+            current: ApiWeatherRepository::parse_current_weather(weather.current.as_ref().unwrap()),
+            hourly: ApiWeatherRepository::parse_hourly_forecasts(weather.hourly, None),
+            daily: Vec::new(),
+            minutely: Vec::new(),
+        };
+
+        let hour = data.current_hour().unwrap();
+        assert_eq!(hour.time, "2024-01-15T10:00");
+        assert_eq!(weather.elevation, Some(1820.0));
+    }
+
+    #[test]
+    fn test_contact_goes_in_the_header_and_nominatim_query() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/search").json(serde_json::json!([{"lat": "46.02", "lon": "7.75"}])),
+            MockRoute::new("/v1/forecast").json(serde_json::json!({
+                "current": {"temperature_2m": -1.0}
+            })),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+            .with_user_agent(UserAgent::new(None, Some("me@example.com")));
+
+        repository.fetch_weather("Zermatt").unwrap();
+        let requests = server.requests();
+        assert!(requests[0].ends_with("&limit=1&email=me%40example.com"));
+        assert!(!requests[1].contains("email"));
+        assert_eq!(
+            server.user_agents(),
+            vec!["RustWeatherApp/1.0 (mailto:me@example.com)"; 2]
+        );
+    }
+
+    fn zermatt_search() -> MockServer {
+        MockServer::start(vec![MockRoute::new("/search").json(serde_json::json!([
+            {"lat": "46.02", "lon": "7.75", "display_name": "Zermatt, Switzerland"}
+        ]))])
+    }
+
+    #[test]
+    fn test_nominatim_requests_are_spaced_across_threads() {
+        let server = zermatt_search();
+        let clock = Arc::new(SimulatedClock::default());
+        let interval = Duration::from_secs(1);
+        let repository = || {
+            ApiWeatherRepository::new()
+                .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+                .with_nominatim_interval(interval)
+                .with_spacing_clock(clock.clone())
+        };
+
+        let shared = repository();
+        thread::scope(|scope| {
+            // Separate repositories share the spacing with each other
+            for place in ["Zermatt", "Täsch"] {
+                scope.spawn(move || repository().geocode(place).unwrap());
+            }
+            scope.spawn(|| shared.geocode("Randa").unwrap());
+        });
+        shared.geocode("Visp").unwrap();
+        assert_eq!(server.requests_to("/search").len(), 4);
+        assert_eq!(
+            clock.waits(),
+            [Duration::ZERO, interval, interval, interval]
+        );
+        assert_eq!(clock.elapsed(), interval * 3);
+
+        // Open-Meteo has no such policy
+        let open_meteo = repository().with_geocoder(GeocodingProvider::OpenMeteo);
+        assert_eq!(
+            open_meteo.geocoding_spacing(GeocodingProvider::OpenMeteo, &server.url("/search")),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn test_nominatim_spacing_can_refuse_instead_of_waiting() {
+        let server = zermatt_search();
+        let interval = Duration::from_secs(30);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+            .with_spacing_clock(Arc::new(SimulatedClock::default()))
+            .with_nominatim_interval(interval)
+            .with_nominatim_spacing(SpacingMode::Fail);
+
+        repository.geocode("Zermatt").unwrap();
+        let error = repository.geocode("Täsch").unwrap_err();
+        assert!(
+            matches!(error, WeatherError::Throttled { retry_after } if retry_after == interval),
+            "{error:?}"
+        );
+        // The response cache answers without a request, so without a turn
+        repository.geocode("Zermatt").unwrap();
+        assert_eq!(server.requests_to("/search").len(), 1);
+    }
+
+    #[test]
+    fn test_nominatim_retries_wait_for_their_turn() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/search").status(502).times(1),
+            MockRoute::new("/search").json(serde_json::json!([
+                {"lat": "46.02", "lon": "7.75", "display_name": "Zermatt, Switzerland"}
+            ])),
+        ]);
+        let clock = Arc::new(SimulatedClock::default());
+        let interval = Duration::from_secs(1);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+            .with_retry_policy(RetryPolicy::new(2, Duration::from_millis(10)))
+            .with_nominatim_interval(interval)
+            .with_spacing_clock(clock.clone());
+
+        repository.geocode("Zermatt").unwrap();
+        assert_eq!(server.requests_to("/search").len(), 2);
+        assert_eq!(clock.waits(), [Duration::ZERO, interval]);
+    }
+
+    #[test]
+    fn test_refused_retry_reports_the_failure_it_repeats() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/search").status(502).times(1),
+            MockRoute::new("/search").json(serde_json::json!([
+                {"lat": "46.02", "lon": "7.75", "display_name": "Zermatt, Switzerland"}
+            ])),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+            .with_spacing_clock(Arc::new(SimulatedClock::default()))
+            .with_retry_policy(RetryPolicy::new(2, Duration::from_millis(10)))
+            .with_nominatim_interval(Duration::from_secs(30))
+            .with_nominatim_spacing(SpacingMode::Fail);
+
+        // The retry is refused its turn; the 502 is what went wrong
+        let error = repository.geocode("Zermatt").unwrap_err();
+        assert!(matches!(error, WeatherError::ServerError(_)), "{error:?}");
+        assert_eq!(server.requests_to("/search").len(), 1);
+    }
+
+    #[test]
+    fn test_only_the_public_nominatim_is_spaced_by_default() {
+        let repository = ApiWeatherRepository::new();
+        assert_eq!(
+            repository.nominatim_interval("https://nominatim.openstreetmap.org/reverse?lat=1"),
+            NOMINATIM_MIN_INTERVAL
+        );
+        assert_eq!(
+            repository.nominatim_interval("http://localhost:8080/search"),
+            Duration::ZERO
+        );
+        let repository = repository.with_nominatim_interval(Duration::from_millis(500));
+        assert_eq!(
+            repository.nominatim_interval("http://localhost:8080/search"),
+            Duration::from_millis(500)
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_cools_down_every_request() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/search").json(serde_json::json!([{"lat": "46.02", "lon": "7.75"}])),
+            MockRoute::new("/v1/forecast")
+                .status(429)
+                .header("Retry-After", "120"),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"));
+        assert_eq!(repository.rate_limited_for(), None);
+
+        let error = repository.fetch_weather("46.02, 7.75").unwrap_err();
+        assert!(
+            matches!(error, WeatherError::RateLimited { retry_after } if retry_after == Duration::from_secs(120)),
+            "{error}"
+        );
+        assert_eq!(server.requests_to("/v1/forecast").len(), 1);
+        let hits = server.hits();
+
+        // Geocoding goes through the same cooldown and never reaches the server
+        let error = repository.fetch_weather("Zermatt").unwrap_err();
+        assert!(matches!(error, WeatherError::RateLimited { .. }));
+        assert_eq!(server.hits(), hits);
+        assert!(repository.rate_limited_for().unwrap() > Duration::from_secs(100));
+    }
+
+    #[test]
+    fn test_unavailable_without_retry_after_uses_default_cooldown() {
+        let server = MockServer::start(vec![MockRoute::new("/v1/forecast").status(503)]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"));
+
+        let error = repository.fetch_weather("46.02, 7.75").unwrap_err();
+        assert!(matches!(
+            error,
+            WeatherError::RateLimited { retry_after } if retry_after == crate::constants::RATE_LIMIT_COOLDOWN
+        ));
+        assert!(repository.fetch_weather("46.02, 7.75").is_err());
+        assert_eq!(server.requests_to("/v1/forecast").len(), 1);
+    }
+
+    /// Geocoding, forecast and marine each answering after `delay`
+    fn slow_server(delay: Duration) -> MockServer {
+        MockServer::start(vec![
+            MockRoute::new("/search")
+                .json(serde_json::json!([{"lat": "-33.87", "lon": "151.21"}]))
+                .delay(delay),
+            MockRoute::new("/v1/forecast")
+                .json(serde_json::json!({
+                    "current": {"time": "2025-12-10T10:00", "temperature_2m": 24.0}
+                }))
+                .delay(delay),
+            MockRoute::new("/v1/marine")
+                .json(serde_json::json!({"hourly": {
+                    "time": ["2025-12-10T10:00"], "wave_height": [1.4]
+                }}))
+                .delay(delay),
+        ])
+    }
+
+    #[test]
+    fn test_secondary_requests_run_alongside_the_forecast() {
+        let delay = Duration::from_millis(300);
+        let server = slow_server(delay);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+            .with_marine_url(server.url("/v1/marine"))
+            .with_data_selection(DataSelection::full().with_marine())
+            .with_response_cache(false);
+
+        // Coordinates skip the search (the reverse lookup finds no route):
+        // max(forecast, marine), not their sum
+        let info = repository.fetch_weather("-33.87, 151.21").unwrap();
+        let timings = info.timings.unwrap();
+        assert!(info.marine.is_some());
+        assert_eq!(timings.geocoding, None);
+        assert!(timings.forecast >= delay && timings.marine.unwrap() >= delay);
+        assert!(timings.total < delay * 2 - delay / 3, "{timings}");
+
+        // Names add geocoding in front: about two delays rather than three
+        let timings = repository.fetch_weather("Sydney").unwrap().timings.unwrap();
+        assert!(timings.geocoding.unwrap() >= delay);
+        assert!(timings.total >= delay * 2);
+        assert!(timings.total < delay * 3 - delay / 3, "{timings}");
+    }
+
+    #[test]
+    fn test_resolve_place_names_queries_and_coordinates() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/search").json(serde_json::json!([{
+                "lat": "46.0207",
+                "lon": "7.7491",
+                "display_name": "Zermatt, Visp, Valais, Switzerland"
+            }])),
+            MockRoute::new("/reverse").json(serde_json::json!({"display_name": "Oslo, Norway"})),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"));
+
+        assert_eq!(
+            repository.resolve_place("zermat", None).unwrap(),
+            Some(ResolvedPlace::new("Zermatt, Switzerland", 46.0207, 7.7491))
+        );
+        // Coordinates keep their position and only gain a name
+        assert_eq!(
+            repository.resolve_place("59.91, 10.75", None).unwrap(),
+            Some(ResolvedPlace::new("Oslo, Norway", 59.91, 10.75))
+        );
+        assert!(server
+            .requests()
+            .iter()
+            .any(|r| r.contains("/reverse?lat=59.91")));
+
+        // Nothing found is no error; a failing geocoder is
+        let empty = MockServer::start(vec![
+            MockRoute::new("/search").json(serde_json::json!([])),
+            MockRoute::new("/reverse").json(serde_json::json!({"error": "Unable to geocode"})),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(empty.url("/search"), empty.url("/v1/forecast"));
+        assert_eq!(repository.resolve_place("Atlantis", None).unwrap(), None);
+        assert_eq!(repository.resolve_place("0, -30", None).unwrap(), None);
+        let failing = MockServer::start(vec![MockRoute::new("/search").status(500)]);
+        assert!(ApiWeatherRepository::new()
+            .with_endpoints(failing.url("/search"), failing.url("/v1/forecast"))
+            .resolve_place("Bern", None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_confidence_request_is_optional_and_failure_tolerant() {
+        let forecast = MockRoute::new("/v1/forecast").json(serde_json::json!({
+            "current": {"time": "2025-12-10T10:00", "temperature_2m": 8.0},
+            "daily": {"time": ["2025-12-10", "2025-12-11"], "temperature_2m_max": [8.0, 9.0]}
+        }));
+        let members: serde_json::Map<String, serde_json::Value> = (0..5)
+            .map(|m| {
+                let value = serde_json::json!([8.0 + m as f64 * 2.0]);
+                (format!("temperature_2m_max_member{m:02}"), value)
+            })
+            .chain([("time".to_string(), serde_json::json!(["2025-12-11"]))])
+            .collect();
+        let server = MockServer::start(vec![
+            forecast.clone(),
+            MockRoute::new("/v1/ensemble").json(serde_json::json!({ "daily": members })),
+        ]);
+        let repository = |server: &MockServer, selection: DataSelection| {
+            ApiWeatherRepository::new()
+                .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+                .with_ensemble_url(server.url("/v1/ensemble"))
+                .with_data_selection(selection)
+        };
+
+        let info = repository(&server, DataSelection::full().with_confidence())
+            .fetch_weather("51.5, -0.13")
+            .unwrap();
+        let confidence: Vec<_> = info
+            .weather_data
+            .daily
+            .iter()
+            .map(|d| d.confidence)
+            .collect();
+        assert_eq!(confidence, [None, Some(Confidence::Low)]);
+        assert!(info.timings.unwrap().confidence.is_some());
+
+        // Not requested unless selected
+        let info = repository(&server, DataSelection::full())
+            .fetch_weather("51.5, -0.13")
+            .unwrap();
+        assert!(info
+            .weather_data
+            .daily
+            .iter()
+            .all(|d| d.confidence.is_none()));
+        assert_eq!(
+            server
+                .requests()
+                .iter()
+                .filter(|r| r.contains("ensemble"))
+                .count(),
+            1
+        );
+
+        // A failing ensemble leaves the forecast intact
+        let failing = MockServer::start(vec![forecast, MockRoute::new("/v1/ensemble").status(502)]);
+        let info = repository(&failing, DataSelection::full().with_confidence())
+            .fetch_weather("51.5, -0.13")
+            .unwrap();
+        assert_eq!(info.weather_data.daily.len(), 2);
+        assert!(info
+            .weather_data
+            .daily
+            .iter()
+            .all(|d| d.confidence.is_none()));
+    }
+
+    #[test]
+    fn test_unresponsive_server_times_out() {
+        // Connections complete in the backlog but are never read or answered
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(format!("{url}/search"), format!("{url}/v1/forecast"))
+            .with_timeouts(Duration::from_secs(1), Duration::from_millis(200))
+            .with_retry_policy(RetryPolicy::none());
+        assert_eq!(
+            repository.timeouts(),
+            (Duration::from_secs(1), Duration::from_millis(200))
+        );
+
+        let started = Instant::now();
+        let error = repository.fetch_weather("51.5, -0.13").unwrap_err();
+        assert!(matches!(error, WeatherError::Timeout(_)), "{error:?}");
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(error.to_string().starts_with("Timed out"), "{error}");
+
+        // A slow answer inside the timeout is still taken
+        let server = MockServer::start(vec![MockRoute::new("/v1/forecast")
+            .json(serde_json::json!({"current": {"temperature_2m": 8.0}}))
+            .delay(Duration::from_millis(100))]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+            .with_timeouts(Duration::from_secs(1), Duration::from_secs(2));
+        assert!(repository.fetch_weather("51.5, -0.13").is_ok());
+        drop(listener);
+    }
+
+    #[test]
+    fn test_geocode_candidates_and_fetch_at_choice() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/search").json(serde_json::json!([
+                {"lat": "39.80", "lon": "-89.64", "addresstype": "city",
+                 "display_name": "Springfield, Sangamon County, Illinois, United States",
+                 "address": {"state": "Illinois", "country": "United States"}},
+                {"lat": "42.10", "lon": "-72.59", "addresstype": "city",
+                 "display_name": "Springfield, Hampden County, Massachusetts, United States",
+                 "address": {"state": "Massachusetts", "country": "United States"}}
+            ])),
+            forecast_route(),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+            .with_response_cache(false);
+
+        let candidates = repository.geocode("Springfield").unwrap();
+        assert_eq!(candidates.len(), 2);
+        assert!(server.requests()[0].contains("limit=5"));
+        // The state tells the two apart
+        assert_eq!(
+            candidates[0].label(),
+            "Springfield, Illinois, United States"
+        );
+        assert_eq!(
+            candidates[1].label(),
+            "Springfield, Massachusetts, United States"
+        );
+
+        let info = repository.fetch_weather_at(&candidates[1]).unwrap();
+        assert_eq!((info.latitude, info.longitude), (42.10, -72.59));
+        assert_eq!(info.location, "Springfield, Massachusetts, United States");
+        assert!(!server.requests()[1].starts_with("/search"));
+
+        // A plain fetch still takes the first hit, named as the geocoder has it
+        let info = repository.fetch_weather(" Springfield").unwrap();
+        assert_eq!((info.latitude, info.longitude), (39.80, -89.64));
+        assert_eq!(info.location, "Springfield, United States");
+        assert_eq!(info.query.as_deref(), Some("Springfield"));
+
+        let nothing =
+            MockServer::start(vec![MockRoute::new("/search").json(serde_json::json!([]))]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(nothing.url("/search"), nothing.url("/v1/forecast"));
+        assert!(matches!(
+            repository.geocode("Xyzzy"),
+            Err(WeatherError::LocationNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_fallback_geocoder_when_the_first_fails() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/osm/search?q=Berlin").status(403).times(1),
+            MockRoute::new("/osm/search").json(serde_json::json!([])),
+            MockRoute::new("/geo/search?name=Berlin").json(serde_json::json!({"results": [
+                {"name": "Berlin", "latitude": 52.52, "longitude": 13.41, "country": "Germany",
+                 "timezone": "Europe/Berlin"}
+            ]})),
+            MockRoute::new("/geo/search").status(500),
+            forecast_route(),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/osm/search"), server.url("/v1/forecast"))
+            .with_fallback_geocoder(Some(GeocodingProvider::OpenMeteo))
+            .with_fallback_geocoding_url(server.url("/geo/search"))
+            .with_retry_policy(RetryPolicy::none())
+            .with_response_cache(false);
+
+        // Nominatim refuses, then finds nothing: Open-Meteo answers both times
+        for _ in 0..2 {
+            let info = repository.fetch_weather("Berlin").unwrap();
+            assert_eq!((info.latitude, info.longitude), (52.52, 13.41));
+            assert_eq!(info.location, "Berlin, Germany");
+        }
+        assert_eq!(server.requests_to("/geo/search").len(), 2);
+
+        // Both fail: the error names both
+        let error = repository.fetch_weather("Xyzzy").unwrap_err();
+        assert!(matches!(error, WeatherError::ServerError(_)), "{error:?}");
+        let message = error.to_string();
+        assert!(
+            message.contains("Nominatim (Location 'Xyzzy' not found)")
+                && message.contains("Open-Meteo (Server error: "),
+            "{message}"
+        );
+
+        // Without a fallback only Nominatim is asked
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/osm/search"), server.url("/v1/forecast"));
+        assert!(matches!(
+            repository.fetch_weather("Xyzzy"),
+            Err(WeatherError::LocationNotFound { .. })
+        ));
+        assert_eq!(server.requests_to("/geo/search").len(), 3);
+    }
+
+    #[test]
+    fn test_fallback_geocoder_is_on_by_default() {
+        let repository = ApiWeatherRepository::new();
+        assert_eq!(
+            repository.fallback_geocoder(),
+            Some((GeocodingProvider::OpenMeteo, OPEN_METEO_GEOCODING_API_URL))
+        );
+        let repository = repository.with_geocoder(GeocodingProvider::OpenMeteo);
+        assert_eq!(
+            repository.fallback_geocoder(),
+            Some((GeocodingProvider::Nominatim, NOMINATIM_API_URL))
+        );
+        // A server of one's own is not backed by a public one, unless asked
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints("http://osm.local/search", OPEN_METEO_API_URL);
+        assert_eq!(repository.fallback_geocoder(), None);
+        assert_eq!(
+            repository
+                .with_fallback_geocoding_url("http://geo.local/search")
+                .fallback_geocoder(),
+            Some((GeocodingProvider::OpenMeteo, "http://geo.local/search"))
+        );
+        assert_eq!(
+            ApiWeatherRepository::new()
+                .with_fallback_geocoder(None)
+                .fallback_geocoder(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_not_found_names_the_geocoders_searched() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/osm/search").json(serde_json::json!([])),
+            MockRoute::new("/geo/search").json(serde_json::json!({"generationtime_ms": 0.5})),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/osm/search"), server.url("/v1/forecast"))
+            .with_fallback_geocoding_url(server.url("/geo/search"))
+            .with_retry_policy(RetryPolicy::none());
+        let error = repository.fetch_weather("Xyzzy").unwrap_err();
+        assert!(matches!(error, WeatherError::LocationNotFound { .. }));
+        assert_eq!(
+            error.to_string(),
+            "Location 'Xyzzy' not found by Nominatim or Open-Meteo"
+        );
+    }
+
+    #[test]
+    fn test_geocode_cache_skips_repeat_lookups() {
+        let dir =
+            std::env::temp_dir().join(format!("weather-app-geocode-repo-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let server = MockServer::start(vec![
+            MockRoute::new("/search").json(serde_json::json!([
+                {"lat": "52.52", "lon": "13.40", "display_name": "Berlin, Deutschland"}
+            ])),
+            forecast_route(),
+        ]);
+        let repository = || {
+            ApiWeatherRepository::new()
+                .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+                .with_response_cache(false)
+                .with_geocode_cache(Some(GeocodeCache::new(dir.join("geocode_cache.json"))))
+        };
+        let geocoding_requests = || {
+            server
+                .requests()
+                .iter()
+                .filter(|path| path.starts_with("/search"))
+                .count()
+        };
+
+        let info = repository().fetch_weather("Berlin").unwrap();
+        assert_eq!((info.latitude, info.longitude), (52.52, 13.40));
+        // A later run with the same cache file
+        let info = repository().fetch_weather(" berlin").unwrap();
+        assert_eq!((info.latitude, info.longitude), (52.52, 13.40));
+        assert_eq!(geocoding_requests(), 1);
+
+        // Name refreshes always ask the geocoder
+        repository().resolve_place("Berlin", None).unwrap();
+        assert_eq!(geocoding_requests(), 2);
+
+        repository().geocode_cache().unwrap().clear().unwrap();
+        repository().fetch_weather("Berlin").unwrap();
+        assert_eq!(geocoding_requests(), 3);
+
+        // An answer far from the recorded place waits for the user, so it
+        // must not be what the next fetch finds in the cache either
+        repository().geocode_cache().unwrap().clear().unwrap();
+        let paris = ResolvedPlace::new("Paris, France", 48.86, 2.35);
+        let moved = repository().resolve_place("Berlin", Some(&paris)).unwrap();
+        assert!(moved.is_some());
+        repository().fetch_weather("Berlin").unwrap();
+        assert_eq!(geocoding_requests(), 5);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    fn forecast_route() -> MockRoute {
+        MockRoute::new("/v1/forecast").json(serde_json::json!({"current": {"temperature_2m": 8.0}}))
+    }
+
+    #[test]
+    fn test_transient_failures_are_retried() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/search").hang_up().times(1),
+            MockRoute::new("/search").json(serde_json::json!([{"lat": "46.02", "lon": "7.75"}])),
+            MockRoute::new("/v1/forecast").status(502).times(2),
+            forecast_route(),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+            .with_retry_policy(RetryPolicy::new(3, Duration::from_millis(10)));
+        assert!(repository.fetch_weather("Zermatt").is_ok());
+        assert_eq!(server.hits(), 5);
+
+        // The last attempt's failure is reported
+        let server = MockServer::start(vec![
+            MockRoute::new("/v1/forecast").status(500).times(3),
+            forecast_route(),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+            .with_retry_policy(RetryPolicy::new(3, Duration::from_millis(10)));
+        let error = repository.fetch_weather("46.02, 7.75").unwrap_err();
+        assert!(matches!(error, WeatherError::ServerError(_)), "{error:?}");
+        assert_eq!(server.requests_to("/v1/forecast").len(), 3);
+    }
+
+    #[test]
+    fn test_permanent_failures_are_not_retried() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/search").json(serde_json::json!([])),
+            MockRoute::new("/v1/forecast").status(404).times(1),
+            forecast_route(),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+            .with_retry_policy(RetryPolicy::new(3, Duration::from_secs(5)));
+
+        let started = Instant::now();
+        let error = repository.fetch_weather("46.02, 7.75").unwrap_err();
+        assert!(matches!(error, WeatherError::ApiError(_)), "{error:?}");
+        let error = repository.fetch_weather("Atlantis").unwrap_err();
+        assert!(
+            matches!(error, WeatherError::LocationNotFound { .. }),
+            "{error:?}"
+        );
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(server.requests_to("/v1/forecast").len(), 1);
+        assert_eq!(server.requests_to("/search").len(), 1);
+    }
+
+    #[test]
+    fn test_contact_stays_out_of_error_messages() {
+        // A port nothing listens on any more
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(
+                format!("http://127.0.0.1:{port}/search"),
+                format!("http://127.0.0.1:{port}/v1/forecast"),
+            )
+            .with_user_agent(UserAgent::new(None, Some("me@example.com")))
+            .with_retry_policy(RetryPolicy::none());
+
+        let error = repository.fetch_weather("Zermatt").unwrap_err();
+        assert!(matches!(error, WeatherError::NetworkError(_)));
+        assert!(!error.to_string().contains("example.com"), "{error}");
+    }
+
+    #[test]
+    fn test_fetch_weather_uses_endpoint_overrides() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/search").json(serde_json::json!([{"lat": "46.02", "lon": "7.75"}])),
+            MockRoute::new("/v1/forecast").json(serde_json::json!({
+                "elevation": 1608.0,
+                "current": {"temperature_2m": -1.0}
+            })),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"));
+
+        let info = repository.fetch_weather("Zermatt").unwrap();
+        assert_eq!(info.latitude, 46.02);
+        assert_eq!(info.elevation, Some(1608.0));
+        assert_eq!(info.weather_data.current.temperature, Some(-1.0));
+
+        let requests = server.requests();
+        assert!(requests[0].starts_with("/search?q=Zermatt"));
+        assert!(requests[1].starts_with("/v1/forecast?latitude=46.02&longitude=7.75"));
+    }
+
+    #[test]
+    fn test_identical_urls_are_served_from_memory() {
+        let routes = vec![
+            MockRoute::new("/search").json(serde_json::json!([{"lat": "46.02", "lon": "7.75"}])),
+            MockRoute::new("/v1/forecast")
+                .json(serde_json::json!({"current": {"temperature_2m": -1.0}})),
+        ];
+        let server = MockServer::start(routes.clone());
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"));
+
+        let first = repository.fetch_weather("Zermatt").unwrap();
+        let second = repository.fetch_weather("Zermatt").unwrap();
+        assert_eq!(server.hits(), 2);
+        assert_eq!(repository.response_cache_hits(), 2);
+        assert_eq!(first.weather_data, second.weather_data);
+
+        // --no-cache always asks the server
+        let server = MockServer::start(routes);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+            .with_response_cache(false);
+        repository.fetch_weather("Zermatt").unwrap();
+        repository.fetch_weather("Zermatt").unwrap();
+        assert_eq!(server.hits(), 4);
+        assert_eq!(repository.response_cache_hits(), 0);
+    }
+
+    #[test]
+    fn test_skewed_system_clock_is_corrected() {
+        // The server's clock is 2 h 14 m ahead of this machine's
+        let server_now = Utc::now() + chrono::TimeDelta::minutes(134);
+        let server = MockServer::start(vec![MockRoute::new("/v1/forecast")
+            .header("Date", &server_now.to_rfc2822())
+            .json(serde_json::json!({
+                "utc_offset_seconds": 0,
+                "current": {"temperature_2m": 12.0}
+            }))]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"));
+
+        let info = repository.fetch_weather("47.62, -122.33").unwrap();
+        let skew = info.clock_skew.unwrap();
+        assert!((skew - chrono::TimeDelta::minutes(134)).abs() < chrono::TimeDelta::seconds(5));
+        let location_now = info.location_now(Utc::now()).unwrap();
+        assert!((location_now - server_now.naive_utc()).abs() < chrono::TimeDelta::seconds(5));
+
+        // A server agreeing with the system clock leaves it alone
+        let server = MockServer::start(vec![MockRoute::new("/v1/forecast")
+            .header("Date", &Utc::now().to_rfc2822())
+            .json(serde_json::json!({"current": {"temperature_2m": 12.0}}))]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"));
+        assert_eq!(
+            repository
+                .fetch_weather("47.62, -122.33")
+                .unwrap()
+                .clock_skew,
+            None
+        );
+    }
+
+    #[test]
+    fn test_errors_are_not_cached() {
+        let server = MockServer::start(vec![MockRoute::new("/v1/forecast").status(500)]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+            .with_retry_policy(RetryPolicy::none());
+        assert!(repository.fetch_weather("46.02, 7.75").is_err());
+        assert!(repository.fetch_weather("46.02, 7.75").is_err());
+        assert_eq!(server.requests_to("/v1/forecast").len(), 2);
+    }
+
+    #[test]
+    fn test_pasted_coordinates_skip_geocoding() {
+        let server = MockServer::start(vec![MockRoute::new("/v1/forecast")
+            .json(serde_json::json!({"current": {"temperature_2m": 12.0}}))]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"));
+
+        let info = repository
+            .fetch_weather("47°37'12\"N 122°19'48\"W")
+            .unwrap();
+        // No reverse geocoding answer: named by the formatted coordinates
+        assert_eq!(info.location, "47.6200°N, 122.3300°W");
+        assert_eq!(info.query.as_deref(), Some("47°37'12\"N 122°19'48\"W"));
+
+        assert!(server.requests_to("/search").is_empty());
+        let requests = server.requests_to("/v1/forecast");
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("/v1/forecast?latitude=47.62"));
+        assert!(requests[0].contains("&longitude=-122.33"));
+    }
+
+    #[test]
+    fn test_pasted_coordinates_are_reverse_geocoded() {
+        let forecast = || {
+            MockRoute::new("/v1/forecast")
+                .json(serde_json::json!({"current": {"temperature_2m": 12.0}}))
+        };
+        let server = MockServer::start(vec![
+            MockRoute::new("/reverse?lat=35.68")
+                .json(serde_json::json!({"display_name": "Shinjuku, Tokyo, 160-0022, Japan"})),
+            MockRoute::new("/reverse?lat=0")
+                .json(serde_json::json!({"error": "Unable to geocode"})),
+            MockRoute::new("/reverse").status(500),
+            forecast(),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+            .with_retry_policy(RetryPolicy::none());
+
+        let info = repository.fetch_weather("35.68, 139.69").unwrap();
+        assert_eq!(info.location, "Shinjuku, Japan");
+        assert_eq!(info.weather_data.current.temperature, Some(12.0));
+        assert_eq!(
+            repository
+                .reverse_geocode(35.68, 139.69)
+                .unwrap()
+                .as_deref(),
+            Some("Shinjuku, Japan")
+        );
+
+        // Open sea, and a failing lookup, still give the weather
+        let info = repository.fetch_weather("0, -30").unwrap();
+        assert_eq!(info.location, "0.0000°N, 30.0000°W");
+        assert_eq!(repository.reverse_geocode(0.0, -30.0).unwrap(), None);
+        let info = repository.fetch_weather("-45.5, 170.5").unwrap();
+        assert_eq!(info.location, "45.5000°S, 170.5000°E");
+        assert!(repository.reverse_geocode(-45.5, 170.5).is_err());
+
+        assert!(matches!(
+            repository.reverse_geocode(91.0, 0.0),
+            Err(WeatherError::LocationNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_refused_marine_data_does_not_hold_back_forecasts() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/v1/marine").status(503),
+            MockRoute::new("/v1/forecast")
+                .json(serde_json::json!({"current": {"temperature_2m": 12.0}})),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+            .with_marine_url(server.url("/v1/marine"))
+            .with_data_selection(DataSelection::full().with_marine())
+            .with_retry_policy(RetryPolicy::none())
+            .with_response_cache(false);
+
+        assert_eq!(repository.fetch_weather("50.1, -5.5").unwrap().marine, None);
+        assert_eq!(repository.rate_limited_for(), None);
+        repository.fetch_weather("50.1, -5.5").unwrap();
+        assert_eq!(server.requests_to("/v1/forecast").len(), 2);
+    }
+
+    #[test]
+    fn test_refused_air_quality_does_not_hold_back_forecasts() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/v1/air-quality").status(429),
+            MockRoute::new("/v1/forecast")
+                .json(serde_json::json!({"current": {"temperature_2m": 12.0}})),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+            .with_air_quality_url(server.url("/v1/air-quality"))
+            .with_response_cache(false);
+
+        let info = repository.fetch_weather_full("51.51, -0.13").unwrap();
+        assert_eq!(info.air_quality, None);
+        assert!(matches!(
+            repository.fetch_air_quality(51.51, -0.13),
+            Err(WeatherError::RateLimited { .. })
+        ));
+        assert_eq!(repository.rate_limited_for(), None);
+        repository.fetch_weather("51.51, -0.13").unwrap();
+        assert_eq!(server.requests_to("/v1/forecast").len(), 2);
+    }
+
+    #[test]
+    fn test_coordinate_names_neither_delay_nor_hold_back_the_forecast() {
+        let delay = Duration::from_millis(300);
+        let server = MockServer::start(vec![
+            MockRoute::new("/reverse?lat=35.68")
+                .json(serde_json::json!({"display_name": "Shinjuku, Tokyo, Japan"}))
+                .delay(delay),
+            MockRoute::new("/reverse").status(429),
+            MockRoute::new("/v1/forecast")
+                .json(serde_json::json!({"current": {"temperature_2m": 12.0}}))
+                .delay(delay),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+            .with_response_cache(false);
+
+        let started = Instant::now();
+        let info = repository.fetch_weather("35.68, 139.69").unwrap();
+        assert_eq!(info.location, "Shinjuku, Japan");
+        assert!(started.elapsed() < delay * 2 - delay / 3);
+
+        // A refused name leaves the coordinates, and the next fetch is sent
+        let info = repository.fetch_weather("-45.5, 170.5").unwrap();
+        assert_eq!(info.location, "45.5000°S, 170.5000°E");
+        assert_eq!(repository.rate_limited_for(), None);
+        repository.fetch_weather("-45.5, 170.5").unwrap();
+        assert_eq!(server.requests_to("/v1/forecast").len(), 3);
+    }
+
+    #[test]
+    fn test_open_meteo_geocoder_timezone_skips_auto_resolution() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/v1/search").json(serde_json::json!({"results": [{
+                "name": "Zermatt", "latitude": 46.02, "longitude": 7.75,
+                "population": 5643, "timezone": "Europe/Zurich"
+            }]})),
+            MockRoute::new("/v1/forecast").json(serde_json::json!({
+                "utc_offset_seconds": 7200,
+                "current": {"temperature_2m": -1.0}
+            })),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_geocoder(GeocodingProvider::OpenMeteo)
+            .with_endpoints(server.url("/v1/search"), server.url("/v1/forecast"));
+
+        let info = repository.fetch_weather("Zermatt").unwrap();
+        assert_eq!((info.latitude, info.longitude), (46.02, 7.75));
+        assert_eq!(info.utc_offset_seconds, Some(7200));
+
+        let requests = server.requests();
+        assert!(requests[0].starts_with("/v1/search?name=Zermatt&count=10"));
+        assert!(
+            requests[1].ends_with("&timezone=Europe%2FZurich"),
+            "{}",
+            requests[1]
+        );
+        assert!(!requests[1].contains("timezone=auto"));
+    }
+
+    #[test]
+    fn test_with_geocoder_switches_default_endpoint_only() {
+        let repository = ApiWeatherRepository::new().with_geocoder(GeocodingProvider::OpenMeteo);
+        assert_eq!(repository.geocoding_url(), OPEN_METEO_GEOCODING_API_URL);
+
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints("http://geo.local/search", OPEN_METEO_API_URL)
+            .with_geocoder(GeocodingProvider::OpenMeteo);
+        assert_eq!(repository.geocoding_url(), "http://geo.local/search");
+    }
+
+    #[test]
+    fn test_skips_malformed_geocoding_results() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/search").json(serde_json::json!([
+                {"lat": "4.6e", "lon": "7.75"},
+                {"lat": "46.02", "lon": "7.75"}
+            ])),
+            MockRoute::new("/v1/forecast").json(serde_json::json!({
+                "current": {"temperature_2m": -1.0}
+            })),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"));
+
+        let info = repository.fetch_weather("Zermatt").unwrap();
+        assert_eq!((info.latitude, info.longitude), (46.02, 7.75));
+
+        let found = ApiWeatherRepository::parse_coordinates(
+            &serde_json::json!([{"lon": "7.75"}, {"lat": "4.6e+1", "lon": "7.75"}]),
+            "Zermatt",
+        )
+        .unwrap();
+        assert_eq!(found.latitude, 46.0);
+        assert_eq!(found.skipped, vec!["result 1: missing lat".to_string()]);
+    }
+
+    #[test]
+    fn test_geocoding_fails_when_no_result_parses() {
+        let json = serde_json::json!([
+            {"lat": "95.0", "lon": "7.75"},
+            {"lat": "46.02", "lon": "NaN"}
+        ]);
+        match ApiWeatherRepository::parse_coordinates(&json, "Zermatt") {
+            Err(WeatherError::ParseError(message)) => assert_eq!(
+                message,
+                "None of the 2 geocoding results for 'Zermatt' had valid coordinates \
+                 (first: result 1: invalid lat \"95.0\")"
+            ),
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_legacy_current_weather() {
+        let weather: OpenMeteoWeather = serde_json::from_value(serde_json::json!({
+            "current_weather": {
+                "time": "2024-06-01T14:00",
+                "temperature": 18.4,
+                "windspeed": 11.2,
+                "winddirection": 250.0,
+                "weathercode": 3,
+                "is_day": 1
+            }
+        }))
+        .unwrap();
+
+        let current =
+            ApiWeatherRepository::parse_legacy_current_weather(&weather.current_weather.unwrap());
+        assert_eq!(current.temperature, Some(18.4));
+        assert_eq!(current.wind_speed, Some(11.2));
+        assert_eq!(current.wind_direction, Some(250.0));
+        assert_eq!(current.weather_code, Some(3));
+        assert_eq!(current.is_day, Some(true));
+        assert_eq!(current.humidity, None);
+        assert_eq!(current.pressure, None);
+    }
+
+    #[test]
+    fn test_falls_back_to_legacy_on_parameter_error() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/search").json(serde_json::json!([{"lat": "60.39", "lon": "5.32"}])),
+            MockRoute::new("/v1/forecast?latitude=60.39&longitude=5.32&current_weather=true").json(
+                serde_json::json!({
+                    "current_weather": {"temperature": 9.5, "weathercode": 61, "is_day": 0}
+                }),
+            ),
+            MockRoute::new("/v1/forecast")
+                .status(400)
+                .json(serde_json::json!({
+                    "error": true,
+                    "reason": "Cannot initialize WeatherVariable from invalid String value cape"
+                })),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"));
+
+        let info = repository.fetch_weather("Bergen").unwrap();
+        assert!(info.reduced_data);
+        assert_eq!(info.weather_data.current.temperature, Some(9.5));
+        assert_eq!(info.weather_data.current.is_day, Some(false));
+        assert_eq!(server.hits(), 3);
+    }
+
+    #[test]
+    fn test_no_fallback_on_server_error() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/search").json(serde_json::json!([{"lat": "60.39", "lon": "5.32"}])),
+            MockRoute::new("/v1/forecast").status(502),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+            .with_retry_policy(RetryPolicy::none());
+
+        let result = repository.fetch_weather("Bergen");
+        assert!(matches!(result, Err(WeatherError::ServerError(_))));
+        assert_eq!(server.hits(), 2);
+    }
+
+    #[test]
+    fn test_rejected_model_reports_the_reason() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/search").json(serde_json::json!([{"lat": "-33.87", "lon": "151.21"}])),
+            MockRoute::new("/v1/forecast")
+                .status(400)
+                .json(serde_json::json!({
+                    "error": true,
+                    "reason": "No data is available for this location"
+                })),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+            .with_model(ForecastModel::Ukmo);
+
+        let error = repository.fetch_weather("Sydney").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "API error: Weather API returned status: 400 Bad Request (No data is available for this location)"
+        );
+        // The legacy request would quietly drop the model
+        assert_eq!(server.hits(), 2);
+        assert!(server.requests()[1].contains("&models=ukmo_seamless&"));
+    }
+
+    #[test]
+    fn test_fetched_forecast_records_the_model() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/search").json(serde_json::json!([{"lat": "47.6", "lon": "-122.3"}])),
+            MockRoute::new("/v1/forecast").json(serde_json::json!({
+                "current": {"temperature_2m": 14.0, "weather_code": 2, "is_day": 1}
+            })),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+            .with_model(ForecastModel::Gfs);
+
+        let info = repository.fetch_weather("Seattle").unwrap();
+        assert_eq!(info.model, ForecastModel::Gfs);
+        assert_eq!(info.model_tag().as_deref(), Some("(GFS)"));
+    }
+
+    #[test]
+    fn test_past_days_are_requested() {
+        let options = FetchOptions::new().with_past_days(2);
+        let url = ApiWeatherRepository::build_weather_api_url(
+            OPEN_METEO_API_URL,
+            51.5,
+            -0.12,
+            &DataSelection::default(),
+            None,
+            &options,
+        );
+        assert!(url.contains("&forecast_days=7&past_days=2&"), "{url}");
+        let (hourly, daily) = ApiWeatherRepository::build_split_weather_api_urls(
+            OPEN_METEO_API_URL,
+            51.5,
+            -0.12,
+            &DataSelection::default(),
+            None,
+            &options.with_forecast_days(16),
+            SPLIT_FETCH_HOURLY_HOURS,
+        );
+        assert!(
+            hourly.contains("&forecast_hours=48&past_hours=72&"),
+            "{hourly}"
+        );
+        assert!(daily.contains("&forecast_days=16&past_days=2&"), "{daily}");
+
+        let url = ApiWeatherRepository::build_weather_api_url(
+            OPEN_METEO_API_URL,
+            51.5,
+            -0.12,
+            &DataSelection::default(),
+            None,
+            &FetchOptions::default(),
+        );
+        assert!(!url.contains("past_"), "{url}");
+    }
+
+    /// Open-Meteo style forecast from 2024-06-01 with `hours` hourly entries,
+    /// `days` daily ones and, unless only daily data is wanted, current conditions
+    fn long_forecast(hours: usize, days: usize) -> serde_json::Value {
+        let start = chrono::NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        let midnight = start.and_hms_opt(0, 0, 0).unwrap();
+        let times: Vec<String> = (0..hours as i64)
+            .map(|h| {
+                (midnight + chrono::TimeDelta::hours(h))
+                    .format("%Y-%m-%dT%H:%M")
+                    .to_string()
+            })
+            .collect();
+        let dates: Vec<String> = (0..days as i64)
+            .map(|d| (start + chrono::TimeDelta::days(d)).to_string())
+            .collect();
+        let mut json = serde_json::json!({"elevation": 35.0, "utc_offset_seconds": 3600});
+        if hours > 0 {
+            json["current"] =
+                serde_json::json!({"time": "2024-06-01T00:00", "temperature_2m": 15.0});
+            json["hourly"] = serde_json::json!({
+                "time": times,
+                "temperature_2m": vec![15.0; hours],
+                "precipitation_probability": vec![20.0; hours],
+            });
+        }
+        if days > 0 {
+            json["daily"] = serde_json::json!({
+                "time": dates,
+                "temperature_2m_max": vec![20.0; days],
+                "temperature_2m_min": vec![10.0; days],
+            });
+        }
+        json
+    }
+
+    #[test]
+    fn test_fetch_with_options_for_a_two_week_horizon() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/v1/forecast?latitude=51.5&longitude=-0.12&current=")
+                .json(long_forecast(SPLIT_FETCH_HOURLY_HOURS, 0)),
+            MockRoute::new("/v1/forecast?latitude=51.5&longitude=-0.12&daily=")
+                .json(long_forecast(0, 16)),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"));
+        assert_eq!(repository.forecast_days(), 7);
+
+        let options = FetchOptions::new().with_forecast_days(16);
+        let info = repository
+            .fetch_weather_with_options("51.5, -0.12", &options)
+            .unwrap();
+        assert_eq!(info.weather_data.daily.len(), 16);
+        assert_eq!(info.weather_data.daily[15].date, "2024-06-16");
+        assert!(server
+            .requests_to("/v1/forecast")
+            .iter()
+            .any(|r| r.contains("&forecast_days=16&")));
+
+        // Out of range: an error, and nothing sent
+        let sent = server.hits();
+        for days in [0, 17] {
+            let options = FetchOptions::new().with_forecast_days(days);
+            assert!(matches!(
+                repository.fetch_weather_with_options("51.5, -0.12", &options),
+                Err(WeatherError::InvalidOption(_))
+            ));
+        }
+        assert_eq!(server.hits(), sent);
+    }
+
+    #[test]
+    fn test_long_forecast_is_split_and_merged() {
+        let hourly = long_forecast(SPLIT_FETCH_HOURLY_HOURS, 0).to_string();
+        let daily = long_forecast(0, 16).to_string();
+        let server = MockServer::start(vec![
+            MockRoute::new("/v1/forecast?latitude=51.5&longitude=-0.12&current=")
+                .body(hourly.clone()),
+            MockRoute::new("/v1/forecast?latitude=51.5&longitude=-0.12&daily=").body(daily.clone()),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+            .with_forecast_days(16);
+
+        let info = repository.fetch_weather("51.5, -0.12").unwrap();
+        let data = &info.weather_data;
+        assert_eq!(data.current.temperature, Some(15.0));
+        assert_eq!(info.elevation, Some(35.0));
+        assert_eq!(info.utc_offset_seconds, Some(3600));
+
+        // Every day and hour once, in order
+        assert_eq!(data.daily.len(), 16);
+        assert_eq!(data.daily[0].date, "2024-06-01");
+        assert_eq!(data.daily[15].date, "2024-06-16");
+        assert!(data
+            .daily
+            .windows(2)
+            .all(|pair| pair[0].date < pair[1].date));
+        assert_eq!(data.hourly.len(), SPLIT_FETCH_HOURLY_HOURS);
+        assert_eq!(data.hourly[47].time, "2024-06-02T23:00");
+        assert!(data
+            .hourly
+            .windows(2)
+            .all(|pair| pair[0].time < pair[1].time));
+
+        let timings = info.timings.unwrap();
+        assert_eq!(timings.strategy, FetchStrategy::Split);
+        assert_eq!(timings.forecast_bytes, hourly.len() + daily.len());
+
+        let requests = server.requests_to("/v1/forecast");
+        assert_eq!(requests.len(), 2);
+        let hourly_request = requests.iter().find(|r| r.contains("&hourly=")).unwrap();
+        assert!(hourly_request.contains("&forecast_hours=48&past_hours=24&"));
+        assert!(!hourly_request.contains("daily="));
+        let daily_request = requests.iter().find(|r| r.contains("&daily=")).unwrap();
+        assert!(daily_request.contains("&forecast_days=16&"));
+        assert!(!daily_request.contains("current=") && !daily_request.contains("hourly="));
+    }
+
+    #[test]
+    fn test_split_shrinks_the_long_horizon_payload() {
+        // One request for 16 days carries 16 days of hourly data
+        let single = long_forecast(16 * 24, 16).to_string();
+        let server = MockServer::start(vec![MockRoute::new("/v1/forecast").body(single.clone())]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"));
+        let timings = repository
+            .fetch_weather("51.5, -0.12")
+            .unwrap()
+            .timings
+            .unwrap();
+        assert_eq!(timings.strategy, FetchStrategy::Single);
+        assert_eq!(timings.forecast_bytes, single.len());
+        assert!(server.requests_to("/v1/forecast")[0].contains("&forecast_days=7&"));
+
+        let split = MockServer::start(vec![
+            MockRoute::new("/v1/forecast?latitude=51.5&longitude=-0.12&current=")
+                .json(long_forecast(SPLIT_FETCH_HOURLY_HOURS, 0)),
+            MockRoute::new("/v1/forecast?latitude=51.5&longitude=-0.12&daily=")
+                .json(long_forecast(0, 16)),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(split.url("/search"), split.url("/v1/forecast"))
+            .with_forecast_days(16);
+        let timings = repository
+            .fetch_weather("51.5, -0.12")
+            .unwrap()
+            .timings
+            .unwrap();
+        assert_eq!(timings.strategy, FetchStrategy::Split);
+        assert!(
+            timings.forecast_bytes * 3 < single.len(),
+            "{} vs {}",
+            timings.forecast_bytes,
+            single.len()
+        );
+    }
+
+    #[test]
+    fn test_split_falls_back_to_legacy_request() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/v1/forecast?latitude=51.5&longitude=-0.12&current_weather=true").json(
+                serde_json::json!({
+                    "current_weather": {"time": "2024-06-01T00:00", "temperature": 14.0}
+                }),
+            ),
+            MockRoute::new("/v1/forecast").status(400),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+            .with_forecast_days(16);
+
+        let info = repository.fetch_weather("51.5, -0.12").unwrap();
+        assert!(info.reduced_data);
+        assert_eq!(info.timings.unwrap().strategy, FetchStrategy::Single);
+        // Both halves, the single granular request, then the legacy one
+        assert_eq!(server.requests_to("/v1/forecast").len(), 4);
+        assert_eq!(
+            ApiWeatherRepository::new()
+                .with_forecast_days(40)
+                .forecast_days(),
+            16
+        );
+    }
+
+    #[test]
+    fn test_weather_url_uses_data_selection() {
+        let url = ApiWeatherRepository::build_weather_api_url(
+            OPEN_METEO_API_URL,
+            47.6,
+            -122.3,
+            &DataSelection::current_only(),
+            None,
+            &FetchOptions::default(),
+        );
+        assert!(url.starts_with(&format!(
+            "{OPEN_METEO_API_URL}?latitude=47.6&longitude=-122.3&current="
+        )));
+        assert!(!url.contains("hourly="));
+        assert!(!url.contains("daily="));
+        // Times come back in the location's zone so "today" is the location's day
+        assert!(url.ends_with("&timezone=auto"));
+    }
+
+    #[test]
+    fn test_weather_url_names_the_model() {
+        let url = |model| {
+            ApiWeatherRepository::build_weather_api_url(
+                OPEN_METEO_API_URL,
+                47.6,
+                -122.3,
+                &DataSelection::current_only(),
+                None,
+                &FetchOptions::new().with_model(model),
+            )
+        };
+        let expected = [
+            (ForecastModel::Ecmwf, "ecmwf_ifs025"),
+            (ForecastModel::Gfs, "gfs_seamless"),
+            (ForecastModel::IconSeamless, "icon_seamless"),
+            (ForecastModel::Gem, "gem_seamless"),
+            (ForecastModel::MeteoFrance, "meteofrance_seamless"),
+            (ForecastModel::Ukmo, "ukmo_seamless"),
+        ];
+        for (model, name) in expected {
+            assert!(
+                url(model).contains(&format!("&models={name}&timezone=auto")),
+                "{}",
+                url(model)
+            );
+        }
+        // Open-Meteo's own choice needs no parameter
+        assert!(!url(ForecastModel::BestMatch).contains("models="));
+
+        let options = FetchOptions::new()
+            .with_forecast_days(14)
+            .with_model(ForecastModel::Ecmwf);
+        let (hourly, daily) = ApiWeatherRepository::build_split_weather_api_urls(
+            OPEN_METEO_API_URL,
+            47.6,
+            -122.3,
+            &DataSelection::full(),
+            None,
+            &options,
+            48,
+        );
+        assert!(hourly.contains("&models=ecmwf_ifs025&"));
+        assert!(daily.contains("&models=ecmwf_ifs025&"));
+    }
+
+    #[test]
+    fn test_compact_selection_parses_partial_sections() {
+        let server = MockServer::start(vec![
+            MockRoute::new("/search").json(serde_json::json!([{"lat": "47.6", "lon": "-122.3"}])),
+            MockRoute::new("/v1/forecast").json(serde_json::json!({
+                "current": {"temperature_2m": 14.0, "weather_code": 2, "is_day": 1},
+                "hourly": {
+                    "time": ["2024-06-01T14:00"],
+                    "precipitation_probability": [30.0]
+                },
+                "daily": {
+                    "time": ["2024-06-01"],
+                    "temperature_2m_max": [18.0],
+                    "temperature_2m_min": [9.0],
+                    "sunrise": ["2024-06-01T05:12"],
+                    "sunset": ["2024-06-01T21:02"]
+                }
+            })),
+        ]);
+        let repository = ApiWeatherRepository::new()
+            .with_endpoints(server.url("/search"), server.url("/v1/forecast"))
+            .with_data_selection(DataSelection::compact());
+
+        let info = repository.fetch_weather("Seattle").unwrap();
+        let data = &info.weather_data;
+        assert_eq!(data.hourly[0].precipitation_probability, Some(30.0));
+        assert_eq!(data.hourly[0].temperature, None);
+        assert_eq!(data.daily[0].temperature_max, Some(18.0));
+        assert_eq!(data.daily[0].weather_code, None);
+        assert!(server.requests()[1].contains("&hourly=precipitation_probability&"));
     }
 }
@@ -0,0 +1,252 @@
+//! Test helpers: a minimal HTTP server serving canned responses, and a
+//! simulated clock for request spacing
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::repositories::request_spacing::Clock;
+
+/// Canned response for requests whose path starts with `path_prefix`
+#[derive(Clone, Debug)]
+pub struct MockRoute {
+    path_prefix: String,
+    status: u16,
+    body: String,
+    headers: Vec<(String, String)>,
+    delay: Duration,
+    /// Requests left before the route stops matching, shared between clones
+    remaining: Option<Arc<AtomicUsize>>,
+    hang_up: bool,
+}
+
+impl MockRoute {
+    pub fn new(path_prefix: &str) -> Self {
+        MockRoute {
+            path_prefix: path_prefix.to_string(),
+            status: 200,
+            body: String::new(),
+            headers: Vec::new(),
+            delay: Duration::ZERO,
+            remaining: None,
+            hang_up: false,
+        }
+    }
+
+    pub fn status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    pub fn json(self, value: serde_json::Value) -> Self {
+        self.body(value.to_string())
+    }
+
+    /// Adds a response header, e.g. `Retry-After`
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Waits this long before answering, like a slow upstream
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Answers only the first `n` matching requests and then lets later
+    /// routes match, e.g. a few failures before the real response
+    pub fn times(mut self, n: usize) -> Self {
+        self.remaining = Some(Arc::new(AtomicUsize::new(n)));
+        self
+    }
+
+    /// Closes the connection without answering, like a dropped connection
+    pub fn hang_up(mut self) -> Self {
+        self.hang_up = true;
+        self
+    }
+
+    /// Whether the route answers a request for `path`, using up one of its
+    /// `times` if it does
+    fn claim(&self, path: &str) -> bool {
+        path.starts_with(&self.path_prefix)
+            && self.remaining.as_ref().is_none_or(|remaining| {
+                remaining
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                    .is_ok()
+            })
+    }
+}
+
+/// HTTP server on an ephemeral localhost port; the thread lives until the test process exits
+pub struct MockServer {
+    port: u16,
+    hits: Arc<AtomicUsize>,
+    requests: Arc<std::sync::Mutex<Vec<String>>>,
+    user_agents: Arc<std::sync::Mutex<Vec<String>>>,
+}
+
+impl MockServer {
+    pub fn start(routes: Vec<MockRoute>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock server");
+        let port = listener.local_addr().unwrap().port();
+        let hits = Arc::new(AtomicUsize::new(0));
+        let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let user_agents = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let routes = Arc::new(routes);
+
+        let server_hits = Arc::clone(&hits);
+        let server_requests = Arc::clone(&requests);
+        let server_user_agents = Arc::clone(&user_agents);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let routes = Arc::clone(&routes);
+                let hits = Arc::clone(&server_hits);
+                let requests = Arc::clone(&server_requests);
+                let user_agents = Arc::clone(&server_user_agents);
+                thread::spawn(move || {
+                    if let Some((path, user_agent)) = read_request(&stream) {
+                        hits.fetch_add(1, Ordering::SeqCst);
+                        requests.lock().unwrap().push(path.clone());
+                        user_agents.lock().unwrap().extend(user_agent);
+                        respond(stream, &routes, &path);
+                    }
+                });
+            }
+        });
+
+        MockServer {
+            port,
+            hits,
+            requests,
+            user_agents,
+        }
+    }
+
+    /// Absolute URL for a path on this server
+    pub fn url(&self, path: &str) -> String {
+        format!("http://127.0.0.1:{}{path}", self.port)
+    }
+
+    /// Number of requests received so far
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::SeqCst)
+    }
+
+    /// Request paths (including query strings) received so far
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Requests received so far whose path starts with `path`
+    pub fn requests_to(&self, path: &str) -> Vec<String> {
+        self.requests()
+            .into_iter()
+            .filter(|request| request.starts_with(path))
+            .collect()
+    }
+
+    /// `User-Agent` headers received so far
+    pub fn user_agents(&self) -> Vec<String> {
+        self.user_agents.lock().unwrap().clone()
+    }
+}
+
+/// Path and `User-Agent` header of a request
+fn read_request(stream: &TcpStream) -> Option<(String, Option<String>)> {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).ok()?;
+
+    // Drain headers so the client sees a well-behaved server
+    let mut user_agent = None;
+    let mut line = String::new();
+    while reader.read_line(&mut line).ok()? > 2 {
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("user-agent") {
+                user_agent = Some(value.trim().to_string());
+            }
+        }
+        line.clear();
+    }
+
+    let path = request_line.split_whitespace().nth(1)?.to_string();
+    Some((path, user_agent))
+}
+
+fn respond(mut stream: TcpStream, routes: &[MockRoute], path: &str) {
+    let route = routes.iter().find(|r| r.claim(path));
+    if let Some(route) = route {
+        thread::sleep(route.delay);
+        if route.hang_up {
+            return;
+        }
+    }
+    let (status, body, headers) = match route {
+        Some(r) => (r.status, r.body.as_str(), r.headers.as_slice()),
+        None => (404, "", [].as_slice()),
+    };
+    let extra: String = headers
+        .iter()
+        .map(|(name, value)| format!("{name}: {value}\r\n"))
+        .collect();
+
+    let response = format!(
+        "HTTP/1.1 {status} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{extra}Connection: close\r\n\r\n{body}",
+        body.len()
+    );
+
+    let _ = stream.write_all(response.as_bytes());
+}
+
+/// Clock for request spacing that jumps to each slot instead of sleeping,
+/// keeping the waits handed out
+#[derive(Debug)]
+pub struct SimulatedClock {
+    started: Instant,
+    state: Mutex<(Instant, Vec<Duration>)>,
+}
+
+impl Default for SimulatedClock {
+    fn default() -> Self {
+        let now = Instant::now();
+        SimulatedClock {
+            started: now,
+            state: Mutex::new((now, Vec::new())),
+        }
+    }
+}
+
+impl SimulatedClock {
+    /// Waits handed out so far, in order
+    pub fn waits(&self) -> Vec<Duration> {
+        self.state.lock().unwrap().1.clone()
+    }
+
+    /// How far the clock has moved
+    pub fn elapsed(&self) -> Duration {
+        self.state.lock().unwrap().0 - self.started
+    }
+}
+
+impl Clock for SimulatedClock {
+    fn now(&self) -> Instant {
+        self.state.lock().unwrap().0
+    }
+
+    fn until(&self, now: Instant, slot: Instant) -> Duration {
+        let mut state = self.state.lock().unwrap();
+        state.1.push(slot - now);
+        state.0 = state.0.max(slot);
+        Duration::ZERO
+    }
+}
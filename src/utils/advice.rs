@@ -0,0 +1,315 @@
+//! Shared advice rules used by both views to call out notable conditions
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::weather_info::CurrentWeather;
+use crate::utils::conversions::Temperature;
+use crate::utils::format::{self, Precision};
+
+/// Minimum gap (°C) between apparent and actual temperature worth calling out
+pub const FEELS_LIKE_DIVERGENCE_C: f64 = 5.0;
+
+/// Wind speed (km/h) above which a cold feels-like is attributed to wind chill
+const WIND_CHILL_MIN_KMH: f64 = 10.0;
+
+/// Relative humidity (%) above which a warm feels-like is attributed to humidity
+const HEAT_INDEX_MIN_HUMIDITY: f64 = 40.0;
+
+/// Air temperature (°C) below which heat-stress indices are not meaningful
+const HEAT_STRESS_MIN_TEMP_C: f64 = 20.0;
+
+/// Index used for heat-stress "feels like" values and heat advisories
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FeelsLikeFormula {
+    /// US National Weather Service heat index
+    #[default]
+    HeatIndex,
+    /// Canadian humidex
+    Humidex,
+}
+
+impl FeelsLikeFormula {
+    pub fn label(&self) -> &'static str {
+        match self {
+            FeelsLikeFormula::HeatIndex => "Heat index",
+            FeelsLikeFormula::Humidex => "Humidex",
+        }
+    }
+}
+
+impl fmt::Display for FeelsLikeFormula {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FeelsLikeFormula::HeatIndex => "heat-index",
+            FeelsLikeFormula::Humidex => "humidex",
+        })
+    }
+}
+
+impl FromStr for FeelsLikeFormula {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+            "heat-index" => Ok(FeelsLikeFormula::HeatIndex),
+            "humidex" => Ok(FeelsLikeFormula::Humidex),
+            other => Err(format!(
+                "unknown feels-like formula '{other}' (expected heat-index or humidex)"
+            )),
+        }
+    }
+}
+
+/// Heat-stress index for warm conditions, or `None` when it does not apply
+pub fn heat_stress_index(current: &CurrentWeather, formula: FeelsLikeFormula) -> Option<f64> {
+    let temperature = current
+        .temperature
+        .filter(|t| *t >= HEAT_STRESS_MIN_TEMP_C)?;
+    let humidity = current.humidity?;
+
+    Some(match formula {
+        FeelsLikeFormula::HeatIndex => Temperature::heat_index(temperature, humidity),
+        FeelsLikeFormula::Humidex => {
+            Temperature::humidex(temperature, Temperature::dew_point(temperature, humidity))
+        }
+    })
+}
+
+/// Feels-like temperature: the API's apparent temperature, falling back to the
+/// selected heat-stress index when the server did not provide one
+pub fn feels_like(current: &CurrentWeather, formula: FeelsLikeFormula) -> Option<f64> {
+    current
+        .apparent_temperature
+        .or_else(|| heat_stress_index(current, formula))
+}
+
+/// Heat-stress category with its recommended precaution
+#[derive(Clone, Debug, PartialEq)]
+pub struct HeatAdvisory {
+    pub formula: FeelsLikeFormula,
+    pub value: f64,
+    /// Category name from the formula's own scale
+    pub category: &'static str,
+    pub precaution: &'static str,
+}
+
+impl HeatAdvisory {
+    /// e.g. "Humidex 40 — limit outdoor exertion" or
+    /// "Heat index 42°C (Danger) — limit outdoor exertion"
+    pub fn message(&self) -> String {
+        match self.formula {
+            FeelsLikeFormula::Humidex => {
+                format!(
+                    "Humidex {} — {}",
+                    format::number(self.value, Precision::Whole),
+                    self.precaution
+                )
+            }
+            FeelsLikeFormula::HeatIndex => format!(
+                "Heat index {} ({}) — {}",
+                format::celsius(self.value, Precision::Whole),
+                self.category,
+                self.precaution
+            ),
+        }
+    }
+
+    /// Whether the heat is dangerous: humidex 40+ or heat index 41 °C+
+    pub fn is_severe(&self) -> bool {
+        match self.formula {
+            FeelsLikeFormula::Humidex => self.value.round() >= 40.0,
+            FeelsLikeFormula::HeatIndex => self.value >= 41.0,
+        }
+    }
+}
+
+/// Heat advisory for the current conditions on the selected scale.
+///
+/// Humidex uses the Environment Canada bands (30, 40, 46), the heat index the
+/// NWS bands (27, 32, 41, 54 °C).
+pub fn heat_advisory(current: &CurrentWeather, formula: FeelsLikeFormula) -> Option<HeatAdvisory> {
+    let value = heat_stress_index(current, formula)?;
+
+    let (category, precaution) = match formula {
+        FeelsLikeFormula::Humidex => match value.round() {
+            v if v >= 46.0 => ("Dangerous", "avoid outdoor exertion"),
+            v if v >= 40.0 => ("Great discomfort", "limit outdoor exertion"),
+            v if v >= 30.0 => ("Some discomfort", "take breaks in the shade"),
+            _ => return None,
+        },
+        FeelsLikeFormula::HeatIndex => match value {
+            v if v >= 54.0 => ("Extreme danger", "heat stroke is likely; stay indoors"),
+            v if v >= 41.0 => ("Danger", "limit outdoor exertion"),
+            v if v >= 32.0 => ("Extreme caution", "heat exhaustion possible; drink water"),
+            v if v >= 27.0 => ("Caution", "fatigue possible with prolonged activity"),
+            _ => return None,
+        },
+    };
+
+    Some(HeatAdvisory {
+        formula,
+        value,
+        category,
+        precaution,
+    })
+}
+
+/// Most likely reason the feels-like temperature differs from the air temperature
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeelsLikeCause {
+    /// Wind carrying heat away (wind chill)
+    Wind,
+    /// Humidity limiting evaporative cooling (heat index)
+    Humidity,
+}
+
+/// Callout for a large apparent vs actual temperature divergence
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FeelsLikeCallout {
+    /// Apparent minus actual temperature in °C (negative feels colder)
+    pub difference: f64,
+    pub cause: Option<FeelsLikeCause>,
+}
+
+impl FeelsLikeCallout {
+    /// Human-readable callout, e.g. "Feels 9° colder than the thermometer due to wind"
+    pub fn message(&self) -> String {
+        let direction = if self.difference < 0.0 {
+            "colder"
+        } else {
+            "warmer"
+        };
+        let mut message = format!(
+            "Feels {}° {direction} than the thermometer",
+            format::number(self.difference.abs(), Precision::Whole)
+        );
+        match self.cause {
+            Some(FeelsLikeCause::Wind) => message.push_str(" due to wind"),
+            Some(FeelsLikeCause::Humidity) => message.push_str(" due to humidity"),
+            None => {}
+        }
+        message
+    }
+}
+
+/// Returns a callout when the feels-like temperature is at least 5°C off the actual
+pub fn feels_like_callout(current: &CurrentWeather) -> Option<FeelsLikeCallout> {
+    let actual = current.temperature?;
+    let apparent = current.apparent_temperature?;
+    let difference = apparent - actual;
+
+    if difference.abs() < FEELS_LIKE_DIVERGENCE_C {
+        return None;
+    }
+
+    let cause = if difference < 0.0 {
+        current
+            .wind_speed
+            .filter(|w| *w >= WIND_CHILL_MIN_KMH)
+            .map(|_| FeelsLikeCause::Wind)
+    } else {
+        current
+            .humidity
+            .filter(|h| *h >= HEAT_INDEX_MIN_HUMIDITY)
+            .map(|_| FeelsLikeCause::Humidity)
+    };
+
+    Some(FeelsLikeCallout { difference, cause })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conditions(temp: f64, apparent: f64, wind: f64, humidity: f64) -> CurrentWeather {
+        CurrentWeather {
+            temperature: Some(temp),
+            apparent_temperature: Some(apparent),
+            wind_speed: Some(wind),
+            humidity: Some(humidity),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_windy_cold_callout() {
+        let callout = feels_like_callout(&conditions(5.0, -4.0, 35.0, 60.0)).unwrap();
+        assert_eq!(callout.cause, Some(FeelsLikeCause::Wind));
+        assert_eq!(
+            callout.message(),
+            "Feels 9° colder than the thermometer due to wind"
+        );
+    }
+
+    #[test]
+    fn test_humid_hot_callout() {
+        let callout = feels_like_callout(&conditions(32.0, 39.0, 5.0, 75.0)).unwrap();
+        assert_eq!(callout.cause, Some(FeelsLikeCause::Humidity));
+        assert_eq!(
+            callout.message(),
+            "Feels 7° warmer than the thermometer due to humidity"
+        );
+    }
+
+    #[test]
+    fn test_no_callout_below_threshold() {
+        assert_eq!(feels_like_callout(&conditions(12.0, 8.5, 25.0, 50.0)), None);
+        assert_eq!(feels_like_callout(&CurrentWeather::default()), None);
+    }
+
+    #[test]
+    fn test_humidex_advisory_uses_canadian_bands() {
+        // 33°C at 55% RH: dew point ~22.8°C, humidex ~43
+        let hot = conditions(33.0, 38.0, 5.0, 55.0);
+        let advisory = heat_advisory(&hot, FeelsLikeFormula::Humidex).unwrap();
+        assert_eq!(advisory.category, "Great discomfort");
+        assert_eq!(advisory.message(), "Humidex 43 — limit outdoor exertion");
+        assert!(advisory.is_severe());
+
+        let mild = conditions(24.0, 24.0, 5.0, 40.0);
+        assert_eq!(heat_advisory(&mild, FeelsLikeFormula::Humidex), None);
+    }
+
+    #[test]
+    fn test_heat_index_advisory_uses_nws_bands() {
+        let hot = conditions(33.0, 38.0, 5.0, 55.0);
+        let advisory = heat_advisory(&hot, FeelsLikeFormula::HeatIndex).unwrap();
+        assert_eq!(advisory.category, "Extreme caution");
+        assert!(advisory
+            .message()
+            .starts_with("Heat index 38°C (Extreme caution)"));
+        // Same air, but the heat index only reaches its caution band
+        assert!(!advisory.is_severe());
+    }
+
+    #[test]
+    fn test_feels_like_falls_back_to_formula() {
+        let mut current = conditions(30.0, 0.0, 5.0, 60.0);
+        current.apparent_temperature = None;
+        let humidex = feels_like(&current, FeelsLikeFormula::Humidex).unwrap();
+        let heat_index = feels_like(&current, FeelsLikeFormula::HeatIndex).unwrap();
+        assert_eq!(humidex.round(), 39.0);
+        assert_eq!(heat_index.round(), 33.0);
+
+        current.temperature = Some(5.0);
+        assert_eq!(feels_like(&current, FeelsLikeFormula::Humidex), None);
+    }
+
+    #[test]
+    fn test_parse_formula() {
+        assert_eq!("humidex".parse(), Ok(FeelsLikeFormula::Humidex));
+        assert_eq!("heat_index".parse(), Ok(FeelsLikeFormula::HeatIndex));
+        assert!("wet-bulb".parse::<FeelsLikeFormula>().is_err());
+    }
+
+    #[test]
+    fn test_callout_without_clear_cause() {
+        let callout = feels_like_callout(&conditions(20.0, 26.0, 3.0, 20.0)).unwrap();
+        assert_eq!(callout.cause, None);
+        assert_eq!(callout.message(), "Feels 6° warmer than the thermometer");
+    }
+}
@@ -0,0 +1,380 @@
+//! Cold and heat advisories in the tiers of a national weather service
+//!
+//! People expect the names and cutoffs their own weather service uses, so
+//! a "Heat Advisory" in Phoenix and a "Heat warning" in Toronto are not the
+//! same thing. Each `AdvisoryStandard` has a table per hazard, measured in
+//! that service's own index. Services refine the cutoffs by region and
+//! duration; the tables hold the most widely used values for a single
+//! reading.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::weather_info::CurrentWeather;
+use crate::utils::advice::{self, FeelsLikeFormula};
+use crate::utils::conversions::Temperature;
+use crate::utils::format::{self, Precision};
+
+/// Weather service whose advisory tiers are used
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AdvisoryStandard {
+    /// US National Weather Service
+    #[default]
+    Us,
+    /// Environment and Climate Change Canada
+    Canada,
+    /// Generic European tiers, modelled on the Deutscher Wetterdienst
+    Eu,
+}
+
+impl AdvisoryStandard {
+    pub const ALL: [AdvisoryStandard; 3] = [
+        AdvisoryStandard::Us,
+        AdvisoryStandard::Canada,
+        AdvisoryStandard::Eu,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AdvisoryStandard::Us => "US (NWS)",
+            AdvisoryStandard::Canada => "Canada (ECCC)",
+            AdvisoryStandard::Eu => "EU (DWD)",
+        }
+    }
+
+    /// Tiers for `hazard`, most severe first
+    pub fn tiers(self, hazard: Hazard) -> &'static [Tier] {
+        match (self, hazard) {
+            (AdvisoryStandard::Us, Hazard::Heat) => &US_HEAT,
+            (AdvisoryStandard::Us, Hazard::Cold) => &US_COLD,
+            (AdvisoryStandard::Canada, Hazard::Heat) => &CANADA_HEAT,
+            (AdvisoryStandard::Canada, Hazard::Cold) => &CANADA_COLD,
+            (AdvisoryStandard::Eu, Hazard::Heat) => &EU_HEAT,
+            (AdvisoryStandard::Eu, Hazard::Cold) => &EU_COLD,
+        }
+    }
+
+    /// Name of the index the tiers for `hazard` are measured in
+    pub fn index_name(self, hazard: Hazard) -> &'static str {
+        match (self, hazard) {
+            (AdvisoryStandard::Us, Hazard::Heat) => "heat index",
+            (AdvisoryStandard::Canada, Hazard::Heat) => "humidex",
+            (AdvisoryStandard::Eu, Hazard::Heat) => "perceived temperature",
+            (AdvisoryStandard::Us | AdvisoryStandard::Canada, Hazard::Cold) => "wind chill",
+            (AdvisoryStandard::Eu, Hazard::Cold) => "temperature",
+        }
+    }
+}
+
+impl fmt::Display for AdvisoryStandard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AdvisoryStandard::Us => "us",
+            AdvisoryStandard::Canada => "canada",
+            AdvisoryStandard::Eu => "eu",
+        })
+    }
+}
+
+impl FromStr for AdvisoryStandard {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "us" | "nws" => Ok(AdvisoryStandard::Us),
+            "canada" | "ca" | "eccc" => Ok(AdvisoryStandard::Canada),
+            "eu" | "dwd" => Ok(AdvisoryStandard::Eu),
+            other => Err(format!(
+                "unknown advisory standard '{other}' (expected us, canada or eu)"
+            )),
+        }
+    }
+}
+
+/// What an advisory is about
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hazard {
+    Cold,
+    Heat,
+}
+
+/// How the views weight a tier: advisories are a heads-up, warnings call
+/// for action
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Advisory,
+    Warning,
+}
+
+/// One row of a standard's table
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tier {
+    /// Official name, e.g. "Heat Advisory"
+    pub name: &'static str,
+    pub severity: Severity,
+    /// Index value in °C at which the tier starts: at or above it for heat,
+    /// at or below it for cold
+    pub cutoff: f64,
+}
+
+impl Tier {
+    const fn new(name: &'static str, severity: Severity, cutoff: f64) -> Self {
+        Tier {
+            name,
+            severity,
+            cutoff,
+        }
+    }
+}
+
+// NWS heat index criteria used by most forecast offices: 105°F and 115°F.
+// The warning was called "Excessive Heat Warning" until 2025.
+const US_HEAT: [Tier; 2] = [
+    Tier::new("Extreme Heat Warning", Severity::Warning, 46.1),
+    Tier::new("Heat Advisory", Severity::Advisory, 40.6),
+];
+
+// NWS wind chill criteria of the northern states: -20°F and -35°F. Until
+// 2024 these were the "Wind Chill Advisory" and "Wind Chill Warning".
+const US_COLD: [Tier; 2] = [
+    Tier::new("Extreme Cold Warning", Severity::Warning, -37.2),
+    Tier::new("Cold Weather Advisory", Severity::Advisory, -28.9),
+];
+
+// ECCC warns for humidex 40 (southern Ontario and Quebec); there is no
+// advisory tier below the warning
+const CANADA_HEAT: [Tier; 1] = [Tier::new("Heat warning", Severity::Warning, 40.0)];
+
+// ECCC wind chill criterion for most of the country, from the Prairies to
+// the Atlantic; southern Ontario warns at -30, the Arctic at -50 and below
+const CANADA_COLD: [Tier; 1] = [Tier::new("Extreme cold warning", Severity::Warning, -40.0)];
+
+// DWD perceived-temperature criteria: strong heat at 32°C, extreme at 38°C
+const EU_HEAT: [Tier; 2] = [
+    Tier::new("Extreme heat warning", Severity::Warning, 38.0),
+    Tier::new("Strong heat warning", Severity::Advisory, 32.0),
+];
+
+// DWD frost criteria on air temperature: severe frost from -10°C, and
+// extreme frost from -20°C
+const EU_COLD: [Tier; 2] = [
+    Tier::new("Extreme frost warning", Severity::Warning, -20.0),
+    Tier::new("Severe frost warning", Severity::Advisory, -10.0),
+];
+
+/// Most severe tier of `standard` that an index value of `value` °C reaches
+pub fn tier(standard: AdvisoryStandard, hazard: Hazard, value: f64) -> Option<&'static Tier> {
+    standard.tiers(hazard).iter().find(|tier| match hazard {
+        Hazard::Heat => value >= tier.cutoff,
+        Hazard::Cold => value <= tier.cutoff,
+    })
+}
+
+/// The standard's index for `hazard` from the current conditions, `None`
+/// when the data it needs is missing
+pub fn index_value(
+    current: &CurrentWeather,
+    standard: AdvisoryStandard,
+    hazard: Hazard,
+) -> Option<f64> {
+    match (standard, hazard) {
+        (AdvisoryStandard::Us, Hazard::Heat) => {
+            advice::heat_stress_index(current, FeelsLikeFormula::HeatIndex)
+        }
+        (AdvisoryStandard::Canada, Hazard::Heat) => {
+            advice::heat_stress_index(current, FeelsLikeFormula::Humidex)
+        }
+        // Open-Meteo's apparent temperature is the closest match to DWD's
+        // perceived temperature
+        (AdvisoryStandard::Eu, Hazard::Heat) => current.apparent_temperature,
+        (AdvisoryStandard::Us | AdvisoryStandard::Canada, Hazard::Cold) => Some(
+            Temperature::wind_chill(current.temperature?, current.wind_speed?),
+        ),
+        (AdvisoryStandard::Eu, Hazard::Cold) => current.temperature,
+    }
+}
+
+/// A tier reached by the current conditions
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Advisory {
+    pub standard: AdvisoryStandard,
+    pub hazard: Hazard,
+    pub tier: &'static Tier,
+    /// Index value that reached the tier
+    pub value: f64,
+}
+
+impl Advisory {
+    /// e.g. "Heat Advisory (heat index 41°C)" or "Heat warning (humidex 42)"
+    pub fn message(&self) -> String {
+        let value = match (self.standard, self.hazard) {
+            // Humidex is read like °C but has no unit
+            (AdvisoryStandard::Canada, Hazard::Heat) => {
+                format::number(self.value, Precision::Whole)
+            }
+            _ => format::celsius(self.value, Precision::Whole),
+        };
+        format!(
+            "{} ({} {value})",
+            self.tier.name,
+            self.standard.index_name(self.hazard)
+        )
+    }
+}
+
+/// The advisory the current conditions call for under `standard`, if any
+pub fn advisory(current: &CurrentWeather, standard: AdvisoryStandard) -> Option<Advisory> {
+    [Hazard::Heat, Hazard::Cold].into_iter().find_map(|hazard| {
+        let value = index_value(current, standard, hazard)?;
+        Some(Advisory {
+            standard,
+            hazard,
+            tier: tier(standard, hazard, value)?,
+            value,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Just inside and just outside each cutoff
+    const EPSILON: f64 = 0.01;
+
+    /// Tier names at, just before and just past every cutoff of a table,
+    /// with the band short of the mildest tier giving no advisory
+    fn assert_boundaries(standard: AdvisoryStandard, hazard: Hazard, expected: &[&str]) {
+        let tiers = standard.tiers(hazard);
+        assert_eq!(
+            tiers.iter().map(|t| t.name).collect::<Vec<_>>(),
+            expected,
+            "{standard} {hazard:?}"
+        );
+        // Towards milder weather
+        let milder = match hazard {
+            Hazard::Heat => -EPSILON,
+            Hazard::Cold => EPSILON,
+        };
+        for (i, t) in tiers.iter().enumerate() {
+            let name = |value| tier(standard, hazard, value).map(|t| t.name);
+            assert_eq!(name(t.cutoff), Some(t.name), "{standard} at {}", t.cutoff);
+            assert_eq!(name(t.cutoff - milder), Some(t.name));
+            assert_eq!(
+                name(t.cutoff + milder),
+                tiers.get(i + 1).map(|next| next.name),
+                "{standard} short of {}",
+                t.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_us_tiers() {
+        assert_boundaries(
+            AdvisoryStandard::Us,
+            Hazard::Heat,
+            &["Extreme Heat Warning", "Heat Advisory"],
+        );
+        assert_boundaries(
+            AdvisoryStandard::Us,
+            Hazard::Cold,
+            &["Extreme Cold Warning", "Cold Weather Advisory"],
+        );
+        // The cutoffs are the published Fahrenheit values
+        let cutoffs = |hazard| {
+            AdvisoryStandard::Us
+                .tiers(hazard)
+                .iter()
+                .map(|t| Temperature::celsius_to_fahrenheit(t.cutoff).round())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(cutoffs(Hazard::Heat), [115.0, 105.0]);
+        assert_eq!(cutoffs(Hazard::Cold), [-35.0, -20.0]);
+    }
+
+    #[test]
+    fn test_canada_tiers() {
+        assert_boundaries(AdvisoryStandard::Canada, Hazard::Heat, &["Heat warning"]);
+        assert_boundaries(
+            AdvisoryStandard::Canada,
+            Hazard::Cold,
+            &["Extreme cold warning"],
+        );
+    }
+
+    #[test]
+    fn test_eu_tiers() {
+        assert_boundaries(
+            AdvisoryStandard::Eu,
+            Hazard::Heat,
+            &["Extreme heat warning", "Strong heat warning"],
+        );
+        assert_boundaries(
+            AdvisoryStandard::Eu,
+            Hazard::Cold,
+            &["Extreme frost warning", "Severe frost warning"],
+        );
+    }
+
+    #[test]
+    fn test_no_advisory_band() {
+        for standard in AdvisoryStandard::ALL {
+            for value in [-25.0, 0.0, 15.0, 30.0] {
+                assert_eq!(tier(standard, Hazard::Heat, value), None, "{standard}");
+            }
+            for value in [-9.0, 0.0, 15.0, 45.0] {
+                assert_eq!(tier(standard, Hazard::Cold, value), None, "{standard}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_advisory_from_current_conditions() {
+        let hot = CurrentWeather {
+            temperature: Some(37.0),
+            apparent_temperature: Some(42.0),
+            humidity: Some(40.0),
+            wind_speed: Some(10.0),
+            ..Default::default()
+        };
+        let us = advisory(&hot, AdvisoryStandard::Us).unwrap();
+        assert_eq!(us.tier.name, "Heat Advisory");
+        assert_eq!(us.message(), "Heat Advisory (heat index 41°C)");
+        let canada = advisory(&hot, AdvisoryStandard::Canada).unwrap();
+        assert_eq!(canada.message(), "Heat warning (humidex 46)");
+        let eu = advisory(&hot, AdvisoryStandard::Eu).unwrap();
+        assert_eq!(eu.tier.name, "Extreme heat warning");
+
+        // -25°C in a 30 km/h wind: wind chill -39°C, air temperature -25°C
+        let cold = CurrentWeather {
+            temperature: Some(-25.0),
+            apparent_temperature: Some(-34.0),
+            humidity: Some(70.0),
+            wind_speed: Some(30.0),
+            ..Default::default()
+        };
+        let us = advisory(&cold, AdvisoryStandard::Us).unwrap();
+        assert_eq!(us.message(), "Extreme Cold Warning (wind chill -39°C)");
+        assert_eq!(us.tier.severity, Severity::Warning);
+        assert_eq!(advisory(&cold, AdvisoryStandard::Canada), None);
+        let eu = advisory(&cold, AdvisoryStandard::Eu).unwrap();
+        assert_eq!(eu.message(), "Extreme frost warning (temperature -25°C)");
+
+        assert_eq!(
+            advisory(&CurrentWeather::default(), AdvisoryStandard::Us),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_standard() {
+        assert_eq!("US".parse(), Ok(AdvisoryStandard::Us));
+        assert_eq!("eccc".parse(), Ok(AdvisoryStandard::Canada));
+        assert_eq!("dwd".parse(), Ok(AdvisoryStandard::Eu));
+        assert!("uk".parse::<AdvisoryStandard>().is_err());
+    }
+}
@@ -0,0 +1,81 @@
+//! Agriculture helpers for garden irrigation planning
+
+use chrono::NaiveDate;
+
+use crate::models::weather_info::DailyForecast;
+use crate::utils::time;
+
+/// Daily irrigation deficit in mm: ET₀ minus forecast precipitation, clamped at zero.
+///
+/// Days without an ET₀ value or with an unparseable date are skipped. Missing
+/// precipitation is treated as no rain.
+pub fn irrigation_deficit(daily: &[DailyForecast]) -> Vec<(NaiveDate, f64)> {
+    daily
+        .iter()
+        .filter_map(|day| {
+            let date = time::parse_date(&day.date)?;
+            let et0 = day.et0_evapotranspiration?;
+            let rain = day.precipitation_sum.unwrap_or(0.0);
+            Some((date, (et0 - rain).max(0.0)))
+        })
+        .collect()
+}
+
+/// Total water needed over the deficit period in mm
+pub fn total_deficit(deficit: &[(NaiveDate, f64)]) -> f64 {
+    deficit.iter().map(|(_, mm)| mm).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(date: &str, et0: Option<f64>, rain: Option<f64>) -> DailyForecast {
+        DailyForecast {
+            date: date.to_string(),
+            et0_evapotranspiration: et0,
+            precipitation_sum: rain,
+            ..Default::default()
+        }
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_rain_fully_covers_et0() {
+        let deficit = irrigation_deficit(&[day("2024-06-01", Some(3.5), Some(8.0))]);
+        assert_eq!(deficit, vec![(date("2024-06-01"), 0.0)]);
+    }
+
+    #[test]
+    fn test_rain_partially_covers_et0() {
+        let deficit = irrigation_deficit(&[day("2024-06-02", Some(5.0), Some(1.5))]);
+        assert_eq!(deficit.len(), 1);
+        assert!((deficit[0].1 - 3.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_no_rain_leaves_full_et0() {
+        let deficit = irrigation_deficit(&[
+            day("2024-06-03", Some(4.2), Some(0.0)),
+            day("2024-06-04", Some(4.6), None),
+        ]);
+        assert_eq!(
+            deficit,
+            vec![(date("2024-06-03"), 4.2), (date("2024-06-04"), 4.6)]
+        );
+        assert!((total_deficit(&deficit) - 8.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_skips_missing_et0_and_bad_dates() {
+        let deficit = irrigation_deficit(&[
+            day("2024-06-05", None, Some(0.0)),
+            day("not-a-date", Some(4.0), Some(0.0)),
+        ]);
+        assert!(deficit.is_empty());
+        assert_eq!(total_deficit(&deficit), 0.0);
+    }
+}
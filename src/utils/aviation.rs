@@ -0,0 +1,91 @@
+//! Winds aloft for drone pilots: wind at 10 m, 80 m and 120 m above ground
+
+use crate::models::weather_info::HourlyForecast;
+use crate::utils::conversions::{Direction, WindUnit};
+
+/// Height above ground a wind value applies to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WindLevel {
+    /// 10 m, the standard surface wind
+    Surface,
+    Meters80,
+    Meters120,
+}
+
+impl WindLevel {
+    pub const ALL: [WindLevel; 3] = [
+        WindLevel::Surface,
+        WindLevel::Meters80,
+        WindLevel::Meters120,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            WindLevel::Surface => "10 m",
+            WindLevel::Meters80 => "80 m",
+            WindLevel::Meters120 => "120 m",
+        }
+    }
+
+    /// Speed (km/h) and direction (degrees) at this height
+    pub fn wind(&self, hour: &HourlyForecast) -> (Option<f64>, Option<f64>) {
+        match self {
+            WindLevel::Surface => (hour.wind_speed, hour.wind_direction),
+            WindLevel::Meters80 => (hour.wind_speed_80m, hour.wind_direction_80m),
+            WindLevel::Meters120 => (hour.wind_speed_120m, hour.wind_direction_120m),
+        }
+    }
+}
+
+/// Whether any hour reports wind above the surface
+pub fn has_winds_aloft(hours: &[HourlyForecast]) -> bool {
+    hours
+        .iter()
+        .any(|h| h.wind_speed_80m.is_some() || h.wind_speed_120m.is_some())
+}
+
+/// Speed in `unit` with a compass direction, e.g. "12 kn SW"; "-" without a speed
+pub fn wind_text(speed_kmh: Option<f64>, direction: Option<f64>, unit: WindUnit) -> String {
+    let Some(speed) = speed_kmh else {
+        return "-".to_string();
+    };
+    let mut text = format!("{:.0} {}", unit.from_kmh(speed), unit.label());
+    if let Some(direction) = direction {
+        text.push(' ');
+        text.push_str(Direction::compass_point(direction));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wind_levels() {
+        let hour = HourlyForecast::builder("2024-06-01T12:00")
+            .wind_speed(10.0)
+            .wind_speed_80m(25.0)
+            .wind_direction_80m(230.0)
+            .build();
+        assert_eq!(WindLevel::Surface.wind(&hour), (Some(10.0), None));
+        assert_eq!(WindLevel::Meters80.wind(&hour), (Some(25.0), Some(230.0)));
+        assert_eq!(WindLevel::Meters120.wind(&hour), (None, None));
+        assert!(has_winds_aloft(&[hour]));
+        assert!(!has_winds_aloft(&[HourlyForecast::builder(
+            "2024-06-01T12:00"
+        )
+        .wind_speed(10.0)
+        .build()]));
+    }
+
+    #[test]
+    fn test_wind_text() {
+        assert_eq!(
+            wind_text(Some(37.04), Some(225.0), WindUnit::Knots),
+            "20 kn SW"
+        );
+        assert_eq!(wind_text(Some(12.0), None, WindUnit::Kmh), "12 km/h");
+        assert_eq!(wind_text(None, Some(90.0), WindUnit::Mph), "-");
+    }
+}
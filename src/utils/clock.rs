@@ -0,0 +1,160 @@
+//! Wall clock at the forecast location, so "today" never depends on the host's time zone
+
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, TimeDelta, Utc, Weekday};
+
+use crate::utils::time;
+
+/// Local date and time at a location with a fixed UTC offset, as of one instant
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LocationClock {
+    offset: FixedOffset,
+    instant: DateTime<Utc>,
+}
+
+impl LocationClock {
+    /// Clock at `now` for a location `utc_offset_seconds` east of UTC (the API's
+    /// `utc_offset_seconds`); offsets beyond ±24 h fall back to UTC
+    pub fn new(utc_offset_seconds: i32, now: DateTime<Utc>) -> Self {
+        let offset = FixedOffset::east_opt(utc_offset_seconds)
+            .unwrap_or_else(|| FixedOffset::east_opt(0).expect("zero offset"));
+        LocationClock {
+            offset,
+            instant: now,
+        }
+    }
+
+    /// The same clock moved by `skew`, the server time minus the system
+    /// time the clock was created with
+    pub fn corrected(mut self, skew: TimeDelta) -> Self {
+        self.instant += skew;
+        self
+    }
+
+    pub fn utc_offset_seconds(&self) -> i32 {
+        self.offset.local_minus_utc()
+    }
+
+    /// Current local time at the location
+    pub fn now(&self) -> NaiveDateTime {
+        self.local(self.instant)
+    }
+
+    /// Current date at the location
+    pub fn today(&self) -> NaiveDate {
+        self.now().date()
+    }
+
+    /// Local time at the location for an instant
+    pub fn local(&self, instant: DateTime<Utc>) -> NaiveDateTime {
+        instant.with_timezone(&self.offset).naive_local()
+    }
+
+    /// Whether two instants fall on the same calendar day at the location
+    pub fn is_same_local_day(&self, a: DateTime<Utc>, b: DateTime<Utc>) -> bool {
+        self.local(a).date() == self.local(b).date()
+    }
+
+    /// Whether today is Saturday or Sunday at the location
+    pub fn is_weekend(&self) -> bool {
+        matches!(self.today().weekday(), Weekday::Sat | Weekday::Sun)
+    }
+
+    /// "today", "tomorrow" or the weekday name of a local date
+    pub fn relative_day(&self, date: NaiveDate) -> String {
+        time::relative_day(date, self.today())
+    }
+
+    /// Time until a local timestamp such as "2024-06-01T21:02"; `None` once it has passed
+    pub fn until(&self, local_time: &str) -> Option<TimeDelta> {
+        let remaining = time::parse_hour(local_time)? - self.now();
+        (remaining >= TimeDelta::zero()).then_some(remaining)
+    }
+}
+
+/// Size of a clock skew in either direction: "2 h 14 m", "12 m", "3 d 1 h"
+pub fn skew_amount(skew: TimeDelta) -> String {
+    let minutes = skew.num_minutes().abs();
+    let (days, hours, minutes) = (minutes / (24 * 60), minutes / 60 % 24, minutes % 60);
+    match (days, hours) {
+        (0, 0) => format!("{minutes} m"),
+        (0, _) => format!("{hours} h {minutes} m"),
+        _ => format!("{days} d {hours} h"),
+    }
+}
+
+/// "System clock appears off by 2 h 14 m"
+pub fn skew_warning(skew: TimeDelta) -> String {
+    format!("System clock appears off by {}", skew_amount(skew))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// 11 hours ahead of UTC, e.g. Sydney in summer
+    const AHEAD: i32 = 11 * 3600;
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn test_today_uses_location_offset() {
+        // Friday 14:30 UTC is already Saturday 01:30 eleven hours ahead
+        let clock = LocationClock::new(AHEAD, utc(2024, 6, 7, 14, 30));
+        assert_eq!(clock.now().to_string(), "2024-06-08 01:30:00");
+        assert_eq!(clock.today(), NaiveDate::from_ymd_opt(2024, 6, 8).unwrap());
+        assert!(clock.is_weekend());
+        assert!(!LocationClock::new(0, utc(2024, 6, 7, 14, 30)).is_weekend());
+    }
+
+    #[test]
+    fn test_relative_day() {
+        let clock = LocationClock::new(AHEAD, utc(2024, 6, 7, 14, 30));
+        let date = |d| NaiveDate::from_ymd_opt(2024, 6, d).unwrap();
+        assert_eq!(clock.relative_day(date(8)), "today");
+        assert_eq!(clock.relative_day(date(9)), "tomorrow");
+        assert_eq!(clock.relative_day(date(11)), "Tuesday");
+    }
+
+    #[test]
+    fn test_is_same_local_day() {
+        let clock = LocationClock::new(AHEAD, utc(2024, 6, 7, 14, 30));
+        // 12:00 and 14:00 UTC straddle local midnight
+        assert!(!clock.is_same_local_day(utc(2024, 6, 7, 12, 0), utc(2024, 6, 7, 14, 0)));
+        assert!(clock.is_same_local_day(utc(2024, 6, 7, 14, 0), utc(2024, 6, 8, 12, 0)));
+    }
+
+    #[test]
+    fn test_until() {
+        let clock = LocationClock::new(AHEAD, utc(2024, 6, 7, 14, 30));
+        assert_eq!(
+            clock.until("2024-06-08T05:10"),
+            Some(TimeDelta::minutes(220))
+        );
+        assert_eq!(clock.until("2024-06-07T20:00"), None);
+        assert_eq!(clock.until("garbage"), None);
+    }
+
+    #[test]
+    fn test_corrected_clock_uses_server_time() {
+        // The system clock says 14:30 UTC, the server 2 h 14 m later
+        let skew = TimeDelta::minutes(134);
+        let clock = LocationClock::new(AHEAD, utc(2024, 6, 7, 14, 30)).corrected(skew);
+        assert_eq!(clock.now().to_string(), "2024-06-08 03:44:00");
+        assert_eq!(
+            clock.until("2024-06-08T05:10"),
+            Some(TimeDelta::minutes(86))
+        );
+        assert_eq!(skew_warning(skew), "System clock appears off by 2 h 14 m");
+        assert_eq!(skew_amount(TimeDelta::minutes(-12)), "12 m");
+        assert_eq!(skew_amount(TimeDelta::hours(73)), "3 d 1 h");
+    }
+
+    #[test]
+    fn test_invalid_offset_falls_back_to_utc() {
+        let clock = LocationClock::new(90_000, utc(2024, 6, 7, 14, 30));
+        assert_eq!(clock.utc_offset_seconds(), 0);
+    }
+}
@@ -0,0 +1,128 @@
+//! Forecast confidence from the spread of a model ensemble
+//!
+//! An ensemble runs the same model many times from slightly different
+//! starting points. When the members agree on a day's high and low, the
+//! forecast for that day is trustworthy; when they scatter, it is a guess.
+//! The interquartile range ignores a few outlying members and is a better
+//! proxy than the full range.
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::i18n::{tr, Key, Locale};
+
+/// Fewest members for a meaningful spread
+const MIN_MEMBERS: usize = 5;
+
+/// Largest interquartile range in °C for high confidence
+const HIGH_MAX_SPREAD: f64 = 1.5;
+
+/// Largest interquartile range in °C for medium confidence
+const MEDIUM_MAX_SPREAD: f64 = 3.0;
+
+/// How much the ensemble agrees on a day's temperatures
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Confidence {
+    High,
+    Medium,
+    Low,
+}
+
+impl Confidence {
+    /// Confidence for an interquartile range of member temperatures in °C
+    pub fn from_spread(spread: f64) -> Self {
+        if spread <= HIGH_MAX_SPREAD {
+            Confidence::High
+        } else if spread <= MEDIUM_MAX_SPREAD {
+            Confidence::Medium
+        } else {
+            Confidence::Low
+        }
+    }
+
+    /// "low confidence"
+    pub fn label(self, locale: Locale) -> &'static str {
+        tr(
+            locale,
+            match self {
+                Confidence::High => Key::HighConfidence,
+                Confidence::Medium => Key::MediumConfidence,
+                Confidence::Low => Key::LowConfidence,
+            },
+        )
+    }
+
+    /// Explanation for a tooltip
+    pub fn description(self, locale: Locale) -> &'static str {
+        tr(
+            locale,
+            match self {
+                Confidence::High => Key::HighConfidenceNote,
+                Confidence::Medium => Key::MediumConfidenceNote,
+                Confidence::Low => Key::LowConfidenceNote,
+            },
+        )
+    }
+}
+
+/// Interquartile range of `values` (linear interpolation between ranks),
+/// `None` with fewer than `MIN_MEMBERS` values
+pub fn interquartile_range(values: &[f64]) -> Option<f64> {
+    if values.len() < MIN_MEMBERS {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let quantile = |q: f64| {
+        let rank = q * (sorted.len() - 1) as f64;
+        let (low, high) = (rank.floor() as usize, rank.ceil() as usize);
+        sorted[low] + (sorted[high] - sorted[low]) * (rank - low as f64)
+    };
+    Some(quantile(0.75) - quantile(0.25))
+}
+
+/// Confidence for a day from its members' highs and lows: the wider of the
+/// two spreads decides. `None` when neither has enough members.
+pub fn day_confidence(highs: &[f64], lows: &[f64]) -> Option<Confidence> {
+    let spread = match (interquartile_range(highs), interquartile_range(lows)) {
+        (Some(high), Some(low)) => high.max(low),
+        (spread, None) | (None, spread) => spread?,
+    };
+    Some(Confidence::from_spread(spread))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interquartile_range() {
+        assert_eq!(interquartile_range(&[1.0, 2.0, 3.0, 4.0, 5.0]), Some(2.0));
+        // Order and a single outlier do not matter
+        assert_eq!(
+            interquartile_range(&[5.0, 1.0, 40.0, 3.0, 2.0, 4.0, 3.0, 3.0, 2.0]),
+            Some(2.0)
+        );
+        assert_eq!(interquartile_range(&[1.0, 2.0, 3.0, 4.0]), None);
+    }
+
+    #[test]
+    fn test_spread_to_confidence() {
+        assert_eq!(Confidence::from_spread(0.0), Confidence::High);
+        assert_eq!(Confidence::from_spread(1.5), Confidence::High);
+        assert_eq!(Confidence::from_spread(1.51), Confidence::Medium);
+        assert_eq!(Confidence::from_spread(3.0), Confidence::Medium);
+        assert_eq!(Confidence::from_spread(3.01), Confidence::Low);
+        assert_eq!(Confidence::from_spread(12.0), Confidence::Low);
+    }
+
+    #[test]
+    fn test_day_confidence_takes_the_wider_spread() {
+        let tight = [20.0, 20.2, 20.4, 20.6, 20.8];
+        let wide = [10.0, 12.0, 14.0, 16.0, 18.0];
+        assert_eq!(day_confidence(&tight, &tight), Some(Confidence::High));
+        assert_eq!(day_confidence(&tight, &wide), Some(Confidence::Low));
+        assert_eq!(day_confidence(&wide, &[]), Some(Confidence::Low));
+        assert_eq!(day_confidence(&[1.0], &[2.0]), None);
+    }
+}
@@ -3,6 +3,11 @@
 //! Data is stored in metric (scientific standard) but displayed in both systems.
 //! Using zero-sized types as namespaces for related conversion functions.
 
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
 /// Temperature conversions
 pub struct Temperature;
 
@@ -10,6 +15,65 @@ impl Temperature {
     pub fn celsius_to_fahrenheit(celsius: f64) -> f64 {
         (celsius * 9.0 / 5.0) + 32.0
     }
+
+    pub fn celsius_to_kelvin(celsius: f64) -> f64 {
+        celsius + 273.15
+    }
+
+    pub fn kelvin_to_celsius(kelvin: f64) -> f64 {
+        kelvin - 273.15
+    }
+
+    pub fn fahrenheit_to_celsius(fahrenheit: f64) -> f64 {
+        (fahrenheit - 32.0) * 5.0 / 9.0
+    }
+
+    /// Dew point in °C from air temperature and relative humidity (Magnus formula)
+    pub fn dew_point(celsius: f64, humidity: f64) -> f64 {
+        const B: f64 = 17.62;
+        const C: f64 = 243.12;
+        let gamma = (humidity.max(1.0) / 100.0).ln() + B * celsius / (C + celsius);
+        C * gamma / (B - gamma)
+    }
+
+    /// Canadian humidex (dimensionless, read like °C) from air temperature and dew point
+    pub fn humidex(celsius: f64, dew_point: f64) -> f64 {
+        let vapour_pressure =
+            6.11 * (5417.7530 * (1.0 / 273.16 - 1.0 / (273.15 + dew_point))).exp();
+        celsius + 0.5555 * (vapour_pressure - 10.0)
+    }
+
+    /// US National Weather Service heat index in °C (Rothfusz regression)
+    pub fn heat_index(celsius: f64, humidity: f64) -> f64 {
+        let t = Self::celsius_to_fahrenheit(celsius);
+        let rh = humidity;
+
+        // The simple formula is accurate below 80°F; the regression is not
+        let simple = 0.5 * (t + 61.0 + (t - 68.0) * 1.2 + rh * 0.094);
+        let fahrenheit = if (simple + t) / 2.0 < 80.0 {
+            simple
+        } else {
+            -42.379 + 2.04901523 * t + 10.14333127 * rh
+                - 0.22475541 * t * rh
+                - 0.00683783 * t * t
+                - 0.05481717 * rh * rh
+                + 0.00122874 * t * t * rh
+                + 0.00085282 * t * rh * rh
+                - 0.00000199 * t * t * rh * rh
+        };
+        Self::fahrenheit_to_celsius(fahrenheit)
+    }
+
+    /// Wind chill in °C from air temperature and 10 m wind speed (the 2001
+    /// formula shared by the NWS and Environment Canada); the air temperature
+    /// itself above 10°C or in winds under 4.8 km/h, where it is not defined
+    pub fn wind_chill(celsius: f64, wind_kmh: f64) -> f64 {
+        if celsius > 10.0 || wind_kmh < 4.8 {
+            return celsius;
+        }
+        let v = wind_kmh.powf(0.16);
+        13.12 + 0.6215 * celsius - 11.37 * v + 0.3965 * celsius * v
+    }
 }
 
 /// Distance conversions
@@ -20,6 +84,19 @@ impl Distance {
         mm * 0.0393701
     }
 
+    pub fn cm_to_inches(cm: f64) -> f64 {
+        cm * 0.393701
+    }
+
+    pub fn cm_to_meters(cm: f64) -> f64 {
+        cm / 100.0
+    }
+
+    /// Liquid water depth in mm to areal mass in kg/m² (1 mm of water weighs 1 kg/m²)
+    pub fn mm_to_kg_per_m2(mm: f64) -> f64 {
+        mm
+    }
+
     pub fn meters_to_feet(meters: f64) -> f64 {
         meters * 3.28084
     }
@@ -32,6 +109,180 @@ impl Speed {
     pub fn kmh_to_mph(kmh: f64) -> f64 {
         kmh * 0.621371
     }
+
+    pub fn kmh_to_ms(kmh: f64) -> f64 {
+        kmh / 3.6
+    }
+
+    pub fn kmh_to_knots(kmh: f64) -> f64 {
+        kmh / 1.852
+    }
+
+    pub fn mph_to_kmh(mph: f64) -> f64 {
+        mph * 1.609344
+    }
+
+    pub fn ms_to_kmh(ms: f64) -> f64 {
+        ms * 3.6
+    }
+}
+
+/// Unit for displaying wind speeds (the API reports km/h)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WindUnit {
+    #[default]
+    Kmh,
+    Mph,
+    Knots,
+    Ms,
+}
+
+impl WindUnit {
+    pub const ALL: [WindUnit; 4] = [WindUnit::Kmh, WindUnit::Mph, WindUnit::Knots, WindUnit::Ms];
+
+    /// Unit symbol, e.g. "kn"
+    pub fn label(&self) -> &'static str {
+        match self {
+            WindUnit::Kmh => "km/h",
+            WindUnit::Mph => "mph",
+            WindUnit::Knots => "kn",
+            WindUnit::Ms => "m/s",
+        }
+    }
+
+    /// Converts a speed in km/h to this unit
+    pub fn from_kmh(&self, kmh: f64) -> f64 {
+        match self {
+            WindUnit::Kmh => kmh,
+            WindUnit::Mph => Speed::kmh_to_mph(kmh),
+            WindUnit::Knots => Speed::kmh_to_knots(kmh),
+            WindUnit::Ms => Speed::kmh_to_ms(kmh),
+        }
+    }
+}
+
+impl fmt::Display for WindUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            WindUnit::Kmh => "kmh",
+            WindUnit::Mph => "mph",
+            WindUnit::Knots => "knots",
+            WindUnit::Ms => "ms",
+        })
+    }
+}
+
+impl FromStr for WindUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().replace('/', "").as_str() {
+            "kmh" | "kph" => Ok(WindUnit::Kmh),
+            "mph" => Ok(WindUnit::Mph),
+            "knots" | "knot" | "kn" | "kt" => Ok(WindUnit::Knots),
+            "ms" => Ok(WindUnit::Ms),
+            other => Err(format!(
+                "unknown wind unit '{other}' (expected kmh, mph, knots or ms)"
+            )),
+        }
+    }
+}
+
+/// Which pressure the views show: reduced to sea level, as weather maps and
+/// most forecasts give it, or measured at the surface, which reads low at
+/// altitude (about 830 hPa in Denver)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PressureReference {
+    #[default]
+    SeaLevel,
+    Surface,
+}
+
+impl PressureReference {
+    pub const ALL: [PressureReference; 2] =
+        [PressureReference::SeaLevel, PressureReference::Surface];
+
+    /// Qualifier for a pressure label, e.g. "sea level"
+    pub fn label(&self) -> &'static str {
+        match self {
+            PressureReference::SeaLevel => "sea level",
+            PressureReference::Surface => "surface",
+        }
+    }
+}
+
+impl fmt::Display for PressureReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PressureReference::SeaLevel => "msl",
+            PressureReference::Surface => "surface",
+        })
+    }
+}
+
+impl FromStr for PressureReference {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().replace(' ', "-").as_str() {
+            "msl" | "sea-level" | "sea" => Ok(PressureReference::SeaLevel),
+            "surface" | "station" => Ok(PressureReference::Surface),
+            other => Err(format!(
+                "unknown pressure '{other}' (expected msl or surface)"
+            )),
+        }
+    }
+}
+
+/// Compass directions
+pub struct Direction;
+
+impl Direction {
+    /// Eight-point compass name for a bearing in degrees, e.g. 225 → "SW"
+    pub fn compass_point(degrees: f64) -> &'static str {
+        let normalized = ((degrees % 360.0) + 360.0) % 360.0;
+        match normalized {
+            d if !(22.5..337.5).contains(&d) => "N",
+            d if (22.5..67.5).contains(&d) => "NE",
+            d if (67.5..112.5).contains(&d) => "E",
+            d if (112.5..157.5).contains(&d) => "SE",
+            d if (157.5..202.5).contains(&d) => "S",
+            d if (202.5..247.5).contains(&d) => "SW",
+            d if (247.5..292.5).contains(&d) => "W",
+            d if (292.5..337.5).contains(&d) => "NW",
+            _ => "Unknown",
+        }
+    }
+
+    /// Bearing in degrees of a 16-point compass name, e.g. "SSW" → 202.5
+    pub fn bearing(point: &str) -> Option<f64> {
+        const POINTS: [&str; 16] = [
+            "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW", "WSW", "W", "WNW",
+            "NW", "NNW",
+        ];
+        let point = point.trim().to_ascii_uppercase();
+        POINTS
+            .iter()
+            .position(|p| *p == point)
+            .map(|i| i as f64 * 22.5)
+    }
+
+    /// Arrow pointing downwind for wind from a bearing, e.g. 225 (from SW) → "↗"
+    pub fn arrow(degrees: f64) -> &'static str {
+        match Self::compass_point(degrees) {
+            "N" => "↓",
+            "NE" => "↙",
+            "E" => "←",
+            "SE" => "↖",
+            "S" => "↑",
+            "SW" => "↗",
+            "W" => "→",
+            "NW" => "↘",
+            _ => "·",
+        }
+    }
 }
 
 /// Pressure conversions
@@ -41,6 +292,14 @@ impl Pressure {
     pub fn hpa_to_inhg(hpa: f64) -> f64 {
         hpa * 0.02953
     }
+
+    pub fn hpa_to_pa(hpa: f64) -> f64 {
+        hpa * 100.0
+    }
+
+    pub fn hpa_to_mmhg(hpa: f64) -> f64 {
+        hpa * 0.750062
+    }
 }
 
 #[cfg(test)]
@@ -53,18 +312,156 @@ mod tests {
         assert_eq!(Temperature::celsius_to_fahrenheit(100.0), 212.0);
     }
 
+    #[test]
+    fn test_si_conversions() {
+        assert_eq!(Temperature::celsius_to_kelvin(0.0), 273.15);
+        assert_eq!(Temperature::celsius_to_kelvin(-273.15), 0.0);
+        assert!((Temperature::kelvin_to_celsius(281.95) - 8.8).abs() < 1e-9);
+        assert_eq!(Speed::kmh_to_ms(36.0), 10.0);
+        assert_eq!(Pressure::hpa_to_pa(1013.25), 101_325.0);
+        assert_eq!(Distance::cm_to_meters(45.0), 0.45);
+    }
+
+    #[test]
+    fn test_wind_units() {
+        assert_eq!(WindUnit::Knots.from_kmh(18.52), 10.0);
+        assert_eq!(WindUnit::Ms.from_kmh(36.0), 10.0);
+        assert_eq!(WindUnit::Kmh.from_kmh(12.0), 12.0);
+        for unit in WindUnit::ALL {
+            assert_eq!(unit.to_string().parse::<WindUnit>(), Ok(unit));
+            assert_eq!(unit.label().parse::<WindUnit>(), Ok(unit));
+        }
+        assert_eq!("KT".parse::<WindUnit>(), Ok(WindUnit::Knots));
+        assert!("furlongs".parse::<WindUnit>().is_err());
+    }
+
+    #[test]
+    fn test_pressure_reference_parsing() {
+        assert_eq!(PressureReference::default(), PressureReference::SeaLevel);
+        for reference in PressureReference::ALL {
+            let parsed = reference.to_string().parse::<PressureReference>();
+            assert_eq!(parsed, Ok(reference));
+            assert_eq!(
+                reference.label().parse::<PressureReference>(),
+                Ok(reference)
+            );
+        }
+        assert_eq!(
+            "Station".parse::<PressureReference>(),
+            Ok(PressureReference::Surface)
+        );
+        assert!("qnh".parse::<PressureReference>().is_err());
+    }
+
+    #[test]
+    fn test_compass_point() {
+        assert_eq!(Direction::compass_point(0.0), "N");
+        assert_eq!(Direction::compass_point(350.0), "N");
+        assert_eq!(Direction::compass_point(225.0), "SW");
+        assert_eq!(Direction::compass_point(-90.0), "W");
+        assert_eq!(Direction::compass_point(450.0), "E");
+    }
+
+    #[test]
+    fn test_bearing_of_compass_names() {
+        assert_eq!(Direction::bearing("N"), Some(0.0));
+        assert_eq!(Direction::bearing("ssw"), Some(202.5));
+        assert_eq!(Direction::bearing("NW"), Some(315.0));
+        assert_eq!(Direction::bearing("Variable"), None);
+        for degrees in [0.0, 45.0, 225.0, 315.0] {
+            let point = Direction::compass_point(degrees);
+            assert_eq!(Direction::bearing(point), Some(degrees));
+        }
+    }
+
+    #[test]
+    fn test_arrow_matches_compass_bucket() {
+        for (degrees, point, arrow) in [
+            (0.0, "N", "↓"),
+            (22.4, "N", "↓"),
+            (22.5, "NE", "↙"),
+            (135.0, "SE", "↖"),
+            (200.0, "S", "↑"),
+            (225.0, "SW", "↗"),
+            (-45.0, "NW", "↘"),
+            (337.5, "N", "↓"),
+        ] {
+            assert_eq!(Direction::compass_point(degrees), point, "{degrees}°");
+            assert_eq!(Direction::arrow(degrees), arrow, "{degrees}°");
+        }
+    }
+
+    #[test]
+    fn test_humidex_matches_environment_canada_table() {
+        // Air temperature, dew point, published humidex
+        for (temp, dew_point, expected) in [
+            (30.0, 15.0, 34.0),
+            (30.0, 20.0, 38.0),
+            (30.0, 25.0, 42.0),
+            (35.0, 25.0, 47.0),
+            (25.0, 20.0, 33.0),
+        ] {
+            assert_eq!(Temperature::humidex(temp, dew_point).round(), expected);
+        }
+    }
+
+    #[test]
+    fn test_wind_chill_matches_environment_canada_table() {
+        // Air temperature, wind speed, published wind chill
+        for (temp, wind, expected) in [
+            (0.0, 10.0, -3.0),
+            (-10.0, 20.0, -18.0),
+            (-20.0, 30.0, -33.0),
+            (-30.0, 50.0, -49.0),
+        ] {
+            assert_eq!(Temperature::wind_chill(temp, wind).round(), expected);
+        }
+        // Undefined in calm air and above 10°C
+        assert_eq!(Temperature::wind_chill(-10.0, 3.0), -10.0);
+        assert_eq!(Temperature::wind_chill(15.0, 40.0), 15.0);
+    }
+
+    #[test]
+    fn test_dew_point() {
+        assert!((Temperature::dew_point(30.0, 100.0) - 30.0).abs() < 0.01);
+        assert!((Temperature::dew_point(25.0, 50.0) - 13.9).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_heat_index() {
+        // 90°F at 70% RH is 105°F (40.6°C) in the NWS table
+        assert!((Temperature::heat_index(32.2, 70.0) - 40.6).abs() < 0.5);
+        // Mild conditions stay close to the air temperature
+        assert!((Temperature::heat_index(20.0, 50.0) - 20.0).abs() < 1.0);
+    }
+
     #[test]
     fn test_kmh_to_mph() {
         let result = Speed::kmh_to_mph(100.0);
         assert!((result - 62.1371).abs() < 0.0001);
     }
 
+    #[test]
+    fn test_mph_to_kmh() {
+        let result = Speed::mph_to_kmh(15.0);
+        assert!((result - 24.14016).abs() < 0.0001);
+        assert!((Speed::kmh_to_mph(Speed::mph_to_kmh(40.0)) - 40.0).abs() < 0.001);
+        assert!((Speed::ms_to_kmh(4.12) - 14.832).abs() < 1e-9);
+        assert!((Speed::kmh_to_ms(Speed::ms_to_kmh(7.5)) - 7.5).abs() < 1e-9);
+    }
+
     #[test]
     fn test_mm_to_inches() {
         let result = Distance::mm_to_inches(25.4);
         assert!((result - 1.0).abs() < 0.001);
     }
 
+    #[test]
+    fn test_cm_to_inches() {
+        let result = Distance::cm_to_inches(2.54);
+        assert!((result - 1.0).abs() < 0.001);
+    }
+
     #[test]
     fn test_meters_to_feet() {
         let result = Distance::meters_to_feet(1.0);
@@ -76,4 +473,11 @@ mod tests {
         let result = Pressure::hpa_to_inhg(1013.25);
         assert!((result - 29.92).abs() < 0.01);
     }
+
+    #[test]
+    fn test_hpa_to_mmhg() {
+        // One standard atmosphere is 760 mmHg
+        let result = Pressure::hpa_to_mmhg(1013.25);
+        assert!((result - 760.0).abs() < 0.01);
+    }
 }
@@ -0,0 +1,387 @@
+//! Coordinates typed or pasted in place of a place name
+//!
+//! Accepts decimal degrees with signs or hemisphere letters ("47.62 N, 122.33 W"),
+//! degrees-minutes-seconds (`47°37'12"N 122°19'48"W`), surrounding parentheses
+//! and comma, semicolon, slash or whitespace separators. Anything that could be
+//! read more than one way is rejected so the input falls through to geocoding.
+
+use std::fmt;
+
+/// Mean radius of the Earth
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// A point in decimal degrees, north and east positive
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Coordinates {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl Coordinates {
+    /// `None` outside ±90° latitude or ±180° longitude; −180° becomes 180°
+    pub fn new(latitude: f64, longitude: f64) -> Option<Self> {
+        ((-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude)).then_some(
+            Coordinates {
+                latitude,
+                longitude: normalize_longitude(longitude),
+            },
+        )
+    }
+
+    /// Great-circle distance in km, by the haversine formula on a sphere
+    pub fn distance_km(&self, other: &Coordinates) -> f64 {
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let half_lat = (lat2 - lat1) / 2.0;
+        let half_lon = (other.longitude - self.longitude).to_radians() / 2.0;
+        let a = half_lat.sin().powi(2) + lat1.cos() * lat2.cos() * half_lon.sin().powi(2);
+        2.0 * EARTH_RADIUS_KM * a.sqrt().min(1.0).asin()
+    }
+}
+
+/// Longitude wrapped into (−180°, 180°], so both sides of the antimeridian
+/// and values past it (190° → −170°) compare and display alike
+pub fn normalize_longitude(longitude: f64) -> f64 {
+    // Values already in range are returned untouched, without float error
+    if longitude > -180.0 && longitude <= 180.0 {
+        return longitude;
+    }
+    let wrapped = (longitude + 180.0).rem_euclid(360.0) - 180.0;
+    if wrapped <= -180.0 {
+        180.0
+    } else {
+        wrapped
+    }
+}
+
+/// "47.6205°N, 122.3493°W"; values are rounded before the hemisphere is
+/// chosen, so −179.99999° shows as 180.0000°E and −0.00001° as 0.0000°N
+impl fmt::Display for Coordinates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let round = |degrees: f64| (degrees * 1e4).round() / 1e4 + 0.0;
+        let latitude = round(self.latitude);
+        let longitude = normalize_longitude(round(self.longitude));
+        let north_south = if latitude < 0.0 { 'S' } else { 'N' };
+        let east_west = if longitude < 0.0 { 'W' } else { 'E' };
+        write!(
+            f,
+            "{:.4}°{north_south}, {:.4}°{east_west}",
+            latitude.abs(),
+            longitude.abs()
+        )
+    }
+}
+
+/// Lexical pieces of a coordinate string
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Degrees,
+    Minutes,
+    Seconds,
+    Hemisphere(char),
+    Separator,
+}
+
+fn tokenize(input: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            _ if c.is_whitespace() => {
+                chars.next();
+            }
+            '0'..='9' | '.' | '+' | '-' | '−' => {
+                let mut number = String::new();
+                if let Some(sign) = chars.next_if(|c| matches!(c, '+' | '-' | '−')) {
+                    number.push(if sign == '+' { '+' } else { '-' });
+                }
+                while let Some(digit) = chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+                    number.push(digit);
+                }
+                tokens.push(Token::Number(number.parse().ok()?));
+            }
+            '°' | 'º' => {
+                chars.next();
+                tokens.push(Token::Degrees);
+            }
+            '\'' | '′' | '’' => {
+                chars.next();
+                // Two apostrophes stand in for a double quote
+                if chars.next_if(|c| matches!(c, '\'' | '′' | '’')).is_some() {
+                    tokens.push(Token::Seconds);
+                } else {
+                    tokens.push(Token::Minutes);
+                }
+            }
+            '"' | '″' | '”' => {
+                chars.next();
+                tokens.push(Token::Seconds);
+            }
+            ',' | ';' | '/' => {
+                chars.next();
+                tokens.push(Token::Separator);
+            }
+            _ => {
+                chars.next();
+                let hemisphere = c.to_ascii_uppercase();
+                // A letter followed by another letter is a word, not a hemisphere
+                if !matches!(hemisphere, 'N' | 'S' | 'E' | 'W')
+                    || chars.peek().is_some_and(|c| c.is_alphabetic())
+                {
+                    return None;
+                }
+                tokens.push(Token::Hemisphere(hemisphere));
+            }
+        }
+    }
+    Some(tokens)
+}
+
+/// One axis: its unsigned angle, sign and hemisphere letter if any
+struct Component {
+    value: f64,
+    hemisphere: Option<char>,
+}
+
+/// Removes and returns the first token if it satisfies `expected`
+fn take(tokens: &mut &[Token], expected: fn(&Token) -> bool) -> Option<Token> {
+    let (first, rest) = tokens.split_first()?;
+    expected(first).then(|| {
+        *tokens = rest;
+        *first
+    })
+}
+
+fn take_number(tokens: &mut &[Token]) -> Option<f64> {
+    match take(tokens, |t| matches!(t, Token::Number(_))) {
+        Some(Token::Number(n)) => Some(n),
+        _ => None,
+    }
+}
+
+fn take_hemisphere(tokens: &mut &[Token]) -> Option<char> {
+    match take(tokens, |t| matches!(t, Token::Hemisphere(_))) {
+        Some(Token::Hemisphere(h)) => Some(h),
+        _ => None,
+    }
+}
+
+/// Reads `number mark` for minutes or seconds below 60; `Ok(None)` if no
+/// number follows, `Err` for a number without its mark or out of range
+fn take_fraction(tokens: &mut &[Token], mark: Token) -> Result<Option<f64>, ()> {
+    let snapshot = *tokens;
+    let Some(value) = take_number(tokens) else {
+        *tokens = snapshot;
+        return Ok(None);
+    };
+    match tokens.split_first() {
+        Some((token, rest)) if *token == mark && (0.0..60.0).contains(&value) => {
+            *tokens = rest;
+            Ok(Some(value))
+        }
+        _ => Err(()),
+    }
+}
+
+/// Reads `[H] deg[°] [min' [sec"]] [H]` from the front of `tokens`
+fn component(tokens: &mut &[Token]) -> Option<Component> {
+    let leading = take_hemisphere(tokens);
+    let degrees = take_number(tokens)?;
+    let mut value = degrees.abs();
+    // Minutes and seconds only follow an explicit degree sign
+    if take(tokens, |t| *t == Token::Degrees).is_some() {
+        if let Some(minutes) = take_fraction(tokens, Token::Minutes).ok()? {
+            value += minutes / 60.0;
+            if let Some(seconds) = take_fraction(tokens, Token::Seconds).ok()? {
+                value += seconds / 3600.0;
+            }
+        }
+    }
+    // After a leading letter, the next letter belongs to the other component
+    let hemisphere = leading.or_else(|| take_hemisphere(tokens));
+    // "-47 S" could mean either hemisphere
+    if hemisphere.is_some() && degrees.is_sign_negative() {
+        return None;
+    }
+    let negative = degrees.is_sign_negative() || matches!(hemisphere, Some('S' | 'W'));
+    Some(Component {
+        value: if negative { -value } else { value },
+        hemisphere,
+    })
+}
+
+/// Coordinates in `input`, or `None` if it is not unambiguously a coordinate pair
+pub fn parse(input: &str) -> Option<Coordinates> {
+    let mut input = input.trim();
+    for (open, close) in [('(', ')'), ('[', ']')] {
+        if let Some(inner) = input
+            .strip_prefix(open)
+            .and_then(|rest| rest.strip_suffix(close))
+        {
+            input = inner.trim();
+        }
+    }
+
+    let tokens = tokenize(input)?;
+    let mut rest = &tokens[..];
+    let first = component(&mut rest)?;
+    if let Some((Token::Separator, after)) = rest.split_first() {
+        rest = after;
+    }
+    let second = component(&mut rest)?;
+    if !rest.is_empty() {
+        return None;
+    }
+
+    let is_latitude = |h: char| matches!(h, 'N' | 'S');
+    // Without letters the order is latitude, longitude
+    let latitude_first = match (first.hemisphere, second.hemisphere) {
+        (Some(a), Some(b)) if is_latitude(a) == is_latitude(b) => return None,
+        (Some(a), _) => is_latitude(a),
+        (None, Some(b)) => !is_latitude(b),
+        (None, None) => true,
+    };
+    if latitude_first {
+        Coordinates::new(first.value, second.value)
+    } else {
+        Coordinates::new(second.value, first.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_parses(input: &str, latitude: f64, longitude: f64) {
+        let coordinates = parse(input).unwrap_or_else(|| panic!("{input:?} did not parse"));
+        assert!(
+            (coordinates.latitude - latitude).abs() < 1e-4
+                && (coordinates.longitude - longitude).abs() < 1e-4,
+            "{input:?} parsed as {coordinates:?}"
+        );
+    }
+
+    #[test]
+    fn test_distance() {
+        let paris = Coordinates::new(48.8566, 2.3522).unwrap();
+        let london = Coordinates::new(51.5074, -0.1278).unwrap();
+        assert!((paris.distance_km(&london) - 343.5).abs() < 1.0);
+        assert_eq!(paris.distance_km(&paris), 0.0);
+        // Across the antimeridian the short way round
+        let east = Coordinates::new(0.0, 179.9).unwrap();
+        let west = Coordinates::new(0.0, -179.9).unwrap();
+        assert!((east.distance_km(&west) - 22.24).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_decimal_degrees() {
+        assert_parses("47.6205,-122.3493", 47.6205, -122.3493);
+        assert_parses("47.6205, -122.3493", 47.6205, -122.3493);
+        assert_parses("(47.6205, -122.3493)", 47.6205, -122.3493);
+        assert_parses("[47.6205 -122.3493]", 47.6205, -122.3493);
+        assert_parses("47.6205; -122.3493", 47.6205, -122.3493);
+        assert_parses("47.6205 / -122.3493", 47.6205, -122.3493);
+        assert_parses("-33.8688 151.2093", -33.8688, 151.2093);
+        assert_parses("+51.5 −0.12", 51.5, -0.12);
+        assert_parses("0,0", 0.0, 0.0);
+    }
+
+    #[test]
+    fn test_hemisphere_letters() {
+        assert_parses("47.62 N, 122.33 W", 47.62, -122.33);
+        assert_parses("47.62N 122.33W", 47.62, -122.33);
+        assert_parses("N47.62 W122.33", 47.62, -122.33);
+        assert_parses("33.87 s, 151.21 e", -33.87, 151.21);
+        // Letters say which number is which
+        assert_parses("122.33 W, 47.62 N", 47.62, -122.33);
+        assert_parses("-122.33, 47.62 N", 47.62, -122.33);
+        assert_parses("47.62 N, -122.33", 47.62, -122.33);
+    }
+
+    #[test]
+    fn test_degrees_minutes_seconds() {
+        assert_parses("47°37'12\"N 122°19'48\"W", 47.62, -122.33);
+        assert_parses("47° 37′ 12″ N, 122° 19′ 48″ W", 47.62, -122.33);
+        assert_parses("47°37'12''N 122°19'48''W", 47.62, -122.33);
+        assert_parses("47°37.2'N 122°19.8'W", 47.62, -122.33);
+        assert_parses("47°N 122°W", 47.0, -122.0);
+        assert_parses("S 33°52' E 151°12'", -33.8667, 151.2);
+    }
+
+    #[test]
+    fn test_ambiguous_or_invalid_input_is_rejected() {
+        for input in [
+            "",
+            "Seattle",
+            "New York",
+            "Route 66",
+            "47.62",
+            // Decimal commas: is it 47.62, 122.33 or 47, 62.122?
+            "47,62, 122,33",
+            // Out of range, and not swapped on a guess
+            "122.33, 47.62",
+            "91 0",
+            "0 181",
+            // Both numbers on the same axis
+            "47.62 N, 12.5 S",
+            "10 E 20 W",
+            // Sign and hemisphere disagree or both given
+            "-47.62 S, 122.33 W",
+            "N 47.62 N, 122.33 W",
+            // Minutes without a degree sign, or out of range
+            "47 37 12 N, 122 19 48 W",
+            "47°75'N 122°W",
+            "47°37N 122°W",
+            "1, 2, 3",
+            "47.62.1, 3",
+        ] {
+            assert_eq!(parse(input), None, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn test_display_uses_hemispheres() {
+        let seattle = Coordinates::new(47.6205, -122.3493).unwrap();
+        assert_eq!(seattle.to_string(), "47.6205°N, 122.3493°W");
+        let sydney = Coordinates::new(-33.8688, 151.2093).unwrap();
+        assert_eq!(sydney.to_string(), "33.8688°S, 151.2093°E");
+        assert_eq!(Coordinates::new(91.0, 0.0), None);
+    }
+
+    #[test]
+    fn test_antimeridian_and_poles() {
+        assert_eq!(normalize_longitude(180.0), 180.0);
+        assert_eq!(normalize_longitude(-180.0), 180.0);
+        assert_eq!(normalize_longitude(190.0), -170.0);
+        assert_eq!(normalize_longitude(-190.0), 170.0);
+        assert_eq!(normalize_longitude(540.0), 180.0);
+        assert_eq!(normalize_longitude(-122.5), -122.5);
+
+        // Both spellings of the antimeridian are the same place
+        let west = Coordinates::new(-17.0, -180.0).unwrap();
+        assert_eq!(west, Coordinates::new(-17.0, 180.0).unwrap());
+        assert_eq!(west.to_string(), "17.0000°S, 180.0000°E");
+        assert_parses("16.5 S, 180 W", -16.5, 180.0);
+
+        // Rounding up to the seam does not flip the hemisphere
+        let fiji = Coordinates {
+            latitude: -16.5,
+            longitude: -179.99999,
+        };
+        assert_eq!(fiji.to_string(), "16.5000°S, 180.0000°E");
+        let equator = Coordinates {
+            latitude: -0.00001,
+            longitude: 0.0,
+        };
+        assert_eq!(equator.to_string(), "0.0000°N, 0.0000°E");
+
+        assert_eq!(
+            Coordinates::new(90.0, 0.0).unwrap().to_string(),
+            "90.0000°N, 0.0000°E"
+        );
+        assert_eq!(
+            Coordinates::new(-90.0, 45.0).unwrap().to_string(),
+            "90.0000°S, 45.0000°E"
+        );
+    }
+}
@@ -0,0 +1,349 @@
+//! Polar day and polar night
+//!
+//! Beyond the polar circles the sun stays above or below the horizon for
+//! days to months, and the forecast then has no sunrise or sunset for those
+//! dates (or reports both at midnight). The sun's declination tells which of
+//! the two it is, so views can say "polar night" instead of a blank.
+//!
+//! The same geometry places an hour of the hourly forecast in daylight or
+//! darkness when the forecast lacks that day's sunrise and sunset.
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+use serde::Serialize;
+
+/// Earth's axial tilt in degrees
+const AXIAL_TILT: f64 = 23.44;
+
+/// Degrees the sun's upper edge shows above the geometric horizon because of
+/// refraction and its apparent radius, as used for published sunrise times
+const HORIZON_ALLOWANCE: f64 = 0.83;
+
+/// Whether the sun rises and sets on a day
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Daylight {
+    /// At least one sunrise or sunset
+    RisesAndSets,
+    /// The sun stays up all day
+    PolarDay,
+    /// The sun stays down all day
+    PolarNight,
+}
+
+impl Daylight {
+    /// Text for the views, `None` for an ordinary day
+    pub fn label(self) -> Option<&'static str> {
+        match self {
+            Daylight::RisesAndSets => None,
+            Daylight::PolarDay => Some("polar day"),
+            Daylight::PolarNight => Some("polar night"),
+        }
+    }
+}
+
+/// Whether an hour of the forecast is light or dark, for its icon
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HourLight {
+    Day,
+    Night,
+    /// The sun rises during the hour
+    Sunrise,
+    /// The sun sets during the hour
+    Sunset,
+}
+
+/// Solar declination in degrees on `date`, accurate to about a degree
+pub fn solar_declination(date: NaiveDate) -> f64 {
+    let day_of_year = f64::from(date.ordinal());
+    AXIAL_TILT * (360.0 / 365.0 * (284.0 + day_of_year)).to_radians().sin()
+}
+
+/// Polar day or night at `latitude` on `date` from the sun's declination
+/// alone; days close to the threshold may come out either way, so prefer
+/// [`daylight`] when sunrise and sunset times are at hand
+pub fn polar_daylight(latitude: f64, date: NaiveDate) -> Daylight {
+    let declination = solar_declination(date);
+    // Highest and lowest altitude of the sun's center over the day
+    let noon = 90.0 - (latitude - declination).abs();
+    let midnight = (latitude + declination).abs() - 90.0;
+    if midnight > -HORIZON_ALLOWANCE {
+        Daylight::PolarDay
+    } else if noon < -HORIZON_ALLOWANCE {
+        Daylight::PolarNight
+    } else {
+        Daylight::RisesAndSets
+    }
+}
+
+/// Daylight for a forecast day: an ordinary day whenever the forecast has a
+/// distinct sunrise or sunset, otherwise decided by [`polar_daylight`]
+pub fn daylight(
+    sunrise: Option<&str>,
+    sunset: Option<&str>,
+    date: NaiveDate,
+    latitude: f64,
+) -> Daylight {
+    match (sunrise, sunset) {
+        (Some(sunrise), Some(sunset)) if sunrise != sunset => Daylight::RisesAndSets,
+        (Some(_), None) | (None, Some(_)) => Daylight::RisesAndSets,
+        _ => polar_daylight(latitude, date),
+    }
+}
+
+/// Sunrise and sunset on `date` at `latitude` from the sun's hour angle,
+/// taking solar noon as 12:00 local time; good to within half an hour or so
+/// for most places and `None` on days of polar day or night
+pub fn approximate_sun_times(
+    latitude: f64,
+    date: NaiveDate,
+) -> Option<(NaiveDateTime, NaiveDateTime)> {
+    let (latitude, declination) = (latitude.to_radians(), solar_declination(date).to_radians());
+    let cos_hour_angle = ((-HORIZON_ALLOWANCE).to_radians().sin()
+        - latitude.sin() * declination.sin())
+        / (latitude.cos() * declination.cos());
+    if !(-1.0..=1.0).contains(&cos_hour_angle) {
+        return None;
+    }
+    // 15° of hour angle per hour, in seconds either side of noon
+    let half_day = TimeDelta::seconds((cos_hour_angle.acos().to_degrees() * 240.0).round() as i64);
+    let noon = date.and_time(NaiveTime::from_hms_opt(12, 0, 0)?);
+    Some((noon - half_day, noon + half_day))
+}
+
+/// Light for the hour starting at `start`: the hour in which the sun rises or
+/// sets is a transition hour, any other is day or night by its midpoint.
+/// Without the day's sunrise and sunset (or with both at the same instant,
+/// the forecast's way of saying neither happens) they are approximated from
+/// `latitude`.
+pub fn hour_light(
+    start: NaiveDateTime,
+    sunrise: Option<NaiveDateTime>,
+    sunset: Option<NaiveDateTime>,
+    latitude: f64,
+) -> HourLight {
+    let end = start + TimeDelta::hours(1);
+    let midpoint = start + TimeDelta::minutes(30);
+    let within = |time: NaiveDateTime| start <= time && time < end;
+
+    let (sunrise, sunset) = match (sunrise, sunset) {
+        (Some(sunrise), Some(sunset)) if sunrise == sunset => (None, None),
+        times => times,
+    };
+    match (sunrise, sunset) {
+        (Some(sunrise), _) if within(sunrise) => HourLight::Sunrise,
+        (_, Some(sunset)) if within(sunset) => HourLight::Sunset,
+        (Some(sunrise), Some(sunset)) => {
+            let after_sunrise = midpoint >= sunrise;
+            let before_sunset = midpoint < sunset;
+            // A sunset before the sunrise is the end of the previous night's
+            // daylight, as on days around the midnight sun
+            let day = if sunrise <= sunset {
+                after_sunrise && before_sunset
+            } else {
+                after_sunrise || before_sunset
+            };
+            if day {
+                HourLight::Day
+            } else {
+                HourLight::Night
+            }
+        }
+        (Some(sunrise), None) if midpoint >= sunrise => HourLight::Day,
+        (Some(_), None) => HourLight::Night,
+        (None, Some(sunset)) if midpoint < sunset => HourLight::Day,
+        (None, Some(_)) => HourLight::Night,
+        (None, None) => {
+            let date = start.date();
+            match approximate_sun_times(latitude, date) {
+                Some((sunrise, sunset)) => hour_light(start, Some(sunrise), Some(sunset), latitude),
+                None if polar_daylight(latitude, date) == Daylight::PolarDay => HourLight::Day,
+                None => HourLight::Night,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_declination_at_solstices_and_equinox() {
+        assert!((solar_declination(date("2024-06-21")) - AXIAL_TILT).abs() < 0.5);
+        assert!((solar_declination(date("2024-12-21")) + AXIAL_TILT).abs() < 0.5);
+        assert!(solar_declination(date("2024-03-21")).abs() < 1.5);
+    }
+
+    #[test]
+    fn test_polar_day_and_night() {
+        // Longyearbyen, Svalbard (78.2°N): midnight sun and polar night
+        assert_eq!(
+            polar_daylight(78.22, date("2024-06-21")),
+            Daylight::PolarDay
+        );
+        assert_eq!(
+            polar_daylight(78.22, date("2024-12-21")),
+            Daylight::PolarNight
+        );
+        assert_eq!(
+            polar_daylight(78.22, date("2024-03-21")),
+            Daylight::RisesAndSets
+        );
+        // McMurdo Station (77.8°S): the other way round
+        assert_eq!(
+            polar_daylight(-77.85, date("2024-06-21")),
+            Daylight::PolarNight
+        );
+        assert_eq!(
+            polar_daylight(-77.85, date("2024-12-21")),
+            Daylight::PolarDay
+        );
+        // The poles themselves, and the equator never
+        assert_eq!(polar_daylight(90.0, date("2024-06-21")), Daylight::PolarDay);
+        assert_eq!(
+            polar_daylight(-90.0, date("2024-06-21")),
+            Daylight::PolarNight
+        );
+        assert_eq!(
+            polar_daylight(0.0, date("2024-06-21")),
+            Daylight::RisesAndSets
+        );
+        // Tromsø (69.6°N) keeps the sun through the whole June night
+        assert_eq!(
+            polar_daylight(69.65, date("2024-06-21")),
+            Daylight::PolarDay
+        );
+        assert_eq!(
+            polar_daylight(60.0, date("2024-06-21")),
+            Daylight::RisesAndSets
+        );
+    }
+
+    #[test]
+    fn test_forecast_times_take_precedence() {
+        let june = date("2024-06-21");
+        assert_eq!(
+            daylight(
+                Some("2024-06-21T03:50"),
+                Some("2024-06-21T22:10"),
+                june,
+                78.22
+            ),
+            Daylight::RisesAndSets
+        );
+        // The last sunset before the midnight sun has no sunrise that day
+        assert_eq!(
+            daylight(None, Some("2024-06-21T23:58"), june, 69.65),
+            Daylight::RisesAndSets
+        );
+        assert_eq!(daylight(None, None, june, 78.22), Daylight::PolarDay);
+        // Both reported at midnight means neither happens
+        let midnight = Some("2024-12-21T00:00");
+        assert_eq!(
+            daylight(midnight, midnight, date("2024-12-21"), 78.22),
+            Daylight::PolarNight
+        );
+        assert_eq!(Daylight::PolarNight.label(), Some("polar night"));
+        assert_eq!(Daylight::RisesAndSets.label(), None);
+    }
+
+    #[test]
+    fn test_hour_light_by_midpoint_and_transition() {
+        let sunrise = Some(at("2024-06-01T05:50"));
+        let sunset = Some(at("2024-06-01T21:10"));
+        let light = |hour: &str| hour_light(at(hour), sunrise, sunset, 52.5);
+
+        // The hour before the sunrise hour is dark, not lit by its sunrise
+        assert_eq!(light("2024-06-01T04:00"), HourLight::Night);
+        assert_eq!(light("2024-06-01T05:00"), HourLight::Sunrise);
+        assert_eq!(light("2024-06-01T06:00"), HourLight::Day);
+        assert_eq!(light("2024-06-01T20:00"), HourLight::Day);
+        assert_eq!(light("2024-06-01T21:00"), HourLight::Sunset);
+        assert_eq!(light("2024-06-01T22:00"), HourLight::Night);
+        assert_eq!(light("2024-06-01T00:00"), HourLight::Night);
+    }
+
+    #[test]
+    fn test_hour_light_at_exact_boundaries() {
+        // An event on the hour belongs to the hour it starts, not the one it ends
+        let sunrise = Some(at("2024-03-20T06:00"));
+        let sunset = Some(at("2024-03-20T18:00"));
+        let light = |hour: &str| hour_light(at(hour), sunrise, sunset, 0.0);
+        assert_eq!(light("2024-03-20T05:00"), HourLight::Night);
+        assert_eq!(light("2024-03-20T06:00"), HourLight::Sunrise);
+        assert_eq!(light("2024-03-20T17:00"), HourLight::Day);
+        assert_eq!(light("2024-03-20T18:00"), HourLight::Sunset);
+
+        // Events on the half hour: the midpoint rule never decides a
+        // transition hour, and the neighbours fall on the right side
+        let sunrise = Some(at("2024-03-20T06:30"));
+        let sunset = Some(at("2024-03-20T18:30"));
+        let light = |hour: &str| hour_light(at(hour), sunrise, sunset, 0.0);
+        assert_eq!(light("2024-03-20T05:00"), HourLight::Night);
+        assert_eq!(light("2024-03-20T06:00"), HourLight::Sunrise);
+        assert_eq!(light("2024-03-20T07:00"), HourLight::Day);
+        assert_eq!(light("2024-03-20T18:00"), HourLight::Sunset);
+        assert_eq!(light("2024-03-20T19:00"), HourLight::Night);
+    }
+
+    #[test]
+    fn test_hour_light_with_one_event() {
+        // The last sunset before the midnight sun, with no sunrise that day
+        let sunset = Some(at("2024-05-18T23:58"));
+        assert_eq!(
+            hour_light(at("2024-05-18T03:00"), None, sunset, 69.65),
+            HourLight::Day
+        );
+        assert_eq!(
+            hour_light(at("2024-05-18T23:00"), None, sunset, 69.65),
+            HourLight::Sunset
+        );
+        // A sunrise just after midnight following a night of a few minutes
+        let sunrise = Some(at("2024-07-27T00:10"));
+        assert_eq!(
+            hour_light(at("2024-07-27T00:00"), sunrise, None, 69.65),
+            HourLight::Sunrise
+        );
+        assert_eq!(
+            hour_light(at("2024-07-27T12:00"), sunrise, None, 69.65),
+            HourLight::Day
+        );
+    }
+
+    #[test]
+    fn test_hour_light_polar_and_approximated() {
+        // Midnight sun and polar night at Longyearbyen, with or without the
+        // forecast's both-at-midnight times
+        for times in [None, Some(at("2024-06-21T00:00"))] {
+            assert_eq!(
+                hour_light(at("2024-06-21T00:00"), times, times, 78.22),
+                HourLight::Day
+            );
+        }
+        assert_eq!(
+            hour_light(at("2024-12-21T12:00"), None, None, 78.22),
+            HourLight::Night
+        );
+
+        // On the equator at the equinox the sun rises and sets near six
+        let (sunrise, sunset) = approximate_sun_times(0.0, date("2024-03-20")).unwrap();
+        assert_eq!(sunrise.format("%H").to_string(), "05");
+        assert_eq!(sunset.format("%H").to_string(), "18");
+        let light = |hour: &str| hour_light(at(hour), None, None, 0.0);
+        assert_eq!(light("2024-03-20T03:00"), HourLight::Night);
+        assert_eq!(light("2024-03-20T05:00"), HourLight::Sunrise);
+        assert_eq!(light("2024-03-20T12:00"), HourLight::Day);
+        assert_eq!(light("2024-03-20T18:00"), HourLight::Sunset);
+        assert_eq!(light("2024-03-20T21:00"), HourLight::Night);
+        assert_eq!(approximate_sun_times(78.22, date("2024-06-21")), None);
+    }
+}
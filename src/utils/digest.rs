@@ -0,0 +1,267 @@
+//! Morning digest: locations ordered by how much their weather needs attention
+
+use chrono::{DateTime, Utc};
+
+use crate::constants::{DIGEST_PRECIPITATION_PROBABILITY, FROST_THRESHOLD_C};
+use crate::models::summary::WeatherSummary;
+use crate::models::weather_info::{HourlyForecast, WeatherInfo};
+use crate::utils::advisories::{self, AdvisoryStandard, Hazard, Severity};
+use crate::utils::format::{self, Precision};
+use crate::utils::storm::{self, StormSeverity};
+use crate::utils::time;
+
+/// How urgently a location's weather needs attention, most urgent first
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Urgency {
+    /// Thunderstorms today or a heat or cold warning
+    Severe,
+    /// Precipitation expected today
+    Wet,
+    Calm,
+}
+
+/// One location in the digest
+#[derive(Clone, Debug, PartialEq)]
+pub struct DigestEntry {
+    /// Name shown for the location, e.g. a favorite's title
+    pub name: String,
+    pub summary: WeatherSummary,
+    pub urgency: Urgency,
+    /// Why the location ranks where it does, e.g. "thunderstorms today";
+    /// `None` for calm weather
+    pub reason: Option<String>,
+    /// The single most important warning: storm, then heat or cold, then frost
+    pub callout: Option<String>,
+}
+
+impl DigestEntry {
+    /// Rates a location's weather for its own "today" as of `now`, with
+    /// heat and cold in the tiers of `standard`
+    pub fn assess(
+        name: impl Into<String>,
+        info: &WeatherInfo,
+        now: DateTime<Utc>,
+        standard: AdvisoryStandard,
+    ) -> Self {
+        let data = &info.weather_data;
+        let today = info
+            .today(now)
+            .or_else(|| data.daily.first().and_then(|d| time::parse_date(&d.date)));
+        let day = today
+            .and_then(|date| data.day(date))
+            .or_else(|| data.daily.first());
+
+        let hours_today: Vec<HourlyForecast> = today
+            .map(|date| data.hourly_on(date).cloned().collect())
+            .unwrap_or_default();
+        let storm = storm::thunderstorm_periods(&hours_today)
+            .into_iter()
+            .min_by_key(|p| p.severity != StormSeverity::Severe);
+        let advisory = advisories::advisory(&data.current, standard);
+        let frost = day
+            .and_then(|d| d.temperature_min)
+            .filter(|low| *low <= FROST_THRESHOLD_C);
+
+        let probability = day.and_then(|d| d.precipitation_probability);
+        let amount = day.and_then(|d| d.precipitation_sum).filter(|p| *p > 0.0);
+        let wet = match probability {
+            Some(p) => p >= DIGEST_PRECIPITATION_PROBABILITY,
+            None => amount.is_some(),
+        };
+
+        let (urgency, reason) = if storm.is_some() {
+            (Urgency::Severe, Some("thunderstorms today".to_string()))
+        } else if let Some(warning) = advisory.filter(|a| a.tier.severity == Severity::Warning) {
+            let reason = match warning.hazard {
+                Hazard::Heat => "dangerous heat",
+                Hazard::Cold => "dangerous cold",
+            };
+            (Urgency::Severe, Some(reason.to_string()))
+        } else if wet {
+            let reason = match (probability, amount) {
+                (Some(p), Some(mm)) => format!("precipitation {p:.0}%, {mm:.1} mm today"),
+                (Some(p), None) => format!("precipitation {p:.0}% today"),
+                (None, mm) => format!("{:.1} mm precipitation today", mm.unwrap_or_default()),
+            };
+            (Urgency::Wet, Some(reason))
+        } else {
+            (Urgency::Calm, None)
+        };
+
+        let callout = storm
+            .map(|period| {
+                let severity = match period.severity {
+                    StormSeverity::Severe => " (severe)",
+                    StormSeverity::Thunderstorm => "",
+                };
+                format!(
+                    "Thunderstorms {}–{}{severity}",
+                    clock_time(&period.start),
+                    clock_time(&period.end)
+                )
+            })
+            .or_else(|| advisory.map(|a| a.message()))
+            .or_else(|| {
+                frost.map(|low| format!("Frost: low {}", format::celsius(low, Precision::Whole)))
+            });
+
+        let name = name.into();
+        let mut summary = info.summary_at(now);
+        summary.location = name.clone();
+        DigestEntry {
+            name,
+            summary,
+            urgency,
+            reason,
+            callout,
+        }
+    }
+}
+
+/// Orders entries most urgent first; entries of equal urgency keep their order
+pub fn rank(mut entries: Vec<DigestEntry>) -> Vec<DigestEntry> {
+    entries.sort_by_key(|entry| entry.urgency);
+    entries
+}
+
+/// "15:00" from "2024-06-01T15:00"
+fn clock_time(time: &str) -> &str {
+    time.split('T').nth(1).unwrap_or(time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::weather_info::{CurrentWeather, DailyForecast};
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2024-06-01T08:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn location(
+        name: &str,
+        current: CurrentWeather,
+        hourly: Vec<HourlyForecast>,
+        today: DailyForecast,
+    ) -> WeatherInfo {
+        WeatherInfo::builder(name, 0.0, 0.0)
+            .utc_offset_seconds(0)
+            .current(current)
+            .hourly(hourly)
+            .daily(vec![today])
+            .build()
+    }
+
+    fn calm_day() -> DailyForecast {
+        DailyForecast::builder("2024-06-01")
+            .temperature_min(12.0)
+            .temperature_max(20.0)
+            .precipitation_probability(10.0)
+            .build()
+    }
+
+    fn assess(info: &WeatherInfo) -> DigestEntry {
+        DigestEntry::assess(info.location.clone(), info, now(), AdvisoryStandard::Us)
+    }
+
+    #[test]
+    fn test_rank_orders_severe_then_wet_then_calm() {
+        let calm = location("Calm", CurrentWeather::default(), Vec::new(), calm_day());
+        let wet = location(
+            "Wet",
+            CurrentWeather::default(),
+            Vec::new(),
+            DailyForecast::builder("2024-06-01")
+                .precipitation_probability(70.0)
+                .precipitation_sum(4.2)
+                .build(),
+        );
+        let stormy = location(
+            "Stormy",
+            CurrentWeather::default(),
+            vec![
+                HourlyForecast::builder("2024-06-01T15:00")
+                    .weather_code(95)
+                    .cape(2900.0)
+                    .build(),
+                HourlyForecast::builder("2024-06-01T16:00")
+                    .weather_code(95)
+                    .build(),
+                // Tomorrow's storm does not count
+                HourlyForecast::builder("2024-06-02T15:00")
+                    .weather_code(95)
+                    .build(),
+            ],
+            calm_day(),
+        );
+        let hot = location(
+            "Hot",
+            CurrentWeather::builder()
+                .temperature(38.0)
+                .humidity(60.0)
+                .build(),
+            Vec::new(),
+            calm_day(),
+        );
+
+        let ranked = rank(vec![
+            assess(&calm),
+            assess(&wet),
+            assess(&stormy),
+            assess(&hot),
+        ]);
+        let names: Vec<&str> = ranked.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["Stormy", "Hot", "Wet", "Calm"]);
+
+        assert_eq!(ranked[0].reason.as_deref(), Some("thunderstorms today"));
+        assert_eq!(
+            ranked[0].callout.as_deref(),
+            Some("Thunderstorms 15:00–16:00 (severe)")
+        );
+        assert_eq!(ranked[1].reason.as_deref(), Some("dangerous heat"));
+        assert!(ranked[1]
+            .callout
+            .as_deref()
+            .unwrap()
+            .starts_with("Extreme Heat Warning (heat index"));
+        assert_eq!(
+            ranked[2].reason.as_deref(),
+            Some("precipitation 70%, 4.2 mm today")
+        );
+        assert_eq!(ranked[3].reason, None);
+        assert_eq!(ranked[3].callout, None);
+        assert!(ranked[3].summary.to_string().starts_with("Calm: "));
+    }
+
+    #[test]
+    fn test_frost_is_a_callout_but_not_severe() {
+        let frosty = location(
+            "Frosty",
+            CurrentWeather::default(),
+            Vec::new(),
+            DailyForecast::builder("2024-06-01")
+                .temperature_min(-2.4)
+                .build(),
+        );
+        let entry = assess(&frosty);
+        assert_eq!(entry.urgency, Urgency::Calm);
+        assert_eq!(entry.callout.as_deref(), Some("Frost: low -2°C"));
+    }
+
+    #[test]
+    fn test_precipitation_without_probability() {
+        let drizzly = location(
+            "Drizzly",
+            CurrentWeather::default(),
+            Vec::new(),
+            DailyForecast::builder("2024-06-01")
+                .precipitation_sum(0.6)
+                .build(),
+        );
+        let entry = assess(&drizzly);
+        assert_eq!(entry.urgency, Urgency::Wet);
+        assert_eq!(entry.reason.as_deref(), Some("0.6 mm precipitation today"));
+    }
+}
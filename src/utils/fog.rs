@@ -0,0 +1,176 @@
+//! Low-visibility (fog) window detection
+
+use chrono::{Duration, NaiveDate};
+
+use crate::models::weather_info::HourlyForecast;
+use crate::utils::time;
+
+/// Shortest run of low-visibility hours reported as a window; single-hour blips are noise
+pub const MIN_WINDOW_HOURS: usize = 2;
+
+/// Consecutive forecast hours with low visibility or fog
+#[derive(Clone, Debug, PartialEq)]
+pub struct LowVisibilityWindow {
+    /// First hour of the window (ISO 8601)
+    pub start: String,
+    /// End of the window, one hour after the last low-visibility hour (ISO 8601)
+    pub end: String,
+    pub hours: usize,
+    /// Lowest forecast visibility in meters, if any hour reported one
+    pub min_visibility: Option<f64>,
+}
+
+impl LowVisibilityWindow {
+    /// e.g. "Fog likely 05:00–09:00 tomorrow", with the day relative to the
+    /// location's `today` when known
+    pub fn message(&self, today: Option<NaiveDate>) -> String {
+        let clock = |t: &str| {
+            time::parse_hour(t).map_or_else(|| t.to_string(), |t| t.format("%H:%M").to_string())
+        };
+        let mut message = format!("Fog likely {}–{}", clock(&self.start), clock(&self.end));
+        let start = time::parse_hour(&self.start).map(|t| t.date());
+        if let (Some(start), Some(today)) = (start, today) {
+            message.push(' ');
+            message.push_str(&time::relative_day(start, today));
+        }
+        message
+    }
+}
+
+/// Whether a WMO weather code is fog (45) or depositing rime fog (48)
+pub fn is_fog_code(code: i32) -> bool {
+    code == 45 || code == 48
+}
+
+fn is_low_visibility(hour: &HourlyForecast, threshold_m: f64) -> bool {
+    hour.visibility.is_some_and(|v| v < threshold_m) || hour.weather_code.is_some_and(is_fog_code)
+}
+
+/// One hour after an hourly timestamp, falling back to the timestamp itself
+fn hour_after(time: &str) -> String {
+    time::parse_hour(time)
+        .map(|t| {
+            (t + Duration::hours(1))
+                .format("%Y-%m-%dT%H:%M")
+                .to_string()
+        })
+        .unwrap_or_else(|| time.to_string())
+}
+
+/// Merges adjacent hours with visibility below `threshold_m` (or a fog code) into
+/// windows, dropping runs shorter than `MIN_WINDOW_HOURS`
+pub fn low_visibility_windows(
+    hourly: &[HourlyForecast],
+    threshold_m: f64,
+) -> Vec<LowVisibilityWindow> {
+    let mut windows = Vec::new();
+    let mut run: Vec<&HourlyForecast> = Vec::new();
+
+    let mut close_run = |run: &mut Vec<&HourlyForecast>| {
+        if run.len() >= MIN_WINDOW_HOURS {
+            windows.push(LowVisibilityWindow {
+                start: run[0].time.clone(),
+                end: hour_after(&run[run.len() - 1].time),
+                hours: run.len(),
+                min_visibility: run.iter().filter_map(|h| h.visibility).reduce(f64::min),
+            });
+        }
+        run.clear();
+    };
+
+    for hour in hourly {
+        if is_low_visibility(hour, threshold_m) {
+            run.push(hour);
+        } else {
+            close_run(&mut run);
+        }
+    }
+    close_run(&mut run);
+
+    windows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hour(time: &str, visibility: Option<f64>, code: i32) -> HourlyForecast {
+        let hour = HourlyForecast::builder(time).weather_code(code);
+        match visibility {
+            Some(v) => hour.visibility(v).build(),
+            None => hour.build(),
+        }
+    }
+
+    #[test]
+    fn test_adjacent_hours_merge() {
+        let hourly = vec![
+            hour("2024-11-02T04:00", Some(5000.0), 3),
+            hour("2024-11-02T05:00", Some(800.0), 3),
+            hour("2024-11-02T06:00", Some(300.0), 45),
+            hour("2024-11-02T07:00", None, 45),
+            hour("2024-11-02T08:00", Some(900.0), 3),
+            hour("2024-11-02T09:00", Some(4000.0), 3),
+        ];
+
+        let windows = low_visibility_windows(&hourly, 1000.0);
+        assert_eq!(
+            windows,
+            vec![LowVisibilityWindow {
+                start: "2024-11-02T05:00".to_string(),
+                end: "2024-11-02T09:00".to_string(),
+                hours: 4,
+                min_visibility: Some(300.0),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_single_hour_blips_are_dropped() {
+        let hourly = vec![
+            hour("2024-11-02T05:00", Some(600.0), 3),
+            hour("2024-11-02T06:00", Some(3000.0), 3),
+            hour("2024-11-02T07:00", None, 48),
+            hour("2024-11-02T08:00", Some(3000.0), 3),
+        ];
+        assert!(low_visibility_windows(&hourly, 1000.0).is_empty());
+    }
+
+    #[test]
+    fn test_window_running_to_end_of_series() {
+        let hourly = vec![
+            hour("2024-11-02T22:00", Some(2000.0), 3),
+            hour("2024-11-02T23:00", Some(400.0), 3),
+            hour("2024-11-03T00:00", Some(200.0), 45),
+        ];
+
+        let windows = low_visibility_windows(&hourly, 1000.0);
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].end, "2024-11-03T01:00");
+    }
+
+    #[test]
+    fn test_message() {
+        let window = LowVisibilityWindow {
+            start: "2024-11-03T05:00".to_string(),
+            end: "2024-11-03T09:00".to_string(),
+            hours: 4,
+            min_visibility: Some(200.0),
+        };
+        assert_eq!(
+            window.message(NaiveDate::from_ymd_opt(2024, 11, 2)),
+            "Fog likely 05:00–09:00 tomorrow"
+        );
+        assert_eq!(window.message(None), "Fog likely 05:00–09:00");
+    }
+
+    #[test]
+    fn test_threshold_is_respected() {
+        let hourly = vec![
+            hour("2024-11-02T05:00", Some(1500.0), 3),
+            hour("2024-11-02T06:00", Some(1800.0), 3),
+        ];
+        assert!(low_visibility_windows(&hourly, 1000.0).is_empty());
+        assert_eq!(low_visibility_windows(&hourly, 2000.0).len(), 1);
+    }
+}
@@ -0,0 +1,262 @@
+//! Significant differences between two forecasts for the same location
+//!
+//! Used when a location is fetched again, so the user's attention goes to
+//! what changed instead of a silently re-rendered report.
+
+use std::fmt;
+
+use crate::models::weather_info::{DailyForecast, WeatherData};
+use crate::utils::storm::{self, StormPeriod, StormSeverity};
+
+/// Points today's precipitation probability must move by to be reported
+pub const RAIN_CHANCE_JUMP: f64 = 30.0;
+
+/// Degrees Celsius a daily high or low must move by to be reported
+pub const TEMPERATURE_SHIFT: f64 = 4.0;
+
+/// One change worth alerting about
+#[derive(Clone, Debug, PartialEq)]
+pub enum ForecastChange {
+    /// Today's precipitation probability moved by at least [`RAIN_CHANCE_JUMP`]
+    RainChance { from: f64, to: f64 },
+    /// Severe thunderstorms in hours the previous forecast had none
+    SevereStorm { start: String },
+    /// The largest shift of a daily high or low, at least [`TEMPERATURE_SHIFT`]
+    Temperature {
+        date: String,
+        high: bool,
+        from: f64,
+        to: f64,
+    },
+}
+
+impl fmt::Display for ForecastChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let direction = |from: f64, to: f64| if to > from { "up" } else { "down" };
+        match self {
+            ForecastChange::RainChance { from, to } => write!(
+                f,
+                "Rain chance today {} from {from:.0}% to {to:.0}%",
+                direction(*from, *to)
+            ),
+            ForecastChange::SevereStorm { start } => write!(
+                f,
+                "Severe thunderstorms now forecast from {}",
+                start.replace('T', " ")
+            ),
+            ForecastChange::Temperature {
+                date,
+                high,
+                from,
+                to,
+            } => write!(
+                f,
+                "Forecast {} for {date} {} from {from:.1}°C to {to:.1}°C",
+                if *high { "high" } else { "low" },
+                direction(*from, *to)
+            ),
+        }
+    }
+}
+
+/// Changes from `previous` to `next` that cross the significance thresholds,
+/// in the order rain, storms, temperature
+pub fn significant_changes(previous: &WeatherData, next: &WeatherData) -> Vec<ForecastChange> {
+    let mut changes = Vec::new();
+    changes.extend(rain_chance_change(previous, next));
+    changes.extend(new_severe_storms(previous, next));
+    changes.extend(temperature_change(previous, next));
+    changes
+}
+
+/// Date of the current observation, or of the first forecast day
+fn today(data: &WeatherData) -> Option<&str> {
+    data.current
+        .time
+        .as_deref()
+        .and_then(|t| t.get(..10))
+        .or_else(|| data.daily.first().map(|d| d.date.as_str()))
+}
+
+fn day<'a>(data: &'a WeatherData, date: &str) -> Option<&'a DailyForecast> {
+    data.daily.iter().find(|d| d.date == date)
+}
+
+fn rain_chance_change(previous: &WeatherData, next: &WeatherData) -> Option<ForecastChange> {
+    let date = today(next)?;
+    let from = day(previous, date)?.precipitation_probability?;
+    let to = day(next, date)?.precipitation_probability?;
+    ((to - from).abs() >= RAIN_CHANCE_JUMP).then_some(ForecastChange::RainChance { from, to })
+}
+
+fn new_severe_storms(previous: &WeatherData, next: &WeatherData) -> Option<ForecastChange> {
+    let severe = |data: &WeatherData| -> Vec<StormPeriod> {
+        storm::thunderstorm_periods(data.hourly_from_today())
+            .into_iter()
+            .filter(|p| p.severity == StormSeverity::Severe)
+            .collect()
+    };
+    let known = severe(previous);
+    // Times share one format, so string order is time order
+    severe(next)
+        .into_iter()
+        .find(|period| {
+            !known
+                .iter()
+                .any(|k| k.start <= period.end && period.start <= k.end)
+        })
+        .map(|period| ForecastChange::SevereStorm {
+            start: period.start,
+        })
+}
+
+fn temperature_change(previous: &WeatherData, next: &WeatherData) -> Option<ForecastChange> {
+    next.daily
+        .iter()
+        .filter_map(|new| Some((day(previous, &new.date)?, new)))
+        .flat_map(|(old, new)| {
+            [
+                (true, old.temperature_max, new.temperature_max),
+                (false, old.temperature_min, new.temperature_min),
+            ]
+            .into_iter()
+            .filter_map(move |(high, from, to)| {
+                Some(ForecastChange::Temperature {
+                    date: new.date.clone(),
+                    high,
+                    from: from?,
+                    to: to?,
+                })
+            })
+        })
+        .filter(|change| shift(change) >= TEMPERATURE_SHIFT)
+        .reduce(|best, change| {
+            if shift(&change) > shift(&best) {
+                change
+            } else {
+                best
+            }
+        })
+}
+
+fn shift(change: &ForecastChange) -> f64 {
+    match change {
+        ForecastChange::Temperature { from, to, .. } => (to - from).abs(),
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::weather_info::{CurrentWeather, HourlyForecast};
+
+    fn forecast(rain: f64, highs: [f64; 2], storm_codes: &[(u32, i32)]) -> WeatherData {
+        let daily = ["2024-06-01", "2024-06-02"]
+            .iter()
+            .zip(highs)
+            .map(|(date, high)| {
+                DailyForecast::builder(*date)
+                    .temperature_max(high)
+                    .temperature_min(12.0)
+                    .precipitation_probability(rain)
+                    .build()
+            })
+            .collect();
+        let hourly = (0..24)
+            .map(|h| {
+                let code = storm_codes
+                    .iter()
+                    .find(|(hour, _)| *hour == h)
+                    .map_or(1, |(_, code)| *code);
+                HourlyForecast::builder(format!("2024-06-01T{h:02}:00"))
+                    .weather_code(code)
+                    .cape(3000.0)
+                    .build()
+            })
+            .collect();
+        let current = CurrentWeather::builder().time("2024-06-01T09:15").build();
+        WeatherData::new(current, hourly, daily)
+    }
+
+    #[test]
+    fn test_small_changes_are_not_significant() {
+        let previous = forecast(20.0, [25.0, 26.0], &[]);
+        let next = forecast(45.0, [22.0, 29.5], &[]);
+        assert_eq!(significant_changes(&previous, &next), vec![]);
+        assert_eq!(significant_changes(&previous, &previous), vec![]);
+    }
+
+    #[test]
+    fn test_rain_chance_jump() {
+        let previous = forecast(20.0, [25.0, 26.0], &[]);
+        let changes = significant_changes(&previous, &forecast(50.0, [25.0, 26.0], &[]));
+        assert_eq!(
+            changes,
+            vec![ForecastChange::RainChance {
+                from: 20.0,
+                to: 50.0
+            }]
+        );
+        assert_eq!(
+            changes[0].to_string(),
+            "Rain chance today up from 20% to 50%"
+        );
+
+        let changes = significant_changes(&forecast(90.0, [25.0, 26.0], &[]), &previous);
+        assert_eq!(
+            changes[0].to_string(),
+            "Rain chance today down from 90% to 20%"
+        );
+    }
+
+    #[test]
+    fn test_new_severe_period() {
+        let previous = forecast(20.0, [25.0, 26.0], &[(15, 95), (16, 95)]);
+        // The same storm an hour later is not news
+        let shifted = forecast(20.0, [25.0, 26.0], &[(16, 95), (17, 95)]);
+        assert_eq!(significant_changes(&previous, &shifted), vec![]);
+
+        let evening = forecast(20.0, [25.0, 26.0], &[(15, 95), (16, 95), (21, 99)]);
+        let changes = significant_changes(&previous, &evening);
+        assert_eq!(
+            changes,
+            vec![ForecastChange::SevereStorm {
+                start: "2024-06-01T21:00".to_string()
+            }]
+        );
+        assert_eq!(
+            changes[0].to_string(),
+            "Severe thunderstorms now forecast from 2024-06-01 21:00"
+        );
+        // A storm disappearing is not alerted
+        assert_eq!(significant_changes(&evening, &previous), vec![]);
+    }
+
+    #[test]
+    fn test_largest_temperature_shift_is_reported() {
+        let previous = forecast(20.0, [25.0, 26.0], &[]);
+        let next = forecast(20.0, [20.5, 20.0], &[]);
+        let changes = significant_changes(&previous, &next);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(
+            changes[0].to_string(),
+            "Forecast high for 2024-06-02 down from 26.0°C to 20.0°C"
+        );
+
+        // Every kind at once, rain first
+        let next = forecast(70.0, [30.0, 26.0], &[(20, 96)]);
+        let changes = significant_changes(&previous, &next);
+        assert!(
+            matches!(
+                changes.as_slice(),
+                [
+                    ForecastChange::RainChance { .. },
+                    ForecastChange::SevereStorm { .. },
+                    ForecastChange::Temperature { high: true, .. },
+                ]
+            ),
+            "{changes:?}"
+        );
+    }
+}
@@ -0,0 +1,283 @@
+//! Numeric formatting shared by the views, so every temperature is rounded the same way
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::conversions::Temperature;
+
+/// Decimal places shown for temperatures
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Precision {
+    /// Whole degrees ("19°C")
+    Whole,
+    /// One decimal ("19.4°C")
+    Tenths,
+}
+
+impl Precision {
+    fn decimals(&self) -> usize {
+        match self {
+            Precision::Whole => 0,
+            Precision::Tenths => 1,
+        }
+    }
+}
+
+impl fmt::Display for Precision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Precision::Whole => "whole",
+            Precision::Tenths => "tenths",
+        })
+    }
+}
+
+impl FromStr for Precision {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "whole" | "0" => Ok(Precision::Whole),
+            "tenths" | "1" => Ok(Precision::Tenths),
+            other => Err(format!(
+                "unknown precision '{other}' (expected whole or tenths)"
+            )),
+        }
+    }
+}
+
+/// Rounds half away from zero to `precision`, never returning negative zero
+pub fn round(value: f64, precision: Precision) -> f64 {
+    let factor = 10f64.powi(precision.decimals() as i32);
+    let rounded = (value * factor).round() / factor;
+    // -0.04 rounds to -0.0, which would print as "-0"
+    if rounded == 0.0 {
+        0.0
+    } else {
+        rounded
+    }
+}
+
+/// `value` rounded to `precision`, without a unit
+pub fn number(value: f64, precision: Precision) -> String {
+    format!("{:.*}", precision.decimals(), round(value, precision))
+}
+
+/// Visibility in a few characters: "400 m" under a kilometer, "24 km" beyond
+pub fn visibility(meters: f64) -> String {
+    if meters < 1000.0 {
+        format!("{meters:.0} m")
+    } else {
+        format!("{:.0} km", meters / 1000.0)
+    }
+}
+
+/// Duration in seconds as hours and minutes, e.g. "9h 42m", rounded to the
+/// minute; days are not split out, so a full polar day is "24h 0m"
+pub fn hours_minutes(seconds: f64) -> String {
+    let minutes = (seconds.max(0.0) / 60.0).round() as u64;
+    format!("{}h {}m", minutes / 60, minutes % 60)
+}
+
+/// Celsius temperature, e.g. "19.4°C"
+pub fn celsius(value: f64, precision: Precision) -> String {
+    format!("{}°C", number(value, precision))
+}
+
+/// Celsius temperature converted to Fahrenheit before rounding, e.g. "66.9°F"
+pub fn fahrenheit(celsius: f64, precision: Precision) -> String {
+    format!(
+        "{}°F",
+        number(Temperature::celsius_to_fahrenheit(celsius), precision)
+    )
+}
+
+/// How numbers and dates are written in the text views, independent of the
+/// interface language. JSON output always uses the canonical forms.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NumberLocale {
+    /// Decimal point and ISO dates ("14.2°C", "2024-06-12")
+    #[default]
+    En,
+    /// Decimal comma and day-first dates ("14,2 °C", "12.06.")
+    De,
+}
+
+impl NumberLocale {
+    pub const ALL: [NumberLocale; 2] = [NumberLocale::En, NumberLocale::De];
+
+    pub fn decimal_separator(&self) -> char {
+        match self {
+            NumberLocale::En => '.',
+            NumberLocale::De => ',',
+        }
+    }
+
+    /// `value` with `decimals` places and this locale's separator
+    pub fn decimal(&self, value: f64, decimals: usize) -> String {
+        self.localize(format!("{value:.decimals$}"))
+    }
+
+    /// `value` rounded to `precision`, without a unit
+    pub fn number(&self, value: f64, precision: Precision) -> String {
+        self.localize(number(value, precision))
+    }
+
+    /// Celsius temperature, e.g. "19.4°C" or "19,4 °C"
+    pub fn celsius(&self, value: f64, precision: Precision) -> String {
+        self.with_unit(self.number(value, precision), "°C")
+    }
+
+    /// Celsius temperature converted to Fahrenheit before rounding
+    pub fn fahrenheit(&self, celsius: f64, precision: Precision) -> String {
+        self.with_unit(
+            self.number(Temperature::celsius_to_fahrenheit(celsius), precision),
+            "°F",
+        )
+    }
+
+    /// Day and month of an ISO date ("2024-06-12" or "12.06."), or the input
+    /// unchanged if it is not a date
+    pub fn date(&self, iso: &str) -> String {
+        let Ok(date) = NaiveDate::parse_from_str(iso, "%Y-%m-%d") else {
+            return iso.to_string();
+        };
+        match self {
+            NumberLocale::En => date.format("%Y-%m-%d").to_string(),
+            NumberLocale::De => date.format("%d.%m.").to_string(),
+        }
+    }
+
+    fn localize(&self, number: String) -> String {
+        match self.decimal_separator() {
+            '.' => number,
+            separator => number.replace('.', &separator.to_string()),
+        }
+    }
+
+    /// German typesetting puts a space between the number and the degree sign
+    fn with_unit(&self, number: String, unit: &str) -> String {
+        match self {
+            NumberLocale::En => format!("{number}{unit}"),
+            NumberLocale::De => format!("{number} {unit}"),
+        }
+    }
+}
+
+impl fmt::Display for NumberLocale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            NumberLocale::En => "en",
+            NumberLocale::De => "de",
+        })
+    }
+}
+
+impl FromStr for NumberLocale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "en" => Ok(NumberLocale::En),
+            "de" => Ok(NumberLocale::De),
+            other => Err(format!(
+                "unknown number locale '{other}' (expected en or de)"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rounding_is_half_away_from_zero() {
+        assert_eq!(number(19.45, Precision::Tenths), "19.5");
+        assert_eq!(number(19.5, Precision::Whole), "20");
+        assert_eq!(number(-2.5, Precision::Whole), "-3");
+        assert_eq!(number(-2.44, Precision::Tenths), "-2.4");
+        assert_eq!(number(7.0, Precision::Tenths), "7.0");
+    }
+
+    #[test]
+    fn test_visibility_in_meters_or_kilometers() {
+        assert_eq!(visibility(400.0), "400 m");
+        assert_eq!(visibility(999.4), "999 m");
+        assert_eq!(visibility(1000.0), "1 km");
+        assert_eq!(visibility(24_140.0), "24 km");
+    }
+
+    #[test]
+    fn test_hours_minutes() {
+        assert_eq!(hours_minutes(34_920.0), "9h 42m");
+        assert_eq!(hours_minutes(18_600.0), "5h 10m");
+        assert_eq!(hours_minutes(0.0), "0h 0m");
+        assert_eq!(hours_minutes(29.0), "0h 0m");
+        assert_eq!(hours_minutes(30.0), "0h 1m");
+        // Rounding up may carry into the hour
+        assert_eq!(hours_minutes(3_599.5), "1h 0m");
+        assert_eq!(hours_minutes(86_400.0), "24h 0m");
+        assert_eq!(hours_minutes(93_780.0), "26h 3m");
+    }
+
+    #[test]
+    fn test_negative_zero_renders_as_zero() {
+        assert_eq!(celsius(-0.04, Precision::Whole), "0°C");
+        assert_eq!(celsius(-0.04, Precision::Tenths), "0.0°C");
+        assert_eq!(celsius(-0.4, Precision::Whole), "0°C");
+        assert_eq!(celsius(-0.0, Precision::Tenths), "0.0°C");
+        assert_eq!(celsius(-0.06, Precision::Tenths), "-0.1°C");
+    }
+
+    #[test]
+    fn test_fahrenheit_rounds_after_conversion() {
+        // 19.4°C is 66.92°F; rounding to 19°C first would give 66.2°F
+        assert_eq!(fahrenheit(19.4, Precision::Tenths), "66.9°F");
+        // 0.3°C is 32.54°F and crosses a degree boundary when rounded
+        assert_eq!(fahrenheit(0.3, Precision::Whole), "33°F");
+        assert_eq!(fahrenheit(0.25, Precision::Whole), "32°F");
+        // -17.8°C is -0.04°F
+        assert_eq!(fahrenheit(-17.8, Precision::Whole), "0°F");
+        assert_eq!(fahrenheit(-40.0, Precision::Whole), "-40°F");
+    }
+
+    #[test]
+    fn test_precision_parsing() {
+        assert_eq!("whole".parse(), Ok(Precision::Whole));
+        assert_eq!("Tenths".parse(), Ok(Precision::Tenths));
+        assert!("hundredths".parse::<Precision>().is_err());
+        assert_eq!(Precision::Whole.to_string(), "whole");
+    }
+
+    #[test]
+    fn test_number_locales_follow_precision() {
+        let (en, de) = (NumberLocale::En, NumberLocale::De);
+        assert_eq!(en.celsius(14.24, Precision::Tenths), "14.2°C");
+        assert_eq!(de.celsius(14.24, Precision::Tenths), "14,2 °C");
+        // Whole degrees have no separator to swap
+        assert_eq!(de.celsius(14.5, Precision::Whole), "15 °C");
+        assert_eq!(de.celsius(-0.04, Precision::Tenths), "0,0 °C");
+        assert_eq!(de.fahrenheit(19.4, Precision::Tenths), "66,9 °F");
+        assert_eq!(de.number(-2.44, Precision::Tenths), "-2,4");
+        assert_eq!(de.decimal(1013.25, 2), "1013,25");
+        assert_eq!(en.decimal(0.5, 1), "0.5");
+    }
+
+    #[test]
+    fn test_number_locale_dates() {
+        assert_eq!(NumberLocale::En.date("2024-06-12"), "2024-06-12");
+        assert_eq!(NumberLocale::De.date("2024-06-12"), "12.06.");
+        assert_eq!(NumberLocale::De.date("soon"), "soon");
+        assert_eq!("DE".parse(), Ok(NumberLocale::De));
+        assert!("fr".parse::<NumberLocale>().is_err());
+        for locale in NumberLocale::ALL {
+            assert_eq!(locale.to_string().parse(), Ok(locale));
+        }
+    }
+}
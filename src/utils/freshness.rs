@@ -0,0 +1,110 @@
+//! How current a forecast still is, given when it was fetched and what it covers
+
+use chrono::NaiveDateTime;
+
+/// Whether a forecast still covers the present
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Freshness {
+    /// The hourly series still reaches the present
+    Current,
+    /// The present is past the last hourly point by more than the grace period
+    Expired {
+        /// Whole hours since the last hourly point
+        hours_past: i64,
+    },
+}
+
+impl Freshness {
+    /// Warning for expired data, e.g. "Forecast ended 30 h ago; this data is very stale"
+    pub fn warning(&self) -> Option<String> {
+        match self {
+            Freshness::Current => None,
+            Freshness::Expired { hours_past } => Some(format!(
+                "Forecast ended {hours_past} h ago; this data is very stale"
+            )),
+        }
+    }
+}
+
+/// "Forecast data as of 2024-06-01 00:00 model run, fetched 2024-06-01 14:15".
+/// `reference_time` is the ISO 8601 reference hour and `fetched_at` the fetch
+/// time in the viewer's time zone.
+pub fn provenance_line(
+    reference_time: Option<&str>,
+    fetched_at: Option<NaiveDateTime>,
+) -> Option<String> {
+    let fetched = fetched_at.map(|t| format!("fetched {}", t.format("%Y-%m-%d %H:%M")));
+    match (reference_time, fetched) {
+        (Some(reference), Some(fetched)) => Some(format!(
+            "Forecast data as of {} model run, {fetched}",
+            reference.replace('T', " ")
+        )),
+        (Some(reference), None) => Some(format!(
+            "Forecast data as of {} model run",
+            reference.replace('T', " ")
+        )),
+        (None, Some(fetched)) => Some(format!("Data {fetched}")),
+        (None, None) => None,
+    }
+}
+
+/// Classifies data whose last hourly point is `last_hour` at local time `now`
+/// (both in the location's time zone). Up to `grace_hours` past the last point
+/// still counts as current, since that hour's values describe the hour after it.
+pub fn classify(last_hour: NaiveDateTime, now: NaiveDateTime, grace_hours: i64) -> Freshness {
+    let hours_past = (now - last_hour).num_hours();
+    if hours_past > grace_hours {
+        Freshness::Expired { hours_past }
+    } else {
+        Freshness::Current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::time::parse_hour;
+
+    fn at(time: &str) -> NaiveDateTime {
+        parse_hour(time).unwrap()
+    }
+
+    #[test]
+    fn test_classify() {
+        let last = at("2024-06-02T23:00");
+        assert_eq!(
+            classify(last, at("2024-06-01T14:00"), 1),
+            Freshness::Current
+        );
+        assert_eq!(
+            classify(last, at("2024-06-03T00:30"), 1),
+            Freshness::Current
+        );
+        assert_eq!(
+            classify(last, at("2024-06-04T05:00"), 1),
+            Freshness::Expired { hours_past: 30 }
+        );
+    }
+
+    #[test]
+    fn test_provenance_line() {
+        assert_eq!(
+            provenance_line(Some("2024-06-01T00:00"), Some(at("2024-06-01T14:15"))).as_deref(),
+            Some("Forecast data as of 2024-06-01 00:00 model run, fetched 2024-06-01 14:15")
+        );
+        assert_eq!(
+            provenance_line(None, Some(at("2024-06-01T14:15"))).as_deref(),
+            Some("Data fetched 2024-06-01 14:15")
+        );
+        assert_eq!(provenance_line(None, None), None);
+    }
+
+    #[test]
+    fn test_warning() {
+        assert_eq!(Freshness::Current.warning(), None);
+        assert_eq!(
+            Freshness::Expired { hours_past: 30 }.warning().as_deref(),
+            Some("Forecast ended 30 h ago; this data is very stale")
+        );
+    }
+}
@@ -0,0 +1,226 @@
+//! How far ahead the GUI's hourly strip looks
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{NaiveDateTime, TimeDelta};
+use serde::{Deserialize, Serialize};
+
+use crate::models::weather_info::{self, HourlyForecast};
+use crate::utils::time;
+
+/// Hours shown from the current hour onwards
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HourlyRange {
+    #[serde(rename = "12h")]
+    Hours12,
+    #[default]
+    #[serde(rename = "24h")]
+    Hours24,
+    #[serde(rename = "48h")]
+    Hours48,
+    /// Every remaining hour of the forecast
+    All,
+}
+
+/// Hours of a forecast within a range, and whether the forecast fell short of it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HourlyWindow<'a> {
+    pub hours: &'a [HourlyForecast],
+    /// Hours the range asked for when fewer were available
+    pub requested: Option<usize>,
+}
+
+impl HourlyWindow<'_> {
+    /// Whether the range is longer than the available data
+    pub fn is_clamped(&self) -> bool {
+        self.requested.is_some()
+    }
+}
+
+impl HourlyRange {
+    pub const ALL: [HourlyRange; 4] = [
+        HourlyRange::Hours12,
+        HourlyRange::Hours24,
+        HourlyRange::Hours48,
+        HourlyRange::All,
+    ];
+
+    /// Number of hours, `None` for all of them
+    pub fn hours(&self) -> Option<usize> {
+        match self {
+            HourlyRange::Hours12 => Some(12),
+            HourlyRange::Hours24 => Some(24),
+            HourlyRange::Hours48 => Some(48),
+            HourlyRange::All => None,
+        }
+    }
+
+    /// Hours of `hourly` in this range, starting with the hour containing `now`
+    /// (local time at the location). Hours that have already ended are skipped;
+    /// without `now` the window starts at the first hour. The range is real
+    /// time, so a day across a clock change still holds 24 entries.
+    pub fn window<'a>(
+        &self,
+        hourly: &'a [HourlyForecast],
+        now: Option<NaiveDateTime>,
+    ) -> HourlyWindow<'a> {
+        let start = now.map_or(0, |now| weather_info::first_upcoming_hour(hourly, now));
+        let upcoming = &hourly[start..];
+        let offsets = time::elapsed(upcoming.iter().map(|h| h.time.as_str()));
+        // Real hours from the first upcoming entry to the end of the last
+        let available = offsets
+            .last()
+            .map_or(0, |last| last.num_hours() as usize + 1);
+
+        match self.hours() {
+            Some(hours) if hours > available => HourlyWindow {
+                hours: upcoming,
+                requested: Some(hours),
+            },
+            Some(hours) => HourlyWindow {
+                hours: &upcoming[..offsets
+                    .iter()
+                    .take_while(|offset| **offset < TimeDelta::hours(hours as i64))
+                    .count()],
+                requested: None,
+            },
+            None => HourlyWindow {
+                hours: upcoming,
+                requested: None,
+            },
+        }
+    }
+}
+
+impl fmt::Display for HourlyRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.hours() {
+            Some(hours) => write!(f, "{hours} h"),
+            None => f.write_str("all"),
+        }
+    }
+}
+
+impl FromStr for HourlyRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().replace(' ', "").as_str() {
+            "12h" | "12" => Ok(HourlyRange::Hours12),
+            "24h" | "24" => Ok(HourlyRange::Hours24),
+            "48h" | "48" => Ok(HourlyRange::Hours48),
+            "all" => Ok(HourlyRange::All),
+            other => Err(format!(
+                "unknown hourly range '{other}' (expected 12h, 24h, 48h or all)"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 36 hours from 2024-06-01T00:00
+    fn hours() -> Vec<HourlyForecast> {
+        (0..36)
+            .map(|i| {
+                HourlyForecast::builder(format!("2024-06-{:02}T{:02}:00", 1 + i / 24, i % 24))
+                    .build()
+            })
+            .collect()
+    }
+
+    fn at(time: &str) -> Option<NaiveDateTime> {
+        NaiveDateTime::parse_from_str(time, "%Y-%m-%dT%H:%M").ok()
+    }
+
+    #[test]
+    fn test_window_starts_at_the_current_hour() {
+        let hourly = hours();
+        let window = HourlyRange::Hours12.window(&hourly, at("2024-06-01T09:40"));
+        assert_eq!(window.hours.len(), 12);
+        assert_eq!(window.hours[0].time, "2024-06-01T09:00");
+        assert!(!window.is_clamped());
+
+        let window = HourlyRange::Hours24.window(&hourly, None);
+        assert_eq!(window.hours[0].time, "2024-06-01T00:00");
+        assert_eq!(window.hours.len(), 24);
+    }
+
+    #[test]
+    fn test_window_clamps_to_available_hours() {
+        let hourly = hours();
+        let window = HourlyRange::Hours48.window(&hourly, at("2024-06-01T00:00"));
+        assert_eq!(window.hours.len(), 36);
+        assert_eq!(window.requested, Some(48));
+
+        let window = HourlyRange::Hours24.window(&hourly, at("2024-06-01T20:15"));
+        assert_eq!(window.hours.len(), 16);
+        assert!(window.is_clamped());
+
+        // "All" never asks for more than there is
+        let window = HourlyRange::All.window(&hourly, at("2024-06-01T20:15"));
+        assert_eq!(window.hours.len(), 16);
+        assert!(!window.is_clamped());
+    }
+
+    #[test]
+    fn test_window_measures_real_time_across_clock_changes() {
+        let day = |date: &str, skip: Option<u32>, repeat: Option<u32>| {
+            let mut hours = Vec::new();
+            for h in 0..24 {
+                let copies = if Some(h) == repeat { 2 } else { 1 };
+                if Some(h) != skip {
+                    for _ in 0..copies {
+                        hours.push(HourlyForecast::builder(format!("{date}T{h:02}:00")).build());
+                    }
+                }
+            }
+            hours
+        };
+
+        // Spring forward: 24 real hours reach 00:00 the next day
+        let mut spring = day("2024-03-31", Some(2), None);
+        spring.extend(day("2024-04-01", None, None));
+        let window = HourlyRange::Hours24.window(&spring, at("2024-03-31T00:10"));
+        assert_eq!(window.hours.len(), 24);
+        assert_eq!(window.hours.last().unwrap().time, "2024-04-01T00:00");
+
+        // Fall back: 24 real hours end before the local day's last hour
+        let fall = day("2024-10-27", None, Some(2));
+        let window = HourlyRange::Hours24.window(&fall, at("2024-10-27T00:10"));
+        assert_eq!(window.hours.len(), 24);
+        assert_eq!(window.hours.last().unwrap().time, "2024-10-27T22:00");
+        assert!(!window.is_clamped());
+
+        // Missing entries shorten the window instead of stretching it
+        let mut gappy = hours();
+        gappy.drain(3..6);
+        let window = HourlyRange::Hours12.window(&gappy, at("2024-06-01T00:00"));
+        assert_eq!(window.hours.len(), 9);
+        assert_eq!(window.hours.last().unwrap().time, "2024-06-01T11:00");
+    }
+
+    #[test]
+    fn test_window_after_the_forecast_is_empty() {
+        let hourly = hours();
+        let window = HourlyRange::Hours12.window(&hourly, at("2024-06-03T00:00"));
+        assert!(window.hours.is_empty());
+        assert_eq!(window.requested, Some(12));
+    }
+
+    #[test]
+    fn test_parse_and_serialize() {
+        for range in HourlyRange::ALL {
+            assert_eq!(range.to_string().parse(), Ok(range));
+        }
+        assert_eq!(
+            serde_json::to_string(&HourlyRange::Hours48).unwrap(),
+            "\"48h\""
+        );
+        assert!("36h".parse::<HourlyRange>().is_err());
+    }
+}
@@ -0,0 +1,802 @@
+//! Translated interface strings
+//!
+//! Each locale has a table of `(Key, text)` pairs; lookups fall back to
+//! English when a locale is missing a key.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Interface language
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+    Es,
+}
+
+impl Locale {
+    /// Every shipped locale
+    pub const ALL: [Locale; 3] = [Locale::En, Locale::De, Locale::Es];
+
+    /// Language name in that language, for pickers
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Locale::En => "English",
+            Locale::De => "Deutsch",
+            Locale::Es => "Español",
+        }
+    }
+
+    fn table(&self) -> &'static [(Key, &'static str)] {
+        match self {
+            Locale::En => EN,
+            Locale::De => DE,
+            Locale::Es => ES,
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Locale::En => "en",
+            Locale::De => "de",
+            Locale::Es => "es",
+        })
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Accept region and encoding suffixes as found in $LANG ("de_DE.UTF-8")
+        let language = s.trim().split(['_', '-', '.']).next().unwrap_or_default();
+        match language.to_ascii_lowercase().as_str() {
+            "en" => Ok(Locale::En),
+            "de" => Ok(Locale::De),
+            "es" => Ok(Locale::Es),
+            other => Err(format!(
+                "unsupported locale '{other}' (expected en, de or es)"
+            )),
+        }
+    }
+}
+
+/// Translatable interface string
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Key {
+    Dashboard,
+    LocationLabel,
+    LocationHint,
+    Search,
+    SaveToFavorites,
+    Settings,
+    Error,
+    TabCurrent,
+    TabHourly,
+    TabDaily,
+    GetStarted,
+    Favorites,
+    Rename,
+    Remove,
+    RenameFavorite,
+    LabelHint,
+    Save,
+    Cancel,
+    MountainMode,
+    Agriculture,
+    Units,
+    HeatStress,
+    Aviation,
+    WindUnit,
+    Precision,
+    PrecisionAuto,
+    PrecisionWhole,
+    PrecisionTenths,
+    Language,
+    UsageStats,
+    UsageStatsHint,
+    ReducedData,
+    FeelsLike,
+    Humidity,
+    DewPoint,
+    WindSpeed,
+    WindGusts,
+    Precipitation,
+    Pressure,
+    CloudCover,
+    Visibility,
+    SnowDepth,
+    Snowfall,
+    FreezingLevel,
+    NextHours,
+    MinutelyHoursHeading,
+    MinutelyMinutesHeading,
+    NoHourlyData,
+    NoDailyData,
+    TryAgain,
+    AllHours,
+    HoursAvailable,
+    SaveChart,
+    ChartSaved,
+    ClocksForward,
+    ClocksBack,
+    Marine,
+    Waves,
+    WavePeriod,
+    WindCoverage,
+    ForecastChanged,
+    RateLimitedRetry,
+    PrecipPeak,
+    WetHours,
+    LoadingLocation,
+    SkinType,
+    SkinTypeUnset,
+    UvLowRisk,
+    TimeToBurn,
+    PolarDay,
+    PolarNight,
+    ShowingCached,
+    ClearCachedData,
+    CacheCleared,
+    ClockOff,
+    AdvisoryTiers,
+    PressureShown,
+    SeaLevel,
+    Surface,
+    ForecastConfidence,
+    HighConfidence,
+    MediumConfidence,
+    LowConfidence,
+    HighConfidenceNote,
+    MediumConfidenceNote,
+    LowConfidenceNote,
+    RefreshName,
+    AcceptMove,
+    FavoriteMoved,
+    Refresh,
+    OfflineData,
+    SevenDayForecast,
+    Average,
+    High,
+    Low,
+    Precip,
+    Wind,
+    Water,
+    UvIndex,
+    Sunrise,
+    Sunset,
+    DaylightDuration,
+    SunshineDuration,
+    VisibilityDownTo,
+    SevereThunderstorms,
+    Thunderstorms,
+    StormExpected,
+    StormEnergy,
+    CapeMarginal,
+    CapeModerate,
+    CapeHigh,
+    CapeExtreme,
+    SnowBy,
+    SnowAbove,
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Key {
+    /// Every key, for completeness checks
+    pub const ALL: [Key; 121] = [
+        Key::Dashboard,
+        Key::LocationLabel,
+        Key::LocationHint,
+        Key::Search,
+        Key::SaveToFavorites,
+        Key::Settings,
+        Key::Error,
+        Key::TabCurrent,
+        Key::TabHourly,
+        Key::TabDaily,
+        Key::GetStarted,
+        Key::Favorites,
+        Key::Rename,
+        Key::Remove,
+        Key::RenameFavorite,
+        Key::LabelHint,
+        Key::Save,
+        Key::Cancel,
+        Key::MountainMode,
+        Key::Agriculture,
+        Key::Units,
+        Key::HeatStress,
+        Key::Aviation,
+        Key::WindUnit,
+        Key::Precision,
+        Key::PrecisionAuto,
+        Key::PrecisionWhole,
+        Key::PrecisionTenths,
+        Key::Language,
+        Key::UsageStats,
+        Key::UsageStatsHint,
+        Key::ReducedData,
+        Key::FeelsLike,
+        Key::Humidity,
+        Key::DewPoint,
+        Key::WindSpeed,
+        Key::WindGusts,
+        Key::Precipitation,
+        Key::Pressure,
+        Key::CloudCover,
+        Key::Visibility,
+        Key::SnowDepth,
+        Key::Snowfall,
+        Key::FreezingLevel,
+        Key::NextHours,
+        Key::MinutelyHoursHeading,
+        Key::MinutelyMinutesHeading,
+        Key::NoHourlyData,
+        Key::NoDailyData,
+        Key::TryAgain,
+        Key::AllHours,
+        Key::HoursAvailable,
+        Key::SaveChart,
+        Key::ChartSaved,
+        Key::ClocksForward,
+        Key::ClocksBack,
+        Key::Marine,
+        Key::Waves,
+        Key::WavePeriod,
+        Key::WindCoverage,
+        Key::ForecastChanged,
+        Key::RateLimitedRetry,
+        Key::PrecipPeak,
+        Key::WetHours,
+        Key::LoadingLocation,
+        Key::SkinType,
+        Key::SkinTypeUnset,
+        Key::UvLowRisk,
+        Key::TimeToBurn,
+        Key::PolarDay,
+        Key::PolarNight,
+        Key::ShowingCached,
+        Key::ClearCachedData,
+        Key::CacheCleared,
+        Key::ClockOff,
+        Key::AdvisoryTiers,
+        Key::PressureShown,
+        Key::SeaLevel,
+        Key::Surface,
+        Key::ForecastConfidence,
+        Key::HighConfidence,
+        Key::MediumConfidence,
+        Key::LowConfidence,
+        Key::HighConfidenceNote,
+        Key::MediumConfidenceNote,
+        Key::LowConfidenceNote,
+        Key::RefreshName,
+        Key::AcceptMove,
+        Key::FavoriteMoved,
+        Key::Refresh,
+        Key::OfflineData,
+        Key::SevenDayForecast,
+        Key::Average,
+        Key::High,
+        Key::Low,
+        Key::Precip,
+        Key::Wind,
+        Key::Water,
+        Key::UvIndex,
+        Key::Sunrise,
+        Key::Sunset,
+        Key::DaylightDuration,
+        Key::SunshineDuration,
+        Key::VisibilityDownTo,
+        Key::SevereThunderstorms,
+        Key::Thunderstorms,
+        Key::StormExpected,
+        Key::StormEnergy,
+        Key::CapeMarginal,
+        Key::CapeModerate,
+        Key::CapeHigh,
+        Key::CapeExtreme,
+        Key::SnowBy,
+        Key::SnowAbove,
+        Key::Monday,
+        Key::Tuesday,
+        Key::Wednesday,
+        Key::Thursday,
+        Key::Friday,
+        Key::Saturday,
+        Key::Sunday,
+    ];
+}
+
+/// Text for `key` in `locale`, falling back to English
+pub fn tr(locale: Locale, key: Key) -> &'static str {
+    lookup(locale.table(), key)
+        .or_else(|| lookup(EN, key))
+        .unwrap_or_default()
+}
+
+fn lookup(table: &[(Key, &'static str)], key: Key) -> Option<&'static str> {
+    table.iter().find(|(k, _)| *k == key).map(|(_, text)| *text)
+}
+
+const EN: &[(Key, &str)] = &[
+    (Key::Dashboard, "Weather Dashboard"),
+    (Key::LocationLabel, "Location:"),
+    (Key::LocationHint, "Enter city name..."),
+    (Key::Search, "Search"),
+    (Key::SaveToFavorites, "Save to favorites"),
+    (Key::Settings, "Settings"),
+    (Key::Error, "Error"),
+    (Key::TabCurrent, "Current"),
+    (Key::TabHourly, "Hourly"),
+    (Key::TabDaily, "Daily"),
+    (Key::GetStarted, "Enter a location to get started"),
+    (Key::Favorites, "Favorites"),
+    (Key::Rename, "Rename…"),
+    (Key::Remove, "Remove"),
+    (Key::RenameFavorite, "Rename favorite"),
+    (Key::LabelHint, "Label, e.g. Home (empty to clear)"),
+    (Key::Save, "Save"),
+    (Key::Cancel, "Cancel"),
+    (
+        Key::MountainMode,
+        "Mountain mode (freezing level and snow line)",
+    ),
+    (Key::Agriculture, "Agriculture (daily irrigation need)"),
+    (Key::Units, "Units"),
+    (Key::HeatStress, "Heat stress:"),
+    (Key::Aviation, "Aviation (winds aloft in hourly tooltips)"),
+    (Key::WindUnit, "Wind unit:"),
+    (Key::Precision, "Temperatures:"),
+    (Key::PrecisionAuto, "Automatic"),
+    (Key::PrecisionWhole, "Whole degrees"),
+    (Key::PrecisionTenths, "Tenths"),
+    (Key::Language, "Language:"),
+    (Key::UsageStats, "Keep local usage statistics"),
+    (Key::UsageStatsHint, "Counts lookups per location and day in usage.jsonl next to settings.json. Nothing is sent anywhere; see `weather-app stats`."),
+    (
+        Key::ReducedData,
+        "This server only provides basic current conditions",
+    ),
+    (Key::FeelsLike, "Feels Like"),
+    (Key::Humidity, "Humidity"),
+    (Key::DewPoint, "Dew Point"),
+    (Key::WindSpeed, "Wind Speed"),
+    (Key::WindGusts, "Gusts"),
+    (Key::Precipitation, "Precipitation"),
+    (Key::Pressure, "Pressure"),
+    (Key::CloudCover, "Cloud Cover"),
+    (Key::Visibility, "Visibility"),
+    (Key::SnowDepth, "Snow Depth"),
+    (Key::Snowfall, "Snowfall"),
+    (Key::FreezingLevel, "Freezing Level"),
+    (Key::NextHours, "Next"),
+    (Key::MinutelyHoursHeading, "Next {} Hours (Every {} Minutes)"),
+    (Key::MinutelyMinutesHeading, "Next {} Minutes (Every {} Minutes)"),
+    (
+        Key::NoHourlyData,
+        "No hourly data available for this location",
+    ),
+    (
+        Key::NoDailyData,
+        "No daily data available for this location",
+    ),
+    (Key::TryAgain, "Try searching again in a few minutes"),
+    (Key::AllHours, "All"),
+    (Key::HoursAvailable, "Only {} h available"),
+    (Key::SaveChart, "Save chart as PNG"),
+    (Key::ChartSaved, "Chart saved to {}"),
+    (Key::ClocksForward, "Clocks go forward"),
+    (Key::ClocksBack, "Clocks go back"),
+    (Key::Marine, "Marine"),
+    (Key::Waves, "Waves"),
+    (Key::WavePeriod, "Period"),
+    (Key::WindCoverage, "Direction known for {}% of hours"),
+    (Key::ForecastChanged, "Forecast changed"),
+    (Key::RateLimitedRetry, "Rate limited, retrying at {}"),
+    (Key::PrecipPeak, "{}% peak"),
+    (Key::WetHours, "wet hours ~{}"),
+    (Key::LoadingLocation, "Loading {}…"),
+    (Key::SkinType, "Skin type:"),
+    (Key::SkinTypeUnset, "Not set"),
+    (Key::UvLowRisk, "low risk"),
+    (Key::TimeToBurn, "{} to burn without protection"),
+    (Key::PolarDay, "Polar day"),
+    (Key::PolarNight, "Polar night"),
+    (Key::ShowingCached, "Showing cached data"),
+    (Key::ClearCachedData, "Clear cached data"),
+    (Key::CacheCleared, "Cached data cleared ({} freed)"),
+    (Key::ClockOff, "System clock appears off by {}"),
+    (Key::AdvisoryTiers, "Advisories:"),
+    (Key::PressureShown, "Pressure:"),
+    (Key::SeaLevel, "Sea level"),
+    (Key::Surface, "Surface"),
+    (Key::ForecastConfidence, "Forecast confidence (one more request)"),
+    (Key::HighConfidence, "high confidence"),
+    (Key::MediumConfidence, "medium confidence"),
+    (Key::LowConfidence, "low confidence"),
+    (
+        Key::HighConfidenceNote,
+        "Forecast models agree closely on this day's temperatures",
+    ),
+    (
+        Key::MediumConfidenceNote,
+        "Forecast models differ by a few degrees on this day",
+    ),
+    (
+        Key::LowConfidenceNote,
+        "Forecast models disagree widely on this day; expect the forecast to change",
+    ),
+    (Key::RefreshName, "Refresh name"),
+    (Key::AcceptMove, "Accept new location"),
+    (Key::FavoriteMoved, "The geocoder now places this at {}; right-click to accept"),
+    (Key::Refresh, "Refresh now, skipping cached data"),
+    (Key::OfflineData, "No connection; showing data from {}"),
+    (Key::SevenDayForecast, "7-Day Forecast"),
+    (Key::Average, "Avg"),
+    (Key::High, "High"),
+    (Key::Low, "Low"),
+    (Key::Precip, "Precip"),
+    (Key::Wind, "Wind"),
+    (Key::Water, "Water"),
+    (Key::UvIndex, "UV"),
+    (Key::Sunrise, "Rise"),
+    (Key::Sunset, "Set"),
+    (Key::DaylightDuration, "Day"),
+    (Key::SunshineDuration, "Sun"),
+    (Key::VisibilityDownTo, "Visibility down to {}"),
+    (Key::SevereThunderstorms, "Severe thunderstorms"),
+    (Key::Thunderstorms, "Thunderstorms"),
+    (Key::StormExpected, "{} expected {} – {}"),
+    (Key::StormEnergy, "Storm energy"),
+    (Key::CapeMarginal, "Marginal"),
+    (Key::CapeModerate, "Moderate"),
+    (Key::CapeHigh, "High"),
+    (Key::CapeExtreme, "Extreme"),
+    (Key::SnowBy, "+{} by {}"),
+    (Key::SnowAbove, "snow above ~{}"),
+    (Key::Monday, "Monday"),
+    (Key::Tuesday, "Tuesday"),
+    (Key::Wednesday, "Wednesday"),
+    (Key::Thursday, "Thursday"),
+    (Key::Friday, "Friday"),
+    (Key::Saturday, "Saturday"),
+    (Key::Sunday, "Sunday"),
+];
+
+const DE: &[(Key, &str)] = &[
+    (Key::Dashboard, "Wetter-Übersicht"),
+    (Key::LocationLabel, "Ort:"),
+    (Key::LocationHint, "Stadtname eingeben..."),
+    (Key::Search, "Suchen"),
+    (Key::SaveToFavorites, "Zu Favoriten hinzufügen"),
+    (Key::Settings, "Einstellungen"),
+    (Key::Error, "Fehler"),
+    (Key::TabCurrent, "Aktuell"),
+    (Key::TabHourly, "Stündlich"),
+    (Key::TabDaily, "Täglich"),
+    (Key::GetStarted, "Gib einen Ort ein, um zu beginnen"),
+    (Key::Favorites, "Favoriten"),
+    (Key::Rename, "Umbenennen…"),
+    (Key::Remove, "Entfernen"),
+    (Key::RenameFavorite, "Favorit umbenennen"),
+    (Key::LabelHint, "Name, z. B. Zuhause (leer zum Entfernen)"),
+    (Key::Save, "Speichern"),
+    (Key::Cancel, "Abbrechen"),
+    (
+        Key::MountainMode,
+        "Bergmodus (Nullgradgrenze und Schneefallgrenze)",
+    ),
+    (
+        Key::Agriculture,
+        "Landwirtschaft (täglicher Bewässerungsbedarf)",
+    ),
+    (Key::Units, "Einheiten"),
+    (Key::HeatStress, "Hitzebelastung:"),
+    (
+        Key::Aviation,
+        "Luftfahrt (Höhenwind in stündlichen Tooltips)",
+    ),
+    (Key::WindUnit, "Windeinheit:"),
+    (Key::Precision, "Temperaturen:"),
+    (Key::PrecisionAuto, "Automatisch"),
+    (Key::PrecisionWhole, "Ganze Grad"),
+    (Key::PrecisionTenths, "Zehntel"),
+    (Key::Language, "Sprache:"),
+    (Key::UsageStats, "Lokale Nutzungsstatistik führen"),
+    (Key::UsageStatsHint, "Zählt Abfragen pro Ort und Tag in usage.jsonl neben settings.json. Es wird nichts gesendet; siehe `weather-app stats`."),
+    (
+        Key::ReducedData,
+        "Dieser Server liefert nur einfache aktuelle Bedingungen",
+    ),
+    (Key::FeelsLike, "Gefühlt"),
+    (Key::Humidity, "Luftfeuchtigkeit"),
+    (Key::DewPoint, "Taupunkt"),
+    (Key::WindSpeed, "Windgeschwindigkeit"),
+    (Key::WindGusts, "Böen"),
+    (Key::Precipitation, "Niederschlag"),
+    (Key::Pressure, "Luftdruck"),
+    (Key::CloudCover, "Bewölkung"),
+    (Key::Visibility, "Sichtweite"),
+    (Key::SnowDepth, "Schneehöhe"),
+    (Key::Snowfall, "Schneefall"),
+    (Key::FreezingLevel, "Nullgradgrenze"),
+    (Key::NextHours, "Nächste"),
+    (Key::MinutelyHoursHeading, "Nächste {} Stunden (alle {} Minuten)"),
+    (Key::MinutelyMinutesHeading, "Nächste {} Minuten (alle {} Minuten)"),
+    (
+        Key::NoHourlyData,
+        "Keine stündlichen Daten für diesen Ort verfügbar",
+    ),
+    (
+        Key::NoDailyData,
+        "Keine Tagesdaten für diesen Ort verfügbar",
+    ),
+    (Key::TryAgain, "Versuche es in ein paar Minuten erneut"),
+    (Key::AllHours, "Alle"),
+    (Key::HoursAvailable, "Nur {} h verfügbar"),
+    (Key::SaveChart, "Diagramm als PNG speichern"),
+    (Key::ChartSaved, "Diagramm gespeichert unter {}"),
+    (Key::ClocksForward, "Uhren werden vorgestellt"),
+    (Key::ClocksBack, "Uhren werden zurückgestellt"),
+    (Key::Marine, "Seegang"),
+    (Key::Waves, "Wellen"),
+    (Key::WavePeriod, "Periode"),
+    (Key::WindCoverage, "Richtung für {} % der Stunden bekannt"),
+    (Key::ForecastChanged, "Vorhersage geändert"),
+    (Key::RateLimitedRetry, "Zu viele Anfragen, neuer Versuch um {}"),
+    (Key::PrecipPeak, "{}% Spitze"),
+    (Key::WetHours, "Regenstunden ~{}"),
+    (Key::LoadingLocation, "Lade {}…"),
+    (Key::SkinType, "Hauttyp:"),
+    (Key::SkinTypeUnset, "Nicht gesetzt"),
+    (Key::UvLowRisk, "geringes Risiko"),
+    (Key::TimeToBurn, "{} bis zum Sonnenbrand ohne Schutz"),
+    (Key::PolarDay, "Polartag"),
+    (Key::PolarNight, "Polarnacht"),
+    (Key::ShowingCached, "Zwischengespeicherte Daten"),
+    (Key::ClearCachedData, "Zwischenspeicher leeren"),
+    (Key::CacheCleared, "Zwischenspeicher geleert ({} freigegeben)"),
+    (Key::ClockOff, "Die Systemuhr scheint um {} falsch zu gehen"),
+    (Key::AdvisoryTiers, "Warnstufen:"),
+    (Key::PressureShown, "Luftdruck:"),
+    (Key::SeaLevel, "Meereshöhe"),
+    (Key::Surface, "Boden"),
+    (Key::ForecastConfidence, "Vorhersagesicherheit (eine Anfrage mehr)"),
+    (Key::HighConfidence, "hohe Sicherheit"),
+    (Key::MediumConfidence, "mittlere Sicherheit"),
+    (Key::LowConfidence, "geringe Sicherheit"),
+    (
+        Key::HighConfidenceNote,
+        "Die Vorhersagemodelle stimmen bei den Temperaturen dieses Tages gut überein",
+    ),
+    (
+        Key::MediumConfidenceNote,
+        "Die Vorhersagemodelle weichen an diesem Tag um einige Grad voneinander ab",
+    ),
+    (
+        Key::LowConfidenceNote,
+        "Die Vorhersagemodelle sind sich an diesem Tag uneinig; die Vorhersage wird sich wahrscheinlich ändern",
+    ),
+    (Key::RefreshName, "Namen aktualisieren"),
+    (Key::AcceptMove, "Neuen Ort übernehmen"),
+    (Key::FavoriteMoved, "Der Geocoder verortet dies jetzt bei {}; zum Übernehmen rechtsklicken"),
+    (Key::Refresh, "Jetzt aktualisieren, ohne zwischengespeicherte Daten"),
+    (Key::OfflineData, "Keine Verbindung; Daten vom {}"),
+    (Key::SevenDayForecast, "7-Tage-Vorhersage"),
+    (Key::Average, "Mittel"),
+    (Key::High, "Höchstwert"),
+    (Key::Low, "Tiefstwert"),
+    (Key::Precip, "Niederschlag"),
+    (Key::Wind, "Wind"),
+    (Key::Water, "Bewässerung"),
+    (Key::UvIndex, "UV-Index"),
+    (Key::Sunrise, "Aufgang"),
+    (Key::Sunset, "Untergang"),
+    (Key::DaylightDuration, "Tag"),
+    (Key::SunshineDuration, "Sonne"),
+    (Key::VisibilityDownTo, "Sicht bis auf {}"),
+    (Key::SevereThunderstorms, "Schwere Gewitter"),
+    (Key::Thunderstorms, "Gewitter"),
+    (Key::StormExpected, "{} erwartet {} – {}"),
+    (Key::StormEnergy, "Gewitterenergie"),
+    (Key::CapeMarginal, "Gering"),
+    (Key::CapeModerate, "Mäßig"),
+    (Key::CapeHigh, "Hoch"),
+    (Key::CapeExtreme, "Extrem"),
+    (Key::SnowBy, "+{} bis {}"),
+    (Key::SnowAbove, "Schnee oberhalb von ~{}"),
+    (Key::Monday, "Montag"),
+    (Key::Tuesday, "Dienstag"),
+    (Key::Wednesday, "Mittwoch"),
+    (Key::Thursday, "Donnerstag"),
+    (Key::Friday, "Freitag"),
+    (Key::Saturday, "Samstag"),
+    (Key::Sunday, "Sonntag"),
+];
+
+const ES: &[(Key, &str)] = &[
+    (Key::Dashboard, "Panel del tiempo"),
+    (Key::LocationLabel, "Ubicación:"),
+    (Key::LocationHint, "Introduce una ciudad..."),
+    (Key::Search, "Buscar"),
+    (Key::SaveToFavorites, "Guardar en favoritos"),
+    (Key::Settings, "Ajustes"),
+    (Key::Error, "Error"),
+    (Key::TabCurrent, "Actual"),
+    (Key::TabHourly, "Por horas"),
+    (Key::TabDaily, "Diario"),
+    (Key::GetStarted, "Introduce una ubicación para empezar"),
+    (Key::Favorites, "Favoritos"),
+    (Key::Rename, "Renombrar…"),
+    (Key::Remove, "Eliminar"),
+    (Key::RenameFavorite, "Renombrar favorito"),
+    (Key::LabelHint, "Etiqueta, p. ej. Casa (vacía para borrar)"),
+    (Key::Save, "Guardar"),
+    (Key::Cancel, "Cancelar"),
+    (
+        Key::MountainMode,
+        "Modo montaña (isoterma cero y cota de nieve)",
+    ),
+    (Key::Agriculture, "Agricultura (riego diario necesario)"),
+    (Key::Units, "Unidades"),
+    (Key::HeatStress, "Estrés térmico:"),
+    (
+        Key::Aviation,
+        "Aviación (viento en altura en la información por hora)",
+    ),
+    (Key::WindUnit, "Unidad de viento:"),
+    (Key::Precision, "Temperaturas:"),
+    (Key::PrecisionAuto, "Automático"),
+    (Key::PrecisionWhole, "Grados enteros"),
+    (Key::PrecisionTenths, "Décimas"),
+    (Key::Language, "Idioma:"),
+    (Key::UsageStats, "Guardar estadísticas de uso locales"),
+    (Key::UsageStatsHint, "Cuenta las consultas por ubicación y día en usage.jsonl junto a settings.json. No se envía nada; consulta `weather-app stats`."),
+    (
+        Key::ReducedData,
+        "Este servidor solo ofrece condiciones actuales básicas",
+    ),
+    (Key::FeelsLike, "Sensación"),
+    (Key::Humidity, "Humedad"),
+    (Key::DewPoint, "Punto de rocío"),
+    (Key::WindSpeed, "Velocidad del viento"),
+    (Key::WindGusts, "Ráfagas"),
+    (Key::Precipitation, "Precipitación"),
+    (Key::Pressure, "Presión"),
+    (Key::CloudCover, "Nubosidad"),
+    (Key::Visibility, "Visibilidad"),
+    (Key::SnowDepth, "Espesor de nieve"),
+    (Key::Snowfall, "Nevada"),
+    (Key::FreezingLevel, "Isoterma cero"),
+    (Key::NextHours, "Próximas"),
+    (Key::MinutelyHoursHeading, "Próximas {} horas (cada {} minutos)"),
+    (Key::MinutelyMinutesHeading, "Próximos {} minutos (cada {} minutos)"),
+    (
+        Key::NoHourlyData,
+        "No hay datos por horas para esta ubicación",
+    ),
+    (Key::NoDailyData, "No hay datos diarios para esta ubicación"),
+    (Key::TryAgain, "Vuelve a intentarlo en unos minutos"),
+    (Key::AllHours, "Todas"),
+    (Key::HoursAvailable, "Solo {} h disponibles"),
+    (Key::SaveChart, "Guardar gráfico como PNG"),
+    (Key::ChartSaved, "Gráfico guardado en {}"),
+    (Key::ClocksForward, "Se adelanta la hora"),
+    (Key::ClocksBack, "Se atrasa la hora"),
+    (Key::Marine, "Estado del mar"),
+    (Key::Waves, "Olas"),
+    (Key::WavePeriod, "Periodo"),
+    (Key::WindCoverage, "Dirección conocida en el {} % de las horas"),
+    (Key::ForecastChanged, "La previsión ha cambiado"),
+    (Key::RateLimitedRetry, "Demasiadas solicitudes, reintento a las {}"),
+    (Key::PrecipPeak, "{}% máx."),
+    (Key::WetHours, "horas de lluvia ~{}"),
+    (Key::LoadingLocation, "Cargando {}…"),
+    (Key::SkinType, "Fototipo:"),
+    (Key::SkinTypeUnset, "Sin definir"),
+    (Key::UvLowRisk, "riesgo bajo"),
+    (Key::TimeToBurn, "{} hasta quemarse sin protección"),
+    (Key::PolarDay, "Día polar"),
+    (Key::PolarNight, "Noche polar"),
+    (Key::ShowingCached, "Mostrando datos en caché"),
+    (Key::ClearCachedData, "Borrar datos en caché"),
+    (Key::CacheCleared, "Caché borrada ({} liberados)"),
+    (Key::ClockOff, "El reloj del sistema parece desviado {}"),
+    (Key::AdvisoryTiers, "Avisos:"),
+    (Key::PressureShown, "Presión:"),
+    (Key::SeaLevel, "Nivel del mar"),
+    (Key::Surface, "Superficie"),
+    (Key::ForecastConfidence, "Fiabilidad del pronóstico (una petición más)"),
+    (Key::HighConfidence, "fiabilidad alta"),
+    (Key::MediumConfidence, "fiabilidad media"),
+    (Key::LowConfidence, "fiabilidad baja"),
+    (
+        Key::HighConfidenceNote,
+        "Los modelos coinciden en las temperaturas de este día",
+    ),
+    (
+        Key::MediumConfidenceNote,
+        "Los modelos difieren en unos pocos grados este día",
+    ),
+    (
+        Key::LowConfidenceNote,
+        "Los modelos discrepan mucho este día; es probable que el pronóstico cambie",
+    ),
+    (Key::RefreshName, "Actualizar nombre"),
+    (Key::AcceptMove, "Aceptar la nueva ubicación"),
+    (Key::FavoriteMoved, "El geocodificador ahora lo sitúa en {}; clic derecho para aceptar"),
+    (Key::Refresh, "Actualizar ahora, sin datos en caché"),
+    (Key::OfflineData, "Sin conexión; datos del {}"),
+    (Key::SevenDayForecast, "Previsión a 7 días"),
+    (Key::Average, "Media"),
+    (Key::High, "Máx."),
+    (Key::Low, "Mín."),
+    (Key::Precip, "Precip."),
+    (Key::Wind, "Viento"),
+    (Key::Water, "Riego"),
+    (Key::UvIndex, "Índice UV"),
+    (Key::Sunrise, "Amanecer"),
+    (Key::Sunset, "Atardecer"),
+    (Key::DaylightDuration, "Día"),
+    (Key::SunshineDuration, "Sol"),
+    (Key::VisibilityDownTo, "Visibilidad de hasta {}"),
+    (Key::SevereThunderstorms, "Tormentas fuertes"),
+    (Key::Thunderstorms, "Tormentas"),
+    (Key::StormExpected, "{} previstas {} – {}"),
+    (Key::StormEnergy, "Energía de tormenta"),
+    (Key::CapeMarginal, "Marginal"),
+    (Key::CapeModerate, "Moderada"),
+    (Key::CapeHigh, "Alta"),
+    (Key::CapeExtreme, "Extrema"),
+    (Key::SnowBy, "+{} hasta el {}"),
+    (Key::SnowAbove, "nieve por encima de ~{}"),
+    (Key::Monday, "lunes"),
+    (Key::Tuesday, "martes"),
+    (Key::Wednesday, "miércoles"),
+    (Key::Thursday, "jueves"),
+    (Key::Friday, "viernes"),
+    (Key::Saturday, "sábado"),
+    (Key::Sunday, "domingo"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_key_exists_in_every_locale() {
+        for locale in Locale::ALL {
+            for key in Key::ALL {
+                assert!(
+                    lookup(locale.table(), key).is_some(),
+                    "{locale} is missing {key:?}"
+                );
+            }
+            assert_eq!(
+                locale.table().len(),
+                Key::ALL.len(),
+                "{locale} has extra entries"
+            );
+        }
+    }
+
+    #[test]
+    fn test_locale_parsing() {
+        assert_eq!("de_DE.UTF-8".parse(), Ok(Locale::De));
+        assert_eq!("ES".parse(), Ok(Locale::Es));
+        assert!("fr".parse::<Locale>().is_err());
+        assert_eq!(tr(Locale::De, Key::Search), "Suchen");
+    }
+}
@@ -0,0 +1,137 @@
+//! Location identity helpers for caches, favorites, and history
+//!
+//! Free-text queries are normalized so trivially different spellings share a key,
+//! and resolved coordinates are rounded so different names for the same place
+//! ("NYC" and "New York") share weather data after geocoding.
+
+use std::fmt;
+
+use crate::utils::coords::normalize_longitude;
+
+/// Precision of coordinate keys in degrees (~1 km)
+const COORDINATE_KEY_PRECISION: f64 = 0.01;
+
+/// Normalizes a free-text location query into a stable key.
+///
+/// Trims, collapses internal whitespace, lowercases (Unicode-aware), and strips
+/// trailing commas, so "Seattle", " seattle ", and "SEATTLE," map to the same key.
+pub fn normalize_location_key(query: &str) -> String {
+    query
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+        .trim_end_matches(|c: char| c == ',' || c.is_whitespace())
+        .to_string()
+}
+
+/// Canonical location identity based on coordinates rounded to 0.01°, with
+/// longitudes wrapped into (−180°, 180°] so both sides of the antimeridian
+/// share a key
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CoordinateKey {
+    lat_hundredths: i32,
+    lon_hundredths: i32,
+}
+
+impl CoordinateKey {
+    pub fn new(latitude: f64, longitude: f64) -> Self {
+        let half_turn = (180.0 / COORDINATE_KEY_PRECISION) as i32;
+        let lon_hundredths =
+            (normalize_longitude(longitude) / COORDINATE_KEY_PRECISION).round() as i32;
+        CoordinateKey {
+            lat_hundredths: (latitude.clamp(-90.0, 90.0) / COORDINATE_KEY_PRECISION).round() as i32,
+            // −179.996° rounds onto the seam from the west
+            lon_hundredths: if lon_hundredths <= -half_turn {
+                half_turn
+            } else {
+                lon_hundredths
+            },
+        }
+    }
+
+    pub fn latitude(&self) -> f64 {
+        f64::from(self.lat_hundredths) * COORDINATE_KEY_PRECISION
+    }
+
+    pub fn longitude(&self) -> f64 {
+        f64::from(self.lon_hundredths) * COORDINATE_KEY_PRECISION
+    }
+}
+
+impl fmt::Display for CoordinateKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.2},{:.2}", self.latitude(), self.longitude())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_trims_and_collapses_whitespace() {
+        assert_eq!(normalize_location_key("  Seattle "), "seattle");
+        assert_eq!(normalize_location_key("New \t York"), "new york");
+    }
+
+    #[test]
+    fn test_normalize_strips_trailing_commas() {
+        assert_eq!(normalize_location_key("Seattle,"), "seattle");
+        assert_eq!(normalize_location_key("Seattle, WA ,, "), "seattle, wa");
+    }
+
+    #[test]
+    fn test_normalize_unicode_case() {
+        assert_eq!(
+            normalize_location_key("MÜNCHEN"),
+            normalize_location_key("münchen")
+        );
+        assert_eq!(normalize_location_key("ÎLE-DE-FRANCE"), "île-de-france");
+        assert_eq!(normalize_location_key("ΑΘΗΝΑ"), "αθηνα");
+    }
+
+    #[test]
+    fn test_normalize_empty() {
+        assert_eq!(normalize_location_key("   "), "");
+        assert_eq!(normalize_location_key(",,"), "");
+    }
+
+    #[test]
+    fn test_coordinate_key_rounding_identity() {
+        let nyc = CoordinateKey::new(40.7128, -74.0060);
+        let new_york = CoordinateKey::new(40.7143, -74.0058);
+        assert_eq!(nyc, new_york);
+        assert_eq!(nyc.to_string(), "40.71,-74.01");
+    }
+
+    #[test]
+    fn test_coordinate_key_distinguishes_nearby_places() {
+        let a = CoordinateKey::new(47.60, -122.33);
+        let b = CoordinateKey::new(47.62, -122.33);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_coordinate_key_across_the_antimeridian() {
+        let east = CoordinateKey::new(-16.78, 179.998);
+        let west = CoordinateKey::new(-16.78, -179.998);
+        assert_eq!(east, west);
+        assert_eq!(east, CoordinateKey::new(-16.78, -180.0));
+        assert_eq!(east.to_string(), "-16.78,180.00");
+        assert_eq!(
+            CoordinateKey::new(10.0, 190.0),
+            CoordinateKey::new(10.0, -170.0)
+        );
+        assert_ne!(
+            CoordinateKey::new(-16.78, 179.98),
+            CoordinateKey::new(-16.78, -179.98)
+        );
+        // Every longitude is one place at the poles, but keys keep them apart
+        assert_eq!(CoordinateKey::new(90.0, 0.0).to_string(), "90.00,0.00");
+        assert_eq!(
+            CoordinateKey::new(-90.0, -180.0).to_string(),
+            "-90.00,180.00"
+        );
+    }
+}
@@ -1 +1,27 @@
+pub mod advice;
+pub mod advisories;
+pub mod agriculture;
+pub mod aviation;
+pub mod clock;
+pub mod confidence;
 pub mod conversions;
+pub mod coords;
+pub mod daylight;
+pub mod digest;
+pub mod fog;
+pub mod forecast_change;
+pub mod format;
+pub mod freshness;
+pub mod hourly_range;
+pub mod i18n;
+pub mod location;
+pub mod mountain;
+pub mod precip_phase;
+pub mod precipitation;
+pub mod shutdown;
+pub mod snow;
+pub mod storm;
+pub mod terminal;
+pub mod time;
+pub mod uv;
+pub mod weather_codes;
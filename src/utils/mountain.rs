@@ -0,0 +1,44 @@
+//! Mountain forecast helpers (freezing level and snow line)
+
+use crate::constants::MOUNTAIN_ELEVATION_THRESHOLD_M;
+
+/// Typical distance (meters) snow falls below the freezing level before melting.
+///
+/// Falling snow needs roughly 150–300 m of above-freezing air to melt completely,
+/// so the snow line usually sits that far below the 0°C isotherm. The lower bound
+/// is used so the estimate reports the highest elevation where snow is likely.
+pub const SNOW_LINE_OFFSET_M: f64 = 150.0;
+
+/// Estimates the snow line (meters above sea level) from the freezing level height
+pub fn snow_line(freezing_level_m: f64) -> f64 {
+    (freezing_level_m - SNOW_LINE_OFFSET_M).max(0.0)
+}
+
+/// Whether mountain data should be shown for a location at this elevation
+pub fn is_mountain_elevation(elevation_m: Option<f64>) -> bool {
+    elevation_m.is_some_and(|e| e > MOUNTAIN_ELEVATION_THRESHOLD_M)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snow_line_below_freezing_level() {
+        assert_eq!(snow_line(1850.0), 1700.0);
+    }
+
+    #[test]
+    fn test_snow_line_never_negative() {
+        assert_eq!(snow_line(100.0), 0.0);
+        assert_eq!(snow_line(0.0), 0.0);
+    }
+
+    #[test]
+    fn test_is_mountain_elevation() {
+        assert!(is_mountain_elevation(Some(1200.0)));
+        assert!(!is_mountain_elevation(Some(500.0)));
+        assert!(!is_mountain_elevation(Some(20.0)));
+        assert!(!is_mountain_elevation(None));
+    }
+}
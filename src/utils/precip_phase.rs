@@ -0,0 +1,198 @@
+//! Rain or snow near freezing
+//!
+//! Between about -1°C and 3°C the weather code is a coin toss for what
+//! actually reaches the ground: dry air cools falling precipitation by
+//! evaporation, so rain in dry air often arrives as wet snow, while snow in
+//! humid air just above freezing melts on the way down. The wet-bulb
+//! temperature captures both and is estimated here from temperature and dew
+//! point. The views only annotate the official description with the result;
+//! they never replace it.
+
+use crate::utils::conversions::Temperature;
+use crate::utils::weather_codes::WeatherCode;
+
+/// Air temperatures in °C where the heuristic is consulted; outside them the
+/// weather code is always taken as it is
+const MARGINAL_BAND: (f64, f64) = (-1.0, 3.0);
+
+/// Wet-bulb temperature in °C up to which precipitation falls as snow
+const SNOW_WET_BULB: f64 = 0.5;
+
+/// Wet-bulb temperature in °C up to which rain and snow fall mixed
+const SLEET_WET_BULB: f64 = 1.5;
+
+/// Largest dew point depression in °C at which rain reported into sub-zero
+/// air is taken to stay liquid until it lands and freezes
+const FREEZING_MAX_SPREAD: f64 = 1.5;
+
+/// What precipitation is like when it reaches the ground
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    Rain,
+    /// Rain and snow mixed, or wet snow that melts on landing
+    Sleet,
+    Snow,
+    /// Rain or drizzle that freezes on contact
+    Freezing,
+}
+
+impl Phase {
+    /// Phase the weather code describes, `None` for codes without precipitation
+    pub fn from_code(code: i32) -> Option<Self> {
+        Some(match WeatherCode::from_wmo(code)? {
+            WeatherCode::Drizzle
+            | WeatherCode::Rain
+            | WeatherCode::RainShowers
+            | WeatherCode::Thunderstorm
+            | WeatherCode::ThunderstormHail => Phase::Rain,
+            WeatherCode::FreezingDrizzle | WeatherCode::FreezingRain => Phase::Freezing,
+            WeatherCode::Snow | WeatherCode::SnowGrains | WeatherCode::SnowShowers => Phase::Snow,
+            WeatherCode::Clear
+            | WeatherCode::MainlyClear
+            | WeatherCode::PartlyCloudy
+            | WeatherCode::Overcast
+            | WeatherCode::Fog => return None,
+        })
+    }
+
+    /// Annotation for a description whose code suggests another phase
+    pub fn note(self) -> &'static str {
+        match self {
+            Phase::Rain => "(may fall as rain)",
+            Phase::Sleet => "(may fall as sleet)",
+            Phase::Snow => "(may fall as wet snow)",
+            Phase::Freezing => "(may freeze on contact)",
+        }
+    }
+}
+
+/// Wet-bulb temperature in °C by the one-third rule, good to about half a
+/// degree near freezing
+pub fn wet_bulb(temp_c: f64, dew_point_c: f64) -> f64 {
+    temp_c - (temp_c - dew_point_c.min(temp_c)) / 3.0
+}
+
+/// Phase most likely to reach the ground for a weather code, `None` when
+/// the code has no precipitation. Outside the marginal band, and for codes
+/// that already say freezing, this is the code's own phase.
+pub fn likely_phase(temp_c: f64, dew_point_c: f64, code: i32) -> Option<Phase> {
+    let official = Phase::from_code(code)?;
+    let (low, high) = MARGINAL_BAND;
+    if !(low..=high).contains(&temp_c) || official == Phase::Freezing {
+        return Some(official);
+    }
+
+    let wet_bulb = wet_bulb(temp_c, dew_point_c);
+    Some(
+        if official == Phase::Rain && temp_c < 0.0 && temp_c - dew_point_c < FREEZING_MAX_SPREAD {
+            // Rain into near-saturated sub-zero air: too little evaporation to
+            // turn it to snow before it freezes on the ground
+            Phase::Freezing
+        } else if wet_bulb <= SNOW_WET_BULB {
+            Phase::Snow
+        } else if wet_bulb <= SLEET_WET_BULB {
+            Phase::Sleet
+        } else {
+            Phase::Rain
+        },
+    )
+}
+
+/// The note to append to a code's description when temperature and
+/// humidity suggest another phase; `None` when they agree or data is missing
+pub fn phase_note(
+    temperature: Option<f64>,
+    humidity: Option<f64>,
+    code: i32,
+) -> Option<&'static str> {
+    let (temperature, humidity) = (temperature?, humidity?);
+    let dew_point = Temperature::dew_point(temperature, humidity);
+    let likely = likely_phase(temperature, dew_point, code)?;
+    (Phase::from_code(code) != Some(likely)).then(|| likely.note())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RAIN: i32 = 63;
+    const SNOW: i32 = 73;
+    const FREEZING_RAIN: i32 = 66;
+
+    #[test]
+    fn test_wet_bulb() {
+        assert!((wet_bulb(3.0, 3.0) - 3.0).abs() < 1e-9);
+        assert!((wet_bulb(3.0, -3.0) - 1.0).abs() < 1e-9);
+        // A dew point above the temperature is treated as saturation
+        assert!((wet_bulb(1.0, 2.0) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_marginal_grid() {
+        // (temperature, dew point, phase for a rain code, for a snow code)
+        let grid = [
+            (2.5, 2.5, Phase::Rain, Phase::Rain),
+            (2.5, 0.0, Phase::Rain, Phase::Rain),
+            (2.5, -2.0, Phase::Sleet, Phase::Sleet),
+            (2.5, -5.0, Phase::Snow, Phase::Snow),
+            (1.5, 1.5, Phase::Sleet, Phase::Sleet),
+            (1.5, -1.5, Phase::Snow, Phase::Snow),
+            (1.0, 0.5, Phase::Sleet, Phase::Sleet),
+            (1.0, -2.0, Phase::Snow, Phase::Snow),
+            (0.5, 0.5, Phase::Snow, Phase::Snow),
+            (0.0, -1.0, Phase::Snow, Phase::Snow),
+            (-0.5, -0.5, Phase::Freezing, Phase::Snow),
+            (-0.5, -4.0, Phase::Snow, Phase::Snow),
+            (-1.0, -1.0, Phase::Freezing, Phase::Snow),
+        ];
+        for (temp, dew_point, rain, snow) in grid {
+            assert_eq!(
+                likely_phase(temp, dew_point, RAIN),
+                Some(rain),
+                "rain code at {temp}°C, dew point {dew_point}°C"
+            );
+            assert_eq!(
+                likely_phase(temp, dew_point, SNOW),
+                Some(snow),
+                "snow code at {temp}°C, dew point {dew_point}°C"
+            );
+        }
+    }
+
+    #[test]
+    fn test_outside_band_defers_to_code() {
+        for code in 0..=99 {
+            for temp in [-30.0, -10.0, -1.1, 3.1, 8.0, 25.0] {
+                for dew_point in [temp - 15.0, temp - 3.0, temp] {
+                    assert_eq!(
+                        likely_phase(temp, dew_point, code),
+                        Phase::from_code(code),
+                        "code {code} at {temp}°C"
+                    );
+                }
+            }
+        }
+        // Freezing rain is kept in the band too, and dry codes have no phase
+        assert_eq!(
+            likely_phase(1.0, -5.0, FREEZING_RAIN),
+            Some(Phase::Freezing)
+        );
+        assert_eq!(likely_phase(1.0, -5.0, 3), None);
+    }
+
+    #[test]
+    fn test_note_only_on_disagreement() {
+        assert_eq!(
+            phase_note(Some(1.0), Some(60.0), RAIN),
+            Some("(may fall as wet snow)")
+        );
+        assert_eq!(phase_note(Some(1.0), Some(60.0), SNOW), None);
+        assert_eq!(
+            phase_note(Some(2.5), Some(100.0), SNOW),
+            Some("(may fall as rain)")
+        );
+        assert_eq!(phase_note(Some(8.0), Some(40.0), RAIN), None);
+        assert_eq!(phase_note(None, Some(60.0), RAIN), None);
+        assert_eq!(phase_note(Some(1.0), None, RAIN), None);
+    }
+}
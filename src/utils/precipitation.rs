@@ -0,0 +1,91 @@
+//! Precipitation and snowfall expected over the coming hours
+
+use crate::models::weather_info::{AccumulationTotal, WeatherData};
+use chrono::TimeDelta;
+
+/// Rain and snow totals for the hours after the current observation
+#[derive(Clone, Debug, PartialEq)]
+pub struct PrecipitationOutlook {
+    pub hours: i64,
+    /// Precipitation in mm
+    pub precipitation: Option<AccumulationTotal>,
+    /// Snowfall in cm
+    pub snow: Option<AccumulationTotal>,
+}
+
+impl PrecipitationOutlook {
+    /// e.g. "Next 24 h: 8.0 mm, 2.5 cm snow (18 of 24 h reported)"
+    pub fn message(&self) -> String {
+        format!("Next {} h: {}", self.hours, self.amounts())
+    }
+
+    /// The message without the "Next 24 h" prefix
+    pub fn amounts(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(precipitation) = &self.precipitation {
+            parts.push(format!("{:.1} mm", precipitation.total));
+        }
+        if let Some(snow) = self.snow.as_ref().filter(|s| s.total > 0.0) {
+            parts.push(format!("{:.1} cm snow", snow.total));
+        }
+
+        let mut message = parts.join(", ");
+        let coverage = self.precipitation.as_ref().or(self.snow.as_ref());
+        if let Some(total) = coverage.filter(|t| !t.is_complete()) {
+            message.push_str(&format!(
+                " ({} of {} h reported)",
+                total.hours_with_data, total.window_hours
+            ));
+        }
+        message
+    }
+}
+
+/// Totals for the next `hours` hours, or `None` when nothing is expected or reported
+pub fn outlook(data: &WeatherData, hours: i64) -> Option<PrecipitationOutlook> {
+    let from = data.current_hour_start()?;
+    let window = TimeDelta::hours(hours);
+    let precipitation = data.precipitation_total(window, from);
+    let snow = data.snow_total(window, from);
+
+    let expected =
+        |total: &Option<AccumulationTotal>| total.as_ref().is_some_and(|t| t.total > 0.0);
+    if !expected(&precipitation) && !expected(&snow) {
+        return None;
+    }
+
+    Some(PrecipitationOutlook {
+        hours,
+        precipitation,
+        snow,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::sample_weather_info;
+
+    #[test]
+    fn test_outlook_message() {
+        let info = sample_weather_info();
+        let day = outlook(&info.weather_data, 24).unwrap();
+        assert_eq!(day.message(), "Next 24 h: 1.2 mm");
+
+        let two_days = outlook(&info.weather_data, 48).unwrap();
+        assert_eq!(
+            two_days.message(),
+            "Next 48 h: 7.2 mm (34 of 48 h reported)"
+        );
+    }
+
+    #[test]
+    fn test_no_outlook_when_dry() {
+        let mut info = sample_weather_info();
+        for hour in &mut info.weather_data.hourly {
+            hour.precipitation = Some(0.0);
+        }
+        assert_eq!(outlook(&info.weather_data, 24), None);
+        assert_eq!(outlook(&WeatherData::default(), 24), None);
+    }
+}
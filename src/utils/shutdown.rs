@@ -0,0 +1,62 @@
+//! Graceful shutdown on Ctrl-C / SIGTERM for long-running CLI modes
+//!
+//! Loops poll `Shutdown::is_requested` between units of work instead of being
+//! killed mid-write, then call `cleanup` and exit with `INTERRUPTED_EXIT_CODE`.
+
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Conventional exit status for a process stopped by SIGINT (128 + 2)
+pub const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// Shared flag set when the user or the service manager asks the process to stop
+#[derive(Clone, Debug, Default)]
+pub struct Shutdown(Arc<AtomicBool>);
+
+impl Shutdown {
+    /// Installs a SIGINT/SIGTERM handler that sets the flag. Can only be called once per process.
+    pub fn install() -> Result<Self, ctrlc::Error> {
+        let shutdown = Shutdown::default();
+        let handler = shutdown.clone();
+        ctrlc::set_handler(move || handler.request())?;
+        Ok(shutdown)
+    }
+
+    /// Sets the flag, as the signal handler does
+    pub fn request(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_requested(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Flushes pending output and reports the interruption before exiting
+pub fn cleanup(out: &mut impl Write, err: &mut impl Write) -> io::Result<()> {
+    out.flush()?;
+    writeln!(err, "Interrupted")?;
+    err.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flag_is_shared_between_clones() {
+        let shutdown = Shutdown::default();
+        let handler = shutdown.clone();
+        assert!(!shutdown.is_requested());
+        handler.request();
+        assert!(shutdown.is_requested());
+    }
+
+    #[test]
+    fn test_cleanup_reports_interruption() {
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        cleanup(&mut out, &mut err).unwrap();
+        assert_eq!(String::from_utf8(err).unwrap(), "Interrupted\n");
+    }
+}
@@ -0,0 +1,95 @@
+//! Snow depth and snowfall accumulation helpers
+
+use chrono::TimeDelta;
+
+use crate::models::weather_info::{AccumulationTotal, WeatherData};
+
+/// Snowfall expected over the hours after the current observation
+#[derive(Clone, Debug, PartialEq)]
+pub struct SnowAccumulation {
+    /// Snowfall in cm, with how much of the window reported it
+    pub total: AccumulationTotal,
+    /// Last hour in the window (ISO 8601)
+    pub until: String,
+}
+
+/// Snowfall over the next `hours` hours, the same `WeatherData::snow_total`
+/// the precipitation outlook reports.
+///
+/// Returns `None` when no hour in the window reports snowfall, so a missing
+/// series is never mistaken for "no snow expected".
+pub fn snowfall_accumulation(data: &WeatherData, hours: i64) -> Option<SnowAccumulation> {
+    let from = data.current_hour_start()?;
+    let window = TimeDelta::hours(hours);
+    let total = data.snow_total(window, from)?;
+    let until = data.hours_within(window, from).last()?.time.clone();
+    Some(SnowAccumulation { total, until })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::weather_info::HourlyForecast;
+
+    fn data(hours: &[(&str, Option<f64>)]) -> WeatherData {
+        let mut data = WeatherData::default();
+        data.current.time = Some(hours[0].0.to_string());
+        data.hourly = hours
+            .iter()
+            .map(|(time, snowfall)| HourlyForecast {
+                time: time.to_string(),
+                snowfall: *snowfall,
+                ..Default::default()
+            })
+            .collect();
+        data
+    }
+
+    #[test]
+    fn test_accumulation_sums_series() {
+        let data = data(&[
+            ("2024-01-13T10:00", Some(0.5)),
+            ("2024-01-13T11:00", Some(1.5)),
+            ("2024-01-13T12:00", Some(0.0)),
+        ]);
+
+        let accumulation = snowfall_accumulation(&data, 3).unwrap();
+        assert!((accumulation.total.total - 2.0).abs() < 1e-9);
+        assert!(accumulation.total.is_complete());
+        assert_eq!(accumulation.until, "2024-01-13T12:00");
+    }
+
+    #[test]
+    fn test_accumulation_reports_gaps() {
+        let data = data(&[
+            ("2024-01-13T10:00", Some(1.2)),
+            ("2024-01-13T11:00", None),
+            ("2024-01-13T12:00", Some(0.8)),
+            ("2024-01-13T13:00", None),
+        ]);
+
+        let accumulation = snowfall_accumulation(&data, 48).unwrap();
+        assert!((accumulation.total.total - 2.0).abs() < 1e-9);
+        assert_eq!(accumulation.until, "2024-01-13T13:00");
+        assert_eq!(
+            (
+                accumulation.total.hours_with_data,
+                accumulation.total.window_hours
+            ),
+            (2, 48)
+        );
+        // The same total as the precipitation outlook's
+        let from = data.current_hour_start().unwrap();
+        assert_eq!(
+            Some(accumulation.total),
+            data.snow_total(TimeDelta::hours(48), from)
+        );
+    }
+
+    #[test]
+    fn test_accumulation_none_without_data() {
+        let data = data(&[("2024-01-13T10:00", None), ("2024-01-13T11:00", None)]);
+        assert_eq!(snowfall_accumulation(&data, 48), None);
+        assert_eq!(snowfall_accumulation(&WeatherData::default(), 48), None);
+    }
+}
@@ -0,0 +1,232 @@
+//! Thunderstorm detection and storm-energy (CAPE) classification
+
+use crate::models::weather_info::HourlyForecast;
+
+/// Convective available potential energy category (J/kg)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CapeLevel {
+    /// Below 1000 J/kg
+    Marginal,
+    /// 1000 to 2500 J/kg
+    Moderate,
+    /// 2500 to 3500 J/kg
+    High,
+    /// Above 3500 J/kg
+    Extreme,
+}
+
+impl CapeLevel {
+    /// Classifies a CAPE value in J/kg
+    pub fn from_cape(cape: f64) -> Self {
+        match cape {
+            c if c < 1000.0 => CapeLevel::Marginal,
+            c if c < 2500.0 => CapeLevel::Moderate,
+            c if c <= 3500.0 => CapeLevel::High,
+            _ => CapeLevel::Extreme,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CapeLevel::Marginal => "Marginal",
+            CapeLevel::Moderate => "Moderate",
+            CapeLevel::High => "High",
+            CapeLevel::Extreme => "Extreme",
+        }
+    }
+
+    /// Whether this much storm energy supports severe thunderstorms
+    pub fn is_severe(&self) -> bool {
+        *self >= CapeLevel::High
+    }
+}
+
+/// Thunderstorm severity for a forecast period
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StormSeverity {
+    Thunderstorm,
+    /// Thunderstorm with High or Extreme CAPE
+    Severe,
+}
+
+/// Consecutive forecast hours with thunderstorms
+#[derive(Clone, Debug, PartialEq)]
+pub struct StormPeriod {
+    /// First hour of the period (ISO 8601)
+    pub start: String,
+    /// Last hour of the period (ISO 8601)
+    pub end: String,
+    pub severity: StormSeverity,
+    /// Highest CAPE during the period in J/kg
+    pub peak_cape: Option<f64>,
+}
+
+/// Window of hours around the highest CAPE value
+#[derive(Clone, Debug, PartialEq)]
+pub struct CapeWindow {
+    pub start: String,
+    pub end: String,
+    pub peak_time: String,
+    pub peak_cape: f64,
+    pub level: CapeLevel,
+}
+
+/// Fraction of the peak CAPE an hour must reach to count as part of the peak window
+const PEAK_WINDOW_FRACTION: f64 = 0.75;
+
+/// Whether a WMO weather code is a thunderstorm (95, 96, 99)
+pub fn is_thunderstorm_code(code: i32) -> bool {
+    (95..=99).contains(&code)
+}
+
+/// Groups consecutive thunderstorm hours into periods, upgrading to severe on High+ CAPE
+pub fn thunderstorm_periods(hourly: &[HourlyForecast]) -> Vec<StormPeriod> {
+    let mut periods = Vec::new();
+    let mut current: Option<StormPeriod> = None;
+
+    for hour in hourly {
+        if !hour.weather_code.is_some_and(is_thunderstorm_code) {
+            periods.extend(current.take());
+            continue;
+        }
+
+        let period = current.get_or_insert_with(|| StormPeriod {
+            start: hour.time.clone(),
+            end: hour.time.clone(),
+            severity: StormSeverity::Thunderstorm,
+            peak_cape: None,
+        });
+        period.end = hour.time.clone();
+        if let Some(cape) = hour.cape {
+            period.peak_cape = Some(period.peak_cape.map_or(cape, |peak| peak.max(cape)));
+            if CapeLevel::from_cape(cape).is_severe() {
+                period.severity = StormSeverity::Severe;
+            }
+        }
+    }
+    periods.extend(current);
+
+    periods
+}
+
+/// Finds the peak CAPE hour and the surrounding hours within 75% of that peak
+pub fn peak_cape_window(hourly: &[HourlyForecast]) -> Option<CapeWindow> {
+    let (peak_index, peak_cape) = hourly
+        .iter()
+        .enumerate()
+        .filter_map(|(i, h)| h.cape.map(|c| (i, c)))
+        .fold(None, |best: Option<(usize, f64)>, (i, c)| match best {
+            Some((_, best_cape)) if best_cape >= c => best,
+            _ => Some((i, c)),
+        })?;
+
+    if peak_cape <= 0.0 {
+        return None;
+    }
+
+    let threshold = peak_cape * PEAK_WINDOW_FRACTION;
+    let in_window = |h: &HourlyForecast| h.cape.is_some_and(|c| c >= threshold);
+
+    let start = hourly[..peak_index]
+        .iter()
+        .rposition(|h| !in_window(h))
+        .map_or(0, |i| i + 1);
+    let end = hourly[peak_index..]
+        .iter()
+        .position(|h| !in_window(h))
+        .map_or(hourly.len() - 1, |i| peak_index + i - 1);
+
+    Some(CapeWindow {
+        start: hourly[start].time.clone(),
+        end: hourly[end].time.clone(),
+        peak_time: hourly[peak_index].time.clone(),
+        peak_cape,
+        level: CapeLevel::from_cape(peak_cape),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hour(time: &str, code: i32, cape: Option<f64>) -> HourlyForecast {
+        let hour = HourlyForecast::builder(time).weather_code(code);
+        match cape {
+            Some(cape) => hour.cape(cape).build(),
+            None => hour.build(),
+        }
+    }
+
+    #[test]
+    fn test_cape_level_thresholds() {
+        assert_eq!(CapeLevel::from_cape(0.0), CapeLevel::Marginal);
+        assert_eq!(CapeLevel::from_cape(999.9), CapeLevel::Marginal);
+        assert_eq!(CapeLevel::from_cape(1000.0), CapeLevel::Moderate);
+        assert_eq!(CapeLevel::from_cape(2499.0), CapeLevel::Moderate);
+        assert_eq!(CapeLevel::from_cape(2500.0), CapeLevel::High);
+        assert_eq!(CapeLevel::from_cape(3500.0), CapeLevel::High);
+        assert_eq!(CapeLevel::from_cape(3500.1), CapeLevel::Extreme);
+    }
+
+    #[test]
+    fn test_cape_level_severity() {
+        assert!(!CapeLevel::Marginal.is_severe());
+        assert!(!CapeLevel::Moderate.is_severe());
+        assert!(CapeLevel::High.is_severe());
+        assert!(CapeLevel::Extreme.is_severe());
+    }
+
+    #[test]
+    fn test_thunderstorm_periods_upgrade_on_high_cape() {
+        let hourly = vec![
+            hour("2024-06-01T12:00", 2, Some(800.0)),
+            hour("2024-06-01T13:00", 95, Some(1800.0)),
+            hour("2024-06-01T14:00", 95, Some(2900.0)),
+            hour("2024-06-01T15:00", 3, Some(1200.0)),
+            hour("2024-06-01T16:00", 95, Some(900.0)),
+        ];
+
+        let periods = thunderstorm_periods(&hourly);
+        assert_eq!(periods.len(), 2);
+        assert_eq!(periods[0].start, "2024-06-01T13:00");
+        assert_eq!(periods[0].end, "2024-06-01T14:00");
+        assert_eq!(periods[0].severity, StormSeverity::Severe);
+        assert_eq!(periods[0].peak_cape, Some(2900.0));
+        assert_eq!(periods[1].severity, StormSeverity::Thunderstorm);
+    }
+
+    #[test]
+    fn test_thunderstorm_without_cape_stays_unclassified() {
+        let hourly = vec![hour("2024-06-01T13:00", 99, None)];
+        let periods = thunderstorm_periods(&hourly);
+        assert_eq!(periods[0].severity, StormSeverity::Thunderstorm);
+        assert_eq!(periods[0].peak_cape, None);
+    }
+
+    #[test]
+    fn test_peak_cape_window() {
+        let hourly = vec![
+            hour("2024-06-01T12:00", 1, Some(1000.0)),
+            hour("2024-06-01T13:00", 1, Some(2400.0)),
+            hour("2024-06-01T14:00", 1, Some(3000.0)),
+            hour("2024-06-01T15:00", 1, None),
+            hour("2024-06-01T16:00", 1, Some(2800.0)),
+        ];
+
+        let window = peak_cape_window(&hourly).unwrap();
+        assert_eq!(window.start, "2024-06-01T13:00");
+        assert_eq!(window.end, "2024-06-01T14:00");
+        assert_eq!(window.peak_time, "2024-06-01T14:00");
+        assert_eq!(window.level, CapeLevel::High);
+    }
+
+    #[test]
+    fn test_peak_cape_window_none_without_energy() {
+        let hourly = vec![
+            hour("2024-06-01T12:00", 0, Some(0.0)),
+            hour("2024-06-01T13:00", 0, None),
+        ];
+        assert_eq!(peak_cape_window(&hourly), None);
+        assert_eq!(peak_cape_window(&[]), None);
+    }
+}
@@ -0,0 +1,78 @@
+//! Output width detection for the console view
+
+use std::io::IsTerminal;
+
+/// Width used when stdout is not a terminal, so piped output is stable
+pub const PIPED_OUTPUT_WIDTH: usize = 100;
+
+/// Narrowest width the text view lays out for
+pub const MIN_OUTPUT_WIDTH: usize = 40;
+
+/// Columns available for text output: the `--width` override, then the terminal
+/// size, then `$COLUMNS`; piped output uses a fixed 100 columns
+pub fn output_width(override_width: Option<usize>) -> usize {
+    let stdout = std::io::stdout();
+    let is_terminal = stdout.is_terminal();
+    let terminal = terminal_size::terminal_size_of(&stdout).map(|(w, _)| usize::from(w.0));
+    resolve_width(
+        override_width,
+        is_terminal,
+        terminal,
+        std::env::var("COLUMNS").ok().as_deref(),
+    )
+}
+
+fn resolve_width(
+    override_width: Option<usize>,
+    is_terminal: bool,
+    terminal: Option<usize>,
+    columns: Option<&str>,
+) -> usize {
+    let detected = || {
+        if !is_terminal {
+            return None;
+        }
+        terminal
+            .filter(|w| *w > 0)
+            .or_else(|| columns.and_then(|c| c.trim().parse().ok()))
+    };
+
+    override_width
+        .or_else(detected)
+        .unwrap_or(PIPED_OUTPUT_WIDTH)
+        .max(MIN_OUTPUT_WIDTH)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_override_wins() {
+        assert_eq!(resolve_width(Some(72), true, Some(200), Some("90")), 72);
+        assert_eq!(resolve_width(Some(72), false, None, None), 72);
+    }
+
+    #[test]
+    fn test_terminal_then_columns() {
+        assert_eq!(resolve_width(None, true, Some(132), Some("90")), 132);
+        assert_eq!(resolve_width(None, true, None, Some("90")), 90);
+        assert_eq!(
+            resolve_width(None, true, None, Some("wide")),
+            PIPED_OUTPUT_WIDTH
+        );
+    }
+
+    #[test]
+    fn test_piped_output_is_fixed() {
+        assert_eq!(
+            resolve_width(None, false, None, Some("60")),
+            PIPED_OUTPUT_WIDTH
+        );
+    }
+
+    #[test]
+    fn test_minimum_width() {
+        assert_eq!(resolve_width(Some(10), true, None, None), MIN_OUTPUT_WIDTH);
+    }
+}
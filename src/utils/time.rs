@@ -0,0 +1,183 @@
+//! Helpers for the ISO 8601 timestamps returned by Open-Meteo
+
+use chrono::{NaiveDate, NaiveDateTime, TimeDelta};
+
+/// Parses an hourly timestamp such as "2024-06-01T14:00"
+pub fn parse_hour(time: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(time, "%Y-%m-%dT%H:%M").ok()
+}
+
+/// Parses a daily date such as "2024-06-01"
+pub fn parse_date(date: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+}
+
+/// Jump of the local clock between two consecutive hourly entries, as when
+/// daylight-saving time starts or ends. Open-Meteo's local-time series then
+/// skips or repeats an hour, although the entries remain one real hour apart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClockChange {
+    /// Clocks went forward; this much local time was skipped
+    Forward(TimeDelta),
+    /// Clocks went back; this much local time repeats
+    Back(TimeDelta),
+}
+
+impl ClockChange {
+    /// Longest shift taken for a clock change; wider gaps are missing data
+    const MAX_SHIFT: TimeDelta = TimeDelta::hours(1);
+
+    /// Clock change between consecutive entries starting at `previous` and `next`
+    pub fn between(previous: NaiveDateTime, next: NaiveDateTime) -> Option<Self> {
+        let hour = TimeDelta::hours(1);
+        let gap = next - previous;
+        if gap == hour {
+            None
+        } else if gap < hour {
+            let shift = hour - gap;
+            (shift <= Self::MAX_SHIFT).then_some(ClockChange::Back(shift))
+        } else {
+            let shift = gap - hour;
+            (shift <= Self::MAX_SHIFT).then_some(ClockChange::Forward(shift))
+        }
+    }
+
+    /// Real time between consecutive entries: one hour across a clock change,
+    /// otherwise the difference of their timestamps
+    pub fn step(previous: NaiveDateTime, next: NaiveDateTime) -> TimeDelta {
+        match Self::between(previous, next) {
+            Some(_) => TimeDelta::hours(1),
+            None => next - previous,
+        }
+    }
+
+    /// "clocks go forward 1 h", "clocks go back 30 min"
+    pub fn note(&self) -> String {
+        let (direction, shift) = match self {
+            ClockChange::Forward(shift) => ("forward", shift),
+            ClockChange::Back(shift) => ("back", shift),
+        };
+        if shift.num_minutes() % 60 == 0 {
+            format!("clocks go {direction} {} h", shift.num_hours())
+        } else {
+            format!("clocks go {direction} {} min", shift.num_minutes())
+        }
+    }
+}
+
+/// Real time from the first of `times` to each of them (so the first is zero),
+/// stepping one hour across clock changes and past unreadable timestamps
+pub fn elapsed<'a>(times: impl IntoIterator<Item = &'a str>) -> Vec<TimeDelta> {
+    let mut total = TimeDelta::zero();
+    let mut previous: Option<NaiveDateTime> = None;
+    let mut offsets = Vec::new();
+    for (i, time) in times.into_iter().enumerate() {
+        let current = parse_hour(time);
+        if i > 0 {
+            total += match (previous, current) {
+                (Some(previous), Some(current)) => ClockChange::step(previous, current),
+                _ => TimeDelta::hours(1),
+            };
+        }
+        offsets.push(total);
+        previous = current;
+    }
+    offsets
+}
+
+/// Full weekday name ("Sunday") for an hourly timestamp
+pub fn weekday_name(time: &str) -> Option<String> {
+    parse_hour(time).map(|t| t.format("%A").to_string())
+}
+
+/// "today", "tomorrow" or the weekday name of `date` relative to `today`
+/// (see `LocationClock::today` for the location's date)
+pub fn relative_day(date: NaiveDate, today: NaiveDate) -> String {
+    match (date - today).num_days() {
+        0 => "today".to_string(),
+        1 => "tomorrow".to_string(),
+        _ => date.format("%A").to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hour() {
+        let parsed = parse_hour("2024-06-01T14:00").unwrap();
+        assert_eq!(parsed.format("%H:%M").to_string(), "14:00");
+        assert!(parse_hour("2024-06-01").is_none());
+    }
+
+    #[test]
+    fn test_clock_changes_between_hours() {
+        let at = |time| parse_hour(time).unwrap();
+        let between = |a, b| ClockChange::between(at(a), at(b));
+        assert_eq!(between("2024-03-31T01:00", "2024-03-31T02:00"), None);
+        // Spring forward: 02:00 does not exist
+        assert_eq!(
+            between("2024-03-31T01:00", "2024-03-31T03:00"),
+            Some(ClockChange::Forward(TimeDelta::hours(1)))
+        );
+        // Fall back: 02:00 happens twice
+        assert_eq!(
+            between("2024-10-27T02:00", "2024-10-27T02:00"),
+            Some(ClockChange::Back(TimeDelta::hours(1)))
+        );
+        // Lord Howe Island shifts by half an hour
+        assert_eq!(
+            between("2024-04-07T01:00", "2024-04-07T01:30"),
+            Some(ClockChange::Back(TimeDelta::minutes(30)))
+        );
+        // Three missing hours are a gap in the data, not a clock change
+        assert_eq!(between("2024-06-01T01:00", "2024-06-01T05:00"), None);
+        assert_eq!(
+            ClockChange::Back(TimeDelta::minutes(30)).note(),
+            "clocks go back 30 min"
+        );
+    }
+
+    #[test]
+    fn test_elapsed_counts_real_hours() {
+        let hours = |offsets: Vec<TimeDelta>| -> Vec<i64> {
+            offsets.iter().map(TimeDelta::num_hours).collect()
+        };
+        let spring = [
+            "2024-03-31T00:00",
+            "2024-03-31T01:00",
+            "2024-03-31T03:00",
+            "2024-03-31T04:00",
+        ];
+        assert_eq!(hours(elapsed(spring)), [0, 1, 2, 3]);
+        let fall = [
+            "2024-10-27T01:00",
+            "2024-10-27T02:00",
+            "2024-10-27T02:00",
+            "2024-10-27T03:00",
+        ];
+        assert_eq!(hours(elapsed(fall)), [0, 1, 2, 3]);
+        let gap = [
+            "2024-06-01T00:00",
+            "2024-06-01T04:00",
+            "garbage",
+            "2024-06-01T06:00",
+        ];
+        assert_eq!(hours(elapsed(gap)), [0, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_relative_day() {
+        let date = |d| NaiveDate::from_ymd_opt(2024, 6, d).unwrap();
+        assert_eq!(relative_day(date(1), date(1)), "today");
+        assert_eq!(relative_day(date(2), date(1)), "tomorrow");
+        assert_eq!(relative_day(date(4), date(1)), "Tuesday");
+    }
+
+    #[test]
+    fn test_weekday_name() {
+        assert_eq!(weekday_name("2024-06-02T08:00").as_deref(), Some("Sunday"));
+        assert_eq!(weekday_name("garbage"), None);
+    }
+}
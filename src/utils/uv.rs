@@ -0,0 +1,217 @@
+//! UV index categories (WHO Global Solar UV Index) and time to sunburn
+//!
+//! Sunburn times use the minimal erythemal dose (MED) of each Fitzpatrick
+//! skin type: one UV index unit is 0.025 W/m² of erythemally weighted
+//! irradiance, so the dose is reached after MED / (0.025 × index) seconds.
+//! The results are rough guides for unprotected skin, not medical advice.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Erythemally weighted irradiance of one UV index unit, W/m²
+const UV_INDEX_UNIT: f64 = 0.025;
+
+/// Below this index the WHO advises no protection, so no time is given
+pub const LOW_RISK_INDEX: f64 = 3.0;
+
+/// Sunburn times under this many minutes are shown as "<10 min"
+pub const SHORTEST_SHOWN_MINUTES: u32 = 10;
+
+/// Exposure category for a UV index value
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum UvCategory {
+    /// 0 to 2
+    Low,
+    /// 3 to 5
+    Moderate,
+    /// 6 to 7
+    High,
+    /// 8 to 10
+    VeryHigh,
+    /// 11 and above
+    Extreme,
+}
+
+impl UvCategory {
+    /// Classifies a UV index, rounding to the nearest whole index as published
+    pub fn from_index(index: f64) -> Self {
+        match index.round() {
+            i if i < 3.0 => UvCategory::Low,
+            i if i < 6.0 => UvCategory::Moderate,
+            i if i < 8.0 => UvCategory::High,
+            i if i < 11.0 => UvCategory::VeryHigh,
+            _ => UvCategory::Extreme,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            UvCategory::Low => "Low",
+            UvCategory::Moderate => "Moderate",
+            UvCategory::High => "High",
+            UvCategory::VeryHigh => "Very high",
+            UvCategory::Extreme => "Extreme",
+        }
+    }
+}
+
+/// Fitzpatrick skin type, from always burns (I) to never burns (VI)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SkinType {
+    I,
+    II,
+    III,
+    IV,
+    V,
+    VI,
+}
+
+impl SkinType {
+    pub const ALL: [SkinType; 6] = [
+        SkinType::I,
+        SkinType::II,
+        SkinType::III,
+        SkinType::IV,
+        SkinType::V,
+        SkinType::VI,
+    ];
+
+    /// Minimal erythemal dose in J/m²
+    pub fn med(&self) -> f64 {
+        match self {
+            SkinType::I => 200.0,
+            SkinType::II => 250.0,
+            SkinType::III => 350.0,
+            SkinType::IV => 450.0,
+            SkinType::V => 600.0,
+            SkinType::VI => 1000.0,
+        }
+    }
+}
+
+impl fmt::Display for SkinType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SkinType::I => "I",
+            SkinType::II => "II",
+            SkinType::III => "III",
+            SkinType::IV => "IV",
+            SkinType::V => "V",
+            SkinType::VI => "VI",
+        })
+    }
+}
+
+impl FromStr for SkinType {
+    type Err = String;
+
+    /// Roman numerals in any case, or 1 to 6
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        SkinType::ALL
+            .into_iter()
+            .enumerate()
+            .find(|(i, skin)| s.eq_ignore_ascii_case(&skin.to_string()) || s == (i + 1).to_string())
+            .map(|(_, skin)| skin)
+            .ok_or_else(|| format!("Unknown skin type '{s}' (expected I to VI or 1 to 6)"))
+    }
+}
+
+/// Minutes until unprotected skin of `skin_type` burns at `uv_index`, or
+/// `None` below [`LOW_RISK_INDEX`]
+pub fn safe_exposure_minutes(uv_index: f64, skin_type: SkinType) -> Option<u32> {
+    if uv_index.is_nan() || uv_index < LOW_RISK_INDEX {
+        return None;
+    }
+    let seconds = skin_type.med() / (UV_INDEX_UNIT * uv_index);
+    Some((seconds / 60.0).round() as u32)
+}
+
+/// How long to burn, e.g. "~25 min", "<10 min", or `None` at low risk.
+/// Longer times are rounded to five minutes, as they are only a rough guide.
+pub fn exposure_time_text(uv_index: f64, skin_type: SkinType) -> Option<String> {
+    safe_exposure_minutes(uv_index, skin_type).map(|minutes| {
+        if minutes < SHORTEST_SHOWN_MINUTES {
+            format!("<{SHORTEST_SHOWN_MINUTES} min")
+        } else {
+            format!("~{} min", (minutes + 2) / 5 * 5)
+        }
+    })
+}
+
+/// Exposure advice for the text views: "~25 min to burn without
+/// protection", or "low risk"
+pub fn exposure_note(uv_index: f64, skin_type: SkinType) -> String {
+    match exposure_time_text(uv_index, skin_type) {
+        Some(time) => format!("{time} to burn without protection"),
+        None => "low risk".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_safe_exposure_minutes() {
+        // MED / (0.025 W/m² × index), as in the published MED tables
+        assert_eq!(safe_exposure_minutes(3.0, SkinType::I), Some(44));
+        assert_eq!(safe_exposure_minutes(6.0, SkinType::II), Some(28));
+        assert_eq!(safe_exposure_minutes(8.0, SkinType::III), Some(29));
+        assert_eq!(safe_exposure_minutes(10.0, SkinType::II), Some(17));
+        assert_eq!(safe_exposure_minutes(11.0, SkinType::VI), Some(61));
+        assert_eq!(safe_exposure_minutes(2.9, SkinType::I), None);
+        assert_eq!(safe_exposure_minutes(f64::NAN, SkinType::I), None);
+        // Darker skin always takes longer
+        for pair in SkinType::ALL.windows(2) {
+            assert!(safe_exposure_minutes(7.0, pair[0]) < safe_exposure_minutes(7.0, pair[1]));
+        }
+    }
+
+    #[test]
+    fn test_exposure_time_text() {
+        assert_eq!(
+            exposure_time_text(6.0, SkinType::II).as_deref(),
+            Some("~30 min")
+        );
+        assert_eq!(
+            exposure_time_text(9.0, SkinType::III).as_deref(),
+            Some("~25 min")
+        );
+        assert_eq!(
+            exposure_time_text(16.0, SkinType::I).as_deref(),
+            Some("<10 min")
+        );
+        assert_eq!(exposure_time_text(1.0, SkinType::I), None);
+        assert_eq!(
+            exposure_note(9.0, SkinType::III),
+            "~25 min to burn without protection"
+        );
+        assert_eq!(exposure_note(2.0, SkinType::III), "low risk");
+    }
+
+    #[test]
+    fn test_skin_type_parsing() {
+        assert_eq!("iii".parse(), Ok(SkinType::III));
+        assert_eq!("VI".parse(), Ok(SkinType::VI));
+        assert_eq!("2".parse(), Ok(SkinType::II));
+        assert!("7".parse::<SkinType>().is_err());
+        assert_eq!(serde_json::to_string(&SkinType::IV).unwrap(), "\"iv\"");
+    }
+
+    #[test]
+    fn test_uv_category_thresholds() {
+        assert_eq!(UvCategory::from_index(0.0), UvCategory::Low);
+        assert_eq!(UvCategory::from_index(2.4), UvCategory::Low);
+        assert_eq!(UvCategory::from_index(2.5), UvCategory::Moderate);
+        assert_eq!(UvCategory::from_index(5.0), UvCategory::Moderate);
+        assert_eq!(UvCategory::from_index(6.0), UvCategory::High);
+        assert_eq!(UvCategory::from_index(7.4), UvCategory::High);
+        assert_eq!(UvCategory::from_index(8.0), UvCategory::VeryHigh);
+        assert_eq!(UvCategory::from_index(10.4), UvCategory::VeryHigh);
+        assert_eq!(UvCategory::from_index(10.5), UvCategory::Extreme);
+    }
+}
@@ -0,0 +1,158 @@
+//! WMO weather interpretation codes used by Open-Meteo
+
+use crate::utils::daylight::HourLight;
+
+/// Short human-readable description of a WMO weather code
+pub fn description(code: i32) -> &'static str {
+    match code {
+        0 => "Clear sky",
+        1 => "Mainly clear",
+        2 => "Partly cloudy",
+        3 => "Overcast",
+        45 | 48 => "Fog",
+        51 | 53 | 55 => "Drizzle",
+        61 | 63 | 65 => "Rain",
+        71 | 73 | 75 => "Snow",
+        77 => "Snow grains",
+        80..=82 => "Rain showers",
+        85 | 86 => "Snow showers",
+        95 => "Thunderstorm",
+        96 | 99 => "Thunderstorm with hail",
+        _ => "Unknown",
+    }
+}
+
+/// Whether a WMO weather code reports snow (snowfall, snow grains or snow showers)
+pub fn is_snow(code: i32) -> bool {
+    matches!(code, 71..=77 | 85 | 86)
+}
+
+/// Unicode symbol for a WMO weather code
+pub fn icon(code: i32) -> &'static str {
+    match code {
+        0 => "☀",        // Clear sky - sun (U+2600)
+        1..=3 => "⛅",   // Partly cloudy (U+26C5)
+        45 | 48 => "~",  // Fog - horizontal lines
+        51..=57 => "☂",  // Drizzle - umbrella (U+2602)
+        61..=67 => "☔", // Rain - umbrella with rain (U+2614)
+        71..=77 => "❄",  // Snow (U+2744)
+        80..=82 => "☔", // Rain showers - umbrella (U+2614)
+        85 | 86 => "❄",  // Snow (U+2744)
+        95..=99 => "⚡", // Thunderstorm - lightning bolt (U+26A1)
+        _ => "○",        // Default - simple circle (U+25CB)
+    }
+}
+
+/// Symbol for a WMO weather code in an hour of the given light: clear and
+/// cloudy skies show the moon at night and the sun on the horizon in the
+/// sunrise and sunset hours; fog and precipitation look the same either way
+pub fn icon_at(code: i32, light: HourLight) -> &'static str {
+    match (code, light) {
+        (0..=3, HourLight::Sunrise) => "🌅",
+        (0..=3, HourLight::Sunset) => "🌇",
+        (0, HourLight::Night) => "☾",     // Last quarter moon (U+263E)
+        (1..=3, HourLight::Night) => "☁", // Cloud (U+2601)
+        _ => icon(code),
+    }
+}
+
+/// Groups of WMO codes that look alike, for mappings that must cover every
+/// kind of weather
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WeatherCode {
+    Clear,
+    MainlyClear,
+    PartlyCloudy,
+    Overcast,
+    Fog,
+    Drizzle,
+    FreezingDrizzle,
+    Rain,
+    FreezingRain,
+    Snow,
+    SnowGrains,
+    RainShowers,
+    SnowShowers,
+    Thunderstorm,
+    ThunderstormHail,
+}
+
+impl WeatherCode {
+    pub const ALL: [WeatherCode; 15] = [
+        WeatherCode::Clear,
+        WeatherCode::MainlyClear,
+        WeatherCode::PartlyCloudy,
+        WeatherCode::Overcast,
+        WeatherCode::Fog,
+        WeatherCode::Drizzle,
+        WeatherCode::FreezingDrizzle,
+        WeatherCode::Rain,
+        WeatherCode::FreezingRain,
+        WeatherCode::Snow,
+        WeatherCode::SnowGrains,
+        WeatherCode::RainShowers,
+        WeatherCode::SnowShowers,
+        WeatherCode::Thunderstorm,
+        WeatherCode::ThunderstormHail,
+    ];
+
+    /// Group of a WMO code, `None` for codes Open-Meteo does not use
+    pub fn from_wmo(code: i32) -> Option<Self> {
+        Some(match code {
+            0 => WeatherCode::Clear,
+            1 => WeatherCode::MainlyClear,
+            2 => WeatherCode::PartlyCloudy,
+            3 => WeatherCode::Overcast,
+            45 | 48 => WeatherCode::Fog,
+            51 | 53 | 55 => WeatherCode::Drizzle,
+            56 | 57 => WeatherCode::FreezingDrizzle,
+            61 | 63 | 65 => WeatherCode::Rain,
+            66 | 67 => WeatherCode::FreezingRain,
+            71 | 73 | 75 => WeatherCode::Snow,
+            77 => WeatherCode::SnowGrains,
+            80..=82 => WeatherCode::RainShowers,
+            85 | 86 => WeatherCode::SnowShowers,
+            95 => WeatherCode::Thunderstorm,
+            96 | 99 => WeatherCode::ThunderstormHail,
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_group_has_a_code() {
+        let groups: Vec<WeatherCode> = (0..=99).filter_map(WeatherCode::from_wmo).collect();
+        for code in WeatherCode::ALL {
+            assert!(groups.contains(&code), "{code:?}");
+        }
+        assert_eq!(WeatherCode::from_wmo(4), None);
+        assert_eq!(WeatherCode::from_wmo(66), Some(WeatherCode::FreezingRain));
+    }
+
+    #[test]
+    fn test_snow_codes() {
+        for code in [71, 73, 75, 77, 85, 86] {
+            assert!(is_snow(code), "{code}");
+        }
+        for code in [0, 3, 66, 67, 80, 82, 95, 99] {
+            assert!(!is_snow(code), "{code}");
+        }
+    }
+
+    #[test]
+    fn test_icon_by_light() {
+        assert_eq!(icon_at(0, HourLight::Day), icon(0));
+        assert_eq!(icon_at(0, HourLight::Night), "☾");
+        assert_eq!(icon_at(2, HourLight::Night), "☁");
+        assert_eq!(icon_at(1, HourLight::Sunrise), "🌅");
+        assert_eq!(icon_at(3, HourLight::Sunset), "🌇");
+        // Rain looks the same at any hour
+        for light in [HourLight::Night, HourLight::Sunrise, HourLight::Sunset] {
+            assert_eq!(icon_at(63, light), icon(63));
+        }
+    }
+}
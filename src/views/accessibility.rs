@@ -0,0 +1,140 @@
+//! Screen-reader text for the GUI
+//!
+//! egui hands each widget's text to AccessKit, so a label showing "62%" or an
+//! icon showing "⛅" is read out as written. These helpers compose what should
+//! be read instead: units spelled out, and a value read with its label.
+
+/// Unit symbols as shown in the GUI and how they are read, longest first so
+/// "°C" wins over "°"
+const SPOKEN_UNITS: &[(&str, &str)] = &[
+    ("km/h", "kilometers per hour"),
+    ("m/s", "meters per second"),
+    ("mph", "miles per hour"),
+    ("hPa", "hectopascals"),
+    ("°C", "degrees Celsius"),
+    ("°F", "degrees Fahrenheit"),
+    ("mm", "millimeters"),
+    ("cm", "centimeters"),
+    ("km", "kilometers"),
+    ("kn", "knots"),
+    ("in", "inches"),
+    ("m", "meters"),
+    ("s", "seconds"),
+    ("h", "hours"),
+    ("%", "percent"),
+    ("°", "degrees"),
+];
+
+/// How a unit symbol is read, e.g. "km/h" → "kilometers per hour"
+pub fn spoken_unit(symbol: &str) -> Option<&'static str> {
+    SPOKEN_UNITS
+        .iter()
+        .find(|(unit, _)| *unit == symbol)
+        .map(|(_, spoken)| *spoken)
+}
+
+/// A displayed value with its unit symbols spelled out:
+/// "19.4°C / 66.9°F" → "19.4 degrees Celsius, 66.9 degrees Fahrenheit",
+/// "12.0 km/h (225°)" → "12.0 kilometers per hour (225 degrees)".
+/// Words that are not units are kept as they are, and a unit standing on
+/// its own is only read as one right after a number ("snow in Oslo").
+pub fn speak(text: &str) -> String {
+    let mut after_number = false;
+    let words: Vec<String> = text
+        .split_whitespace()
+        .map(|word| {
+            let (spoken, is_number) = speak_word(word, after_number);
+            after_number = is_number;
+            spoken
+        })
+        .collect();
+    words.join(" ").replace(" /,", ",")
+}
+
+/// One whitespace-separated word: a number glued to its unit ("62%"), a unit
+/// on its own ("hPa"), a separator ("/") or any other word. Also returns
+/// whether the word was a bare number.
+fn speak_word(word: &str, after_number: bool) -> (String, bool) {
+    if word == "/" {
+        return ("/,".to_string(), false);
+    }
+    // Keep surrounding brackets and punctuation around the spoken form
+    let start = word.find(|c: char| !"([".contains(c)).unwrap_or(word.len());
+    let end = word
+        .rfind(|c: char| !")],;".contains(c))
+        .map_or(start, |i| {
+            i + word[i..].chars().next().map_or(1, char::len_utf8)
+        });
+    let (open, core, close) = (&word[..start], &word[start..end], &word[end..]);
+
+    let approximate = core.strip_prefix('~');
+    let core = approximate.unwrap_or(core);
+    let number_end = core
+        .find(|c: char| !(c.is_ascii_digit() || "+-.,".contains(c)))
+        .unwrap_or(core.len());
+    let (number, unit) = core.split_at(number_end);
+    let spoken = match (number.is_empty(), spoken_unit(unit)) {
+        (true, Some(unit)) if after_number => unit.to_string(),
+        (false, Some(unit)) => format!("{number} {unit}"),
+        (false, None) if unit.is_empty() => number.to_string(),
+        _ => core.to_string(),
+    };
+    let about = if approximate.is_some() { "about " } else { "" };
+    let is_number = !number.is_empty() && unit.is_empty() && close.is_empty();
+    (format!("{open}{about}{spoken}{close}"), is_number)
+}
+
+/// A label and its value read as one phrase: ("Humidity", "62%") →
+/// "Humidity 62 percent"
+pub fn labeled_value(label: &str, value: &str) -> String {
+    format!("{} {}", label.trim_end_matches(':'), speak(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_units_are_spelled_out() {
+        assert_eq!(speak("62%"), "62 percent");
+        assert_eq!(speak("1016 hPa"), "1016 hectopascals");
+        assert_eq!(
+            speak("19.4°C / 66.9°F"),
+            "19.4 degrees Celsius, 66.9 degrees Fahrenheit"
+        );
+        assert_eq!(
+            speak("12.0 km/h (225°)"),
+            "12.0 kilometers per hour (225 degrees)"
+        );
+        assert_eq!(speak("-3°C"), "-3 degrees Celsius");
+        assert_eq!(speak("14,2 °C"), "14,2 degrees Celsius");
+        assert_eq!(spoken_unit("mph"), Some("miles per hour"));
+        assert_eq!(spoken_unit("furlongs"), None);
+    }
+
+    #[test]
+    fn test_other_words_are_kept() {
+        assert_eq!(
+            speak("3200 m (snow above ~2900 m)"),
+            "3200 meters (snow above about 2900 meters)"
+        );
+        assert_eq!(speak("Partly cloudy"), "Partly cloudy");
+        assert_eq!(speak("80% peak"), "80 percent peak");
+        assert_eq!(speak("12:30"), "12:30");
+        assert_eq!(speak("Rain in 2 h"), "Rain in 2 hours");
+        assert_eq!(speak(""), "");
+    }
+
+    #[test]
+    fn test_label_and_value_read_together() {
+        assert_eq!(labeled_value("Humidity", "62%"), "Humidity 62 percent");
+        assert_eq!(
+            labeled_value("Pressure:", "1016 hPa"),
+            "Pressure 1016 hectopascals"
+        );
+        assert_eq!(
+            labeled_value("Wind", "18 km/h"),
+            "Wind 18 kilometers per hour"
+        );
+    }
+}
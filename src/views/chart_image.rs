@@ -0,0 +1,306 @@
+//! Hourly temperature and precipitation chart drawn into an RGBA buffer and
+//! encoded as PNG, with no window or GPU, so it can be saved from the GUI and
+//! rendered in tests alike
+
+use std::path::Path;
+
+use crate::errors::WeatherError;
+use crate::models::weather_info::HourlyForecast;
+use crate::utils::time;
+
+/// RGBA color
+pub type Rgba = [u8; 4];
+
+/// Colors of the chart, matching the GUI's dark theme by default
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChartTheme {
+    pub background: Rgba,
+    pub grid: Rgba,
+    pub temperature: Rgba,
+    pub precipitation: Rgba,
+    /// Marker where the local clock skips or repeats an hour
+    pub clock_change: Rgba,
+}
+
+impl Default for ChartTheme {
+    fn default() -> Self {
+        ChartTheme {
+            background: [15, 23, 42, 255],
+            grid: [51, 65, 85, 255],
+            temperature: [251, 146, 60, 255],
+            precipitation: [34, 211, 238, 140],
+            clock_change: [148, 163, 184, 255],
+        }
+    }
+}
+
+/// Rendered chart: `width × height` pixels, four bytes each, row by row
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChartImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl ChartImage {
+    fn new(width: u32, height: u32, fill: Rgba) -> Self {
+        ChartImage {
+            width,
+            height,
+            pixels: fill.repeat(width as usize * height as usize),
+        }
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> Rgba {
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        [
+            self.pixels[i],
+            self.pixels[i + 1],
+            self.pixels[i + 2],
+            self.pixels[i + 3],
+        ]
+    }
+
+    /// Alpha-blends `color` over the pixel; coordinates outside the image are ignored
+    fn blend(&mut self, x: i64, y: i64, color: Rgba) {
+        if x < 0 || y < 0 || x >= i64::from(self.width) || y >= i64::from(self.height) {
+            return;
+        }
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        let alpha = u16::from(color[3]);
+        for (channel, value) in color.iter().take(3).enumerate() {
+            let below = u16::from(self.pixels[i + channel]);
+            self.pixels[i + channel] =
+                ((u16::from(*value) * alpha + below * (255 - alpha)) / 255) as u8;
+        }
+        self.pixels[i + 3] = 255;
+    }
+
+    fn fill_rect(&mut self, x0: i64, y0: i64, x1: i64, y1: i64, color: Rgba) {
+        for y in y0.min(y1)..y0.max(y1) {
+            for x in x0.min(x1)..x0.max(x1) {
+                self.blend(x, y, color);
+            }
+        }
+    }
+
+    /// Line with a square pen `thickness` pixels wide
+    fn line(&mut self, from: (f64, f64), to: (f64, f64), thickness: i64, color: Rgba) {
+        let steps = (to.0 - from.0)
+            .abs()
+            .max((to.1 - from.1).abs())
+            .ceil()
+            .max(1.0);
+        let half = thickness / 2;
+        let mut last = None;
+        for step in 0..=steps as i64 {
+            let t = step as f64 / steps;
+            let x = (from.0 + (to.0 - from.0) * t).round() as i64;
+            let y = (from.1 + (to.1 - from.1) * t).round() as i64;
+            // Consecutive steps often land on the same pixel; blending it twice darkens the line
+            if last == Some((x, y)) {
+                continue;
+            }
+            last = Some((x, y));
+            self.fill_rect(
+                x - half,
+                y - half,
+                x - half + thickness,
+                y - half + thickness,
+                color,
+            );
+        }
+    }
+
+    /// PNG encoding of the image
+    pub fn to_png(&self) -> Result<Vec<u8>, WeatherError> {
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.pixels))
+            .map_err(|e| WeatherError::StorageError(format!("cannot encode chart: {e}")))?;
+        Ok(png)
+    }
+
+    /// Writes the image as a PNG file
+    pub fn save(&self, path: &Path) -> Result<(), WeatherError> {
+        std::fs::write(path, self.to_png()?).map_err(|e| {
+            WeatherError::StorageError(format!("cannot write {}: {e}", path.display()))
+        })
+    }
+}
+
+/// Draws precipitation probability as bars (0–100 % of the plot height) and
+/// temperature as a line scaled to its own range. Hours are placed by real
+/// time, so gaps in the data stay visible, and a dashed line marks where the
+/// clocks change. `scale` multiplies line widths and margins, e.g. 2 for an
+/// image twice the on-screen size.
+pub fn render(
+    hours: &[HourlyForecast],
+    width: u32,
+    height: u32,
+    scale: u32,
+    theme: &ChartTheme,
+) -> ChartImage {
+    let mut image = ChartImage::new(width, height, theme.background);
+    let scale = i64::from(scale.max(1));
+    let margin = 8 * scale;
+    let (left, top) = (margin, margin);
+    let right = i64::from(width) - margin;
+    let bottom = i64::from(height) - margin;
+    if right <= left || bottom <= top {
+        return image;
+    }
+
+    let plot_height = (bottom - top) as f64;
+    for quarter in 0..=4 {
+        let y = top + (plot_height * f64::from(quarter) / 4.0).round() as i64;
+        image.fill_rect(left, y, right, y + scale, theme.grid);
+    }
+    if hours.is_empty() {
+        return image;
+    }
+
+    // Hours since the first entry, which is each entry's slot
+    let offsets: Vec<f64> = time::elapsed(hours.iter().map(|h| h.time.as_str()))
+        .iter()
+        .map(|offset| offset.num_minutes() as f64 / 60.0)
+        .collect();
+    let span = offsets.last().map_or(1.0, |last| last + 1.0);
+    let slot = (right - left) as f64 / span;
+    let start = |i: usize| left as f64 + slot * offsets[i];
+    let center = |i: usize| start(i) + slot * 0.5;
+
+    for (i, hour) in hours.iter().enumerate() {
+        if let Some(probability) = hour.precipitation_probability {
+            let bar_top = bottom - (plot_height * probability.clamp(0.0, 100.0) / 100.0) as i64;
+            let x0 = (start(i) + slot * 0.15).round() as i64;
+            let x1 = (start(i) + slot * 0.85).round() as i64;
+            image.fill_rect(x0, bar_top, x1.max(x0 + 1), bottom, theme.precipitation);
+        }
+    }
+
+    let dash = 4 * scale;
+    for i in (0..hours.len()).filter(|i| hours[*i].clock_change.is_some()) {
+        let x = start(i).round() as i64;
+        for y in (top..bottom).step_by((2 * dash) as usize) {
+            image.fill_rect(x, y, x + scale, (y + dash).min(bottom), theme.clock_change);
+        }
+    }
+
+    let temperatures: Vec<(usize, f64)> = hours
+        .iter()
+        .enumerate()
+        .filter_map(|(i, h)| h.temperature.map(|t| (i, t)))
+        .collect();
+    let low = temperatures
+        .iter()
+        .map(|(_, t)| *t)
+        .fold(f64::INFINITY, f64::min);
+    let high = temperatures
+        .iter()
+        .map(|(_, t)| *t)
+        .fold(f64::NEG_INFINITY, f64::max);
+    // A flat series sits mid-plot instead of dividing by zero
+    let range = (high - low).max(1.0);
+    let y = |t: f64| bottom as f64 - plot_height * (0.1 + 0.8 * (t - low) / range);
+
+    for pair in temperatures.windows(2) {
+        let ((i, a), (j, b)) = (pair[0], pair[1]);
+        image.line(
+            (center(i), y(a)),
+            (center(j), y(b)),
+            2 * scale,
+            theme.temperature,
+        );
+    }
+    if let [(i, t)] = temperatures[..] {
+        image.line(
+            (center(i), y(t)),
+            (center(i), y(t)),
+            4 * scale,
+            theme.temperature,
+        );
+    }
+
+    image
+}
+
+/// "<location>-hourly-<date>.png", with the location reduced to lowercase
+/// letters, digits and dashes
+pub fn default_file_name(location: &str, date: &str) -> String {
+    let mut slug = String::new();
+    for c in location.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    let slug = if slug.is_empty() { "weather" } else { slug };
+    format!("{slug}-hourly-{date}.png")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::sample_weather_info;
+
+    #[test]
+    fn test_render_fixture_to_png() {
+        let hours = &sample_weather_info().weather_data.hourly[..24];
+        let theme = ChartTheme::default();
+        // Twice an 480 × 160 on-screen chart
+        let image = render(hours, 960, 320, 2, &theme);
+        assert_eq!(image.pixels.len(), 960 * 320 * 4);
+        assert_eq!(image.pixel(0, 0), theme.background);
+        assert!((0..960).any(|x| (0..320).any(|y| image.pixel(x, y) == theme.temperature)));
+
+        let png = image.to_png().unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        // IHDR: width and height as big-endian u32 after the chunk header
+        assert_eq!(u32::from_be_bytes(png[16..20].try_into().unwrap()), 960);
+        assert_eq!(u32::from_be_bytes(png[20..24].try_into().unwrap()), 320);
+    }
+
+    #[test]
+    fn test_render_marks_clock_changes() {
+        use crate::models::weather_info::{CurrentWeather, WeatherData};
+
+        // Spring forward: 02:00 is skipped, so 03:00 starts the third slot
+        let hourly = ["00:00", "01:00", "03:00", "04:00"]
+            .iter()
+            .map(|clock| HourlyForecast::builder(format!("2024-03-31T{clock}")).build())
+            .collect();
+        let data = WeatherData::new(CurrentWeather::default(), hourly, Vec::new());
+        let theme = ChartTheme::default();
+        let image = render(&data.hourly, 216, 100, 1, &theme);
+        // 200 px of plot over four hours: the marker sits at 8 + 2 × 50
+        assert_eq!(image.pixel(108, 10), theme.clock_change);
+        assert_ne!(image.pixel(58, 10), theme.clock_change);
+    }
+
+    #[test]
+    fn test_render_without_data_or_room() {
+        let theme = ChartTheme::default();
+        let empty = render(&[], 200, 100, 1, &theme);
+        assert_eq!(empty.pixel(100, 40), theme.background);
+        assert_eq!(render(&[], 4, 4, 1, &theme).pixels.len(), 64);
+    }
+
+    #[test]
+    fn test_default_file_name() {
+        assert_eq!(
+            default_file_name("São Paulo, Brazil", "2024-06-01"),
+            "são-paulo-brazil-hourly-2024-06-01.png"
+        );
+        assert_eq!(
+            default_file_name("  ", "2024-06-01"),
+            "weather-hourly-2024-06-01.png"
+        );
+    }
+}
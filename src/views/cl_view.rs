@@ -2,220 +2,2619 @@
 //!
 //! Displays data in both metric and imperial units for user convenience.
 
-use crate::models::weather_info::WeatherInfo;
-use crate::utils::conversions::{Distance, Pressure, Speed, Temperature};
+use std::path::Path;
+
+use chrono::{DateTime, Local, NaiveDate, Utc};
+
+use crate::constants::{
+    FOG_VISIBILITY_THRESHOLD_M, GARDEN_WATERING_DAYS, HOURLY_DISPLAY_LIMIT, MINUTELY_DISPLAY_STEPS,
+    PRECIPITATION_OUTLOOK_HOURS, SNOW_ACCUMULATION_HOURS, STORM_OUTLOOK_HOURS, WINDS_ALOFT_HOURS,
+    WIND_ROSE_SPOKES,
+};
+use crate::errors::WeatherError;
+use crate::models::air_quality::AirQualityReading;
+use crate::models::favorites::{Favorite, RefreshOutcome};
+use crate::models::hourly_stats::HourlyStats;
+use crate::models::marine::MarineHour;
+use crate::models::usage::UsageSummary;
+use crate::models::weather_info::{MinutelyForecast, WeatherData, WeatherInfo};
+use crate::models::weather_log::WeatherLogSummary;
+use crate::models::wind_rose::WindRose;
+use crate::repositories::app_state::{self, StateFile, StateItem};
+use crate::repositories::health::HealthCheck;
+use crate::repositories::weather_repository::ApiWeatherRepository;
+use crate::utils::aviation::{self, WindLevel};
+use crate::utils::confidence::Confidence;
+use crate::utils::conversions::{Direction, Distance, Pressure, Speed, WindUnit};
+use crate::utils::digest::DigestEntry;
+use crate::utils::format::{self, NumberLocale, Precision};
+use crate::utils::i18n::{tr, Key, Locale};
+use crate::utils::storm::{self, StormSeverity};
+use crate::utils::uv::{self, SkinType, UvCategory};
+use crate::utils::{
+    advice, advisories, agriculture, clock, freshness, mountain, precip_phase, precipitation, snow,
+    time, weather_codes,
+};
+use crate::views::export::{self, OutputFormat};
+use crate::views::DisplayOptions;
+
+/// Width from which the daily forecast is shown as a table instead of a list
+const DAILY_TABLE_MIN_WIDTH: usize = 100;
+/// Extra width the optional daily humidity column needs beyond the base table
+const DAILY_HUMIDITY_COLUMN_WIDTH: usize = 6;
+/// Extra width the optional daily UV column needs beyond the base table
+const DAILY_UV_COLUMN_WIDTH: usize = 16;
+/// Locations listed by `weather-app stats`
+const USAGE_TOP_LOCATIONS: usize = 10;
+/// Rows from the center of the console wind rose to the tip of a full spoke
+const WIND_ROSE_RADIUS: i32 = 5;
+/// Language of the translated labels in the text report
+const REPORT_LOCALE: Locale = Locale::En;
+/// Spacing of minutely steps when there are too few to measure it
+const DEFAULT_MINUTELY_INTERVAL_MINUTES: i64 = 15;
+
+/// Level of detail of an hourly line, reduced to fit narrow terminals
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum HourlyDetail {
+    /// Metric and imperial units, with notable gusts, cloud cover and visibility
+    Extended,
+    /// Metric and imperial units
+    Full,
+    /// Metric units only
+    MetricOnly,
+    /// Metric units without wind
+    Compact,
+}
+
+/// `println!` into the lines of a report being rendered
+macro_rules! push_line {
+    ($out:expr) => {
+        $out.push(String::new())
+    };
+    ($out:expr, $($arg:tt)*) => {
+        $out.push(format!($($arg)*))
+    };
+}
+
+/// Rendered lines as printed, each ending in a newline
+fn lines_to_string(lines: Vec<String>) -> String {
+    lines.into_iter().map(|line| line + "\n").collect()
+}
+
+/// Truncates `line` to `width` characters, ending with an ellipsis when cut
+fn fit(line: &str, width: usize) -> String {
+    if line.chars().count() <= width {
+        return line.to_string();
+    }
+    let mut truncated: String = line.chars().take(width.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
 
 /// Stateless view for console output
 pub struct ClView;
 
 impl ClView {
     /// Main display entry point
-    pub fn display(weather_info: &WeatherInfo) {
-        println!("\n=== Weather Report ===");
-        println!("Location: {}", weather_info.location);
-        println!(
-            "Coordinates: {:.4}°N, {:.4}°E",
-            weather_info.latitude, weather_info.longitude
+    pub fn display(weather_info: &WeatherInfo, options: &DisplayOptions) {
+        if let Some(timings) = weather_info.timings.filter(|_| options.verbose) {
+            eprintln!("Timing: {timings}");
+        }
+        print!("{}", Self::render(weather_info, options));
+    }
+
+    /// What `display` prints for `options`: the one-line summary, JSON, CSV
+    /// or the text report
+    pub fn render(weather_info: &WeatherInfo, options: &DisplayOptions) -> String {
+        let mut out = Vec::new();
+        if options.oneline {
+            push_line!(out, "{}", weather_info.summary());
+            return lines_to_string(out);
+        }
+
+        match options.format {
+            OutputFormat::Json => {
+                let json = export::to_json(weather_info, options);
+                push_line!(out, "{json:#}");
+                return lines_to_string(out);
+            }
+            OutputFormat::Csv => return export::hourly_csv(weather_info, options),
+            OutputFormat::DailyCsv => return export::daily_csv(weather_info, options),
+            OutputFormat::Text => {}
+        }
+
+        let width = options.width();
+        let now = Utc::now();
+
+        match weather_info.model_tag() {
+            Some(model) => push_line!(out, "\n=== Weather Report {model} ==="),
+            None => push_line!(out, "\n=== Weather Report ==="),
+        }
+        push_line!(
+            out,
+            "{}",
+            Self::location_line(&weather_info.location_label(), width)
+        );
+        push_line!(out, "Coordinates: {}", weather_info.coordinates());
+        if weather_info.reduced_data {
+            push_line!(
+                out,
+                "Note: this server only provides basic current conditions"
+            );
+        }
+
+        Self::render_heads_up(
+            &mut out,
+            &weather_info.weather_data,
+            weather_info.today(now),
+            options,
+        );
+        Self::render_current_conditions(&mut out, &weather_info.weather_data.current, options);
+        if let Some(uv) = weather_info
+            .weather_data
+            .current_hour()
+            .and_then(|hour| hour.uv_index)
+        {
+            let mut line = format!("UV Index: {}", Self::uv_text(uv));
+            if let Some(skin_type) = options.skin_type {
+                line.push_str(&format!(", {}", uv::exposure_note(uv, skin_type)));
+            }
+            push_line!(out, "{}", fit(&line, width));
+        }
+        // Left out when the air quality request failed
+        if let Some(reading) = weather_info.current_air_quality() {
+            push_line!(
+                out,
+                "{}",
+                fit(
+                    &Self::air_quality_line(reading, options.number_locale),
+                    width
+                )
+            );
+        }
+        if let Some(outlook) =
+            precipitation::outlook(&weather_info.weather_data, PRECIPITATION_OUTLOOK_HOURS)
+        {
+            push_line!(out, "{}", outlook.message());
+        }
+        if let Some(line) = Self::sun_countdown(weather_info, now) {
+            push_line!(out, "{line}");
+        }
+        Self::render_snow(&mut out, &weather_info.weather_data, options);
+        if options.mountain || mountain::is_mountain_elevation(weather_info.elevation) {
+            Self::render_freezing_level(&mut out, &weather_info.weather_data);
+        }
+        if options.storm {
+            Self::render_storm_outlook(&mut out, &weather_info.weather_data);
+        }
+        if options.winds_aloft {
+            Self::render_winds_aloft(&mut out, &weather_info.weather_data, options);
+        }
+        if options.marine {
+            Self::render_marine(&mut out, weather_info, options);
+        }
+        if options.wind_rose {
+            Self::render_wind_rose(&mut out, &weather_info.weather_data, options);
+        }
+        Self::render_minutely_forecast(&mut out, &weather_info.weather_data, options);
+        Self::render_hourly_forecast(&mut out, &weather_info.weather_data, options);
+        Self::render_daily_forecast(
+            &mut out,
+            weather_info.weather_data.daily_from_today(),
+            weather_info.latitude,
+            options,
         );
+        if options.garden {
+            Self::render_garden_watering(
+                &mut out,
+                weather_info.weather_data.daily_from_today(),
+                options,
+            );
+        }
 
-        Self::display_current_conditions(&weather_info.weather_data.current);
-        Self::display_hourly_forecast(&weather_info.weather_data.hourly);
-        Self::display_daily_forecast(&weather_info.weather_data.daily);
+        push_line!(out);
+        for line in Self::footer_lines(weather_info, now) {
+            push_line!(out, "{line}");
+        }
+        push_line!(out, "======================\n");
+        lines_to_string(out)
+    }
 
-        println!("\n======================\n");
+    /// Time to the next sunrise or sunset at the location, e.g. "Sunset in 2 h 05 min (21:02)"
+    fn sun_countdown(weather_info: &WeatherInfo, now: DateTime<Utc>) -> Option<String> {
+        let clock = weather_info.clock(now)?;
+        weather_info
+            .weather_data
+            .daily
+            .iter()
+            .flat_map(|day| [("Sunrise", &day.sunrise), ("Sunset", &day.sunset)])
+            .filter_map(|(label, time)| {
+                let time = time.as_deref()?;
+                Some((label, time, clock.until(time)?))
+            })
+            .next()
+            .map(|(label, time, remaining)| {
+                format!(
+                    "{label} in {} h {:02} min ({})",
+                    remaining.num_hours(),
+                    remaining.num_minutes() % 60,
+                    time.split('T').nth(1).unwrap_or(time)
+                )
+            })
     }
 
-    fn display_current_conditions(current: &crate::models::weather_info::CurrentWeather) {
-        println!("\n--- Current Conditions ---");
+    /// Provider, model run and fetch time, plus a warning once the forecast
+    /// no longer covers `now`
+    fn footer_lines(weather_info: &WeatherInfo, now: DateTime<Utc>) -> Vec<String> {
+        let fetched_at = weather_info
+            .fetched_at
+            .map(|t| t.with_timezone(&Local).naive_local());
+        let mut lines: Vec<String> = weather_info.attribution().into_iter().collect();
+        lines.extend(freshness::provenance_line(
+            weather_info.reference_time(),
+            fetched_at,
+        ));
+        if let Some(warning) = weather_info.freshness(now).and_then(|f| f.warning()) {
+            lines.push(format!("Warning: {warning}"));
+        }
+        lines
+    }
 
-        if let Some(temp) = current.temperature {
-            let fahrenheit = Temperature::celsius_to_fahrenheit(temp);
-            println!("Temperature: {temp:.1}°C / {fahrenheit:.1}°F");
+    /// Prints one summary (or error) line per location, in input order
+    pub fn display_batch(locations: &[String], results: &[Result<WeatherInfo, WeatherError>]) {
+        if let Some(skew) = results
+            .iter()
+            .find_map(|result| result.as_ref().ok()?.clock_skew)
+        {
+            eprintln!("Warning: {}", clock::skew_warning(skew));
+        }
+        for line in Self::batch_lines(locations, results) {
+            println!("{line}");
+        }
+    }
+
+    /// Lines printed by `display_batch`: as from `ClController::show_batch`,
+    /// they end at the first location the server rate-limited, with a line
+    /// naming the locations skipped
+    pub fn batch_lines(
+        locations: &[String],
+        results: &[Result<WeatherInfo, WeatherError>],
+    ) -> Vec<String> {
+        let mut lines = Vec::new();
+        for (index, (location, result)) in locations.iter().zip(results).enumerate() {
+            lines.push(Self::batch_line(location, result));
+            let rest = locations[index + 1..].iter().map(|l| l.trim());
+            if let Some(line) = Self::rate_limit_stop(result, rest) {
+                lines.push(line);
+                break;
+            }
         }
+        lines
+    }
 
-        if let Some(apparent) = current.apparent_temperature {
-            let fahrenheit = Temperature::celsius_to_fahrenheit(apparent);
-            println!("Feels Like: {apparent:.1}°C / {fahrenheit:.1}°F");
+    /// Summary line for a location, or the error that prevented fetching it
+    pub fn batch_line(location: &str, result: &Result<WeatherInfo, WeatherError>) -> String {
+        match result {
+            Ok(info) => info.summary().to_string(),
+            Err(e) => format!("{}: {e}", location.trim()),
         }
+    }
 
-        if let Some(humidity) = current.humidity {
-            println!("Humidity: {humidity:.0}%");
+    /// Line naming the `rest` of a batch that a rate-limited `result` cuts
+    /// off; `None` when the result is not rate limited by the server (a
+    /// request held back for Nominatim's spacing only costs its location)
+    /// or nothing is left
+    pub fn rate_limit_stop<'a, T>(
+        result: &Result<T, WeatherError>,
+        rest: impl Iterator<Item = &'a str>,
+    ) -> Option<String> {
+        let Err(WeatherError::RateLimited { retry_after }) = result else {
+            return None;
+        };
+        let skipped: Vec<&str> = rest.collect();
+        if skipped.is_empty() {
+            return None;
         }
+        let retry_at = chrono::Local::now() + chrono::TimeDelta::from_std(*retry_after).ok()?;
+        Some(Self::rate_limited_line(&skipped, retry_at.time()))
+    }
 
-        if let Some(precip) = current.precipitation {
-            let inches = Distance::mm_to_inches(precip);
-            println!("Precipitation: {precip:.1} mm / {inches:.2} in");
+    /// Locations a batch skipped after the server rate-limited it
+    pub fn rate_limited_line(skipped: &[&str], retry_at: chrono::NaiveTime) -> String {
+        format!(
+            "Rate limited by the server until {}; skipped {}",
+            retry_at.format("%H:%M"),
+            skipped.join(", ")
+        )
+    }
+
+    /// Note for weather served from the offline copy, fetched at the local
+    /// time `fetched_at`
+    pub fn offline_line(fetched_at: Option<chrono::NaiveDateTime>) -> String {
+        match fetched_at {
+            Some(time) => format!(
+                "No connection; showing cached data from {}",
+                time.format("%Y-%m-%d %H:%M")
+            ),
+            None => "No connection; showing cached data from an earlier run".to_string(),
         }
+    }
 
-        if let Some(code) = current.weather_code {
+    /// Digest lines: each location's summary with why it ranks where it does,
+    /// its callout indented below, and locations that failed at the end
+    pub fn digest_lines(
+        entries: &[DigestEntry],
+        failures: &[(String, WeatherError)],
+    ) -> Vec<String> {
+        let mut lines = Vec::new();
+        for entry in entries {
+            match &entry.reason {
+                Some(reason) => lines.push(format!("{} [{reason}]", entry.summary)),
+                None => lines.push(entry.summary.to_string()),
+            }
+            if let Some(callout) = &entry.callout {
+                lines.push(format!("  ⚠ {callout}"));
+            }
+        }
+        for (name, e) in failures {
+            lines.push(format!("{name}: {e}"));
+        }
+        lines
+    }
+
+    /// Favorite line such as "Home (Seattle): 14°C ⛅", or the fetch error
+    pub fn favorite_line(
+        favorite: &Favorite,
+        result: &Result<WeatherInfo, WeatherError>,
+    ) -> String {
+        let current = match result {
+            Ok(info) => &info.weather_data.current,
+            Err(e) => return format!("{}: {e}", favorite.title()),
+        };
+        let temperature = current
+            .temperature
+            .map_or("—".to_string(), |t| format::celsius(t, Precision::Whole));
+        let icon = current.weather_code.map_or("", weather_codes::icon);
+        let line = format!("{}: {temperature} {icon}", favorite.title())
+            .trim_end()
+            .to_string();
+        match &favorite.moved {
+            Some(place) => format!(
+                "{line} (now geocoded to {}; see --accept-moves)",
+                place.name
+            ),
+            None => line,
+        }
+    }
+
+    /// What a name refresh changed for `favorite` (as it is after the
+    /// refresh), `None` when nothing did
+    pub fn refresh_line(favorite: &Favorite, outcome: &RefreshOutcome) -> Option<String> {
+        match outcome {
+            RefreshOutcome::Unchanged => None,
+            RefreshOutcome::Renamed { from, .. } => {
+                Some(format!("{from} is now {}", favorite.title()))
+            }
+            RefreshOutcome::NeedsConfirmation { distance_km } => Some(format!(
+                "{}: the geocoder now places this {distance_km:.0} km away; kept as it was until accepted with --favorites --accept-moves",
+                favorite.title()
+            )),
+        }
+    }
+
+    /// Prints the effective configuration and endpoint health checks
+    pub fn display_doctor_report(
+        repository: &ApiWeatherRepository,
+        options: &DisplayOptions,
+        files: &[StateFile],
+        checks: &[HealthCheck],
+    ) {
+        println!("\n=== Weather Doctor ===");
+        println!("\n--- Configuration ---");
+        println!("Geocoder:           {}", repository.geocoder().label());
+        println!("Geocoding endpoint: {}", repository.geocoding_url());
+        println!("Weather endpoint:   {}", repository.weather_url());
+        println!("User agent:         {}", repository.user_agent());
+
+        println!("\n--- Display ---");
+        println!("Export units:       {}", options.units);
+        println!("Wind unit:          {}", options.wind_unit);
+        println!("Pressure:           {}", options.pressure);
+        println!(
+            "Precision:          {}",
+            options.precision.unwrap_or(Precision::Tenths)
+        );
+        println!("Number locale:      {}", options.number_locale);
+        println!("Advisory standard:  {}", options.advisory_standard);
+        println!("Feels-like formula: {}", options.feels_like_formula);
+
+        println!("\n--- Files ---");
+        for file in files {
+            let note = match (file.item, file.size) {
+                (StateItem::GeocodeCache, _) if repository.geocode_cache().is_none() => {
+                    " (not used with --no-cache)"
+                }
+                (_, None) => " (not present)",
+                (_, Some(_)) => "",
+            };
             println!(
-                "Weather Code: {} ({})",
-                code,
-                Self::weather_code_description(code)
+                "{:<19} {}{note}",
+                file.item.to_string(),
+                file.path.display()
             );
         }
 
-        if let Some(speed) = current.wind_speed {
-            let mph = Speed::kmh_to_mph(speed);
-            print!("Wind: {speed:.1} km/h / {mph:.1} mph");
-            if let Some(direction) = current.wind_direction {
-                println!(
-                    " from {}° ({})",
-                    direction,
-                    Self::wind_direction_name(direction)
-                );
-            } else {
-                println!();
+        println!("\n--- Checks ---");
+        for check in checks {
+            let latency = check.latency.as_millis();
+            match &check.result {
+                Ok(status) => println!(
+                    "[ OK ] {}: HTTP {status} in {latency} ms, response parsed",
+                    check.service
+                ),
+                Err(e) => println!("[FAIL] {}: {e} (after {latency} ms)", check.service),
             }
         }
+        println!();
+    }
 
-        if let Some(clouds) = current.cloud_cover {
-            println!("Cloud Cover: {clouds:.0}%");
+    /// Record count, temperature range and mean, and logged precipitation
+    /// from the weather log at `path`
+    pub fn display_log_summary(summary: &WeatherLogSummary, path: &Path, options: &DisplayOptions) {
+        println!("\n=== Weather Log: {} ===", path.display());
+        for line in Self::log_summary_lines(summary, options) {
+            println!("{line}");
         }
+    }
 
-        if let Some(pressure) = current.pressure {
-            let inhg = Pressure::hpa_to_inhg(pressure);
-            println!("Pressure: {pressure:.1} hPa / {inhg:.2} inHg");
+    /// Lines printed by `display_log_summary` under its heading
+    pub fn log_summary_lines(summary: &WeatherLogSummary, options: &DisplayOptions) -> Vec<String> {
+        let locale = options.number_locale;
+        let mut lines = Vec::new();
+        if summary.skipped > 0 {
+            lines.push(format!("Skipped {} unreadable line(s)", summary.skipped));
+        }
+        if summary.records == 0 {
+            lines.push("No records".to_string());
+            return lines;
         }
 
-        if let Some(visibility) = current.visibility {
-            let feet = Distance::meters_to_feet(visibility);
-            println!("Visibility: {visibility:.0} meters / {feet:.0} feet");
+        lines.push(format!("Records:        {}", summary.records));
+        if let (Some(min), Some(max)) = (summary.temperature_min, summary.temperature_max) {
+            lines.push(format!(
+                "Temperature:    {} to {}",
+                locale.celsius(min, Precision::Tenths),
+                locale.celsius(max, Precision::Tenths)
+            ));
+        }
+        if let Some(mean) = summary.temperature_mean {
+            lines.push(format!(
+                "Mean:           {}",
+                locale.celsius(mean, Precision::Tenths)
+            ));
         }
+        if let Some(total) = summary.precipitation_total {
+            lines.push(format!(
+                "Precipitation:  {} mm (sum of logged hours)",
+                locale.decimal(total, 1)
+            ));
+        }
+        lines
     }
 
-    fn display_hourly_forecast(hourly: &[crate::models::weather_info::HourlyForecast]) {
-        if hourly.is_empty() {
+    /// Files from `weather-app state paths`, with sizes
+    pub fn display_state_files(files: &[StateFile]) {
+        println!("\n=== Stored State ===");
+        for file in files {
+            let size = file
+                .size
+                .map_or_else(|| "not present".to_string(), app_state::format_size);
+            println!(
+                "{:<18} {:>12}  {}",
+                file.item.to_string(),
+                size,
+                file.path.display()
+            );
+        }
+    }
+
+    /// Outcome of `cache clear` and `history clear`
+    pub fn display_removed_state(removed: &[StateFile]) {
+        if removed.is_empty() {
+            println!("Nothing to remove");
+        }
+        for file in removed {
+            println!(
+                "Removed {} ({}): {}",
+                file.item,
+                app_state::format_size(file.size.unwrap_or_default()),
+                file.path.display()
+            );
+        }
+    }
+
+    /// Usage totals, lookups per weekday and the most-checked locations
+    pub fn display_usage_stats(summary: &UsageSummary, enabled: bool, path: &Path) {
+        println!("\n=== Usage Statistics ===");
+        if !enabled {
+            println!("Recording is off; set \"usage_stats\": true in settings.json or tick it in the GUI settings");
+        }
+        if summary.fetches == 0 {
+            println!("No lookups recorded in {}", path.display());
             return;
         }
 
-        println!("\n--- Hourly Forecast (Next 24 Hours) ---");
-        for (i, hour) in hourly.iter().enumerate() {
-            if i >= 24 {
-                break;
+        println!(
+            "Lookups:          {} ({} failed)",
+            summary.fetches, summary.failures
+        );
+        if let Some(rate) = summary.cache_hit_rate() {
+            println!("Cache hit rate:   {:.0}%", rate * 100.0);
+        }
+        if let Some(latency) = summary.average_latency {
+            println!("Average latency:  {} ms", latency.as_millis());
+        }
+
+        println!("\n--- By Day ---");
+        for day in ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]
+            .iter()
+            .zip(summary.per_weekday)
+        {
+            println!("{:<4} {:>6}", day.0, day.1);
+        }
+
+        println!("\n--- Most-Checked Locations ---");
+        for (rank, entry) in summary
+            .per_location
+            .iter()
+            .take(USAGE_TOP_LOCATIONS)
+            .enumerate()
+        {
+            println!(
+                "{:>2}. {:<30} {:>6}",
+                rank + 1,
+                entry.location,
+                entry.fetches
+            );
+        }
+        println!();
+    }
+
+    /// "Location: ..." truncated with an ellipsis to fit `width`
+    fn location_line(location: &str, width: usize) -> String {
+        fit(&format!("Location: {location}"), width)
+    }
+
+    /// Prints notable upcoming hazards, if any
+    fn render_heads_up(
+        out: &mut Vec<String>,
+        weather_data: &WeatherData,
+        today: Option<NaiveDate>,
+        options: &DisplayOptions,
+    ) {
+        let mut lines = Vec::new();
+
+        if let Some(advisory) =
+            advisories::advisory(&weather_data.current, options.advisory_standard)
+        {
+            lines.push(advisory.message());
+        }
+
+        for period in storm::thunderstorm_periods(weather_data.upcoming_hours(usize::MAX)) {
+            let label = match period.severity {
+                StormSeverity::Thunderstorm => "Thunderstorms",
+                StormSeverity::Severe => "Severe thunderstorms",
+            };
+            let mut line = format!(
+                "{label} {} to {}",
+                Self::format_hour(&period.start),
+                Self::format_hour(&period.end)
+            );
+            if let Some(cape) = period.peak_cape {
+                let level = storm::CapeLevel::from_cape(cape);
+                line.push_str(&format!(
+                    " (storm energy: {}, {cape:.0} J/kg)",
+                    level.label()
+                ));
+            }
+            lines.push(line);
+        }
+
+        for window in weather_data.low_visibility_windows(FOG_VISIBILITY_THRESHOLD_M) {
+            let mut line = window.message(today);
+            if let Some(visibility) = window.min_visibility {
+                line.push_str(&format!(" (visibility down to {visibility:.0} m)"));
             }
-            Self::display_hourly_item(hour);
+            lines.push(line);
+        }
+
+        if lines.is_empty() {
+            return;
+        }
+
+        push_line!(out, "\n--- Heads Up ---");
+        for line in lines {
+            push_line!(out, "{}", fit(&line, options.width()));
         }
     }
 
-    fn display_hourly_item(hour: &crate::models::weather_info::HourlyForecast) {
-        let time_display = if let Some(t) = hour.time.split('T').nth(1) {
-            t.split(':').next().unwrap_or(&hour.time)
-        } else {
-            &hour.time
-        };
+    /// Prints the window of highest convective energy in the next 48 hours
+    fn render_storm_outlook(out: &mut Vec<String>, weather_data: &WeatherData) {
+        push_line!(
+            out,
+            "\n--- Storm Outlook (Next {STORM_OUTLOOK_HOURS} Hours) ---"
+        );
+
+        match storm::peak_cape_window(weather_data.upcoming_hours(STORM_OUTLOOK_HOURS)) {
+            Some(window) => {
+                push_line!(
+                    out,
+                    "Peak CAPE: {:.0} J/kg ({}) at {}",
+                    window.peak_cape,
+                    window.level.label(),
+                    Self::format_hour(&window.peak_time)
+                );
+                push_line!(
+                    out,
+                    "Peak window: {} to {}",
+                    Self::format_hour(&window.start),
+                    Self::format_hour(&window.end)
+                );
+            }
+            None => push_line!(out, "No convective energy forecast"),
+        }
+    }
 
-        print!("{time_display}:00 - ");
+    fn render_winds_aloft(
+        out: &mut Vec<String>,
+        weather_data: &WeatherData,
+        options: &DisplayOptions,
+    ) {
+        push_line!(
+            out,
+            "\n--- Winds Aloft (Next {WINDS_ALOFT_HOURS} Hours) ---"
+        );
 
-        if let Some(temp) = hour.temperature {
-            let fahrenheit = Temperature::celsius_to_fahrenheit(temp);
-            print!("{temp:.1}°C / {fahrenheit:.1}°F");
+        let hours = weather_data.upcoming_hours(WINDS_ALOFT_HOURS);
+        if !aviation::has_winds_aloft(hours) {
+            push_line!(out, "Winds aloft are not available from this server");
+            return;
+        }
+        for line in Self::winds_aloft_lines(hours, options.wind_unit) {
+            push_line!(out, "{}", fit(&line, options.width()));
         }
+    }
 
-        if let Some(code) = hour.weather_code {
-            print!(" ({})", Self::weather_code_description(code));
+    /// Table of wind speed and direction at 10 m, 80 m and 120 m per hour
+    fn winds_aloft_lines(
+        hours: &[crate::models::weather_info::HourlyForecast],
+        unit: WindUnit,
+    ) -> Vec<String> {
+        let mut header = format!("{:<7}", "Time");
+        for level in WindLevel::ALL {
+            header.push_str(&format!("{:>14}", level.label()));
         }
 
-        if let Some(precip_prob) = hour.precipitation_probability {
-            print!(" | Rain: {precip_prob:.0}%");
+        let mut lines = vec![header];
+        for hour in hours {
+            let mut line = format!("{:<7}", hour.time.split('T').nth(1).unwrap_or(&hour.time));
+            for level in WindLevel::ALL {
+                let (speed, direction) = level.wind(hour);
+                line.push_str(&format!(
+                    "{:>14}",
+                    aviation::wind_text(speed, direction, unit)
+                ));
+            }
+            lines.push(line);
         }
+        lines
+    }
 
-        if let Some(precip) = hour.precipitation {
-            if precip > 0.0 {
-                let inches = Distance::mm_to_inches(precip);
-                print!(" ({precip:.1}mm / {inches:.2}in)");
+    /// "Air Quality: US AQI 39 (Good) · European AQI 21 · PM2.5 9.4 µg/m³ · …"
+    fn air_quality_line(reading: &AirQualityReading, locale: NumberLocale) -> String {
+        let mut parts = Vec::new();
+        if let (Some(aqi), Some(category)) = (reading.us_aqi, reading.us_aqi_category()) {
+            parts.push(format!("US AQI {aqi:.0} ({category})"));
+        }
+        if let Some(aqi) = reading.european_aqi {
+            parts.push(format!("European AQI {aqi:.0}"));
+        }
+        for (label, value) in [
+            ("PM2.5", reading.pm2_5),
+            ("PM10", reading.pm10),
+            ("O₃", reading.ozone),
+            ("NO₂", reading.nitrogen_dioxide),
+        ] {
+            if let Some(value) = value {
+                parts.push(format!("{label} {} µg/m³", locale.decimal(value, 1)));
             }
         }
+        format!("Air Quality: {}", parts.join(" · "))
+    }
 
-        if let Some(wind) = hour.wind_speed {
-            let mph = Speed::kmh_to_mph(wind);
-            print!(" | Wind: {wind:.0} km/h / {mph:.0} mph");
+    fn render_marine(out: &mut Vec<String>, weather_info: &WeatherInfo, options: &DisplayOptions) {
+        push_line!(out, "\n--- Marine (Next {HOURLY_DISPLAY_LIMIT} Hours) ---");
+
+        let hours = weather_info.upcoming_marine(HOURLY_DISPLAY_LIMIT);
+        if hours.is_empty() {
+            push_line!(out, "No marine data for this location");
+            return;
+        }
+        for line in Self::marine_lines(hours, options.number_locale) {
+            push_line!(out, "{}", fit(&line, options.width()));
         }
+    }
 
-        println!();
+    /// Table of wave height, period and the direction waves come from per hour
+    fn marine_lines(hours: &[MarineHour], locale: NumberLocale) -> Vec<String> {
+        let mut lines = vec![format!(
+            "{:<7}{:>9}{:>9}{:>7}",
+            "Time", "Waves", "Period", "From"
+        )];
+        for hour in hours {
+            let height = hour
+                .wave_height
+                .map_or("-".to_string(), |h| format!("{} m", locale.decimal(h, 1)));
+            let period = hour
+                .wave_period
+                .map_or("-".to_string(), |p| format!("{p:.0} s"));
+            let from = hour.wave_direction.map_or("-", Direction::compass_point);
+            lines.push(format!(
+                "{:<7}{height:>9}{period:>9}{from:>7}",
+                hour.time.split('T').nth(1).unwrap_or(&hour.time)
+            ));
+        }
+        lines
     }
 
-    /// Displays daily forecast (next 7 days)
-    fn display_daily_forecast(daily: &[crate::models::weather_info::DailyForecast]) {
-        if daily.is_empty() {
+    fn render_wind_rose(
+        out: &mut Vec<String>,
+        weather_data: &WeatherData,
+        options: &DisplayOptions,
+    ) {
+        let rose = weather_data.wind_rose(WIND_ROSE_SPOKES);
+        push_line!(out, "\n--- Wind Rose (Next {} Hours) ---", rose.total);
+
+        if rose.counted == 0 {
+            push_line!(out, "No wind directions in the forecast");
             return;
         }
+        for line in Self::wind_rose_lines(&rose, options.wind_unit) {
+            push_line!(out, "{}", fit(&line, options.width()));
+        }
+    }
 
-        println!("\n--- Daily Forecast (Next 7 Days) ---");
-        for day in daily {
-            Self::display_daily_item(day);
+    /// Eight spokes scaled to the most frequent direction, then the share and
+    /// mean speed per direction and how many hours had a direction at all
+    fn wind_rose_lines(rose: &WindRose, unit: WindUnit) -> Vec<String> {
+        // Clockwise from north: step per spoke cell (columns doubled to look round)
+        const SPOKES: [(i32, i32, char); 8] = [
+            (0, -1, '|'),
+            (1, -1, '/'),
+            (1, 0, '-'),
+            (1, 1, '\\'),
+            (0, 1, '|'),
+            (-1, 1, '/'),
+            (-1, 0, '-'),
+            (-1, -1, '\\'),
+        ];
+        let radius = WIND_ROSE_RADIUS;
+        let (cx, cy) = (2 * (radius + 1), radius + 1);
+        let mut grid = vec![vec![' '; (4 * radius + 7) as usize]; (2 * radius + 3) as usize];
+        let mut put = |x: i32, y: i32, c: char| grid[y as usize][x as usize] = c;
+
+        let max = rose.max_frequency();
+        for (bin, &(dx, dy, spoke)) in rose.bins.iter().zip(&SPOKES) {
+            let length = if bin.hours > 0 && max > 0.0 {
+                ((bin.frequency / max * radius as f64).round() as i32).max(1)
+            } else {
+                0
+            };
+            for step in 1..=length {
+                put(cx + 2 * dx * step, cy + dy * step, spoke);
+                if dy == 0 {
+                    put(cx + 2 * dx * step - dx, cy, spoke);
+                }
+            }
+            let label = Direction::compass_point(bin.center);
+            let (x, y) = (cx + 2 * dx * (radius + 1), cy + dy * (radius + 1));
+            for (offset, c) in label.chars().enumerate() {
+                put(x + offset as i32, y, c);
+            }
         }
+        put(cx, cy, 'o');
+
+        let mut lines: Vec<String> = grid
+            .into_iter()
+            .map(|row| row.into_iter().collect::<String>().trim_end().to_string())
+            .collect();
+        lines.push(String::new());
+        lines.push(format!("{:<6}{:>7}{:>12}", "From", "Share", "Mean wind"));
+        for bin in rose.bins.iter().filter(|bin| bin.hours > 0) {
+            let speed = bin.mean_speed.map_or("-".to_string(), |s| {
+                format!("{:.0} {}", unit.from_kmh(s), unit.label())
+            });
+            lines.push(format!(
+                "{:<6}{:>6.0}%{speed:>12}",
+                Direction::compass_point(bin.center),
+                bin.frequency * 100.0
+            ));
+        }
+        lines.push(format!(
+            "Direction known for {:.0}% of {} hours",
+            rose.coverage(),
+            rose.total
+        ));
+        lines
     }
 
-    fn display_daily_item(day: &crate::models::weather_info::DailyForecast) {
-        println!("\n{}", day.date);
+    /// Formats an ISO 8601 timestamp as "YYYY-MM-DD HH:MM"
+    fn format_hour(time: &str) -> String {
+        time.replace('T', " ")
+    }
 
-        if let (Some(max), Some(min)) = (day.temperature_max, day.temperature_min) {
-            let min_f = Temperature::celsius_to_fahrenheit(min);
-            let max_f = Temperature::celsius_to_fahrenheit(max);
-            println!("  Temperature: {min:.1}°C to {max:.1}°C / {min_f:.1}°F to {max_f:.1}°F");
+    fn render_current_conditions(
+        out: &mut Vec<String>,
+        current: &crate::models::weather_info::CurrentWeather,
+        options: &DisplayOptions,
+    ) {
+        push_line!(out, "\n--- Current Conditions ---");
+
+        let precision = options.precision();
+        let locale = options.number_locale;
+        if let Some(temp) = current.temperature {
+            push_line!(
+                out,
+                "Temperature: {} / {}",
+                locale.celsius(temp, precision),
+                locale.fahrenheit(temp, precision)
+            );
         }
 
-        if let Some(code) = day.weather_code {
-            println!("  Conditions: {}", Self::weather_code_description(code));
+        if let Some(apparent) = advice::feels_like(current, options.feels_like_formula) {
+            push_line!(
+                out,
+                "Feels Like: {} / {}",
+                locale.celsius(apparent, precision),
+                locale.fahrenheit(apparent, precision)
+            );
         }
 
-        if let Some(precip_sum) = day.precipitation_sum {
-            if precip_sum > 0.0 {
-                let inches = Distance::mm_to_inches(precip_sum);
-                print!("  Precipitation: {precip_sum:.1} mm / {inches:.2} in");
-                if let Some(prob) = day.precipitation_probability {
-                    print!(" ({prob}% chance)");
-                }
-                println!();
+        if let Some(callout) = advice::feels_like_callout(current) {
+            push_line!(out, "  {}", callout.message());
+        }
+
+        if let Some(advisory) = advisories::advisory(current, options.advisory_standard) {
+            push_line!(out, "  {}", advisory.message());
+        }
+
+        if let Some(humidity) = current.humidity {
+            push_line!(out, "Humidity: {humidity:.0}%");
+        }
+
+        if let Some(dew_point) = current.dew_point {
+            push_line!(
+                out,
+                "Dew Point: {} / {}",
+                locale.celsius(dew_point, precision),
+                locale.fahrenheit(dew_point, precision)
+            );
+        }
+
+        if let Some(precip) = current.precipitation {
+            let inches = Distance::mm_to_inches(precip);
+            let mut line = format!(
+                "Precipitation: {} mm / {} in",
+                locale.decimal(precip, 1),
+                locale.decimal(inches, 2)
+            );
+            if let Some(snowfall) = current.snowfall.filter(|s| *s > 0.0) {
+                line.push_str(&format!(
+                    " ({} of snow)",
+                    Self::snowfall_text(snowfall, locale)
+                ));
             }
+            out.push(line);
         }
 
-        if let Some(wind) = day.wind_speed_max {
-            let mph = Speed::kmh_to_mph(wind);
-            println!("  Max Wind Speed: {wind:.1} km/h / {mph:.1} mph");
+        if let Some(code) = current.weather_code {
+            let mut line = format!(
+                "Weather Code: {} ({})",
+                code,
+                weather_codes::description(code)
+            );
+            if let Some(note) =
+                precip_phase::phase_note(current.temperature, current.humidity, code)
+            {
+                line.push_str(&format!(" {note}"));
+            }
+            push_line!(out, "{line}");
         }
 
-        if let (Some(sunrise), Some(sunset)) = (&day.sunrise, &day.sunset) {
-            let sunrise_time = sunrise.split('T').nth(1).unwrap_or(sunrise);
-            let sunset_time = sunset.split('T').nth(1).unwrap_or(sunset);
-            println!("  Sunrise: {sunrise_time} | Sunset: {sunset_time}");
-        }
-    }
-
-    /// Converts weather code to human-readable description
-    fn weather_code_description(code: i32) -> &'static str {
-        match code {
-            0 => "Clear sky",
-            1 => "Mainly clear",
-            2 => "Partly cloudy",
-            3 => "Overcast",
-            45 | 48 => "Fog",
-            51 | 53 | 55 => "Drizzle",
-            61 | 63 | 65 => "Rain",
-            71 | 73 | 75 => "Snow",
-            77 => "Snow grains",
-            80..=82 => "Rain showers",
-            85 | 86 => "Snow showers",
-            95 => "Thunderstorm",
-            96 | 99 => "Thunderstorm with hail",
-            _ => "Unknown",
-        }
-    }
-
-    /// Converts wind direction degrees to cardinal direction
-    fn wind_direction_name(degrees: f64) -> &'static str {
-        let normalized = ((degrees % 360.0) + 360.0) % 360.0;
-        match normalized {
-            d if !(22.5..337.5).contains(&d) => "N",
-            d if (22.5..67.5).contains(&d) => "NE",
-            d if (67.5..112.5).contains(&d) => "E",
-            d if (112.5..157.5).contains(&d) => "SE",
-            d if (157.5..202.5).contains(&d) => "S",
-            d if (202.5..247.5).contains(&d) => "SW",
-            d if (247.5..292.5).contains(&d) => "W",
-            d if (292.5..337.5).contains(&d) => "NW",
-            _ => "Unknown",
+        if let Some(speed) = current.wind_speed {
+            let mph = Speed::kmh_to_mph(speed);
+            let mut line = format!(
+                "Wind: {} km/h / {} mph",
+                locale.decimal(speed, 1),
+                locale.decimal(mph, 1)
+            );
+            if let Some(direction) = current.wind_direction {
+                line.push_str(&format!(
+                    " from {}° ({})",
+                    direction,
+                    Direction::compass_point(direction)
+                ));
+            }
+            if let Some(gusts) = current.notable_gusts() {
+                line.push_str(&Self::gusts_text(gusts, locale));
+            }
+            out.push(line);
         }
+
+        if let Some(clouds) = current.cloud_cover {
+            push_line!(out, "Cloud Cover: {clouds:.0}%");
+        }
+
+        if let Some((reference, pressure)) = current.pressure_reading(options.pressure) {
+            let inhg = Pressure::hpa_to_inhg(pressure);
+            push_line!(
+                out,
+                "Pressure ({}): {} hPa / {} inHg",
+                reference.label(),
+                locale.decimal(pressure, 1),
+                locale.decimal(inhg, 2)
+            );
+        }
+
+        if let Some(visibility) = current.visibility {
+            let feet = Distance::meters_to_feet(visibility);
+            push_line!(out, "Visibility: {visibility:.0} meters / {feet:.0} feet");
+        }
+    }
+
+    fn render_snow(out: &mut Vec<String>, weather_data: &WeatherData, options: &DisplayOptions) {
+        let locale = options.number_locale;
+        if let Some(depth) = weather_data.current.snow_depth.filter(|d| *d > 0.0) {
+            let cm = depth * 100.0;
+            let inches = Distance::cm_to_inches(cm);
+            push_line!(
+                out,
+                "Snow depth: {} cm / {} in",
+                locale.decimal(cm, 0),
+                locale.decimal(inches, 0)
+            );
+        }
+
+        if let Some(accumulation) =
+            snow::snowfall_accumulation(weather_data, SNOW_ACCUMULATION_HOURS)
+                .filter(|a| a.total.total > 0.0)
+        {
+            let cm = accumulation.total.total;
+            let inches = Distance::cm_to_inches(cm);
+            let by = time::weekday_name(&accumulation.until).unwrap_or(accumulation.until);
+            let mut line = format!(
+                "Snowfall: ~{} cm / {} in expected by {by}",
+                locale.decimal(cm, 0),
+                locale.decimal(inches, 1)
+            );
+            if !accumulation.total.is_complete() {
+                line.push_str(&format!(
+                    " ({} of {} h reported)",
+                    accumulation.total.hours_with_data, accumulation.total.window_hours
+                ));
+            }
+            out.push(line);
+        }
+    }
+
+    fn render_freezing_level(out: &mut Vec<String>, weather_data: &WeatherData) {
+        let Some(freezing_level) = weather_data
+            .current_hour()
+            .and_then(|h| h.freezing_level_height)
+        else {
+            return;
+        };
+
+        let snow_line = mountain::snow_line(freezing_level);
+        let freezing_feet = Distance::meters_to_feet(freezing_level);
+        let snow_line_feet = Distance::meters_to_feet(snow_line);
+        push_line!(out,
+            "Freezing level: {freezing_level:.0} m / {freezing_feet:.0} ft (snow above ~{snow_line:.0} m / {snow_line_feet:.0} ft)"
+        );
+    }
+
+    /// The next two hours in 15-minute steps, when the minutely forecast was fetched
+    fn render_minutely_forecast(
+        out: &mut Vec<String>,
+        data: &WeatherData,
+        options: &DisplayOptions,
+    ) {
+        let steps = data.upcoming_minutely(MINUTELY_DISPLAY_STEPS);
+        if steps.is_empty() {
+            return;
+        }
+
+        push_line!(out, "\n--- {} ---", Self::minutely_heading(steps));
+        for line in Self::minutely_lines(steps, options.precision(), options.number_locale) {
+            push_line!(out, "{}", fit(&line, options.width()));
+        }
+    }
+
+    /// "Next 2 Hours (Every 15 Minutes)", from the number of steps shown
+    /// and the spacing of the first two
+    fn minutely_heading(steps: &[MinutelyForecast]) -> String {
+        let parse = |step: &MinutelyForecast| {
+            chrono::NaiveDateTime::parse_from_str(&step.time, "%Y-%m-%dT%H:%M").ok()
+        };
+        let interval = match steps {
+            [first, second, ..] => parse(first)
+                .zip(parse(second))
+                .map(|(first, second)| (second - first).num_minutes())
+                .filter(|minutes| *minutes > 0),
+            _ => None,
+        }
+        .unwrap_or(DEFAULT_MINUTELY_INTERVAL_MINUTES);
+        let span = interval * steps.len() as i64;
+        let heading = if span > 60 && span % 60 == 0 {
+            tr(REPORT_LOCALE, Key::MinutelyHoursHeading).replacen("{}", &(span / 60).to_string(), 1)
+        } else {
+            tr(REPORT_LOCALE, Key::MinutelyMinutesHeading).replacen("{}", &span.to_string(), 1)
+        };
+        heading.replacen("{}", &interval.to_string(), 1)
+    }
+
+    /// Table of temperature, precipitation in the quarter hour and conditions
+    fn minutely_lines(
+        steps: &[MinutelyForecast],
+        precision: Precision,
+        locale: NumberLocale,
+    ) -> Vec<String> {
+        let mut lines = vec![format!(
+            "{:<7}{:>9}{:>10}  {}",
+            "Time", "Temp", "Precip", "Conditions"
+        )];
+        for step in steps {
+            let temperature = step
+                .temperature
+                .map_or("-".to_string(), |t| locale.celsius(t, precision));
+            let precipitation = step
+                .precipitation
+                .map_or("-".to_string(), |p| format!("{} mm", locale.decimal(p, 1)));
+            let conditions = step.weather_code.map_or("-", weather_codes::description);
+            lines.push(format!(
+                "{:<7}{temperature:>9}{precipitation:>10}  {conditions}",
+                step.time.split('T').nth(1).unwrap_or(&step.time)
+            ));
+        }
+        lines
+    }
+
+    fn render_hourly_forecast(out: &mut Vec<String>, data: &WeatherData, options: &DisplayOptions) {
+        let hourly = data.hourly_from_today();
+        if hourly.is_empty() {
+            if options.verbose {
+                eprintln!("Note: no hourly data available for this location");
+            }
+            return;
+        }
+
+        push_line!(out, "\n--- Hourly Forecast (Next 24 Hours) ---");
+        let shown = &hourly[..hourly.len().min(HOURLY_DISPLAY_LIMIT)];
+        for hour in shown {
+            // The local times either side of this line are not an hour apart
+            if let Some(change) = hour.clock_change {
+                push_line!(
+                    out,
+                    "{}",
+                    fit(&format!("  ({})", change.note()), options.width())
+                );
+            }
+            push_line!(
+                out,
+                "{}",
+                Self::hourly_line(
+                    hour,
+                    options.width(),
+                    options.precision(),
+                    options.number_locale
+                )
+            );
+        }
+        // Summarizes the lines above, not the past days ahead of them
+        if let Some(footer) = HourlyStats::from_hours(shown).footer(
+            options.precision(),
+            options.number_locale,
+            options.wind_unit,
+        ) {
+            push_line!(out, "{}", fit(&footer, options.width()));
+        }
+    }
+
+    /// One hourly line, dropping gusts and sky, imperial units and then wind
+    /// to fit `width`
+    fn hourly_line(
+        hour: &crate::models::weather_info::HourlyForecast,
+        width: usize,
+        precision: Precision,
+        locale: NumberLocale,
+    ) -> String {
+        [
+            HourlyDetail::Extended,
+            HourlyDetail::Full,
+            HourlyDetail::MetricOnly,
+            HourlyDetail::Compact,
+        ]
+        .into_iter()
+        .map(|detail| Self::format_hourly(hour, detail, precision, locale))
+        .find(|line| line.chars().count() <= width)
+        .unwrap_or_else(|| {
+            fit(
+                &Self::format_hourly(hour, HourlyDetail::Compact, precision, locale),
+                width,
+            )
+        })
+    }
+
+    fn format_hourly(
+        hour: &crate::models::weather_info::HourlyForecast,
+        detail: HourlyDetail,
+        precision: Precision,
+        locale: NumberLocale,
+    ) -> String {
+        let imperial = matches!(detail, HourlyDetail::Extended | HourlyDetail::Full);
+        let time_display = if let Some(t) = hour.time.split('T').nth(1) {
+            t.split(':').next().unwrap_or(&hour.time)
+        } else {
+            &hour.time
+        };
+
+        let mut line = format!("{time_display}:00 - ");
+
+        if let Some(temp) = hour.temperature {
+            line.push_str(&locale.celsius(temp, precision));
+            if imperial {
+                line.push_str(&format!(" / {}", locale.fahrenheit(temp, precision)));
+            }
+        }
+
+        if let Some(code) = hour.weather_code {
+            line.push_str(&format!(" ({})", weather_codes::description(code)));
+            if let Some(note) = precip_phase::phase_note(hour.temperature, hour.humidity, code) {
+                line.push_str(&format!(" {note}"));
+            }
+        }
+
+        if let Some(precip_prob) = hour.precipitation_probability {
+            line.push_str(&format!(" | Rain: {precip_prob:.0}%"));
+        }
+
+        if let Some(precip) = hour.precipitation.filter(|p| *p > 0.0) {
+            let mm = locale.decimal(precip, 1);
+            if imperial {
+                let inches = locale.decimal(Distance::mm_to_inches(precip), 2);
+                line.push_str(&format!(" ({mm}mm / {inches}in)"));
+            } else {
+                line.push_str(&format!(" ({mm}mm)"));
+            }
+        }
+
+        if let Some(wind) = hour.wind_speed.filter(|_| detail != HourlyDetail::Compact) {
+            line.push_str(&format!(" | Wind: {wind:.0} km/h"));
+            if imperial {
+                let mph = Speed::kmh_to_mph(wind);
+                line.push_str(&format!(" / {mph:.0} mph"));
+            }
+            if let Some(direction) = hour.wind_direction {
+                line.push(' ');
+                line.push_str(Direction::compass_point(direction));
+            }
+            if let Some(gusts) = hour
+                .notable_gusts()
+                .filter(|_| detail == HourlyDetail::Extended)
+            {
+                let mph = Speed::kmh_to_mph(gusts);
+                line.push_str(&format!(", gusting to {gusts:.0} km/h / {mph:.0} mph"));
+            }
+        }
+
+        if detail == HourlyDetail::Extended {
+            if let Some(clouds) = hour.cloud_cover {
+                line.push_str(&format!(" | Clouds: {clouds:.0}%"));
+            }
+            if let Some(visibility) = hour.visibility {
+                line.push_str(&format!(" | Vis: {}", format::visibility(visibility)));
+            }
+        }
+
+        line
+    }
+
+    /// Displays daily forecast (next 7 days)
+    fn render_daily_forecast(
+        out: &mut Vec<String>,
+        daily: &[crate::models::weather_info::DailyForecast],
+        latitude: f64,
+        options: &DisplayOptions,
+    ) {
+        if daily.is_empty() {
+            if options.verbose {
+                eprintln!("Note: no daily data available for this location");
+            }
+            return;
+        }
+
+        push_line!(out, "\n--- Daily Forecast (Next {} Days) ---", daily.len());
+        for line in Self::daily_lines(
+            daily,
+            latitude,
+            options.width(),
+            options.precision(),
+            options.number_locale,
+            options.skin_type,
+        ) {
+            push_line!(out, "{line}");
+        }
+    }
+
+    /// Daily forecast as a table on wide outputs, otherwise as a list.
+    /// Days without sunrise or sunset at `latitude` show polar day or night.
+    /// With a skin type, the list adds the time to sunburn to each UV line
+    /// and the table adds one line for today's peak below it
+    fn daily_lines(
+        daily: &[crate::models::weather_info::DailyForecast],
+        latitude: f64,
+        width: usize,
+        precision: Precision,
+        locale: NumberLocale,
+        skin_type: Option<SkinType>,
+    ) -> Vec<String> {
+        let lines = if width >= DAILY_TABLE_MIN_WIDTH {
+            let mut lines = Self::daily_table(daily, latitude, width, precision, locale);
+            let today = daily.first().and_then(|day| day.uv_index_max);
+            if let (Some(uv), Some(skin_type)) = (today, skin_type) {
+                lines.push(format!(
+                    "UV today: {}, {} (skin type {skin_type})",
+                    Self::uv_text(uv),
+                    uv::exposure_note(uv, skin_type)
+                ));
+            }
+            lines
+        } else {
+            daily
+                .iter()
+                .flat_map(|day| Self::daily_list_item(day, latitude, precision, locale, skin_type))
+                .collect()
+        };
+        lines.iter().map(|line| fit(line, width)).collect()
+    }
+
+    /// Humidity and UV columns appear only when some day has the data and the
+    /// width leaves room for them; they are the first to go on narrower outputs
+    fn daily_table(
+        daily: &[crate::models::weather_info::DailyForecast],
+        latitude: f64,
+        width: usize,
+        precision: Precision,
+        locale: NumberLocale,
+    ) -> Vec<String> {
+        let clock = |t: &Option<String>| {
+            t.as_deref()
+                .and_then(|t| t.split('T').nth(1))
+                .unwrap_or("--:--")
+                .to_string()
+        };
+        let mut spare = width.saturating_sub(DAILY_TABLE_MIN_WIDTH);
+        let mut fits = |needed: usize| {
+            let fits = spare >= needed;
+            if fits {
+                spare -= needed;
+            }
+            fits
+        };
+        let show_humidity =
+            daily.iter().any(|d| d.humidity_mean.is_some()) && fits(DAILY_HUMIDITY_COLUMN_WIDTH);
+        let show_uv = daily.iter().any(|d| d.uv_index_max.is_some()) && fits(DAILY_UV_COLUMN_WIDTH);
+        let extra_columns = |humidity: String, uv: String| {
+            let mut columns = String::new();
+            if show_humidity {
+                columns.push_str(&format!("{humidity:>6}"));
+            }
+            if show_uv {
+                columns.push_str(&format!("{uv:>16}"));
+            }
+            columns
+        };
+
+        let mut lines = vec![format!(
+            "{:<12}{:>15}  {:<24}{:>18}{:>10}{}  {}",
+            "Date",
+            "Low / High",
+            "Conditions",
+            "Precip",
+            "Wind",
+            extra_columns("Hum".to_string(), "UV".to_string()),
+            "Sun"
+        )];
+
+        for day in daily {
+            let temperature = match (day.temperature_min, day.temperature_max) {
+                (Some(min), Some(max)) => format!(
+                    "{} / {}",
+                    locale.number(min, precision),
+                    locale.celsius(max, precision)
+                ),
+                _ => "-".to_string(),
+            };
+            let conditions = day.weather_code.map_or("-", weather_codes::description);
+            // Peak chance, with the wet hours abbreviated to "~3h" when known
+            let chance = day
+                .precipitation_probability
+                .map(|peak| match day.wet_hours {
+                    Some(wet_hours) => format!("{peak:.0}% ~{wet_hours}h"),
+                    None => format!("{peak:.0}%"),
+                });
+            let precipitation = match (day.precipitation_sum, chance) {
+                (Some(sum), Some(chance)) => format!("{} mm {chance}", locale.decimal(sum, 1)),
+                (Some(sum), None) => format!("{} mm", locale.decimal(sum, 1)),
+                _ => "-".to_string(),
+            };
+            let wind = day
+                .wind_speed_max
+                .map_or_else(|| "-".to_string(), |w| format!("{w:.0} km/h"));
+            let humidity = day
+                .humidity_mean
+                .map_or_else(|| "-".to_string(), |h| format!("{h:.0}%"));
+            let uv = day
+                .uv_index_max
+                .map_or_else(|| "-".to_string(), Self::uv_text);
+            let sun = match day.daylight(latitude).label() {
+                Some(polar) => polar.to_string(),
+                None => format!("{}–{}", clock(&day.sunrise), clock(&day.sunset)),
+            };
+            lines.push(format!(
+                "{:<12}{temperature:>15}  {conditions:<24}{precipitation:>18}{wind:>10}{}  {}{}",
+                locale.date(&day.date),
+                extra_columns(humidity, uv),
+                sun,
+                Self::confidence_note(day)
+            ));
+        }
+
+        lines
+    }
+
+    /// " (low confidence)" for days the ensemble disagrees on; the views
+    /// stay quiet about medium and high confidence
+    fn confidence_note(day: &crate::models::weather_info::DailyForecast) -> String {
+        match day.confidence {
+            Some(confidence @ Confidence::Low) => format!(" ({})", confidence.label(REPORT_LOCALE)),
+            _ => String::new(),
+        }
+    }
+
+    fn daily_list_item(
+        day: &crate::models::weather_info::DailyForecast,
+        latitude: f64,
+        precision: Precision,
+        locale: NumberLocale,
+        skin_type: Option<SkinType>,
+    ) -> Vec<String> {
+        let mut lines = vec![
+            String::new(),
+            format!("{}{}", locale.date(&day.date), Self::confidence_note(day)),
+        ];
+
+        if let (Some(max), Some(min)) = (day.temperature_max, day.temperature_min) {
+            lines.push(format!(
+                "  Temperature: {} to {} / {} to {}",
+                locale.celsius(min, precision),
+                locale.celsius(max, precision),
+                locale.fahrenheit(min, precision),
+                locale.fahrenheit(max, precision)
+            ));
+        }
+
+        if let Some(code) = day.weather_code {
+            lines.push(format!(
+                "  Conditions: {}",
+                weather_codes::description(code)
+            ));
+        }
+
+        if let Some(precip_sum) = day.precipitation_sum.filter(|p| *p > 0.0) {
+            let inches = Distance::mm_to_inches(precip_sum);
+            let mut line = format!(
+                "  Precipitation: {} mm / {} in",
+                locale.decimal(precip_sum, 1),
+                locale.decimal(inches, 2)
+            );
+            if let Some(chance) = day.precipitation_chance() {
+                line.push_str(&format!(" ({chance})"));
+            }
+            lines.push(line);
+        }
+
+        if let Some(snowfall) = day.snowfall_sum.filter(|s| *s > 0.0) {
+            lines.push(format!(
+                "  Snowfall: {}",
+                Self::snowfall_text(snowfall, locale)
+            ));
+        }
+
+        if let Some(wind) = day.wind_speed_max {
+            let mph = Speed::kmh_to_mph(wind);
+            let mut line = format!(
+                "  Max Wind Speed: {} km/h / {} mph",
+                locale.decimal(wind, 1),
+                locale.decimal(mph, 1)
+            );
+            if let Some(gusts) = day.notable_gusts() {
+                line.push_str(&Self::gusts_text(gusts, locale));
+            }
+            lines.push(line);
+        }
+
+        if let Some(humidity) = day.humidity_mean {
+            lines.push(format!("  Humidity: {humidity:.0}%"));
+        }
+
+        if let Some(uv) = day.uv_index_max {
+            let mut line = format!("  UV Index: {}", Self::uv_text(uv));
+            if let Some(skin_type) = skin_type {
+                line.push_str(&format!(", {}", uv::exposure_note(uv, skin_type)));
+            }
+            lines.push(line);
+        }
+
+        if let Some(polar) = day.daylight(latitude).label() {
+            lines.push(format!("  Sun: {polar}"));
+        } else if let (Some(sunrise), Some(sunset)) = (&day.sunrise, &day.sunset) {
+            let sunrise_time = sunrise.split('T').nth(1).unwrap_or(sunrise);
+            let sunset_time = sunset.split('T').nth(1).unwrap_or(sunset);
+            lines.push(format!("  Sunrise: {sunrise_time} | Sunset: {sunset_time}"));
+        }
+
+        let durations: Vec<String> = [
+            ("Daylight", day.daylight_duration),
+            ("Sunshine", day.sunshine_duration),
+        ]
+        .into_iter()
+        .filter_map(|(label, seconds)| {
+            seconds.map(|s| format!("{label}: {}", format::hours_minutes(s)))
+        })
+        .collect();
+        if !durations.is_empty() {
+            lines.push(format!("  {}", durations.join(", ")));
+        }
+
+        lines
+    }
+
+    /// Snowfall in cm and inches, e.g. "6.2 cm / 2.4 in"
+    fn snowfall_text(cm: f64, locale: NumberLocale) -> String {
+        format!(
+            "{} cm / {} in",
+            locale.decimal(cm, 1),
+            locale.decimal(Distance::cm_to_inches(cm), 1)
+        )
+    }
+
+    /// ", gusting to 61.2 km/h / 38.0 mph", appended to a wind line
+    fn gusts_text(gusts: f64, locale: NumberLocale) -> String {
+        format!(
+            ", gusting to {} km/h / {} mph",
+            locale.decimal(gusts, 1),
+            locale.decimal(Speed::kmh_to_mph(gusts), 1)
+        )
+    }
+
+    /// UV index with its category, e.g. "7 (High)"
+    fn uv_text(uv: f64) -> String {
+        format!("{uv:.0} ({})", UvCategory::from_index(uv).label())
+    }
+
+    /// Prints the daily irrigation deficit table and total for the first
+    /// `GARDEN_WATERING_DAYS` days, "this week" when the forecast has them all
+    fn render_garden_watering(
+        out: &mut Vec<String>,
+        daily: &[crate::models::weather_info::DailyForecast],
+        options: &DisplayOptions,
+    ) {
+        let daily = &daily[..daily.len().min(GARDEN_WATERING_DAYS)];
+        let deficit = agriculture::irrigation_deficit(daily);
+        if deficit.is_empty() {
+            return;
+        }
+
+        let locale = options.number_locale;
+        push_line!(out, "\n--- Garden Watering ---");
+        push_line!(out, "{:<12}{:>9}{:>9}{:>9}", "Date", "ET₀", "Rain", "Need");
+        for (day, (date, need)) in daily
+            .iter()
+            .filter(|d| d.et0_evapotranspiration.is_some())
+            .zip(&deficit)
+        {
+            let et0 = day.et0_evapotranspiration.unwrap_or(0.0);
+            let rain = day.precipitation_sum.unwrap_or(0.0);
+            push_line!(
+                out,
+                "{:<12}{:>6} mm{:>6} mm{:>6} mm",
+                locale.date(&date.format("%Y-%m-%d").to_string()),
+                locale.decimal(et0, 1),
+                locale.decimal(rain, 1),
+                locale.decimal(*need, 1)
+            );
+        }
+
+        let total = agriculture::total_deficit(&deficit);
+        let inches = Distance::mm_to_inches(total);
+        let span = match deficit.len() {
+            GARDEN_WATERING_DAYS => "this week".to_string(),
+            1 => "today".to_string(),
+            days => format!("over the next {days} days"),
+        };
+        push_line!(
+            out,
+            "Water equivalent needed {span}: ~{} mm / {} in",
+            locale.decimal(total, 0),
+            locale.decimal(inches, 1)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::sample_weather_info;
+    use crate::models::favorites::ResolvedPlace;
+    use crate::models::forecast_model::ForecastModel;
+    use crate::models::weather_info::{DailyForecast, HourlyForecast};
+    use crate::utils::advice::FeelsLikeFormula;
+    use crate::utils::advisories::AdvisoryStandard;
+    use crate::utils::conversions::PressureReference;
+    use crate::views::export::{CsvDialect, UnitSystem};
+
+    fn empty_weather_info() -> WeatherInfo {
+        WeatherInfo::builder("Nowhere", 0.0, 0.0).build()
+    }
+
+    fn all_sections() -> DisplayOptions {
+        DisplayOptions {
+            mountain: true,
+            storm: true,
+            garden: true,
+            verbose: true,
+            oneline: false,
+            feels_like_formula: FeelsLikeFormula::Humidex,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_display_with_empty_data_does_not_panic() {
+        ClView::display(&empty_weather_info(), &all_sections());
+        ClView::display(&empty_weather_info(), &DisplayOptions::default());
+        ClView::display(
+            &empty_weather_info(),
+            &DisplayOptions {
+                oneline: true,
+                ..Default::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_display_sample_data() {
+        ClView::display(&sample_weather_info(), &all_sections());
+        ClView::display(&sample_weather_info(), &DisplayOptions::default());
+        for format in [
+            OutputFormat::Json,
+            OutputFormat::Csv,
+            OutputFormat::DailyCsv,
+        ] {
+            let options = DisplayOptions {
+                format,
+                units: UnitSystem::Si,
+                ..Default::default()
+            };
+            ClView::display(&sample_weather_info(), &options);
+        }
+    }
+
+    #[test]
+    fn test_render_option_matrix() {
+        let info = sample_weather_info();
+        let summary = info.summary().to_string();
+        let sections = [
+            "--- Storm Outlook",
+            "--- Winds Aloft",
+            "--- Marine",
+            "--- Wind Rose",
+            "--- Garden Watering",
+            "Freezing level:",
+        ];
+        let options = DisplayOptions::builder();
+        let cases = [
+            (
+                options.clone().build(),
+                vec!["=== Weather Report ===", "Temperature: 19.4°C"],
+                sections.to_vec(),
+            ),
+            (
+                options
+                    .clone()
+                    .mountain(true)
+                    .storm(true)
+                    .garden(true)
+                    .build(),
+                vec![
+                    "--- Storm Outlook",
+                    "--- Garden Watering",
+                    "Freezing level: 3200 m",
+                ],
+                vec!["--- Winds Aloft", "--- Marine", "--- Wind Rose"],
+            ),
+            (
+                options
+                    .clone()
+                    .winds_aloft(true)
+                    .marine(true)
+                    .wind_rose(true)
+                    .build(),
+                vec!["--- Winds Aloft", "--- Marine", "--- Wind Rose"],
+                vec!["--- Storm Outlook", "--- Garden Watering"],
+            ),
+            (
+                options.clone().precision(Precision::Whole).build(),
+                vec!["Temperature: 19°C / 67°F"],
+                vec!["19.4°C"],
+            ),
+            (
+                options.clone().number_locale(NumberLocale::De).build(),
+                vec!["Temperature: 19,4 °C"],
+                vec!["19.4°C"],
+            ),
+            (
+                options.clone().width(120).build(),
+                vec!["Date "],
+                vec!["  Temperature:"],
+            ),
+            (
+                options.clone().width(80).build(),
+                vec!["  Temperature:"],
+                vec!["Date "],
+            ),
+            (
+                options.clone().oneline(true).storm(true).build(),
+                vec![summary.as_str()],
+                vec!["=== Weather Report ===", "--- Storm Outlook"],
+            ),
+            (
+                options.clone().format(OutputFormat::Json).build(),
+                vec!["\"temperature\": 19.4", "\"pressure_msl_hpa\""],
+                vec!["=== Weather Report ===", "pressure_msl_inhg"],
+            ),
+            (
+                options
+                    .clone()
+                    .format(OutputFormat::Json)
+                    .units(UnitSystem::Si)
+                    .all_units(true)
+                    .build(),
+                vec!["\"temperature_k\"", "pressure_msl_inhg"],
+                vec!["\"temperature\""],
+            ),
+            (
+                options.clone().format(OutputFormat::Csv).build(),
+                vec!["time,temperature,"],
+                vec!["=== Weather Report ==="],
+            ),
+            (
+                options
+                    .clone()
+                    .format(OutputFormat::DailyCsv)
+                    .csv_dialect(CsvDialect::ExcelDe)
+                    .build(),
+                vec!["date;temperature_max;"],
+                vec!["time,", "date,"],
+            ),
+        ];
+        for (options, present, absent) in cases {
+            let output = ClView::render(&info, &options);
+            for fragment in present {
+                assert!(
+                    output.contains(fragment),
+                    "{options:?} lacks {fragment:?}:\n{output}"
+                );
+            }
+            for fragment in absent {
+                assert!(
+                    !output.contains(fragment),
+                    "{options:?} has {fragment:?}:\n{output}"
+                );
+            }
+        }
+    }
+
+    /// Lines whose layout depends on the output width, rendered from the fixture
+    fn width_dependent_lines(width: usize) -> Vec<String> {
+        let info = WeatherInfo::builder(
+            "Llanfairpwllgwyngyllgogerychwyrndrobwllllantysiliogogogoch, Isle of Anglesey, Wales",
+            53.2,
+            -4.2,
+        )
+        .build();
+        let sample = sample_weather_info();
+
+        let mut lines = vec![ClView::location_line(&info.location, width)];
+        lines.extend(
+            sample
+                .weather_data
+                .hourly
+                .iter()
+                .map(|h| ClView::hourly_line(h, width, Precision::Tenths, NumberLocale::En)),
+        );
+        lines.extend(ClView::daily_lines(
+            &sample.weather_data.daily,
+            sample.latitude,
+            width,
+            Precision::Tenths,
+            NumberLocale::En,
+            Some(SkinType::II),
+        ));
+        lines
+    }
+
+    #[test]
+    fn test_rendering_fits_width() {
+        for width in [60, 80, 120] {
+            for line in width_dependent_lines(width) {
+                assert!(
+                    line.chars().count() <= width,
+                    "{width} columns exceeded: {line}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_narrow_rendering_drops_detail() {
+        let showers = &sample_weather_info().weather_data.hourly[37];
+
+        let widest = ClView::hourly_line(showers, 170, Precision::Tenths, NumberLocale::En);
+        assert!(
+            widest.ends_with("S, gusting to 42 km/h / 26 mph | Clouds: 90% | Vis: 20 km"),
+            "{widest}"
+        );
+
+        let wide = ClView::hourly_line(showers, 120, Precision::Tenths, NumberLocale::En);
+        assert!(wide.contains("°F") && wide.contains("mph") && !wide.contains("gusting"));
+        assert!(!wide.contains("Clouds"));
+
+        // A quieter hour has room for the sky at the same width
+        let fog = &sample_weather_info().weather_data.hourly[30];
+        let line = ClView::hourly_line(fog, 120, Precision::Tenths, NumberLocale::En);
+        assert!(line.ends_with("NW | Clouds: 100% | Vis: 400 m"), "{line}");
+
+        let medium = ClView::hourly_line(showers, 80, Precision::Tenths, NumberLocale::En);
+        assert!(!medium.contains("°F") && medium.ends_with("Wind: 18 km/h S"));
+
+        let narrow = ClView::hourly_line(showers, 60, Precision::Tenths, NumberLocale::En);
+        assert!(!narrow.contains("Wind") && narrow.contains("Rain: 70%"));
+
+        let location = ClView::location_line(&"x".repeat(100), 60);
+        assert!(location.ends_with('…'));
+    }
+
+    #[test]
+    fn test_daily_layout_switches_to_table_when_wide() {
+        let daily = sample_weather_info().weather_data.daily;
+
+        let table =
+            ClView::daily_lines(&daily, 47.6, 120, Precision::Tenths, NumberLocale::En, None);
+        assert_eq!(table.len(), daily.len() + 1);
+        assert!(table[0].starts_with("Date"));
+
+        let list = ClView::daily_lines(&daily, 47.6, 80, Precision::Tenths, NumberLocale::En, None);
+        assert!(list.iter().any(|l| l.starts_with("  Temperature:")));
+    }
+
+    #[test]
+    fn test_polar_days_have_no_sun_times() {
+        let daily = vec![
+            DailyForecast::builder("2024-12-21")
+                .temperature_max(-8.0)
+                .build(),
+            DailyForecast::builder("2024-06-21")
+                .sunrise("2024-06-21T00:00")
+                .sunset("2024-06-21T00:00")
+                .build(),
+        ];
+        // Longyearbyen, Svalbard
+        let table = ClView::daily_lines(
+            &daily,
+            78.22,
+            120,
+            Precision::Tenths,
+            NumberLocale::En,
+            None,
+        );
+        assert!(table[1].ends_with("  polar night"), "{}", table[1]);
+        assert!(table[2].ends_with("  polar day"), "{}", table[2]);
+
+        let list =
+            ClView::daily_list_item(&daily[0], 78.22, Precision::Tenths, NumberLocale::En, None);
+        assert_eq!(list.last().unwrap(), "  Sun: polar night");
+        // Missing times further south are just missing
+        let list =
+            ClView::daily_list_item(&daily[0], 47.6, Precision::Tenths, NumberLocale::En, None);
+        assert!(!list.iter().any(|l| l.contains("Sun")));
+    }
+
+    #[test]
+    fn test_low_confidence_days_are_marked() {
+        let mut daily = sample_weather_info().weather_data.daily;
+        daily[0].confidence = Some(Confidence::High);
+        daily[1].confidence = Some(Confidence::Low);
+
+        let table =
+            ClView::daily_lines(&daily, 47.6, 140, Precision::Tenths, NumberLocale::En, None);
+        assert!(!table[1].contains("confidence"), "{}", table[1]);
+        assert!(table[2].ends_with(" (low confidence)"), "{}", table[2]);
+
+        let list =
+            ClView::daily_list_item(&daily[1], 47.6, Precision::Tenths, NumberLocale::En, None);
+        assert_eq!(list[1], "2024-06-02 (low confidence)");
+    }
+
+    #[test]
+    fn test_daily_lines_in_german_number_locale() {
+        let daily = sample_weather_info().weather_data.daily;
+        let first = &daily[0];
+        let (min, max) = (
+            first.temperature_min.unwrap(),
+            first.temperature_max.unwrap(),
+        );
+
+        let table =
+            ClView::daily_lines(&daily, 47.6, 120, Precision::Tenths, NumberLocale::De, None);
+        assert!(table[1].starts_with("01.06."), "{}", table[1]);
+        assert!(
+            table[1].contains(&format!(
+                "{} / {}",
+                NumberLocale::De.number(min, Precision::Tenths),
+                NumberLocale::De.celsius(max, Precision::Tenths)
+            )),
+            "{}",
+            table[1]
+        );
+
+        let list = ClView::daily_list_item(first, 47.6, Precision::Whole, NumberLocale::De, None);
+        assert_eq!(list[1], "01.06.");
+        assert!(list[2].contains(" °C to "), "{}", list[2]);
+    }
+
+    #[test]
+    fn test_daily_humidity_and_uv_columns_collapse_first() {
+        let mut daily = sample_weather_info().weather_data.daily;
+        let header = |daily: &[DailyForecast], width| {
+            ClView::daily_lines(
+                daily,
+                47.6,
+                width,
+                Precision::Tenths,
+                NumberLocale::En,
+                None,
+            )
+            .remove(0)
+        };
+
+        // No data, no columns
+        for day in &mut daily {
+            day.humidity_mean = None;
+            day.uv_index_max = None;
+        }
+        assert!(!header(&daily, 140).contains("Hum"));
+
+        daily[0].humidity_mean = Some(64.4);
+        daily[0].uv_index_max = Some(7.2);
+        assert!(header(&daily, 122).contains("Hum              UV"));
+        assert!(header(&daily, 110).contains("Hum  Sun"));
+        assert!(!header(&daily, 100).contains("Hum"));
+
+        let table =
+            ClView::daily_lines(&daily, 47.6, 122, Precision::Tenths, NumberLocale::En, None);
+        assert!(table[1].contains("   64%        7 (High)"), "{}", table[1]);
+        assert!(table[2].contains("     -               -"), "{}", table[2]);
+
+        let list =
+            ClView::daily_list_item(&daily[0], 47.6, Precision::Tenths, NumberLocale::En, None);
+        assert!(list.contains(&"  Humidity: 64%".to_string()));
+        assert!(list.contains(&"  UV Index: 7 (High)".to_string()));
+
+        // A skin type adds the time to sunburn, in the table below it
+        let skin_type = Some(SkinType::III);
+        let list = ClView::daily_list_item(
+            &daily[0],
+            47.6,
+            Precision::Tenths,
+            NumberLocale::En,
+            skin_type,
+        );
+        assert!(
+            list.contains(&"  UV Index: 7 (High), ~30 min to burn without protection".to_string())
+        );
+        let table = ClView::daily_lines(
+            &daily,
+            47.6,
+            120,
+            Precision::Tenths,
+            NumberLocale::En,
+            skin_type,
+        );
+        assert_eq!(
+            table.last().unwrap(),
+            "UV today: 7 (High), ~30 min to burn without protection (skin type III)"
+        );
+        daily[0].uv_index_max = Some(2.0);
+        let list = ClView::daily_list_item(
+            &daily[0],
+            47.6,
+            Precision::Tenths,
+            NumberLocale::En,
+            skin_type,
+        );
+        assert!(list.contains(&"  UV Index: 2 (Low), low risk".to_string()));
+    }
+
+    #[test]
+    fn test_daylight_and_sunshine_durations() {
+        let mut day = DailyForecast::builder("2024-12-01")
+            .daylight_duration(34_920.0)
+            .sunshine_duration(18_600.0)
+            .build();
+        let list = ClView::daily_list_item(&day, 47.6, Precision::Tenths, NumberLocale::En, None);
+        assert!(list.contains(&"  Daylight: 9h 42m, Sunshine: 5h 10m".to_string()));
+
+        day.sunshine_duration = None;
+        let list = ClView::daily_list_item(&day, 47.6, Precision::Tenths, NumberLocale::En, None);
+        assert!(list.contains(&"  Daylight: 9h 42m".to_string()));
+
+        day.daylight_duration = None;
+        let list = ClView::daily_list_item(&day, 47.6, Precision::Tenths, NumberLocale::En, None);
+        assert!(!list.iter().any(|line| line.contains("Daylight")));
+    }
+
+    #[test]
+    fn test_winds_aloft_table() {
+        use crate::models::weather_info::HourlyForecast;
+
+        let hours = vec![
+            HourlyForecast::builder("2024-06-01T12:00")
+                .wind_speed(18.52)
+                .wind_direction(200.0)
+                .wind_speed_80m(37.04)
+                .wind_direction_80m(225.0)
+                .wind_speed_120m(46.3)
+                .wind_direction_120m(230.0)
+                .build(),
+            HourlyForecast::builder("2024-06-01T13:00")
+                .wind_speed(20.0)
+                .build(),
+        ];
+
+        let lines = ClView::winds_aloft_lines(&hours, WindUnit::Knots);
+        assert_eq!(
+            lines,
+            vec![
+                "Time             10 m          80 m         120 m",
+                "12:00         10 kn S      20 kn SW      25 kn SW",
+                "13:00           11 kn             -             -",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_marine_table() {
+        let hours = vec![
+            MarineHour {
+                wave_height: Some(1.42),
+                wave_period: Some(8.9),
+                wave_direction: Some(135.0),
+                ..MarineHour::new("2024-06-01T12:00")
+            },
+            MarineHour {
+                wave_period: Some(9.2),
+                ..MarineHour::new("2024-06-01T13:00")
+            },
+        ];
+
+        assert_eq!(
+            ClView::marine_lines(&hours, NumberLocale::De),
+            vec![
+                "Time       Waves   Period   From",
+                "12:00      1,4 m      9 s     SE",
+                "13:00          -      9 s      -",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_marginal_rain_is_annotated_not_replaced() {
+        let mut info = sample_weather_info();
+        let current = &mut info.weather_data.current;
+        current.temperature = Some(1.0);
+        current.humidity = Some(60.0);
+        current.weather_code = Some(63);
+        let output = ClView::render(&info, &DisplayOptions::default());
+        assert!(output.contains("Weather Code: 63 (Rain) (may fall as wet snow)"));
+
+        info.weather_data.current.temperature = Some(8.0);
+        let output = ClView::render(&info, &DisplayOptions::default());
+        assert!(output.contains("Weather Code: 63 (Rain)\n"));
+    }
+
+    #[test]
+    fn test_gusts_follow_the_wind() {
+        let mut info = sample_weather_info();
+        let output = ClView::render(&info, &DisplayOptions::default());
+        assert!(output.contains(
+            "Wind: 11.2 km/h / 7.0 mph from 225° (SW), gusting to 24.1 km/h / 15.0 mph\n"
+        ));
+
+        // Within the margin of the sustained wind they add nothing
+        info.weather_data.current.wind_gusts = Some(18.0);
+        let output = ClView::render(&info, &DisplayOptions::default());
+        assert!(output.contains("Wind: 11.2 km/h / 7.0 mph from 225° (SW)\n"));
+
+        let daily = &info.weather_data.daily;
+        let list =
+            ClView::daily_list_item(&daily[1], 47.6, Precision::Tenths, NumberLocale::En, None);
+        assert!(list.contains(
+            &"  Max Wind Speed: 22.0 km/h / 13.7 mph, gusting to 44.0 km/h / 27.3 mph".to_string()
+        ));
+        let list =
+            ClView::daily_list_item(&daily[0], 47.6, Precision::Tenths, NumberLocale::En, None);
+        assert!(list
+            .iter()
+            .any(|line| line.starts_with("  Max Wind Speed:")));
+        assert!(!list.iter().any(|line| line.contains("gusting")));
+    }
+
+    #[test]
+    fn test_hourly_footer_ignores_past_days() {
+        let footer = |info: &WeatherInfo| {
+            ClView::render(info, &DisplayOptions::default())
+                .lines()
+                .find(|line| line.contains("avg "))
+                .map(str::to_string)
+        };
+        let mut info = sample_weather_info();
+        let expected = footer(&info);
+        assert!(expected.is_some());
+
+        // A hot day before the forecast, as with past days
+        let data = &mut info.weather_data;
+        let mut past: Vec<_> = data.hourly[..24].to_vec();
+        for hour in &mut past {
+            let time = chrono::NaiveDateTime::parse_from_str(&hour.time, "%Y-%m-%dT%H:%M").unwrap();
+            hour.time = (time - chrono::TimeDelta::days(1))
+                .format("%Y-%m-%dT%H:%M")
+                .to_string();
+            hour.temperature = Some(40.0);
+            hour.is_past = true;
+        }
+        data.hourly.splice(..0, past);
+        assert_eq!(footer(&info), expected);
+    }
+
+    #[test]
+    fn test_current_uv_index_line() {
+        let mut info = sample_weather_info();
+        let output = ClView::render(&info, &DisplayOptions::default());
+        assert!(output.contains("\nUV Index: 6 (High)\n"), "{output}");
+
+        for hour in &mut info.weather_data.hourly {
+            hour.uv_index = None;
+        }
+        let output = ClView::render(&info, &DisplayOptions::default());
+        assert!(!output.contains("\nUV Index:"));
+    }
+
+    #[test]
+    fn test_pressure_line_names_the_reference() {
+        let mut info = sample_weather_info();
+        let options = |pressure| DisplayOptions {
+            pressure,
+            ..Default::default()
+        };
+        let output = ClView::render(&info, &DisplayOptions::default());
+        assert!(output.contains("\nPressure (sea level): 1023.1 hPa / 30.21 inHg\n"));
+        let output = ClView::render(&info, &options(PressureReference::Surface));
+        assert!(output.contains("\nPressure (surface): 1016.3 hPa / 30.01 inHg\n"));
+
+        // Without the preferred one the other is shown, under its own name
+        info.weather_data.current.pressure_msl = None;
+        let output = ClView::render(&info, &options(PressureReference::SeaLevel));
+        assert!(
+            output.contains("\nPressure (surface): 1016.3 hPa"),
+            "{output}"
+        );
+    }
+
+    #[test]
+    fn test_header_names_a_pinned_model() {
+        let mut info = sample_weather_info();
+        let output = ClView::render(&info, &DisplayOptions::default());
+        assert!(output.starts_with("\n=== Weather Report ===\n"), "{output}");
+        info.model = ForecastModel::Ecmwf;
+        let output = ClView::render(&info, &DisplayOptions::default());
+        assert!(
+            output.starts_with("\n=== Weather Report (ECMWF) ===\n"),
+            "{output}"
+        );
+    }
+
+    #[test]
+    fn test_heads_up_names_the_chosen_standards_tier() {
+        let mut info = sample_weather_info();
+        let current = &mut info.weather_data.current;
+        current.temperature = Some(-25.0);
+        current.wind_speed = Some(30.0);
+        let options = |standard| DisplayOptions {
+            advisory_standard: standard,
+            ..Default::default()
+        };
+
+        let output = ClView::render(&info, &options(AdvisoryStandard::Us));
+        assert!(output.contains("--- Heads Up ---\nExtreme Cold Warning (wind chill -39°C)"));
+        let output = ClView::render(&info, &options(AdvisoryStandard::Eu));
+        assert!(output.contains("Extreme frost warning (temperature -25°C)"));
+        // Short of Canada's cutoff
+        let output = ClView::render(&info, &options(AdvisoryStandard::Canada));
+        assert!(!output.contains("warning ("));
+    }
+
+    #[test]
+    fn test_storm_outlook_starts_at_the_current_hour() {
+        let mut info = sample_weather_info();
+        let data = &mut info.weather_data;
+        data.current.time = Some("2025-07-01T15:00".to_string());
+        let start = NaiveDate::from_ymd_opt(2025, 7, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        data.hourly = (0..72)
+            .map(|hour| {
+                let time = start + chrono::TimeDelta::hours(hour);
+                let (code, cape) = match hour {
+                    // A morning storm, over by mid-afternoon
+                    9 | 10 => (95, 3500.0),
+                    18 => (3, 1000.0),
+                    // The last hour of the 48 from now
+                    62 => (3, 2000.0),
+                    _ => (3, 0.0),
+                };
+                HourlyForecast {
+                    time: time.format("%Y-%m-%dT%H:%M").to_string(),
+                    weather_code: Some(code),
+                    cape: Some(cape),
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        let output = ClView::render(&info, &all_sections());
+        assert!(
+            output.contains("Peak CAPE: 2000 J/kg (Moderate) at 2025-07-03 14:00"),
+            "{output}"
+        );
+        assert!(
+            !output.contains("thunderstorms 2025-07-01 09:00"),
+            "{output}"
+        );
+    }
+
+    #[test]
+    fn test_rate_limited_line() {
+        let retry_at = chrono::NaiveTime::from_hms_opt(14, 5, 40).unwrap();
+        assert_eq!(
+            ClView::rate_limited_line(&["Oslo", "Bergen"], retry_at),
+            "Rate limited by the server until 14:05; skipped Oslo, Bergen"
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_stop() {
+        let result: Result<WeatherInfo, _> = Err(WeatherError::RateLimited {
+            retry_after: std::time::Duration::from_secs(30),
+        });
+        let line = ClView::rate_limit_stop(&result, ["Oslo", "60.39, 5.32"].into_iter()).unwrap();
+        assert!(line.ends_with("; skipped Oslo, 60.39, 5.32"), "{line}");
+        assert_eq!(ClView::rate_limit_stop(&result, std::iter::empty()), None);
+        let throttled: Result<WeatherInfo, _> = Err(WeatherError::Throttled {
+            retry_after: std::time::Duration::from_millis(400),
+        });
+        assert_eq!(
+            ClView::rate_limit_stop(&throttled, ["Oslo"].into_iter()),
+            None
+        );
+        assert_eq!(
+            ClView::rate_limit_stop(&Ok(sample_weather_info()), ["Oslo"].into_iter()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_offline_line() {
+        let fetched_at = NaiveDate::from_ymd_opt(2024, 6, 1)
+            .unwrap()
+            .and_hms_opt(9, 41, 12)
+            .unwrap();
+        assert_eq!(
+            ClView::offline_line(Some(fetched_at)),
+            "No connection; showing cached data from 2024-06-01 09:41"
+        );
+        assert!(ClView::offline_line(None).ends_with("from an earlier run"));
+    }
+
+    #[test]
+    fn test_wind_rose_spokes() {
+        use crate::models::weather_info::HourlyForecast;
+
+        // Four hours from the west, two from the north-east, one without a direction
+        let hours: Vec<_> = [
+            Some(270.0),
+            Some(265.0),
+            Some(280.0),
+            Some(275.0),
+            Some(40.0),
+            Some(50.0),
+            None,
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(i, direction)| {
+            let hour = HourlyForecast::builder(format!("2024-06-01T{i:02}:00")).wind_speed(20.0);
+            match direction {
+                Some(d) => hour.wind_direction(d).build(),
+                None => hour.build(),
+            }
+        })
+        .collect();
+        let rose = WindRose::from_hours(&hours, WIND_ROSE_SPOKES);
+
+        assert_eq!(
+            ClView::wind_rose_lines(&rose, WindUnit::Knots),
+            vec![
+                "NW          N           NE",
+                "",
+                "",
+                "                  /",
+                "                /",
+                "              /",
+                "W ----------o           E",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "SW          S           SE",
+                "",
+                "From    Share   Mean wind",
+                "NE        33%       11 kn",
+                "W         67%       11 kn",
+                "Direction known for 86% of 7 hours",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sun_countdown_uses_location_clock() {
+        use crate::models::weather_info::DailyForecast;
+        use chrono::TimeZone;
+
+        let day = |date: &str| {
+            DailyForecast::builder(date)
+                .sunrise(format!("{date}T05:10"))
+                .sunset(format!("{date}T19:40"))
+                .build()
+        };
+        let info = WeatherInfo::builder("Sydney", -33.9, 151.2)
+            .utc_offset_seconds(11 * 3600)
+            .daily(vec![day("2024-06-07"), day("2024-06-08")])
+            .build();
+
+        // 14:30 UTC is 01:30 on the 8th at the location: next is that morning's sunrise
+        let now = Utc.with_ymd_and_hms(2024, 6, 7, 14, 30, 0).unwrap();
+        assert_eq!(
+            ClView::sun_countdown(&info, now).as_deref(),
+            Some("Sunrise in 3 h 40 min (05:10)")
+        );
+
+        let evening = Utc.with_ymd_and_hms(2024, 6, 8, 5, 0, 0).unwrap();
+        assert_eq!(
+            ClView::sun_countdown(&info, evening).as_deref(),
+            Some("Sunset in 3 h 40 min (19:40)")
+        );
+
+        let without_offset = WeatherInfo::builder("Sydney", -33.9, 151.2)
+            .daily(vec![day("2024-06-08")])
+            .build();
+        assert_eq!(ClView::sun_countdown(&without_offset, now), None);
+    }
+
+    #[test]
+    fn test_batch_lines() {
+        let locations = vec!["Seattle".to_string(), "Atlantis".to_string()];
+        let results = vec![
+            Ok(sample_weather_info()),
+            Err(WeatherError::location_not_found("Atlantis")),
+        ];
+
+        let lines = ClView::batch_lines(&locations, &results);
+        assert_eq!(lines[0], sample_weather_info().summary().to_string());
+        assert_eq!(lines[1], "Atlantis: Location 'Atlantis' not found");
+    }
+
+    #[test]
+    fn test_footer_flags_expired_forecast() {
+        let info = sample_weather_info();
+        let now = Utc::now();
+        // Hand-built data has no fetch time, so "now" is the observation time
+        assert_eq!(
+            ClView::footer_lines(&info, now),
+            vec!["Forecast data as of 2024-06-01 00:00 model run".to_string()]
+        );
+
+        let mut stale = info.clone();
+        stale.weather_data.current.time = Some("2024-06-04T05:15".to_string());
+        assert_eq!(
+            ClView::footer_lines(&stale, now)[1],
+            "Warning: Forecast ended 30 h ago; this data is very stale"
+        );
+    }
+
+    #[test]
+    fn test_footer_names_the_provider_that_answered() {
+        let mut info = sample_weather_info();
+        info.source = Some("Met.no".to_string());
+        assert_eq!(
+            ClView::footer_lines(&info, Utc::now()),
+            vec![
+                "Forecast by Met.no".to_string(),
+                "Forecast data as of 2024-06-01 00:00 model run".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_saved_forecasts() {
+        use crate::repositories::file_weather_repository::FileWeatherRepository;
+        use crate::repositories::weather_repository::WeatherRepository;
+
+        let render = |name: &str, location: &str| {
+            let path = format!(
+                "{}/tests/fixtures/{name}/forecast.json",
+                env!("CARGO_MANIFEST_DIR")
+            );
+            let info = FileWeatherRepository::new(path)
+                .fetch_weather(location)
+                .unwrap();
+            ClView::render(&info, &all_sections())
+        };
+
+        let report = render("sunny", "Seville");
+        assert!(report.contains("Location: Seville (CEST)"), "{report}");
+        assert!(report.contains("Weather Code: 0 (Clear sky)"), "{report}");
+        assert!(
+            report.contains("2025-06-16    20.0 / 35.0°C  Clear sky"),
+            "{report}"
+        );
+        assert!(report.contains("Water equivalent needed this week: ~50 mm"));
+
+        let report = render("rainy", "Bergen");
+        assert!(report.contains("Weather Code: 65 (Rain)"), "{report}");
+        assert!(report.contains("Next 24 h: 39.0 mm"), "{report}");
+        assert!(report.contains("Water equivalent needed this week: ~0 mm"));
+
+        let report = render("snowy", "Innsbruck");
+        assert!(report.contains("Temperature: -5.2°C / 22.6°F"), "{report}");
+        assert!(
+            report.contains("Next 24 h: 19.2 mm, 13.4 cm snow"),
+            "{report}"
+        );
+        assert!(report.contains("Snow depth: 40 cm / 16 in"), "{report}");
+        assert!(
+            report.contains("2026-01-12    -9.0 / -3.0°C  Snow"),
+            "{report}"
+        );
+    }
+
+    #[test]
+    fn test_number_locale_reaches_snow_garden_and_log_summary() {
+        use crate::repositories::file_weather_repository::FileWeatherRepository;
+        use crate::repositories::weather_repository::WeatherRepository;
+
+        let german = DisplayOptions {
+            number_locale: NumberLocale::De,
+            ..all_sections()
+        };
+        let render = |name: &str| {
+            let path = format!(
+                "{}/tests/fixtures/{name}/forecast.json",
+                env!("CARGO_MANIFEST_DIR")
+            );
+            let info = FileWeatherRepository::new(path)
+                .fetch_weather(name)
+                .unwrap();
+            ClView::render(&info, &german)
+        };
+        let report = render("snowy");
+        assert!(
+            report.contains("Snowfall: ~20 cm / 8,0 in expected by"),
+            "{report}"
+        );
+        assert!(
+            report.contains("12.01.         0,1 mm  19,2 mm   0,0 mm"),
+            "{report}"
+        );
+        assert!(report.contains("~0 mm / 0,0 in"), "{report}");
+
+        let summary = WeatherLogSummary {
+            records: 3,
+            skipped: 0,
+            temperature_min: Some(-1.5),
+            temperature_max: Some(4.2),
+            temperature_mean: Some(1.0),
+            precipitation_total: Some(3.5),
+        };
+        assert_eq!(
+            ClView::log_summary_lines(&summary, &german),
+            [
+                "Records:        3",
+                "Temperature:    -1,5 °C to 4,2 °C",
+                "Mean:           1,0 °C",
+                "Precipitation:  3,5 mm (sum of logged hours)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_garden_watering_covers_a_week_of_a_long_forecast() {
+        use crate::repositories::weather_repository::ApiWeatherRepository;
+
+        // The daily half of the split 16-day fixture, with the current
+        // conditions of its hourly half
+        let fixture = |file: &str| -> serde_json::Value {
+            let path = format!(
+                "{}/tests/fixtures/london_16_days/{file}",
+                env!("CARGO_MANIFEST_DIR")
+            );
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+        };
+        let mut forecast = fixture("forecast.json");
+        let hourly = fixture("forecast_hourly.json");
+        forecast["current"] = hourly["current"].clone();
+        forecast["current_units"] = hourly["current_units"].clone();
+        let mut info =
+            ApiWeatherRepository::parse_weather_info("London", 51.5, -0.13, &forecast.to_string())
+                .unwrap();
+        assert_eq!(info.weather_data.daily_from_today().len(), 16);
+        let garden = |info: &WeatherInfo| {
+            let mut out = Vec::new();
+            ClView::render_garden_watering(
+                &mut out,
+                info.weather_data.daily_from_today(),
+                &all_sections(),
+            );
+            out
+        };
+
+        // Seven of the sixteen days, and what they need
+        let lines = garden(&info);
+        assert_eq!(lines.len(), 2 + 7 + 1, "{lines:#?}");
+        assert!(lines[8].starts_with("2025-12-16 "), "{lines:#?}");
+        assert_eq!(
+            lines[9],
+            "Water equivalent needed this week: ~2 mm / 0.1 in"
+        );
+
+        // A shorter forecast says how many days it covers
+        info.weather_data.daily.truncate(3);
+        let lines = garden(&info);
+        assert_eq!(lines.len(), 2 + 3 + 1, "{lines:#?}");
+        assert_eq!(
+            lines[5],
+            "Water equivalent needed over the next 3 days: ~1 mm / 0.1 in"
+        );
+    }
+
+    #[test]
+    fn test_favorite_line() {
+        let mut favorites = crate::models::favorites::Favorites::default();
+        favorites.save("Seattle", Some("Home".to_string()), None);
+        let home = &favorites.entries()[0];
+
+        assert_eq!(
+            ClView::favorite_line(home, &Ok(sample_weather_info())),
+            "Home (Seattle): 19°C ⛅"
+        );
+        assert_eq!(
+            ClView::favorite_line(home, &Ok(empty_weather_info())),
+            "Home (Seattle): —"
+        );
+
+        let today = chrono::NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+        let place = |name: &str, latitude| Some(ResolvedPlace::new(name, latitude, -122.3));
+        let outcome = favorites
+            .apply_refresh("Seattle", place("Seattle, United States", 47.6), today)
+            .unwrap();
+        let home = &favorites.entries()[0];
+        assert_eq!(
+            ClView::refresh_line(home, &outcome).unwrap(),
+            "Seattle is now Home (Seattle, United States)"
+        );
+        favorites.apply_refresh("Seattle", place("Tacoma, United States", 47.25), today);
+        assert_eq!(
+            ClView::favorite_line(&favorites.entries()[0], &Ok(empty_weather_info())),
+            "Home (Seattle, United States): — (now geocoded to Tacoma, United States; see --accept-moves)"
+        );
+    }
+
+    #[test]
+    fn test_digest_lines() {
+        use crate::utils::digest::{DigestEntry, Urgency};
+
+        let mut wet = DigestEntry::assess(
+            "Home (Seattle)",
+            &sample_weather_info(),
+            Utc::now(),
+            AdvisoryStandard::Us,
+        );
+        wet.urgency = Urgency::Wet;
+        wet.reason = Some("precipitation 80% today".to_string());
+        wet.callout = Some("Frost: low -1°C".to_string());
+        let failures = vec![(
+            "Atlantis".to_string(),
+            WeatherError::location_not_found("Atlantis"),
+        )];
+
+        let lines = ClView::digest_lines(&[wet], &failures);
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("Home (Seattle): 19°C"));
+        assert!(lines[0].ends_with(" [precipitation 80% today]"));
+        assert_eq!(lines[1], "  ⚠ Frost: low -1°C");
+        assert!(lines[2].starts_with("Atlantis: "));
+    }
+
+    #[test]
+    fn test_minutely_heading_follows_the_steps() {
+        let steps = |times: &[&str]| -> Vec<MinutelyForecast> {
+            times
+                .iter()
+                .map(|time| MinutelyForecast::new(format!("2024-06-01T{time}")))
+                .collect()
+        };
+        let two_hours = steps(&[
+            "10:15", "10:30", "10:45", "11:00", "11:15", "11:30", "11:45", "12:00",
+        ]);
+        assert_eq!(
+            ClView::minutely_heading(&two_hours),
+            "Next 2 Hours (Every 15 Minutes)"
+        );
+        assert_eq!(
+            ClView::minutely_heading(&two_hours[..3]),
+            "Next 45 Minutes (Every 15 Minutes)"
+        );
+        assert_eq!(
+            ClView::minutely_heading(&steps(&["10:10", "10:20", "10:30"])),
+            "Next 30 Minutes (Every 10 Minutes)"
+        );
+        // A single step has no spacing to measure
+        assert_eq!(
+            ClView::minutely_heading(&two_hours[..1]),
+            "Next 15 Minutes (Every 15 Minutes)"
+        );
     }
 }
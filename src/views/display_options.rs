@@ -0,0 +1,199 @@
+//! Presentation options shared by the console and GUI views
+//!
+//! Built once from the command line (or the GUI's saved settings) and passed
+//! to every renderer: the text report, one-line summary, JSON and CSV.
+
+use serde::{Deserialize, Serialize};
+
+use crate::repositories::data_selection::DataSelection;
+use crate::utils::advice::FeelsLikeFormula;
+use crate::utils::advisories::AdvisoryStandard;
+use crate::utils::conversions::{PressureReference, WindUnit};
+use crate::utils::format::{NumberLocale, Precision};
+use crate::utils::terminal::PIPED_OUTPUT_WIDTH;
+use crate::utils::uv::SkinType;
+use crate::views::export::{CsvDialect, OutputFormat, UnitSystem};
+
+/// Options controlling which optional sections the views render and how
+/// values are written
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct DisplayOptions {
+    /// Always show mountain data (freezing level, snow line) regardless of elevation
+    pub mountain: bool,
+    /// Show the storm-energy (CAPE) outlook for the next 48 hours
+    pub storm: bool,
+    /// Show the irrigation deficit (evapotranspiration minus rain) per day
+    pub garden: bool,
+    /// Report skipped sections and other diagnostics on stderr
+    pub verbose: bool,
+    /// Print a single summary line instead of the full report
+    pub oneline: bool,
+    /// Heat-stress index for the feels-like fallback and heat advisories
+    pub feels_like_formula: FeelsLikeFormula,
+    /// Weather service whose cold and heat tiers the heads-up and banners use
+    pub advisory_standard: AdvisoryStandard,
+    /// Text report, JSON or CSV
+    pub format: OutputFormat,
+    /// Units for JSON and CSV output (the text report always shows metric and imperial)
+    pub units: UnitSystem,
+    /// Add imperial and mmHg conversions (e.g. `pressure_msl_inhg`) to JSON output
+    pub all_units: bool,
+    /// Columns available for the text report (100 when unset)
+    pub width: Option<usize>,
+    /// Show wind at 10 m, 80 m and 120 m for the next hours (drone and aviation use)
+    pub winds_aloft: bool,
+    /// Show the next two hours in 15-minute steps (requests the minutely
+    /// forecast as well)
+    pub minutely: bool,
+    /// Show waves for the next hours (requests the marine API as well)
+    pub marine: bool,
+    /// Rate each day's confidence from the ensemble spread (requests the
+    /// ensemble API as well)
+    pub confidence: bool,
+    /// Show pollutants and AQI with the current conditions (requests the air
+    /// quality API as well)
+    pub air_quality: bool,
+    /// Show how often the wind blows from each direction over the forecast
+    pub wind_rose: bool,
+    /// Unit for wind speeds in the winds-aloft table and tooltips
+    pub wind_unit: WindUnit,
+    /// Pressure shown with the current conditions: sea level or surface
+    pub pressure: PressureReference,
+    /// Temperature precision; unset uses one decimal, except whole degrees for the GUI's headline temperature
+    pub precision: Option<Precision>,
+    /// Decimal separator and date order of the text report
+    pub number_locale: NumberLocale,
+    /// Delimiter and decimal separator of CSV output
+    pub csv_dialect: CsvDialect,
+    /// Adds the time to sunburn to UV values; unset hides it
+    pub skin_type: Option<SkinType>,
+}
+
+/// Generates consuming setters, one per field
+macro_rules! setters {
+    ($($field:ident: $ty:ty),* $(,)?) => {
+        $(
+            pub fn $field(mut self, value: impl Into<$ty>) -> Self {
+                self.0.$field = value.into();
+                self
+            }
+        )*
+    };
+}
+
+/// Builder returned by `DisplayOptions::builder()`, starting from the defaults
+#[derive(Clone, Debug, Default)]
+pub struct DisplayOptionsBuilder(DisplayOptions);
+
+impl DisplayOptionsBuilder {
+    setters! {
+        mountain: bool,
+        storm: bool,
+        garden: bool,
+        verbose: bool,
+        oneline: bool,
+        feels_like_formula: FeelsLikeFormula,
+        advisory_standard: AdvisoryStandard,
+        format: OutputFormat,
+        units: UnitSystem,
+        all_units: bool,
+        width: Option<usize>,
+        winds_aloft: bool,
+        minutely: bool,
+        marine: bool,
+        confidence: bool,
+        air_quality: bool,
+        wind_rose: bool,
+        wind_unit: WindUnit,
+        pressure: PressureReference,
+        precision: Option<Precision>,
+        number_locale: NumberLocale,
+        csv_dialect: CsvDialect,
+        skin_type: Option<SkinType>,
+    }
+
+    pub fn build(self) -> DisplayOptions {
+        self.0
+    }
+}
+
+impl DisplayOptions {
+    pub fn builder() -> DisplayOptionsBuilder {
+        DisplayOptionsBuilder::default()
+    }
+
+    /// Columns available for the text report
+    pub fn width(&self) -> usize {
+        self.width.unwrap_or(PIPED_OUTPUT_WIDTH)
+    }
+
+    /// Temperature precision for everything but the GUI's headline temperature
+    pub fn precision(&self) -> Precision {
+        self.precision.unwrap_or(Precision::Tenths)
+    }
+
+    /// Forecast variables needed to render with these options
+    pub fn data_selection(&self) -> DataSelection {
+        if self.oneline {
+            return DataSelection::compact();
+        }
+        let selection = if self.winds_aloft {
+            DataSelection::full().with_aviation()
+        } else {
+            DataSelection::full()
+        };
+        let selection = if self.minutely {
+            selection.with_minutely()
+        } else {
+            selection
+        };
+        let selection = if self.marine {
+            selection.with_marine()
+        } else {
+            selection
+        };
+        let selection = if self.confidence {
+            selection.with_confidence()
+        } else {
+            selection
+        };
+        if self.air_quality {
+            selection.with_air_quality()
+        } else {
+            selection
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_and_serialization() {
+        let options = DisplayOptions::builder()
+            .garden(true)
+            .format(OutputFormat::DailyCsv)
+            .width(72)
+            .precision(Precision::Whole)
+            .wind_unit(WindUnit::Knots)
+            .build();
+        assert!(options.garden && !options.storm);
+        assert_eq!(options.width(), 72);
+
+        let json = serde_json::to_value(&options).unwrap();
+        assert_eq!(json["format"], "daily-csv");
+        assert_eq!(json["precision"], "whole");
+        assert_eq!(json["wind_unit"], "knots");
+        assert_eq!(
+            serde_json::from_value::<DisplayOptions>(json).unwrap(),
+            options
+        );
+
+        // Options saved by an older version keep the defaults for the rest
+        let saved: DisplayOptions = serde_json::from_str(r#"{"mountain": true}"#).unwrap();
+        assert_eq!(saved, DisplayOptions::builder().mountain(true).build());
+    }
+}
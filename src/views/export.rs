@@ -0,0 +1,861 @@
+//! Machine-readable JSON and CSV output
+//!
+//! Metric output uses the model's field names and units, except that pressures
+//! name their level and unit (`pressure_surface_hpa`, `pressure_msl_hpa`). SI
+//! output converts every quantity to pure SI and suffixes field names with the
+//! unit (`temperature_k`, `wind_speed_ms`, `pressure_msl_pa`,
+//! `precipitation_kg_m2`). With all units, JSON also carries imperial and
+//! medical pressure readings (`pressure_msl_inhg`, `pressure_msl_mmhg`).
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::models::weather_info::{CurrentWeather, DailyForecast, HourlyForecast, WeatherInfo};
+use crate::utils::conversions::{Distance, Pressure, Speed, Temperature};
+use crate::views::DisplayOptions;
+
+/// Output format for the weather report
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    /// Human-readable report
+    #[default]
+    Text,
+    /// Full report as JSON
+    Json,
+    /// Hourly forecast as CSV
+    Csv,
+    /// Daily forecast as CSV
+    DailyCsv,
+}
+
+/// Unit system for JSON and CSV output
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UnitSystem {
+    /// Units as stored in the models (°C, km/h, hPa, mm)
+    #[default]
+    Metric,
+    /// Pure SI (K, m/s, Pa, kg/m²) with unit-suffixed field names
+    Si,
+}
+
+/// Delimiter and decimal separator of CSV output
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CsvDialect {
+    /// Comma-separated with a decimal point (RFC 4180)
+    #[default]
+    Standard,
+    /// Semicolon-separated with a decimal comma, as German Excel expects
+    ExcelDe,
+}
+
+impl CsvDialect {
+    fn delimiter(self) -> char {
+        match self {
+            CsvDialect::Standard => ',',
+            CsvDialect::ExcelDe => ';',
+        }
+    }
+
+    fn number(self, value: &Value) -> String {
+        match self {
+            CsvDialect::Standard => value.to_string(),
+            CsvDialect::ExcelDe => value.to_string().replace('.', ","),
+        }
+    }
+}
+
+impl fmt::Display for CsvDialect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CsvDialect::Standard => "standard",
+            CsvDialect::ExcelDe => "excel-de",
+        })
+    }
+}
+
+impl FromStr for CsvDialect {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "standard" => Ok(CsvDialect::Standard),
+            "excel-de" => Ok(CsvDialect::ExcelDe),
+            other => Err(format!(
+                "unknown CSV dialect '{other}' (expected standard or excel-de)"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Json => "json",
+            OutputFormat::Csv => "csv",
+            OutputFormat::DailyCsv => "daily-csv",
+        })
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            "daily-csv" => Ok(OutputFormat::DailyCsv),
+            other => Err(format!(
+                "unknown output format '{other}' (expected text, json, csv or daily-csv)"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for UnitSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            UnitSystem::Metric => "metric",
+            UnitSystem::Si => "si",
+        })
+    }
+}
+
+impl FromStr for UnitSystem {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "metric" => Ok(UnitSystem::Metric),
+            "si" => Ok(UnitSystem::Si),
+            other => Err(format!(
+                "unknown unit system '{other}' (expected metric or si)"
+            )),
+        }
+    }
+}
+
+/// Physical quantity of an exported field, which decides its unit and conversion
+#[derive(Clone, Copy, Debug)]
+enum Quantity {
+    Temperature,
+    Speed,
+    /// Pressure (hPa), converted with the unit system
+    Pressure,
+    /// Pressure in inches of mercury, whatever the unit system
+    PressureInHg,
+    /// Pressure in millimeters of mercury, whatever the unit system
+    PressureMmHg,
+    /// Liquid water depth (mm)
+    Precipitation,
+    /// Snow depth (cm)
+    Snowfall,
+    Length,
+    Percent,
+    Degrees,
+    SpecificEnergy,
+    UvIndex,
+    WeatherCode,
+    /// Count of hours
+    Hours,
+    /// Time span in seconds
+    Duration,
+}
+
+impl Quantity {
+    /// Field-name suffix (SI only) and unit label
+    fn unit(self, units: UnitSystem) -> (&'static str, &'static str) {
+        match (self, units) {
+            (Quantity::Temperature, UnitSystem::Metric) => ("", "°C"),
+            (Quantity::Temperature, UnitSystem::Si) => ("k", "K"),
+            (Quantity::Speed, UnitSystem::Metric) => ("", "km/h"),
+            (Quantity::Speed, UnitSystem::Si) => ("ms", "m/s"),
+            (Quantity::Pressure, UnitSystem::Metric) => ("hpa", "hPa"),
+            (Quantity::Pressure, UnitSystem::Si) => ("pa", "Pa"),
+            (Quantity::PressureInHg, _) => ("inhg", "inHg"),
+            (Quantity::PressureMmHg, _) => ("mmhg", "mmHg"),
+            (Quantity::Precipitation, UnitSystem::Metric) => ("", "mm"),
+            (Quantity::Precipitation, UnitSystem::Si) => ("kg_m2", "kg/m²"),
+            (Quantity::Snowfall, UnitSystem::Metric) => ("", "cm"),
+            (Quantity::Snowfall, UnitSystem::Si) => ("m", "m"),
+            (Quantity::Length, UnitSystem::Metric) => ("", "m"),
+            (Quantity::Length, UnitSystem::Si) => ("m", "m"),
+            (Quantity::Percent, UnitSystem::Metric) => ("", "%"),
+            (Quantity::Percent, UnitSystem::Si) => ("pct", "%"),
+            (Quantity::Degrees, UnitSystem::Metric) => ("", "°"),
+            (Quantity::Degrees, UnitSystem::Si) => ("deg", "°"),
+            (Quantity::SpecificEnergy, UnitSystem::Metric) => ("", "J/kg"),
+            (Quantity::SpecificEnergy, UnitSystem::Si) => ("j_kg", "J/kg"),
+            (Quantity::UvIndex, _) => ("", "UV index"),
+            (Quantity::WeatherCode, _) => ("", "WMO code"),
+            (Quantity::Hours, _) => ("", "h"),
+            (Quantity::Duration, UnitSystem::Metric) => ("", "s"),
+            (Quantity::Duration, UnitSystem::Si) => ("s", "s"),
+        }
+    }
+
+    fn convert(self, value: f64, units: UnitSystem) -> f64 {
+        match self {
+            Quantity::PressureInHg => return Pressure::hpa_to_inhg(value),
+            Quantity::PressureMmHg => return Pressure::hpa_to_mmhg(value),
+            _ if units == UnitSystem::Metric => return value,
+            _ => {}
+        }
+        match self {
+            Quantity::Temperature => Temperature::celsius_to_kelvin(value),
+            Quantity::Speed => Speed::kmh_to_ms(value),
+            Quantity::Pressure => Pressure::hpa_to_pa(value),
+            Quantity::Precipitation => Distance::mm_to_kg_per_m2(value),
+            Quantity::Snowfall => Distance::cm_to_meters(value),
+            _ => value,
+        }
+    }
+
+    fn to_json(self, value: f64, units: UnitSystem) -> Value {
+        match self {
+            Quantity::WeatherCode | Quantity::Hours => json!(value as i64),
+            _ => json!(self.convert(value, units)),
+        }
+    }
+}
+
+/// Numeric field of a model type
+struct Field<T> {
+    name: &'static str,
+    quantity: Quantity,
+    value: fn(&T) -> Option<f64>,
+}
+
+impl<T> Field<T> {
+    fn key(&self, units: UnitSystem) -> String {
+        match self.quantity.unit(units).0 {
+            "" => self.name.to_string(),
+            suffix => format!("{}_{suffix}", self.name),
+        }
+    }
+}
+
+const CURRENT_FIELDS: &[Field<CurrentWeather>] = &[
+    Field {
+        name: "temperature",
+        quantity: Quantity::Temperature,
+        value: |c| c.temperature,
+    },
+    Field {
+        name: "apparent_temperature",
+        quantity: Quantity::Temperature,
+        value: |c| c.apparent_temperature,
+    },
+    Field {
+        name: "humidity",
+        quantity: Quantity::Percent,
+        value: |c| c.humidity,
+    },
+    Field {
+        name: "dew_point",
+        quantity: Quantity::Temperature,
+        value: |c| c.dew_point,
+    },
+    Field {
+        name: "precipitation",
+        quantity: Quantity::Precipitation,
+        value: |c| c.precipitation,
+    },
+    Field {
+        name: "snowfall",
+        quantity: Quantity::Snowfall,
+        value: |c| c.snowfall,
+    },
+    Field {
+        name: "weather_code",
+        quantity: Quantity::WeatherCode,
+        value: |c| c.weather_code.map(f64::from),
+    },
+    Field {
+        name: "wind_speed",
+        quantity: Quantity::Speed,
+        value: |c| c.wind_speed,
+    },
+    Field {
+        name: "wind_direction",
+        quantity: Quantity::Degrees,
+        value: |c| c.wind_direction,
+    },
+    Field {
+        name: "wind_gusts",
+        quantity: Quantity::Speed,
+        value: |c| c.wind_gusts,
+    },
+    Field {
+        name: "cloud_cover",
+        quantity: Quantity::Percent,
+        value: |c| c.cloud_cover,
+    },
+    Field {
+        name: "pressure_surface",
+        quantity: Quantity::Pressure,
+        value: |c| c.pressure,
+    },
+    Field {
+        name: "pressure_msl",
+        quantity: Quantity::Pressure,
+        value: |c| c.pressure_msl,
+    },
+    Field {
+        name: "visibility",
+        quantity: Quantity::Length,
+        value: |c| c.visibility,
+    },
+    Field {
+        name: "snow_depth",
+        quantity: Quantity::Length,
+        value: |c| c.snow_depth,
+    },
+];
+
+/// Converted current-condition fields added to JSON with all units
+const ALL_UNITS_CURRENT_FIELDS: &[Field<CurrentWeather>] = &[
+    Field {
+        name: "pressure_msl",
+        quantity: Quantity::PressureInHg,
+        value: |c| c.pressure_msl,
+    },
+    Field {
+        name: "pressure_msl",
+        quantity: Quantity::PressureMmHg,
+        value: |c| c.pressure_msl,
+    },
+];
+
+/// Current-condition fields exported for a unit system
+fn current_fields(all_units: bool) -> impl Iterator<Item = &'static Field<CurrentWeather>> {
+    let extra = if all_units {
+        ALL_UNITS_CURRENT_FIELDS
+    } else {
+        &[]
+    };
+    CURRENT_FIELDS.iter().chain(extra)
+}
+
+const HOURLY_FIELDS: &[Field<HourlyForecast>] = &[
+    Field {
+        name: "temperature",
+        quantity: Quantity::Temperature,
+        value: |h| h.temperature,
+    },
+    Field {
+        name: "apparent_temperature",
+        quantity: Quantity::Temperature,
+        value: |h| h.apparent_temperature,
+    },
+    Field {
+        name: "precipitation_probability",
+        quantity: Quantity::Percent,
+        value: |h| h.precipitation_probability,
+    },
+    Field {
+        name: "precipitation",
+        quantity: Quantity::Precipitation,
+        value: |h| h.precipitation,
+    },
+    Field {
+        name: "weather_code",
+        quantity: Quantity::WeatherCode,
+        value: |h| h.weather_code.map(f64::from),
+    },
+    Field {
+        name: "wind_speed",
+        quantity: Quantity::Speed,
+        value: |h| h.wind_speed,
+    },
+    Field {
+        name: "wind_direction",
+        quantity: Quantity::Degrees,
+        value: |h| h.wind_direction,
+    },
+    Field {
+        name: "wind_gusts",
+        quantity: Quantity::Speed,
+        value: |h| h.wind_gusts,
+    },
+    Field {
+        name: "humidity",
+        quantity: Quantity::Percent,
+        value: |h| h.humidity,
+    },
+    Field {
+        name: "dew_point",
+        quantity: Quantity::Temperature,
+        value: |h| h.dew_point,
+    },
+    Field {
+        name: "freezing_level_height",
+        quantity: Quantity::Length,
+        value: |h| h.freezing_level_height,
+    },
+    Field {
+        name: "cape",
+        quantity: Quantity::SpecificEnergy,
+        value: |h| h.cape,
+    },
+    Field {
+        name: "snowfall",
+        quantity: Quantity::Snowfall,
+        value: |h| h.snowfall,
+    },
+    Field {
+        name: "snow_depth",
+        quantity: Quantity::Length,
+        value: |h| h.snow_depth,
+    },
+    Field {
+        name: "cloud_cover",
+        quantity: Quantity::Percent,
+        value: |h| h.cloud_cover,
+    },
+    Field {
+        name: "visibility",
+        quantity: Quantity::Length,
+        value: |h| h.visibility,
+    },
+    Field {
+        name: "uv_index",
+        quantity: Quantity::UvIndex,
+        value: |h| h.uv_index,
+    },
+];
+
+const DAILY_FIELDS: &[Field<DailyForecast>] = &[
+    Field {
+        name: "temperature_max",
+        quantity: Quantity::Temperature,
+        value: |d| d.temperature_max,
+    },
+    Field {
+        name: "temperature_min",
+        quantity: Quantity::Temperature,
+        value: |d| d.temperature_min,
+    },
+    Field {
+        name: "weather_code",
+        quantity: Quantity::WeatherCode,
+        value: |d| d.weather_code.map(f64::from),
+    },
+    Field {
+        name: "precipitation_sum",
+        quantity: Quantity::Precipitation,
+        value: |d| d.precipitation_sum,
+    },
+    Field {
+        name: "snowfall_sum",
+        quantity: Quantity::Snowfall,
+        value: |d| d.snowfall_sum,
+    },
+    Field {
+        name: "precipitation_probability_max",
+        quantity: Quantity::Percent,
+        value: |d| d.precipitation_probability,
+    },
+    Field {
+        name: "precipitation_probability_mean",
+        quantity: Quantity::Percent,
+        value: |d| d.precipitation_probability_mean,
+    },
+    Field {
+        name: "wet_hours",
+        quantity: Quantity::Hours,
+        value: |d| d.wet_hours.map(f64::from),
+    },
+    Field {
+        name: "wind_speed_max",
+        quantity: Quantity::Speed,
+        value: |d| d.wind_speed_max,
+    },
+    Field {
+        name: "wind_gusts_max",
+        quantity: Quantity::Speed,
+        value: |d| d.wind_gusts_max,
+    },
+    Field {
+        name: "daylight_duration",
+        quantity: Quantity::Duration,
+        value: |d| d.daylight_duration,
+    },
+    Field {
+        name: "sunshine_duration",
+        quantity: Quantity::Duration,
+        value: |d| d.sunshine_duration,
+    },
+    Field {
+        name: "et0_evapotranspiration",
+        quantity: Quantity::Precipitation,
+        value: |d| d.et0_evapotranspiration,
+    },
+    Field {
+        name: "humidity_mean",
+        quantity: Quantity::Percent,
+        value: |d| d.humidity_mean,
+    },
+    Field {
+        name: "uv_index_max",
+        quantity: Quantity::UvIndex,
+        value: |d| d.uv_index_max,
+    },
+];
+
+const ELEVATION: Field<WeatherInfo> = Field {
+    name: "elevation",
+    quantity: Quantity::Length,
+    value: |i| i.elevation,
+};
+
+/// Adds every field of `item` to `object` (missing values become `null`)
+fn insert_fields<'a, T: 'a>(
+    object: &mut Map<String, Value>,
+    fields: impl IntoIterator<Item = &'a Field<T>>,
+    item: &T,
+    units: UnitSystem,
+) {
+    for field in fields {
+        let value = (field.value)(item).map_or(Value::Null, |v| field.quantity.to_json(v, units));
+        object.insert(field.key(units), value);
+    }
+}
+
+/// Full report as JSON in `options.units`; `options.all_units` adds the
+/// imperial and mmHg pressure fields
+pub fn to_json(info: &WeatherInfo, options: &DisplayOptions) -> Value {
+    let (units, all_units) = (options.units, options.all_units);
+    let data = &info.weather_data;
+
+    let mut root = Map::new();
+    root.insert("units".to_string(), json!(units.to_string()));
+    root.insert("location".to_string(), json!(info.location));
+    if let Some(query) = &info.query {
+        root.insert("query".to_string(), json!(query));
+    }
+    // Every time below is local to this zone
+    if let Some(timezone) = &info.timezone {
+        root.insert("timezone".to_string(), json!(timezone));
+    }
+    if let Some(abbreviation) = &info.timezone_abbreviation {
+        root.insert("timezone_abbreviation".to_string(), json!(abbreviation));
+    }
+    root.insert("latitude".to_string(), json!(info.latitude));
+    root.insert("longitude".to_string(), json!(info.longitude));
+    insert_fields(&mut root, [&ELEVATION], info, units);
+
+    let mut current = Map::new();
+    current.insert("time".to_string(), json!(data.current.time));
+    insert_fields(
+        &mut current,
+        current_fields(all_units),
+        &data.current,
+        units,
+    );
+    current.insert("is_day".to_string(), json!(data.current.is_day));
+    root.insert("current".to_string(), Value::Object(current));
+
+    let hourly = data
+        .hourly
+        .iter()
+        .map(|hour| {
+            let mut object = Map::new();
+            object.insert("time".to_string(), json!(hour.time));
+            insert_fields(&mut object, HOURLY_FIELDS, hour, units);
+            object.insert("is_past".to_string(), json!(hour.is_past));
+            Value::Object(object)
+        })
+        .collect();
+    root.insert("hourly".to_string(), Value::Array(hourly));
+
+    let daily = data
+        .daily
+        .iter()
+        .map(|day| {
+            let mut object = Map::new();
+            object.insert("date".to_string(), json!(day.date));
+            insert_fields(&mut object, DAILY_FIELDS, day, units);
+            object.insert("sunrise".to_string(), json!(day.sunrise));
+            object.insert("sunset".to_string(), json!(day.sunset));
+            object.insert("is_past".to_string(), json!(day.is_past));
+            Value::Object(object)
+        })
+        .collect();
+    root.insert("daily".to_string(), Value::Array(daily));
+
+    Value::Object(root)
+}
+
+/// Hourly forecast as CSV in `options.units` and `options.csv_dialect`, with
+/// a header row; missing values are empty cells
+pub fn hourly_csv(info: &WeatherInfo, options: &DisplayOptions) -> String {
+    to_csv(
+        "time",
+        &info.weather_data.hourly,
+        |h| &h.time,
+        HOURLY_FIELDS,
+        options.units,
+        options.csv_dialect,
+    )
+}
+
+/// Daily forecast as CSV in `options.units` and `options.csv_dialect`, with
+/// a header row; missing values are empty cells
+pub fn daily_csv(info: &WeatherInfo, options: &DisplayOptions) -> String {
+    to_csv(
+        "date",
+        &info.weather_data.daily,
+        |d| &d.date,
+        DAILY_FIELDS,
+        options.units,
+        options.csv_dialect,
+    )
+}
+
+/// CSV with a leading key column followed by one column per field
+fn to_csv<T>(
+    key: &str,
+    rows: &[T],
+    row_key: fn(&T) -> &str,
+    fields: &[Field<T>],
+    units: UnitSystem,
+    dialect: CsvDialect,
+) -> String {
+    let mut csv = String::from(key);
+    for field in fields {
+        csv.push(dialect.delimiter());
+        csv.push_str(&field.key(units));
+    }
+    csv.push('\n');
+
+    for row in rows {
+        csv.push_str(row_key(row));
+        for field in fields {
+            csv.push(dialect.delimiter());
+            if let Some(value) = (field.value)(row) {
+                csv.push_str(&dialect.number(&field.quantity.to_json(value, units)));
+            }
+        }
+        csv.push('\n');
+    }
+
+    csv
+}
+
+/// Field names and units of the JSON and CSV output for `options`
+pub fn schema(options: &DisplayOptions) -> Value {
+    let (units, all_units) = (options.units, options.all_units);
+    fn section<'a, T: 'a>(
+        fields: impl IntoIterator<Item = &'a Field<T>>,
+        units: UnitSystem,
+    ) -> Value {
+        let object: Map<String, Value> = fields
+            .into_iter()
+            .map(|f| (f.key(units), json!(f.quantity.unit(units).1)))
+            .collect();
+        Value::Object(object)
+    }
+
+    json!({
+        "units": units.to_string(),
+        ELEVATION.key(units): ELEVATION.quantity.unit(units).1,
+        "current": section(current_fields(all_units), units),
+        "hourly": section(HOURLY_FIELDS, units),
+        "daily": section(DAILY_FIELDS, units),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::sample_weather_info;
+
+    fn options(units: UnitSystem, all_units: bool) -> DisplayOptions {
+        DisplayOptions::builder()
+            .units(units)
+            .all_units(all_units)
+            .build()
+    }
+
+    fn csv_options(units: UnitSystem, dialect: CsvDialect) -> DisplayOptions {
+        DisplayOptions::builder()
+            .units(units)
+            .csv_dialect(dialect)
+            .build()
+    }
+
+    #[test]
+    fn test_si_json_converts_values_and_renames_keys() {
+        let json = to_json(&sample_weather_info(), &options(UnitSystem::Si, false));
+        let current = &json["current"];
+
+        assert_eq!(json["units"], "si");
+        assert_eq!(json["elevation_m"], 56.0);
+        assert!((current["temperature_k"].as_f64().unwrap() - 292.55).abs() < 1e-9);
+        assert!((current["wind_speed_ms"].as_f64().unwrap() - 11.2 / 3.6).abs() < 1e-9);
+        assert!((current["pressure_surface_pa"].as_f64().unwrap() - 101_630.0).abs() < 1e-6);
+        assert!((current["pressure_msl_pa"].as_f64().unwrap() - 102_310.0).abs() < 1e-6);
+        assert_eq!(current["precipitation_kg_m2"], 0.0);
+        assert_eq!(current["weather_code"], 2);
+        assert!(current.get("temperature").is_none());
+
+        let showers = &json["hourly"][37];
+        assert_eq!(showers["precipitation_kg_m2"], 1.2);
+        assert!(json["daily"][0].get("temperature_max_k").is_some());
+    }
+
+    #[test]
+    fn test_metric_json_keeps_model_names() {
+        let json = to_json(&sample_weather_info(), &options(UnitSystem::Metric, false));
+        assert_eq!(json["current"]["temperature"], 19.4);
+        assert_eq!(json["current"]["pressure_surface_hpa"], 1016.3);
+        assert_eq!(json["current"]["pressure_msl_hpa"], 1023.1);
+        assert!(json["current"].get("pressure").is_none());
+        assert!(json["current"].get("pressure_msl_inhg").is_none());
+        assert_eq!(json["daily"][0]["sunrise"], "2024-06-01T05:15");
+
+        // The daily probability is the peak hour; mean and wet hours come from hourly data
+        let showers = &json["daily"][1];
+        assert_eq!(showers["precipitation_probability_max"], 70.0);
+        assert_eq!(showers["precipitation_probability_mean"], 25.0);
+        assert_eq!(showers["wet_hours"], 6);
+        assert!(showers.get("precipitation_probability").is_none());
+        assert!(json["daily"][2]["wet_hours"].is_null());
+    }
+
+    #[test]
+    fn test_hourly_csv() {
+        let csv = hourly_csv(
+            &sample_weather_info(),
+            &csv_options(UnitSystem::Si, CsvDialect::Standard),
+        );
+        let mut lines = csv.lines();
+        let header = lines.next().unwrap();
+        assert!(header.starts_with("time,temperature_k,apparent_temperature_k,"));
+        assert_eq!(lines.count(), 48);
+
+        let empty = hourly_csv(
+            &WeatherInfo::builder("Nowhere", 0.0, 0.0).build(),
+            &DisplayOptions::default(),
+        );
+        assert_eq!(empty.lines().count(), 1);
+    }
+
+    #[test]
+    fn test_daily_csv() {
+        let mut info = sample_weather_info();
+        info.weather_data.daily[0].humidity_mean = Some(64.5);
+        info.weather_data.daily[0].uv_index_max = Some(7.25);
+
+        let csv = daily_csv(&info, &csv_options(UnitSystem::Si, CsvDialect::Standard));
+        let mut lines = csv.lines();
+        let header = lines.next().unwrap();
+        assert!(header.starts_with("date,temperature_max_k,"));
+        assert!(header.ends_with(",humidity_mean_pct,uv_index_max"));
+        assert!(lines.next().unwrap().ends_with(",64.5,7.25"));
+        assert_eq!(lines.count(), 6);
+
+        let excel = daily_csv(&info, &csv_options(UnitSystem::Metric, CsvDialect::ExcelDe));
+        let mut lines = excel.lines();
+        assert!(lines.next().unwrap().starts_with("date;temperature_max;"));
+        let first = lines.next().unwrap();
+        assert!(first.starts_with("2024-06-01;"), "{first}");
+        assert!(first.ends_with(";64,5;7,25"), "{first}");
+        assert!(!first.contains('.'));
+        assert_eq!("excel-de".parse(), Ok(CsvDialect::ExcelDe));
+        assert_eq!(
+            "daily-csv".parse::<OutputFormat>(),
+            Ok(OutputFormat::DailyCsv)
+        );
+    }
+
+    #[test]
+    fn test_schema_reflects_unit_system() {
+        let si = schema(&options(UnitSystem::Si, false));
+        assert_eq!(si["current"]["temperature_k"], "K");
+        assert_eq!(si["current"]["precipitation_kg_m2"], "kg/m²");
+        assert_eq!(si["hourly"]["snowfall_m"], "m");
+
+        let metric = schema(&options(UnitSystem::Metric, false));
+        assert_eq!(metric["current"]["temperature"], "°C");
+        assert_eq!(metric["current"]["pressure_msl_hpa"], "hPa");
+        assert_eq!(metric["hourly"]["snowfall"], "cm");
+        assert!(metric["current"].get("pressure_msl_mmhg").is_none());
+    }
+
+    #[test]
+    fn test_all_units_adds_converted_pressures() {
+        let json = to_json(&sample_weather_info(), &options(UnitSystem::Metric, true));
+        let current = &json["current"];
+        assert!((current["pressure_msl_inhg"].as_f64().unwrap() - 30.21).abs() < 0.01);
+        assert!((current["pressure_msl_mmhg"].as_f64().unwrap() - 767.4).abs() < 0.1);
+
+        // Converted fields keep their unit in the SI system too
+        let si = schema(&options(UnitSystem::Si, true));
+        assert_eq!(si["current"]["pressure_msl_inhg"], "inHg");
+        assert_eq!(si["current"]["pressure_msl_mmhg"], "mmHg");
+    }
+
+    #[test]
+    fn test_every_registered_field_is_exported() {
+        let info = sample_weather_info();
+        for units in [UnitSystem::Metric, UnitSystem::Si] {
+            let schema = schema(&options(units, true));
+            let json = to_json(&info, &options(units, true));
+            let sections = [
+                ("current", &json["current"]),
+                ("hourly", &json["hourly"][0]),
+                ("daily", &json["daily"][0]),
+            ];
+            for (section, exported) in sections {
+                for key in schema[section].as_object().unwrap().keys() {
+                    assert!(
+                        exported[key].is_number(),
+                        "{units} {section}.{key} missing from {exported}"
+                    );
+                }
+            }
+            assert!(json[ELEVATION.key(units)].is_number());
+
+            let columns = |csv: String| -> Vec<String> {
+                let mut columns: Vec<String> = csv
+                    .lines()
+                    .next()
+                    .unwrap()
+                    .split(',')
+                    .skip(1)
+                    .map(String::from)
+                    .collect();
+                columns.sort();
+                columns
+            };
+            let registered = |section: &str| -> Vec<String> {
+                let mut keys: Vec<String> = schema[section]
+                    .as_object()
+                    .unwrap()
+                    .keys()
+                    .cloned()
+                    .collect();
+                keys.sort();
+                keys
+            };
+            assert_eq!(
+                columns(hourly_csv(&info, &csv_options(units, CsvDialect::Standard))),
+                registered("hourly")
+            );
+            assert_eq!(
+                columns(daily_csv(&info, &csv_options(units, CsvDialect::Standard))),
+                registered("daily")
+            );
+        }
+    }
+}
@@ -1,20 +1,261 @@
 //! GUI view using egui for desktop application
 
-use crate::models::weather_info::{DailyForecast, WeatherInfo};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Instant;
+
+use chrono::Datelike;
+
+use crate::constants::{
+    CHANGE_ALERT_DURATION, FAVORITE_REFRESH_PAUSE, FOG_VISIBILITY_THRESHOLD_M,
+    GUI_SHUTDOWN_TIMEOUT, PRECIPITATION_OUTLOOK_HOURS, SNOW_ACCUMULATION_HOURS,
+    STATUS_MESSAGE_DURATION, WIND_ROSE_SECTORS,
+};
+use crate::controllers::gui_controller::GuiController;
+use crate::errors::WeatherError;
+use crate::models::favorites::{Favorites, ResolvedPlace};
+use crate::models::hourly_stats::HourlyStats;
+use crate::models::settings::Settings;
+use crate::models::weather_info::{DailyForecast, HourlyForecast, WeatherInfo};
+use crate::models::wind_rose::WindRose;
+use crate::repositories::app_state::{self, AppDirs, StateItem};
+use crate::repositories::data_selection::DataSelection;
+use crate::repositories::favorites_store::FavoritesStore;
+use crate::repositories::file_weather_repository::FileWeatherRepository;
+use crate::repositories::geocode_cache::GeocodeCache;
+use crate::repositories::persistent_weather_repository::{
+    OfflineStore, PersistentWeatherRepository,
+};
+use crate::repositories::settings_store::SettingsStore;
+use crate::repositories::snapshot_store::SnapshotStore;
+use crate::repositories::usage_log::UsageLog;
+use crate::repositories::user_agent::UserAgent;
 use crate::repositories::weather_repository::{ApiWeatherRepository, WeatherRepository};
-use crate::utils::conversions::Temperature;
+use crate::utils::advice::FeelsLikeFormula;
+use crate::utils::advisories::{AdvisoryStandard, Hazard, Severity};
+use crate::utils::aviation::{self, WindLevel};
+use crate::utils::confidence::Confidence;
+use crate::utils::conversions::{Direction, Distance, PressureReference, WindUnit};
+use crate::utils::daylight::Daylight;
+use crate::utils::forecast_change;
+use crate::utils::format::{self, Precision};
+use crate::utils::hourly_range::HourlyRange;
+use crate::utils::i18n::{tr, Key, Locale};
+use crate::utils::storm::{self, StormSeverity};
+use crate::utils::time::ClockChange;
+use crate::utils::uv::{self, SkinType, UvCategory};
+use crate::utils::weather_codes::WeatherCode;
+use crate::utils::{
+    advice, advisories, agriculture, clock, freshness, mountain, precip_phase, precipitation, snow,
+    time, weather_codes,
+};
+use crate::views::accessibility;
+use crate::views::chart_image::{self, ChartTheme};
+use crate::views::theme::{Theme, ThemeAccents};
+use crate::views::DisplayOptions;
 use eframe::egui;
 
+/// Window title shown when no weather is loaded
+pub const WINDOW_TITLE: &str = "Weather App";
+
+/// Height of the hourly chart in points
+const HOURLY_CHART_HEIGHT: f32 = 160.0;
+
+/// Width beside the hourly chart kept for the save button
+const CHART_BUTTON_WIDTH: f32 = 40.0;
+
+/// Saved chart images have this many times the on-screen pixels per side
+const CHART_EXPORT_SCALE: f32 = 2.0;
+
+/// The desktop application: search bar, favorites, settings and a
+/// [`WeatherPanel`] showing the result of the latest request
 pub struct WeatherApp {
     location_input: String,
-    weather_info: Option<WeatherInfo>,
     error_message: Option<String>,
-    repository: ApiWeatherRepository,
+    controller: GuiController<Box<dyn WeatherRepository + Send + Sync>>,
+    // Forecast file every search reads instead of the network, for demos
+    saved_forecast: Option<PathBuf>,
+    panel: WeatherPanel,
+    show_settings: bool,
+    // Window title to apply on the next frame (summary line of the latest result)
+    pending_title: Option<String>,
+    favorites: Favorites,
+    // Favorites are kept in memory only when no store is configured
+    favorites_store: Option<FavoritesStore>,
+    // Favorite index and label being edited in the rename dialog
+    renaming: Option<(usize, String)>,
+    // Results of favorite name refreshes running in the background
+    name_refresh: (Sender<NameRefresh>, Receiver<NameRefresh>),
+    settings: Settings,
+    // Settings are kept in memory only when no store is configured
+    settings_store: Option<SettingsStore>,
+    // Where the controller saves the latest weather for the next warm start
+    snapshot_store: Option<SnapshotStore>,
+    // Where every fetch is kept for display without a connection
+    offline_store: Option<OfflineStore>,
+    // Showing the snapshot of an earlier session until the first result arrives
+    warm_start: bool,
+    // Significant changes from the previous fetch of the same location, and when they arrived
+    change_alert: Option<(Vec<String>, Instant)>,
+    // Error message that last took keyboard focus, so a new one is announced once
+    announced_error: Option<String>,
+    // Location to fetch again once the server's rate-limit cooldown ends
+    rate_limit_retry: Option<(String, chrono::DateTime<chrono::Local>)>,
+    /// Whether the system clock warning was shown this session
+    clock_warned: bool,
+}
+
+/// Location header, hazard banners and the current / hourly / daily tabs for
+/// one [`WeatherInfo`], usable as a widget inside any egui application.
+///
+/// The panel only renders what it is given: fetching stays with the host,
+/// which passes results in with [`WeatherPanel::set_weather`] (for example
+/// from a [`GuiController`]).
+pub struct WeatherPanel {
+    weather_info: Option<WeatherInfo>,
     selected_tab: Tab,
+    display_options: DisplayOptions,
+    locale: Locale,
+    hourly_range: HourlyRange,
+    // Daily row column widths, measured on the first frame
+    column_widths: Option<DailyColumnWidths>,
+    // Outcome of the last chart export (true for errors) and when it was shown
+    status_message: Option<(String, bool, Instant)>,
+}
+
+/// Widths of the fixed daily-row columns, wide enough for the longest
+/// translation of their labels so switching language never reflows rows.
+/// Humidity and UV are optional and give way first when the window is narrow
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct DailyColumnWidths {
+    /// Precipitation, wind and water columns
+    label: f32,
+    /// High / low temperatures
+    temperature: f32,
+    /// Sunrise / sunset, daylight and sunshine
+    sun: f32,
+}
+
+impl DailyColumnWidths {
+    /// Minimum widths, fitting the English labels
+    const MIN: DailyColumnWidths = DailyColumnWidths {
+        label: 80.0,
+        temperature: 180.0,
+        sun: 70.0,
+    };
+
+    /// Space between a column's widest text and the next column
+    const PADDING: f32 = 8.0;
+
+    /// Date column, weather icon and the spacing between columns
+    const ROW_OVERHEAD: f32 = 90.0 + 10.0 + 60.0 + 15.0 + 15.0;
+
+    /// Width of one label column including the gap after it
+    fn label_column(&self) -> f32 {
+        self.label + 10.0
+    }
+
+    /// Which of the humidity and UV columns fit in a row `available` wide,
+    /// given which of them have data; humidity is kept over UV
+    fn optional_columns(
+        &self,
+        available: f32,
+        garden: bool,
+        humidity: bool,
+        uv: bool,
+    ) -> (bool, bool) {
+        let fixed_columns = if garden { 3.0 } else { 2.0 };
+        let mut spare = available
+            - Self::ROW_OVERHEAD
+            - self.temperature
+            - self.sun
+            - fixed_columns * self.label_column();
+        let mut fits = |wanted: bool| {
+            let fits = wanted && spare >= self.label_column();
+            if fits {
+                spare -= self.label_column();
+            }
+            fits
+        };
+        let show_humidity = fits(humidity);
+        (show_humidity, fits(uv))
+    }
+
+    /// Measures the longest translated label of each column across all locales
+    fn measure(ctx: &egui::Context) -> Self {
+        let text_width = |text: String, size: f32| {
+            ctx.fonts(|fonts| {
+                fonts
+                    .layout_no_wrap(text, egui::FontId::proportional(size), Colors::TEXT_PRIMARY)
+                    .size()
+                    .x
+            })
+        };
+        let widest = |keys: &[Key], size: f32, format: &dyn Fn(&str) -> String| {
+            Locale::ALL
+                .iter()
+                .flat_map(|locale| keys.iter().map(|key| tr(*locale, *key)))
+                .map(|label| text_width(format(label), size) + Self::PADDING)
+                .fold(0.0, f32::max)
+        };
+
+        DailyColumnWidths {
+            label: widest(
+                &[
+                    Key::Precip,
+                    Key::Wind,
+                    Key::Water,
+                    Key::Humidity,
+                    Key::UvIndex,
+                ],
+                13.0,
+                &|label| label.to_string(),
+            )
+            .max(Self::MIN.label),
+            temperature: widest(&[Key::High, Key::Low], 14.0, &|label| {
+                format!("{label}: 100.0°F / 37.8°C")
+            })
+            .max(Self::MIN.temperature),
+            sun: widest(&[Key::Sunrise, Key::Sunset], 12.0, &|label| {
+                format!("{label} 00:00")
+            })
+            .max(widest(
+                &[Key::DaylightDuration, Key::SunshineDuration],
+                12.0,
+                &|label| format!("{label} 24h 0m"),
+            ))
+            .max(Self::MIN.sun),
+        }
+    }
+}
+
+/// Hourly-tab interaction applied after the tab has been drawn
+enum HourlyAction {
+    PickRange(HourlyRange),
+    /// Save the chart at `CHART_EXPORT_SCALE` times its on-screen size in pixels
+    SaveChart {
+        width: u32,
+        height: u32,
+    },
+}
+
+/// Sidebar interaction applied after the favorites list has been drawn
+enum FavoriteAction {
+    Open(usize),
+    Rename(usize),
+    Remove(usize),
+    RefreshName(usize),
+    AcceptMove(usize),
 }
 
-#[derive(PartialEq)]
-enum Tab {
+/// A favorite's location and what geocoding it again found
+type NameRefresh = (String, Result<Option<ResolvedPlace>, WeatherError>);
+
+/// Tabs of the [`WeatherPanel`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Tab {
+    #[default]
     Current,
     Hourly,
     Daily,
@@ -24,7 +265,7 @@ enum Tab {
 struct Colors;
 impl Colors {
     // Background colors
-    const BG_PRIMARY: egui::Color32 = egui::Color32::from_rgb(15, 23, 42); // Dark blue-gray
+    const BG_PRIMARY: egui::Color32 = Theme::DARK.background; // Dark blue-gray
     const BG_SECONDARY: egui::Color32 = egui::Color32::from_rgb(30, 41, 59); // Lighter blue-gray
     const BG_CARD: egui::Color32 = egui::Color32::from_rgb(51, 65, 85); // Card background
     #[allow(dead_code)]
@@ -33,10 +274,10 @@ impl Colors {
     // Accent colors
     const ACCENT_BLUE: egui::Color32 = egui::Color32::from_rgb(59, 130, 246); // Bright blue
     const ACCENT_CYAN: egui::Color32 = egui::Color32::from_rgb(34, 211, 238); // Cyan
-    #[allow(dead_code)]
     const ACCENT_GREEN: egui::Color32 = egui::Color32::from_rgb(52, 211, 153); // Green
     const ACCENT_YELLOW: egui::Color32 = egui::Color32::from_rgb(251, 191, 36); // Yellow/sun
     const ACCENT_ORANGE: egui::Color32 = egui::Color32::from_rgb(251, 146, 60); // Orange
+    const UV_EXTREME: egui::Color32 = egui::Color32::from_rgb(192, 132, 252); // Violet
 
     // Text colors
     const TEXT_PRIMARY: egui::Color32 = egui::Color32::from_rgb(248, 250, 252); // Near white
@@ -53,58 +294,115 @@ impl Default for WeatherApp {
     fn default() -> Self {
         Self {
             location_input: String::new(),
-            weather_info: None,
             error_message: None,
-            repository: ApiWeatherRepository::new(),
-            selected_tab: Tab::Current,
+            controller: GuiController::new(Box::new(PersistentWeatherRepository::new(
+                WeatherApp::repository(&Settings::default()),
+                None,
+            ))),
+            saved_forecast: None,
+            panel: WeatherPanel::default(),
+            show_settings: false,
+            pending_title: None,
+            favorites: Favorites::default(),
+            favorites_store: None,
+            renaming: None,
+            name_refresh: mpsc::channel(),
+            settings: Settings::default(),
+            settings_store: None,
+            snapshot_store: None,
+            offline_store: None,
+            warm_start: false,
+            change_alert: None,
+            announced_error: None,
+            rate_limit_retry: None,
+            clock_warned: false,
         }
     }
 }
 
+impl Default for WeatherPanel {
+    fn default() -> Self {
+        WeatherPanel::new(DisplayOptions::default())
+    }
+}
+
 impl eframe::App for WeatherApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        let locale = self.settings.locale;
+        let hourly_range = self.settings.hourly_range;
+        self.panel.set_locale(locale);
+        self.panel.hourly_range = hourly_range;
         // Apply dark theme
         ctx.set_visuals(create_custom_visuals());
 
+        let requested = self.controller.pending_location().map(str::to_string);
+        if let Some(result) = self.controller.poll() {
+            if result.is_ok() && requested.is_some() && self.settings.last_location != requested {
+                self.settings.last_location = requested.clone();
+                self.persist_settings();
+            }
+            self.show_result(requested.as_deref(), result);
+            self.warm_start = false;
+        }
+        self.retry_after_rate_limit(ctx);
+        if self.controller.is_loading() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+
+        if let Some(title) = self.pending_title.take() {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Title(title));
+        }
+
+        self.apply_name_refreshes();
+        self.display_favorites_sidebar(ctx);
+        self.display_status_bar(ctx);
+        self.display_change_alert(ctx);
+
+        let accents = self.panel.theme_accents();
         egui::CentralPanel::default()
             .frame(
                 egui::Frame::none()
-                    .fill(Colors::BG_PRIMARY)
+                    .fill(accents.gradient_bottom)
                     .inner_margin(egui::Margin::same(20.0)),
             )
             .show(ctx, |ui| {
+                paint_gradient(ui, ui.max_rect().expand(20.0), &accents);
                 ui.add_space(20.0);
 
                 // Header with search
                 ui.vertical_centered(|ui| {
                     ui.label(
-                        egui::RichText::new("Weather Dashboard")
+                        egui::RichText::new(tr(locale, Key::Dashboard))
                             .size(36.0)
                             .strong()
-                            .color(Colors::TEXT_PRIMARY),
+                            .color(accents.accent),
                     );
                     ui.add_space(8.0);
                 });
 
                 // Search bar
-                egui::Frame::none()
+                let actions_rect = egui::Frame::none()
                     .fill(Colors::BG_SECONDARY)
                     .rounding(12.0)
                     .inner_margin(egui::Margin::symmetric(20.0, 15.0))
                     .show(ui, |ui| {
                         ui.horizontal(|ui| {
                             ui.label(
-                                egui::RichText::new("Location:")
+                                egui::RichText::new(tr(locale, Key::LocationLabel))
                                     .color(Colors::TEXT_SECONDARY)
                                     .size(15.0),
                             );
 
                             let text_edit = egui::TextEdit::singleline(&mut self.location_input)
-                                .hint_text("Enter city name...")
-                                .desired_width(ui.available_width() - 120.0)
+                                .hint_text(tr(locale, Key::LocationHint))
+                                .desired_width(ui.available_width() - 300.0)
                                 .font(egui::TextStyle::Body);
 
                             let response = ui.add(text_edit);
+                            // Keyboard users start in the search box
+                            if ctx.cumulative_pass_nr() == 0 {
+                                response.request_focus();
+                            }
 
                             if response.lost_focus()
                                 && ui.input(|i| i.key_pressed(egui::Key::Enter))
@@ -113,7 +411,7 @@ impl eframe::App for WeatherApp {
                             }
 
                             let button = egui::Button::new(
-                                egui::RichText::new("Search")
+                                egui::RichText::new(tr(locale, Key::Search))
                                     .size(15.0)
                                     .color(Colors::TEXT_PRIMARY),
                             )
@@ -124,174 +422,1493 @@ impl eframe::App for WeatherApp {
                             if ui.add(button).clicked() {
                                 self.fetch_weather();
                             }
-                        });
-                    });
+
+                            // Room for ☆, ⟳ and ⚙, drawn after the panel so Tab
+                            // reaches the tabs and content first
+                            let (actions_rect, _) = ui.allocate_exact_size(
+                                egui::vec2(120.0 + 2.0 * ui.spacing().item_spacing.x, 32.0),
+                                egui::Sense::hover(),
+                            );
+
+                            if self.controller.is_loading() {
+                                ui.spinner();
+                            }
+                            actions_rect
+                        })
+                        .inner
+                    })
+                    .inner;
 
                 ui.add_space(15.0);
 
-                // Error display
+                // Error display; a new error takes focus so screen readers
+                // announce it
                 if let Some(error) = &self.error_message {
                     egui::Frame::none()
                         .fill(egui::Color32::from_rgb(127, 29, 29))
                         .rounding(8.0)
                         .inner_margin(12.0)
                         .show(ui, |ui| {
-                            ui.label(
-                                egui::RichText::new(format!("Error: {error}"))
+                            let response = ui.add(
+                                egui::Label::new(
+                                    egui::RichText::new(format!(
+                                        "{}: {error}",
+                                        tr(locale, Key::Error)
+                                    ))
                                     .color(Colors::ERROR_RED)
                                     .size(14.0),
+                                )
+                                .sense(egui::Sense::focusable_noninteractive()),
                             );
+                            if self.announced_error.as_ref() != Some(error) {
+                                response.request_focus();
+                                self.announced_error = Some(error.clone());
+                            }
                         });
                     ui.add_space(10.0);
+                } else {
+                    self.announced_error = None;
                 }
 
-                // Weather display
-                if let Some(weather) = &self.weather_info {
-                    self.display_location_header(ui, weather);
-                    ui.add_space(15.0);
-
-                    // Tab navigation
-                    ui.horizontal(|ui| {
-                        let tab_button = |ui: &mut egui::Ui, label: &str, is_selected: bool| {
-                            let button = egui::Button::new(
-                                egui::RichText::new(label).size(15.0).color(if is_selected {
-                                    Colors::TEXT_PRIMARY
-                                } else {
-                                    Colors::TEXT_MUTED
-                                }),
-                            )
-                            .fill(if is_selected {
-                                Colors::ACCENT_BLUE
-                            } else {
-                                Colors::BG_CARD
-                            })
-                            .rounding(8.0)
-                            .min_size(egui::vec2(100.0, 36.0));
-                            ui.add(button)
-                        };
-
-                        if tab_button(ui, "Current", self.selected_tab == Tab::Current).clicked() {
-                            self.selected_tab = Tab::Current;
-                        }
-                        ui.add_space(5.0);
-                        if tab_button(ui, "Hourly", self.selected_tab == Tab::Hourly).clicked() {
-                            self.selected_tab = Tab::Hourly;
-                        }
-                        ui.add_space(5.0);
-                        if tab_button(ui, "Daily", self.selected_tab == Tab::Daily).clicked() {
-                            self.selected_tab = Tab::Daily;
-                        }
-                    });
-
-                    ui.add_space(15.0);
-
-                    // Tab content
-                    egui::ScrollArea::vertical().show(ui, |ui| match self.selected_tab {
-                        Tab::Current => self.display_current_weather(ui, weather),
-                        Tab::Hourly => self.display_hourly_forecast(ui, weather),
-                        Tab::Daily => self.display_daily_forecast(ui, weather),
-                    });
-                } else {
-                    ui.vertical_centered(|ui| {
-                        ui.add_space(120.0);
-                        ui.label(
-                            egui::RichText::new("Enter a location to get started")
-                                .size(20.0)
-                                .color(Colors::TEXT_MUTED),
-                        );
+                if let Some(weather) = self.panel.weather_info.as_ref().filter(|w| w.offline) {
+                    let fetched_at = weather.fetched_at.map_or_else(String::new, |t| {
+                        t.with_timezone(&chrono::Local)
+                            .format("%Y-%m-%d %H:%M")
+                            .to_string()
                     });
+                    egui::Frame::none()
+                        .fill(Colors::BG_SECONDARY)
+                        .stroke(egui::Stroke::new(1.5, Colors::ACCENT_ORANGE))
+                        .rounding(8.0)
+                        .inner_margin(12.0)
+                        .show(ui, |ui| {
+                            ui.label(
+                                egui::RichText::new(
+                                    tr(locale, Key::OfflineData).replace("{}", &fetched_at),
+                                )
+                                .color(Colors::ACCENT_ORANGE)
+                                .size(14.0),
+                            );
+                        });
+                    ui.add_space(10.0);
                 }
 
+                self.panel.ui(ui);
+                self.display_search_actions(ui, actions_rect);
+
                 ui.add_space(20.0);
             });
+
+        self.display_settings_window(ctx);
+        self.display_rename_window(ctx);
+        self.settings.hourly_range = self.panel.hourly_range();
+        if self.settings.hourly_range != hourly_range {
+            self.persist_settings();
+        }
+    }
+
+    fn save(&mut self, _storage: &mut dyn eframe::Storage) {
+        self.persist_settings();
+        self.persist_favorites();
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.shut_down();
     }
 }
 
 impl WeatherApp {
-    fn fetch_weather(&mut self) {
-        self.error_message = None;
+    /// Geocodes the favorites whose names are due for a refresh (see
+    /// `models::favorites`) in the background, repainting `ctx` as each
+    /// result arrives
+    pub fn with_name_refresh(self, ctx: &egui::Context) -> Self {
+        let due = self
+            .favorites
+            .due_for_refresh(chrono::Local::now().date_naive());
+        self.refresh_favorite_names(ctx, due);
+        self
+    }
 
-        match self.repository.fetch_weather(&self.location_input) {
-            Ok(weather) => {
-                self.weather_info = Some(weather);
+    /// Geocodes `locations` one after another on a background thread; the
+    /// results are applied by `apply_name_refreshes` on the repaint each one
+    /// requests
+    fn refresh_favorite_names(&self, ctx: &egui::Context, locations: Vec<String>) {
+        if locations.is_empty() {
+            return;
+        }
+        let repository = WeatherApp::repository(&self.settings);
+        let sender = self.name_refresh.0.clone();
+        let recorded: Vec<Option<ResolvedPlace>> = locations
+            .iter()
+            .map(|location| {
+                self.favorites
+                    .find(location)
+                    .and_then(|f| f.resolved.clone())
+            })
+            .collect();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            for (index, (location, recorded)) in locations.into_iter().zip(recorded).enumerate() {
+                if index > 0 {
+                    thread::sleep(FAVORITE_REFRESH_PAUSE);
+                }
+                let result = repository.resolve_place(&location, recorded.as_ref());
+                let rate_limited = matches!(result, Err(WeatherError::RateLimited { .. }));
+                let sent = sender.send((location, result)).is_ok();
+                ctx.request_repaint();
+                if !sent || rate_limited {
+                    break;
+                }
             }
-            Err(e) => {
+        });
+    }
+
+    /// Records finished name refreshes. Failed lookups are dropped and tried
+    /// again at the next start.
+    fn apply_name_refreshes(&mut self) {
+        let today = chrono::Local::now().date_naive();
+        let mut changed = false;
+        while let Ok((location, result)) = self.name_refresh.1.try_recv() {
+            if let Ok(place) = result {
+                changed |= self
+                    .favorites
+                    .apply_refresh(&location, place, today)
+                    .is_some();
+            }
+        }
+        if changed {
+            self.persist_favorites();
+        }
+    }
+
+    /// Loads favorites from `store` and saves changes back to it
+    pub fn with_favorites_store(mut self, store: FavoritesStore) -> Self {
+        match store.load() {
+            Ok(favorites) => self.favorites = favorites,
+            Err(e) => self.error_message = Some(format!("{e}")),
+        }
+        self.favorites_store = Some(store);
+        self
+    }
+
+    /// Loads preferences (such as the language) from `store` and saves changes back to it
+    pub fn with_settings_store(mut self, store: SettingsStore) -> Self {
+        match store.load() {
+            Ok(settings) => self.settings = settings,
+            Err(e) => self.error_message = Some(format!("{e}")),
+        }
+        *self.panel.display_options_mut() = self.settings.display.clone();
+        self.settings_store = Some(store);
+        self.rebuild_controller();
+        self
+    }
+
+    /// Replaces the controller with one for the current settings while the
+    /// app is built; later changes go through `apply_confidence_setting`
+    fn rebuild_controller(&mut self) {
+        self.controller = GuiController::new(self.weather_repository());
+        self.controller
+            .set_snapshot_store(self.snapshot_store.clone());
+        self.apply_usage_stats();
+    }
+
+    /// Repository searches go to: the saved forecast if there is one, else
+    /// the API as configured in the settings, with the offline store
+    fn weather_repository(&self) -> Box<dyn WeatherRepository + Send + Sync> {
+        match &self.saved_forecast {
+            Some(path) => Box::new(FileWeatherRepository::new(path)),
+            None => Box::new(PersistentWeatherRepository::new(
+                Self::repository(&self.settings),
+                self.offline_store.clone(),
+            )),
+        }
+    }
+
+    /// Fetches with or without the ensemble to match the confidence setting.
+    /// The session carries on with the new repository; the pending search,
+    /// or else the shown location, is fetched again past the cache.
+    fn apply_confidence_setting(&mut self) {
+        self.controller.set_repository(self.weather_repository());
+        let location = match self.controller.pending_location() {
+            Some(pending) => Some(pending.to_string()),
+            None if self.panel.weather_info.is_some() => self.settings.last_location.clone(),
+            None => None,
+        };
+        if let Some(location) = location {
+            self.error_message = None;
+            self.controller.refresh_weather(&location);
+        }
+    }
+
+    /// Answers every search with the forecast saved at `path` instead of
+    /// fetching it: an Open-Meteo response or a forecast saved with
+    /// `FileWeatherRepository::record`. For demos and offline work.
+    pub fn with_saved_forecast(mut self, path: impl Into<PathBuf>) -> Self {
+        self.saved_forecast = Some(path.into());
+        self.rebuild_controller();
+        self
+    }
+
+    /// Keeps the weather of each fetch in `store` and shows it, marked as
+    /// offline, when a later fetch of the same location fails to connect.
+    /// Call before `with_snapshot_store`, whose warm start it would cancel.
+    pub fn with_offline_store(mut self, store: OfflineStore) -> Self {
+        self.offline_store = Some(store);
+        self.rebuild_controller();
+        self
+    }
+
+    /// Saves the weather of each fetch to `store` and, when the settings
+    /// name a last location, shows its saved weather at once while fetching
+    /// it again. Call after `with_settings_store`, which supplies that location.
+    pub fn with_snapshot_store(mut self, store: SnapshotStore) -> Self {
+        self.controller.set_snapshot_store(Some(store.clone()));
+        self.snapshot_store = Some(store);
+        if let Some(location) = self.settings.last_location.clone() {
+            self.location_input = location.clone();
+            if let Some(cached) = self.controller.warm_start(&location) {
+                self.show_result(Some(&location), Ok(cached));
+                self.warm_start = true;
+            }
+        }
+        self
+    }
+
+    /// Repository identified as configured in `settings`. Winds aloft are
+    /// always requested so the aviation setting needs no refetch, and waves
+    /// so coastal locations get the marine card. The ensemble request for
+    /// forecast confidence is made only when the setting is on.
+    fn repository(settings: &Settings) -> ApiWeatherRepository {
+        let selection = DataSelection::full().with_aviation().with_marine();
+        let selection = if settings.display.confidence {
+            selection.with_confidence()
+        } else {
+            selection
+        };
+        ApiWeatherRepository::new()
+            .with_data_selection(selection)
+            .with_user_agent(UserAgent::from_settings(settings))
+            .with_geocode_cache(GeocodeCache::default_location())
+    }
+
+    /// Starts or stops recording usage statistics to match the settings
+    fn apply_usage_stats(&mut self) {
+        self.controller.set_usage_log(
+            UsageLog::default_location().and_then(|log| log.enabled_by(&self.settings)),
+        );
+    }
+
+    /// Empties the in-memory weather cache and removes the cache files, as
+    /// `weather-app cache clear` does, reporting the result in the status bar
+    fn clear_cached_data(&mut self) {
+        self.controller.repository().clear();
+        let removed = AppDirs::default_location()
+            .ok_or_else(|| WeatherError::StorageError("No cache directory found".to_string()))
+            .and_then(|dirs| dirs.clear(&StateItem::CACHES));
+        match removed {
+            Ok(removed) => {
+                let bytes = removed.iter().filter_map(|file| file.size).sum();
+                self.panel.set_status(
+                    tr(self.settings.locale, Key::CacheCleared)
+                        .replace("{}", &app_state::format_size(bytes)),
+                    false,
+                );
+            }
+            Err(e) => self.panel.set_status(format!("{e}"), true),
+        }
+    }
+
+    fn persist_settings(&mut self) {
+        if let Some(store) = &self.settings_store {
+            if let Err(e) = store.save(&self.settings) {
                 self.error_message = Some(format!("{e}"));
-                self.weather_info = None;
             }
         }
     }
 
-    fn display_location_header(&self, ui: &mut egui::Ui, weather: &WeatherInfo) {
-        egui::Frame::none()
-            .fill(Colors::BG_SECONDARY)
-            .rounding(12.0)
-            .inner_margin(15.0)
-            .show(ui, |ui| {
-                ui.vertical(|ui| {
-                    ui.label(
-                        egui::RichText::new(&weather.location)
-                            .size(26.0)
-                            .strong()
-                            .color(Colors::TEXT_PRIMARY),
-                    );
-                    ui.label(
-                        egui::RichText::new(format!(
-                            "{:.4}°N, {:.4}°E",
-                            weather.latitude, weather.longitude
-                        ))
-                        .size(13.0)
-                        .color(Colors::TEXT_MUTED),
-                    );
+    /// Stops the background refresh (waiting at most `GUI_SHUTDOWN_TIMEOUT`),
+    /// saves settings and favorites, and logs the session metrics
+    fn shut_down(&mut self) {
+        let report = self.controller.shutdown(GUI_SHUTDOWN_TIMEOUT);
+        let saved = [
+            self.settings_store
+                .as_ref()
+                .map(|store| store.save(&self.settings)),
+            self.favorites_store
+                .as_ref()
+                .map(|store| store.save(&self.favorites)),
+        ];
+        for e in saved.into_iter().flatten().filter_map(Result::err) {
+            eprintln!("Error: {e}");
+        }
+        eprintln!("Session: {}", report.metrics);
+    }
+
+    /// ☆ (save to favorites) and ⚙ (settings) in the space the search bar
+    /// left for them
+    fn display_search_actions(&mut self, ui: &mut egui::Ui, rect: egui::Rect) {
+        let locale = self.settings.locale;
+        let action_button = |icon: &str| {
+            egui::Button::new(
+                egui::RichText::new(icon)
+                    .size(15.0)
+                    .color(Colors::TEXT_PRIMARY),
+            )
+            .fill(Colors::BG_CARD)
+            .rounding(8.0)
+            .min_size(egui::vec2(40.0, 32.0))
+        };
+        let size = egui::vec2(40.0, rect.height());
+        let save_rect = egui::Rect::from_min_size(rect.left_top(), size);
+        let refresh_rect = egui::Rect::from_center_size(rect.center(), size);
+        let settings_rect =
+            egui::Rect::from_min_size(rect.right_top() - egui::vec2(40.0, 0.0), size);
+
+        let has_location = !self.location_input.trim().is_empty();
+        let save = ui
+            .add_enabled_ui(has_location, |ui| ui.put(save_rect, action_button("☆")))
+            .inner;
+        save.widget_info(|| {
+            egui::WidgetInfo::labeled(
+                egui::WidgetType::Button,
+                has_location,
+                tr(locale, Key::SaveToFavorites),
+            )
+        });
+        if save
+            .on_hover_text(tr(locale, Key::SaveToFavorites))
+            .clicked()
+        {
+            let location = self.location_input.clone();
+            self.favorites.save(&location, None, None);
+            self.persist_favorites();
+        }
+
+        let refresh = ui
+            .add_enabled_ui(has_location, |ui| ui.put(refresh_rect, action_button("⟳")))
+            .inner;
+        refresh.widget_info(|| {
+            egui::WidgetInfo::labeled(
+                egui::WidgetType::Button,
+                has_location,
+                tr(locale, Key::Refresh),
+            )
+        });
+        if refresh.on_hover_text(tr(locale, Key::Refresh)).clicked() {
+            self.error_message = None;
+            self.controller.refresh_weather(&self.location_input);
+        }
+
+        let settings = ui.put(settings_rect, action_button("⚙"));
+        settings.widget_info(|| {
+            egui::WidgetInfo::labeled(egui::WidgetType::Button, true, tr(locale, Key::Settings))
+        });
+        if settings.on_hover_text(tr(locale, Key::Settings)).clicked() {
+            self.show_settings = !self.show_settings;
+        }
+    }
+
+    fn persist_favorites(&mut self) {
+        if let Some(store) = &self.favorites_store {
+            if let Err(e) = store.save(&self.favorites) {
+                self.error_message = Some(format!("{e}"));
+            }
+        }
+    }
+
+    /// Progress of the latest search, then the provider, model run and fetch
+    /// time of the shown data, with a warning once it has expired
+    fn display_status_bar(&self, ctx: &egui::Context) {
+        let loading = self
+            .controller
+            .pending_location()
+            .map(|location| tr(self.settings.locale, Key::LoadingLocation).replace("{}", location));
+        let weather = self.panel.weather_info.as_ref();
+        let cached = (weather.is_some() && self.controller.showing_cached())
+            .then(|| tr(self.settings.locale, Key::ShowingCached));
+        let status = self.panel.status_message();
+        let provenance = weather.and_then(|weather| {
+            let fetched_at = weather
+                .fetched_at
+                .map(|t| t.with_timezone(&chrono::Local).naive_local());
+            let provenance = freshness::provenance_line(weather.reference_time(), fetched_at);
+            match (weather.attribution(), provenance) {
+                (Some(attribution), Some(provenance)) => {
+                    Some(format!("{attribution} · {provenance}"))
+                }
+                (attribution, provenance) => attribution.or(provenance),
+            }
+        });
+        let warning = weather
+            .and_then(|weather| weather.freshness(chrono::Utc::now()))
+            .and_then(|f| f.warning());
+        if loading.is_none()
+            && cached.is_none()
+            && provenance.is_none()
+            && warning.is_none()
+            && status.is_none()
+        {
+            return;
+        }
+
+        egui::TopBottomPanel::bottom("status_bar")
+            .frame(
+                egui::Frame::none()
+                    .fill(Colors::BG_SECONDARY)
+                    .inner_margin(egui::Margin::symmetric(12.0, 6.0)),
+            )
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if let Some(loading) = loading {
+                        ui.label(
+                            egui::RichText::new(loading)
+                                .size(12.0)
+                                .color(Colors::TEXT_SECONDARY),
+                        );
+                    }
+                    if let Some(cached) = cached {
+                        ui.label(
+                            egui::RichText::new(cached)
+                                .size(12.0)
+                                .color(Colors::ACCENT_ORANGE),
+                        );
+                    }
+                    if let Some(provenance) = provenance {
+                        ui.label(
+                            egui::RichText::new(provenance)
+                                .size(12.0)
+                                .color(Colors::TEXT_MUTED),
+                        );
+                    }
+                    if let Some(warning) = warning {
+                        ui.label(
+                            egui::RichText::new(warning)
+                                .size(12.0)
+                                .strong()
+                                .color(Colors::ACCENT_ORANGE),
+                        );
+                    }
+                    if let Some((message, is_error)) = status {
+                        ui.label(egui::RichText::new(message).size(12.0).color(if is_error {
+                            Colors::ERROR_RED
+                        } else {
+                            Colors::TEXT_SECONDARY
+                        }));
+                    }
                 });
             });
     }
 
-    fn display_current_weather(&self, ui: &mut egui::Ui, weather: &WeatherInfo) {
-        let current = &weather.weather_data.current;
+    /// Toast listing what changed since the location was last fetched;
+    /// closes on click or after `CHANGE_ALERT_DURATION`
+    fn display_change_alert(&mut self, ctx: &egui::Context) {
+        let Some((lines, shown_at)) = &self.change_alert else {
+            return;
+        };
+        let remaining = CHANGE_ALERT_DURATION.saturating_sub(shown_at.elapsed());
+        if remaining.is_zero() {
+            self.change_alert = None;
+            return;
+        }
+        ctx.request_repaint_after(remaining);
 
-        ui.horizontal_top(|ui| {
-            // Main temperature card
-            egui::Frame::none()
-                .fill(Colors::BG_CARD)
-                .rounding(12.0)
-                .inner_margin(25.0)
-                .show(ui, |ui| {
-                    ui.set_min_width(240.0);
-                    ui.vertical_centered(|ui| {
-                        if let Some(code) = current.weather_code {
+        let locale = self.settings.locale;
+        let response = egui::Area::new(egui::Id::new("change_alert"))
+            .anchor(egui::Align2::RIGHT_TOP, [-20.0, 20.0])
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(Colors::BG_SECONDARY)
+                    .stroke(egui::Stroke::new(1.5, Colors::ACCENT_ORANGE))
+                    .rounding(8.0)
+                    .inner_margin(12.0)
+                    .show(ui, |ui| {
+                        ui.label(
+                            egui::RichText::new(format!("🔔 {}", tr(locale, Key::ForecastChanged)))
+                                .size(14.0)
+                                .strong()
+                                .color(Colors::ACCENT_ORANGE),
+                        );
+                        for line in lines {
                             ui.label(
-                                egui::RichText::new(weather_code_to_icon(code))
-                                    .size(80.0)
-                                    .color(weather_code_to_color(code)),
+                                egui::RichText::new(line)
+                                    .size(13.0)
+                                    .color(Colors::TEXT_PRIMARY),
                             );
                         }
+                    })
+            })
+            .response;
+        if response.interact(egui::Sense::click()).clicked() {
+            self.change_alert = None;
+        }
+    }
 
-                        ui.add_space(10.0);
+    /// Saved locations: the label (or location) as the row title and the
+    /// location as the subtitle of labelled rows. Right-click to rename or remove.
+    fn display_favorites_sidebar(&mut self, ctx: &egui::Context) {
+        let locale = self.settings.locale;
+        if self.favorites.is_empty() {
+            return;
+        }
 
-                        if let Some(temp) = current.temperature {
-                            let temp_f = Temperature::celsius_to_fahrenheit(temp);
+        let mut action = None;
+        egui::SidePanel::left("favorites")
+            .resizable(false)
+            .frame(
+                egui::Frame::none()
+                    .fill(Colors::BG_SECONDARY)
+                    .inner_margin(egui::Margin::same(12.0)),
+            )
+            .show(ctx, |ui| {
+                ui.label(
+                    egui::RichText::new(tr(locale, Key::Favorites))
+                        .size(16.0)
+                        .strong()
+                        .color(Colors::TEXT_PRIMARY),
+                );
+                if let Some((_, retry_at)) = &self.rate_limit_retry {
+                    ui.label(
+                        egui::RichText::new(
+                            tr(locale, Key::RateLimitedRetry)
+                                .replace("{}", &retry_at.format("%H:%M").to_string()),
+                        )
+                        .size(11.0)
+                        .color(Colors::TEXT_MUTED),
+                    );
+                }
+                ui.add_space(8.0);
+
+                for (index, favorite) in self.favorites.entries().iter().enumerate() {
+                    let row = ui.vertical(|ui| {
+                        ui.horizontal(|ui| {
                             ui.label(
-                                egui::RichText::new(format!("{temp_f:.1}°F"))
-                                    .size(52.0)
-                                    .strong()
+                                egui::RichText::new(favorite.name())
+                                    .size(14.0)
                                     .color(Colors::TEXT_PRIMARY),
                             );
+                            if favorite.moved.is_some() {
+                                ui.label(
+                                    egui::RichText::new("⚠")
+                                        .size(12.0)
+                                        .color(Colors::ACCENT_ORANGE),
+                                );
+                            }
+                        });
+                        if favorite.label.is_some() {
                             ui.label(
-                                egui::RichText::new(format!("{temp:.1}°C"))
-                                    .size(18.0)
-                                    .color(Colors::TEXT_SECONDARY),
+                                egui::RichText::new(favorite.place_name())
+                                    .size(11.0)
+                                    .color(Colors::TEXT_MUTED),
                             );
                         }
+                    });
 
-                        ui.add_space(8.0);
-
-                        if let Some(code) = current.weather_code {
-                            ui.label(
-                                egui::RichText::new(weather_code_to_description(code))
-                                    .size(16.0)
-                                    .color(Colors::TEXT_SECONDARY),
-                            );
+                    let mut response = row.response.interact(egui::Sense::click());
+                    if let Some(note) = &favorite.note {
+                        response = response.on_hover_text(note);
+                    }
+                    if let Some(place) = &favorite.moved {
+                        response = response.on_hover_text(
+                            tr(locale, Key::FavoriteMoved).replace("{}", &place.name),
+                        );
+                    }
+                    if response.clicked() {
+                        action = Some(FavoriteAction::Open(index));
+                    }
+                    response.context_menu(|ui| {
+                        if ui.button(tr(locale, Key::Rename)).clicked() {
+                            action = Some(FavoriteAction::Rename(index));
+                            ui.close_menu();
+                        }
+                        if ui.button(tr(locale, Key::RefreshName)).clicked() {
+                            action = Some(FavoriteAction::RefreshName(index));
+                            ui.close_menu();
+                        }
+                        if favorite.moved.is_some()
+                            && ui.button(tr(locale, Key::AcceptMove)).clicked()
+                        {
+                            action = Some(FavoriteAction::AcceptMove(index));
+                            ui.close_menu();
+                        }
+                        if ui.button(tr(locale, Key::Remove)).clicked() {
+                            action = Some(FavoriteAction::Remove(index));
+                            ui.close_menu();
+                        }
+                    });
+                    ui.add_space(6.0);
+                }
+            });
+
+        match action {
+            Some(FavoriteAction::Open(index)) => {
+                let favorite = &self.favorites.entries()[index];
+                self.location_input = favorite.location.clone();
+                let query = favorite.query();
+                self.fetch_location(&query);
+            }
+            Some(FavoriteAction::Rename(index)) => {
+                let label = self.favorites.entries()[index].label.clone();
+                self.renaming = Some((index, label.unwrap_or_default()));
+            }
+            Some(FavoriteAction::Remove(index)) => {
+                self.favorites.remove(index);
+                self.persist_favorites();
+            }
+            Some(FavoriteAction::RefreshName(index)) => {
+                let location = self.favorites.entries()[index].location.clone();
+                self.refresh_favorite_names(ctx, vec![location]);
+            }
+            Some(FavoriteAction::AcceptMove(index)) if self.favorites.accept_move(index) => {
+                self.persist_favorites();
+            }
+            Some(FavoriteAction::AcceptMove(_)) | None => {}
+        }
+    }
+
+    fn display_rename_window(&mut self, ctx: &egui::Context) {
+        let locale = self.settings.locale;
+        let Some((index, label)) = &mut self.renaming else {
+            return;
+        };
+        let index = *index;
+
+        let mut done = None;
+        egui::Window::new(tr(locale, Key::RenameFavorite))
+            .resizable(false)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                let response =
+                    ui.add(egui::TextEdit::singleline(label).hint_text(tr(locale, Key::LabelHint)));
+                let submitted =
+                    response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                ui.horizontal(|ui| {
+                    if ui.button(tr(locale, Key::Save)).clicked() || submitted {
+                        done = Some(true);
+                    }
+                    if ui.button(tr(locale, Key::Cancel)).clicked() {
+                        done = Some(false);
+                    }
+                });
+            });
+
+        match done {
+            Some(true) => {
+                if let Some((_, label)) = self.renaming.take() {
+                    self.favorites.rename(index, &label);
+                    self.persist_favorites();
+                }
+            }
+            Some(false) => self.renaming = None,
+            None => {}
+        }
+    }
+
+    /// Fetches the rate-limited location again once its cooldown has passed
+    fn retry_after_rate_limit(&mut self, ctx: &egui::Context) {
+        let Some((_, retry_at)) = &self.rate_limit_retry else {
+            return;
+        };
+        match (*retry_at - chrono::Local::now()).to_std() {
+            Ok(remaining) if !remaining.is_zero() => ctx.request_repaint_after(remaining),
+            _ => {
+                if let Some((location, _)) = self.rate_limit_retry.take() {
+                    self.fetch_location(&location);
+                }
+            }
+        }
+    }
+
+    /// Starts a background fetch, showing cached data for the location right away
+    fn fetch_weather(&mut self) {
+        let location = self.location_input.clone();
+        self.fetch_location(&location);
+    }
+
+    /// Fetches `location`, which may differ from the search box, e.g. the
+    /// recorded position of a favorite shown by its name
+    fn fetch_location(&mut self, location: &str) {
+        self.error_message = None;
+
+        if let Some(cached) = self.controller.request_weather(location) {
+            self.show_result(Some(location), Ok(cached));
+        }
+    }
+
+    /// Shows the result of the request for `requested`, the location the
+    /// controller was asked for
+    fn show_result(&mut self, requested: Option<&str>, result: Result<WeatherInfo, WeatherError>) {
+        match result {
+            Ok(weather) => {
+                if let Some(skew) = weather.clock_skew.filter(|_| !self.clock_warned) {
+                    self.clock_warned = true;
+                    self.panel.set_status(
+                        tr(self.settings.locale, Key::ClockOff)
+                            .replace("{}", &clock::skew_amount(skew)),
+                        true,
+                    );
+                }
+                self.pending_title = Some(weather.summary().to_string());
+                // Yesterday's snapshot is no forecast the user saw change
+                let changes = self
+                    .panel
+                    .weather()
+                    .filter(|_| !self.warm_start)
+                    .filter(|shown| shown.location == weather.location)
+                    .map(|shown| {
+                        forecast_change::significant_changes(
+                            &shown.weather_data,
+                            &weather.weather_data,
+                        )
+                    })
+                    .unwrap_or_default();
+                if !changes.is_empty() {
+                    let lines = changes.iter().map(ToString::to_string).collect();
+                    self.change_alert = Some((lines, Instant::now()));
+                }
+                self.panel.set_weather(Some(weather));
+            }
+            Err(e) => {
+                if let (WeatherError::RateLimited { retry_after }, Some(requested)) =
+                    (&e, requested)
+                {
+                    let retry_at = chrono::Local::now()
+                        + chrono::TimeDelta::from_std(*retry_after).unwrap_or_default();
+                    self.rate_limit_retry = Some((requested.trim().to_string(), retry_at));
+                }
+                self.error_message = Some(format!("{e}"));
+                // A failed refresh leaves the cached data on screen
+                if self.controller.showing_cached() {
+                    return;
+                }
+                self.panel.set_weather(None);
+                self.pending_title = Some(WINDOW_TITLE.to_string());
+            }
+        }
+    }
+
+    fn display_settings_window(&mut self, ctx: &egui::Context) {
+        let locale = self.settings.locale;
+        let usage_stats = self.settings.usage_stats;
+        let display = self.panel.display_options.clone();
+        let mut clear_cache = false;
+        egui::Window::new(tr(locale, Key::Settings))
+            .open(&mut self.show_settings)
+            .resizable(false)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.checkbox(
+                    &mut self.panel.display_options.mountain,
+                    tr(locale, Key::MountainMode),
+                );
+                ui.checkbox(
+                    &mut self.panel.display_options.garden,
+                    tr(locale, Key::Agriculture),
+                );
+                ui.checkbox(
+                    &mut self.panel.display_options.winds_aloft,
+                    tr(locale, Key::Aviation),
+                );
+                ui.checkbox(
+                    &mut self.panel.display_options.confidence,
+                    tr(locale, Key::ForecastConfidence),
+                );
+
+                ui.separator();
+                ui.label(egui::RichText::new(tr(locale, Key::Units)).strong());
+                ui.horizontal(|ui| {
+                    ui.label(tr(locale, Key::HeatStress));
+                    for formula in [FeelsLikeFormula::HeatIndex, FeelsLikeFormula::Humidex] {
+                        ui.radio_value(
+                            &mut self.panel.display_options.feels_like_formula,
+                            formula,
+                            formula.label(),
+                        );
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr(locale, Key::AdvisoryTiers));
+                    for standard in AdvisoryStandard::ALL {
+                        ui.radio_value(
+                            &mut self.panel.display_options.advisory_standard,
+                            standard,
+                            standard.label(),
+                        );
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr(locale, Key::PressureShown));
+                    for reference in PressureReference::ALL {
+                        ui.radio_value(
+                            &mut self.panel.display_options.pressure,
+                            reference,
+                            tr(locale, pressure_key(reference)),
+                        );
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr(locale, Key::WindUnit));
+                    egui::ComboBox::from_id_salt("wind_unit")
+                        .selected_text(self.panel.display_options.wind_unit.label())
+                        .show_ui(ui, |ui| {
+                            for unit in WindUnit::ALL {
+                                ui.selectable_value(
+                                    &mut self.panel.display_options.wind_unit,
+                                    unit,
+                                    unit.label(),
+                                );
+                            }
+                        });
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr(locale, Key::Precision));
+                    for (precision, key) in [
+                        (None, Key::PrecisionAuto),
+                        (Some(Precision::Whole), Key::PrecisionWhole),
+                        (Some(Precision::Tenths), Key::PrecisionTenths),
+                    ] {
+                        ui.radio_value(
+                            &mut self.panel.display_options.precision,
+                            precision,
+                            tr(locale, key),
+                        );
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label(tr(locale, Key::SkinType));
+                    let skin_type = &mut self.panel.display_options.skin_type;
+                    let selected = skin_type.map_or_else(
+                        || tr(locale, Key::SkinTypeUnset).to_string(),
+                        |s| s.to_string(),
+                    );
+                    egui::ComboBox::from_id_salt("skin_type")
+                        .selected_text(selected)
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(skin_type, None, tr(locale, Key::SkinTypeUnset));
+                            for option in SkinType::ALL {
+                                ui.selectable_value(skin_type, Some(option), option.to_string());
+                            }
+                        });
+                });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label(tr(locale, Key::Language));
+                    egui::ComboBox::from_id_salt("locale")
+                        .selected_text(self.settings.locale.native_name())
+                        .show_ui(ui, |ui| {
+                            for option in Locale::ALL {
+                                ui.selectable_value(
+                                    &mut self.settings.locale,
+                                    option,
+                                    option.native_name(),
+                                );
+                            }
+                        });
+                });
+                ui.checkbox(&mut self.settings.usage_stats, tr(locale, Key::UsageStats))
+                    .on_hover_text(tr(locale, Key::UsageStatsHint));
+                clear_cache = ui.button(tr(locale, Key::ClearCachedData)).clicked();
+            });
+        if clear_cache {
+            self.clear_cached_data();
+        }
+
+        if self.settings.usage_stats != usage_stats {
+            self.apply_usage_stats();
+        }
+        let display_changed = self.panel.display_options != display;
+        if display_changed {
+            self.settings.display = self.panel.display_options.clone();
+        }
+        if self.settings.display.confidence != display.confidence {
+            self.apply_confidence_setting();
+        }
+        if self.settings.locale != locale
+            || self.settings.usage_stats != usage_stats
+            || display_changed
+        {
+            self.persist_settings();
+        }
+    }
+}
+
+impl WeatherPanel {
+    /// Empty panel (showing the get-started hint) with English labels
+    pub fn new(display_options: DisplayOptions) -> Self {
+        WeatherPanel {
+            weather_info: None,
+            selected_tab: Tab::Current,
+            display_options,
+            locale: Locale::default(),
+            hourly_range: HourlyRange::default(),
+            column_widths: None,
+            status_message: None,
+        }
+    }
+
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    pub fn with_hourly_range(mut self, range: HourlyRange) -> Self {
+        self.hourly_range = range;
+        self
+    }
+
+    /// Shows `weather`, or the get-started hint for `None`. The selected tab
+    /// is kept so a refresh does not move the user away from what they read.
+    pub fn set_weather(&mut self, weather: Option<WeatherInfo>) {
+        self.weather_info = weather;
+    }
+
+    pub fn weather(&self) -> Option<&WeatherInfo> {
+        self.weather_info.as_ref()
+    }
+
+    pub fn set_locale(&mut self, locale: Locale) {
+        self.locale = locale;
+    }
+
+    /// Range picked on the hourly tab, for hosts that persist it
+    pub fn hourly_range(&self) -> HourlyRange {
+        self.hourly_range
+    }
+
+    pub fn selected_tab(&self) -> Tab {
+        self.selected_tab
+    }
+
+    pub fn select_tab(&mut self, tab: Tab) {
+        self.selected_tab = tab;
+    }
+
+    pub fn display_options_mut(&mut self) -> &mut DisplayOptions {
+        &mut self.display_options
+    }
+
+    /// Outcome of the last chart export, `true` for errors; cleared after
+    /// `STATUS_MESSAGE_DURATION` or by the next tab or range change
+    pub fn status_message(&self) -> Option<(&str, bool)> {
+        self.status_message
+            .as_ref()
+            .map(|(message, is_error, _)| (message.as_str(), *is_error))
+    }
+
+    /// Shows `message` in the host's status bar for `STATUS_MESSAGE_DURATION`
+    pub fn set_status(&mut self, message: String, is_error: bool) {
+        self.status_message = Some((message, is_error, Instant::now()));
+    }
+
+    /// Drops the status message once it has been shown long enough,
+    /// otherwise asks for a repaint when it is due to go
+    fn expire_status(&mut self, ctx: &egui::Context) {
+        let Some((_, _, shown_at)) = &self.status_message else {
+            return;
+        };
+        let remaining = STATUS_MESSAGE_DURATION.saturating_sub(shown_at.elapsed());
+        if remaining.is_zero() {
+            self.status_message = None;
+        } else {
+            ctx.request_repaint_after(remaining);
+        }
+    }
+
+    /// Draws the panel into `ui`, filling the available width
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        let locale = self.locale;
+        if self.column_widths.is_none() {
+            self.column_widths = Some(DailyColumnWidths::measure(ui.ctx()));
+        }
+        self.expire_status(ui.ctx());
+
+        if let Some(weather) = &self.weather_info {
+            self.display_location_header(ui, weather);
+            ui.add_space(15.0);
+
+            self.display_banners(ui, weather);
+
+            // Tab navigation
+            ui.horizontal(|ui| {
+                let tab_button = |ui: &mut egui::Ui, label: &str, is_selected: bool| {
+                    let button = egui::Button::new(egui::RichText::new(label).size(15.0).color(
+                        if is_selected {
+                            Colors::TEXT_PRIMARY
+                        } else {
+                            Colors::TEXT_MUTED
+                        },
+                    ))
+                    .fill(if is_selected {
+                        Colors::ACCENT_BLUE
+                    } else {
+                        Colors::BG_CARD
+                    })
+                    .rounding(8.0)
+                    .min_size(egui::vec2(100.0, 36.0));
+                    ui.add(button)
+                };
+
+                if tab_button(
+                    ui,
+                    tr(locale, Key::TabCurrent),
+                    self.selected_tab == Tab::Current,
+                )
+                .clicked()
+                {
+                    self.selected_tab = Tab::Current;
+                    self.status_message = None;
+                }
+                ui.add_space(5.0);
+                if tab_button(
+                    ui,
+                    tr(locale, Key::TabHourly),
+                    self.selected_tab == Tab::Hourly,
+                )
+                .clicked()
+                {
+                    self.selected_tab = Tab::Hourly;
+                    self.status_message = None;
+                }
+                ui.add_space(5.0);
+                if tab_button(
+                    ui,
+                    tr(locale, Key::TabDaily),
+                    self.selected_tab == Tab::Daily,
+                )
+                .clicked()
+                {
+                    self.selected_tab = Tab::Daily;
+                    self.status_message = None;
+                }
+            });
+
+            ui.add_space(15.0);
+
+            // Tab content
+            let hourly_action = egui::ScrollArea::vertical()
+                .show(ui, |ui| match self.selected_tab {
+                    Tab::Current => {
+                        self.display_current_weather(ui, weather);
+                        None
+                    }
+                    Tab::Hourly => self.display_hourly_forecast(ui, weather),
+                    Tab::Daily => {
+                        self.display_daily_forecast(ui, weather);
+                        None
+                    }
+                })
+                .inner;
+            match hourly_action {
+                Some(HourlyAction::PickRange(range)) => {
+                    self.hourly_range = range;
+                    self.status_message = None;
+                }
+                Some(HourlyAction::SaveChart { width, height }) => {
+                    self.status_message = None;
+                    let picked = chart_save_dialog(weather, self.hourly_range).save_file();
+                    // A cancelled dialog saves nothing and reports nothing
+                    if let Some(path) = picked {
+                        match save_hourly_chart(weather, self.hourly_range, width, height, &path) {
+                            Ok(()) => {
+                                self.status_message = Some((
+                                    tr(locale, Key::ChartSaved)
+                                        .replace("{}", &path.display().to_string()),
+                                    false,
+                                    Instant::now(),
+                                ))
+                            }
+                            Err(e) => {
+                                self.status_message = Some((format!("{e}"), true, Instant::now()))
+                            }
+                        }
+                    }
+                }
+                None => {}
+            }
+        } else {
+            ui.vertical_centered(|ui| {
+                ui.add_space(120.0);
+                ui.label(
+                    egui::RichText::new(tr(locale, Key::GetStarted))
+                        .size(20.0)
+                        .color(Colors::TEXT_MUTED),
+                );
+            });
+        }
+    }
+
+    fn daily_column_widths(&self) -> DailyColumnWidths {
+        self.column_widths.unwrap_or(DailyColumnWidths::MIN)
+    }
+
+    /// Captions under the hourly chart: low, high, mean, precipitation and peak wind
+    fn display_hourly_stats(&self, ui: &mut egui::Ui, stats: &HourlyStats) {
+        let locale = self.locale;
+        let precision = self.display_options.precision();
+        let wind_unit = self.display_options.wind_unit;
+        let captions = [
+            stats.temperature_min.as_ref().map(|min| {
+                format!(
+                    "{} {} ({})",
+                    tr(locale, Key::Low),
+                    format::celsius(min.value, precision),
+                    min.clock()
+                )
+            }),
+            stats.temperature_max.as_ref().map(|max| {
+                format!(
+                    "{} {} ({})",
+                    tr(locale, Key::High),
+                    format::celsius(max.value, precision),
+                    max.clock()
+                )
+            }),
+            stats.temperature_mean.map(|mean| {
+                format!(
+                    "{} {}",
+                    tr(locale, Key::Average),
+                    format::celsius(mean, precision)
+                )
+            }),
+            stats
+                .precipitation_total
+                .map(|total| format!("{} {total:.1} mm", tr(locale, Key::Precip))),
+            stats.wind_peak.as_ref().map(|peak| {
+                format!(
+                    "{} {:.0} {} ({})",
+                    tr(locale, Key::Wind),
+                    wind_unit.from_kmh(peak.value),
+                    wind_unit.label(),
+                    peak.clock()
+                )
+            }),
+        ];
+        ui.horizontal_wrapped(|ui| {
+            for caption in captions.into_iter().flatten() {
+                ui.label(
+                    egui::RichText::new(caption)
+                        .size(12.0)
+                        .color(Colors::TEXT_MUTED),
+                );
+                ui.add_space(8.0);
+            }
+        });
+    }
+
+    /// Background and header colors for the loaded conditions, plain without weather
+    pub fn theme_accents(&self) -> ThemeAccents {
+        let current = self
+            .weather_info
+            .as_ref()
+            .map(|weather| &weather.weather_data.current);
+        match current.and_then(|c| c.weather_code.and_then(WeatherCode::from_wmo)) {
+            Some(code) => Theme::for_conditions(
+                &Theme::DARK,
+                code,
+                current.and_then(|c| c.is_day).unwrap_or(true),
+            ),
+            None => Theme::DARK.plain(),
+        }
+    }
+
+    fn display_location_header(&self, ui: &mut egui::Ui, weather: &WeatherInfo) {
+        let locale = self.locale;
+        egui::Frame::none()
+            .fill(Colors::BG_SECONDARY)
+            .rounding(12.0)
+            .inner_margin(15.0)
+            .show(ui, |ui| {
+                ui.vertical(|ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            egui::RichText::new(&weather.location)
+                                .size(26.0)
+                                .strong()
+                                .color(Colors::TEXT_PRIMARY),
+                        );
+                        if let Some(model) = weather.model_tag() {
+                            ui.label(
+                                egui::RichText::new(model)
+                                    .size(14.0)
+                                    .color(Colors::TEXT_SECONDARY),
+                            );
+                        }
+                        // Times on the page are local to this zone
+                        if let Some(abbreviation) = &weather.timezone_abbreviation {
+                            ui.label(
+                                egui::RichText::new(abbreviation)
+                                    .size(14.0)
+                                    .color(Colors::TEXT_MUTED),
+                            )
+                            .on_hover_text(weather.timezone.as_deref().unwrap_or(abbreviation));
+                        }
+                    });
+                    ui.label(
+                        egui::RichText::new(weather.coordinates().to_string())
+                            .size(13.0)
+                            .color(Colors::TEXT_MUTED),
+                    );
+                    if weather.reduced_data {
+                        ui.label(
+                            egui::RichText::new(tr(locale, Key::ReducedData))
+                                .size(12.0)
+                                .italics()
+                                .color(Colors::ACCENT_YELLOW),
+                        );
+                    }
+                });
+            });
+    }
+
+    /// Hazard banners shown above the tabs
+    fn display_banners(&self, ui: &mut egui::Ui, weather: &WeatherInfo) {
+        self.display_advisory_banner(ui, weather);
+        self.display_storm_banner(ui, weather);
+        self.display_fog_banner(ui, weather);
+    }
+
+    fn display_advisory_banner(&self, ui: &mut egui::Ui, weather: &WeatherInfo) {
+        let Some(advisory) = advisories::advisory(
+            &weather.weather_data.current,
+            self.display_options.advisory_standard,
+        ) else {
+            return;
+        };
+
+        let icon = match advisory.hazard {
+            Hazard::Heat => "🌡",
+            Hazard::Cold => "❄",
+        };
+        let (fill, accent) = match advisory.tier.severity {
+            Severity::Warning => (egui::Color32::from_rgb(127, 29, 29), Colors::ERROR_RED),
+            Severity::Advisory => (Colors::BG_CARD, Colors::ACCENT_ORANGE),
+        };
+
+        egui::Frame::none()
+            .fill(fill)
+            .rounding(8.0)
+            .inner_margin(12.0)
+            .show(ui, |ui| {
+                ui.set_width(ui.available_width());
+                describe(
+                    ui.label(
+                        egui::RichText::new(format!("{icon} {}", advisory.message()))
+                            .color(accent)
+                            .size(14.0)
+                            .strong(),
+                    ),
+                    advisory.message(),
+                );
+            });
+        ui.add_space(15.0);
+    }
+
+    fn display_fog_banner(&self, ui: &mut egui::Ui, weather: &WeatherInfo) {
+        let data = &weather.weather_data;
+        let Some(window) = data
+            .low_visibility_windows(FOG_VISIBILITY_THRESHOLD_M)
+            .into_iter()
+            .next()
+        else {
+            return;
+        };
+
+        egui::Frame::none()
+            .fill(Colors::BG_CARD)
+            .rounding(8.0)
+            .inner_margin(12.0)
+            .show(ui, |ui| {
+                ui.set_width(ui.available_width());
+                let message = window.message(weather.today(chrono::Utc::now()));
+                let spoken = format!("{}: {message}", weather_code_to_description(45));
+                describe(
+                    ui.label(
+                        egui::RichText::new(format!("{} {message}", weather_codes::icon(45)))
+                            .color(Colors::TEXT_PRIMARY)
+                            .size(14.0)
+                            .strong(),
+                    ),
+                    spoken,
+                );
+                if let Some(visibility) = window.min_visibility {
+                    ui.label(
+                        egui::RichText::new(
+                            tr(self.locale, Key::VisibilityDownTo)
+                                .replace("{}", &format!("{visibility:.0} m")),
+                        )
+                        .color(Colors::TEXT_SECONDARY)
+                        .size(12.0),
+                    );
+                }
+            });
+        ui.add_space(15.0);
+    }
+
+    fn display_storm_banner(&self, ui: &mut egui::Ui, weather: &WeatherInfo) {
+        let periods = storm::thunderstorm_periods(weather.weather_data.upcoming_hours(usize::MAX));
+        let Some(period) = periods
+            .iter()
+            .find(|p| p.severity == StormSeverity::Severe)
+            .or_else(|| periods.first())
+        else {
+            return;
+        };
+
+        let locale = self.locale;
+        let is_severe = period.severity == StormSeverity::Severe;
+        let (fill, accent, title) = if is_severe {
+            (
+                egui::Color32::from_rgb(127, 29, 29),
+                Colors::ERROR_RED,
+                tr(locale, Key::SevereThunderstorms),
+            )
+        } else {
+            (
+                egui::Color32::from_rgb(30, 58, 138),
+                Colors::ACCENT_YELLOW,
+                tr(locale, Key::Thunderstorms),
+            )
+        };
+
+        egui::Frame::none()
+            .fill(fill)
+            .rounding(8.0)
+            .inner_margin(12.0)
+            .show(ui, |ui| {
+                ui.set_width(ui.available_width());
+                ui.label(
+                    egui::RichText::new(format!(
+                        "⚡ {}",
+                        tr(locale, Key::StormExpected)
+                            .replacen("{}", title, 1)
+                            .replacen("{}", &extract_time(&period.start), 1)
+                            .replacen("{}", &extract_time(&period.end), 1)
+                    ))
+                    .color(accent)
+                    .size(14.0)
+                    .strong(),
+                );
+                if let Some(cape) = period.peak_cape.filter(|_| is_severe) {
+                    let level = storm::CapeLevel::from_cape(cape);
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "{}: {} ({cape:.0} J/kg)",
+                            tr(locale, Key::StormEnergy),
+                            tr(locale, cape_level_key(level))
+                        ))
+                        .color(Colors::TEXT_SECONDARY)
+                        .size(12.0),
+                    );
+                }
+            });
+        ui.add_space(15.0);
+    }
+
+    fn display_current_weather(&self, ui: &mut egui::Ui, weather: &WeatherInfo) {
+        let locale = self.locale;
+        let current = &weather.weather_data.current;
+        let show_mountain =
+            self.display_options.mountain || mountain::is_mountain_elevation(weather.elevation);
+        let freezing_level = weather
+            .weather_data
+            .current_hour()
+            .and_then(|h| h.freezing_level_height)
+            .filter(|_| show_mountain);
+        let snow_depth = current.snow_depth.filter(|d| *d > 0.0);
+        let snow_accumulation =
+            snow::snowfall_accumulation(&weather.weather_data, SNOW_ACCUMULATION_HOURS)
+                .filter(|a| a.total.total > 0.0);
+        let precipitation_outlook =
+            precipitation::outlook(&weather.weather_data, PRECIPITATION_OUTLOOK_HOURS);
+
+        ui.horizontal_top(|ui| {
+            // Main temperature card
+            egui::Frame::none()
+                .fill(Colors::BG_CARD)
+                .rounding(12.0)
+                .inner_margin(25.0)
+                .show(ui, |ui| {
+                    ui.set_min_width(240.0);
+                    ui.vertical_centered(|ui| {
+                        if let Some(code) = current.weather_code {
+                            describe(
+                                ui.label(
+                                    egui::RichText::new(weather_codes::icon(code))
+                                        .size(80.0)
+                                        .color(weather_code_to_color(code)),
+                                ),
+                                weather_code_to_description(code),
+                            );
+                        }
+
+                        ui.add_space(10.0);
+
+                        if let Some(temp) = current.temperature {
+                            // The headline number defaults to whole degrees
+                            let precision =
+                                self.display_options.precision.unwrap_or(Precision::Whole);
+                            let fahrenheit = format::fahrenheit(temp, precision);
+                            let celsius = format::celsius(temp, precision);
+                            describe(
+                                ui.label(
+                                    egui::RichText::new(&fahrenheit)
+                                        .size(52.0)
+                                        .strong()
+                                        .color(Colors::TEXT_PRIMARY),
+                                ),
+                                accessibility::speak(&fahrenheit),
+                            );
+                            describe(
+                                ui.label(
+                                    egui::RichText::new(&celsius)
+                                        .size(18.0)
+                                        .color(Colors::TEXT_SECONDARY),
+                                ),
+                                accessibility::speak(&celsius),
+                            );
+                        }
+
+                        if let Some(callout) = advice::feels_like_callout(current) {
+                            ui.label(
+                                egui::RichText::new(callout.message())
+                                    .size(13.0)
+                                    .italics()
+                                    .color(Colors::ACCENT_ORANGE),
+                            );
+                        }
+
+                        if let Some(advisory) =
+                            advisories::advisory(current, self.display_options.advisory_standard)
+                        {
+                            ui.label(
+                                egui::RichText::new(advisory.message())
+                                    .size(13.0)
+                                    .strong()
+                                    .color(Colors::ERROR_RED),
+                            );
+                        }
+
+                        ui.add_space(8.0);
+
+                        if let Some(code) = current.weather_code {
+                            ui.label(
+                                egui::RichText::new(weather_code_to_description(code))
+                                    .size(16.0)
+                                    .color(Colors::TEXT_SECONDARY),
+                            );
+                            if let Some(note) = precip_phase::phase_note(
+                                current.temperature,
+                                current.humidity,
+                                code,
+                            ) {
+                                ui.label(
+                                    egui::RichText::new(note)
+                                        .size(13.0)
+                                        .italics()
+                                        .color(Colors::TEXT_MUTED),
+                                );
+                            }
                         }
                     });
                 });
@@ -309,128 +1926,391 @@ impl WeatherApp {
                     egui::Grid::new("current_weather_grid")
                         .spacing([25.0, 18.0])
                         .show(ui, |ui| {
-                            if let Some(apparent_temp) = current.apparent_temperature {
-                                let apparent_f = Temperature::celsius_to_fahrenheit(apparent_temp);
-                                ui.label(
-                                    egui::RichText::new("Feels Like")
-                                        .color(Colors::TEXT_SECONDARY)
-                                        .size(14.0),
-                                );
-                                ui.label(
-                                    egui::RichText::new(format!(
-                                        "{apparent_f:.1}°F / {apparent_temp:.1}°C"
-                                    ))
-                                    .color(Colors::TEXT_PRIMARY)
-                                    .size(15.0),
+                            if let Some(apparent_temp) =
+                                advice::feels_like(current, self.display_options.feels_like_formula)
+                            {
+                                let precision = self.display_options.precision();
+                                display_grid_row(
+                                    ui,
+                                    tr(locale, Key::FeelsLike),
+                                    format!(
+                                        "{} / {}",
+                                        format::fahrenheit(apparent_temp, precision),
+                                        format::celsius(apparent_temp, precision)
+                                    ),
+                                    Colors::TEXT_PRIMARY,
                                 );
-                                ui.end_row();
                             }
 
                             if let Some(humidity) = current.humidity {
-                                ui.label(
-                                    egui::RichText::new("Humidity")
-                                        .color(Colors::TEXT_SECONDARY)
-                                        .size(14.0),
+                                display_grid_row(
+                                    ui,
+                                    tr(locale, Key::Humidity),
+                                    format!("{humidity:.0}%"),
+                                    Colors::ACCENT_CYAN,
                                 );
-                                ui.label(
-                                    egui::RichText::new(format!("{humidity:.0}%"))
-                                        .color(Colors::ACCENT_CYAN)
-                                        .size(15.0),
+                            }
+
+                            if let Some(dew_point) = current.dew_point {
+                                let precision = self.display_options.precision();
+                                display_grid_row(
+                                    ui,
+                                    tr(locale, Key::DewPoint),
+                                    format!(
+                                        "{} / {}",
+                                        format::fahrenheit(dew_point, precision),
+                                        format::celsius(dew_point, precision)
+                                    ),
+                                    Colors::ACCENT_CYAN,
                                 );
-                                ui.end_row();
                             }
 
+                            let wind_unit = self.display_options.wind_unit;
                             if let Some(wind) = current.wind_speed {
-                                ui.label(
-                                    egui::RichText::new("Wind Speed")
-                                        .color(Colors::TEXT_SECONDARY)
-                                        .size(14.0),
-                                );
-                                let mut wind_text = format!("{wind:.1} km/h");
+                                let mut wind_text = wind_speed_text(wind_unit, wind, 1);
                                 if let Some(direction) = current.wind_direction {
                                     wind_text.push_str(&format!(" ({}°)", direction as i32));
                                 }
-                                ui.label(
-                                    egui::RichText::new(wind_text)
-                                        .color(Colors::TEXT_PRIMARY)
-                                        .size(15.0),
+                                display_grid_row(
+                                    ui,
+                                    tr(locale, Key::WindSpeed),
+                                    wind_text,
+                                    Colors::TEXT_PRIMARY,
+                                );
+                            }
+
+                            if let Some(gusts) = current.wind_gusts {
+                                display_grid_row(
+                                    ui,
+                                    tr(locale, Key::WindGusts),
+                                    wind_speed_text(wind_unit, gusts, 1),
+                                    if current.notable_gusts().is_some() {
+                                        Colors::ACCENT_ORANGE
+                                    } else {
+                                        Colors::TEXT_PRIMARY
+                                    },
                                 );
-                                ui.end_row();
                             }
 
                             if let Some(precip) = current.precipitation {
-                                ui.label(
-                                    egui::RichText::new("Precipitation")
-                                        .color(Colors::TEXT_SECONDARY)
-                                        .size(14.0),
+                                display_grid_row(
+                                    ui,
+                                    tr(locale, Key::Precipitation),
+                                    format!("{precip:.1} mm"),
+                                    Colors::ACCENT_CYAN,
                                 );
-                                ui.label(
-                                    egui::RichText::new(format!("{precip:.1} mm"))
-                                        .color(Colors::ACCENT_CYAN)
-                                        .size(15.0),
+                            }
+
+                            if let Some(snowfall) =
+                                snowfall_to_show(current.snowfall, current.weather_code)
+                            {
+                                display_grid_row(
+                                    ui,
+                                    tr(locale, Key::Snowfall),
+                                    format!(
+                                        "{snowfall:.1} cm / {:.1} in",
+                                        Distance::cm_to_inches(snowfall)
+                                    ),
+                                    Colors::ACCENT_CYAN,
                                 );
-                                ui.end_row();
                             }
 
-                            if let Some(pressure) = current.pressure {
-                                ui.label(
-                                    egui::RichText::new("Pressure")
-                                        .color(Colors::TEXT_SECONDARY)
-                                        .size(14.0),
+                            if let Some((reference, pressure)) =
+                                current.pressure_reading(self.display_options.pressure)
+                            {
+                                let label = format!(
+                                    "{} ({})",
+                                    tr(locale, Key::Pressure),
+                                    tr(locale, pressure_key(reference))
                                 );
-                                ui.label(
-                                    egui::RichText::new(format!("{pressure:.0} hPa"))
-                                        .color(Colors::TEXT_PRIMARY)
-                                        .size(15.0),
+                                display_grid_row(
+                                    ui,
+                                    &label,
+                                    format!("{pressure:.0} hPa"),
+                                    Colors::TEXT_PRIMARY,
                                 );
-                                ui.end_row();
                             }
 
                             if let Some(cloud) = current.cloud_cover {
-                                ui.label(
-                                    egui::RichText::new("Cloud Cover")
-                                        .color(Colors::TEXT_SECONDARY)
-                                        .size(14.0),
+                                display_grid_row(
+                                    ui,
+                                    tr(locale, Key::CloudCover),
+                                    format!("{cloud:.0}%"),
+                                    Colors::TEXT_PRIMARY,
                                 );
-                                ui.label(
-                                    egui::RichText::new(format!("{cloud:.0}%"))
-                                        .color(Colors::TEXT_PRIMARY)
-                                        .size(15.0),
-                                );
-                                ui.end_row();
                             }
 
                             if let Some(visibility) = current.visibility {
-                                ui.label(
-                                    egui::RichText::new("Visibility")
-                                        .color(Colors::TEXT_SECONDARY)
-                                        .size(14.0),
+                                display_grid_row(
+                                    ui,
+                                    tr(locale, Key::Visibility),
+                                    format!("{:.1} km", visibility / 1000.0),
+                                    Colors::TEXT_PRIMARY,
                                 );
-                                ui.label(
-                                    egui::RichText::new(format!("{:.1} km", visibility / 1000.0))
-                                        .color(Colors::TEXT_PRIMARY)
-                                        .size(15.0),
+                            }
+
+                            if let Some(outlook) = &precipitation_outlook {
+                                display_grid_row(
+                                    ui,
+                                    &format!("{} {} h", tr(locale, Key::NextHours), outlook.hours),
+                                    outlook.amounts(),
+                                    Colors::ACCENT_CYAN,
+                                );
+                            }
+
+                            if snow_depth.is_some() || snow_accumulation.is_some() {
+                                let mut snow_text = match snow_depth {
+                                    Some(depth) => format!("{:.0} cm", depth * 100.0),
+                                    None => "0 cm".to_string(),
+                                };
+                                if let Some(accumulation) = &snow_accumulation {
+                                    let by = time::parse_hour(&accumulation.until).map_or_else(
+                                        || accumulation.until.clone(),
+                                        |t| tr(locale, weekday_key(t.weekday())).to_string(),
+                                    );
+                                    let total = format!("{:.0} cm", accumulation.total.total);
+                                    snow_text.push_str(&format!(
+                                        " ({})",
+                                        tr(locale, Key::SnowBy)
+                                            .replacen("{}", &total, 1)
+                                            .replacen("{}", &by, 1)
+                                    ));
+                                }
+                                display_grid_row(
+                                    ui,
+                                    tr(locale, Key::SnowDepth),
+                                    snow_text,
+                                    Colors::TEXT_PRIMARY,
+                                );
+                            }
+
+                            if let Some(freezing_level) = freezing_level {
+                                display_grid_row(
+                                    ui,
+                                    tr(locale, Key::FreezingLevel),
+                                    freezing_level_text(locale, freezing_level),
+                                    Colors::ACCENT_CYAN,
                                 );
-                                ui.end_row();
                             }
                         });
                 });
         });
+
+        self.display_marine_card(ui, weather);
+        self.display_wind_rose_card(ui, weather);
+    }
+
+    /// Polar chart of wind directions over the rest of the forecast; wedge
+    /// length is the share of hours, opacity the mean speed
+    fn display_wind_rose_card(&self, ui: &mut egui::Ui, weather: &WeatherInfo) {
+        let rose = weather.weather_data.wind_rose(WIND_ROSE_SECTORS);
+        if rose.counted == 0 {
+            return;
+        }
+        let locale = self.locale;
+
+        ui.add_space(15.0);
+        egui::Frame::none()
+            .fill(Colors::BG_CARD)
+            .rounding(12.0)
+            .inner_margin(20.0)
+            .show(ui, |ui| {
+                ui.set_min_width(ui.available_width());
+                ui.label(
+                    egui::RichText::new(format!("🧭 {}", tr(locale, Key::Wind)))
+                        .size(16.0)
+                        .strong()
+                        .color(Colors::TEXT_PRIMARY),
+                );
+                ui.add_space(8.0);
+                let (rect, response) =
+                    ui.allocate_exact_size(egui::vec2(200.0, 200.0), egui::Sense::hover());
+                paint_wind_rose(ui.painter(), rect, &rose);
+                if let Some(bin) = rose.prevailing() {
+                    let speed = bin.mean_speed.map_or(String::new(), |s| {
+                        let unit = self.display_options.wind_unit;
+                        format!(" · {:.0} {}", unit.from_kmh(s), unit.label())
+                    });
+                    response.on_hover_text(format!(
+                        "{} {:.0}%{speed}",
+                        Direction::compass_point(bin.center),
+                        bin.frequency * 100.0
+                    ));
+                }
+                ui.label(
+                    egui::RichText::new(
+                        tr(locale, Key::WindCoverage)
+                            .replace("{}", &format!("{:.0}", rose.coverage())),
+                    )
+                    .size(12.0)
+                    .color(Colors::TEXT_MUTED),
+                );
+            });
     }
 
-    fn display_hourly_forecast(&self, ui: &mut egui::Ui, weather: &WeatherInfo) {
-        ui.label(
-            egui::RichText::new("Next 24 Hours")
-                .size(20.0)
-                .strong()
-                .color(Colors::TEXT_PRIMARY),
+    /// Wave height, period and direction for the current hour; nothing inland
+    fn display_marine_card(&self, ui: &mut egui::Ui, weather: &WeatherInfo) {
+        let Some(hour) = weather.upcoming_marine(1).first() else {
+            return;
+        };
+        let locale = self.locale;
+        let rows = [
+            hour.wave_height.map(|height| {
+                let mut text = format!("{height:.1} m");
+                if let Some(direction) = hour.wave_direction {
+                    text.push_str(&format!(" {}", Direction::compass_point(direction)));
+                }
+                (Key::Waves, text)
+            }),
+            hour.wave_period
+                .map(|period| (Key::WavePeriod, format!("{period:.0} s"))),
+        ];
+
+        ui.add_space(15.0);
+        egui::Frame::none()
+            .fill(Colors::BG_CARD)
+            .rounding(12.0)
+            .inner_margin(20.0)
+            .show(ui, |ui| {
+                ui.set_min_width(ui.available_width());
+                ui.label(
+                    egui::RichText::new(format!("🌊 {}", tr(locale, Key::Marine)))
+                        .size(16.0)
+                        .strong()
+                        .color(Colors::TEXT_PRIMARY),
+                );
+                ui.add_space(8.0);
+                egui::Grid::new("marine_grid")
+                    .spacing([25.0, 12.0])
+                    .show(ui, |ui| {
+                        for (key, value) in rows.into_iter().flatten() {
+                            ui.label(
+                                egui::RichText::new(tr(locale, key))
+                                    .color(Colors::TEXT_SECONDARY)
+                                    .size(14.0),
+                            );
+                            ui.label(
+                                egui::RichText::new(value)
+                                    .color(Colors::ACCENT_CYAN)
+                                    .size(15.0),
+                            );
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    /// Chart and cards from the current hour for the selected range; returns a
+    /// newly picked range or a request to save the chart
+    fn display_hourly_forecast(
+        &self,
+        ui: &mut egui::Ui,
+        weather: &WeatherInfo,
+    ) -> Option<HourlyAction> {
+        let locale = self.locale;
+        if weather.weather_data.hourly.is_empty() {
+            display_empty_state(ui, locale, Key::NoHourlyData);
+            return None;
+        }
+
+        let selected = self.hourly_range;
+        let window = selected.window(
+            weather.weather_data.hourly_from_today(),
+            weather.location_now(chrono::Utc::now()),
         );
+        let mut picked = None;
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new(tr(locale, Key::TabHourly))
+                    .size(20.0)
+                    .strong()
+                    .color(Colors::TEXT_PRIMARY),
+            );
+            ui.add_space(12.0);
+            for range in HourlyRange::ALL {
+                let label = match range {
+                    HourlyRange::All => tr(locale, Key::AllHours).to_string(),
+                    _ => range.to_string(),
+                };
+                if ui.selectable_label(range == selected, label).clicked() && range != selected {
+                    picked = Some(HourlyAction::PickRange(range));
+                }
+            }
+            if window.is_clamped() {
+                ui.label(
+                    egui::RichText::new(
+                        tr(locale, Key::HoursAvailable)
+                            .replace("{}", &window.hours.len().to_string()),
+                    )
+                    .size(12.0)
+                    .color(Colors::TEXT_MUTED),
+                );
+            }
+        });
         ui.add_space(12.0);
 
+        if !window.hours.is_empty() {
+            let size = egui::vec2(
+                (ui.available_width() - CHART_BUTTON_WIDTH).max(1.0),
+                HOURLY_CHART_HEIGHT,
+            );
+            let pixels_per_point = ui.ctx().pixels_per_point();
+            let max_side = ui.ctx().input(|i| i.max_texture_side) as f32;
+            let (width, height) = (
+                (size.x * pixels_per_point).clamp(1.0, max_side) as u32,
+                (size.y * pixels_per_point).clamp(1.0, max_side) as u32,
+            );
+            // Redraw only when the hours or the size change, not every frame
+            let key = chart_key(window.hours, width, height);
+            let id = egui::Id::new("hourly_chart");
+            let texture = match ui
+                .ctx()
+                .data(|data| data.get_temp::<(u64, egui::TextureHandle)>(id))
+            {
+                Some((cached, texture)) if cached == key => texture,
+                _ => {
+                    let image = chart_image::render(
+                        window.hours,
+                        width,
+                        height,
+                        pixels_per_point.round() as u32,
+                        &ChartTheme::default(),
+                    );
+                    let texture = ui.ctx().load_texture(
+                        "hourly_chart",
+                        egui::ColorImage::from_rgba_unmultiplied(
+                            [width as usize, height as usize],
+                            &image.pixels,
+                        ),
+                        egui::TextureOptions::LINEAR,
+                    );
+                    // The texture must outlive this frame's paint; the next chart replaces it
+                    ui.ctx()
+                        .data_mut(|data| data.insert_temp(id, (key, texture.clone())));
+                    texture
+                }
+            };
+            ui.horizontal(|ui| {
+                ui.image((texture.id(), size));
+                if ui
+                    .button("📷")
+                    .on_hover_text(tr(locale, Key::SaveChart))
+                    .clicked()
+                {
+                    picked = Some(HourlyAction::SaveChart {
+                        width: (width as f32 * CHART_EXPORT_SCALE) as u32,
+                        height: (height as f32 * CHART_EXPORT_SCALE) as u32,
+                    });
+                }
+            });
+            self.display_hourly_stats(ui, &HourlyStats::from_hours(window.hours));
+            ui.add_space(12.0);
+        }
+
         egui::ScrollArea::horizontal().show(ui, |ui| {
             ui.horizontal(|ui| {
-                for hour in weather.weather_data.hourly.iter().take(24) {
-                    egui::Frame::none()
+                for hour in window.hours {
+                    let card = egui::Frame::none()
                         .fill(Colors::BG_CARD)
                         .rounding(10.0)
                         .inner_margin(15.0)
@@ -449,33 +2329,67 @@ impl WeatherApp {
                                             .color(Colors::TEXT_SECONDARY),
                                     );
                                 }
+                                if let Some(change) = hour.clock_change {
+                                    let key = match change {
+                                        ClockChange::Forward(_) => Key::ClocksForward,
+                                        ClockChange::Back(_) => Key::ClocksBack,
+                                    };
+                                    ui.label(
+                                        egui::RichText::new(tr(locale, key))
+                                            .size(10.0)
+                                            .color(Colors::ACCENT_CYAN),
+                                    )
+                                    .on_hover_text(change.note());
+                                }
 
                                 ui.add_space(8.0);
 
                                 // Weather icon
                                 if let Some(code) = hour.weather_code {
-                                    ui.label(
-                                        egui::RichText::new(weather_code_to_icon(code))
+                                    describe(
+                                        ui.label(
+                                            egui::RichText::new(
+                                                weather.hour_light(hour).map_or_else(
+                                                    || weather_codes::icon(code),
+                                                    |light| weather_codes::icon_at(code, light),
+                                                ),
+                                            )
                                             .size(36.0)
                                             .color(weather_code_to_color(code)),
+                                        ),
+                                        weather_code_to_description(code),
                                     );
                                     ui.label(
                                         egui::RichText::new(weather_code_to_description(code))
                                             .size(10.0)
                                             .color(Colors::TEXT_MUTED),
                                     );
+                                    if let Some(note) = precip_phase::phase_note(
+                                        hour.temperature,
+                                        hour.humidity,
+                                        code,
+                                    ) {
+                                        ui.label(
+                                            egui::RichText::new(note)
+                                                .size(9.0)
+                                                .italics()
+                                                .color(Colors::TEXT_MUTED),
+                                        );
+                                    }
                                 }
 
                                 ui.add_space(8.0);
 
                                 // Temperature
                                 if let Some(temp) = hour.temperature {
-                                    let temp_f = Temperature::celsius_to_fahrenheit(temp);
                                     ui.label(
-                                        egui::RichText::new(format!("{temp_f:.1}°F"))
-                                            .size(18.0)
-                                            .strong()
-                                            .color(Colors::TEXT_PRIMARY),
+                                        egui::RichText::new(format::fahrenheit(
+                                            temp,
+                                            self.display_options.precision(),
+                                        ))
+                                        .size(18.0)
+                                        .strong()
+                                        .color(Colors::TEXT_PRIMARY),
                                     );
                                 }
 
@@ -492,39 +2406,121 @@ impl WeatherApp {
                                     }
                                 }
 
-                                // Wind
+                                // Wind, with an arrow pointing downwind
                                 if let Some(wind) = hour.wind_speed {
+                                    let mut wind_text =
+                                        wind_speed_text(self.display_options.wind_unit, wind, 0);
+                                    if let Some(direction) = hour.wind_direction {
+                                        wind_text.push(' ');
+                                        wind_text.push_str(Direction::arrow(direction));
+                                    }
                                     ui.label(
-                                        egui::RichText::new(format!("{wind:.0} km/h"))
+                                        egui::RichText::new(wind_text)
                                             .size(11.0)
                                             .color(Colors::TEXT_MUTED),
                                     );
                                 }
+
+                                // Sky, one short line each, for stargazers and pilots
+                                if let Some(clouds) = hour.cloud_cover {
+                                    describe(
+                                        ui.label(
+                                            egui::RichText::new(format!("☁ {clouds:.0}%"))
+                                                .size(11.0)
+                                                .color(Colors::TEXT_MUTED),
+                                        ),
+                                        accessibility::labeled_value(
+                                            tr(locale, Key::CloudCover),
+                                            &format!("{clouds:.0}%"),
+                                        ),
+                                    );
+                                }
+                                if let Some(visibility) = hour.visibility {
+                                    let distance = format::visibility(visibility);
+                                    describe(
+                                        ui.label(
+                                            egui::RichText::new(format!("👁 {distance}"))
+                                                .size(11.0)
+                                                .color(Colors::TEXT_MUTED),
+                                        ),
+                                        accessibility::labeled_value(
+                                            tr(locale, Key::Visibility),
+                                            &distance,
+                                        ),
+                                    );
+                                }
                             });
                         });
+                    let aloft = self.display_options.winds_aloft
+                        && aviation::has_winds_aloft(std::slice::from_ref(hour));
+                    if aloft || hour.wind_direction.is_some() {
+                        card.response.on_hover_ui(|ui| {
+                            if let Some(direction) = hour.wind_direction {
+                                ui.label(format!(
+                                    "{}: {} {} ({direction:.0}°)",
+                                    tr(locale, Key::Wind),
+                                    Direction::arrow(direction),
+                                    Direction::compass_point(direction)
+                                ));
+                            }
+                            if !aloft {
+                                return;
+                            }
+                            for level in WindLevel::ALL {
+                                let (speed, direction) = level.wind(hour);
+                                ui.label(format!(
+                                    "{}: {}",
+                                    level.label(),
+                                    aviation::wind_text(
+                                        speed,
+                                        direction,
+                                        self.display_options.wind_unit
+                                    )
+                                ));
+                            }
+                        });
+                    }
 
                     ui.add_space(8.0);
                 }
             });
         });
+        picked
     }
 
     fn display_daily_forecast(&self, ui: &mut egui::Ui, weather: &WeatherInfo) {
+        let locale = self.locale;
+        let widths = self.daily_column_widths();
+        let daily = weather.weather_data.daily_from_today();
+        if daily.is_empty() {
+            display_empty_state(ui, locale, Key::NoDailyData);
+            return;
+        }
+
         ui.label(
-            egui::RichText::new("7-Day Forecast")
+            egui::RichText::new(tr(locale, Key::SevenDayForecast))
                 .size(20.0)
                 .strong()
                 .color(Colors::TEXT_PRIMARY),
         );
         ui.add_space(12.0);
 
-        for day in weather.weather_data.daily.iter().take(7) {
+        let has_humidity = daily.iter().any(|d| d.humidity_mean.is_some());
+        let has_uv = daily.iter().any(|d| d.uv_index_max.is_some());
+
+        for day in daily.iter().take(7) {
             egui::Frame::none()
                 .fill(Colors::BG_CARD)
                 .rounding(10.0)
                 .inner_margin(18.0)
                 .show(ui, |ui| {
                     ui.set_width(ui.available_width());
+                    let (show_humidity, show_uv) = widths.optional_columns(
+                        ui.available_width(),
+                        self.display_options.garden,
+                        has_humidity,
+                        has_uv,
+                    );
 
                     ui.horizontal(|ui| {
                         // Date
@@ -544,10 +2540,13 @@ impl WeatherApp {
                         // Weather icon
                         if let Some(code) = day.weather_code {
                             ui.vertical(|ui| {
-                                ui.label(
-                                    egui::RichText::new(weather_code_to_icon(code))
-                                        .size(32.0)
-                                        .color(weather_code_to_color(code)),
+                                describe(
+                                    ui.label(
+                                        egui::RichText::new(weather_codes::icon(code))
+                                            .size(32.0)
+                                            .color(weather_code_to_color(code)),
+                                    ),
+                                    weather_code_to_description(code),
                                 );
                                 ui.label(
                                     egui::RichText::new(weather_code_to_description(code))
@@ -560,42 +2559,94 @@ impl WeatherApp {
                         ui.add_space(15.0);
 
                         // Temperature range
-                        ui.vertical(|ui| {
-                            ui.set_width(180.0);
+                        let range = ui.vertical(|ui| {
+                            ui.set_width(widths.temperature);
                             if let (Some(max), Some(min)) =
                                 (day.temperature_max, day.temperature_min)
                             {
-                                let max_f = Temperature::celsius_to_fahrenheit(max);
-                                let min_f = Temperature::celsius_to_fahrenheit(min);
-                                ui.label(
-                                    egui::RichText::new(format!("High: {max_f:.1}°F / {max:.1}°C"))
-                                        .color(Colors::ACCENT_ORANGE)
-                                        .size(14.0),
+                                let precision = self.display_options.precision();
+                                let high = ui.label(
+                                    egui::RichText::new(format!(
+                                        "{}: {} / {}",
+                                        tr(locale, Key::High),
+                                        format::fahrenheit(max, precision),
+                                        format::celsius(max, precision)
+                                    ))
+                                    .color(Colors::ACCENT_ORANGE)
+                                    .size(14.0),
                                 );
-                                ui.label(
-                                    egui::RichText::new(format!("Low:  {min_f:.1}°F / {min:.1}°C"))
-                                        .color(Colors::ACCENT_CYAN)
-                                        .size(14.0),
+                                let low = ui.label(
+                                    egui::RichText::new(format!(
+                                        "{}: {} / {}",
+                                        tr(locale, Key::Low),
+                                        format::fahrenheit(min, precision),
+                                        format::celsius(min, precision)
+                                    ))
+                                    .color(Colors::ACCENT_CYAN)
+                                    .size(14.0),
                                 );
+                                return Some(high.rect.union(low.rect));
                             }
+                            None
                         });
+                        if let (Some(rect), Some(confidence @ Confidence::Low)) =
+                            (range.inner, day.confidence)
+                        {
+                            mark_low_confidence(ui, rect, &day.date, confidence, locale);
+                        }
 
                         ui.add_space(15.0);
 
                         // Precipitation
                         if let Some(precip_prob) = day.precipitation_probability {
                             ui.vertical(|ui| {
-                                ui.set_width(80.0);
+                                ui.set_width(widths.label);
                                 ui.label(
-                                    egui::RichText::new("Precip")
+                                    egui::RichText::new(tr(locale, Key::Precip))
                                         .color(Colors::TEXT_SECONDARY)
                                         .size(13.0),
                                 );
+                                // The daily value is the peak hour, so say so
+                                // when the hourly data gives the wet hours too
+                                let peak = format!("{precip_prob:.0}");
+                                let peak = match day.wet_hours {
+                                    Some(_) => tr(locale, Key::PrecipPeak).replace("{}", &peak),
+                                    None => format!("{peak}%"),
+                                };
                                 ui.label(
-                                    egui::RichText::new(format!("{precip_prob:.0}%"))
+                                    egui::RichText::new(peak)
                                         .color(Colors::ACCENT_CYAN)
                                         .size(14.0),
                                 );
+                                if let Some(wet_hours) = day.wet_hours {
+                                    ui.label(
+                                        egui::RichText::new(
+                                            tr(locale, Key::WetHours)
+                                                .replace("{}", &wet_hours.to_string()),
+                                        )
+                                        .color(Colors::TEXT_MUTED)
+                                        .size(12.0),
+                                    );
+                                }
+                                if let Some(snowfall) =
+                                    snowfall_to_show(day.snowfall_sum, day.weather_code)
+                                {
+                                    let amount = format!(
+                                        "{snowfall:.1} cm / {:.1} in",
+                                        Distance::cm_to_inches(snowfall)
+                                    );
+                                    describe(
+                                        ui.label(
+                                            egui::RichText::new(format!("❄ {amount}"))
+                                                .color(Colors::ACCENT_CYAN)
+                                                .size(12.0),
+                                        ),
+                                        accessibility::labeled_value(
+                                            tr(locale, Key::Snowfall),
+                                            &amount,
+                                        ),
+                                    );
+                                }
                             });
                         }
 
@@ -604,22 +2655,64 @@ impl WeatherApp {
                         // Wind
                         if let Some(wind) = day.wind_speed_max {
                             ui.vertical(|ui| {
-                                ui.set_width(80.0);
+                                ui.set_width(widths.label);
                                 ui.label(
-                                    egui::RichText::new("Wind")
+                                    egui::RichText::new(tr(locale, Key::Wind))
                                         .color(Colors::TEXT_SECONDARY)
                                         .size(13.0),
                                 );
                                 ui.label(
-                                    egui::RichText::new(format!("{wind:.0} km/h"))
-                                        .color(Colors::TEXT_PRIMARY)
-                                        .size(14.0),
+                                    egui::RichText::new(wind_speed_text(
+                                        self.display_options.wind_unit,
+                                        wind,
+                                        0,
+                                    ))
+                                    .color(Colors::TEXT_PRIMARY)
+                                    .size(14.0),
                                 );
                             });
                         }
 
                         ui.add_space(10.0);
 
+                        // Humidity and UV, when there is room for them
+                        if show_humidity {
+                            let humidity = day
+                                .humidity_mean
+                                .map_or_else(|| "-".to_string(), |h| format!("{h:.0}%"));
+                            display_label_column(
+                                ui,
+                                widths.label,
+                                tr(locale, Key::Humidity),
+                                egui::RichText::new(humidity).color(Colors::TEXT_PRIMARY),
+                            );
+                        }
+                        if show_uv {
+                            let uv = match day.uv_index_max {
+                                Some(uv) => egui::RichText::new(format!("{uv:.0}"))
+                                    .color(uv_category_color(UvCategory::from_index(uv))),
+                                None => egui::RichText::new("-").color(Colors::TEXT_PRIMARY),
+                            };
+                            display_label_column(ui, widths.label, tr(locale, Key::UvIndex), uv);
+                            let skin_type = self.display_options.skin_type;
+                            if let (Some(uv), Some(skin_type)) = (day.uv_index_max, skin_type) {
+                                let note = match uv::exposure_time_text(uv, skin_type) {
+                                    Some(time) => tr(locale, Key::TimeToBurn).replace("{}", &time),
+                                    None => tr(locale, Key::UvLowRisk).to_string(),
+                                };
+                                ui.label(
+                                    egui::RichText::new(note)
+                                        .color(Colors::TEXT_SECONDARY)
+                                        .size(12.0),
+                                );
+                            }
+                        }
+
+                        // Irrigation need
+                        if self.display_options.garden {
+                            self.display_water_need(ui, day);
+                        }
+
                         // Sun times
                         self.display_sun_times(ui, day);
                     });
@@ -629,45 +2722,292 @@ impl WeatherApp {
         }
     }
 
+    fn display_water_need(&self, ui: &mut egui::Ui, day: &DailyForecast) {
+        let locale = self.locale;
+        let widths = self.daily_column_widths();
+        let Some((_, need)) = agriculture::irrigation_deficit(std::slice::from_ref(day))
+            .into_iter()
+            .next()
+        else {
+            return;
+        };
+
+        ui.vertical(|ui| {
+            ui.set_width(widths.label);
+            ui.label(
+                egui::RichText::new(tr(locale, Key::Water))
+                    .color(Colors::TEXT_SECONDARY)
+                    .size(13.0),
+            );
+            ui.label(
+                egui::RichText::new(format!("{need:.1} mm"))
+                    .color(Colors::ACCENT_GREEN)
+                    .size(14.0),
+            );
+        });
+        ui.add_space(10.0);
+    }
+
     fn display_sun_times(&self, ui: &mut egui::Ui, day: &DailyForecast) {
+        let locale = self.locale;
+        let widths = self.daily_column_widths();
+        let latitude = self.weather_info.as_ref().map_or(0.0, |info| info.latitude);
         ui.vertical(|ui| {
-            ui.set_width(70.0);
-            if let Some(sunrise) = &day.sunrise {
-                let time = extract_time(sunrise);
-                ui.label(
-                    egui::RichText::new(format!("Rise {time}"))
-                        .color(Colors::ACCENT_YELLOW)
-                        .size(12.0),
-                );
+            ui.set_width(widths.sun);
+            let polar = match day.daylight(latitude) {
+                Daylight::RisesAndSets => None,
+                Daylight::PolarDay => Some((Key::PolarDay, Colors::ACCENT_YELLOW)),
+                Daylight::PolarNight => Some((Key::PolarNight, Colors::TEXT_SECONDARY)),
+            };
+            if let Some((key, color)) = polar {
+                ui.label(egui::RichText::new(tr(locale, key)).color(color).size(12.0));
+            } else {
+                if let Some(sunrise) = &day.sunrise {
+                    let time = extract_time(sunrise);
+                    ui.label(
+                        egui::RichText::new(format!("{} {time}", tr(locale, Key::Sunrise)))
+                            .color(Colors::ACCENT_YELLOW)
+                            .size(12.0),
+                    );
+                }
+                if let Some(sunset) = &day.sunset {
+                    let time = extract_time(sunset);
+                    ui.label(
+                        egui::RichText::new(format!("{} {time}", tr(locale, Key::Sunset)))
+                            .color(Colors::ACCENT_ORANGE)
+                            .size(12.0),
+                    );
+                }
             }
-            if let Some(sunset) = &day.sunset {
-                let time = extract_time(sunset);
-                ui.label(
-                    egui::RichText::new(format!("Set {time}"))
-                        .color(Colors::ACCENT_ORANGE)
+            let durations = [
+                (
+                    Key::DaylightDuration,
+                    day.daylight_duration,
+                    Colors::TEXT_SECONDARY,
+                ),
+                (
+                    Key::SunshineDuration,
+                    day.sunshine_duration,
+                    Colors::ACCENT_YELLOW,
+                ),
+            ];
+            for (key, seconds, color) in durations {
+                if let Some(seconds) = seconds {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "{} {}",
+                            tr(locale, key),
+                            format::hours_minutes(seconds)
+                        ))
+                        .color(color)
                         .size(12.0),
-                );
+                    );
+                }
             }
         });
     }
 }
 
 // Helper functions
-fn weather_code_to_icon(code: i32) -> &'static str {
-    match code {
-        0 => "☀",        // Clear sky - sun (U+2600)
-        1..=3 => "⛅",   // Partly cloudy (U+26C5)
-        45 | 48 => "~",  // Fog - horizontal lines
-        51..=57 => "☂",  // Drizzle - umbrella (U+2602)
-        61..=67 => "☔", // Rain - umbrella with rain (U+2614)
-        71..=77 => "❄",  // Snow (U+2744)
-        80..=82 => "☔", // Rain showers - umbrella (U+2614)
-        85 | 86 => "❄",  // Snow (U+2744)
-        95..=99 => "⚡", // Thunderstorm - lightning bolt (U+26A1)
-        _ => "○",        // Default - simple circle (U+25CB)
+/// Identifies the drawn chart: the plotted values of `hours` and the pixel size
+fn chart_key(hours: &[HourlyForecast], width: u32, height: u32) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    (width, height).hash(&mut hasher);
+    for hour in hours {
+        hour.time.hash(&mut hasher);
+        hour.temperature.map(f64::to_bits).hash(&mut hasher);
+        hour.precipitation_probability
+            .map(f64::to_bits)
+            .hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Native save dialog for the chart of `range`, opened in the pictures
+/// directory (downloads or home as fallbacks) with the default file name
+fn chart_save_dialog(weather: &WeatherInfo, range: HourlyRange) -> rfd::FileDialog {
+    let now = weather.location_now(chrono::Utc::now());
+    let window = range.window(weather.weather_data.hourly_from_today(), now);
+    let date = window
+        .hours
+        .first()
+        .and_then(|h| h.time.split('T').next())
+        .unwrap_or_default();
+    let dialog = rfd::FileDialog::new()
+        .add_filter("PNG", &["png"])
+        .set_file_name(chart_image::default_file_name(&weather.location, date));
+    match dirs::picture_dir()
+        .or_else(dirs::download_dir)
+        .or_else(dirs::home_dir)
+    {
+        Some(dir) => dialog.set_directory(dir),
+        None => dialog,
+    }
+}
+
+/// Renders the hourly chart for `range` at `width × height` pixels into `path`
+fn save_hourly_chart(
+    weather: &WeatherInfo,
+    range: HourlyRange,
+    width: u32,
+    height: u32,
+    path: &std::path::Path,
+) -> Result<(), WeatherError> {
+    let now = weather.location_now(chrono::Utc::now());
+    let window = range.window(weather.weather_data.hourly_from_today(), now);
+    let image = chart_image::render(
+        window.hours,
+        width,
+        height,
+        CHART_EXPORT_SCALE as u32,
+        &ChartTheme::default(),
+    );
+    image.save(path)
+}
+
+fn display_empty_state(ui: &mut egui::Ui, locale: Locale, message: Key) {
+    ui.vertical_centered(|ui| {
+        ui.add_space(60.0);
+        ui.label(
+            egui::RichText::new(tr(locale, message))
+                .size(18.0)
+                .color(Colors::TEXT_MUTED),
+        );
+        ui.add_space(6.0);
+        ui.label(
+            egui::RichText::new(tr(locale, Key::TryAgain))
+                .size(13.0)
+                .color(Colors::TEXT_MUTED),
+        );
+    });
+}
+
+/// Daily-row column with a muted label above its value
+fn display_label_column(ui: &mut egui::Ui, width: f32, label: &str, value: egui::RichText) {
+    ui.vertical(|ui| {
+        ui.set_width(width);
+        ui.label(
+            egui::RichText::new(label)
+                .color(Colors::TEXT_SECONDARY)
+                .size(13.0),
+        );
+        let spoken = accessibility::labeled_value(label, value.text());
+        describe(ui.label(value.size(14.0)), spoken);
+    });
+    ui.add_space(10.0);
+}
+
+/// Current-weather grid row: a muted label, then its value read out together
+/// with the label ("Humidity 62 percent")
+fn display_grid_row(ui: &mut egui::Ui, label: &str, value: String, color: egui::Color32) {
+    ui.label(
+        egui::RichText::new(label)
+            .color(Colors::TEXT_SECONDARY)
+            .size(14.0),
+    );
+    let spoken = accessibility::labeled_value(label, &value);
+    describe(
+        ui.label(egui::RichText::new(value).color(color).size(15.0)),
+        spoken,
+    );
+    ui.end_row();
+}
+
+/// Dotted underline below a day's temperatures, with a tooltip saying why
+fn mark_low_confidence(
+    ui: &mut egui::Ui,
+    rect: egui::Rect,
+    date: &str,
+    confidence: Confidence,
+    locale: Locale,
+) {
+    let y = rect.bottom() + 2.0;
+    ui.painter().extend(egui::Shape::dotted_line(
+        &[egui::pos2(rect.left(), y), egui::pos2(rect.right(), y)],
+        Colors::TEXT_MUTED,
+        4.0,
+        1.0,
+    ));
+    ui.interact(
+        rect,
+        egui::Id::new(("confidence", date)),
+        egui::Sense::hover(),
+    )
+    .on_hover_text(confidence.description(locale));
+}
+
+/// Replaces what screen readers announce for a label, e.g. the description
+/// of a weather icon instead of the emoji
+fn describe(response: egui::Response, text: impl Into<String>) -> egui::Response {
+    let text = text.into();
+    response.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Label, true, &text));
+    response
+}
+
+fn uv_category_color(category: UvCategory) -> egui::Color32 {
+    match category {
+        UvCategory::Low => Colors::ACCENT_GREEN,
+        UvCategory::Moderate => Colors::ACCENT_YELLOW,
+        UvCategory::High => Colors::ACCENT_ORANGE,
+        UvCategory::VeryHigh => Colors::ERROR_RED,
+        UvCategory::Extreme => Colors::UV_EXTREME,
+    }
+}
+
+/// Label of a pressure reference in the interface language
+fn pressure_key(reference: PressureReference) -> Key {
+    match reference {
+        PressureReference::SeaLevel => Key::SeaLevel,
+        PressureReference::Surface => Key::Surface,
+    }
+}
+
+/// Wind speed in the chosen unit with `decimals` places, e.g. "12.4 kn"
+fn wind_speed_text(unit: WindUnit, kmh: f64, decimals: usize) -> String {
+    format!("{:.decimals$} {}", unit.from_kmh(kmh), unit.label())
+}
+
+/// Freezing level and snow line in meters and feet, as in the CLI report
+fn freezing_level_text(locale: Locale, freezing_level: f64) -> String {
+    let height = |m: f64| format!("{m:.0} m / {:.0} ft", Distance::meters_to_feet(m));
+    let snow_line = mountain::snow_line(freezing_level);
+    format!(
+        "{} ({})",
+        height(freezing_level),
+        tr(locale, Key::SnowAbove).replace("{}", &height(snow_line))
+    )
+}
+
+/// Name of a storm energy level in the interface language
+fn cape_level_key(level: storm::CapeLevel) -> Key {
+    match level {
+        storm::CapeLevel::Marginal => Key::CapeMarginal,
+        storm::CapeLevel::Moderate => Key::CapeModerate,
+        storm::CapeLevel::High => Key::CapeHigh,
+        storm::CapeLevel::Extreme => Key::CapeExtreme,
     }
 }
 
+/// Name of a weekday in the interface language
+fn weekday_key(weekday: chrono::Weekday) -> Key {
+    match weekday {
+        chrono::Weekday::Mon => Key::Monday,
+        chrono::Weekday::Tue => Key::Tuesday,
+        chrono::Weekday::Wed => Key::Wednesday,
+        chrono::Weekday::Thu => Key::Thursday,
+        chrono::Weekday::Fri => Key::Friday,
+        chrono::Weekday::Sat => Key::Saturday,
+        chrono::Weekday::Sun => Key::Sunday,
+    }
+}
+
+/// Snowfall (cm) worth a line: any amount, or none so far while the weather
+/// code says snow
+fn snowfall_to_show(snowfall: Option<f64>, code: Option<i32>) -> Option<f64> {
+    snowfall.filter(|cm| *cm > 0.0 || code.is_some_and(weather_codes::is_snow))
+}
+
 fn weather_code_to_color(code: i32) -> egui::Color32 {
     match code {
         0 => Colors::ACCENT_YELLOW,      // Clear - yellow/sun
@@ -745,6 +3085,69 @@ fn extract_time(datetime_str: &str) -> String {
     datetime_str.to_string()
 }
 
+/// Vertical gradient from `accents.gradient_top` to `accents.gradient_bottom`
+fn paint_gradient(ui: &egui::Ui, rect: egui::Rect, accents: &ThemeAccents) {
+    let mut mesh = egui::Mesh::default();
+    mesh.colored_vertex(rect.left_top(), accents.gradient_top);
+    mesh.colored_vertex(rect.right_top(), accents.gradient_top);
+    mesh.colored_vertex(rect.right_bottom(), accents.gradient_bottom);
+    mesh.colored_vertex(rect.left_bottom(), accents.gradient_bottom);
+    mesh.add_triangle(0, 1, 2);
+    mesh.add_triangle(0, 2, 3);
+    ui.painter().add(egui::Shape::mesh(mesh));
+}
+
+/// Rings at quarters of the largest share, one wedge per sector and the
+/// cardinal points, with north up
+fn paint_wind_rose(painter: &egui::Painter, rect: egui::Rect, rose: &WindRose) {
+    let center = rect.center();
+    let radius = rect.width().min(rect.height()) / 2.0 - 14.0;
+    let point = |bearing: f64, r: f32| {
+        let angle = bearing.to_radians() as f32;
+        center + egui::vec2(angle.sin(), -angle.cos()) * r
+    };
+    let ring = egui::Stroke::new(1.0, Colors::BG_SECONDARY);
+    for quarter in 1..=4 {
+        painter.circle_stroke(center, radius * quarter as f32 / 4.0, ring);
+    }
+
+    let max_share = rose.max_frequency();
+    let max_speed = rose
+        .bins
+        .iter()
+        .filter_map(|bin| bin.mean_speed)
+        .fold(0.0, f64::max);
+    let half_width = 180.0 / rose.bins.len() as f64;
+    for bin in rose.bins.iter().filter(|bin| bin.hours > 0) {
+        let r = (bin.frequency / max_share) as f32 * radius;
+        let strength = match (bin.mean_speed, max_speed > 0.0) {
+            (Some(speed), true) => (speed / max_speed) as f32,
+            _ => 0.0,
+        };
+        let fill = Colors::ACCENT_CYAN.gamma_multiply(0.3 + 0.7 * strength);
+        let mut points = vec![center];
+        for step in 0..=4 {
+            let bearing = bin.center - half_width + half_width * step as f64 / 2.0;
+            points.push(point(bearing, r));
+        }
+        painter.add(egui::Shape::convex_polygon(
+            points,
+            fill,
+            egui::Stroke::NONE,
+        ));
+    }
+
+    for (bearing, label) in [(0.0, "N"), (90.0, "E"), (180.0, "S"), (270.0, "W")] {
+        painter.text(
+            point(bearing, radius + 8.0),
+            egui::Align2::CENTER_CENTER,
+            label,
+            egui::FontId::proportional(12.0),
+            Colors::TEXT_SECONDARY,
+        );
+    }
+}
+
 fn create_custom_visuals() -> egui::Visuals {
     let mut visuals = egui::Visuals::dark();
 
@@ -765,3 +3168,387 @@ fn create_custom_visuals() -> egui::Visuals {
 
     visuals
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::weather_info::WeatherData;
+
+    fn render(panel: &WeatherPanel, weather: &WeatherInfo) {
+        let ctx = egui::Context::default();
+        let _ = ctx.run(egui::RawInput::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                panel.display_banners(ui, weather);
+                panel.display_current_weather(ui, weather);
+                panel.display_hourly_forecast(ui, weather);
+                panel.display_daily_forecast(ui, weather);
+            });
+        });
+    }
+
+    /// Runs one frame of `panel.ui` in a fresh context
+    fn show(panel: &mut WeatherPanel) {
+        let ctx = egui::Context::default();
+        let _ = ctx.run(egui::RawInput::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| panel.ui(ui));
+        });
+    }
+
+    #[test]
+    fn test_tabs_render_with_empty_data() {
+        let weather = WeatherInfo::new("Nowhere".to_string(), 0.0, 0.0, WeatherData::default());
+        let mut panel = WeatherPanel::default();
+        render(&panel, &weather);
+
+        panel.display_options_mut().mountain = true;
+        panel.display_options_mut().garden = true;
+        render(&panel, &weather);
+    }
+
+    #[test]
+    fn test_column_widths_fit_every_locale() {
+        let ctx = egui::Context::default();
+        let mut widths = None;
+        let _ = ctx.run(egui::RawInput::default(), |ctx| {
+            widths = Some(DailyColumnWidths::measure(ctx));
+        });
+        let widths = widths.unwrap();
+
+        // German "Niederschlag" and "Bewässerung" are wider than the English minimum
+        assert!(widths.label > DailyColumnWidths::MIN.label);
+        assert!(widths.sun > DailyColumnWidths::MIN.sun);
+
+        let mut panel = WeatherPanel::default().with_locale(Locale::De);
+        panel.column_widths = Some(widths);
+        render(&panel, &crate::fixtures::sample_weather_info());
+    }
+
+    #[test]
+    fn test_snowfall_shown_when_snowing() {
+        assert_eq!(snowfall_to_show(Some(1.4), Some(3)), Some(1.4));
+        // Snow codes show even a trace, other codes need an amount
+        assert_eq!(snowfall_to_show(Some(0.0), Some(71)), Some(0.0));
+        assert_eq!(snowfall_to_show(Some(0.0), Some(86)), Some(0.0));
+        assert_eq!(snowfall_to_show(Some(0.0), Some(61)), None);
+        assert_eq!(snowfall_to_show(None, Some(73)), None);
+    }
+
+    #[test]
+    fn test_banner_words_follow_the_locale() {
+        assert_eq!(tr(Locale::De, weekday_key(chrono::Weekday::Sat)), "Samstag");
+        assert_eq!(
+            tr(Locale::Es, cape_level_key(storm::CapeLevel::Extreme)),
+            "Extrema"
+        );
+        let by = tr(Locale::De, Key::SnowBy)
+            .replacen("{}", "12 cm", 1)
+            .replacen("{}", "Samstag", 1);
+        assert_eq!(by, "+12 cm bis Samstag");
+    }
+
+    #[test]
+    fn test_wind_speed_in_the_chosen_unit() {
+        assert_eq!(wind_speed_text(WindUnit::Kmh, 18.5, 1), "18.5 km/h");
+        assert_eq!(wind_speed_text(WindUnit::Knots, 18.52, 1), "10.0 kn");
+        assert_eq!(wind_speed_text(WindUnit::Ms, 36.0, 0), "10 m/s");
+    }
+
+    #[test]
+    fn test_freezing_level_in_meters_and_feet() {
+        let text = freezing_level_text(Locale::En, 2400.0);
+        assert!(text.starts_with("2400 m / 7874 ft (snow above ~"), "{text}");
+        assert!(text.ends_with(" ft)"), "{text}");
+        assert!(freezing_level_text(Locale::De, 2400.0).contains("Schnee oberhalb von ~"));
+    }
+
+    #[test]
+    fn test_optional_daily_columns_collapse_first() {
+        let widths = DailyColumnWidths::MIN;
+        // 620 points for the fixed columns, 90 for each optional one
+        assert_eq!(
+            widths.optional_columns(800.0, false, true, true),
+            (true, true)
+        );
+        assert_eq!(
+            widths.optional_columns(750.0, false, true, true),
+            (true, false)
+        );
+        assert_eq!(
+            widths.optional_columns(650.0, false, true, true),
+            (false, false)
+        );
+        assert_eq!(
+            widths.optional_columns(720.0, false, false, true),
+            (false, true)
+        );
+        assert_eq!(
+            widths.optional_columns(800.0, true, true, true),
+            (true, false)
+        );
+        assert_eq!(
+            widths.optional_columns(2000.0, false, false, false),
+            (false, false)
+        );
+
+        let mut weather = crate::fixtures::sample_weather_info();
+        weather.weather_data.daily[0].humidity_mean = Some(71.0);
+        weather.marine = crate::models::marine::MarineForecast::from_hours(vec![
+            crate::models::marine::MarineHour {
+                wave_height: Some(1.4),
+                wave_direction: Some(135.0),
+                ..crate::models::marine::MarineHour::new("2024-06-01T14:00")
+            },
+        ]);
+        weather.weather_data.daily[0].uv_index_max = Some(11.4);
+        render(&WeatherPanel::default(), &weather);
+    }
+
+    #[test]
+    fn test_panel_state_transitions() {
+        let mut panel = WeatherPanel::default().with_hourly_range(HourlyRange::Hours48);
+        assert!(panel.weather().is_none());
+        assert_eq!(panel.selected_tab(), Tab::Current);
+        show(&mut panel);
+        // The first frame measures the daily columns even without data
+        assert!(panel.column_widths.is_some());
+
+        panel.set_weather(Some(crate::fixtures::sample_weather_info()));
+        for tab in [Tab::Current, Tab::Hourly, Tab::Daily] {
+            panel.select_tab(tab);
+            show(&mut panel);
+            assert_eq!(panel.selected_tab(), tab);
+        }
+        assert_eq!(panel.hourly_range(), HourlyRange::Hours48);
+        assert_eq!(panel.status_message(), None);
+
+        // Clearing and reloading keeps the tab the user was reading
+        panel.set_weather(None);
+        show(&mut panel);
+        assert!(panel.weather().is_none());
+        let mut weather = crate::fixtures::sample_weather_info();
+        weather.location = "Bend".to_string();
+        panel.set_weather(Some(weather));
+        show(&mut panel);
+        assert_eq!(panel.selected_tab(), Tab::Daily);
+        assert_eq!(panel.weather().unwrap().location, "Bend");
+    }
+
+    #[test]
+    fn test_status_message_expires() {
+        let mut panel = WeatherPanel::default();
+        panel.set_status("Chart saved".to_string(), false);
+        show(&mut panel);
+        assert_eq!(panel.status_message(), Some(("Chart saved", false)));
+
+        let shown_at = Instant::now().checked_sub(STATUS_MESSAGE_DURATION).unwrap();
+        panel.status_message = Some(("Chart saved".to_string(), false, shown_at));
+        show(&mut panel);
+        assert_eq!(panel.status_message(), None);
+    }
+
+    #[test]
+    fn test_chart_key_follows_values_and_size() {
+        let weather = crate::fixtures::sample_weather_info();
+        let hours = &weather.weather_data.hourly;
+        let key = chart_key(hours, 600, 160);
+        assert_eq!(chart_key(hours, 600, 160), key);
+        assert_ne!(chart_key(hours, 640, 160), key);
+
+        let mut warmer = hours.clone();
+        warmer[0].temperature = warmer[0].temperature.map(|t| t + 1.0);
+        assert_ne!(chart_key(&warmer, 600, 160), key);
+    }
+
+    #[test]
+    fn test_screen_reader_labels() {
+        let mut panel = WeatherPanel::default();
+        panel.set_weather(Some(crate::fixtures::sample_weather_info()));
+        let ctx = egui::Context::default();
+        ctx.enable_accesskit();
+        let output = ctx.run(egui::RawInput::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| panel.ui(ui));
+        });
+        let update = output.platform_output.accesskit_update.unwrap();
+        let names: Vec<&str> = update
+            .nodes
+            .iter()
+            .filter_map(|(_, node)| node.name())
+            .collect();
+        assert!(names.contains(&"Humidity 58 percent"), "{names:?}");
+        assert!(names.contains(&"Partly Cloudy"), "{names:?}");
+        assert!(names.contains(&"67 degrees Fahrenheit"), "{names:?}");
+        assert!(!names.iter().any(|name| name.contains("⛅")), "{names:?}");
+    }
+
+    #[test]
+    fn test_app_passes_results_to_panel() {
+        let mut app = WeatherApp::default();
+        app.show_result(Some("Seattle"), Ok(crate::fixtures::sample_weather_info()));
+        assert!(app.panel.weather().is_some());
+        assert!(app.error_message.is_none());
+
+        app.show_result(
+            Some("Atlantis"),
+            Err(WeatherError::location_not_found("Atlantis")),
+        );
+        assert!(app.panel.weather().is_none());
+        assert!(app.error_message.is_some());
+        assert_eq!(app.pending_title.as_deref(), Some(WINDOW_TITLE));
+    }
+
+    #[test]
+    fn test_rate_limit_schedules_a_retry() {
+        // The search box has moved on since Oslo was asked for by its
+        // recorded position: the request is what is retried
+        let mut app = WeatherApp {
+            location_input: "Bergen".to_string(),
+            ..Default::default()
+        };
+        app.show_result(
+            Some(" 59.91, 10.75 "),
+            Err(WeatherError::RateLimited {
+                retry_after: std::time::Duration::from_secs(90),
+            }),
+        );
+        let (location, retry_at) = app.rate_limit_retry.clone().unwrap();
+        assert_eq!(location, "59.91, 10.75");
+        assert!(retry_at > chrono::Local::now() + chrono::TimeDelta::seconds(80));
+
+        app.favorites.save("Oslo", None, None);
+        let ctx = egui::Context::default();
+        let _ = ctx.run(egui::RawInput::default(), |ctx| {
+            app.display_favorites_sidebar(ctx);
+            app.retry_after_rate_limit(ctx);
+        });
+        // Still cooling down: nothing was requested
+        assert!(app.rate_limit_retry.is_some());
+        assert!(!app.controller.is_loading());
+    }
+
+    #[test]
+    fn test_confidence_toggle_keeps_the_pending_search() {
+        let path = format!(
+            "{}/tests/fixtures/london/forecast.json",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let mut app = WeatherApp::default().with_saved_forecast(path);
+        app.settings.last_location = Some("Seattle".to_string());
+        app.panel
+            .set_weather(Some(crate::fixtures::sample_weather_info()));
+        app.fetch_location("London");
+
+        app.settings.display.confidence = true;
+        app.apply_confidence_setting();
+        // The search in flight is fetched again, not the last location
+        assert_eq!(app.controller.pending_location(), Some("London"));
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        let result = loop {
+            if let Some(result) = app.controller.poll() {
+                break result;
+            }
+            assert!(
+                std::time::Instant::now() < deadline,
+                "request did not finish"
+            );
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        };
+        assert!(result.is_ok());
+        let metrics = app.controller.metrics();
+        assert_eq!((metrics.requests, metrics.abandoned), (2, 1));
+        assert_eq!(metrics.succeeded, 1);
+    }
+
+    #[test]
+    fn test_refetch_alerts_on_significant_change() {
+        let mut app = WeatherApp::default();
+        let sample = crate::fixtures::sample_weather_info();
+        app.show_result(Some("Seattle"), Ok(sample.clone()));
+        app.show_result(Some("Seattle"), Ok(sample.clone()));
+        assert!(app.change_alert.is_none());
+
+        let mut warmer = sample.clone();
+        for day in &mut warmer.weather_data.daily {
+            day.temperature_max = day.temperature_max.map(|t| t + 5.0);
+        }
+        app.show_result(Some("Seattle"), Ok(warmer.clone()));
+        let (lines, _) = app.change_alert.as_ref().unwrap();
+        assert!(lines[0].starts_with("Forecast high for "), "{lines:?}");
+
+        // A different location is a new report, not a change
+        app.change_alert = None;
+        warmer.location = "Portland".to_string();
+        app.show_result(Some("Seattle"), Ok(warmer));
+        assert!(app.change_alert.is_none());
+    }
+
+    #[test]
+    fn test_shut_down_persists_settings_and_favorites() {
+        let dir = std::env::temp_dir().join(format!("weather-app-exit-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut app = WeatherApp::default()
+            .with_settings_store(SettingsStore::new(dir.join("settings.json")))
+            .with_favorites_store(FavoritesStore::new(dir.join("favorites.json")));
+
+        // Changed without going through the UI, so nothing has been written yet
+        app.settings.locale = Locale::Es;
+        app.favorites.save("Bend", None, None);
+        app.shut_down();
+
+        let settings = SettingsStore::new(dir.join("settings.json"))
+            .load()
+            .unwrap();
+        assert_eq!(settings.locale, Locale::Es);
+        let favorites = FavoritesStore::new(dir.join("favorites.json"))
+            .load()
+            .unwrap();
+        assert!(!favorites.is_empty());
+    }
+
+    #[test]
+    fn test_favorites_sidebar_renders() {
+        let mut app = WeatherApp::default();
+        app.favorites.save(
+            "Seattle",
+            Some("Home".to_string()),
+            Some("Key under mat".to_string()),
+        );
+        app.favorites.save("Bend", None, None);
+        app.renaming = Some((0, "Home".to_string()));
+
+        let ctx = egui::Context::default();
+        let _ = ctx.run(egui::RawInput::default(), |ctx| {
+            app.display_favorites_sidebar(ctx);
+            app.display_rename_window(ctx);
+        });
+    }
+
+    #[test]
+    fn test_name_refresh_results_are_applied() {
+        let mut app = WeatherApp::default();
+        app.favorites.save("seatle", None, None);
+        app.favorites.save("Bend", None, None);
+        let sender = &app.name_refresh.0;
+        let place = ResolvedPlace::new("Seattle, United States", 47.6, -122.3);
+        sender
+            .send(("seatle".to_string(), Ok(Some(place))))
+            .unwrap();
+        let offline = WeatherError::NetworkError("offline".to_string());
+        sender.send(("Bend".to_string(), Err(offline))).unwrap();
+
+        app.apply_name_refreshes();
+        let entries = app.favorites.entries();
+        assert_eq!(entries[0].name(), "Seattle, United States");
+        assert!(entries[0].refreshed.is_some());
+        // Failed lookups are tried again next time
+        assert_eq!(entries[1].refreshed, None);
+
+        // A held-back move renders with its warning
+        let tacoma = ResolvedPlace::new("Tacoma, United States", 47.25, -122.44);
+        app.favorites
+            .apply_refresh("seatle", Some(tacoma), chrono::Local::now().date_naive());
+        let ctx = egui::Context::default();
+        let _ = ctx.run(egui::RawInput::default(), |ctx| {
+            app.display_favorites_sidebar(ctx);
+        });
+    }
+}
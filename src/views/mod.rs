@@ -1,3 +1,15 @@
 // View layer - handles presentation and user interface
+#[cfg(feature = "gui")]
+pub mod accessibility;
+pub mod chart_image;
 pub mod cl_view;
+pub mod display_options;
+pub mod export;
+#[cfg(feature = "gui")]
 pub mod gui_view;
+#[cfg(feature = "gui")]
+pub mod theme;
+
+pub use display_options::DisplayOptions;
+#[cfg(feature = "gui")]
+pub use gui_view::WeatherPanel;
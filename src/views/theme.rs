@@ -0,0 +1,172 @@
+//! Base color themes and the weather-driven tint applied over them
+
+use egui::Color32;
+
+use crate::utils::weather_codes::WeatherCode;
+
+/// Night tints are pulled this far towards a dark navy
+const NIGHT_DARKENING: f32 = 0.5;
+
+/// Minimum contrast of body text against the gradient (WCAG AA)
+const TEXT_CONTRAST: f32 = 4.5;
+
+/// Minimum contrast of the accent against the gradient (WCAG AA, large text)
+const ACCENT_CONTRAST: f32 = 3.0;
+
+/// Base colors of the GUI
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Theme {
+    pub background: Color32,
+    pub text: Color32,
+    pub accent: Color32,
+    /// How much of a weather tint the top of the background takes (0–1)
+    pub tint_strength: f32,
+}
+
+/// Conditions-dependent colors layered over a base theme
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ThemeAccents {
+    pub accent: Color32,
+    pub gradient_top: Color32,
+    pub gradient_bottom: Color32,
+}
+
+impl Theme {
+    pub const DARK: Theme = Theme {
+        background: Color32::from_rgb(15, 23, 42),
+        text: Color32::from_rgb(248, 250, 252),
+        accent: Color32::from_rgb(59, 130, 246),
+        tint_strength: 0.3,
+    };
+
+    pub const LIGHT: Theme = Theme {
+        background: Color32::from_rgb(248, 250, 252),
+        text: Color32::from_rgb(15, 23, 42),
+        accent: Color32::from_rgb(29, 78, 216),
+        tint_strength: 0.35,
+    };
+
+    /// Flat background and the base accent, for when no weather is loaded
+    pub fn plain(&self) -> ThemeAccents {
+        ThemeAccents {
+            accent: self.accent,
+            gradient_top: self.background,
+            gradient_bottom: self.background,
+        }
+    }
+
+    /// Background gradient and accent tinted by the current conditions: warm
+    /// yellows when clear, slate blues in rain, near-white blue in snow and a
+    /// deep purple-gray in thunderstorms, darker at night. The accent is pulled
+    /// towards the text color until it stands out from the gradient.
+    pub fn for_conditions(base: &Theme, code: WeatherCode, is_day: bool) -> ThemeAccents {
+        let tint = match code {
+            WeatherCode::Clear | WeatherCode::MainlyClear => Color32::from_rgb(250, 204, 21),
+            WeatherCode::PartlyCloudy => Color32::from_rgb(214, 188, 120),
+            WeatherCode::Overcast => Color32::from_rgb(148, 163, 184),
+            WeatherCode::Fog => Color32::from_rgb(203, 213, 225),
+            WeatherCode::Drizzle | WeatherCode::Rain | WeatherCode::RainShowers => {
+                Color32::from_rgb(71, 105, 160)
+            }
+            WeatherCode::FreezingDrizzle | WeatherCode::FreezingRain => {
+                Color32::from_rgb(125, 170, 220)
+            }
+            WeatherCode::Snow | WeatherCode::SnowGrains | WeatherCode::SnowShowers => {
+                Color32::from_rgb(224, 242, 254)
+            }
+            WeatherCode::Thunderstorm | WeatherCode::ThunderstormHail => {
+                Color32::from_rgb(88, 70, 120)
+            }
+        };
+        let tint = if is_day {
+            tint
+        } else {
+            mix(tint, Color32::from_rgb(15, 23, 42), NIGHT_DARKENING)
+        };
+
+        let gradient_top = mix(base.background, tint, base.tint_strength);
+        let mut accent = tint;
+        let mut toward_text = 0.0;
+        while contrast(accent, gradient_top) < ACCENT_CONTRAST && toward_text < 1.0 {
+            toward_text += 0.1;
+            accent = mix(tint, base.text, toward_text);
+        }
+
+        ThemeAccents {
+            accent,
+            gradient_top,
+            gradient_bottom: base.background,
+        }
+    }
+}
+
+/// `a` moved a fraction `t` of the way to `b`
+fn mix(a: Color32, b: Color32, t: f32) -> Color32 {
+    let channel = |x: u8, y: u8| (f32::from(x) + (f32::from(y) - f32::from(x)) * t).round() as u8;
+    Color32::from_rgb(
+        channel(a.r(), b.r()),
+        channel(a.g(), b.g()),
+        channel(a.b(), b.b()),
+    )
+}
+
+/// WCAG relative luminance
+fn luminance(color: Color32) -> f32 {
+    let linear = |c: u8| {
+        let c = f32::from(c) / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(color.r()) + 0.7152 * linear(color.g()) + 0.0722 * linear(color.b())
+}
+
+/// WCAG contrast ratio, from 1 (none) to 21
+fn contrast(a: Color32, b: Color32) -> f32 {
+    let (la, lb) = (luminance(a), luminance(b));
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+/// Whether `text` stays readable over every color of the gradient
+pub fn is_readable(text: Color32, accents: &ThemeAccents) -> bool {
+    contrast(text, accents.gradient_top) >= TEXT_CONTRAST
+        && contrast(text, accents.gradient_bottom) >= TEXT_CONTRAST
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_condition_stays_readable() {
+        for base in [Theme::DARK, Theme::LIGHT] {
+            for code in WeatherCode::ALL {
+                for is_day in [true, false] {
+                    let accents = Theme::for_conditions(&base, code, is_day);
+                    assert!(is_readable(base.text, &accents), "{code:?} {is_day}");
+                    assert!(
+                        contrast(accents.accent, accents.gradient_top) >= ACCENT_CONTRAST,
+                        "{code:?} {is_day}"
+                    );
+                    assert_eq!(accents.gradient_bottom, base.background);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_conditions_tint_the_gradient() {
+        let base = Theme::DARK;
+        let clear = Theme::for_conditions(&base, WeatherCode::Clear, true);
+        let rain = Theme::for_conditions(&base, WeatherCode::Rain, true);
+        let clear_night = Theme::for_conditions(&base, WeatherCode::Clear, false);
+
+        // Clear days lean red/green (yellow), rain leans blue
+        assert!(clear.gradient_top.r() > clear.gradient_top.b());
+        assert!(rain.gradient_top.b() > rain.gradient_top.r());
+        assert!(luminance(clear_night.gradient_top) < luminance(clear.gradient_top));
+        assert_eq!(base.plain().gradient_top, base.background);
+    }
+}
@@ -0,0 +1,895 @@
+//! Fetch → parse → render against captured API responses, fully offline
+
+mod common;
+
+use common::{
+    Fixture, ReplayServer, HOSTILE, LONDON, LONDON_16_DAYS, LONDON_AIR_QUALITY, LONDON_LEGACY,
+    LONDON_MINUTELY, LONDON_OWM, LONDON_PAST_DAYS, NEW_YORK_NWS, OSLO_METNO, QUITO_WITH_NULLS,
+    SYDNEY, SYDNEY_DEW_POINT, TROMSO, TROMSO_SNOW,
+};
+use serde_json::Value;
+
+fn text_report(fixture: Fixture) -> String {
+    let server = ReplayServer::start(fixture);
+    let output = server.run_cli(fixture, &["--width", "120"]);
+    assert!(
+        output.status.success(),
+        "{} failed: {}",
+        fixture.name,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn json_report(fixture: Fixture) -> Value {
+    let server = ReplayServer::start(fixture);
+    let output = server.run_cli(fixture, &["--format", "json"]);
+    assert!(
+        output.status.success(),
+        "{} failed: {}",
+        fixture.name,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).expect("json output")
+}
+
+#[test]
+fn test_text_report_for_every_fixture() {
+    for fixture in common::ALL {
+        let report = text_report(fixture);
+        assert!(report.contains("=== Weather Report ==="), "{report}");
+        assert!(report.contains(fixture.query), "{report}");
+        assert!(report.contains("--- Daily Forecast (Next 7 Days) ---"));
+    }
+}
+
+#[test]
+fn test_json_report_for_every_fixture() {
+    for fixture in common::ALL {
+        let json = json_report(fixture);
+        assert_eq!(json["query"], fixture.query);
+        // Named as the geocoder resolved it
+        let location = json["location"].as_str().unwrap();
+        assert!(location.starts_with(fixture.query), "{location}");
+        assert_eq!(location.matches(", ").count(), 1, "{location}");
+        assert_eq!(json["hourly"].as_array().unwrap().len(), 48);
+        assert_eq!(json["daily"].as_array().unwrap().len(), 7);
+    }
+}
+
+#[test]
+fn test_text_report_london() {
+    let report = text_report(LONDON);
+    assert!(report.contains("London, United Kingdom"), "{report}");
+    assert!(report.contains("Coordinates: 51.5074°N, 0.1278°W"));
+    assert!(report.contains("Temperature: 8.8°C / 47.8°F"));
+    assert!(report.contains("Feels Like: "));
+    assert!(report.contains("2025-12-10"));
+    assert!(report.contains("07:58–15:51"));
+    assert!(report.contains("min ") && report.contains(" · peak wind "));
+}
+
+#[test]
+fn test_legacy_fallback() {
+    let server = ReplayServer::start(LONDON_LEGACY);
+    let output = server.run_cli(LONDON_LEGACY, &["--width", "120"]);
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{report}");
+    assert!(report.contains("Note: this server only provides basic current conditions"));
+    assert!(report.contains("Temperature: 8.8°C / 47.8°F"), "{report}");
+    assert!(report.contains("--- Daily Forecast (Next 7 Days) ---"));
+
+    // The rejected request, then the legacy one
+    let requests = server.requests_to("/v1/forecast");
+    assert_eq!(requests.len(), 2);
+    assert!(!requests[0].contains("current_weather=true"));
+    assert!(requests[1].contains("&current_weather=true"));
+}
+
+#[test]
+fn test_legacy_fallback_keeps_the_request_options() {
+    use weather_app::prelude::*;
+    use weather_app::repositories::geocoding::GeocodingProvider;
+
+    let server = ReplayServer::start(LONDON_LEGACY);
+    let repository = ApiWeatherRepository::new()
+        .with_geocoder(GeocodingProvider::OpenMeteo)
+        .with_endpoints(server.open_meteo_geocoding_url(), server.open_meteo_url());
+    let options = FetchOptions::new().with_forecast_days(16).with_past_days(2);
+    let info = repository
+        .fetch_weather_with_options(LONDON_LEGACY.query, &options)
+        .unwrap();
+    assert!(info.reduced_data);
+
+    let requests = server.requests_to("/v1/forecast");
+    let legacy = requests.last().unwrap();
+    assert!(legacy.contains("&current_weather=true"), "{legacy}");
+    assert!(
+        legacy.contains("&forecast_days=16&past_days=2&"),
+        "{legacy}"
+    );
+    assert!(legacy.ends_with("&timezone=Europe%2FLondon"), "{legacy}");
+}
+
+#[test]
+fn test_open_meteo_geocoder() {
+    let server = ReplayServer::start(LONDON);
+    let output = server.run_cli(LONDON, &["--geocoder", "open-meteo", "--width", "120"]);
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{report}");
+    // The most populous "London" wins over the Canadian and US ones
+    assert!(
+        report.contains("Coordinates: 51.5085°N, 0.1257°W"),
+        "{report}"
+    );
+}
+
+#[test]
+fn test_winds_aloft_table() {
+    let server = ReplayServer::start(LONDON);
+    let output = server.run_cli(LONDON, &["--winds-aloft", "--wind-unit", "knots"]);
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        report.contains("--- Winds Aloft (Next 12 Hours) ---"),
+        "{report}"
+    );
+    assert!(report.contains("Time             10 m          80 m         120 m"));
+    assert!(report.contains("10:00"));
+    assert!(report.contains(" kn SW"));
+
+    // Without aviation arrays the table says so instead of printing dashes
+    let server = ReplayServer::start(QUITO_WITH_NULLS);
+    let output = server.run_cli(QUITO_WITH_NULLS, &["--winds-aloft"]);
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(report.contains("Winds aloft are not available from this server"));
+}
+
+#[test]
+fn test_marine_table() {
+    let server = ReplayServer::start(SYDNEY);
+    let output = server.run_cli(SYDNEY, &["--marine"]);
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{report}");
+    assert!(
+        report.contains("--- Marine (Next 24 Hours) ---"),
+        "{report}"
+    );
+    assert!(report.contains("Time       Waves   Period   From"));
+    assert!(
+        report.contains("10:00      1.8 m     10 s     SE"),
+        "{report}"
+    );
+
+    // Inland the marine API answers with nulls; the report says so and carries on
+    let server = ReplayServer::start(QUITO_WITH_NULLS);
+    let output = server.run_cli(QUITO_WITH_NULLS, &["--marine"]);
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{report}");
+    assert!(report.contains("No marine data for this location"));
+    assert!(report.contains("--- Daily Forecast (Next 7 Days) ---"));
+
+    // Without --marine the marine API is not asked at all
+    let server = ReplayServer::start(LONDON);
+    let report = String::from_utf8(server.run_cli(LONDON, &[]).stdout).unwrap();
+    assert!(!report.contains("Marine"));
+    assert!(server.requests_to("/v1/marine").is_empty());
+    assert_eq!(server.requests_to("/v1/forecast").len(), 1);
+}
+
+#[test]
+fn test_air_quality_line() {
+    let server = ReplayServer::start(LONDON_AIR_QUALITY);
+    let output = server.run_cli(LONDON_AIR_QUALITY, &["--air-quality", "--width", "140"]);
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{report}");
+    assert!(
+        report.contains(
+            "Air Quality: US AQI 50 (Good) · European AQI 26 · PM2.5 11.8 µg/m³ · \
+             PM10 17.1 µg/m³ · O₃ 40.0 µg/m³ · NO₂ 19.2 µg/m³"
+        ),
+        "{report}"
+    );
+
+    // Only when asked for
+    let report = text_report(LONDON);
+    assert!(!report.contains("Air Quality"));
+}
+
+/// `fetch_weather_full` parses current and hourly values, and a failed air
+/// quality request costs only the air quality
+#[test]
+fn test_fetch_weather_full() {
+    use weather_app::prelude::*;
+    use weather_app::repositories::retry::RetryPolicy;
+
+    let server = ReplayServer::start(LONDON_AIR_QUALITY);
+    let repository = ApiWeatherRepository::new()
+        .with_endpoints(server.nominatim_url(), server.open_meteo_url())
+        .with_air_quality_url(server.air_quality_url());
+    let info = repository
+        .fetch_weather_full(LONDON_AIR_QUALITY.query)
+        .unwrap();
+    let air = info.air_quality.as_ref().unwrap();
+    assert_eq!(air.hourly.len(), 48);
+    assert_eq!(air.hourly[0].time, "2025-12-10T00:00");
+    let now = info.current_air_quality().unwrap();
+    assert_eq!(now.us_aqi, Some(50.0));
+    assert_eq!(now.nitrogen_dioxide, Some(19.2));
+    assert!(info.timings.unwrap().air_quality.is_some());
+
+    // Not part of a plain fetch
+    assert_eq!(
+        repository
+            .fetch_weather(LONDON_AIR_QUALITY.query)
+            .unwrap()
+            .air_quality,
+        None
+    );
+
+    // Sydney has no air quality file: the server fails that request only
+    let server = ReplayServer::start(SYDNEY);
+    let repository = ApiWeatherRepository::new()
+        .with_retry_policy(RetryPolicy::none())
+        .with_endpoints(server.nominatim_url(), server.open_meteo_url())
+        .with_air_quality_url(server.air_quality_url());
+    let info = repository.fetch_weather_full(SYDNEY.query).unwrap();
+    assert_eq!(info.air_quality, None);
+    assert!(info.weather_data.current.temperature.is_some());
+    assert!(matches!(
+        repository.fetch_air_quality(info.latitude, info.longitude),
+        Err(WeatherError::ServerError(_))
+    ));
+}
+
+#[test]
+fn test_json_report_southern_hemisphere() {
+    let json = json_report(SYDNEY);
+    assert!(json["latitude"].as_f64().unwrap() < 0.0);
+    assert_eq!(json["elevation"], 39.0);
+    assert_eq!(json["daily"][0]["sunrise"], "2025-12-10T05:39");
+}
+
+#[test]
+fn test_times_are_local_to_the_location() {
+    let report = text_report(SYDNEY);
+    assert!(
+        report.contains("Location: Sydney, Australia (GMT+11)"),
+        "{report}"
+    );
+    let json = json_report(SYDNEY);
+    assert_eq!(json["timezone"], "Australia/Sydney");
+    assert_eq!(json["timezone_abbreviation"], "GMT+11");
+    // Sunrise in Sydney, not in UTC
+    assert_eq!(json["daily"][0]["sunrise"], "2025-12-10T05:39");
+    assert!(text_report(TROMSO).contains("(GMT+1)"));
+}
+
+/// Dew points flow through when the response has them; the recordings
+/// predate them and still parse
+#[test]
+fn test_dew_point_flows_through() {
+    use weather_app::prelude::*;
+
+    let server = ReplayServer::start(SYDNEY_DEW_POINT);
+    let repository =
+        ApiWeatherRepository::new().with_endpoints(server.nominatim_url(), server.open_meteo_url());
+    let data = repository
+        .fetch_weather(SYDNEY_DEW_POINT.query)
+        .unwrap()
+        .weather_data;
+    assert_eq!(data.current.dew_point, Some(19.5));
+    assert_eq!(data.hourly[0].dew_point, Some(14.1));
+    assert!(text_report(SYDNEY_DEW_POINT).contains("Dew Point: 19.5°C / 67.1°F"));
+
+    let server = ReplayServer::start(LONDON);
+    let repository =
+        ApiWeatherRepository::new().with_endpoints(server.nominatim_url(), server.open_meteo_url());
+    let data = repository.fetch_weather(LONDON.query).unwrap().weather_data;
+    assert_eq!(data.current.dew_point, None);
+    assert!(data.hourly.iter().all(|h| h.dew_point.is_none()));
+    assert!(!text_report(LONDON).contains("Dew Point"));
+}
+
+/// Days fetched with `past_days` come first, flagged, and stay out of the views
+#[test]
+fn test_past_days_precede_the_forecast() {
+    use weather_app::prelude::*;
+
+    let server = ReplayServer::start(LONDON_PAST_DAYS);
+    let repository =
+        ApiWeatherRepository::new().with_endpoints(server.nominatim_url(), server.open_meteo_url());
+    let options = FetchOptions::new().with_past_days(2);
+    let info = repository
+        .fetch_weather_with_options(LONDON_PAST_DAYS.query, &options)
+        .unwrap();
+    let data = &info.weather_data;
+
+    assert_eq!(data.daily.len(), 9);
+    assert_eq!(data.daily[0].date, "2025-12-08");
+    assert_eq!(data.daily[1].temperature_max, Some(11.2));
+    assert!(data.daily[..2].iter().all(|d| d.is_past));
+    assert!(data.daily[2..].iter().all(|d| !d.is_past));
+    assert_eq!(data.daily_from_today()[0].date, "2025-12-10");
+
+    // Both past days, then the usual 48 hours from midnight
+    assert_eq!(data.hourly.len(), 96);
+    let today = data.hourly_from_today();
+    assert_eq!(today.len(), 48);
+    assert_eq!(today[0].time, "2025-12-10T00:00");
+    // Observed at 10:00, so the hours before it are over
+    assert!(today[9].is_past && !today[10].is_past);
+    assert_eq!(data.current_hour().unwrap().time, "2025-12-10T10:00");
+
+    // The report reads as without them, apart from the fetch time
+    let without_fetch_time = |report: String| {
+        report
+            .lines()
+            .filter(|line| !line.starts_with("Forecast data as of"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    assert_eq!(
+        without_fetch_time(text_report(LONDON_PAST_DAYS)),
+        without_fetch_time(text_report(LONDON))
+    );
+    let json = json_report(LONDON_PAST_DAYS);
+    assert_eq!(json["daily"].as_array().unwrap().len(), 9);
+    assert_eq!(json["daily"][1]["is_past"], true);
+    assert_eq!(json["daily"][2]["is_past"], false);
+}
+
+#[test]
+fn test_split_forecast_hours_start_at_midnight() {
+    use weather_app::models::timings::FetchStrategy;
+    use weather_app::prelude::*;
+
+    let server = ReplayServer::start(LONDON_16_DAYS);
+    let repository = ApiWeatherRepository::new()
+        .with_endpoints(server.nominatim_url(), server.open_meteo_url())
+        .with_forecast_days(16);
+    let info = repository.fetch_weather(LONDON_16_DAYS.query).unwrap();
+    assert_eq!(info.timings.unwrap().strategy, FetchStrategy::Split);
+    let requests = server.requests_to("/v1/forecast");
+    let hourly_request = requests.iter().find(|r| r.contains("&hourly=")).unwrap();
+    assert!(
+        hourly_request.contains("&forecast_hours=48&past_hours=24&"),
+        "{hourly_request}"
+    );
+
+    // Yesterday's hours are dropped; today's run from midnight
+    let data = &info.weather_data;
+    assert_eq!(data.daily.len(), 16);
+    assert_eq!(data.hourly.len(), 48);
+    assert_eq!(data.hourly[0].time, "2025-12-10T00:00");
+    // so today's means cover the whole day, not just the hours left
+    for day in &data.daily[..2] {
+        assert!(day.humidity_mean.is_some(), "{}", day.date);
+        assert!(day.precipitation_probability_mean.is_some(), "{}", day.date);
+    }
+    assert_eq!(data.daily[2].precipitation_probability_mean, None);
+}
+
+/// `--from-file` renders a saved response without a single request
+#[test]
+fn test_report_from_a_saved_forecast() {
+    let server = ReplayServer::start(LONDON);
+    let path = LONDON.dir().join("forecast.json");
+    let output = server.run_cli(
+        LONDON,
+        &["--from-file", path.to_str().unwrap(), "--width", "120"],
+    );
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{report}");
+    assert!(report.contains("=== Weather Report ==="), "{report}");
+    assert!(report.contains("London"), "{report}");
+    assert!(server.requests_to("/").is_empty());
+
+    let output = server.run_batch(&["--from-file", path.to_str().unwrap()], "London\n");
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_two_week_forecast_from_the_command_line() {
+    let server = ReplayServer::start(LONDON_16_DAYS);
+    let output = server.run_cli(LONDON_16_DAYS, &["--days", "16", "--width", "120"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        report.contains("--- Daily Forecast (Next 16 Days) ---"),
+        "{report}"
+    );
+    assert!(report.contains("2025-12-25"), "{report}");
+    assert!(server
+        .requests_to("/v1/forecast")
+        .iter()
+        .any(|r| r.contains("&daily=") && r.contains("&forecast_days=16&")));
+}
+
+#[test]
+fn test_minutely_forecast_for_the_next_two_hours() {
+    use weather_app::prelude::*;
+
+    let server = ReplayServer::start(LONDON_MINUTELY);
+    let output = server.run_cli(LONDON_MINUTELY, &["--minutely", "--width", "120"]);
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{report}");
+    let section: Vec<&str> = report
+        .lines()
+        .skip_while(|line| *line != "--- Next 2 Hours (Every 15 Minutes) ---")
+        .skip(1)
+        .take_while(|line| !line.is_empty())
+        .collect();
+    assert_eq!(section.len(), 9, "{report}");
+    assert_eq!(section[0], "Time        Temp    Precip  Conditions");
+    // The quarter hour ending at the 10:00 observation is past
+    assert_eq!(section[1], "10:15      8.9°C    0.2 mm  Rain");
+    assert_eq!(section[3], "10:45      9.1°C    0.0 mm  Overcast");
+    assert_eq!(section[8], "12:00     10.0°C    0.0 mm  Overcast");
+
+    let repository =
+        ApiWeatherRepository::new().with_endpoints(server.nominatim_url(), server.open_meteo_url());
+    let info = repository.fetch_weather(LONDON_MINUTELY.query).unwrap();
+    let minutely = &info.weather_data.minutely;
+    let times: Vec<&str> = minutely.iter().map(|m| m.time.as_str()).collect();
+    assert_eq!(times.len(), 12);
+    assert_eq!(
+        times[..3],
+        ["2025-12-10T10:00", "2025-12-10T10:15", "2025-12-10T10:30"]
+    );
+    assert_eq!(times[11], "2025-12-10T12:45");
+    let precipitation: Vec<f64> = minutely.iter().map(|m| m.precipitation.unwrap()).collect();
+    assert_eq!(
+        precipitation,
+        [0.1, 0.2, 0.1, 0.0, 0.2, 0.3, 0.1, 0.1, 0.0, 0.0, 0.1, 0.0]
+    );
+    let codes: Vec<i32> = minutely.iter().map(|m| m.weather_code.unwrap()).collect();
+    assert_eq!(codes, [61, 61, 51, 3, 61, 61, 51, 51, 3, 3, 51, 3]);
+    assert_eq!(minutely[7].temperature, Some(9.9));
+    // The hour to 11:00 adds up to the hourly total
+    let hour: f64 = precipitation[1..5].iter().sum();
+    assert!((hour - 0.5).abs() < 1e-9, "{hour}");
+
+    // Without the block there is no section
+    assert!(!text_report(LONDON).contains("Every 15 Minutes"));
+}
+
+#[test]
+fn test_nominatim_refusals_do_not_stop_a_batch() {
+    let server = ReplayServer::start(LONDON);
+    let output = server.run_batch(
+        &[
+            "--nominatim-spacing",
+            "fail",
+            "--nominatim-interval-ms",
+            "30000",
+            "--no-geocoder-fallback",
+        ],
+        "London\nLondon, UK\n",
+    );
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(!output.status.success(), "{report}");
+    let lines: Vec<&str> = report.lines().collect();
+    assert_eq!(lines.len(), 2, "{report}");
+    // Whichever location geocodes first gets the only turn
+    let refused: Vec<&&str> = lines
+        .iter()
+        .filter(|line| {
+            line.contains("Request not sent, to keep to the server's usage policy; next turn in")
+        })
+        .collect();
+    assert_eq!(refused.len(), 1, "{report}");
+    assert!(
+        lines
+            .iter()
+            .any(|line| line.starts_with("London, United Kingdom: 9°C")),
+        "{report}"
+    );
+    assert!(!report.contains("Rate limited by the server"), "{report}");
+}
+
+#[test]
+fn test_polar_night_has_no_sun_times() {
+    let report = text_report(TROMSO);
+    assert!(report.contains("  polar night"), "{report}");
+    assert!(!report.contains("--:--–--:--"), "{report}");
+    assert!(report.contains("Temperature: -3.0°C / 26.6°F"));
+
+    let json = json_report(TROMSO);
+    for day in json["daily"].as_array().unwrap() {
+        assert_eq!(day.get("sunrise"), Some(&Value::Null));
+        assert_eq!(day.get("sunset"), Some(&Value::Null));
+    }
+    assert_eq!(json["current"]["is_day"], false);
+}
+
+#[test]
+fn test_snowfall_in_cm_and_inches() {
+    let server = ReplayServer::start(TROMSO_SNOW);
+    let output = server.run_cli(TROMSO_SNOW, &["--width", "80"]);
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{report}");
+    assert!(
+        report.contains("Precipitation: 0.5 mm / 0.02 in (0.3 cm / 0.1 in of snow)"),
+        "{report}"
+    );
+    assert!(report.contains("  Snowfall: 7.0 cm / 2.7 in"), "{report}");
+    assert!(report.contains("  Snowfall: 0.4 cm / 0.2 in"), "{report}");
+
+    let json = json_report(TROMSO_SNOW);
+    assert_eq!(json["current"]["snowfall"], 0.35);
+    assert_eq!(json["daily"][0]["snowfall_sum"], 6.98);
+
+    // London had rain, not snow
+    let report = text_report(LONDON);
+    assert!(!report.contains("of snow") && !report.contains("  Snowfall:"));
+}
+
+#[test]
+fn test_nulls_render_as_missing_values() {
+    let report = text_report(QUITO_WITH_NULLS);
+    assert!(!report.contains("Humidity:"), "{report}");
+    assert!(!report.contains("NaN"));
+
+    let json = json_report(QUITO_WITH_NULLS);
+    assert_eq!(json["current"].get("humidity"), Some(&Value::Null));
+    assert_eq!(json["hourly"][3].get("temperature"), Some(&Value::Null));
+    assert!(json["hourly"][2]["temperature"].is_number());
+    assert_eq!(json["daily"][2].get("temperature_max"), Some(&Value::Null));
+    assert_eq!(json["daily"][6].get("sunset"), Some(&Value::Null));
+    assert!(json["daily"][6]["sunrise"].is_string());
+}
+
+#[test]
+fn test_hostile_values_are_dropped() {
+    let server = ReplayServer::start(HOSTILE);
+    let output = server.run_cli(HOSTILE, &["--width", "120", "--verbose"]);
+    let report = String::from_utf8(output.stdout).unwrap();
+    let notes = String::from_utf8(output.stderr).unwrap();
+    assert!(output.status.success(), "{notes}");
+    for garbage in ["NaN", "inf", "999", "250%", "-40%"] {
+        assert!(!report.contains(garbage), "{garbage} in {report}");
+    }
+    assert!(!report.contains("\nTemperature: "), "{report}");
+    assert!(report.contains("Cloud Cover: 100%"), "{report}");
+    assert!(notes.contains("Note: current.temperature: -999 out of range, dropped"));
+    assert!(notes.contains("Note: 8 non-finite values in the forecast read as missing"));
+    assert!(notes.contains("Note: hourly[11].precipitation_probability: 250 out of range, dropped"));
+
+    let json = json_report(HOSTILE);
+    assert_eq!(json["current"].get("temperature"), Some(&Value::Null));
+    assert_eq!(json["current"].get("humidity"), Some(&Value::Null));
+    assert_eq!(json["current"]["cloud_cover"], 100.0);
+    assert_eq!(json["hourly"][10].get("temperature"), Some(&Value::Null));
+    assert_eq!(json["hourly"][11]["precipitation_probability"], Value::Null);
+    assert_eq!(json["hourly"][12].get("weather_code"), Some(&Value::Null));
+    assert!(json["hourly"][13]["precipitation"].is_number());
+    assert_eq!(json["daily"][0].get("temperature_max"), Some(&Value::Null));
+    assert_eq!(json["daily"][1].get("temperature_min"), Some(&Value::Null));
+    assert_eq!(json["daily"][2].get("weather_code"), Some(&Value::Null));
+}
+
+/// Refreshes the recordable fixtures from the live APIs:
+/// `cargo test --features record-fixtures --test replay -- --ignored`
+#[cfg(feature = "record-fixtures")]
+#[test]
+#[ignore = "hits the live Nominatim and Open-Meteo APIs"]
+fn record_fixtures() {
+    for fixture in common::ALL.into_iter().filter(|f| f.recordable) {
+        let server = ReplayServer::record(fixture);
+        // Nominatim last, so forecast.json matches the default geocoder's coordinates
+        for geocoder in ["open-meteo", "nominatim"] {
+            let output = server.run_cli(
+                fixture,
+                &["--format", "json", "--winds-aloft", "--geocoder", geocoder],
+            );
+            assert!(
+                output.status.success(),
+                "recording {} failed: {}",
+                fixture.name,
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        // Nominatim's usage policy allows at most one request per second
+        std::thread::sleep(std::time::Duration::from_secs(1));
+    }
+}
+
+#[test]
+fn test_wind_rose() {
+    let server = ReplayServer::start(LONDON);
+    let output = server.run_cli(LONDON, &["--wind-rose"]);
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{report}");
+    assert!(report.contains("--- Wind Rose (Next 38 Hours) ---"));
+    // A steady south-westerly: one full spoke and the table
+    assert!(
+        report.contains("    /\n  /\nSW          S           SE"),
+        "{report}"
+    );
+    assert!(report.contains("SW       100%     12 km/h"), "{report}");
+    assert!(report.contains("Direction known for 100% of 38 hours"));
+
+    // The fixture with gaps has no wind directions at all
+    let server = ReplayServer::start(QUITO_WITH_NULLS);
+    let output = server.run_cli(QUITO_WITH_NULLS, &["--wind-rose"]);
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{report}");
+    assert!(report.contains("No wind directions in the forecast"));
+
+    let report = text_report(LONDON);
+    assert!(!report.contains("Wind Rose"));
+}
+
+#[test]
+fn test_weather_log_and_summary() {
+    let dir = std::env::temp_dir().join(format!("weather-app-replay-log-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let server = ReplayServer::start(LONDON);
+    for _ in 0..2 {
+        let output = server.run_cli(LONDON, &["--log-to", dir.to_str().unwrap()]);
+        assert!(output.status.success());
+    }
+
+    // Named after the location and the month observed there
+    let file = dir.join("london-2025-12.jsonl");
+    let log = std::fs::read_to_string(&file).unwrap();
+    assert_eq!(log.lines().count(), 2, "{log}");
+    let record: Value = serde_json::from_str(log.lines().next().unwrap()).unwrap();
+    assert_eq!(record["observed"], "2025-12-10T10:00");
+    assert_eq!(record["temperature"], 8.8);
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_weather-app"))
+        .args(["log", "summarize"])
+        .arg(&file)
+        .output()
+        .unwrap();
+    let summary = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{summary}");
+    assert!(summary.contains("Records:        2"), "{summary}");
+    assert!(
+        summary.contains("Temperature:    8.8°C to 8.8°C"),
+        "{summary}"
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// The blocking and async repositories parse every fixture the same way,
+/// directly and through `BlockingWeatherRepository`
+#[cfg(feature = "async")]
+#[test]
+fn test_async_repository_matches_blocking() {
+    use weather_app::models::weather_info::WeatherInfo;
+    use weather_app::prelude::*;
+    use weather_app::repositories::async_weather_repository::{
+        AsyncApiWeatherRepository, AsyncWeatherRepository, BlockingWeatherRepository,
+    };
+    use weather_app::repositories::geocoding::GeocodingProvider;
+
+    /// Without the per-fetch fields that differ between any two fetches
+    fn comparable(result: Result<WeatherInfo, WeatherError>) -> WeatherInfo {
+        let mut info = result.expect("fetch");
+        info.fetched_at = None;
+        info.timings = None;
+        info
+    }
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let cases = common::ALL
+        .iter()
+        .chain([&HOSTILE])
+        .map(|fixture| (*fixture, GeocodingProvider::Nominatim))
+        .chain([LONDON, SYDNEY, TROMSO].map(|f| (f, GeocodingProvider::OpenMeteo)));
+    for (fixture, geocoder) in cases {
+        let server = ReplayServer::start(fixture);
+        let geocoding_url = match geocoder {
+            GeocodingProvider::Nominatim => server.nominatim_url(),
+            GeocodingProvider::OpenMeteo => server.open_meteo_geocoding_url(),
+        };
+        let repository = ApiWeatherRepository::new()
+            .with_geocoder(geocoder)
+            .with_endpoints(geocoding_url, server.open_meteo_url());
+        let async_repository = AsyncApiWeatherRepository::from_config(&repository);
+
+        let blocking = comparable(repository.fetch_weather(fixture.query));
+        let concurrent = comparable(runtime.block_on(AsyncWeatherRepository::fetch_weather(
+            &async_repository,
+            fixture.query,
+        )));
+        assert_eq!(concurrent, blocking, "{} via {geocoder}", fixture.name);
+
+        let wrapped =
+            BlockingWeatherRepository::with_handle(async_repository, runtime.handle().clone());
+        assert_eq!(
+            comparable(wrapped.fetch_weather(fixture.query)),
+            blocking,
+            "{} via {geocoder}, wrapped",
+            fixture.name
+        );
+    }
+
+    // The wrapper's own runtime serves a blocking controller
+    let server = ReplayServer::start(LONDON);
+    let repository =
+        ApiWeatherRepository::new().with_endpoints(server.nominatim_url(), server.open_meteo_url());
+    let wrapped =
+        BlockingWeatherRepository::new(AsyncApiWeatherRepository::from_config(&repository))
+            .unwrap();
+    let controller = weather_app::controllers::cl_controller::ClController::new(wrapped);
+    assert!(controller.show_batch(&[LONDON.query.to_string()]));
+}
+
+/// The concurrent batch asks for the model and forecast days given on the
+/// command line, as the blocking fetch does; ten days go out as the hourly
+/// and daily halves
+#[cfg(feature = "async")]
+#[test]
+fn test_async_batch_pins_the_model() {
+    let server = ReplayServer::start(LONDON_16_DAYS);
+    let output = server.run_batch(&["--model", "ecmwf", "--days", "10"], "London\n");
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{report}");
+    assert!(report.contains("London, United Kingdom: 9°C"), "{report}");
+    let forecasts = server.requests_to("/v1/forecast");
+    assert_eq!(forecasts.len(), 2, "{forecasts:?}");
+    for path in &forecasts {
+        assert!(path.contains("&models=ecmwf_ifs025&"), "{path}");
+    }
+    assert_eq!(
+        forecasts
+            .iter()
+            .filter(|path| path.contains("forecast_days=10"))
+            .count(),
+        1,
+        "{forecasts:?}"
+    );
+}
+
+#[test]
+fn test_nws_points_then_forecast() {
+    let server = ReplayServer::start(NEW_YORK_NWS);
+    let output = server.run_cli(NEW_YORK_NWS, &["--provider", "nws", "--width", "120"]);
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{report}");
+    assert!(
+        report.contains("Location: New York, United States"),
+        "{report}"
+    );
+    assert!(report.contains("Temperature: 2.2°C / 36.0°F"), "{report}");
+    assert!(report.contains("Wind: 8.0 km/h / 5.0 mph from 315° (NW)"));
+    assert!(report.contains("--- Daily Forecast (Next 7 Days) ---"));
+    assert!(
+        report.contains("2025-12-12     -2.8 / 2.8°C  Snow"),
+        "{report}"
+    );
+
+    // The same two steps through the library: the point, then both forecasts
+    use weather_app::repositories::nws_weather_repository::NwsWeatherRepository;
+    use weather_app::repositories::weather_repository::{ApiWeatherRepository, WeatherRepository};
+    let repository = NwsWeatherRepository::new(
+        ApiWeatherRepository::new().with_endpoints(server.nominatim_url(), server.open_meteo_url()),
+    )
+    .with_nws_url(server.nws_url());
+    let info = repository.fetch_weather(NEW_YORK_NWS.query).unwrap();
+    assert_eq!(info.timezone.as_deref(), Some("America/New_York"));
+    assert_eq!(info.utc_offset_seconds, Some(-5 * 3600));
+    let data = &info.weather_data;
+    assert_eq!(data.current.time.as_deref(), Some("2025-12-10T10:00"));
+    assert_eq!(data.current.humidity, Some(56.0));
+    assert_eq!(data.hourly.len(), 48);
+    // Thursday night's rain and snow, 60%, counts towards Thursday
+    let thursday = &data.daily[1];
+    assert_eq!(thursday.date, "2025-12-11");
+    assert_eq!(thursday.precipitation_probability, Some(60.0));
+    assert_eq!(
+        thursday.temperature_min.map(|t| (t * 10.0).round()),
+        Some(6.0)
+    );
+    assert_eq!(data.daily.len(), 7);
+}
+
+#[test]
+fn test_openweathermap_one_call() {
+    let server = ReplayServer::start(LONDON_OWM);
+    let output = server.run_cli(
+        LONDON_OWM,
+        &[
+            "--provider",
+            "openweathermap",
+            "--openweathermap-api-key",
+            "test",
+            "--width",
+            "120",
+        ],
+    );
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{report}");
+    assert!(
+        report.contains("Location: London, United Kingdom"),
+        "{report}"
+    );
+    // 282.4 K and 4.12 m/s
+    assert!(report.contains("Temperature: 9.3°C / 48.7°F"), "{report}");
+    assert!(
+        report.contains("Wind: 14.8 km/h / 9.2 mph from 240° (SW)"),
+        "{report}"
+    );
+    assert!(report.contains("--- Daily Forecast (Next 7 Days) ---"));
+    assert!(
+        report.contains("2025-12-10     5.4 / 10.0°C  Rain"),
+        "{report}"
+    );
+    assert!(!report.contains("2025-12-17"), "{report}");
+
+    // Without a key nothing is fetched and the error says where one goes
+    let output = server.run_cli(LONDON_OWM, &["--provider", "openweathermap"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!output.status.success());
+    assert!(stderr.contains("--openweathermap-api-key"), "{stderr}");
+}
+
+#[test]
+fn test_metno_days_from_hours() {
+    let server = ReplayServer::start(OSLO_METNO);
+    let output = server.run_cli(OSLO_METNO, &["--provider", "met-no", "--width", "120"]);
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{report}");
+    assert!(report.contains("Location: Oslo, Norway"), "{report}");
+    // 2.0 m/s at 10:00 UTC, shown at 11:00 Oslo time
+    assert!(report.contains("Wind: 7.2 km/h / 4.5 mph from 210° (SW)"));
+    assert!(
+        report.contains("11:00 - -1.5°C / 29.3°F (Overcast)"),
+        "{report}"
+    );
+    // Twelve hours of 0.4 mm, and four six-hour periods of 3.5 mm
+    assert!(
+        report.contains("2025-12-11    -6.6 / -0.6°C  Snow                                4.8 mm"),
+        "{report}"
+    );
+    assert!(
+        report.contains("2025-12-15    -8.6 / -3.4°C  Snow showers                       14.0 mm"),
+        "{report}"
+    );
+
+    // Days straddling the switch from hourly to six-hourly steps
+    use weather_app::repositories::metno_repository::MetNoRepository;
+    use weather_app::repositories::weather_repository::{ApiWeatherRepository, WeatherRepository};
+    let repository = MetNoRepository::new(
+        ApiWeatherRepository::new()
+            .with_endpoints(server.nominatim_url(), server.open_meteo_url())
+            .with_forecast_days(16),
+    )
+    .with_metno_url(server.metno_url());
+    let info = repository.fetch_weather(OSLO_METNO.query).unwrap();
+    assert_eq!(info.utc_offset_seconds, Some(3600));
+    assert_eq!(info.timezone.as_deref(), Some("Europe/Oslo"));
+    assert_eq!(info.elevation, Some(12.0));
+    let data = &info.weather_data;
+    assert_eq!(data.hourly.len(), 48);
+    let days: Vec<&str> = data.daily.iter().map(|d| d.date.as_str()).collect();
+    assert_eq!(days.first(), Some(&"2025-12-10"));
+    assert_eq!(days.last(), Some(&"2025-12-20"));
+    // The 12th has hourly steps to 18:00 and a six-hour summary from 19:00;
+    // its first hour is still the 11th's overcast in UTC
+    let twelfth = &data.daily[2];
+    assert_eq!(twelfth.temperature_min, Some(-7.2));
+    assert_eq!(twelfth.temperature_max, Some(-1.2));
+    assert_eq!(twelfth.weather_code, Some(3));
+}
+
+#[test]
+fn test_fallback_provider_answers_for_a_failed_one() {
+    // The fixture has no Open-Meteo forecast, so that request fails
+    let server = ReplayServer::start(OSLO_METNO);
+    let output = server.run_cli(OSLO_METNO, &["--provider", "open-meteo,met-no"]);
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{report}");
+    assert!(report.contains("Location: Oslo, Norway"), "{report}");
+    assert!(report.contains("\nForecast by Met.no\n"), "{report}");
+}